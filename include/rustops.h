@@ -0,0 +1,258 @@
+/*
+ * C interface to RustOps, built as the `libRustOps` shared library.
+ *
+ * Mirrors `src/ffi`; `tests/ffi_test.rs` checks that every exported function is declared
+ * here. Regenerate with `cbindgen --config cbindgen.toml --output include/rustops.h`.
+ *
+ * Every function returns a RustOpsStatus. On failure, rustops_last_error_message() describes
+ * the error. Tensors produced by the library are owned by the caller and must be released
+ * with rustops_tensor_free().
+ */
+
+#ifndef RUSTOPS_H
+#define RUSTOPS_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Element type codes used in place of `Dtype` across the ABI.
+ */
+typedef enum RustOpsDtype {
+  RustOpsDtype_F64 = 0,
+  RustOpsDtype_F32 = 1,
+  RustOpsDtype_F16 = 2,
+  RustOpsDtype_BF16 = 3,
+  RustOpsDtype_I64 = 4,
+  /**
+   * One byte per element holding 0 or 1.
+   */
+  RustOpsDtype_Bool = 5,
+} RustOpsDtype;
+
+/**
+ * Padding modes for `rustops_pad`, mirroring `PadMode`.
+ */
+typedef enum RustOpsPadMode {
+  RustOpsPadMode_Constant = 0,
+  RustOpsPadMode_Reflect = 1,
+  RustOpsPadMode_Replicate = 2,
+  RustOpsPadMode_Circular = 3,
+} RustOpsPadMode;
+
+/**
+ * Result of every `rustops_*` call.
+ */
+typedef enum RustOpsStatus {
+  RustOpsStatus_Ok = 0,
+  /**
+   * A required pointer argument was null.
+   */
+  RustOpsStatus_NullPointer = 1,
+  /**
+   * An argument was out of range or malformed, e.g. an unknown dtype code or invalid UTF-8.
+   */
+  RustOpsStatus_InvalidArgument = 2,
+  /**
+   * The op does not support the dtype of a tensor argument.
+   */
+  RustOpsStatus_UnsupportedDtype = 3,
+  /**
+   * The op rejected its inputs, e.g. because their shapes do not line up.
+   */
+  RustOpsStatus_OpFailed = 4,
+  /**
+   * A caller-provided output buffer is too small.
+   */
+  RustOpsStatus_BufferTooSmall = 5,
+  /**
+   * The library panicked. This is a bug; the call had no effect on its arguments.
+   */
+  RustOpsStatus_Panic = 6,
+} RustOpsStatus;
+
+/**
+ * Opaque handle to a tensor owned by the library.
+ */
+typedef struct RustOpsTensor RustOpsTensor;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the message of the last error on the calling thread, or null if there was none.
+ *
+ * Successful calls leave the message untouched, like `errno`. The string is owned by the
+ * library and stays valid until the next failing call on the same thread.
+ */
+const char *rustops_last_error_message(void);
+
+/**
+ * Clears the last error message of the calling thread.
+ */
+void rustops_clear_last_error(void);
+
+/**
+ * Creates a tensor by copying a C-contiguous buffer of `data` with the given `shape` and
+ * RustOpsDtype code. `data` holds elements in native byte order and need not be aligned.
+ */
+RustOpsStatus rustops_tensor_create(const void *data,
+                                    const size_t *shape,
+                                    size_t ndim,
+                                    int32_t dtype,
+                                    RustOpsTensor **out);
+
+/**
+ * Releases a tensor. Does nothing if `tensor` is null.
+ */
+void rustops_tensor_free(RustOpsTensor *tensor);
+
+RustOpsStatus rustops_tensor_ndim(const RustOpsTensor *tensor, size_t *out);
+
+/**
+ * Writes the dimensions of `tensor` to `out`, which holds `capacity` values.
+ */
+RustOpsStatus rustops_tensor_shape(const RustOpsTensor *tensor, size_t *out, size_t capacity);
+
+/**
+ * Writes the RustOpsDtype code of `tensor` to `out`.
+ */
+RustOpsStatus rustops_tensor_dtype(const RustOpsTensor *tensor, int32_t *out);
+
+RustOpsStatus rustops_tensor_numel(const RustOpsTensor *tensor, size_t *out);
+
+/**
+ * Copies the elements of `tensor` in C-contiguous order into `out`, which holds `capacity`
+ * bytes.
+ */
+RustOpsStatus rustops_tensor_copy_data(const RustOpsTensor *tensor, void *out, size_t capacity);
+
+RustOpsStatus rustops_abs(const RustOpsTensor *x, RustOpsTensor **out);
+
+RustOpsStatus rustops_argmax(const RustOpsTensor *x,
+                             bool has_dim,
+                             intptr_t dim,
+                             bool keepdim,
+                             RustOpsTensor **out);
+
+RustOpsStatus rustops_einsum(const char *equation,
+                             const RustOpsTensor *const *operands,
+                             size_t count,
+                             RustOpsTensor **out);
+
+RustOpsStatus rustops_einsum_named(const char *pattern,
+                                   const RustOpsTensor *const *operands,
+                                   size_t count,
+                                   RustOpsTensor **out);
+
+RustOpsStatus rustops_expand(const RustOpsTensor *x, size_t dim, size_t size, RustOpsTensor **out);
+
+RustOpsStatus rustops_flip(const RustOpsTensor *x,
+                           const intptr_t *dims,
+                           size_t ndims,
+                           RustOpsTensor **out);
+
+RustOpsStatus rustops_gather(const RustOpsTensor *x,
+                             intptr_t dim,
+                             const RustOpsTensor *index,
+                             RustOpsTensor **out);
+
+RustOpsStatus rustops_matmul(const RustOpsTensor *a, const RustOpsTensor *b, RustOpsTensor **out);
+
+RustOpsStatus rustops_bmm(const RustOpsTensor *a, const RustOpsTensor *b, RustOpsTensor **out);
+
+RustOpsStatus rustops_baddbmm(const RustOpsTensor *input,
+                              const RustOpsTensor *batch1,
+                              const RustOpsTensor *batch2,
+                              double beta,
+                              double alpha,
+                              RustOpsTensor **out);
+
+RustOpsStatus rustops_addmm(const RustOpsTensor *input,
+                            const RustOpsTensor *mat1,
+                            const RustOpsTensor *mat2,
+                            double beta,
+                            double alpha,
+                            RustOpsTensor **out);
+
+RustOpsStatus rustops_max(const RustOpsTensor *x,
+                          intptr_t dim,
+                          RustOpsTensor **values_out,
+                          RustOpsTensor **indices_out);
+
+RustOpsStatus rustops_ones(const size_t *shape, size_t ndim, RustOpsTensor **out);
+
+/**
+ * `pads` holds `len` values like the `pad` tuple of `torch.nn.functional.pad`. `mode` is a
+ * RustOpsPadMode code and `value` is only used by the constant mode.
+ */
+RustOpsStatus rustops_pad(const RustOpsTensor *x,
+                          const intptr_t *pads,
+                          size_t len,
+                          int32_t mode,
+                          double value,
+                          RustOpsTensor **out);
+
+RustOpsStatus rustops_rearrange_batch_mems_flag(const RustOpsTensor *x, RustOpsTensor **out);
+
+RustOpsStatus rustops_reduce(const RustOpsTensor *x, const char *equation, RustOpsTensor **out);
+
+RustOpsStatus rustops_repeat_interleave(const RustOpsTensor *x,
+                                        size_t repeats,
+                                        bool has_dim,
+                                        intptr_t dim,
+                                        RustOpsTensor **out);
+
+RustOpsStatus rustops_repeat_interleave_tensor(const RustOpsTensor *x,
+                                               const RustOpsTensor *repeats,
+                                               bool has_dim,
+                                               intptr_t dim,
+                                               RustOpsTensor **out);
+
+RustOpsStatus rustops_reshape(const RustOpsTensor *x,
+                              const int64_t *shape,
+                              size_t ndim,
+                              RustOpsTensor **out);
+
+RustOpsStatus rustops_roll(const RustOpsTensor *x,
+                           const intptr_t *shifts,
+                           size_t nshifts,
+                           const intptr_t *dims,
+                           size_t ndims,
+                           RustOpsTensor **out);
+
+RustOpsStatus rustops_scatter(const RustOpsTensor *target,
+                              intptr_t dim,
+                              const RustOpsTensor *index,
+                              const RustOpsTensor *src,
+                              RustOpsTensor **out);
+
+RustOpsStatus rustops_slice_last_dim(const RustOpsTensor *x, RustOpsTensor **out);
+
+RustOpsStatus rustops_slice_second_dim(const RustOpsTensor *x, size_t amount, RustOpsTensor **out);
+
+RustOpsStatus rustops_sort_last_dim(const RustOpsTensor *x, RustOpsTensor **out);
+
+RustOpsStatus rustops_tile(const RustOpsTensor *x,
+                           const size_t *reps,
+                           size_t nreps,
+                           RustOpsTensor **out);
+
+RustOpsStatus rustops_transpose(const RustOpsTensor *x,
+                                intptr_t dim0,
+                                intptr_t dim1,
+                                RustOpsTensor **out);
+
+RustOpsStatus rustops_tril(const RustOpsTensor *x, intptr_t diagonal, RustOpsTensor **out);
+
+RustOpsStatus rustops_triu(const RustOpsTensor *x, intptr_t diagonal, RustOpsTensor **out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RUSTOPS_H */
@@ -0,0 +1,65 @@
+package com.rustops;
+
+import java.nio.FloatBuffer;
+import java.nio.LongBuffer;
+
+/**
+ * Native methods of the RustOps library, built with {@code cargo build --features jni}.
+ *
+ * <p>Tensors are {@code long} handles owned by the caller and released with {@link #free}.
+ * Buffers must be direct and in native byte order, e.g.
+ * {@code ByteBuffer.allocateDirect(n * 4).order(ByteOrder.nativeOrder()).asFloatBuffer()}, and
+ * are read from and written to starting at index 0. Every op returns a new handle.
+ *
+ * <p>Invalid arguments throw {@link IllegalArgumentException}, null arguments or handles
+ * {@link NullPointerException}, out of range indices {@link IndexOutOfBoundsException} and
+ * unsupported dtypes {@link UnsupportedOperationException}.
+ */
+public final class RustOps {
+    static {
+        System.loadLibrary("RustOps");
+    }
+
+    private RustOps() {}
+
+    /** Creates a float tensor from the first {@code product(shape)} elements of {@code data}. */
+    public static native long fromFloatBuffer(FloatBuffer data, long[] shape);
+
+    /** Creates a long tensor from the first {@code product(shape)} elements of {@code data}. */
+    public static native long fromLongBuffer(LongBuffer data, long[] shape);
+
+    /** Releases a tensor. Does nothing for 0. */
+    public static native void free(long tensor);
+
+    public static native long[] shape(long tensor);
+
+    /** Copies a float tensor in row-major order into {@code out}. */
+    public static native void toFloatBuffer(long tensor, FloatBuffer out);
+
+    /** Copies a long tensor in row-major order into {@code out}. */
+    public static native void toLongBuffer(long tensor, LongBuffer out);
+
+    /** Like {@code torch.gather}; {@code index} must be a long tensor. */
+    public static native long gather(long input, long dim, long index);
+
+    /** Like {@code torch.scatter}; {@code target} is left unchanged. */
+    public static native long scatter(long target, long dim, long index, long src);
+
+    /** Like {@code torch.argmax(input, dim, keepdim)}; returns a long tensor. */
+    public static native long argmax(long input, long dim, boolean keepdim);
+
+    /** Like {@code torch.argmax(input)}; returns a 0-dimensional long tensor. */
+    public static native long argmaxAll(long input);
+
+    /** Like {@code torch.einsum}; all operands must share one dtype. */
+    public static native long einsum(String equation, long[] operands);
+
+    /** Like {@code torch.reshape}; one entry of {@code shape} may be -1. */
+    public static native long reshape(long input, long[] shape);
+
+    /** Rearranges a float tensor {@code batch mems flag -> mems (batch flag)}. */
+    public static native long rearrangeBatchMemsFlag(long input);
+
+    /** Like {@code torch.softmax} on a float tensor. */
+    public static native long softmax(long input, long dim);
+}
@@ -1,5 +1,5 @@
 import torch
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 
@@ -17,13 +17,12 @@ def create_abs(
         shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "abs2d".
+        name (str): Name of the conformance case. Default is "abs2d".
     """
     x = torch.randn(shape, dtype=dtype)
     y = torch.abs(x)
 
-    save_reference(x, dir, f"{name}_abs_x")
-    save_reference(y, dir, f"{name}_abs_y")
+    save_case(dir, name, "abs", {}, {"x": x}, {"y": y})
 
 
 if __name__ == "__main__":
@@ -1,5 +1,5 @@
 import torch
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 def create_argmax(
@@ -14,16 +14,18 @@ def create_argmax(
         shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "../data".
-        name (str): Name of the reference tensor file. Default is "argmax".
+        name (str): Prefix of the conformance case names. Default is "argmax".
     """
     x = torch.rand(shape, dtype=dtype)
-    # get max_v and max_i for each dimension, and save them separately, also save original `x`
-    save_reference(x, dir, f"{name}_argmax_x")
+    # dim=None argmaxes over the flattened tensor
+    args = {"dim": None, "keepdim": False}
+    save_case(dir, f"{name}_flattened", "argmax", args, {"x": x}, {"y": torch.argmax(x)})
     for dim in range(len(shape)):
-        argmaxed = torch.argmax(x, dim=dim)
-        argmaxed_keepdim = torch.argmax(x, dim=dim, keepdim=True)
-        save_reference(argmaxed, dir, f"{name}argmax_dim{dim}_nokeepdim")
-        save_reference(argmaxed_keepdim, dir, f"{name}argmax_dim{dim}_yeskeepdim")
+        for keepdim in (False, True):
+            y = torch.argmax(x, dim=dim, keepdim=keepdim)
+            args = {"dim": dim, "keepdim": keepdim}
+            case = f"{name}_dim{dim}_keepdim{int(keepdim)}"
+            save_case(dir, case, "argmax", args, {"x": x}, {"y": y})
 
 if __name__ == "__main__":
     create_argmax((6, 7, 8, 9, 10, 11), dtype=torch.float32, dir="data", name="argmax")
\ No newline at end of file
@@ -0,0 +1,54 @@
+import torch
+import torch.nn.functional as F
+from util.manifest import save_case
+from typing import Tuple
+
+
+def create_attention(
+    q_shape: Tuple[int, ...],
+    kv_shape: Tuple[int, ...],
+    mask: str | None = None,
+    is_causal: bool = False,
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "attention",
+):
+    """
+    Create random queries, keys and values and save the result of
+    `scaled_dot_product_attention` as a reference.
+    Args:
+        q_shape (Tuple[int, ...]): Shape of the queries, (*, L, E).
+        kv_shape (Tuple[int, ...]): Shape of the keys and values, (*, S, E).
+        mask (str | None): "bool" or "additive" to also create and save a (L, S) mask.
+        is_causal (bool): Whether to apply a causal mask.
+        dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "attention".
+    """
+    q = torch.randn(q_shape, dtype=dtype)
+    k = torch.randn(kv_shape, dtype=dtype)
+    v = torch.randn(kv_shape, dtype=dtype)
+    attn_mask = None
+    L, S = q_shape[-2], kv_shape[-2]
+    if mask == "bool":
+        attn_mask = torch.rand((L, S)) > 0.3
+        # Keep at least one key per query so no row is fully masked
+        attn_mask[:, 0] = True
+    elif mask == "additive":
+        attn_mask = torch.randn((L, S), dtype=dtype)
+    enable_gqa = q_shape[-3] != kv_shape[-3]
+    y = F.scaled_dot_product_attention(
+        q, k, v, attn_mask=attn_mask, is_causal=is_causal, enable_gqa=enable_gqa
+    )
+    inputs = {"q": q, "k": k, "v": v}
+    if attn_mask is not None:
+        inputs["mask"] = attn_mask
+    save_case(dir, name, "attention", {"is_causal": is_causal}, inputs, {"y": y}, atol=1e-4)
+
+
+if __name__ == "__main__":
+    create_attention((2, 4, 70, 16), (2, 4, 200, 16), dir="data", name="attention_plain")
+    create_attention((2, 4, 90, 16), (2, 4, 90, 16), is_causal=True, dir="data", name="attention_causal")
+    create_attention((1, 2, 33, 8), (1, 2, 140, 8), mask="bool", dir="data", name="attention_bool")
+    create_attention((1, 2, 33, 8), (1, 2, 140, 8), mask="additive", dir="data", name="attention_additive")
+    create_attention((2, 8, 20, 16), (2, 2, 50, 16), dir="data", name="attention_gqa")
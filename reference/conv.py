@@ -0,0 +1,154 @@
+import torch
+import torch.nn.functional as F
+from util.manifest import save_case
+from typing import Tuple
+
+
+def pair(value: int | Tuple[int, int] | None) -> list | None:
+    """
+    Spell a 2D size argument out as two integers, as the Rust 2D ops take it
+    Args:
+        value (int | Tuple[int, int] | None): One size for both dimensions, or one per dimension
+    """
+    if value is None:
+        return None
+    return [value, value] if isinstance(value, int) else list(value)
+
+
+def create_conv(
+    input_shape: Tuple[int, ...],
+    weight_shape: Tuple[int, ...],
+    stride: int | Tuple[int, int] = 1,
+    padding: int | Tuple[int, int] = 0,
+    dilation: int | Tuple[int, int] = 1,
+    groups: int = 1,
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "conv",
+):
+    """
+    Create a random input, weight and bias and save the result of `conv1d` or `conv2d`
+    (picked from the weight rank) as a reference.
+    Args:
+        input_shape (Tuple[int, ...]): Shape of the input, (N, C_in, *).
+        weight_shape (Tuple[int, ...]): Shape of the weight, (C_out, C_in / groups, *).
+        stride (int | Tuple[int, int]): Convolution stride.
+        padding (int | Tuple[int, int]): Zero padding on both sides.
+        dilation (int | Tuple[int, int]): Spacing between kernel elements.
+        groups (int): Number of channel groups.
+        dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "conv".
+    """
+    x = torch.randn(input_shape, dtype=dtype)
+    w = torch.randn(weight_shape, dtype=dtype)
+    b = torch.randn(weight_shape[0], dtype=dtype)
+    if len(weight_shape) == 3:
+        conv, op, args = F.conv1d, "conv1d", {"stride": stride, "padding": padding, "dilation": dilation}
+    else:
+        conv, op = F.conv2d, "conv2d"
+        args = {"stride": pair(stride), "padding": pair(padding), "dilation": pair(dilation)}
+    y = conv(x, w, b, stride=stride, padding=padding, dilation=dilation, groups=groups)
+    inputs = {"x": x, "weight": w, "bias": b}
+    save_case(dir, name, op, {**args, "groups": groups}, inputs, {"y": y}, atol=1e-4)
+
+
+def create_conv_transpose1d(
+    input_shape: Tuple[int, ...],
+    weight_shape: Tuple[int, ...],
+    stride: int = 1,
+    padding: int = 0,
+    output_padding: int = 0,
+    groups: int = 1,
+    dilation: int = 1,
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "conv_transpose1d",
+):
+    """
+    Create a random input, weight and bias and save the result of `conv_transpose1d` as a reference.
+    Args:
+        input_shape (Tuple[int, ...]): Shape of the input, (N, C_in, L).
+        weight_shape (Tuple[int, ...]): Shape of the weight, (C_in, C_out / groups, K).
+        stride (int): Convolution stride.
+        padding (int): Padding removed from both sides of the output.
+        output_padding (int): Extra size added to one side of the output.
+        groups (int): Number of channel groups.
+        dilation (int): Spacing between kernel elements.
+        dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "conv_transpose1d".
+    """
+    x = torch.randn(input_shape, dtype=dtype)
+    w = torch.randn(weight_shape, dtype=dtype)
+    b = torch.randn(weight_shape[1] * groups, dtype=dtype)
+    y = F.conv_transpose1d(x, w, b, stride, padding, output_padding, groups, dilation)
+    args = {
+        "stride": stride,
+        "padding": padding,
+        "output_padding": output_padding,
+        "groups": groups,
+        "dilation": dilation,
+    }
+    save_case(dir, name, "conv_transpose1d", args, {"x": x, "weight": w, "bias": b}, {"y": y}, atol=1e-4)
+
+
+def create_pool(
+    input_shape: Tuple[int, ...],
+    kernel_size: int | Tuple[int, int],
+    stride: int | Tuple[int, int] | None,
+    padding: int | Tuple[int, int],
+    ceil_mode: bool,
+    adaptive_size: int | Tuple[int, int],
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "pool",
+):
+    """
+    Create a random input and save the results of max, average and adaptive average pooling
+    (1D or 2D, picked from the input rank) as references.
+    Args:
+        input_shape (Tuple[int, ...]): Shape of the input, (N, C, *).
+        kernel_size (int | Tuple[int, int]): Pooling window size.
+        stride (int | Tuple[int, int] | None): Window stride, kernel_size when None.
+        padding (int | Tuple[int, int]): Padding on both sides.
+        ceil_mode (bool): Use ceil when computing the output shape.
+        adaptive_size (int | Tuple[int, int]): Output size of the adaptive pooling.
+        dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Prefix of the conformance case names. Default is "pool".
+    """
+    x = torch.randn(input_shape, dtype=dtype)
+    if len(input_shape) == 3:
+        max_pool, avg_pool, adaptive = F.max_pool1d, F.avg_pool1d, F.adaptive_avg_pool1d
+        size = lambda value: value
+        dims = "1d"
+    else:
+        max_pool, avg_pool, adaptive = F.max_pool2d, F.avg_pool2d, F.adaptive_avg_pool2d
+        size = pair
+        dims = "2d"
+    # The window arguments the Rust pooling functions take, with PyTorch's defaults spelled out
+    args = {
+        "kernel_size": size(kernel_size),
+        "stride": size(stride),
+        "padding": size(padding),
+        "ceil_mode": ceil_mode,
+    }
+    y = max_pool(x, kernel_size, stride, padding, ceil_mode=ceil_mode)
+    max_args = {**args, "dilation": size(1)}
+    save_case(dir, f"{name}_max", f"max_pool{dims}", max_args, {"x": x}, {"y": y}, atol=1e-6)
+    y = avg_pool(x, kernel_size, stride, padding, ceil_mode=ceil_mode)
+    avg_args = {**args, "count_include_pad": True}
+    save_case(dir, f"{name}_avg", f"avg_pool{dims}", avg_args, {"x": x}, {"y": y}, atol=1e-5)
+    y = adaptive(x, adaptive_size)
+    adaptive_args = {"output_size": size(adaptive_size)}
+    save_case(dir, f"{name}_adaptive", f"adaptive_avg_pool{dims}", adaptive_args, {"x": x}, {"y": y}, atol=1e-5)
+
+
+if __name__ == "__main__":
+    create_conv((2, 4, 17), (6, 2, 3), stride=2, padding=1, dilation=2, groups=2, dir="data", name="conv1d")
+    create_conv((2, 3, 9, 11), (4, 3, 3, 2), stride=(2, 1), padding=(1, 0), dilation=(1, 2), dir="data", name="conv2d")
+    create_conv((1, 4, 8, 8), (4, 1, 3, 3), padding=1, groups=4, dir="data", name="conv2d_depthwise")
+    create_conv_transpose1d((2, 4, 7), (4, 3, 3), stride=2, padding=1, output_padding=1, groups=2, dir="data", name="conv_transpose1d")
+    create_pool((2, 3, 11), 3, 2, 1, True, 4, dir="data", name="pool1d")
+    create_pool((2, 3, 9, 10), (3, 2), None, (1, 1), False, (4, 3), dir="data", name="pool2d")
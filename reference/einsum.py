@@ -1,9 +1,29 @@
 import torch
 from einops import einsum
-from util.save_reference import save_reference
+from torch import Tensor
+from util.manifest import record_case, save_case
 from typing import Tuple, List, Iterable
 
 
+def save_einsum_cases(
+    dir: str, name: str, pattern: str, equation: str, x: Tensor, y: Tensor, z: Tensor
+):
+    """
+    Save a case for the single-letter einsum and one for `einsum_named`, sharing one bundle
+    Args:
+        dir (str): Directory to save the reference tensors
+        name (str): Name of the einsum case; the named case appends "_named"
+        pattern (str): The einops pattern, as `einsum_named` takes it
+        equation (str): The same contraction with single-letter labels
+        x (Tensor): First operand
+        y (Tensor): Second operand
+        z (Tensor): Expected result
+    """
+    case = save_case(dir, name, "einsum", {"equation": equation}, {"x": x, "y": y}, {"z": z}, atol=1e-5)
+    named = {**case, "name": f"{name}_named", "op": "einsum_named", "args": {"pattern": pattern}}
+    record_case(dir, named)
+
+
 def test_einsum_batch_fields_memories(
     shape1: Tuple[int, ...] | List[int] | Iterable[int],
     shape2: Tuple[int, ...] | List[int] | Iterable[int],
@@ -18,16 +38,14 @@ def test_einsum_batch_fields_memories(
         shape2 (Tuple[int, ...]): Shape of the second tensor to create.
         dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
         dir (str): Directory to save the reference tensors. Default is "data".
-        name (str): Name of the reference tensor file. Default is "einsum_batch_fields_memories".
+        name (str): Name of the conformance case. Default is "einsum_batch_fields_memories".
     """
     x = torch.rand(shape1, dtype=dtype)
     y = torch.rand(shape2, dtype=dtype)
-    z = einsum(x, y, 'batch fields memories dim, batch fields dim -> batch fields memories')
-    # Save the original tensors
-    save_reference(x, dir, f"{name}_einsum_bfmd_bfd_x")
-    save_reference(y, dir, f"{name}_einsum_bfmd_bfd_y")
-    # Save the resulting tensor
-    save_reference(z, dir, f"{name}_einsum_bfmd_bfd_z")
+    pattern = 'batch fields memories dim, batch fields dim -> batch fields memories'
+    z = einsum(x, y, pattern)
+    # The same contraction with single-letter labels, as the Rust einsum takes it
+    save_einsum_cases(dir, name, pattern, "bfmd,bfd->bfm", x, y, z)
 
 def test_einsum_batch_hidden_children_mems(
     shape1: Tuple[int, ...] | List[int] | Iterable[int],
@@ -43,16 +61,14 @@ def test_einsum_batch_hidden_children_mems(
         shape2 (Tuple[int, ...]): Shape of the second tensor to create.
         dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
         dir (str): Directory to save the reference tensors. Default is "data".
-        name (str): Name of the reference tensor file. Default is "einsum_batch_hidden_children_mems".
+        name (str): Name of the conformance case. Default is "einsum_batch_hidden_children_mems".
     """
     x = torch.rand(shape1, dtype=dtype)
     y = torch.rand(shape2, dtype=dtype)
-    z = einsum(x, y, 'batch hidden children h_mems c_mems, batch hidden children c_mems -> batch hidden h_mems')
-    # Save the original tensors
-    save_reference(x, dir, f"{name}_einsum_bhchmc_bhcc_x")
-    save_reference(y, dir, f"{name}_einsum_bhchmc_bhcc_y")
-    # Save the resulting tensor
-    save_reference(z, dir, f"{name}_einsum_bhchmc_bhcc_z")
+    pattern = 'batch hidden children h_mems c_mems, batch hidden children c_mems -> batch hidden h_mems'
+    z = einsum(x, y, pattern)
+    # The same contraction with single-letter labels, as the Rust einsum takes it
+    save_einsum_cases(dir, name, pattern, "bukhc,bukc->buh", x, y, z)
 
 def test_einsum_batch_parents_children_pdim_cdim(
     shape1: Tuple[int, ...] | List[int] | Iterable[int],
@@ -68,16 +84,14 @@ def test_einsum_batch_parents_children_pdim_cdim(
         shape2 (Tuple[int, ...]): Shape of the second tensor to create.
         dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
         dir (str): Directory to save the reference tensors. Default is "data".
-        name (str): Name of the reference tensor file. Default is "einsum_batch_parents_children_pdim_cdim".
+        name (str): Name of the conformance case. Default is "einsum_batch_parents_children_pdim_cdim".
     """
     x = torch.rand(shape1, dtype=dtype)
     y = torch.rand(shape2, dtype=dtype)
-    z = einsum(x, y, 'batch parents children pdim, batch parents children pdim cdim -> batch parents children cdim')
-    # Save the original tensors
-    save_reference(x, dir, f"{name}_einsum_bpcp_bpcpc_x")
-    save_reference(y, dir, f"{name}_einsum_bpcp_bpcpc_y")
-    # Save the resulting tensor
-    save_reference(z, dir, f"{name}_einsum_bpcp_bpcpc_z")
+    pattern = 'batch parents children pdim, batch parents children pdim cdim -> batch parents children cdim'
+    z = einsum(x, y, pattern)
+    # The same contraction with single-letter labels, as the Rust einsum takes it
+    save_einsum_cases(dir, name, pattern, "bpcd,bpcdk->bpck", x, y, z)
 
 def test_einsum_nodes_children_memories_dim(
     shape1: Tuple[int, ...] | List[int] | Iterable[int],
@@ -93,16 +107,14 @@ def test_einsum_nodes_children_memories_dim(
         shape2 (Tuple[int, ...]): Shape of the second tensor to create.
         dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
         dir (str): Directory to save the reference tensors. Default is "data".
-        name (str): Name of the reference tensor file. Default is "einsum_nodes_children_memories_dim".
+        name (str): Name of the conformance case. Default is "einsum_nodes_children_memories_dim".
     """
     x = torch.rand(shape1, dtype=dtype)
     y = torch.rand(shape2, dtype=dtype)
-    z = einsum(x, y, 'nodes children_per_node memories dim, batch nodes memories -> batch nodes children_per_node dim')
-    # Save the original tensors
-    save_reference(x, dir, f"{name}_einsum_ncmd_bnm_x")
-    save_reference(y, dir, f"{name}_einsum_ncmd_bnm_y")
-    # Save the resulting tensor
-    save_reference(z, dir, f"{name}_einsum_ncmd_bnm_z")
+    pattern = 'nodes children_per_node memories dim, batch nodes memories -> batch nodes children_per_node dim'
+    z = einsum(x, y, pattern)
+    # The same contraction with single-letter labels, as the Rust einsum takes it
+    save_einsum_cases(dir, name, pattern, "ncmd,bnm->bncd", x, y, z)
 
 def test_einsum_batch_nodes_children_dim_memories(
     shape1: Tuple[int, ...] | List[int] | Iterable[int],
@@ -118,16 +130,14 @@ def test_einsum_batch_nodes_children_dim_memories(
         shape2 (Tuple[int, ...]): Shape of the second tensor to create.
         dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
         dir (str): Directory to save the reference tensors. Default is "data".
-        name (str): Name of the reference tensor file. Default is "einsum_batch_nodes_children_dim_memories".
+        name (str): Name of the conformance case. Default is "einsum_batch_nodes_children_dim_memories".
     """
     x = torch.rand(shape1, dtype=dtype)
     y = torch.rand(shape2, dtype=dtype)
-    z = einsum(x, y, 'batch nodes children_per_node dim, batch nodes memories -> nodes children_per_node memories dim')
-    # Save the original tensors
-    save_reference(x, dir, f"{name}_einsum_bncd_bnm_x")
-    save_reference(y, dir, f"{name}_einsum_bncd_bnm_y")
-    # Save the resulting tensor
-    save_reference(z, dir, f"{name}_einsum_bncd_bnm_z")
+    pattern = 'batch nodes children_per_node dim, batch nodes memories -> nodes children_per_node memories dim'
+    z = einsum(x, y, pattern)
+    # The same contraction with single-letter labels, as the Rust einsum takes it
+    save_einsum_cases(dir, name, pattern, "bncd,bnm->ncmd", x, y, z)
 
 if __name__ == "__main__":
     test_einsum_batch_fields_memories(
@@ -1,5 +1,5 @@
 import torch
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 def create_expand(
@@ -15,16 +15,15 @@ def create_expand(
         shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "expand".
+        name (str): Prefix of the conformance case names. Default is "expand".
     """
     assert len(shape) > 0, "Shape must be a non-empty tuple or list."
     x = torch.randn(shape, dtype=dtype)
-    save_reference(x, dir, f"{name}_original_expand_dim")  # Save the original tensor
 
     for dim in range(len(shape) + 1):
         # Unsqueeze and expand the tensor along the current dimension
         y = x.unsqueeze(dim).expand(*x.shape[:dim], 4, *x.shape[dim:])
-        save_reference(y, dir, f"{name}_expand_dim_{dim}")
+        save_case(dir, f"{name}_dim{dim}", "expand", {"dim": dim, "size": 4}, {"x": x}, {"y": y})
 
 if __name__ == "__main__":
     d2 = (10, 11)
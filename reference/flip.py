@@ -0,0 +1,31 @@
+import torch
+from util.manifest import save_case
+from typing import Tuple, List, Iterable
+
+
+def create_flip(
+    shape: Tuple[int, ...] | List[int] | Iterable[int],
+    dims: Tuple[int, ...],
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "flip",
+):
+    """
+    Create a tensor of random values with the given shape and dtype, flip it along `dims`,
+    and save the original and flipped tensors as references.
+    Args:
+        shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
+        dims (Tuple[int, ...]): Dimensions to flip, may be negative.
+        dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "flip".
+    """
+    x = torch.randn(shape, dtype=dtype)
+    y = torch.flip(x, dims=dims)
+    save_case(dir, name, "flip", {"dims": list(dims)}, {"x": x}, {"y": y})
+
+
+if __name__ == "__main__":
+    create_flip((10, 11, 12), dims=(0,), dir="data", name="flip3d_dim0")
+    create_flip((10, 11, 12), dims=(-1,), dir="data", name="flip3d_dimneg1")
+    create_flip((10, 11, 12, 13), dims=(1, -1), dir="data", name="flip4d_dim1_neg1")
@@ -1,5 +1,5 @@
 import torch
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 
@@ -16,15 +16,13 @@ def create_gather(
         shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "gather2d".
+        name (str): Name of the conformance case. Default is "gather2d".
     """
     x = torch.randn(shape, dtype=dtype)
     indices = torch.argmax(x, dim=-1, keepdim=True)
     y = torch.gather(x, dim=-1, index=indices)
 
-    save_reference(x, dir, f"{name}_gather_x")
-    save_reference(indices, dir, f"{name}_gather_indices")
-    save_reference(y, dir, f"{name}_gather_y")
+    save_case(dir, name, "gather", {"dim": -1}, {"x": x, "indices": indices}, {"y": y})
 
 if __name__ == "__main__":
     d2 = (10, 11)
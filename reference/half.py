@@ -0,0 +1,50 @@
+import torch
+from argmax import create_argmax
+from expand import create_expand
+from gather import create_gather
+from max import create_max
+from reshape import create_reshape
+from scatter import create_scatter
+from sort import create_sort
+from transpose import create_transpose
+from util.manifest import save_case
+
+SUFFIXES = {torch.float16: "f16", torch.bfloat16: "bf16"}
+
+
+def create_half_cases(
+    dtype: torch.dtype,
+    dir: str = "data",
+):
+    """
+    Save conformance cases of the element-generic ops and of the ops that compute in float32
+    for a half precision dtype. bfloat16 cases are stored as float32, see `save_case`.
+    Args:
+        dtype (torch.dtype): torch.float16 or torch.bfloat16
+        dir (str): Directory to save the reference tensors. Default is "data".
+    """
+    suffix = SUFFIXES[dtype]
+    create_gather((10, 11, 12), dtype=dtype, dir=dir, name=f"gather3d_{suffix}")
+    create_scatter((10, 11, 12), dtype=dtype, dir=dir, name=f"scatter3d_{suffix}")
+    create_reshape((8, 3, 10), new_shape=(8, -1), dtype=dtype, dir=dir, name=f"reshape2d_{suffix}")
+    create_transpose((11, 12, 13), dtype=dtype, dir=dir, name=f"transpose_{suffix}")
+    create_expand((5, 6, 7), dtype=dtype, dir=dir, name=f"expand_{suffix}")
+    create_max((6, 7, 8), dtype=dtype, dir=dir, name=f"max_{suffix}")
+    create_argmax((6, 7, 8), dtype=dtype, dir=dir, name=f"argmax_{suffix}")
+    create_sort((7, 64), dtype=dtype, dir=dir, name=f"sort_{suffix}")
+
+    # PyTorch accumulates half precision products in float32 on the CPU but may block the sums
+    # differently, so allow the last bit of the result to differ
+    x = torch.randn((4, 6, 32), dtype=dtype)
+    y = torch.randn((4, 32, 5), dtype=dtype)
+    atol = 2 * torch.finfo(dtype).eps
+    args = {"equation": "bij,bjk->bik"}
+    z = torch.einsum("bij,bjk->bik", x, y)
+    save_case(dir, f"einsum_bmm_{suffix}", "einsum", args, {"x": x, "y": y}, {"z": z}, atol, atol)
+    z = torch.matmul(x, y)
+    save_case(dir, f"matmul_{suffix}", "matmul", {}, {"x": x, "y": y}, {"z": z}, atol, atol)
+
+
+if __name__ == "__main__":
+    create_half_cases(torch.float16, dir="data")
+    create_half_cases(torch.bfloat16, dir="data")
@@ -0,0 +1,33 @@
+import torch
+from util.manifest import save_case
+from typing import Tuple, List, Iterable
+
+
+def create_index_select(
+    shape: Tuple[int, ...] | List[int] | Iterable[int],
+    dim: int,
+    index: List[int],
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "index_select",
+):
+    """
+    Create a tensor of random values with the given shape and dtype, select `index` along
+    `dim`, and save the input, the index and the result as references.
+    Args:
+        shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
+        dim (int): Dimension to select along, may be negative.
+        index (List[int]): Positions to select, may repeat.
+        dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "index_select".
+    """
+    x = torch.randn(shape, dtype=dtype)
+    index = torch.tensor(index, dtype=torch.int64)
+    y = torch.index_select(x, dim, index)
+    save_case(dir, name, "index_select", {"dim": dim}, {"x": x, "index": index}, {"y": y})
+
+
+if __name__ == "__main__":
+    create_index_select((6, 4, 5), dim=0, index=[2, 2, 0, 5], dir="data", name="index_select3d_dim0")
+    create_index_select((3, 8, 5), dim=-2, index=[7, 1, 1], dir="data", name="index_select3d_dimneg2")
@@ -0,0 +1,71 @@
+import torch
+from util.manifest import save_case
+from typing import Tuple
+
+
+def create_linalg(
+    batch: Tuple[int, ...],
+    n: int,
+    dtype: torch.dtype = torch.float64,
+    dir: str = "data",
+    name: str = "linalg",
+):
+    """
+    Create a random batch of square matrices, a symmetric positive-definite batch and
+    right-hand sides, and save the results of the `torch.linalg` functions as references.
+    Args:
+        batch (Tuple[int, ...]): Leading batch shape.
+        n (int): Size of the square matrices.
+        dtype (torch.dtype): Data type of the tensors. Default is torch.float64.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Prefix of the conformance case names. Default is "linalg".
+    """
+    a = torch.randn((*batch, n, n), dtype=dtype)
+    spd = a @ a.mT + n * torch.eye(n, dtype=dtype)
+    b = torch.randn((*batch, n, 2), dtype=dtype)
+    cases = [
+        ("inv", {"a": a}, {"y": torch.linalg.inv(a)}),
+        ("det", {"a": a}, {"y": torch.linalg.det(a)}),
+        ("slogdet", {"a": a}, dict(zip(["sign", "logabsdet"], torch.linalg.slogdet(a)))),
+        ("solve", {"a": a, "b": b}, {"y": torch.linalg.solve(a, b)}),
+        ("cholesky", {"a": spd}, {"y": torch.linalg.cholesky(spd)}),
+        ("qr", {"a": a}, dict(zip(["q", "r"], torch.linalg.qr(a)))),
+        ("eigvalsh", {"a": spd}, {"y": torch.linalg.eigvalsh(spd)}),
+        ("nuclear_norm", {"a": a}, {"y": torch.linalg.matrix_norm(a, "nuc")}),
+    ]
+    for op, inputs, outputs in cases:
+        save_case(dir, f"{name}_{op}", op, {}, inputs, outputs, atol=1e-8)
+
+def create_rectangular(
+    batch: Tuple[int, ...],
+    m: int,
+    n: int,
+    dtype: torch.dtype = torch.float64,
+    dir: str = "data",
+    name: str = "linalg_rect",
+):
+    """
+    Create a random batch of rectangular matrices and save their singular values,
+    pseudo-inverse and least-squares solution as references.
+    Args:
+        batch (Tuple[int, ...]): Leading batch shape.
+        m (int): Number of rows.
+        n (int): Number of columns.
+        dtype (torch.dtype): Data type of the tensors. Default is torch.float64.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Prefix of the conformance case names. Default is "linalg_rect".
+    """
+    a = torch.randn((*batch, m, n), dtype=dtype)
+    b = torch.randn((*batch, m, 3), dtype=dtype)
+    cases = [
+        ("svdvals", {"a": a}, {"y": torch.linalg.svdvals(a)}),
+        ("pinv", {"a": a}, {"y": torch.linalg.pinv(a)}),
+        ("lstsq", {"a": a, "b": b}, {"y": torch.linalg.lstsq(a, b, driver="gelsd").solution}),
+    ]
+    for op, inputs, outputs in cases:
+        save_case(dir, f"{name}_{op}", op, {}, inputs, outputs, atol=1e-8)
+
+if __name__ == "__main__":
+    create_linalg((2, 3), 5, dir="data", name="linalg")
+    create_rectangular((2,), 7, 4, dir="data", name="linalg_tall")
+    create_rectangular((2,), 3, 6, dir="data", name="linalg_wide")
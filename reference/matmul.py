@@ -0,0 +1,68 @@
+import torch
+from util.manifest import save_case
+from typing import Tuple, List, Iterable
+
+
+def create_matmul(
+    shape1: Tuple[int, ...] | List[int] | Iterable[int],
+    shape2: Tuple[int, ...] | List[int] | Iterable[int],
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "matmul",
+):
+    """
+    Create two tensors of random values with the given shapes and dtype, multiply them with
+    `torch.matmul`, and save the operands and product as references.
+    Args:
+        shape1 (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the left operand.
+        shape2 (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the right operand.
+        dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "matmul".
+    """
+    x = torch.randn(shape1, dtype=dtype)
+    y = torch.randn(shape2, dtype=dtype)
+    z = torch.matmul(x, y)
+    save_case(dir, name, "matmul", {}, {"x": x, "y": y}, {"z": z}, atol=1e-4)
+
+
+def create_baddbmm(
+    batch: int,
+    n: int,
+    m: int,
+    p: int,
+    beta: float,
+    alpha: float,
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "baddbmm",
+):
+    """
+    Create random operands and save the result of `torch.baddbmm` with the given beta and alpha.
+    Args:
+        batch (int): Batch size.
+        n (int): Rows of the first batch of matrices.
+        m (int): Shared inner dimension.
+        p (int): Columns of the second batch of matrices.
+        beta (float): Multiplier for the input.
+        alpha (float): Multiplier for the batched product.
+        dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "baddbmm".
+    """
+    input = torch.randn((n, p), dtype=dtype)
+    batch1 = torch.randn((batch, n, m), dtype=dtype)
+    batch2 = torch.randn((batch, m, p), dtype=dtype)
+    y = torch.baddbmm(input, batch1, batch2, beta=beta, alpha=alpha)
+    args = {"beta": beta, "alpha": alpha}
+    inputs = {"input": input, "batch1": batch1, "batch2": batch2}
+    save_case(dir, name, "baddbmm", args, inputs, {"y": y}, atol=1e-4)
+
+
+if __name__ == "__main__":
+    create_matmul((7,), (7,), dir="data", name="matmul_1d_1d")
+    create_matmul((5, 7), (7, 6), dir="data", name="matmul_2d_2d")
+    create_matmul((7,), (3, 7, 6), dir="data", name="matmul_1d_3d")
+    create_matmul((4, 1, 5, 7), (3, 7, 6), dir="data", name="matmul_4d_3d")
+    create_matmul((4, 3, 5, 7), (7,), dir="data", name="matmul_4d_1d")
+    create_baddbmm(4, 5, 7, 6, beta=0.5, alpha=2.0, dir="data", name="baddbmm")
@@ -1,5 +1,5 @@
 import torch
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 def create_max(
@@ -14,15 +14,14 @@ def create_max(
         shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "../data".
-        name (str): Name of the reference tensor file. Default is "max".
+        name (str): Prefix of the conformance case names. Default is "max".
     """
     x = torch.rand(shape, dtype=dtype)
-    # get max_v and max_i for each dimension, and save them separately, also save original `x`
-    save_reference(x, dir, f"{name}_max_x")
+    # get max_v and max_i for each dimension
     for dim in range(len(shape)):
         max_v, max_i = torch.max(x, dim=dim)
-        save_reference(max_v, dir, f"{name}_max_v_dim{dim}")
-        save_reference(max_i, dir, f"{name}_max_i_dim{dim}")
+        outputs = {"values": max_v, "indices": max_i}
+        save_case(dir, f"{name}_dim{dim}", "max", {"dim": dim}, {"x": x}, outputs)
 
 if __name__ == "__main__":
     create_max((6, 7, 8, 9, 10, 11, 12), dtype=torch.float32, dir="data", name="max")
\ No newline at end of file
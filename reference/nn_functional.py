@@ -0,0 +1,143 @@
+import torch
+import torch.nn.functional as F
+from util.manifest import save_case
+from typing import Tuple, List, Iterable
+
+
+def create_softmax(
+    shape: Tuple[int, ...] | List[int] | Iterable[int],
+    dim: int,
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "softmax",
+):
+    """
+    Create a tensor of random values with the given shape and save it along with its
+    `softmax` and `log_softmax` over `dim` as references.
+    Args:
+        shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor.
+        dim (int): Dimension to normalize over.
+        dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Prefix of the conformance case names. Default is "softmax".
+    """
+    x = torch.randn(shape, dtype=dtype) * 10
+    save_case(dir, name, "softmax", {"dim": dim}, {"x": x}, {"y": F.softmax(x, dim=dim)}, atol=1e-6)
+    y = F.log_softmax(x, dim=dim)
+    save_case(dir, f"{name}_log", "log_softmax", {"dim": dim}, {"x": x}, {"y": y}, atol=1e-4)
+
+
+def create_layer_norm(
+    shape: Tuple[int, ...] | List[int] | Iterable[int],
+    normalized_shape: Tuple[int, ...],
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "layer_norm",
+):
+    """
+    Create a random input, weight and bias and save the results of `layer_norm` (with and
+    without the affine parameters) and `rms_norm` as references.
+    Args:
+        shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the input.
+        normalized_shape (Tuple[int, ...]): Trailing shape to normalize over.
+        dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Prefix of the conformance case names. Default is "layer_norm".
+    """
+    x = torch.randn(shape, dtype=dtype)
+    weight = torch.randn(normalized_shape, dtype=dtype)
+    bias = torch.randn(normalized_shape, dtype=dtype)
+    # eps is PyTorch's default, spelled out because the Rust layer_norm takes it explicitly
+    args = {"normalized_shape": list(normalized_shape), "eps": 1e-5}
+    y = F.layer_norm(x, normalized_shape)
+    save_case(dir, f"{name}_plain", "layer_norm", args, {"x": x}, {"y": y}, atol=1e-4)
+    y = F.layer_norm(x, normalized_shape, weight, bias)
+    inputs = {"x": x, "weight": weight, "bias": bias}
+    save_case(dir, f"{name}_affine", "layer_norm", args, inputs, {"y": y}, atol=1e-4)
+    # A null eps makes rms_norm use the machine epsilon of the dtype, like PyTorch
+    args = {"normalized_shape": list(normalized_shape), "eps": None}
+    y = F.rms_norm(x, normalized_shape, weight)
+    save_case(dir, f"{name}_rms", "rms_norm", args, {"x": x, "weight": weight}, {"y": y}, atol=1e-4)
+
+
+def create_group_norm(
+    shape: Tuple[int, ...] | List[int] | Iterable[int],
+    num_groups: int,
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "group_norm",
+):
+    """
+    Create a random `(N, C, *)` input with per-channel weight and bias and save the result
+    of `group_norm` as a reference.
+    Args:
+        shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the input.
+        num_groups (int): Number of channel groups.
+        dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "group_norm".
+    """
+    x = torch.randn(shape, dtype=dtype)
+    weight = torch.randn(shape[1], dtype=dtype)
+    bias = torch.randn(shape[1], dtype=dtype)
+    y = F.group_norm(x, num_groups, weight, bias)
+    args = {"num_groups": num_groups, "eps": 1e-5}
+    inputs = {"x": x, "weight": weight, "bias": bias}
+    save_case(dir, name, "group_norm", args, inputs, {"y": y}, atol=1e-4)
+
+
+def create_linear(
+    shape: Tuple[int, ...] | List[int] | Iterable[int],
+    out_features: int,
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "linear",
+):
+    """
+    Create a random input, weight and bias and save the result of `linear` as a reference.
+    Args:
+        shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the input, ending in in_features.
+        out_features (int): Number of output features.
+        dtype (torch.dtype): Data type of the tensors. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "linear".
+    """
+    x = torch.randn(shape, dtype=dtype)
+    weight = torch.randn((out_features, shape[-1]), dtype=dtype)
+    bias = torch.randn(out_features, dtype=dtype)
+    y = F.linear(x, weight, bias)
+    save_case(dir, name, "linear", {}, {"x": x, "weight": weight, "bias": bias}, {"y": y}, atol=1e-4)
+
+
+def create_embedding(
+    ids_shape: Tuple[int, ...] | List[int] | Iterable[int],
+    num_embeddings: int,
+    embedding_dim: int,
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "embedding",
+):
+    """
+    Create a random embedding table and random ids and save the looked up rows as a reference.
+    Args:
+        ids_shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the ids tensor.
+        num_embeddings (int): Number of rows in the table.
+        embedding_dim (int): Width of each row.
+        dtype (torch.dtype): Data type of the table. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "embedding".
+    """
+    ids = torch.randint(0, num_embeddings, ids_shape, dtype=torch.int64)
+    table = torch.randn((num_embeddings, embedding_dim), dtype=dtype)
+    y = F.embedding(ids, table)
+    save_case(dir, name, "embedding", {}, {"ids": ids, "table": table}, {"y": y})
+
+
+if __name__ == "__main__":
+    create_softmax((4, 7), -1, dir="data", name="softmax_last")
+    create_softmax((3, 5, 6), 1, dir="data", name="softmax_middle")
+    create_layer_norm((2, 3, 8), (8,), dir="data", name="layer_norm_1d")
+    create_layer_norm((2, 3, 4, 5), (4, 5), dir="data", name="layer_norm_2d")
+    create_group_norm((2, 6, 4, 3), 3, dir="data", name="group_norm")
+    create_linear((2, 3, 5), 4, dir="data", name="linear")
+    create_embedding((2, 5), 10, 4, dir="data", name="embedding")
@@ -1,6 +1,6 @@
 from typing import Tuple, List, Iterable
 import torch
-from util.save_reference import save_reference
+from util.manifest import save_case
 
 def create_ones(
     shape: Tuple[int, ...] | List[int] | Iterable[int],
@@ -14,12 +14,11 @@ def create_ones(
         shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "../data".
-        name (str): Name of the reference tensor file. Default is "ones".
+        name (str): Name of the conformance case. Default is "ones".
     """
     # Create a tensor of ones
     x = torch.ones(shape, dtype=dtype)
-    # Save reference
-    save_reference(x, dir, name)
+    save_case(dir, name, "ones", {"shape": list(shape)}, {}, {"y": x})
 
 if __name__ == "__main__":
     create_ones((2, 3), dtype=torch.float32, dir="data", name="ones")
\ No newline at end of file
@@ -0,0 +1,202 @@
+import os
+import struct
+from typing import Iterable, List, Tuple
+
+# The `onnx` package is not needed: the few messages the models use are encoded by hand,
+# following the field numbers of onnx/onnx.proto.
+
+FLOAT, INT64 = 1, 7
+ATTR_FLOAT, ATTR_INT, ATTR_STRING, ATTR_TENSOR, ATTR_INTS = 1, 2, 3, 4, 7
+
+
+def varint(value: int) -> bytes:
+    value &= (1 << 64) - 1  # negative int64 values take ten bytes, as in protobuf
+    out = bytearray()
+    while True:
+        byte = value & 0x7F
+        value >>= 7
+        if value:
+            out.append(byte | 0x80)
+        else:
+            out.append(byte)
+            return bytes(out)
+
+
+def key(field: int, wire_type: int) -> bytes:
+    return varint(field << 3 | wire_type)
+
+
+def int_field(field: int, value: int) -> bytes:
+    return key(field, 0) + varint(value)
+
+
+def bytes_field(field: int, value: bytes) -> bytes:
+    return key(field, 2) + varint(len(value)) + value
+
+
+def string_field(field: int, value: str) -> bytes:
+    return bytes_field(field, value.encode())
+
+
+def tensor(
+    name: str, shape: Iterable[int], values: List[float] | List[int], data_type: int, raw: bool = False
+) -> bytes:
+    """
+    Encode a TensorProto. Values go to `raw_data` if `raw` is set, and to the packed typed field
+    otherwise, so both layouts exporters produce are covered.
+    """
+    out = b"".join(int_field(1, d) for d in shape)  # dims, unpacked
+    out += int_field(2, data_type)
+    fmt = "<f" if data_type == FLOAT else "<q"
+    if raw:
+        out += bytes_field(9, b"".join(struct.pack(fmt, v) for v in values))
+    elif data_type == FLOAT:
+        out += bytes_field(4, b"".join(struct.pack("<f", v) for v in values))
+    else:
+        out += bytes_field(7, b"".join(varint(v) for v in values))
+    return out + string_field(8, name)
+
+
+def attribute(name: str, value) -> bytes:
+    out = string_field(1, name)
+    if isinstance(value, float):
+        return out + key(2, 5) + struct.pack("<f", value) + int_field(20, ATTR_FLOAT)
+    if isinstance(value, int):
+        return out + int_field(3, value) + int_field(20, ATTR_INT)
+    if isinstance(value, str):
+        return out + bytes_field(4, value.encode()) + int_field(20, ATTR_STRING)
+    if isinstance(value, bytes):
+        return out + bytes_field(5, value) + int_field(20, ATTR_TENSOR)
+    return out + b"".join(int_field(8, v) for v in value) + int_field(20, ATTR_INTS)
+
+
+def node(op_type: str, inputs: List[str], outputs: List[str], domain: str = "", **attributes) -> bytes:
+    out = b"".join(string_field(1, i) for i in inputs)
+    out += b"".join(string_field(2, o) for o in outputs)
+    out += string_field(4, op_type)
+    out += b"".join(bytes_field(5, attribute(k, v)) for k, v in attributes.items())
+    if domain:
+        out += string_field(7, domain)
+    return out
+
+
+def model(
+    nodes: List[bytes],
+    inputs: List[str],
+    outputs: List[str],
+    initializers: List[bytes] = [],
+    opset: int = 13,
+    extra_opsets: List[Tuple[str, int]] = [],
+) -> bytes:
+    graph = b"".join(bytes_field(1, n) for n in nodes)
+    graph += string_field(2, "test")
+    graph += b"".join(bytes_field(5, t) for t in initializers)
+    graph += b"".join(bytes_field(11, string_field(1, i)) for i in inputs)
+    graph += b"".join(bytes_field(12, string_field(1, o)) for o in outputs)
+    opsets = [("", opset)] + extra_opsets
+    out = int_field(1, 8) + string_field(2, "RustOps reference")
+    out += bytes_field(7, graph)
+    for domain, version in opsets:
+        out += bytes_field(8, (string_field(1, domain) if domain else b"") + int_field(2, version))
+    return out
+
+
+def create_onnx_models(dir: str = "tests/onnx"):
+    """
+    Write the small ONNX models the interpreter is tested against.
+    Args:
+        dir (str): Directory to save the models to. Default is "tests/onnx".
+    """
+    models = {
+        # x[[2, -3]] -> abs -> max and argmax along the last axis
+        "gather_abs_reduce": model(
+            [
+                node("Gather", ["x", "rows"], ["picked"], axis=0),
+                node("Abs", ["picked"], ["magnitude"]),
+                node("ReduceMax", ["magnitude"], ["max"], axes=[1], keepdims=0),
+                node("ArgMax", ["magnitude"], ["argmax"], axis=-1),
+            ],
+            ["x"],
+            ["max", "argmax"],
+            [tensor("rows", [2], [2, -3], INT64)],
+        ),
+        # Flatten the trailing axes, transpose, and take row-wise dot products with a broadcast
+        # weight vector
+        "reshape_transpose_einsum": model(
+            [
+                node("Constant", [], ["shape"], value=tensor("", [2], [0, -1], INT64, raw=True)),
+                node("Reshape", ["x", "shape"], ["flat"]),
+                node("Transpose", ["flat"], ["columns"], perm=[1, 0]),
+                node("Expand", ["w", "expand_shape"], ["weights"]),
+                node("Einsum", ["columns", "weights"], ["y"], equation="ij,ij->i"),
+            ],
+            ["x"],
+            ["y"],
+            [
+                tensor("w", [2], [0.5, -2.0], FLOAT, raw=True),
+                tensor("expand_shape", [2], [6, 1], INT64),
+            ],
+            opset=14,
+        ),
+        # Nodes are stored out of order; opset 18 passes the ReduceMax axes as an input
+        "slice_scatter": model(
+            [
+                node("ReduceMax", ["picked", "axes"], ["row_max"]),
+                node("GatherElements", ["scattered", "gather_indices"], ["picked"], axis=1),
+                node("ScatterElements", ["sliced", "indices", "updates"], ["scattered"], axis=0),
+                node("Slice", ["data", "starts", "ends", "slice_axes", "steps"], ["sliced"]),
+            ],
+            ["data"],
+            ["scattered", "picked", "row_max"],
+            [
+                tensor("starts", [2], [-1, 0], INT64),
+                tensor("ends", [2], [-10, 5], INT64),
+                tensor("slice_axes", [2], [0, 1], INT64),
+                tensor("steps", [2], [-2, 2], INT64),
+                tensor("indices", [1, 3], [1, 0, 1], INT64),
+                tensor("updates", [1, 3], [10.0, 20.0, 30.0], FLOAT),
+                tensor("gather_indices", [2, 2], [2, -3, 0, 0], INT64),
+                tensor("axes", [1], [-1], INT64),
+            ],
+            opset=18,
+        ),
+        # Reverses both axes with negative steps; the data may be empty along either of them
+        "slice_reverse": model(
+            [node("Slice", ["data", "starts", "ends", "axes", "steps"], ["reversed"])],
+            ["data"],
+            ["reversed"],
+            [
+                tensor("starts", [2], [-1, -1], INT64),
+                tensor("ends", [2], [-(2**63), -(2**63)], INT64),
+                tensor("axes", [2], [0, 1], INT64),
+                tensor("steps", [2], [-1, -1], INT64),
+            ],
+            opset=13,
+        ),
+        # Every bound is a graph input, so tests can try extreme starts, ends and steps
+        "slice_steps": model(
+            [node("Slice", ["data", "starts", "ends", "axes", "steps"], ["sliced"])],
+            ["data", "starts", "ends", "axes", "steps"],
+            ["sliced"],
+        ),
+        "unsupported": model(
+            [
+                node("Relu", ["x"], ["a"]),
+                node("Abs", ["a"], ["b"]),
+                node("Softmax", ["b"], ["c"], axis=-1),
+                node("FusedMatMul", ["c", "c"], ["d"], domain="com.microsoft"),
+                node("Relu", ["d"], ["y"]),
+            ],
+            ["x"],
+            ["y"],
+            extra_opsets=[("com.microsoft", 1)],
+        ),
+    }
+    os.makedirs(dir, exist_ok=True)
+    for name, data in models.items():
+        with open(os.path.join(dir, f"{name}.onnx"), "wb") as f:
+            f.write(data)
+
+
+if __name__ == "__main__":
+    create_onnx_models()
@@ -0,0 +1,42 @@
+import torch
+import torch.nn.functional as F
+from util.manifest import save_case
+from typing import Tuple, List, Iterable
+
+
+def create_pad(
+    shape: Tuple[int, ...] | List[int] | Iterable[int],
+    pad: Tuple[int, ...],
+    mode: str,
+    value: float = 0.0,
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "pad",
+):
+    """
+    Create a tensor of random values with the given shape and dtype, pad it with
+    `torch.nn.functional.pad`, and save the original and padded tensors as references.
+    Args:
+        shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
+        pad (Tuple[int, ...]): Flat pad list starting from the last dimension, may be negative.
+        mode (str): One of "constant", "reflect", "replicate" or "circular".
+        value (float): Fill value for constant padding. Default is 0.0.
+        dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "pad".
+    """
+    x = torch.randn(shape, dtype=dtype)
+    if mode == "constant":
+        y = F.pad(x, pad, mode=mode, value=value)
+    else:
+        y = F.pad(x, pad, mode=mode)
+    args = {"pad": list(pad), "mode": mode, "value": value}
+    save_case(dir, name, "pad", args, {"x": x}, {"y": y})
+
+
+if __name__ == "__main__":
+    create_pad((2, 3, 8), pad=(2, 3), mode="constant", value=1.5, dir="data", name="pad_constant")
+    create_pad((2, 3, 8), pad=(-1, 2, 1, -2), mode="constant", dir="data", name="pad_constant_crop")
+    create_pad((2, 3, 8), pad=(3, 2), mode="reflect", dir="data", name="pad_reflect")
+    create_pad((2, 3, 6, 7), pad=(1, 2, 3, 1), mode="replicate", dir="data", name="pad_replicate")
+    create_pad((2, 3, 6, 7), pad=(2, 3, 1, 2), mode="circular", dir="data", name="pad_circular")
@@ -0,0 +1,48 @@
+import torch
+from util.save_bundle import save_bundle
+import os
+
+
+def create_checkpoint(
+    dir: str = "data",
+    name: str = "checkpoint",
+):
+    """
+    Save a training checkpoint with torch.save, together with an npz bundle of every tensor in it
+    (half precision ones upcast to float32). The checkpoint nests a state dict, shares storage
+    between a tensor and its transpose and includes a view with a storage offset.
+    Args:
+        dir (str): Directory to save the reference files. Default is "data".
+        name (str): Name of the reference files. Default is "checkpoint".
+    """
+    model = torch.nn.Sequential(
+        torch.nn.Linear(3, 4),
+        torch.nn.BatchNorm1d(4),
+        torch.nn.Embedding(5, 2).to(torch.float16),
+    )
+    base = torch.randn((4, 6), dtype=torch.float64)
+    checkpoint = {
+        "model": model.state_dict(),
+        "base": base,
+        "transposed": base.t(),
+        "window": base[1:, 2:5],
+        "mask": torch.rand((2, 3)) > 0.5,
+        "bf16": torch.randn((3,)).to(torch.bfloat16),
+        "epoch": 7,
+    }
+    os.makedirs(dir, exist_ok=True)
+    torch.save(checkpoint, os.path.join(dir, f"{name}.pt"))
+
+    expected = {f"model.{key}": value for key, value in checkpoint["model"].items()}
+    expected.update(
+        {key: value for key, value in checkpoint.items() if isinstance(value, torch.Tensor)}
+    )
+    expected = {
+        key: value.float() if value.dtype in (torch.float16, torch.bfloat16) else value
+        for key, value in expected.items()
+    }
+    save_bundle(expected, dir, name)
+
+
+if __name__ == "__main__":
+    create_checkpoint(dir="data", name="checkpoint")
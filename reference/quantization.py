@@ -0,0 +1,73 @@
+import torch
+from torch.ao.nn.quantized import functional as qF
+from torch.ao.quantization import MinMaxObserver, PerChannelMinMaxObserver
+from util.manifest import save_case
+
+
+def create_quantization(
+    rows: int,
+    in_features: int,
+    out_features: int,
+    dir: str = "data",
+    name: str = "quantization",
+):
+    """
+    Calibrate and quantize a linear layer the way eager-mode post-training quantization does
+    (quint8 affine activations, qint8 per-channel symmetric weights), and save conformance cases
+    for the observers and for the quantized linear layer.
+    Args:
+        rows (int): Number of input rows.
+        in_features (int): Size of each input row.
+        out_features (int): Size of each output row.
+        dir (str): Directory to save the reference tensors. Default is "data".
+        name (str): Prefix of the conformance case names. Default is "quantization".
+    """
+    x = torch.randn((rows, in_features))
+    w = torch.randn((out_features, in_features)) * 0.1
+    b = torch.randn(out_features)
+
+    x_observer = MinMaxObserver(dtype=torch.quint8)
+    x_observer(x)
+    x_scale, x_zero_point = x_observer.calculate_qparams()
+    xq = torch.quantize_per_tensor(x, x_scale.item(), x_zero_point.item(), torch.quint8)
+
+    w_observer = PerChannelMinMaxObserver(
+        ch_axis=0, dtype=torch.qint8, qscheme=torch.per_channel_symmetric
+    )
+    w_observer(w)
+    w_scales, w_zero_points = w_observer.calculate_qparams()
+    wq = torch.quantize_per_channel(w, w_scales, w_zero_points, 0, torch.qint8)
+
+    y_observer = MinMaxObserver(dtype=torch.quint8)
+    y_observer(torch.nn.functional.linear(xq.dequantize(), wq.dequantize(), b))
+    y_scale, y_zero_point = y_observer.calculate_qparams()
+    yq = qF.linear(xq, wq, b, y_scale.item(), y_zero_point.item())
+
+    # npy has no 8-bit quantized dtypes, so integer representations are stored as int64
+    outputs = {
+        "scale": x_scale.float().reshape(()),
+        "zero_point": x_zero_point.long().reshape(()),
+        "int_repr": xq.int_repr().long(),
+    }
+    save_case(dir, f"{name}_x", "quantize_min_max", {}, {"x": x}, outputs)
+    outputs = {"scales": w_scales.float(), "int_repr": wq.int_repr().long()}
+    args = {"axis": 0}
+    save_case(dir, f"{name}_w", "quantize_per_channel_min_max", args, {"x": w}, outputs)
+    args = {
+        "x_scale": x_scale.item(),
+        "x_zero_point": x_zero_point.item(),
+        "y_scale": y_scale.item(),
+        "y_zero_point": y_zero_point.item(),
+    }
+    inputs = {
+        "x_int": xq.int_repr().long(),
+        "w_int": wq.int_repr().long(),
+        "w_scales": w_scales.float(),
+        "w_zero_points": w_zero_points.long(),
+        "b": b,
+    }
+    # FBGEMM may sum the bias in a different order, which can move a tie by one step
+    save_case(dir, f"{name}_linear", "qlinear", args, inputs, {"y_int": yq.int_repr().long()}, atol=1)
+
+if __name__ == "__main__":
+    create_quantization(8, 64, 16, dir="data", name="quantization")
@@ -1,6 +1,6 @@
 import torch
 from einops import rearrange
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 
@@ -16,14 +16,11 @@ def test_rearrange(
         shape (Tuple[int, ...]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "rearranged".
+        name (str): Name of the conformance case. Default is "rearranged".
     """
     reservations = torch.rand(shape, dtype=dtype)
     rearranged = rearrange(reservations, 'batch mems flag -> mems (batch flag)')
-    # Save the original tensor
-    save_reference(reservations, dir, f"{name}_rearrange_original")
-    # Save the rearranged tensor
-    save_reference(rearranged, dir, f"{name}_rearrange_result")
+    save_case(dir, name, "rearrange_batch_mems_flag", {}, {"x": reservations}, {"y": rearranged})
 
 if __name__ == "__main__":
     test_rearrange((2, 3, 4), dtype=torch.float32, dir="data", name="rearranged")
\ No newline at end of file
@@ -1,6 +1,6 @@
 import torch
 from einops import reduce
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 def test_reduce_sum(
@@ -15,14 +15,12 @@ def test_reduce_sum(
         shape (Tuple[int, ...]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "reduced".
+        name (str): Name of the conformance case. Default is "reduced".
     """
     x = torch.rand(shape, dtype=dtype)
     y = reduce(x, 'batch nodes mems -> nodes mems', 'sum')
-    # Save the original tensor
-    save_reference(x, dir, f"{name}_reduce_bnm_nm_reduce_sum_x")
-    # Save the reduced tensor
-    save_reference(y, dir, f"{name}_reduce_bnm_nm_reduce_sum_y")
+    # The Rust reduce contracts the input with ones, so its equation names two operands
+    save_case(dir, name, "reduce", {"equation": "bnm,bnm->nm"}, {"x": x}, {"y": y}, atol=1e-5)
 
 
 def test_reduce_sum_batch_fields_memories(
@@ -37,14 +35,12 @@ def test_reduce_sum_batch_fields_memories(
         shape (Tuple[int, ...]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "reduced_batch_fields_memories".
+        name (str): Name of the conformance case. Default is "reduced_batch_fields_memories".
     """
     x = torch.rand(shape, dtype=dtype)
     y = reduce(x, 'batch fields memories dim -> batch fields memories', 'sum')
-    # Save the original tensor
-    save_reference(x, dir, f"{name}_reduce_bfmd_bfm_x")
-    # Save the reduced tensor
-    save_reference(y, dir, f"{name}_reduce_bfmd_bfm_y")
+    # The Rust reduce contracts the input with ones, so its equation names two operands
+    save_case(dir, name, "reduce", {"equation": "bfmd,bfmd->bfm"}, {"x": x}, {"y": y}, atol=1e-5)
 
 
 def test_reduce_sum_batch_field(
@@ -59,14 +55,12 @@ def test_reduce_sum_batch_field(
         shape (Tuple[int, ...]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "reduced_batch_field".
+        name (str): Name of the conformance case. Default is "reduced_batch_field".
     """
     x = torch.rand(shape, dtype=dtype)
     y = reduce(x, 'batch field dim -> batch field', 'sum')
-    # Save the original tensor
-    save_reference(x, dir, f"{name}_reduce_bfd_bf_x")
-    # Save the reduced tensor
-    save_reference(y, dir, f"{name}_reduce_bfd_bf_y")
+    # The Rust reduce contracts the input with ones, so its equation names two operands
+    save_case(dir, name, "reduce", {"equation": "bfd,bfd->bf"}, {"x": x}, {"y": y}, atol=1e-5)
 
 def test_reduce_sum_batch_hidden(
     shape: Tuple[int, ...] | List[int] | Iterable[int],
@@ -80,14 +74,12 @@ def test_reduce_sum_batch_hidden(
         shape (Tuple[int, ...]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "reduced_batch_hidden".
+        name (str): Name of the conformance case. Default is "reduced_batch_hidden".
     """
     x = torch.rand(shape, dtype=dtype)
     y = reduce(x, 'batch hidden children c_mems -> batch hidden', 'sum')
-    # Save the original tensor
-    save_reference(x, dir, f"{name}_reduce_bhccm_bh_x")
-    # Save the reduced tensor
-    save_reference(y, dir, f"{name}_reduce_bhccm_bh_y")
+    # The Rust reduce contracts the input with ones, so its equation names two operands
+    save_case(dir, name, "reduce", {"equation": "bhcm,bhcm->bh"}, {"x": x}, {"y": y}, atol=1e-5)
 
 
 if __name__ == "__main__":
@@ -0,0 +1,40 @@
+import torch
+from util.manifest import save_case
+from typing import Tuple, List, Iterable
+
+
+def create_repeat_interleave(
+    shape: Tuple[int, ...] | List[int] | Iterable[int],
+    repeats: int | List[int],
+    dim: int | None,
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "repeat_interleave",
+):
+    """
+    Create a tensor of random values with the given shape and dtype, apply repeat_interleave
+    along `dim`, and save the original tensor, repeats and result as references.
+    Args:
+        shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
+        repeats (int | List[int]): Scalar repeat count, or one count per element along `dim`.
+        dim (int | None): Dimension to repeat along. None flattens the tensor first.
+        dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "repeat_interleave".
+    """
+    x = torch.randn(shape, dtype=dtype)
+    if isinstance(repeats, int):
+        y = torch.repeat_interleave(x, repeats, dim=dim)
+        args, inputs = {"repeats": repeats, "dim": dim}, {"x": x}
+    else:
+        # Per-element counts are passed as a tensor input instead of an argument
+        repeats = torch.tensor(repeats, dtype=torch.int64)
+        y = torch.repeat_interleave(x, repeats, dim=dim)
+        args, inputs = {"dim": dim}, {"x": x, "repeats": repeats}
+    save_case(dir, name, "repeat_interleave", args, inputs, {"y": y})
+
+
+if __name__ == "__main__":
+    create_repeat_interleave((4, 5, 6), repeats=3, dim=1, dir="data", name="repeat3d_scalar3_dim1")
+    create_repeat_interleave((4, 5, 6), repeats=2, dim=None, dir="data", name="repeat3d_scalar2_flat")
+    create_repeat_interleave((4, 5, 6), repeats=[0, 1, 2, 3, 1, 2], dim=-1, dir="data", name="repeat3d_tensor_dimneg1")
@@ -1,5 +1,5 @@
 import torch
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 # filepath: /media/john/Tertiary/Projects/ML/RustOps/reference/reshape.py
@@ -18,14 +18,11 @@ def create_reshape(
         new_shape (Tuple[int, int]): New 2D shape to reshape the tensor into.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "reshape".
+        name (str): Name of the conformance case. Default is "reshape".
     """
     x = torch.rand(shape, dtype=dtype)
-    # Save the original tensor
-    save_reference(x, dir, f"{name}_reshape_original")
-    # Reshape the tensor
     reshaped_x = x.reshape(new_shape)
-    save_reference(reshaped_x, dir, f"{name}_reshape_reshaped")
+    save_case(dir, name, "reshape", {"shape": list(new_shape)}, {"x": x}, {"y": reshaped_x})
 
 if __name__ == "__main__":
     create_reshape((8, 3, 10), new_shape=(8, -1), dtype=torch.float32, dir="data", name="reshape2d")
@@ -0,0 +1,58 @@
+import torch
+from util.manifest import save_case
+
+
+def create_rng(
+    seed: int,
+    dir: str = "data",
+    name: str = "rng",
+):
+    """
+    Draw from every random function supported by `RustOps::rng` with a freshly seeded generator
+    and save the results, so the Rust side can check that it reproduces the same streams.
+    Args:
+        seed (int): Seed passed to `torch.manual_seed` before each draw.
+        dir (str): Directory to save the reference tensors. Default is "data".
+        name (str): Prefix of the conformance case names. Default is "rng".
+    """
+    def draw(part: str, op: str, args: dict, f, inputs: dict = {}, atol: float = 0.0):
+        torch.manual_seed(seed)
+        y = f()
+        args = {"seed": seed, **args}
+        save_case(dir, f"{name}_{part}", op, args, inputs, {"y": y}, atol=atol)
+
+    for dtype, suffix in ((torch.float32, "f32"), (torch.float64, "f64")):
+        args = {"shape": [3, 7], "dtype": str(dtype).removeprefix("torch.")}
+        draw(f"rand_{suffix}", "rand", args, lambda: torch.rand(3, 7, dtype=dtype))
+    args = {"shape": [10], "dtype": "float32", "low": -2.0, "high": 3.0}
+    draw("uniform_f32", "uniform_", args, lambda: torch.empty(10).uniform_(-2.0, 3.0))
+    # Small tensors take the cached Box-Muller path, larger ones the blocked fill, with and
+    # without a ragged tail. x86 builds of PyTorch fill float32 tensors of 16+ elements with
+    # AVX2 `log`/`cos`, so float32 draws may differ in the last bits
+    for size in (5, 16, 37):
+        args = {"shape": [size], "dtype": "float32"}
+        draw(f"randn_f32_{size}", "randn", args, lambda: torch.randn(size), atol=1e-6)
+        args = {"shape": [size], "dtype": "float64"}
+        draw(f"randn_f64_{size}", "randn", args, lambda: torch.randn(size, dtype=torch.float64))
+    args = {"shape": [40], "dtype": "float64", "mean": 2.0, "std": 0.5}
+    draw("normal_f64", "normal_", args, lambda: torch.empty(40, dtype=torch.float64).normal_(2.0, 0.5))
+    args = {"low": -5, "high": 17, "shape": [4, 6]}
+    draw("randint_small", "randint", args, lambda: torch.randint(-5, 17, (4, 6)))
+    args = {"low": 0, "high": 2**40, "shape": [8]}
+    draw("randint_large", "randint", args, lambda: torch.randint(0, 2**40, (8,)))
+    draw("randperm", "randperm", {"n": 20}, lambda: torch.randperm(20))
+    p = torch.linspace(0, 1, 24, dtype=torch.float64).reshape(4, 6)
+    draw("bernoulli_f32", "bernoulli", {}, lambda: torch.bernoulli(p.float()), {"p": p.float()})
+    draw("bernoulli_f64", "bernoulli", {}, lambda: torch.bernoulli(p), {"p": p})
+    weights = torch.tensor([[1.0, 2.0, 3.0, 4.0, 0.0], [0.5, 0.0, 0.5, 3.0, 1.0]])
+    inputs = {"weights": weights}
+    args = {"num_samples": 12, "replacement": True}
+    f = lambda: torch.multinomial(weights, 12, replacement=True)
+    draw("multinomial_replacement", "multinomial", args, f, inputs)
+    args = {"num_samples": 1, "replacement": False}
+    draw("multinomial_single", "multinomial", args, lambda: torch.multinomial(weights, 1), inputs)
+    # Sampling several categories without replacement is not recorded: MKL builds of PyTorch
+    # draw the exponentials of that path from their own stream
+
+if __name__ == "__main__":
+    create_rng(0, dir="data", name="rng")
@@ -0,0 +1,35 @@
+import torch
+from util.manifest import save_case
+from typing import Tuple, List, Iterable
+
+
+def create_roll(
+    shape: Tuple[int, ...] | List[int] | Iterable[int],
+    shifts: Tuple[int, ...],
+    dims: Tuple[int, ...] | None,
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "roll",
+):
+    """
+    Create a tensor of random values with the given shape and dtype, roll it by `shifts` along
+    `dims`, and save the original and rolled tensors as references.
+    Args:
+        shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
+        shifts (Tuple[int, ...]): Number of places to shift, may be negative.
+        dims (Tuple[int, ...] | None): Dimensions to roll. None rolls the flattened tensor.
+        dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "roll".
+    """
+    x = torch.randn(shape, dtype=dtype)
+    y = torch.roll(x, shifts=shifts, dims=dims)
+    # An empty dims list rolls the flattened tensor, like None
+    args = {"shifts": list(shifts), "dims": list(dims or ())}
+    save_case(dir, name, "roll", args, {"x": x}, {"y": y})
+
+
+if __name__ == "__main__":
+    create_roll((10, 11, 12), shifts=(3,), dims=(1,), dir="data", name="roll3d_shift3_dim1")
+    create_roll((10, 11, 12), shifts=(-5, 14), dims=(0, -1), dir="data", name="roll3d_shiftneg5_14_dim0_neg1")
+    create_roll((10, 11, 12), shifts=(7,), dims=None, dir="data", name="roll3d_shift7_flat")
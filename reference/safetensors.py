@@ -0,0 +1,36 @@
+import torch
+from safetensors.torch import save_file
+from util.save_reference import save_reference
+import os
+
+
+def create_safetensors(
+    dir: str = "data",
+    name: str = "weights",
+):
+    """
+    Save a small state dict with one tensor per supported dtype as a safetensors file,
+    together with npy copies of every tensor (half precision ones upcast to float32).
+    Args:
+        dir (str): Directory to save the reference files. Default is "data".
+        name (str): Name of the reference files. Default is "weights".
+    """
+    tensors = {
+        "linear.weight": torch.randn((4, 3), dtype=torch.float32),
+        "linear.bias": torch.randn((4,), dtype=torch.float64),
+        "embed.weight": torch.randn((5, 2), dtype=torch.float16),
+        "norm.weight": torch.randn((6,), dtype=torch.bfloat16),
+        "position_ids": torch.arange(7, dtype=torch.int64).reshape(1, 7),
+        "mask": torch.rand((2, 3)) > 0.5,
+        "scalar": torch.tensor(1.5, dtype=torch.float32),
+    }
+    os.makedirs(dir, exist_ok=True)
+    save_file(tensors, os.path.join(dir, f"{name}.safetensors"), metadata={"format": "pt"})
+    for key, value in tensors.items():
+        if value.dtype in (torch.float16, torch.bfloat16):
+            value = value.float()
+        save_reference(value, dir, f"{name}_{key}")
+
+
+if __name__ == "__main__":
+    create_safetensors(dir="data", name="weights")
@@ -1,5 +1,5 @@
 import torch
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 
@@ -16,7 +16,7 @@ def create_scatter(
         shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "scatter2d".
+        name (str): Name of the conformance case. Default is "scatter2d".
     """
     x = torch.randn(shape, dtype=dtype)
     indices = torch.argmax(x, dim=-1, keepdim=True)
@@ -26,11 +26,15 @@ def create_scatter(
     blank = torch.zeros_like(x)
     scattered = torch.scatter(blank, -1, indices, gathered)
 
-    save_reference(x, dir, f"{name}_scatter_x")
-    save_reference(indices, dir, f"{name}_scatter_indices")
-    save_reference(gathered, dir, f"{name}_scatter_gathered")
-    save_reference(blank, dir, f"{name}_scatter_blank")
-    save_reference(scattered, dir, f"{name}_scatter_scattered")
+    save_case(
+        dir,
+        name,
+        "scatter",
+        {"dim": -1},
+        {"target": blank, "indices": indices, "src": gathered},
+        {"y": scattered},
+        atol=1e-5,
+    )
 
 if __name__ == "__main__":
     d2 = (10, 11)
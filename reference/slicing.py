@@ -1,5 +1,5 @@
 import torch
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 
@@ -7,7 +7,7 @@ def test_slicingccn1(
     shape: Tuple[int, ...] | List[int] | Iterable[int],
     dtype: torch.dtype = torch.float32,
     dir: str = "data",
-    name: str = "sliced",
+    name: str = "sliced_last",
 ):
     """
     Test slicing operation `[:, :, -1:]` on a tensor.
@@ -15,21 +15,18 @@ def test_slicingccn1(
         shape (Tuple[int, ...]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "sliced".
+        name (str): Name of the conformance case. Default is "sliced_last".
     """
     x = torch.rand(shape, dtype=dtype)
     y = x[:, :, -1:]
-    # Save the original tensor
-    save_reference(x, dir, f"{name}_sliced[:, :, -1:]_x")
-    # Save the sliced tensor
-    save_reference(y, dir, f"{name}_sliced[:, :, -1:]_y")
+    save_case(dir, name, "slice_last_dim", {}, {"x": x}, {"y": y})
 
 def test_slicingcb(
     shape: Tuple[int, ...] | List[int] | Iterable[int],
     slice_amount: int,
     dtype: torch.dtype = torch.float32,
     dir: str = "data",
-    name: str = "sliced",
+    name: str = "sliced_second",
 ):
     """
     Test slicing operation `[:, :batch_size]` on a tensor. Here `batch_size` is given
@@ -38,16 +35,14 @@ def test_slicingcb(
         slice_amount (int): Amount to slice from the tensor.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "sliced".
+        name (str): Name of the conformance case. Default is "sliced_second".
     """
     x = torch.rand(shape, dtype=dtype)
     y = x[:, :slice_amount]
-    # Save the original tensor
-    save_reference(x, dir, f"{name}_sliced[:, :batch_size]_x")
-    # Save the sliced tensor
-    save_reference(y, dir, f"{name}_{slice_amount}_sliced[:, :batch_size]_y")
+    args = {"amount": slice_amount}
+    save_case(dir, name, "slice_second_dim", args, {"x": x}, {"y": y})
 
 
 if __name__ == "__main__":
-    test_slicingccn1((2, 3, 4), dtype=torch.float32, dir="data", name="sliced")
-    test_slicingcb((3, 4), slice_amount=3, dtype=torch.float32, dir="data", name="sliced")
\ No newline at end of file
+    test_slicingccn1((2, 3, 4), dtype=torch.float32, dir="data", name="sliced_last")
+    test_slicingcb((3, 4), slice_amount=3, dtype=torch.float32, dir="data", name="sliced_second")
\ No newline at end of file
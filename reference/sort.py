@@ -1,5 +1,5 @@
 import torch
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 def create_sort(
@@ -9,20 +9,17 @@ def create_sort(
     name: str = "sort",
 ):
     """
-    Create a tensor and save its sorted values and indices along each dimension.
+    Create a tensor and save its values sorted along the last dimension.
     Args:
         shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "sort".
+        name (str): Name of the conformance case. Default is "sort".
     """
     x = torch.rand(shape, dtype=dtype)
-    # Save the original tensor
-    save_reference(x, dir, f"{name}_sort_x")
-    dim= len(shape) - 1
-    sorted_v, sorted_i = torch.sort(x, dim=dim)
-    save_reference(sorted_v, dir, f"{name}_sorted_v_dim{dim}")
-    save_reference(sorted_i, dir, f"{name}_sorted_i_dim{dim}")
+    # sort_last_dim sorts in place and returns no indices
+    y = torch.sort(x, dim=-1).values
+    save_case(dir, name, "sort_last_dim", {}, {"x": x}, {"y": y})
 
 if __name__ == "__main__":
     create_sort((6, 7, 8, 9, 10, 11, 12), dtype=torch.float32, dir="data", name="sort")
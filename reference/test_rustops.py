@@ -0,0 +1,179 @@
+"""
+Side-by-side checks of the `rustops` Python bindings against PyTorch.
+
+Build the module with `maturin develop --release` from the repository root, then run
+`pytest reference/test_rustops.py`.
+"""
+import numpy as np
+import pytest
+import torch
+
+rustops = pytest.importorskip("rustops")
+
+
+def t(x: np.ndarray) -> torch.Tensor:
+    """
+    Wrap a NumPy array as a tensor sharing its memory
+    Args:
+        x (np.ndarray): Array to wrap
+    """
+    return torch.from_numpy(np.ascontiguousarray(x))
+
+
+def assert_matches(actual: np.ndarray, expected: torch.Tensor, atol: float = 0.0):
+    """
+    Assert that a rustops result has the dtype, shape and values of the PyTorch result
+    Args:
+        actual (np.ndarray): Result of the rustops op
+        expected (torch.Tensor): Result of the PyTorch op
+        atol (float): Absolute tolerance. Default is 0.0 (exact).
+    """
+    expected = expected.numpy()
+    assert actual.dtype == expected.dtype
+    assert actual.shape == expected.shape
+    np.testing.assert_allclose(actual, expected, atol=atol, rtol=0)
+
+
+@pytest.fixture
+def rng() -> np.random.Generator:
+    return np.random.default_rng(0)
+
+
+@pytest.mark.parametrize("dtype", [np.float32, np.float64, np.int64])
+@pytest.mark.parametrize("dim", [0, 1, -1])
+def test_gather(rng, dtype, dim):
+    x = (rng.standard_normal((4, 5, 6)) * 10).astype(dtype)
+    index = rng.integers(0, x.shape[dim], (4, 5, 6))
+    assert_matches(rustops.gather(x, dim, index), torch.gather(t(x), dim, t(index)))
+
+
+@pytest.mark.parametrize("dim", [0, 1, -1])
+def test_scatter(rng, dim):
+    x = rng.standard_normal((4, 5, 6)).astype(np.float32)
+    # A permutation per lane keeps the result independent of write order
+    index = np.argsort(rng.standard_normal((4, 5, 6)), axis=dim)
+    src = rng.standard_normal((4, 5, 6)).astype(np.float32)
+    expected = torch.scatter(t(x), dim, t(index), t(src))
+    assert_matches(rustops.scatter(x, dim, index, src), expected)
+
+
+@pytest.mark.parametrize("dim, keepdim", [(None, False), (0, False), (0, True), (-1, True)])
+def test_argmax_and_max(rng, dim, keepdim):
+    x = rng.standard_normal((3, 4, 5)).astype(np.float32)
+    expected = torch.argmax(t(x), dim=dim, keepdim=keepdim)
+    assert_matches(rustops.argmax(x, dim, keepdim), expected)
+    if dim is not None:
+        values, indices = rustops.max(x, dim)
+        expected = torch.max(t(x), dim)
+        assert_matches(values, expected.values)
+        assert_matches(indices, expected.indices)
+
+
+@pytest.mark.parametrize(
+    "equation, shapes",
+    [
+        ("ij,jk->ik", [(3, 4), (4, 5)]),
+        ("bij,bjk->bik", [(2, 3, 4), (2, 4, 5)]),
+        ("bfmd,bfd->bfm", [(2, 3, 4, 5), (2, 3, 5)]),
+        ("ii->i", [(4, 4)]),
+        ("ij->", [(3, 4)]),
+    ],
+)
+def test_einsum(rng, equation, shapes):
+    operands = [rng.standard_normal(shape).astype(np.float32) for shape in shapes]
+    expected = torch.einsum(equation, *map(t, operands))
+    assert_matches(rustops.einsum(equation, *operands), expected, atol=1e-5)
+
+
+def test_einsum_named(rng):
+    q = rng.standard_normal((2, 3, 4, 5)).astype(np.float32)
+    k = rng.standard_normal((2, 3, 6, 5)).astype(np.float32)
+    actual = rustops.einsum_named("b h q d, b h k d -> b h q k", q, k)
+    assert_matches(actual, torch.einsum("bhqd,bhkd->bhqk", t(q), t(k)), atol=1e-5)
+
+
+def test_matmul_family(rng):
+    a = rng.standard_normal((2, 3, 4)).astype(np.float32)
+    b = rng.standard_normal((2, 4, 5)).astype(np.float32)
+    c = rng.standard_normal((2, 3, 5)).astype(np.float32)
+    assert_matches(rustops.matmul(a, b), torch.matmul(t(a), t(b)), atol=1e-5)
+    assert_matches(rustops.bmm(a, b), torch.bmm(t(a), t(b)), atol=1e-5)
+    expected = torch.baddbmm(t(c), t(a), t(b), beta=0.5, alpha=2.0)
+    assert_matches(rustops.baddbmm(c, a, b, beta=0.5, alpha=2.0), expected, atol=1e-5)
+    expected = torch.addmm(t(c[0]), t(a[0]), t(b[0]), beta=0.5, alpha=2.0)
+    assert_matches(rustops.addmm(c[0], a[0], b[0], beta=0.5, alpha=2.0), expected, atol=1e-5)
+
+
+def test_shape_ops(rng):
+    x = rng.standard_normal((2, 3, 4)).astype(np.float32)
+    assert_matches(rustops.reshape(x, [4, -1]), torch.reshape(t(x), (4, -1)))
+    assert_matches(rustops.transpose(x, 0, -1), torch.transpose(t(x), 0, -1))
+    assert_matches(rustops.flip(x, [0, 2]), torch.flip(t(x), [0, 2]))
+    assert_matches(rustops.roll(x, [1, -2], [0, 2]), torch.roll(t(x), [1, -2], [0, 2]))
+    assert_matches(rustops.tile(x, [2, 1, 3]), torch.tile(t(x), (2, 1, 3)))
+    assert_matches(rustops.expand(x, 1, 5), t(x).unsqueeze(1).expand(2, 5, 3, 4))
+    assert_matches(rustops.slice_last_dim(x), t(x)[..., 1:])
+    assert_matches(rustops.slice_second_dim(x, 2), t(x)[:, :2])
+    assert_matches(rustops.abs(x), torch.abs(t(x)))
+    assert_matches(rustops.ones([2, 3]), torch.ones(2, 3))
+    assert_matches(rustops.sort_last_dim(x), torch.sort(t(x), dim=-1).values)
+
+
+def test_repeat_interleave(rng):
+    x = rng.standard_normal((2, 3)).astype(np.float32)
+    assert_matches(rustops.repeat_interleave(x, 2), torch.repeat_interleave(t(x), 2))
+    repeats = np.array([1, 0, 2])
+    expected = torch.repeat_interleave(t(x), t(repeats), dim=1)
+    assert_matches(rustops.repeat_interleave(x, repeats, 1), expected)
+
+
+@pytest.mark.parametrize("mode", ["constant", "reflect", "replicate", "circular"])
+def test_pad(rng, mode):
+    x = rng.standard_normal((1, 2, 4, 5)).astype(np.float32)
+    pad = [2, 1, 1, 3]
+    value = 1.5 if mode == "constant" else None
+    expected = torch.nn.functional.pad(t(x), pad, mode=mode, value=value)
+    assert_matches(rustops.pad(x, pad, mode, value or 0.0), expected)
+
+
+@pytest.mark.parametrize("diagonal", [-1, 0, 2])
+def test_tril_triu(rng, diagonal):
+    x = rng.standard_normal((3, 4, 5)).astype(np.float64)
+    assert_matches(rustops.tril(x, diagonal), torch.tril(t(x), diagonal))
+    assert_matches(rustops.triu(x, diagonal), torch.triu(t(x), diagonal))
+
+
+def test_einops_ops(rng):
+    einops = pytest.importorskip("einops")
+    x = rng.standard_normal((2, 3, 4)).astype(np.float32)
+    expected = einops.rearrange(t(x), "batch mems flag -> mems (batch flag)")
+    assert_matches(rustops.rearrange_batch_mems_flag(x), expected)
+    expected = einops.reduce(t(x), "a b c -> a", "sum")
+    assert_matches(rustops.reduce(x, "abc,abc->a"), expected, atol=1e-5)
+
+
+def test_float16(rng):
+    x = rng.standard_normal((3, 64)).astype(np.float16)
+    w = rng.standard_normal((64, 5)).astype(np.float16)
+    # Both accumulate in float32, possibly in a different order
+    assert_matches(rustops.matmul(x, w), torch.matmul(t(x), t(w)), atol=1e-2)
+    assert_matches(rustops.einsum("ij,jk->ik", x, w), torch.einsum("ij,jk->ik", t(x), t(w)), atol=1e-2)
+    assert_matches(rustops.transpose(x, 0, 1), torch.transpose(t(x), 0, 1))
+    assert_matches(rustops.expand(x, 0, 2), t(x).unsqueeze(0).expand(2, 3, 64))
+    assert_matches(rustops.sort_last_dim(x), torch.sort(t(x), dim=-1).values)
+
+
+def test_errors():
+    x = np.zeros((2, 3), dtype=np.float32)
+    with pytest.raises(IndexError, match="out of bounds"):
+        rustops.gather(x, 1, np.array([[3], [0]]))
+    with pytest.raises(RuntimeError):
+        torch.gather(t(x), 1, torch.tensor([[3], [0]]))
+    with pytest.raises(TypeError, match="int32"):
+        rustops.gather(x, 1, np.array([[0], [0]], dtype=np.int32))
+    with pytest.raises(TypeError, match="NumPy array"):
+        rustops.matmul(x, [[1.0]])
+    with pytest.raises(ValueError):
+        rustops.reshape(x, [4, -1])
+    with pytest.raises(ValueError):
+        rustops.einsum("ij,jk->ik", x, x)
@@ -0,0 +1,31 @@
+import torch
+from util.manifest import save_case
+from typing import Tuple, List, Iterable
+
+
+def create_tile(
+    shape: Tuple[int, ...] | List[int] | Iterable[int],
+    reps: Tuple[int, ...],
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "tile",
+):
+    """
+    Create a tensor of random values with the given shape and dtype, tile it by `reps`,
+    and save the original and tiled tensors as references.
+    Args:
+        shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
+        reps (Tuple[int, ...]): Number of repetitions per dimension.
+        dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Name of the conformance case. Default is "tile".
+    """
+    x = torch.randn(shape, dtype=dtype)
+    y = torch.tile(x, reps)
+    save_case(dir, name, "tile", {"reps": list(reps)}, {"x": x}, {"y": y})
+
+
+if __name__ == "__main__":
+    create_tile((4, 5, 6), reps=(2, 3, 1), dir="data", name="tile3d_2_3_1")
+    create_tile((4, 5, 6), reps=(3,), dir="data", name="tile3d_3")
+    create_tile((4, 5), reps=(2, 1, 3), dir="data", name="tile2d_2_1_3")
@@ -1,5 +1,5 @@
 import torch
-from util.save_reference import save_reference
+from util.manifest import save_case
 from typing import Tuple, List, Iterable
 
 def create_transpose(
@@ -14,16 +14,14 @@ def create_transpose(
         shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
         dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
         dir (str): Directory to save the reference tensor. Default is "data".
-        name (str): Name of the reference tensor file. Default is "transpose".
+        name (str): Prefix of the conformance case names. Default is "transpose".
     """
     x = torch.rand(shape, dtype=dtype)
-    # Save the original tensor
-    save_reference(x, dir, f"{name}_transpose_x")
     # Apply transpose for every adjacent pair of dimensions
     for i in range(len(shape) - 1):
         transposed = x.transpose(i, i + 1)
-        # Save each transposed tensor
-        save_reference(transposed, dir, f"{name}_transpose_{i}_{i+1}")
+        args = {"dim0": i, "dim1": i + 1}
+        save_case(dir, f"{name}_{i}_{i+1}", "transpose", args, {"x": x}, {"y": transposed})
 
 if __name__ == "__main__":
     create_transpose((11, 12, 13), dtype=torch.float32, dir="data", name="transpose")
\ No newline at end of file
@@ -0,0 +1,32 @@
+import torch
+from util.manifest import save_case
+from typing import Tuple, List, Iterable
+
+
+def create_tril_triu(
+    shape: Tuple[int, ...] | List[int] | Iterable[int],
+    dtype: torch.dtype = torch.float32,
+    dir: str = "data",
+    name: str = "tril",
+):
+    """
+    Create a tensor of random values with the given shape and dtype, and save its lower and upper
+    triangular parts for diagonal offsets -2 through 2.
+    Args:
+        shape (Tuple[int, ...] | List[int] | Iterable[int]): Shape of the tensor to create.
+        dtype (torch.dtype): Data type of the tensor. Default is torch.float32.
+        dir (str): Directory to save the reference tensor. Default is "data".
+        name (str): Prefix of the conformance case names. Default is "tril".
+    """
+    x = torch.randn(shape, dtype=dtype)
+    for diagonal in range(-2, 3):
+        args = {"diagonal": diagonal}
+        y = torch.tril(x, diagonal=diagonal)
+        save_case(dir, f"{name}_tril_diag{diagonal}", "tril", args, {"x": x}, {"y": y})
+        y = torch.triu(x, diagonal=diagonal)
+        save_case(dir, f"{name}_triu_diag{diagonal}", "triu", args, {"x": x}, {"y": y})
+
+
+if __name__ == "__main__":
+    create_tril_triu((7, 9), dir="data", name="triangular2d")
+    create_tril_triu((3, 4, 9, 7), dir="data", name="triangular4d")
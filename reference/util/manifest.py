@@ -0,0 +1,82 @@
+import torch
+from torch import Tensor
+from util.save_bundle import save_bundle
+import json
+import os
+
+MANIFEST = "manifest.json"
+
+
+def save_case(
+    dir: str,
+    name: str,
+    op: str,
+    args: dict,
+    inputs: dict[str, Tensor],
+    outputs: dict[str, Tensor],
+    atol: float = 0.0,
+    rtol: float = 0.0,
+):
+    """
+    Save the tensors of one conformance case as an npz bundle and record the case in the manifest
+    the Rust conformance runner reads. Cases are keyed by name, so rerunning a script replaces
+    its cases instead of duplicating them.
+    Args:
+        dir (str): Directory holding the manifest and fixtures
+        name (str): Unique name of the case, also the name of its bundle
+        op (str): Name the runner's registry dispatches on, e.g. "gather"
+        args (dict): JSON-serializable non-tensor arguments, e.g. {"dim": -1, "keepdim": True}
+        inputs (dict[str, Tensor]): Input tensors keyed by the role the runner fetches them by
+        outputs (dict[str, Tensor]): Expected outputs keyed by role. Half precision tensors are
+            supported; a case may not mix bfloat16 and float32 tensors.
+        atol (float): Absolute tolerance of the comparison. Default is 0.0 (exact).
+        rtol (float): Relative tolerance of the comparison. Default is 0.0.
+    Returns:
+        dict: The recorded case, for recording variants of it that share its bundle
+    """
+    overlap = inputs.keys() & outputs.keys()
+    assert not overlap, f"Roles used as both input and output: {overlap}"
+    tensors = {**inputs, **outputs}
+    # npy has no bfloat16, so store bfloat16 cases as float32, which holds every bfloat16 value
+    # exactly; the runner casts all float32 fixtures of such a case back to bfloat16
+    dtypes = {x.dtype for x in tensors.values()}
+    bfloat16 = torch.bfloat16 in dtypes
+    assert not (bfloat16 and torch.float32 in dtypes), "Cannot mix bfloat16 and float32 tensors"
+    if bfloat16:
+        tensors = {
+            role: x.float() if x.dtype == torch.bfloat16 else x for role, x in tensors.items()
+        }
+    save_bundle(tensors, dir, name)
+    bundle = f"{name}.npz"
+    case = {
+        "name": name,
+        "op": op,
+        "args": args,
+        "inputs": {role: f"{bundle}/{role}" for role in inputs},
+        "outputs": {role: f"{bundle}/{role}" for role in outputs},
+        "atol": atol,
+        "rtol": rtol,
+        **({"dtype": "bfloat16"} if bfloat16 else {}),
+    }
+    record_case(dir, case)
+    return case
+
+
+def record_case(dir: str, case: dict):
+    """
+    Add a case to the manifest in the given directory, replacing any case with the same name
+    Args:
+        dir (str): Directory holding the manifest
+        case (dict): The case; its fixtures are file names relative to `dir`, either "x.npy" or
+            "bundle.npz/key" for an array inside a bundle
+    """
+    os.makedirs(dir, exist_ok=True)
+    path = os.path.join(dir, MANIFEST)
+    cases = []
+    if os.path.exists(path):
+        with open(path) as f:
+            cases = json.load(f)["cases"]
+    cases = [c for c in cases if c["name"] != case["name"]] + [case]
+    cases.sort(key=lambda c: c["name"])
+    with open(path, "w") as f:
+        json.dump({"cases": cases}, f, indent=2)
@@ -0,0 +1,26 @@
+from torch import Tensor
+import numpy as np
+import os
+
+
+def save_bundle(
+    tensors: dict[str, Tensor],
+    dir: str,
+    name: str,
+    compressed: bool = True,
+):
+    """
+    Save given named tensors as a single npz archive
+    Args:
+        tensors (dict[str, Tensor]): Tensors to save, keyed by the name tests fetch them with
+        dir (str): Directory to save the archive
+        name (str): Name of the archive, without the .npz extension
+        compressed (bool): Whether to deflate the arrays. Default is True.
+    """
+    # Ensure directory and its parent directories exist
+    os.makedirs(dir, exist_ok=True)
+    # Convert tensors to numpy arrays
+    arrays = {key: x.detach().cpu().numpy() for key, x in tensors.items()}
+    # Save numpy arrays as one npz file
+    save = np.savez_compressed if compressed else np.savez
+    save(os.path.join(dir, name), **arrays)
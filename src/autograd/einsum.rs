@@ -0,0 +1,115 @@
+use super::elementwise::sum_to;
+use super::shape::broadcast_to;
+use super::{AutogradError, Var};
+use crate::functions::einsum::einsum_ndarray_dyn;
+use ndarray::{ArrayD, Axis};
+
+/// Einstein summation over variables. Mimics `torch.einsum`.
+///
+/// The gradient of each operand is itself an einsum: the other operands and the output
+/// gradient are contracted down to the labels of that operand. Labels that appear only in
+/// that operand were summed away, so the gradient is broadcast back along them.
+///
+/// # Arguments
+///
+/// * `equation`: An equation with explicit (`"ij,jk->ik"`) or implicit (`"ij,jk"`) output.
+///   Ellipses and labels repeated within one operand (diagonals) are not supported.
+/// * `operands`: The variables to contract, all recorded on the same tape.
+///
+/// # Returns
+///
+/// * `Ok(Var)`: The result of the summation.
+/// * `Err(AutogradError)`: If the equation is invalid, does not match the operands or cannot
+///   be differentiated.
+pub fn einsum<'t>(equation: &str, operands: &[&Var<'t>]) -> Result<Var<'t>, AutogradError> {
+    let values: Vec<&ArrayD<f32>> = operands.iter().map(|var| var.value()).collect();
+    let value = einsum_ndarray_dyn(equation, &values)?;
+    let (inputs, output) = split_equation(equation)?;
+
+    let inputs: Vec<Vec<char>> = inputs.iter().map(|term| term.chars().collect()).collect();
+    let output: Vec<char> = output.chars().collect();
+    let values: Vec<ArrayD<f32>> = values.into_iter().cloned().collect();
+    let backward = move |grad: &ArrayD<f32>| {
+        (0..inputs.len())
+            .map(|operand| operand_grad(&inputs, &output, &values, operand, grad))
+            .collect()
+    };
+    Var::record(value, operands, backward)
+}
+
+/// Splits a valid einsum equation into its input terms and output term, working out the
+/// implicit output the same way [`einsum_ndarray_dyn`] does.
+fn split_equation(equation: &str) -> Result<(Vec<&str>, String), AutogradError> {
+    let unsupported = |reason| AutogradError::UnsupportedEquation {
+        equation: equation.to_string(),
+        reason,
+    };
+    if equation.contains('.') {
+        return Err(unsupported("ellipses are not supported"));
+    }
+    let (lhs, output) = match equation.split_once("->") {
+        Some((lhs, output)) => (lhs, Some(output)),
+        None => (equation, None),
+    };
+    let inputs: Vec<&str> = lhs.split(',').collect();
+    for term in &inputs {
+        let mut labels: Vec<char> = term.chars().collect();
+        labels.sort_unstable();
+        if labels.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(unsupported(
+                "labels repeated within an operand are not supported",
+            ));
+        }
+    }
+    let output = match output {
+        Some(output) => output.to_string(),
+        None => {
+            // Labels used exactly once, in sorted order
+            let mut singles: Vec<char> = lhs
+                .chars()
+                .filter(|&c| c != ',' && lhs.matches(c).count() == 1)
+                .collect();
+            singles.sort_unstable();
+            singles.into_iter().collect()
+        }
+    };
+    Ok((inputs, output))
+}
+
+/// Gradient of operand `operand` given the gradient of the output.
+fn operand_grad(
+    inputs: &[Vec<char>],
+    output: &[char],
+    values: &[ArrayD<f32>],
+    operand: usize,
+    grad: &ArrayD<f32>,
+) -> ArrayD<f32> {
+    // Contract everything except the operand itself, keeping the labels of the operand that
+    // are still available there
+    let mut terms: Vec<String> = Vec::with_capacity(inputs.len());
+    let mut tensors: Vec<&ArrayD<f32>> = Vec::with_capacity(inputs.len());
+    for (other, labels) in inputs.iter().enumerate() {
+        if other != operand {
+            terms.push(labels.iter().collect());
+            tensors.push(&values[other]);
+        }
+    }
+    terms.push(output.iter().collect());
+    tensors.push(grad);
+
+    let target = &inputs[operand];
+    let available = |label: &char| terms.iter().any(|term| term.contains(*label));
+    let kept: String = target.iter().filter(|label| available(label)).collect();
+    let equation = format!("{}->{}", terms.join(","), kept);
+    let mut result = einsum_ndarray_dyn(&equation, &tensors)
+        .expect("the gradient equation is derived from a valid equation");
+
+    // Labels summed away in the forward pass receive the same gradient at every position
+    for (axis, label) in target.iter().enumerate() {
+        if !available(label) {
+            result = result.insert_axis(Axis(axis));
+        }
+    }
+    let shape = values[operand].shape();
+    broadcast_to(&sum_to(result, shape), shape)
+}
@@ -0,0 +1,99 @@
+use super::shape::broadcast_to;
+use super::{AutogradError, Var};
+use crate::functions::matmul::broadcast_shapes;
+use ndarray::{ArrayD, Axis, Zip, arr0};
+
+impl<'t> Var<'t> {
+    /// Element-wise `self + other` with broadcasting. Mimics `torch.add`.
+    pub fn add(&self, other: &Var<'t>) -> Result<Var<'t>, AutogradError> {
+        binary(self, other, |a, b| a + b, |g, _, _| (g.clone(), g.clone()))
+    }
+
+    /// Element-wise `self - other` with broadcasting. Mimics `torch.sub`.
+    pub fn sub(&self, other: &Var<'t>) -> Result<Var<'t>, AutogradError> {
+        binary(self, other, |a, b| a - b, |g, _, _| (g.clone(), -g))
+    }
+
+    /// Element-wise `self * other` with broadcasting. Mimics `torch.mul`.
+    pub fn mul(&self, other: &Var<'t>) -> Result<Var<'t>, AutogradError> {
+        binary(self, other, |a, b| a * b, |g, a, b| (g * b, g * a))
+    }
+
+    /// Element-wise `self / other` with broadcasting. Mimics `torch.div`.
+    pub fn div(&self, other: &Var<'t>) -> Result<Var<'t>, AutogradError> {
+        binary(
+            self,
+            other,
+            |a, b| a / b,
+            |g, a, b| {
+                let grad_a = g / b;
+                let grad_b = -&grad_a * a / b;
+                (grad_a, grad_b)
+            },
+        )
+    }
+
+    /// Element-wise absolute value. Mimics `torch.abs`, whose gradient is zero at zero.
+    pub fn abs(&self) -> Var<'t> {
+        let input = self.value().clone();
+        let value = input.mapv(f32::abs);
+        let sign = input.mapv(|x| if x == 0.0 { 0.0 } else { x.signum() });
+        self.record_unary(value, move |grad| grad * &sign)
+    }
+
+    /// Sums every element into a 0-dimensional variable. Mimics `torch.sum` without `dim`.
+    pub fn sum(&self) -> Var<'t> {
+        let shape = self.value().raw_dim();
+        let value = arr0(self.value().sum()).into_dyn();
+        self.record_unary(value, move |grad| {
+            ArrayD::from_elem(shape.clone(), grad.sum())
+        })
+    }
+}
+
+/// Maps the output gradient and both broadcast operands to the gradients of both operands.
+type BinaryBackward = fn(&ArrayD<f32>, &ArrayD<f32>, &ArrayD<f32>) -> (ArrayD<f32>, ArrayD<f32>);
+
+/// Records a broadcasting element-wise op.
+///
+/// # Arguments
+///
+/// * `op`: Computes one output element from one element of each operand.
+/// * `grads`: Given the output gradient and both operands broadcast to the output shape, returns
+///   the gradients of both operands at the output shape. They are summed back down to the
+///   operand shapes afterwards.
+fn binary<'t>(
+    left: &Var<'t>,
+    right: &Var<'t>,
+    op: fn(f32, f32) -> f32,
+    grads: BinaryBackward,
+) -> Result<Var<'t>, AutogradError> {
+    let shape =
+        broadcast_shapes(left.shape(), right.shape()).ok_or_else(|| AutogradError::Broadcast {
+            left: left.shape().to_vec(),
+            right: right.shape().to_vec(),
+        })?;
+    let a = broadcast_to(left.value(), &shape);
+    let b = broadcast_to(right.value(), &shape);
+    let value = Zip::from(&a).and(&b).map_collect(|&a, &b| op(a, b));
+    let (left_shape, right_shape) = (left.shape().to_vec(), right.shape().to_vec());
+    Var::record(value, &[left, right], move |grad| {
+        let (grad_a, grad_b) = grads(grad, &a, &b);
+        vec![sum_to(grad_a, &left_shape), sum_to(grad_b, &right_shape)]
+    })
+}
+
+/// Reverses broadcasting by summing `grad` over every axis that was added or stretched to
+/// reach its shape from `shape`.
+pub(super) fn sum_to(grad: ArrayD<f32>, shape: &[usize]) -> ArrayD<f32> {
+    let mut grad = grad;
+    while grad.ndim() > shape.len() {
+        grad = grad.sum_axis(Axis(0));
+    }
+    for (axis, &size) in shape.iter().enumerate() {
+        if size == 1 && grad.shape()[axis] != 1 {
+            grad = grad.sum_axis(Axis(axis)).insert_axis(Axis(axis));
+        }
+    }
+    grad
+}
@@ -0,0 +1,97 @@
+use super::{AutogradError, Var};
+use crate::functions::argmax::argmax;
+use crate::functions::dim::normalize_dim;
+use crate::functions::gather::gather;
+use crate::functions::max::max;
+use crate::functions::scatter::scatter;
+use ndarray::{ArrayD, Dimension, IxDyn};
+
+impl<'t> Var<'t> {
+    /// Gathers values along `dim` at the positions in `index`. Mimics `torch.gather`.
+    ///
+    /// The gradient is scatter-added back to the gathered positions, so positions gathered
+    /// several times accumulate the gradient of every copy.
+    pub fn gather(&self, dim: isize, index: &ArrayD<i64>) -> Result<Var<'t>, AutogradError> {
+        let value = gather(self.value(), dim, index)?;
+        let input_shape = self.value().raw_dim();
+        let dim = normalize_dim(dim, self.ndim());
+        let index = index.clone();
+        Ok(self.record_unary(value, move |grad| {
+            let mut input_grad = ArrayD::zeros(input_shape.clone());
+            scatter_add(&mut input_grad, dim, &index, grad);
+            input_grad
+        }))
+    }
+
+    /// Writes the values of `src` into a copy of `self` along `dim` at the positions in `index`.
+    /// Mimics `torch.scatter`.
+    ///
+    /// Overwritten positions of `self` receive no gradient, and `src` receives the gradient
+    /// gathered from the positions it was written to.
+    pub fn scatter(
+        &self,
+        dim: isize,
+        index: &ArrayD<i64>,
+        src: &Var<'t>,
+    ) -> Result<Var<'t>, AutogradError> {
+        let mut value = self.value().clone();
+        scatter(&mut value, dim, index, src.value())?;
+        let index = index.clone();
+        let zeros = ArrayD::zeros(index.raw_dim());
+        Var::record(value, &[self, src], move |grad| {
+            let mut target_grad = grad.clone();
+            scatter(&mut target_grad, dim, &index, &zeros)
+                .expect("index was validated by the forward pass");
+            let src_grad =
+                gather(grad, dim, &index).expect("index was validated by the forward pass");
+            vec![target_grad, src_grad]
+        })
+    }
+
+    /// Maximum values along `dim`, with the dimension removed. Mimics `torch.max(x, dim)`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok((Var, ArrayD<i64>))`: The maximum values, and the index of each maximum along `dim`.
+    ///   The gradient of each maximum is routed to the element at its index; every other element
+    ///   gets no gradient.
+    pub fn max(&self, dim: usize) -> Result<(Var<'t>, ArrayD<i64>), AutogradError> {
+        let (values, indices) = max(self.value(), dim)?;
+        let input_shape = self.value().raw_dim();
+        let routes = indices.clone();
+        let var = self.record_unary(values, move |grad| {
+            let mut input_grad = ArrayD::zeros(input_shape.clone());
+            for (coords, &index) in routes.indexed_iter() {
+                let mut target = coords.slice().to_vec();
+                target.insert(dim, index as usize);
+                input_grad[IxDyn(&target)] += grad[&coords];
+            }
+            input_grad
+        });
+        Ok((var, indices))
+    }
+
+    /// Indices of the maximum values. Mimics `torch.argmax`.
+    ///
+    /// Indices are not differentiable, so nothing is recorded on the tape.
+    pub fn argmax(&self, dim: Option<usize>, keepdim: bool) -> Result<ArrayD<i64>, AutogradError> {
+        Ok(argmax(self.value(), dim, keepdim)?)
+    }
+}
+
+/// Adds each element of `src` into `target` at the position given by `index` along `dim`, like
+/// `Tensor.scatter_add_`. `dim` is `None` for 0-dimensional arrays.
+fn scatter_add(
+    target: &mut ArrayD<f32>,
+    dim: Option<usize>,
+    index: &ArrayD<i64>,
+    src: &ArrayD<f32>,
+) {
+    for (coords, &position) in index.indexed_iter() {
+        let mut destination = coords.slice().to_vec();
+        if let Some(dim) = dim {
+            destination[dim] = position as usize;
+        }
+        target[IxDyn(&destination)] += src[&coords];
+    }
+}
@@ -0,0 +1,75 @@
+//! Reverse-mode automatic differentiation over the ops in [`crate::functions`].
+//!
+//! Operations on [`Var`]s are recorded on a [`Tape`]; calling [`Var::backward`] replays the
+//! tape in reverse and returns the gradient of every variable, mirroring `torch.autograd`.
+//!
+//! ```
+//! use RustOps::autograd::Tape;
+//! use ndarray::array;
+//!
+//! let tape = Tape::new();
+//! let x = tape.var(array![1.0f32, -2.0, 3.0].into_dyn());
+//! let y = x.mul(&x).unwrap().sum();
+//! let grads = y.backward();
+//! assert_eq!(grads.wrt(&x), array![2.0f32, -4.0, 6.0].into_dyn());
+//! ```
+
+mod einsum;
+mod elementwise;
+mod indexing;
+mod shape;
+mod tape;
+
+pub use einsum::einsum;
+pub use tape::{Gradients, Tape, Var};
+
+use crate::functions::argmax::ArgmaxError;
+use crate::functions::einsum::EinsumError;
+use crate::functions::gather::GatherError;
+use crate::functions::max::MaxError;
+use crate::functions::reshape::ReshapeError;
+use crate::functions::scatter::ScatterError;
+use thiserror::Error;
+
+/// Error type for recording operations on a [`Tape`].
+#[derive(Error, Debug)]
+pub enum AutogradError {
+    #[error("Variables recorded on different tapes cannot be combined")]
+    DifferentTapes,
+
+    #[error("Shapes {left:?} and {right:?} cannot be broadcast together")]
+    Broadcast { left: Vec<usize>, right: Vec<usize> },
+
+    #[error("Dimension {dim} is out of bounds for a tensor with {ndim} dimensions")]
+    InvalidDimension { dim: usize, ndim: usize },
+
+    #[error("Cannot differentiate einsum equation {equation:?}: {reason}")]
+    UnsupportedEquation {
+        equation: String,
+        reason: &'static str,
+    },
+
+    #[error(transparent)]
+    Gather(#[from] GatherError),
+
+    #[error(transparent)]
+    Scatter(#[from] ScatterError),
+
+    #[error(transparent)]
+    Einsum(#[from] EinsumError),
+
+    #[error(transparent)]
+    Reshape(#[from] ReshapeError),
+
+    #[error(transparent)]
+    Max(#[from] MaxError),
+
+    #[error(transparent)]
+    Argmax(#[from] ArgmaxError),
+
+    #[error("Expand failed: {0}")]
+    Expand(&'static str),
+
+    #[error("Slicing failed: {0}")]
+    Slice(&'static str),
+}
@@ -0,0 +1,84 @@
+use super::{AutogradError, Var};
+use crate::functions::expand::expand_at_dim;
+use crate::functions::reshape::reshape;
+use crate::functions::transpose::transpose_dims;
+use ndarray::{ArrayD, Axis, IxDyn};
+
+impl<'t> Var<'t> {
+    /// Reshapes to `shape`, where one entry may be `-1`. Mimics `torch.reshape`.
+    ///
+    /// The gradient is reshaped back to the input shape.
+    pub fn reshape(&self, shape: &[i64]) -> Result<Var<'t>, AutogradError> {
+        let value = reshape(self.value(), shape)?;
+        let input_shape = self.value().raw_dim();
+        Ok(self.record_unary(value, move |grad| {
+            // Elements are taken in logical order, matching the forward pass
+            ArrayD::from_shape_vec(input_shape.clone(), grad.iter().copied().collect())
+                .expect("gradient has as many elements as the input")
+        }))
+    }
+
+    /// Swaps dimensions `dim0` and `dim1`. Mimics `torch.transpose`.
+    ///
+    /// The gradient is transposed back with the same pair of dimensions.
+    pub fn transpose(&self, dim0: usize, dim1: usize) -> Result<Var<'t>, AutogradError> {
+        let ndim = self.ndim();
+        if let Some(&dim) = [dim0, dim1].iter().find(|&&dim| dim >= ndim) {
+            return Err(AutogradError::InvalidDimension { dim, ndim });
+        }
+        let value = transpose_dims(self.value(), dim0, dim1);
+        Ok(self.record_unary(value, move |grad| transpose_dims(grad, dim0, dim1)))
+    }
+
+    /// Inserts a dimension at `dim` and repeats the input `size` times along it. Mimics
+    /// `x.unsqueeze(dim).expand(...)`.
+    ///
+    /// Every copy receives the gradient, so the gradient is summed over the inserted dimension.
+    pub fn expand(&self, dim: usize, size: usize) -> Result<Var<'t>, AutogradError> {
+        let value = expand_at_dim(self.value(), dim, size).map_err(AutogradError::Expand)?;
+        Ok(self.record_unary(value, move |grad| grad.sum_axis(Axis(dim))))
+    }
+
+    /// Slices like `[:, :, -1:]`. See [`crate::functions::slicing::slice_last_dim`].
+    ///
+    /// The gradient is padded back with zeros for the elements that were dropped.
+    pub fn slice_last_dim(&self) -> Result<Var<'t>, AutogradError> {
+        let value = crate::functions::slicing::slice_last_dim(self.value())
+            .map_err(AutogradError::Slice)?;
+        let input_shape = self.value().raw_dim();
+        Ok(self.record_unary(value, move |grad| {
+            let mut padded = ArrayD::zeros(input_shape.clone());
+            let last = Axis(padded.ndim() - 1);
+            let len = padded.len_of(last);
+            padded
+                .slice_axis_mut(last, (len.saturating_sub(1)..len).into())
+                .assign(grad);
+            padded
+        }))
+    }
+
+    /// Slices like `[:, :amount]`. See [`crate::functions::slicing::slice_second_dim`].
+    ///
+    /// The gradient is padded back with zeros for the elements that were dropped.
+    pub fn slice_second_dim(&self, amount: usize) -> Result<Var<'t>, AutogradError> {
+        let value = crate::functions::slicing::slice_second_dim(self.value(), amount)
+            .map_err(AutogradError::Slice)?;
+        let input_shape = self.value().raw_dim();
+        Ok(self.record_unary(value, move |grad| {
+            let mut padded = ArrayD::zeros(input_shape.clone());
+            let kept = grad.len_of(Axis(1));
+            padded
+                .slice_axis_mut(Axis(1), (0..kept).into())
+                .assign(grad);
+            padded
+        }))
+    }
+}
+
+/// Broadcasts `array` to `shape` and copies it into a new array.
+pub(super) fn broadcast_to(array: &ArrayD<f32>, shape: &[usize]) -> ArrayD<f32> {
+    array
+        .broadcast(IxDyn(shape))
+        .expect("shapes are broadcast compatible")
+        .to_owned()
+}
@@ -0,0 +1,171 @@
+use super::AutogradError;
+use ndarray::ArrayD;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Maps the gradient of a node's output to the gradients of its parents, in parent order.
+type Backward = Box<dyn Fn(&ArrayD<f32>) -> Vec<ArrayD<f32>>>;
+
+struct Node {
+    parents: Vec<usize>,
+    backward: Option<Backward>,
+}
+
+/// Records every operation applied to its variables so that gradients can be computed by
+/// replaying the recording backwards.
+///
+/// Nodes are appended in evaluation order, which is a topological order of the graph, so
+/// [`Var::backward`] only needs a single reverse sweep.
+#[derive(Default)]
+pub struct Tape {
+    nodes: RefCell<Vec<Node>>,
+}
+
+impl Tape {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a leaf variable, i.e. an input or parameter whose gradient is wanted.
+    pub fn var(&self, value: ArrayD<f32>) -> Var<'_> {
+        self.push(value, Vec::new(), None)
+    }
+
+    /// Number of recorded nodes, leaves included.
+    pub fn len(&self) -> usize {
+        self.nodes.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.borrow().is_empty()
+    }
+
+    fn push(&self, value: ArrayD<f32>, parents: Vec<usize>, backward: Option<Backward>) -> Var<'_> {
+        let mut nodes = self.nodes.borrow_mut();
+        nodes.push(Node { parents, backward });
+        Var {
+            tape: self,
+            index: nodes.len() - 1,
+            value: Rc::new(value),
+        }
+    }
+}
+
+/// A value recorded on a [`Tape`], mirroring a `torch.Tensor` with `requires_grad=True`.
+///
+/// Cloning is cheap and yields the same variable.
+#[derive(Clone)]
+pub struct Var<'t> {
+    tape: &'t Tape,
+    index: usize,
+    value: Rc<ArrayD<f32>>,
+}
+
+impl<'t> Var<'t> {
+    pub fn value(&self) -> &ArrayD<f32> {
+        &self.value
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        self.value.shape()
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.value.ndim()
+    }
+
+    pub fn tape(&self) -> &'t Tape {
+        self.tape
+    }
+
+    /// Records the result of an operation on `parents`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The result of the operation.
+    /// * `parents`: The variables the operation read.
+    /// * `backward`: Given the gradient of `value`, returns the gradient of every parent, in the
+    ///   same order and with the same shapes as `parents`.
+    pub(super) fn record(
+        value: ArrayD<f32>,
+        parents: &[&Var<'t>],
+        backward: impl Fn(&ArrayD<f32>) -> Vec<ArrayD<f32>> + 'static,
+    ) -> Result<Var<'t>, AutogradError> {
+        let tape = parents[0].tape;
+        if parents.iter().any(|p| !std::ptr::eq(p.tape, tape)) {
+            return Err(AutogradError::DifferentTapes);
+        }
+        let parents = parents.iter().map(|p| p.index).collect();
+        Ok(tape.push(value, parents, Some(Box::new(backward))))
+    }
+
+    /// Records the result of an operation that only reads `self`, which cannot mix tapes.
+    pub(super) fn record_unary(
+        &self,
+        value: ArrayD<f32>,
+        backward: impl Fn(&ArrayD<f32>) -> ArrayD<f32> + 'static,
+    ) -> Var<'t> {
+        let backward = move |grad: &ArrayD<f32>| vec![backward(grad)];
+        self.tape
+            .push(value, vec![self.index], Some(Box::new(backward)))
+    }
+
+    /// Computes the gradient of this variable with respect to every variable it depends on.
+    ///
+    /// Like `tensor.backward(torch.ones_like(tensor))`: for a non-scalar variable this is the
+    /// gradient of the sum of its elements.
+    ///
+    /// # Returns
+    ///
+    /// * `Gradients`: Holds the gradient of every variable recorded up to this one that this
+    ///   variable depends on.
+    pub fn backward(&self) -> Gradients {
+        let nodes = self.tape.nodes.borrow();
+        let mut grads: Vec<Option<ArrayD<f32>>> = vec![None; self.index + 1];
+        grads[self.index] = Some(ArrayD::ones(self.value.raw_dim()));
+        for index in (0..=self.index).rev() {
+            let node = &nodes[index];
+            let (Some(grad), Some(backward)) = (&grads[index], &node.backward) else {
+                continue;
+            };
+            for (&parent, parent_grad) in node.parents.iter().zip(backward(grad)) {
+                // A variable used several times accumulates the gradient of every use
+                match &mut grads[parent] {
+                    Some(existing) => *existing += &parent_grad,
+                    slot => *slot = Some(parent_grad),
+                }
+            }
+        }
+        Gradients { grads }
+    }
+}
+
+impl std::fmt::Debug for Var<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Var")
+            .field("index", &self.index)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+/// The result of [`Var::backward`].
+#[derive(Debug, Clone)]
+pub struct Gradients {
+    grads: Vec<Option<ArrayD<f32>>>,
+}
+
+impl Gradients {
+    /// The gradient with respect to `var`, or `None` if the differentiated variable does not
+    /// depend on it.
+    pub fn get(&self, var: &Var) -> Option<&ArrayD<f32>> {
+        self.grads.get(var.index).and_then(Option::as_ref)
+    }
+
+    /// The gradient with respect to `var`, with zeros if it does not affect the result.
+    pub fn wrt(&self, var: &Var) -> ArrayD<f32> {
+        self.get(var)
+            .cloned()
+            .unwrap_or_else(|| ArrayD::zeros(var.value.raw_dim()))
+    }
+}
@@ -0,0 +1,281 @@
+//! C ABI over [`crate::functions`], for consumers such as Android apps going through JNI.
+//!
+//! Tensors cross the boundary as opaque [`RustOpsTensor`] handles. Every entry point returns a
+//! [`RustOpsStatus`]; on failure a description of the error can be read with
+//! [`rustops_last_error_message`]. Results are written through `out` pointers as new handles
+//! that the caller owns and must release with [`rustops_tensor_free`].
+//!
+//! The matching C declarations are in `include/rustops.h`.
+
+mod ops;
+mod tensor;
+
+pub use ops::*;
+pub use tensor::*;
+
+use crate::io::{Dtype, Tensor};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use thiserror::Error;
+
+/// Result of every `rustops_*` call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustOpsStatus {
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// An argument was out of range or malformed, e.g. an unknown dtype code or invalid UTF-8.
+    InvalidArgument = 2,
+    /// The op does not support the dtype of a tensor argument.
+    UnsupportedDtype = 3,
+    /// The op rejected its inputs, e.g. because their shapes do not line up.
+    OpFailed = 4,
+    /// A caller-provided output buffer is too small.
+    BufferTooSmall = 5,
+    /// The library panicked. This is a bug; the call had no effect on its arguments.
+    Panic = 6,
+}
+
+/// Element type codes used in place of [`Dtype`] across the ABI.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustOpsDtype {
+    F64 = 0,
+    F32 = 1,
+    F16 = 2,
+    BF16 = 3,
+    I64 = 4,
+    /// One byte per element holding 0 or 1.
+    Bool = 5,
+}
+
+impl RustOpsDtype {
+    /// Parses a dtype code received from C, where any integer may show up.
+    fn from_code(code: i32) -> Option<Self> {
+        let dtype = match code {
+            0 => RustOpsDtype::F64,
+            1 => RustOpsDtype::F32,
+            2 => RustOpsDtype::F16,
+            3 => RustOpsDtype::BF16,
+            4 => RustOpsDtype::I64,
+            5 => RustOpsDtype::Bool,
+            _ => return None,
+        };
+        Some(dtype)
+    }
+}
+
+impl From<RustOpsDtype> for Dtype {
+    fn from(dtype: RustOpsDtype) -> Self {
+        match dtype {
+            RustOpsDtype::F64 => Dtype::F64,
+            RustOpsDtype::F32 => Dtype::F32,
+            RustOpsDtype::F16 => Dtype::F16,
+            RustOpsDtype::BF16 => Dtype::BF16,
+            RustOpsDtype::I64 => Dtype::I64,
+            RustOpsDtype::Bool => Dtype::Bool,
+        }
+    }
+}
+
+impl From<Dtype> for RustOpsDtype {
+    fn from(dtype: Dtype) -> Self {
+        match dtype {
+            Dtype::F64 => RustOpsDtype::F64,
+            Dtype::F32 => RustOpsDtype::F32,
+            Dtype::F16 => RustOpsDtype::F16,
+            Dtype::BF16 => RustOpsDtype::BF16,
+            Dtype::I64 => RustOpsDtype::I64,
+            Dtype::Bool => RustOpsDtype::Bool,
+        }
+    }
+}
+
+/// Opaque handle to a tensor owned by the library.
+pub struct RustOpsTensor {
+    tensor: Tensor,
+}
+
+#[derive(Error, Debug)]
+enum FfiError {
+    #[error("Argument `{0}` is a null pointer")]
+    NullPointer(&'static str),
+
+    #[error("Invalid argument `{argument}`: {reason}")]
+    InvalidArgument {
+        argument: &'static str,
+        reason: String,
+    },
+
+    #[error("{op} does not support tensors of dtype {dtype}")]
+    UnsupportedDtype { op: &'static str, dtype: Dtype },
+
+    #[error("Argument `{argument}` must have dtype {expected}, found {found}")]
+    DtypeMismatch {
+        argument: &'static str,
+        expected: Dtype,
+        found: Dtype,
+    },
+
+    #[error("{op} failed: {message}")]
+    Op { op: &'static str, message: String },
+
+    #[error("Output buffer `{argument}` holds {capacity} elements but {required} are needed")]
+    BufferTooSmall {
+        argument: &'static str,
+        required: usize,
+        capacity: usize,
+    },
+}
+
+impl FfiError {
+    fn op(op: &'static str, err: impl Display) -> Self {
+        FfiError::Op {
+            op,
+            message: err.to_string(),
+        }
+    }
+
+    fn invalid(argument: &'static str, reason: impl Display) -> Self {
+        FfiError::InvalidArgument {
+            argument,
+            reason: reason.to_string(),
+        }
+    }
+
+    fn status(&self) -> RustOpsStatus {
+        match self {
+            FfiError::NullPointer(_) => RustOpsStatus::NullPointer,
+            FfiError::InvalidArgument { .. } => RustOpsStatus::InvalidArgument,
+            FfiError::UnsupportedDtype { .. } | FfiError::DtypeMismatch { .. } => {
+                RustOpsStatus::UnsupportedDtype
+            }
+            FfiError::Op { .. } => RustOpsStatus::OpFailed,
+            FfiError::BufferTooSmall { .. } => RustOpsStatus::BufferTooSmall,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    // Interior NUL bytes would truncate the message in C, so drop them
+    let message = CString::new(message.replace('\0', "")).expect("NUL bytes were removed");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Runs the body of an entry point, recording any error or panic for
+/// [`rustops_last_error_message`].
+fn guard(body: impl FnOnce() -> Result<(), FfiError>) -> RustOpsStatus {
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => RustOpsStatus::Ok,
+        Ok(Err(err)) => {
+            set_last_error(err.to_string());
+            err.status()
+        }
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_last_error(format!("Internal panic: {message}"));
+            RustOpsStatus::Panic
+        }
+    }
+}
+
+/// Returns the message of the last error on the calling thread, or null if there was none.
+///
+/// Successful calls leave the message untouched, like `errno`. The string is owned by the
+/// library and stays valid until the next failing call on the same thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn rustops_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Clears the last error message of the calling thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn rustops_clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// Borrows the tensor behind a handle.
+///
+/// # Safety
+///
+/// `handle` must be null or a live handle returned by this library.
+unsafe fn tensor_arg<'a>(
+    handle: *const RustOpsTensor,
+    argument: &'static str,
+) -> Result<&'a Tensor, FfiError> {
+    // SAFETY: `handle` is null, which as_ref maps to None, or a live handle whose tensor outlives
+    // the borrow
+    unsafe { handle.as_ref() }
+        .map(|handle| &handle.tensor)
+        .ok_or(FfiError::NullPointer(argument))
+}
+
+/// Borrows a C array, which may be null when `len` is zero.
+///
+/// # Safety
+///
+/// Unless `len` is zero, `ptr` must be null or point to `len` initialized values.
+unsafe fn slice_arg<'a, T>(
+    ptr: *const T,
+    len: usize,
+    argument: &'static str,
+) -> Result<&'a [T], FfiError> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if ptr.is_null() {
+        return Err(FfiError::NullPointer(argument));
+    }
+    // SAFETY: `ptr` is not null and `len` is not zero, so it points to `len` initialized values
+    Ok(unsafe { std::slice::from_raw_parts(ptr, len) })
+}
+
+/// Borrows a NUL-terminated UTF-8 string.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string.
+unsafe fn str_arg<'a>(ptr: *const c_char, argument: &'static str) -> Result<&'a str, FfiError> {
+    if ptr.is_null() {
+        return Err(FfiError::NullPointer(argument));
+    }
+    // SAFETY: `ptr` is not null, so it points to a NUL-terminated string
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|err| FfiError::invalid(argument, err))
+}
+
+/// Checks that an output pointer can be written before any work is done.
+fn out_arg<T>(ptr: *mut T, argument: &'static str) -> Result<*mut T, FfiError> {
+    if ptr.is_null() {
+        Err(FfiError::NullPointer(argument))
+    } else {
+        Ok(ptr)
+    }
+}
+
+/// Hands a tensor to the caller as a new handle.
+///
+/// # Safety
+///
+/// `out` must have been checked with [`out_arg`] and be valid for writes.
+unsafe fn store(out: *mut *mut RustOpsTensor, tensor: Tensor) {
+    let handle = Box::into_raw(Box::new(RustOpsTensor { tensor }));
+    // SAFETY: out_arg rejected a null `out`, and the caller guarantees it is valid for writes
+    unsafe { out.write(handle) };
+}
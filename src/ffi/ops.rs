@@ -0,0 +1,999 @@
+//! One `rustops_*` entry point per op in [`crate::functions`].
+//!
+//! Every entry point takes its tensor arguments as handles, writes its result to `out` as a
+//! new handle owned by the caller, and leaves `out` untouched on failure. Dimensions are
+//! `intptr_t` and may be negative to count from the end wherever the Rust op accepts that.
+//! Ops that do arithmetic on f16 and bf16 tensors (einsum, the matmul family and reduce)
+//! compute in f32 and round the result back once.
+//!
+//! # Safety
+//!
+//! For every entry point, tensor arguments must be null or live handles, array arguments must
+//! be null or point to as many values as their length argument says, strings must be null or
+//! NUL-terminated, and output pointers must be null or valid for writes.
+
+use super::{
+    FfiError, RustOpsStatus, RustOpsTensor, guard, out_arg, slice_arg, store, str_arg, tensor_arg,
+};
+use crate::functions::abs::abs_ndarray;
+use crate::functions::argmax::argmax;
+use crate::functions::dim::normalize_dim;
+use crate::functions::einsum::{einsum_named, einsum_ndarray_dyn};
+use crate::functions::expand::expand_at_dim;
+use crate::functions::flip::flip;
+use crate::functions::gather::gather;
+use crate::functions::matmul::{
+    addmm, addmm_upcast, baddbmm, baddbmm_upcast, bmm, bmm_upcast, matmul, matmul_upcast,
+};
+use crate::functions::max::max;
+use crate::functions::ones::ones;
+use crate::functions::pad::{PadMode, pad};
+use crate::functions::rearrange::rearrange_batch_mems_flag;
+use crate::functions::reduce::{reduce, reduce_upcast};
+use crate::functions::repeat_interleave::{repeat_interleave, repeat_interleave_tensor};
+use crate::functions::reshape::reshape;
+use crate::functions::roll::roll;
+use crate::functions::scatter::scatter;
+use crate::functions::slicing::{slice_last_dim, slice_second_dim};
+use crate::functions::sort::sort_last_dim;
+use crate::functions::tile::tile;
+use crate::functions::transpose::transpose_dims;
+use crate::functions::tril::{tril, triu};
+use crate::functions::upcast::{HalfFloat, downcast, upcast};
+use crate::io::{Element, Tensor};
+use half::{bf16, f16};
+use ndarray::ArrayD;
+use num_traits::NumCast;
+use std::ffi::c_char;
+
+/// Padding modes for [`rustops_pad`], mirroring [`PadMode`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RustOpsPadMode {
+    Constant = 0,
+    Reflect = 1,
+    Replicate = 2,
+    Circular = 3,
+}
+
+/// Applies `$body` to the array inside `$tensor`, which must hold one of the listed dtypes,
+/// and wraps the resulting array back into a tensor.
+macro_rules! map_dtypes {
+    ($op:expr, $tensor:expr, [$($variant:ident),+], |$array:ident| $body:expr) => {
+        match $tensor {
+            $(Tensor::$variant($array) => Tensor::from($body),)+
+            #[allow(unreachable_patterns)]
+            other => {
+                return Err(FfiError::UnsupportedDtype {
+                    op: $op,
+                    dtype: other.dtype(),
+                })
+            }
+        }
+    };
+    ($op:expr, $tensor:expr, any, |$array:ident| $body:expr) => {
+        map_dtypes!($op, $tensor, [F64, F32, F16, BF16, I64, Bool], |$array| $body)
+    };
+    // f16 and bf16 run `$half_body`, which computes in f32 through the `*_upcast` variants
+    (
+        $op:expr,
+        $tensor:expr,
+        [$($variant:ident),+],
+        |$array:ident| $body:expr,
+        upcast |$half:ident| $half_body:expr
+    ) => {
+        match $tensor {
+            Tensor::F16($half) => Tensor::from($half_body),
+            Tensor::BF16($half) => Tensor::from($half_body),
+            other => map_dtypes!($op, other, [$($variant),+], |$array| $body),
+        }
+    };
+}
+
+/// Runs an entry point that produces a single tensor.
+fn produce(
+    out: *mut *mut RustOpsTensor,
+    body: impl FnOnce() -> Result<Tensor, FfiError>,
+) -> RustOpsStatus {
+    guard(|| {
+        let out = out_arg(out, "out")?;
+        let tensor = body()?;
+        // SAFETY: out_arg rejected a null `out`, and a non-null one is valid for writes
+        unsafe { store(out, tensor) };
+        Ok(())
+    })
+}
+
+/// Returns `tensor` as an array of the same element type as `like`.
+fn same_dtype<'a, A: Element>(
+    _like: &ArrayD<A>,
+    tensor: &'a Tensor,
+    argument: &'static str,
+) -> Result<&'a ArrayD<A>, FfiError> {
+    tensor
+        .as_array::<A>()
+        .ok_or_else(|| FfiError::DtypeMismatch {
+            argument,
+            expected: A::DTYPE,
+            found: tensor.dtype(),
+        })
+}
+
+/// Returns `tensor` as an index array.
+fn indices<'a>(tensor: &'a Tensor, argument: &'static str) -> Result<&'a ArrayD<i64>, FfiError> {
+    tensor
+        .as_array::<i64>()
+        .ok_or_else(|| FfiError::DtypeMismatch {
+            argument,
+            expected: i64::DTYPE,
+            found: tensor.dtype(),
+        })
+}
+
+/// Converts a scalar received as a double to the element type of an op.
+fn scalar<A: NumCast>(value: f64, argument: &'static str) -> Result<A, FfiError> {
+    A::from(value).ok_or_else(|| FfiError::invalid(argument, format!("{value} is out of range")))
+}
+
+/// Resolves a possibly negative dimension against `ndim`.
+fn dim_arg(dim: isize, ndim: usize, argument: &'static str) -> Result<usize, FfiError> {
+    normalize_dim(dim, ndim).ok_or_else(|| {
+        FfiError::invalid(
+            argument,
+            format!("dimension {dim} is out of range for {ndim} dimensions"),
+        )
+    })
+}
+
+/// Borrows `count` tensor handles that must all share the dtype of the first.
+///
+/// # Safety
+///
+/// `operands` must be null or point to `count` handles, each null or live.
+unsafe fn operand_list<'a>(
+    operands: *const *const RustOpsTensor,
+    count: usize,
+) -> Result<Vec<&'a Tensor>, FfiError> {
+    // SAFETY: `operands` is null or points to `count` handles, as this function requires
+    let handles = unsafe { slice_arg(operands, count, "operands") }?;
+    if handles.is_empty() {
+        return Err(FfiError::invalid(
+            "count",
+            "at least one operand is required",
+        ));
+    }
+    handles
+        .iter()
+        // SAFETY: each handle is null or live, as this function requires
+        .map(|&handle| unsafe { tensor_arg(handle, "operands") })
+        .collect()
+}
+
+/// Runs an einsum-style op over operands that must share one dtype.
+fn contract(
+    op: &'static str,
+    operands: &[&Tensor],
+    f32_op: impl Fn(&[&ArrayD<f32>]) -> Result<ArrayD<f32>, String>,
+    f64_op: impl Fn(&[&ArrayD<f64>]) -> Result<ArrayD<f64>, String>,
+    i64_op: impl Fn(&[&ArrayD<i64>]) -> Result<ArrayD<i64>, String>,
+) -> Result<Tensor, FfiError> {
+    fn collect<'a, A: Element>(operands: &[&'a Tensor]) -> Result<Vec<&'a ArrayD<A>>, FfiError> {
+        operands
+            .iter()
+            .map(|tensor| {
+                tensor
+                    .as_array::<A>()
+                    .ok_or_else(|| FfiError::DtypeMismatch {
+                        argument: "operands",
+                        expected: A::DTYPE,
+                        found: tensor.dtype(),
+                    })
+            })
+            .collect()
+    }
+    let result = match operands[0] {
+        Tensor::F32(_) => f32_op(&collect(operands)?).map(Tensor::from),
+        Tensor::F64(_) => f64_op(&collect(operands)?).map(Tensor::from),
+        Tensor::I64(_) => i64_op(&collect(operands)?).map(Tensor::from),
+        Tensor::F16(_) => upcast_op::<f16>(&collect(operands)?, f32_op).map(Tensor::from),
+        Tensor::BF16(_) => upcast_op::<bf16>(&collect(operands)?, f32_op).map(Tensor::from),
+        other => {
+            return Err(FfiError::UnsupportedDtype {
+                op,
+                dtype: other.dtype(),
+            });
+        }
+    };
+    result.map_err(|message| FfiError::Op { op, message })
+}
+
+/// Runs an `f32` contraction on half-precision operands and rounds the result back once.
+fn upcast_op<A: HalfFloat>(
+    operands: &[&ArrayD<A>],
+    f32_op: impl Fn(&[&ArrayD<f32>]) -> Result<ArrayD<f32>, String>,
+) -> Result<ArrayD<A>, String> {
+    let widened: Vec<ArrayD<f32>> = operands.iter().map(|a| upcast(*a)).collect();
+    let widened: Vec<&ArrayD<f32>> = widened.iter().collect();
+    Ok(downcast(&f32_op(&widened)?))
+}
+
+/// Element-wise absolute value. See [`abs_ndarray`]. Supports f32 and f64.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_abs(
+    x: *const RustOpsTensor,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        Ok(map_dtypes!("abs", x, [F64, F32], |a| abs_ndarray(a)))
+    })
+}
+
+/// Indices of the maximum values as an i64 tensor. See [`argmax`].
+///
+/// When `has_dim` is false the input is flattened and `dim` and `keepdim` are ignored.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_argmax(
+    x: *const RustOpsTensor,
+    has_dim: bool,
+    dim: isize,
+    keepdim: bool,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        let dim = match has_dim {
+            true => Some(dim_arg(dim, x.shape().len(), "dim")?),
+            false => None,
+        };
+        Ok(map_dtypes!("argmax", x, any, |a| argmax(a, dim, keepdim)
+            .map_err(|err| {
+            FfiError::op("argmax", format!("{err:?}"))
+        })?))
+    })
+}
+
+/// Einstein summation. See [`einsum_ndarray_dyn`]. Supports f32, f64, i64, f16 and bf16; all
+/// operands must share one dtype.
+///
+/// # Safety
+///
+/// See the [module documentation](self). `operands` points to `count` handles.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_einsum(
+    equation: *const c_char,
+    operands: *const *const RustOpsTensor,
+    count: usize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `equation` is null or NUL-terminated
+        let equation = unsafe { str_arg(equation, "equation") }?;
+        // SAFETY: `operands` is null or points to `count` handles, each null or live
+        let operands = unsafe { operand_list(operands, count) }?;
+        contract(
+            "einsum",
+            &operands,
+            |t| einsum_ndarray_dyn(equation, t).map_err(|e| e.to_string()),
+            |t| einsum_ndarray_dyn(equation, t).map_err(|e| e.to_string()),
+            |t| einsum_ndarray_dyn(equation, t).map_err(|e| e.to_string()),
+        )
+    })
+}
+
+/// Einstein summation with named axes. See [`einsum_named`]. Supports f32, f64, i64, f16 and
+/// bf16; all operands must share one dtype.
+///
+/// # Safety
+///
+/// See the [module documentation](self). `operands` points to `count` handles.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_einsum_named(
+    pattern: *const c_char,
+    operands: *const *const RustOpsTensor,
+    count: usize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `pattern` is null or NUL-terminated
+        let pattern = unsafe { str_arg(pattern, "pattern") }?;
+        // SAFETY: `operands` is null or points to `count` handles, each null or live
+        let operands = unsafe { operand_list(operands, count) }?;
+        contract(
+            "einsum_named",
+            &operands,
+            |t| einsum_named(pattern, t).map_err(|e| e.to_string()),
+            |t| einsum_named(pattern, t).map_err(|e| e.to_string()),
+            |t| einsum_named(pattern, t).map_err(|e| e.to_string()),
+        )
+    })
+}
+
+/// Inserts a dimension at `dim` and repeats the input `size` times along it. See
+/// [`expand_at_dim`].
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_expand(
+    x: *const RustOpsTensor,
+    dim: usize,
+    size: usize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        Ok(map_dtypes!("expand", x, any, |a| expand_at_dim(
+            a, dim, size
+        )
+        .map_err(|err| FfiError::op("expand", err))?))
+    })
+}
+
+/// Reverses the order of elements along `dims`. See [`flip`].
+///
+/// # Safety
+///
+/// See the [module documentation](self). `dims` points to `ndims` values.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_flip(
+    x: *const RustOpsTensor,
+    dims: *const isize,
+    ndims: usize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        // SAFETY: `dims` is null or points to `ndims` values
+        let dims = unsafe { slice_arg(dims, ndims, "dims") }?;
+        Ok(map_dtypes!("flip", x, any, |a| flip(a, dims)
+            .map_err(|err| FfiError::op("flip", err))?))
+    })
+}
+
+/// Gathers values along `dim` at the positions in the i64 tensor `index`. See [`gather`].
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_gather(
+    x: *const RustOpsTensor,
+    dim: isize,
+    index: *const RustOpsTensor,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        // SAFETY: `index` is null or a live handle
+        let index = indices(unsafe { tensor_arg(index, "index") }?, "index")?;
+        Ok(map_dtypes!("gather", x, any, |a| gather(a, dim, index)
+            .map_err(|err| FfiError::op("gather", err))?))
+    })
+}
+
+/// Matrix product with broadcasting. See [`matmul`]. Supports f32, f64, i64, f16 and bf16.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_matmul(
+    a: *const RustOpsTensor,
+    b: *const RustOpsTensor,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `a` and `b` are null or live handles
+        let (a, b) = unsafe { (tensor_arg(a, "a")?, tensor_arg(b, "b")?) };
+        Ok(map_dtypes!(
+            "matmul",
+            a,
+            [F64, F32, I64],
+            |a| matmul(a, same_dtype(a, b, "b")?).map_err(|err| FfiError::op("matmul", err))?,
+            upcast
+                | a
+                | matmul_upcast(a, same_dtype(a, b, "b")?)
+                    .map_err(|err| FfiError::op("matmul", err))?
+        ))
+    })
+}
+
+/// Batched matrix product of 3-dimensional tensors. See [`bmm`]. Supports f32, f64, i64, f16
+/// and bf16.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_bmm(
+    a: *const RustOpsTensor,
+    b: *const RustOpsTensor,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `a` and `b` are null or live handles
+        let (a, b) = unsafe { (tensor_arg(a, "a")?, tensor_arg(b, "b")?) };
+        Ok(map_dtypes!(
+            "bmm",
+            a,
+            [F64, F32, I64],
+            |a| bmm(a, same_dtype(a, b, "b")?).map_err(|err| FfiError::op("bmm", err))?,
+            upcast
+                | a
+                | bmm_upcast(a, same_dtype(a, b, "b")?).map_err(|err| FfiError::op("bmm", err))?
+        ))
+    })
+}
+
+/// Computes `beta * input + alpha * (batch1 @ batch2)`. See [`baddbmm`]. Supports f32, f64, f16
+/// and bf16.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_baddbmm(
+    input: *const RustOpsTensor,
+    batch1: *const RustOpsTensor,
+    batch2: *const RustOpsTensor,
+    beta: f64,
+    alpha: f64,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `input`, `batch1` and `batch2` are null or live handles
+        let (input, batch1, batch2) = unsafe {
+            (
+                tensor_arg(input, "input")?,
+                tensor_arg(batch1, "batch1")?,
+                tensor_arg(batch2, "batch2")?,
+            )
+        };
+        Ok(map_dtypes!(
+            "baddbmm",
+            input,
+            [F64, F32],
+            |a| baddbmm(
+                a,
+                same_dtype(a, batch1, "batch1")?,
+                same_dtype(a, batch2, "batch2")?,
+                scalar(beta, "beta")?,
+                scalar(alpha, "alpha")?,
+            )
+            .map_err(|err| FfiError::op("baddbmm", err))?,
+            upcast
+                | a
+                | baddbmm_upcast(
+                    a,
+                    same_dtype(a, batch1, "batch1")?,
+                    same_dtype(a, batch2, "batch2")?,
+                    scalar(beta, "beta")?,
+                    scalar(alpha, "alpha")?,
+                )
+                .map_err(|err| FfiError::op("baddbmm", err))?
+        ))
+    })
+}
+
+/// Computes `beta * input + alpha * (mat1 @ mat2)`. See [`addmm`]. Supports f32, f64, f16 and
+/// bf16.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_addmm(
+    input: *const RustOpsTensor,
+    mat1: *const RustOpsTensor,
+    mat2: *const RustOpsTensor,
+    beta: f64,
+    alpha: f64,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `input`, `mat1` and `mat2` are null or live handles
+        let (input, mat1, mat2) = unsafe {
+            (
+                tensor_arg(input, "input")?,
+                tensor_arg(mat1, "mat1")?,
+                tensor_arg(mat2, "mat2")?,
+            )
+        };
+        Ok(map_dtypes!(
+            "addmm",
+            input,
+            [F64, F32],
+            |a| addmm(
+                a,
+                same_dtype(a, mat1, "mat1")?,
+                same_dtype(a, mat2, "mat2")?,
+                scalar(beta, "beta")?,
+                scalar(alpha, "alpha")?,
+            )
+            .map_err(|err| FfiError::op("addmm", err))?,
+            upcast
+                | a
+                | addmm_upcast(
+                    a,
+                    same_dtype(a, mat1, "mat1")?,
+                    same_dtype(a, mat2, "mat2")?,
+                    scalar(beta, "beta")?,
+                    scalar(alpha, "alpha")?,
+                )
+                .map_err(|err| FfiError::op("addmm", err))?
+        ))
+    })
+}
+
+/// Maximum values along `dim` and their i64 indices, with the dimension removed. See [`max`].
+///
+/// Both outputs are written only on success.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_max(
+    x: *const RustOpsTensor,
+    dim: isize,
+    values_out: *mut *mut RustOpsTensor,
+    indices_out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    guard(|| {
+        let values_out = out_arg(values_out, "values_out")?;
+        let indices_out = out_arg(indices_out, "indices_out")?;
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        let dim = dim_arg(dim, x.shape().len(), "dim")?;
+        let (values, max_indices) = match x {
+            Tensor::F64(a) => max_parts(a, dim)?,
+            Tensor::F32(a) => max_parts(a, dim)?,
+            Tensor::F16(a) => max_parts(a, dim)?,
+            Tensor::BF16(a) => max_parts(a, dim)?,
+            Tensor::I64(a) => max_parts(a, dim)?,
+            Tensor::Bool(a) => max_parts(a, dim)?,
+        };
+        // SAFETY: out_arg rejected null outputs, and non-null ones are valid for writes
+        unsafe {
+            store(values_out, values);
+            store(indices_out, Tensor::from(max_indices));
+        }
+        Ok(())
+    })
+}
+
+fn max_parts<A: Element + PartialOrd>(
+    input: &ArrayD<A>,
+    dim: usize,
+) -> Result<(Tensor, ArrayD<i64>), FfiError> {
+    let (values, indices) =
+        max(input, dim).map_err(|err| FfiError::op("max", format!("{err:?}")))?;
+    Ok((A::wrap(values), indices))
+}
+
+/// An f32 tensor of ones. See [`ones`].
+///
+/// # Safety
+///
+/// See the [module documentation](self). `shape` points to `ndim` values.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_ones(
+    shape: *const usize,
+    ndim: usize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `shape` is null or points to `ndim` values
+        let shape = unsafe { slice_arg(shape, ndim, "shape") }?;
+        Ok(Tensor::from(ones(shape)))
+    })
+}
+
+/// Pads the trailing dimensions. See [`pad`]. Supports f32, f64 and i64.
+///
+/// `pads` holds `len` values like the `pad` tuple of `torch.nn.functional.pad`: a
+/// `(before, after)` pair per dimension, starting from the last. `mode` is a
+/// [`RustOpsPadMode`] code and `value` is only used by the constant mode.
+///
+/// # Safety
+///
+/// See the [module documentation](self). `pads` points to `len` values.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_pad(
+    x: *const RustOpsTensor,
+    pads: *const isize,
+    len: usize,
+    mode: i32,
+    value: f64,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        // SAFETY: `pads` is null or points to `len` values
+        let pads = unsafe { slice_arg(pads, len, "pads") }?;
+        if pads.len() % 2 != 0 {
+            return Err(FfiError::invalid(
+                "len",
+                "pads must hold (before, after) pairs",
+            ));
+        }
+        let pairs: Vec<(isize, isize)> = pads.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+        if !(0..=3).contains(&mode) {
+            return Err(FfiError::invalid(
+                "mode",
+                format!("unknown pad mode {mode}"),
+            ));
+        }
+        Ok(map_dtypes!("pad", x, [F64, F32, I64], |a| {
+            let mode = match mode {
+                0 => PadMode::Constant(scalar(value, "value")?),
+                1 => PadMode::Reflect,
+                2 => PadMode::Replicate,
+                _ => PadMode::Circular,
+            };
+            pad(a, &pairs, mode).map_err(|err| FfiError::op("pad", err))?
+        }))
+    })
+}
+
+/// Rearranges `batch mems flag -> mems (batch flag)`. See [`rearrange_batch_mems_flag`].
+/// Supports f32.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_rearrange_batch_mems_flag(
+    x: *const RustOpsTensor,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        Ok(map_dtypes!("rearrange_batch_mems_flag", x, [F32], |a| {
+            rearrange_batch_mems_flag(a)
+                .map_err(|err| FfiError::op("rearrange_batch_mems_flag", format!("{err:?}")))?
+        }))
+    })
+}
+
+/// Sums according to an einsum-like equation. See [`reduce`]. Supports f32, f16 and bf16.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_reduce(
+    x: *const RustOpsTensor,
+    equation: *const c_char,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        // SAFETY: `equation` is null or NUL-terminated
+        let equation = unsafe { str_arg(equation, "equation") }?;
+        Ok(map_dtypes!(
+            "reduce",
+            x,
+            [F32],
+            |a| reduce(a, equation).map_err(|err| FfiError::op("reduce", err))?,
+            upcast | a | reduce_upcast(a, equation).map_err(|err| FfiError::op("reduce", err))?
+        ))
+    })
+}
+
+/// Repeats each element `repeats` times. See [`repeat_interleave`].
+///
+/// When `has_dim` is false the input is flattened and `dim` is ignored.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_repeat_interleave(
+    x: *const RustOpsTensor,
+    repeats: usize,
+    has_dim: bool,
+    dim: isize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        let dim = has_dim.then_some(dim);
+        Ok(map_dtypes!("repeat_interleave", x, any, |a| {
+            repeat_interleave(a, repeats, dim)
+                .map_err(|err| FfiError::op("repeat_interleave", err))?
+        }))
+    })
+}
+
+/// Repeats each element by the counts in the i64 tensor `repeats`. See
+/// [`repeat_interleave_tensor`].
+///
+/// When `has_dim` is false the input is flattened and `dim` is ignored.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_repeat_interleave_tensor(
+    x: *const RustOpsTensor,
+    repeats: *const RustOpsTensor,
+    has_dim: bool,
+    dim: isize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        // SAFETY: `repeats` is null or a live handle
+        let repeats = indices(unsafe { tensor_arg(repeats, "repeats") }?, "repeats")?;
+        let dim = has_dim.then_some(dim);
+        Ok(map_dtypes!("repeat_interleave_tensor", x, any, |a| {
+            repeat_interleave_tensor(a, repeats, dim)
+                .map_err(|err| FfiError::op("repeat_interleave_tensor", err))?
+        }))
+    })
+}
+
+/// Reshapes to `shape`, where one entry may be -1. See [`reshape`].
+///
+/// # Safety
+///
+/// See the [module documentation](self). `shape` points to `ndim` values.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_reshape(
+    x: *const RustOpsTensor,
+    shape: *const i64,
+    ndim: usize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        // SAFETY: `shape` is null or points to `ndim` values
+        let shape = unsafe { slice_arg(shape, ndim, "shape") }?;
+        Ok(map_dtypes!("reshape", x, any, |a| reshape(a, shape)
+            .map_err(|err| FfiError::op(
+                "reshape",
+                format!("{err:?}")
+            ))?))
+    })
+}
+
+/// Rolls elements along `dims` by `shifts`, or the flattened input if `ndims` is zero. See
+/// [`roll`].
+///
+/// # Safety
+///
+/// See the [module documentation](self). `shifts` and `dims` point to `nshifts` and `ndims`
+/// values.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_roll(
+    x: *const RustOpsTensor,
+    shifts: *const isize,
+    nshifts: usize,
+    dims: *const isize,
+    ndims: usize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        // SAFETY: `shifts` and `dims` are null or point to `nshifts` and `ndims` values
+        let (shifts, dims) = unsafe {
+            (
+                slice_arg(shifts, nshifts, "shifts")?,
+                slice_arg(dims, ndims, "dims")?,
+            )
+        };
+        Ok(map_dtypes!("roll", x, any, |a| roll(a, shifts, dims)
+            .map_err(|err| FfiError::op("roll", err))?))
+    })
+}
+
+/// Writes `src` into a copy of `target` along `dim` at the positions in the i64 tensor
+/// `index`. See [`scatter`]. `target` itself is not modified.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_scatter(
+    target: *const RustOpsTensor,
+    dim: isize,
+    index: *const RustOpsTensor,
+    src: *const RustOpsTensor,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `target`, `index` and `src` are null or live handles
+        let (target, index, src) = unsafe {
+            (
+                tensor_arg(target, "target")?,
+                tensor_arg(index, "index")?,
+                tensor_arg(src, "src")?,
+            )
+        };
+        let index = indices(index, "index")?;
+        Ok(map_dtypes!("scatter", target, any, |a| {
+            let mut result = a.clone();
+            scatter(&mut result, dim, index, same_dtype(a, src, "src")?)
+                .map_err(|err| FfiError::op("scatter", err))?;
+            result
+        }))
+    })
+}
+
+/// Slices like `[:, :, -1:]`. See [`slice_last_dim`].
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_slice_last_dim(
+    x: *const RustOpsTensor,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        Ok(map_dtypes!("slice_last_dim", x, any, |a| slice_last_dim(a)
+            .map_err(|err| FfiError::op("slice_last_dim", err))?))
+    })
+}
+
+/// Slices like `[:, :amount]`. See [`slice_second_dim`].
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_slice_second_dim(
+    x: *const RustOpsTensor,
+    amount: usize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        Ok(map_dtypes!("slice_second_dim", x, any, |a| {
+            slice_second_dim(a, amount).map_err(|err| FfiError::op("slice_second_dim", err))?
+        }))
+    })
+}
+
+/// Returns a copy sorted along the last dimension. See [`sort_last_dim`].
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_sort_last_dim(
+    x: *const RustOpsTensor,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        if x.shape().is_empty() {
+            return Err(FfiError::op(
+                "sort_last_dim",
+                "cannot sort a 0-dimensional tensor",
+            ));
+        }
+        Ok(map_dtypes!("sort_last_dim", x, any, |a| {
+            let mut sorted = a.clone();
+            sort_last_dim(&mut sorted);
+            sorted
+        }))
+    })
+}
+
+/// Repeats the whole input `reps` times per dimension. See [`tile`].
+///
+/// # Safety
+///
+/// See the [module documentation](self). `reps` points to `nreps` values.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_tile(
+    x: *const RustOpsTensor,
+    reps: *const usize,
+    nreps: usize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        // SAFETY: `reps` is null or points to `nreps` values
+        let reps = unsafe { slice_arg(reps, nreps, "reps") }?;
+        Ok(map_dtypes!("tile", x, any, |a| tile(a, reps)
+            .map_err(|err| FfiError::op("tile", err))?))
+    })
+}
+
+/// Swaps two dimensions. See [`transpose_dims`].
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_transpose(
+    x: *const RustOpsTensor,
+    dim0: isize,
+    dim1: isize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        // transpose_dims panics on invalid dimensions, so check them here
+        let ndim = x.shape().len();
+        let (dim0, dim1) = (dim_arg(dim0, ndim, "dim0")?, dim_arg(dim1, ndim, "dim1")?);
+        Ok(map_dtypes!("transpose", x, any, |a| transpose_dims(
+            a, dim0, dim1
+        )))
+    })
+}
+
+/// Zeroes the elements above the `diagonal`-th diagonal. See [`tril`]. Supports f32, f64 and
+/// i64.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_tril(
+    x: *const RustOpsTensor,
+    diagonal: isize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        Ok(map_dtypes!("tril", x, [F64, F32, I64], |a| tril(
+            a, diagonal
+        )
+        .map_err(|err| FfiError::op("tril", err))?))
+    })
+}
+
+/// Zeroes the elements below the `diagonal`-th diagonal. See [`triu`]. Supports f32, f64 and
+/// i64.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_triu(
+    x: *const RustOpsTensor,
+    diagonal: isize,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    produce(out, || {
+        // SAFETY: `x` is null or a live handle
+        let x = unsafe { tensor_arg(x, "x") }?;
+        Ok(map_dtypes!("triu", x, [F64, F32, I64], |a| triu(
+            a, diagonal
+        )
+        .map_err(|err| FfiError::op("triu", err))?))
+    })
+}
@@ -0,0 +1,254 @@
+use super::{
+    FfiError, RustOpsDtype, RustOpsStatus, RustOpsTensor, guard, out_arg, slice_arg, store,
+    tensor_arg,
+};
+use crate::io::{Dtype, Tensor};
+use half::{bf16, f16};
+use ndarray::{ArrayD, IxDyn};
+use std::ffi::c_void;
+
+/// Creates a tensor by copying a C-contiguous buffer.
+///
+/// # Arguments
+///
+/// * `data`: `numel * element size` bytes in native byte order, where `numel` is the product
+///   of `shape`. May be null if `numel` is zero. Need not be aligned.
+/// * `shape`, `ndim`: The dimensions of the tensor. `shape` may be null if `ndim` is zero,
+///   which creates a scalar.
+/// * `dtype`: A [`RustOpsDtype`] code. Bool elements must be 0 or 1.
+/// * `out`: Receives the new handle.
+///
+/// # Safety
+///
+/// Pointers must be null or valid for the sizes described above.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_tensor_create(
+    data: *const c_void,
+    shape: *const usize,
+    ndim: usize,
+    dtype: i32,
+    out: *mut *mut RustOpsTensor,
+) -> RustOpsStatus {
+    guard(|| {
+        let out = out_arg(out, "out")?;
+        let dtype = RustOpsDtype::from_code(dtype)
+            .ok_or_else(|| FfiError::invalid("dtype", format!("unknown dtype code {dtype}")))?;
+        // SAFETY: `shape` is null or points to `ndim` values
+        let shape = unsafe { slice_arg(shape, ndim, "shape") }?;
+        let numel = shape
+            .iter()
+            .try_fold(1usize, |acc, &dim| acc.checked_mul(dim))
+            .ok_or_else(|| FfiError::invalid("shape", "element count overflows"))?;
+        let bytes_len = numel
+            .checked_mul(Dtype::from(dtype).size())
+            .ok_or_else(|| FfiError::invalid("shape", "byte count overflows"))?;
+        // SAFETY: `data` is null or holds `numel * element size` bytes, which is `bytes_len`
+        let bytes = unsafe { slice_arg(data as *const u8, bytes_len, "data") }?;
+        let shape = IxDyn(shape);
+        let tensor = match dtype {
+            RustOpsDtype::F64 => Tensor::F64(read_elements::<f64>(bytes, shape)),
+            RustOpsDtype::F32 => Tensor::F32(read_elements::<f32>(bytes, shape)),
+            RustOpsDtype::F16 => Tensor::F16(read_elements::<f16>(bytes, shape)),
+            RustOpsDtype::BF16 => Tensor::BF16(read_elements::<bf16>(bytes, shape)),
+            RustOpsDtype::I64 => Tensor::I64(read_elements::<i64>(bytes, shape)),
+            RustOpsDtype::Bool => {
+                let values = bytes
+                    .iter()
+                    .map(|&byte| match byte {
+                        0 => Ok(false),
+                        1 => Ok(true),
+                        _ => Err(FfiError::invalid("data", format!("{byte} is not a bool"))),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Tensor::Bool(ArrayD::from_shape_vec(shape, values).expect("length matches shape"))
+            }
+        };
+        // SAFETY: out_arg rejected a null `out`, and a non-null one is valid for writes
+        unsafe { store(out, tensor) };
+        Ok(())
+    })
+}
+
+/// Releases a tensor. Does nothing if `tensor` is null.
+///
+/// # Safety
+///
+/// `tensor` must be null or a live handle, and must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_tensor_free(tensor: *mut RustOpsTensor) {
+    if !tensor.is_null() {
+        // SAFETY: `tensor` is a live handle, so it came from Box::into_raw in `store` and has
+        // not been freed yet
+        drop(unsafe { Box::from_raw(tensor) });
+    }
+}
+
+/// Writes the number of dimensions of `tensor` to `out`.
+///
+/// # Safety
+///
+/// `tensor` must be a live handle and `out` valid for writes, or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_tensor_ndim(
+    tensor: *const RustOpsTensor,
+    out: *mut usize,
+) -> RustOpsStatus {
+    guard(|| {
+        let out = out_arg(out, "out")?;
+        // SAFETY: `tensor` is null or a live handle
+        let ndim = unsafe { tensor_arg(tensor, "tensor") }?.shape().len();
+        // SAFETY: out_arg rejected a null `out`, and a non-null one is valid for a write
+        unsafe { out.write(ndim) };
+        Ok(())
+    })
+}
+
+/// Writes the dimensions of `tensor` to `out`, which holds `capacity` values.
+///
+/// Fails with `BufferTooSmall` if `capacity` is less than the number of dimensions.
+///
+/// # Safety
+///
+/// `tensor` must be a live handle and `out` valid for `capacity` writes, or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_tensor_shape(
+    tensor: *const RustOpsTensor,
+    out: *mut usize,
+    capacity: usize,
+) -> RustOpsStatus {
+    guard(|| {
+        // SAFETY: `tensor` is null or a live handle
+        let shape = unsafe { tensor_arg(tensor, "tensor") }?.shape();
+        if shape.len() > capacity {
+            return Err(FfiError::BufferTooSmall {
+                argument: "out",
+                required: shape.len(),
+                capacity,
+            });
+        }
+        if !shape.is_empty() {
+            let out = out_arg(out, "out")?;
+            // SAFETY: the caller guarantees room for `capacity >= shape.len()` values
+            unsafe { out.copy_from_nonoverlapping(shape.as_ptr(), shape.len()) };
+        }
+        Ok(())
+    })
+}
+
+/// Writes the [`RustOpsDtype`] code of `tensor` to `out`.
+///
+/// # Safety
+///
+/// `tensor` must be a live handle and `out` valid for writes, or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_tensor_dtype(
+    tensor: *const RustOpsTensor,
+    out: *mut i32,
+) -> RustOpsStatus {
+    guard(|| {
+        let out = out_arg(out, "out")?;
+        // SAFETY: `tensor` is null or a live handle
+        let dtype = unsafe { tensor_arg(tensor, "tensor") }?.dtype();
+        // SAFETY: out_arg rejected a null `out`, and a non-null one is valid for a write
+        unsafe { out.write(RustOpsDtype::from(dtype) as i32) };
+        Ok(())
+    })
+}
+
+/// Writes the number of elements of `tensor` to `out`.
+///
+/// # Safety
+///
+/// `tensor` must be a live handle and `out` valid for writes, or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_tensor_numel(
+    tensor: *const RustOpsTensor,
+    out: *mut usize,
+) -> RustOpsStatus {
+    guard(|| {
+        let out = out_arg(out, "out")?;
+        // SAFETY: `tensor` is null or a live handle
+        let numel = unsafe { tensor_arg(tensor, "tensor") }?
+            .shape()
+            .iter()
+            .product();
+        // SAFETY: out_arg rejected a null `out`, and a non-null one is valid for a write
+        unsafe { out.write(numel) };
+        Ok(())
+    })
+}
+
+/// Copies the elements of `tensor` in C-contiguous order into `out`, which holds `capacity`
+/// bytes. The layout is the one [`rustops_tensor_create`] accepts.
+///
+/// Fails with `BufferTooSmall` if the elements need more than `capacity` bytes.
+///
+/// # Safety
+///
+/// `tensor` must be a live handle and `out` valid for `capacity` bytes of writes, or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rustops_tensor_copy_data(
+    tensor: *const RustOpsTensor,
+    out: *mut c_void,
+    capacity: usize,
+) -> RustOpsStatus {
+    guard(|| {
+        // SAFETY: `tensor` is null or a live handle
+        let tensor = unsafe { tensor_arg(tensor, "tensor") }?;
+        let numel: usize = tensor.shape().iter().product();
+        let required = numel * tensor.dtype().size();
+        if required > capacity {
+            return Err(FfiError::BufferTooSmall {
+                argument: "out",
+                required,
+                capacity,
+            });
+        }
+        if required == 0 {
+            return Ok(());
+        }
+        let out = out_arg(out, "out")?;
+        // SAFETY: the caller guarantees room for `capacity >= required` bytes
+        unsafe {
+            match tensor {
+                Tensor::F64(array) => write_elements(array, out),
+                Tensor::F32(array) => write_elements(array, out),
+                Tensor::F16(array) => write_elements(array, out),
+                Tensor::BF16(array) => write_elements(array, out),
+                Tensor::I64(array) => write_elements(array, out),
+                Tensor::Bool(array) => write_elements(&array.mapv(u8::from), out),
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Plain numeric element types, for which every bit pattern is a valid value.
+trait Pod: Copy {}
+
+impl Pod for f64 {}
+impl Pod for f32 {}
+impl Pod for f16 {}
+impl Pod for bf16 {}
+impl Pod for i64 {}
+impl Pod for u8 {}
+
+fn read_elements<A: Pod>(bytes: &[u8], shape: IxDyn) -> ArrayD<A> {
+    let values = bytes
+        .chunks_exact(std::mem::size_of::<A>())
+        // SAFETY: each chunk holds size_of::<A>() bytes and any bit pattern is a valid A
+        .map(|chunk| unsafe { (chunk.as_ptr() as *const A).read_unaligned() })
+        .collect();
+    ArrayD::from_shape_vec(shape, values).expect("length matches shape")
+}
+
+/// # Safety
+///
+/// `out` must be valid for `array.len() * size_of::<A>()` bytes of writes.
+unsafe fn write_elements<A: Pod>(array: &ArrayD<A>, out: *mut c_void) {
+    let out = out as *mut A;
+    for (position, &value) in array.iter().enumerate() {
+        // SAFETY: `position < array.len()`, and `out` has room for `array.len()` elements
+        unsafe { out.add(position).write_unaligned(value) };
+    }
+}
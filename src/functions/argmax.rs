@@ -1,16 +1,28 @@
-use ndarray::{Array, ArrayBase, ArrayView, Axis, Data, Dimension, Ix1, IxDyn, RemoveAxis, arr0};
-use std::cmp::Ordering;
+use super::dim::{is_reduced_shape, reduced_shape};
+use ndarray::{
+    Array, ArrayBase, ArrayView, ArrayViewMutD, Axis, Data, Dimension, Ix1, IxDyn, RemoveAxis, arr0,
+};
 use std::fmt::Debug; // For Debug bound in error
+use thiserror::Error;
 
 /// Error types for the argmax function.
-#[derive(Debug, PartialEq)]
+#[derive(Error, Debug, Clone, PartialEq)]
 pub enum ArgmaxError {
     /// The input array is empty (when finding the overall max).
+    #[error("Cannot find the maximum of an empty array")]
     EmptyInput,
     /// The specified dimension has size 0.
+    #[error("Dimension {0} has size 0")]
     ZeroDimSize(usize), // Contains the axis index
     /// The specified axis index is out of bounds.
+    #[error("Axis {0} is out of bounds")]
     InvalidAxis(usize), // Contains the axis index
+    /// The output of [`argmax_into`] does not have the reduced shape.
+    #[error("Output has shape {found:?}, expected {expected:?}")]
+    OutputShapeMismatch {
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
 }
 
 /// Finds the indices (as i64) of the maximum values of an array along a given dimension.
@@ -36,7 +48,7 @@ pub enum ArgmaxError {
 /// # Type Parameters
 ///
 /// * `A`: The element type of the array. Must implement `PartialOrd` for comparison and `Copy`
-///        for efficient processing within closures.
+///   for efficient processing within closures.
 /// * `S`: The data storage type (e.g., `OwnedRepr<A>`, `ViewRepr<&'a A>`).
 /// * `D`: The dimension type of the input array.
 ///
@@ -47,15 +59,10 @@ pub enum ArgmaxError {
 /// from `usize` to `i64` could theoretically panic on 32-bit systems if the index exceeds
 /// `i64::MAX`, although this is highly unlikely for typical array dimensions.
 ///
-/// # NaN Handling Note
+/// # Ties and NaN
 ///
-/// This implementation uses `partial_cmp`. If the input contains NaN values, the behavior might
-/// differ slightly from PyTorch's `argmax`, which has specific NaN propagation/handling rules
-/// (often documented as returning the index of the first `NaN` if encountered, but observed
-/// behavior can sometimes prioritize non-NaN max values). This implementation will typically
-/// *not* select a NaN as the maximum unless forced by the `unwrap_or` fallback, and its
-/// position relative to other maximal values might affect the outcome if NaNs are present.
-/// For precise NaN handling matching PyTorch, a more complex comparison logic would be needed.
+/// Like PyTorch, the index of the first maximal value is returned, and NaN counts as larger than
+/// every number, so the index of the first NaN is returned if there is one.
 pub fn argmax<A, S, D>(
     input: &ArrayBase<S, D>,
     dim: Option<usize>,
@@ -68,19 +75,14 @@ where
     D: Dimension + RemoveAxis,
 {
     // Handle the case where the input array itself is logically empty
-    if input.len() == 0 && dim.is_none() {
+    if input.is_empty() && dim.is_none() {
         return Err(ArgmaxError::EmptyInput);
     }
 
     match dim {
         // --- Case 1: Flattened argmax (dim is None) ---
         None => {
-            let (max_idx, _) = input
-                .iter()
-                .copied()
-                .enumerate()
-                .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Less))
-                .ok_or(ArgmaxError::EmptyInput)?;
+            let (max_idx, _) = first_max(input.iter().copied()).ok_or(ArgmaxError::EmptyInput)?;
 
             // Return a 0-dimensional array containing the flat index, cast to i64
             // Note: Potential panic if max_idx > i64::MAX on 32-bit systems (highly unlikely)
@@ -105,12 +107,7 @@ where
             // The closure now returns i64.
             let result_no_keepdim: Array<i64, _> =
                 input.map_axis(axis, |view: ArrayView<A, Ix1>| {
-                    let (idx, _val) = view
-                        .iter()
-                        .copied()
-                        .enumerate()
-                        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Less))
-                        .unwrap(); // Safe due to dim_size > 0 check
+                    let (idx, _val) = first_max(view.iter().copied()).unwrap(); // Safe due to dim_size > 0 check
                     // Note: Potential panic if idx > i64::MAX on 32-bit systems (highly unlikely)
                     idx as i64 // <-- Cast usize to i64 here
                 });
@@ -123,3 +120,66 @@ where
         }
     }
 }
+
+/// Like [`argmax`] along a dimension, but writes the indices into `out` instead of allocating.
+///
+/// # Arguments
+///
+/// * `out`: Where to write the indices. Must have the shape of `input` with `dim` removed, or
+///   kept with size 1 as `keepdim=True` would give.
+/// * `input`: The input array.
+/// * `dim`: The dimension along which to find the maximum indices.
+///
+/// # Returns
+///
+/// * `Ok(())`: Once `out` is written.
+/// * `Err(ArgmaxError)`: If `dim` is invalid, has size 0, or `out` has the wrong shape.
+pub fn argmax_into<A, S, D>(
+    out: &mut ArrayViewMutD<'_, i64>,
+    input: &ArrayBase<S, D>,
+    dim: usize,
+) -> Result<(), ArgmaxError>
+where
+    A: PartialOrd + Copy,
+    S: Data<Elem = A>,
+    D: Dimension + RemoveAxis,
+{
+    if dim >= input.ndim() {
+        return Err(ArgmaxError::InvalidAxis(dim));
+    }
+    if input.shape()[dim] == 0 {
+        return Err(ArgmaxError::ZeroDimSize(dim));
+    }
+    if !is_reduced_shape(input.shape(), dim, out.shape()) {
+        return Err(ArgmaxError::OutputShapeMismatch {
+            expected: reduced_shape(input.shape(), dim),
+            found: out.shape().to_vec(),
+        });
+    }
+
+    // Lanes along `dim` come in the logical order of the remaining axes, like `out`
+    for (index, lane) in out.iter_mut().zip(input.lanes(Axis(dim))) {
+        *index = first_max(lane.iter().copied()).unwrap().0 as i64; // Safe due to dim_size > 0 check
+    }
+    Ok(())
+}
+
+/// Returns the position and value of the first maximum, treating NaN as the largest value.
+/// Returns `None` if `values` is empty.
+pub(crate) fn first_max<A: PartialOrd + Copy>(
+    values: impl IntoIterator<Item = A>,
+) -> Option<(usize, A)> {
+    // NaN is the only value that is not equal to itself
+    let is_nan = |v: &A| v.partial_cmp(v).is_none();
+    let mut values = values.into_iter().enumerate();
+    let mut best = values.next()?;
+    for (idx, value) in values {
+        if is_nan(&best.1) {
+            break;
+        }
+        if is_nan(&value) || value > best.1 {
+            best = (idx, value);
+        }
+    }
+    Some(best)
+}
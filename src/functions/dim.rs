@@ -0,0 +1,32 @@
+/// Resolves a possibly negative dimension index against an array with `ndim` dimensions.
+///
+/// Mirrors PyTorch's dim wrapping: `-1` refers to the last dimension, `-ndim` to the first.
+///
+/// # Returns
+///
+/// `Some(usize)` with the resolved dimension, or `None` if `dim` is out of range.
+pub(crate) fn normalize_dim(dim: isize, ndim: usize) -> Option<usize> {
+    let resolved = if dim < 0 { ndim as isize + dim } else { dim };
+    if resolved < 0 || resolved as usize >= ndim {
+        None
+    } else {
+        Some(resolved as usize)
+    }
+}
+
+/// Whether `out` is the shape of a reduction of `shape` over `dim`, with `dim` either removed or
+/// kept with size 1, as `keepdim` chooses in PyTorch.
+pub(crate) fn is_reduced_shape(shape: &[usize], dim: usize, out: &[usize]) -> bool {
+    let (before, after) = (&shape[..dim], &shape[dim + 1..]);
+    let removed = out.len() + 1 == shape.len() && out[..dim] == *before && out[dim..] == *after;
+    let kept = out.len() == shape.len()
+        && out[..dim] == *before
+        && out[dim] == 1
+        && out[dim + 1..] == *after;
+    removed || kept
+}
+
+/// `shape` without `dim`, for reporting the expected shape of a reduction.
+pub(crate) fn reduced_shape(shape: &[usize], dim: usize) -> Vec<usize> {
+    [&shape[..dim], &shape[dim + 1..]].concat()
+}
@@ -0,0 +1,278 @@
+use super::parse::{Label, ParsedEquation};
+use ndarray::linalg::general_mat_mul;
+use ndarray::{Array, Array3, ArrayD, ArrayViewD, Axis, CowArray, IxDyn, LinalgScalar};
+use std::collections::HashSet;
+
+/// An operand during evaluation: its data and the label of each of its axes.
+struct Operand<'a, A> {
+    data: CowArray<'a, A, IxDyn>,
+    labels: Vec<Label>,
+}
+
+/// Evaluates `parsed` on `tensors` following the pairwise contraction order in `pairs`.
+///
+/// `pairs` must already have been validated against `parsed`.
+pub(crate) fn execute<A>(
+    parsed: &ParsedEquation,
+    tensors: &[ArrayViewD<'_, A>],
+    pairs: &[(usize, usize)],
+) -> ArrayD<A>
+where
+    A: LinalgScalar,
+{
+    // Take diagonals, broadcast size-1 axes and sum out labels private to one operand
+    let mut operands: Vec<Operand<'_, A>> = tensors
+        .iter()
+        .zip(&parsed.inputs)
+        .enumerate()
+        .map(|(i, (tensor, labels))| {
+            let operand = Operand {
+                data: CowArray::from(tensor.view()),
+                labels: labels.clone(),
+            };
+            let operand = broadcast_to_sizes(take_diagonals(operand), parsed);
+            let keep: HashSet<Label> = parsed
+                .output
+                .iter()
+                .chain(
+                    parsed
+                        .inputs
+                        .iter()
+                        .enumerate()
+                        .filter(|&(k, _)| k != i)
+                        .flat_map(|(_, labels)| labels.iter()),
+                )
+                .copied()
+                .collect();
+            sum_out(operand, &keep)
+        })
+        .collect();
+
+    for &(i, j) in pairs {
+        let (first, second) = if i < j { (i, j) } else { (j, i) };
+        let b = operands.remove(second);
+        let a = operands.remove(first);
+        let (a, b) = if i < j { (a, b) } else { (b, a) };
+
+        let keep: HashSet<Label> = parsed
+            .output
+            .iter()
+            .chain(operands.iter().flat_map(|o| o.labels.iter()))
+            .copied()
+            .collect();
+        operands.push(contract_pair(a, b, &keep));
+    }
+
+    let last = operands
+        .pop()
+        .expect("a validated path leaves exactly one operand");
+    let keep: HashSet<Label> = parsed.output.iter().copied().collect();
+    let last = sum_out(last, &keep);
+
+    let permutation: Vec<usize> = parsed
+        .output
+        .iter()
+        .map(|label| {
+            last.labels
+                .iter()
+                .position(|l| l == label)
+                .expect("output labels survive every contraction")
+        })
+        .collect();
+    let permuted = last.data.view().permuted_axes(IxDyn(&permutation));
+    permuted.as_standard_layout().into_owned()
+}
+
+/// Replaces repeated labels within one operand by the corresponding diagonal.
+fn take_diagonals<A>(operand: Operand<'_, A>) -> Operand<'_, A>
+where
+    A: LinalgScalar,
+{
+    let mut unique: Vec<Label> = Vec::new();
+    for &label in &operand.labels {
+        if !unique.contains(&label) {
+            unique.push(label);
+        }
+    }
+    if unique.len() == operand.labels.len() {
+        return operand;
+    }
+
+    let shape: Vec<usize> = unique
+        .iter()
+        .map(|label| {
+            let axis = operand.labels.iter().position(|l| l == label).unwrap();
+            operand.data.shape()[axis]
+        })
+        .collect();
+    // Which unique position feeds each original axis
+    let sources: Vec<usize> = operand
+        .labels
+        .iter()
+        .map(|label| unique.iter().position(|l| l == label).unwrap())
+        .collect();
+
+    let mut source_index = vec![0; operand.labels.len()];
+    let data = Array::from_shape_fn(IxDyn(&shape), |idx| {
+        for (slot, &source) in source_index.iter_mut().zip(&sources) {
+            *slot = idx[source];
+        }
+        operand.data[&source_index[..]]
+    });
+    Operand {
+        data: CowArray::from(data),
+        labels: unique,
+    }
+}
+
+/// Broadcasts size-1 axes to the size their label has in other operands.
+fn broadcast_to_sizes<'a, A>(operand: Operand<'a, A>, parsed: &ParsedEquation) -> Operand<'a, A>
+where
+    A: LinalgScalar,
+{
+    let target: Vec<usize> = operand.labels.iter().map(|l| parsed.sizes[l]).collect();
+    if target.as_slice() == operand.data.shape() {
+        return operand;
+    }
+    let data = operand
+        .data
+        .broadcast(IxDyn(&target))
+        .expect("sizes were checked to be broadcastable while parsing")
+        .to_owned();
+    Operand {
+        data: CowArray::from(data),
+        labels: operand.labels,
+    }
+}
+
+/// Sums over every axis whose label is not in `keep`.
+fn sum_out<'a, A>(operand: Operand<'a, A>, keep: &HashSet<Label>) -> Operand<'a, A>
+where
+    A: LinalgScalar,
+{
+    if operand.labels.iter().all(|l| keep.contains(l)) {
+        return operand;
+    }
+    let mut data = operand.data.into_owned();
+    let mut labels = operand.labels;
+    for axis in (0..labels.len()).rev() {
+        if !keep.contains(&labels[axis]) {
+            data = data.sum_axis(Axis(axis));
+            labels.remove(axis);
+        }
+    }
+    Operand {
+        data: CowArray::from(data),
+        labels,
+    }
+}
+
+/// Contracts two operands with a batched matrix multiplication.
+///
+/// Labels shared by both operands and still needed later become batch axes, shared labels that
+/// are no longer needed are contracted, and the remaining labels become the matrix rows
+/// (from `a`) and columns (from `b`).
+fn contract_pair<'a, A>(
+    a: Operand<'a, A>,
+    b: Operand<'a, A>,
+    keep: &HashSet<Label>,
+) -> Operand<'a, A>
+where
+    A: LinalgScalar,
+{
+    // Labels private to one side and not needed later can be summed away right now
+    let a_keep: HashSet<Label> = keep.iter().chain(b.labels.iter()).copied().collect();
+    let b_keep: HashSet<Label> = keep.iter().chain(a.labels.iter()).copied().collect();
+    let a = sum_out(a, &a_keep);
+    let b = sum_out(b, &b_keep);
+
+    let batch: Vec<Label> = a
+        .labels
+        .iter()
+        .filter(|l| b.labels.contains(l) && keep.contains(l))
+        .copied()
+        .collect();
+    let contracted: Vec<Label> = a
+        .labels
+        .iter()
+        .filter(|l| b.labels.contains(l) && !keep.contains(l))
+        .copied()
+        .collect();
+    let left: Vec<Label> = a
+        .labels
+        .iter()
+        .filter(|l| !b.labels.contains(l))
+        .copied()
+        .collect();
+    let right: Vec<Label> = b
+        .labels
+        .iter()
+        .filter(|l| !a.labels.contains(l))
+        .copied()
+        .collect();
+
+    let size_of = |operand: &Operand<'_, A>, labels: &[Label]| -> Vec<usize> {
+        labels
+            .iter()
+            .map(|label| {
+                let axis = operand.labels.iter().position(|l| l == label).unwrap();
+                operand.data.shape()[axis]
+            })
+            .collect()
+    };
+    let batch_shape = size_of(&a, &batch);
+    let left_shape = size_of(&a, &left);
+    let right_shape = size_of(&b, &right);
+    let contracted_shape = size_of(&a, &contracted);
+
+    let batch_len: usize = batch_shape.iter().product();
+    let m: usize = left_shape.iter().product();
+    let n: usize = right_shape.iter().product();
+    let k: usize = contracted_shape.iter().product();
+
+    let a_order: Vec<Label> = [&batch[..], &left[..], &contracted[..]].concat();
+    let b_order: Vec<Label> = [&batch[..], &contracted[..], &right[..]].concat();
+    let a3 = to_matrices(&a, &a_order, (batch_len, m, k));
+    let b3 = to_matrices(&b, &b_order, (batch_len, k, n));
+
+    let mut out = Array3::<A>::zeros((batch_len, m, n));
+    for ((a_mat, b_mat), mut out_mat) in a3
+        .outer_iter()
+        .zip(b3.outer_iter())
+        .zip(out.outer_iter_mut())
+    {
+        general_mat_mul(A::one(), &a_mat, &b_mat, A::zero(), &mut out_mat);
+    }
+
+    let out_shape: Vec<usize> = [&batch_shape[..], &left_shape[..], &right_shape[..]].concat();
+    let data = out
+        .into_shape(IxDyn(&out_shape))
+        .expect("batched product has the combined size of its labels");
+    Operand {
+        data: CowArray::from(data),
+        labels: [&batch[..], &left[..], &right[..]].concat(),
+    }
+}
+
+/// Permutes `operand` into `order` and views it as a stack of matrices.
+fn to_matrices<A>(
+    operand: &Operand<'_, A>,
+    order: &[Label],
+    shape: (usize, usize, usize),
+) -> Array3<A>
+where
+    A: LinalgScalar,
+{
+    let permutation: Vec<usize> = order
+        .iter()
+        .map(|label| operand.labels.iter().position(|l| l == label).unwrap())
+        .collect();
+    operand
+        .data
+        .view()
+        .permuted_axes(IxDyn(&permutation))
+        .as_standard_layout()
+        .into_owned()
+        .into_shape(shape)
+        .expect("permuted operand has the combined size of its labels")
+}
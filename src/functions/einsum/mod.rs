@@ -0,0 +1,256 @@
+//! Einstein summation over `ndarray` arrays.
+//!
+//! Equations are parsed into labels, a pairwise contraction order is chosen the way
+//! `opt_einsum` does, and each pairwise contraction is lowered to a batched matrix product
+//! through `ndarray`'s `general_mat_mul`, which uses optimized GEMM kernels for `f32`/`f64`.
+//! [`EinsumPlan`] instead evaluates an equation prepared once into an existing array, without
+//! allocating.
+
+mod contract;
+mod named;
+mod parse;
+mod path;
+mod plan;
+
+pub use named::einsum_named;
+pub use path::{ContractionPath, ContractionStep, PathStrategy};
+pub use plan::EinsumPlan;
+
+use crate::functions::upcast::{HalfFloat, downcast, upcast};
+use ndarray::{ArrayD, ArrayViewD, ArrayViewMutD, LinalgScalar};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum EinsumError {
+    #[error("Invalid character {character:?} in einsum equation")]
+    InvalidCharacter { character: char },
+
+    #[error("Einsum equation may contain at most one '->'")]
+    MultipleArrows,
+
+    #[error("Invalid ellipsis in einsum term {term:?}, expected at most one '...'")]
+    InvalidEllipsis { term: String },
+
+    #[error("Output term {term:?} must contain '...' to keep the {dims} dimensions it covers")]
+    MissingOutputEllipsis { term: String, dims: usize },
+
+    #[error("Equation has {terms} input terms but {operands} operands were given")]
+    OperandCountMismatch { terms: usize, operands: usize },
+
+    #[error("Operand {operand} has {ndim} dimensions but {subscripts} subscripts were given")]
+    SubscriptCountMismatch {
+        operand: usize,
+        subscripts: usize,
+        ndim: usize,
+    },
+
+    #[error("Output label {label:?} does not appear in any input")]
+    UnknownOutputLabel { label: char },
+
+    #[error("Output label {label:?} appears more than once")]
+    RepeatedOutputLabel { label: char },
+
+    #[error("Label {label} has size {expected} in one place and {found} in another")]
+    SizeMismatch {
+        label: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Invalid contraction path: {reason}")]
+    InvalidPath { reason: String },
+
+    #[error("Named einsum pattern requires '->' followed by the output axes")]
+    MissingArrow,
+
+    #[error("Invalid axis name {axis:?}, expected letters, digits and underscores")]
+    InvalidAxisName { axis: String },
+
+    #[error("Axis {axis:?} has size {expected} in one operand and {found} in another")]
+    AxisSizeMismatch {
+        axis: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Output axis {axis:?} does not appear in any input")]
+    UnknownOutputAxis { axis: String },
+
+    #[error("Output axis {axis:?} appears more than once")]
+    RepeatedOutputAxis { axis: String },
+
+    #[error("Pattern uses more than {limit} distinct axis names")]
+    TooManyAxisNames { limit: usize },
+
+    #[error("Operand {operand} has shape {found:?} but the plan expects {expected:?}")]
+    OperandShapeMismatch {
+        operand: usize,
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+
+    #[error("Output has shape {found:?} but the result has shape {expected:?}")]
+    OutputShapeMismatch {
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+}
+
+/// Performs Einstein summation for ndarray arrays.
+///
+/// Supports explicit (`"ij,jk->ik"`) and implicit (`"ij,jk"`) output, repeated labels
+/// (`"ii->i"`), `...` broadcasting and any number of operands. The contraction order is chosen
+/// with [`PathStrategy::Auto`]; use [`einsum_path`] and [`einsum_with_path`] to inspect or
+/// control it.
+///
+/// # Arguments
+///
+/// * `equation` - A string describing the Einstein summation convention to use
+/// * `tensors` - A slice of references to ArrayD<A> arrays to operate on
+///
+/// # Returns
+///
+/// The result of the Einstein summation as an ndarray ArrayD<A>, or an `EinsumError` if the
+/// equation is malformed or does not match the operand shapes.
+///
+/// # Type Parameters
+///
+/// * `A` - Any `LinalgScalar`, which includes the float and integer primitive types.
+pub fn einsum_ndarray_dyn<A>(
+    equation: &str,
+    tensors: &[&ArrayD<A>],
+) -> Result<ArrayD<A>, EinsumError>
+where
+    A: LinalgScalar,
+{
+    let views: Vec<ArrayViewD<'_, A>> = tensors.iter().map(|t| t.view()).collect();
+    einsum_views(equation, &views, PathStrategy::Auto)
+}
+
+/// Performs Einstein summation into an existing array.
+///
+/// Evaluates through an [`EinsumPlan`] made for this call, so no intermediate arrays are
+/// created, but the equation is parsed each time. Keep the plan to evaluate an equation
+/// repeatedly without allocating.
+///
+/// # Arguments
+///
+/// * `out` - Where to write the result. Must have the shape of the result; any layout works.
+/// * `equation` - The einsum equation.
+/// * `tensors` - A slice of references to the operands.
+///
+/// # Returns
+///
+/// `Ok(())` once `out` is written, or an `EinsumError` if the equation is malformed or does not
+/// match the operand shapes or `out`.
+pub fn einsum_into<A>(
+    out: &mut ArrayViewMutD<'_, A>,
+    equation: &str,
+    tensors: &[&ArrayD<A>],
+) -> Result<(), EinsumError>
+where
+    A: LinalgScalar,
+{
+    let shapes: Vec<&[usize]> = tensors.iter().map(|t| t.shape()).collect();
+    let views: Vec<ArrayViewD<'_, A>> = tensors.iter().map(|t| t.view()).collect();
+    EinsumPlan::new(equation, &shapes)?.execute_into(out, &views)
+}
+
+/// Performs Einstein summation on array views using the given path strategy.
+///
+/// # Arguments
+///
+/// * `equation` - The einsum equation.
+/// * `tensors` - Views of the operands.
+/// * `strategy` - How to pick the pairwise contraction order.
+///
+/// # Returns
+///
+/// The result of the Einstein summation, or an `EinsumError`.
+pub fn einsum_views<A>(
+    equation: &str,
+    tensors: &[ArrayViewD<'_, A>],
+    strategy: PathStrategy,
+) -> Result<ArrayD<A>, EinsumError>
+where
+    A: LinalgScalar,
+{
+    let shapes: Vec<&[usize]> = tensors.iter().map(|t| t.shape()).collect();
+    let parsed = parse::parse_equation(equation, &shapes)?;
+    let path = path::compute_path(equation, &parsed, strategy);
+    Ok(contract::execute(&parsed, tensors, &path.pairs()))
+}
+
+/// Computes the contraction path einsum would use for operands of the given shapes.
+///
+/// # Arguments
+///
+/// * `equation` - The einsum equation.
+/// * `shapes` - The shape of each operand.
+/// * `strategy` - How to pick the pairwise contraction order.
+///
+/// # Returns
+///
+/// The chosen [`ContractionPath`], including per-step equations and FLOP estimates, or an
+/// `EinsumError` if the equation does not match the shapes.
+pub fn einsum_path(
+    equation: &str,
+    shapes: &[&[usize]],
+    strategy: PathStrategy,
+) -> Result<ContractionPath, EinsumError> {
+    let parsed = parse::parse_equation(equation, shapes)?;
+    Ok(path::compute_path(equation, &parsed, strategy))
+}
+
+/// Performs Einstein summation following an explicit contraction path.
+///
+/// # Arguments
+///
+/// * `equation` - The einsum equation.
+/// * `tensors` - A slice of references to the operands.
+/// * `path` - Pairs of operand positions in `opt_einsum` format: each pair is removed from the
+///   operand list and their contraction appended to the end. Must reduce the operands to one.
+///
+/// # Returns
+///
+/// The result of the Einstein summation, or an `EinsumError` if the equation or path is invalid.
+pub fn einsum_with_path<A>(
+    equation: &str,
+    tensors: &[&ArrayD<A>],
+    path: &[(usize, usize)],
+) -> Result<ArrayD<A>, EinsumError>
+where
+    A: LinalgScalar,
+{
+    let shapes: Vec<&[usize]> = tensors.iter().map(|t| t.shape()).collect();
+    let parsed = parse::parse_equation(equation, &shapes)?;
+    path::build_path(equation, &parsed, path)?;
+    let views: Vec<ArrayViewD<'_, A>> = tensors.iter().map(|t| t.view()).collect();
+    Ok(contract::execute(&parsed, &views, path))
+}
+
+/// Performs Einstein summation on half-precision operands, accumulating in `f32`.
+///
+/// Each operand is widened to `f32`, contracted like [`einsum_ndarray_dyn`] and the result is
+/// rounded back to `A` once, so no intermediate product loses precision.
+///
+/// # Arguments
+///
+/// * `equation` - The einsum equation.
+/// * `tensors` - A slice of references to the `f16` or `bf16` operands.
+///
+/// # Returns
+///
+/// The result of the Einstein summation in `A`, or an `EinsumError`.
+pub fn einsum_upcast<A>(equation: &str, tensors: &[&ArrayD<A>]) -> Result<ArrayD<A>, EinsumError>
+where
+    A: HalfFloat,
+{
+    let widened: Vec<ArrayD<f32>> = tensors.iter().map(|t| upcast(*t)).collect();
+    let views: Vec<ArrayViewD<'_, f32>> = widened.iter().map(|t| t.view()).collect();
+    Ok(downcast(&einsum_views(
+        equation,
+        &views,
+        PathStrategy::Auto,
+    )?))
+}
@@ -0,0 +1,147 @@
+use super::{EinsumError, einsum_ndarray_dyn};
+use ndarray::{ArrayD, LinalgScalar};
+use std::collections::HashMap;
+
+/// Letters used as internal subscripts for named axes, in assignment order.
+const SUBSCRIPTS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Performs Einstein summation with einops-style named axes.
+///
+/// Axis names are separated by whitespace and operands by commas, for example
+/// `"batch fields dim, batch dim -> batch fields"`. Names may contain letters, digits and
+/// underscores, and each term may contain one `...`. The `->` and output are required, as in
+/// `einops.einsum`. Unlike single-letter einsum, axes sharing a name must have exactly the same
+/// size; size-1 broadcasting is only available through `...`.
+///
+/// # Arguments
+///
+/// * `pattern` - The named-axis einsum pattern.
+/// * `tensors` - A slice of references to the operands.
+///
+/// # Returns
+///
+/// The result of the Einstein summation, or an `EinsumError` naming the offending axis if the
+/// pattern does not match the operands.
+pub fn einsum_named<A>(pattern: &str, tensors: &[&ArrayD<A>]) -> Result<ArrayD<A>, EinsumError>
+where
+    A: LinalgScalar,
+{
+    let (lhs, rhs) = pattern.split_once("->").ok_or(EinsumError::MissingArrow)?;
+    if rhs.contains("->") {
+        return Err(EinsumError::MultipleArrows);
+    }
+
+    let raw_terms: Vec<&str> = lhs.split(',').map(str::trim).collect();
+    let terms: Vec<Vec<&str>> = raw_terms
+        .iter()
+        .map(|term| term.split_whitespace().collect())
+        .collect();
+    if terms.len() != tensors.len() {
+        return Err(EinsumError::OperandCountMismatch {
+            terms: terms.len(),
+            operands: tensors.len(),
+        });
+    }
+    let output: Vec<&str> = rhs.split_whitespace().collect();
+    // A second `...` would make the axis positions below ambiguous
+    for (raw, names) in raw_terms.iter().zip(&terms).chain([(&rhs.trim(), &output)]) {
+        if names.iter().filter(|&&name| name == "...").count() > 1 {
+            return Err(EinsumError::InvalidEllipsis {
+                term: raw.to_string(),
+            });
+        }
+    }
+
+    let mut letters: HashMap<&str, char> = HashMap::new();
+    let mut sizes: HashMap<&str, usize> = HashMap::new();
+    let mut subscripts = SUBSCRIPTS.chars();
+
+    let mut translated_terms = Vec::with_capacity(terms.len());
+    for (operand, (names, tensor)) in terms.iter().zip(tensors).enumerate() {
+        let named_axes = names.iter().filter(|&&name| name != "...").count();
+        let has_ellipsis = names.len() != named_axes;
+        if named_axes > tensor.ndim() || (!has_ellipsis && named_axes != tensor.ndim()) {
+            return Err(EinsumError::SubscriptCountMismatch {
+                operand,
+                subscripts: named_axes,
+                ndim: tensor.ndim(),
+            });
+        }
+
+        let mut translated = String::new();
+        let mut axis = 0;
+        for &name in names {
+            if name == "..." {
+                translated.push_str("...");
+                axis += tensor.ndim() - named_axes;
+                continue;
+            }
+            validate_name(name)?;
+
+            let size = tensor.shape()[axis];
+            match sizes.get(name) {
+                Some(&expected) if expected != size => {
+                    return Err(EinsumError::AxisSizeMismatch {
+                        axis: name.to_string(),
+                        expected,
+                        found: size,
+                    });
+                }
+                _ => {
+                    sizes.insert(name, size);
+                }
+            }
+
+            let letter = match letters.get(name) {
+                Some(&letter) => letter,
+                None => {
+                    let letter = subscripts.next().ok_or(EinsumError::TooManyAxisNames {
+                        limit: SUBSCRIPTS.len(),
+                    })?;
+                    letters.insert(name, letter);
+                    letter
+                }
+            };
+            translated.push(letter);
+            axis += 1;
+        }
+        translated_terms.push(translated);
+    }
+
+    let mut translated_output = String::new();
+    for &name in &output {
+        if name == "..." {
+            translated_output.push_str("...");
+            continue;
+        }
+        let letter = letters
+            .get(name)
+            .ok_or_else(|| EinsumError::UnknownOutputAxis {
+                axis: name.to_string(),
+            })?;
+        if translated_output.contains(*letter) {
+            return Err(EinsumError::RepeatedOutputAxis {
+                axis: name.to_string(),
+            });
+        }
+        translated_output.push(*letter);
+    }
+
+    let equation = format!("{}->{}", translated_terms.join(","), translated_output);
+    einsum_ndarray_dyn(&equation, tensors)
+}
+
+fn validate_name(name: &str) -> Result<(), EinsumError> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(EinsumError::InvalidAxisName {
+            axis: name.to_string(),
+        })
+    }
+}
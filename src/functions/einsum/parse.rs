@@ -0,0 +1,234 @@
+use super::EinsumError;
+use std::collections::HashMap;
+
+/// Number of distinct letter labels (`a-z` followed by `A-Z`).
+const LETTER_LABELS: usize = 52;
+
+/// Internal identifier of an einsum label.
+///
+/// Letters map to `0..52`; dimensions covered by an ellipsis map to `52..`, aligned on the
+/// right so that broadcasting follows NumPy's rules.
+pub(crate) type Label = usize;
+
+/// An einsum equation resolved against concrete operand shapes.
+#[derive(Debug, Clone)]
+pub(crate) struct ParsedEquation {
+    /// Labels of each operand, one per dimension (may contain repeats for diagonals).
+    pub inputs: Vec<Vec<Label>>,
+    /// Labels of the output, one per dimension.
+    pub output: Vec<Label>,
+    /// Broadcast size of every label.
+    pub sizes: HashMap<Label, usize>,
+}
+
+enum Token {
+    Letter(char),
+    Ellipsis,
+}
+
+/// Returns a printable name for a label, `...0`, `...1` for ellipsis dimensions.
+pub(crate) fn label_name(label: Label) -> String {
+    match label {
+        0..=25 => ((b'a' + label as u8) as char).to_string(),
+        26..=51 => ((b'A' + (label - 26) as u8) as char).to_string(),
+        _ => format!("...{}", label - LETTER_LABELS),
+    }
+}
+
+fn letter_label(c: char) -> Label {
+    if c.is_ascii_lowercase() {
+        (c as u8 - b'a') as Label
+    } else {
+        (c as u8 - b'A') as Label + 26
+    }
+}
+
+fn tokenize(term: &str) -> Result<Vec<Token>, EinsumError> {
+    let mut tokens = Vec::new();
+    let mut chars = term.chars().peekable();
+    let mut seen_ellipsis = false;
+
+    while let Some(c) = chars.next() {
+        if c.is_ascii_alphabetic() {
+            tokens.push(Token::Letter(c));
+        } else if c == '.' {
+            if chars.next() != Some('.') || chars.next() != Some('.') {
+                return Err(EinsumError::InvalidEllipsis {
+                    term: term.to_string(),
+                });
+            }
+            if seen_ellipsis {
+                return Err(EinsumError::InvalidEllipsis {
+                    term: term.to_string(),
+                });
+            }
+            seen_ellipsis = true;
+            tokens.push(Token::Ellipsis);
+        } else {
+            return Err(EinsumError::InvalidCharacter { character: c });
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parses `equation` and resolves its labels against `shapes`.
+///
+/// Supports explicit (`ij,jk->ik`) and implicit (`ij,jk`) output, repeated labels within an
+/// operand (diagonals) and a single `...` per term. Dimensions sharing a label must have equal
+/// sizes or size 1, in which case they are broadcast. Like NumPy, an explicit output must keep
+/// the dimensions covered by `...` rather than summing them away.
+pub(crate) fn parse_equation(
+    equation: &str,
+    shapes: &[&[usize]],
+) -> Result<ParsedEquation, EinsumError> {
+    let equation: String = equation.chars().filter(|c| !c.is_whitespace()).collect();
+    let (lhs, rhs) = match equation.split_once("->") {
+        Some((lhs, rhs)) => {
+            if rhs.contains("->") {
+                return Err(EinsumError::MultipleArrows);
+            }
+            (lhs, Some(rhs))
+        }
+        None => (equation.as_str(), None),
+    };
+
+    let terms: Vec<&str> = lhs.split(',').collect();
+    if terms.len() != shapes.len() {
+        return Err(EinsumError::OperandCountMismatch {
+            terms: terms.len(),
+            operands: shapes.len(),
+        });
+    }
+
+    let tokenized = terms
+        .iter()
+        .map(|term| tokenize(term))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Work out how many dimensions each ellipsis covers
+    let mut ellipsis_dims = Vec::with_capacity(shapes.len());
+    for (operand, (tokens, shape)) in tokenized.iter().zip(shapes).enumerate() {
+        let letters = tokens
+            .iter()
+            .filter(|t| matches!(t, Token::Letter(_)))
+            .count();
+        let has_ellipsis = tokens.iter().any(|t| matches!(t, Token::Ellipsis));
+        if letters > shape.len() || (!has_ellipsis && letters != shape.len()) {
+            return Err(EinsumError::SubscriptCountMismatch {
+                operand,
+                subscripts: letters,
+                ndim: shape.len(),
+            });
+        }
+        ellipsis_dims.push(shape.len() - letters);
+    }
+    let max_ellipsis = ellipsis_dims.iter().copied().max().unwrap_or(0);
+
+    let mut inputs = Vec::with_capacity(shapes.len());
+    for (tokens, &covered) in tokenized.iter().zip(&ellipsis_dims) {
+        let mut labels = Vec::new();
+        for token in tokens {
+            match token {
+                Token::Letter(c) => labels.push(letter_label(*c)),
+                Token::Ellipsis => {
+                    labels.extend((max_ellipsis - covered..max_ellipsis).map(|j| LETTER_LABELS + j))
+                }
+            }
+        }
+        inputs.push(labels);
+    }
+
+    // Resolve label sizes, allowing size-1 broadcasting across operands
+    let mut sizes: HashMap<Label, usize> = HashMap::new();
+    for (labels, shape) in inputs.iter().zip(shapes) {
+        let mut local: HashMap<Label, usize> = HashMap::new();
+        for (&label, &size) in labels.iter().zip(shape.iter()) {
+            if let Some(&previous) = local.get(&label)
+                && previous != size
+            {
+                return Err(EinsumError::SizeMismatch {
+                    label: label_name(label),
+                    expected: previous,
+                    found: size,
+                });
+            }
+            local.insert(label, size);
+
+            match sizes.get(&label).copied() {
+                None => {
+                    sizes.insert(label, size);
+                }
+                Some(existing) if existing == size || size == 1 => {}
+                Some(1) => {
+                    sizes.insert(label, size);
+                }
+                Some(existing) => {
+                    return Err(EinsumError::SizeMismatch {
+                        label: label_name(label),
+                        expected: existing,
+                        found: size,
+                    });
+                }
+            }
+        }
+    }
+
+    let output = match rhs {
+        Some(rhs) => {
+            let tokens = tokenize(rhs)?;
+            if max_ellipsis > 0 && !tokens.iter().any(|t| matches!(t, Token::Ellipsis)) {
+                return Err(EinsumError::MissingOutputEllipsis {
+                    term: rhs.to_string(),
+                    dims: max_ellipsis,
+                });
+            }
+            let mut output = Vec::new();
+            for token in tokens {
+                match token {
+                    Token::Letter(c) => {
+                        let label = letter_label(c);
+                        if !sizes.contains_key(&label) {
+                            return Err(EinsumError::UnknownOutputLabel { label: c });
+                        }
+                        if output.contains(&label) {
+                            return Err(EinsumError::RepeatedOutputLabel { label: c });
+                        }
+                        output.push(label);
+                    }
+                    Token::Ellipsis => {
+                        output.extend((0..max_ellipsis).map(|j| LETTER_LABELS + j));
+                    }
+                }
+            }
+            output
+        }
+        None => {
+            // Implicit mode: broadcast dimensions first, then labels that appear exactly once,
+            // in alphabetical (ASCII) order, as NumPy does.
+            let mut counts: HashMap<char, usize> = HashMap::new();
+            for tokens in &tokenized {
+                for token in tokens {
+                    if let Token::Letter(c) = token {
+                        *counts.entry(*c).or_insert(0) += 1;
+                    }
+                }
+            }
+            let mut singles: Vec<char> = counts
+                .into_iter()
+                .filter(|&(_, count)| count == 1)
+                .map(|(c, _)| c)
+                .collect();
+            singles.sort_unstable();
+
+            let mut output: Vec<Label> = (0..max_ellipsis).map(|j| LETTER_LABELS + j).collect();
+            output.extend(singles.into_iter().map(letter_label));
+            output
+        }
+    };
+
+    Ok(ParsedEquation {
+        inputs,
+        output,
+        sizes,
+    })
+}
@@ -0,0 +1,342 @@
+use super::EinsumError;
+use super::parse::{Label, ParsedEquation, label_name};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+/// Above this many operands [`PathStrategy::Auto`] switches from the exhaustive search to the
+/// greedy heuristic, mirroring `opt_einsum`'s default.
+const AUTO_OPTIMAL_LIMIT: usize = 4;
+
+/// How the pairwise contraction order is chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathStrategy {
+    /// Exhaustive search for small operand counts, greedy otherwise.
+    #[default]
+    Auto,
+    /// At each step contract the pair that shrinks the intermediates the most.
+    Greedy,
+    /// Exhaustive search minimizing the total FLOP count. Exponential in the operand count.
+    Optimal,
+    /// Contract operands left to right, like a naive implementation would.
+    Sequential,
+}
+
+/// One pairwise contraction in a [`ContractionPath`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractionStep {
+    /// Positions of the two operands in the current operand list. Both are removed and the
+    /// result is appended to the end of the list, as in `opt_einsum`.
+    pub operands: (usize, usize),
+    /// The pairwise equation performed by this step, e.g. `bij,bjk->bik`.
+    pub equation: String,
+    /// Estimated number of multiply-adds for this step.
+    pub flops: u128,
+    /// Number of elements in the intermediate produced by this step.
+    pub intermediate_size: u128,
+}
+
+/// A contraction order for an einsum equation, as returned by [`super::einsum_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractionPath {
+    /// The equation this path was computed for, with whitespace removed.
+    pub equation: String,
+    /// Pairwise contractions, in execution order.
+    pub steps: Vec<ContractionStep>,
+    /// Estimated cost of evaluating the equation in a single pass over all labels.
+    pub naive_flops: u128,
+    /// Estimated cost of following `steps`.
+    pub optimized_flops: u128,
+    /// Size of the largest intermediate produced by `steps`.
+    pub largest_intermediate: u128,
+}
+
+impl ContractionPath {
+    /// Returns the path as bare `(i, j)` pairs, the format accepted by `opt_einsum`.
+    pub fn pairs(&self) -> Vec<(usize, usize)> {
+        self.steps.iter().map(|step| step.operands).collect()
+    }
+}
+
+impl fmt::Display for ContractionPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Equation: {}", self.equation)?;
+        writeln!(f, "Naive FLOP count: {}", self.naive_flops)?;
+        writeln!(f, "Optimized FLOP count: {}", self.optimized_flops)?;
+        writeln!(
+            f,
+            "Largest intermediate: {} elements",
+            self.largest_intermediate
+        )?;
+        for step in &self.steps {
+            writeln!(
+                f,
+                "  {:?} {} (flops: {}, size: {})",
+                step.operands, step.equation, step.flops, step.intermediate_size
+            )?;
+        }
+        Ok(())
+    }
+}
+
+type LabelSet = BTreeSet<Label>;
+
+/// Label sets and sizes the path search works on.
+struct PathProblem<'a> {
+    operands: Vec<LabelSet>,
+    output: LabelSet,
+    sizes: &'a HashMap<Label, usize>,
+}
+
+impl PathProblem<'_> {
+    fn size_of(&self, labels: &LabelSet) -> u128 {
+        labels.iter().map(|l| self.sizes[l] as u128).product()
+    }
+
+    /// Labels of the intermediate produced by contracting `a` and `b` when `others` remain.
+    fn result_labels(&self, a: &LabelSet, b: &LabelSet, others: &[&LabelSet]) -> LabelSet {
+        a.union(b)
+            .filter(|l| self.output.contains(l) || others.iter().any(|o| o.contains(l)))
+            .copied()
+            .collect()
+    }
+
+    fn pair_cost(&self, current: &[LabelSet], i: usize, j: usize) -> (LabelSet, u128) {
+        let others: Vec<&LabelSet> = current
+            .iter()
+            .enumerate()
+            .filter(|&(k, _)| k != i && k != j)
+            .map(|(_, s)| s)
+            .collect();
+        let result = self.result_labels(&current[i], &current[j], &others);
+        let all: LabelSet = current[i].union(&current[j]).copied().collect();
+        (result, self.size_of(&all))
+    }
+}
+
+/// Removes positions `i` and `j` (in either order) and appends `result`.
+fn apply_step(current: &[LabelSet], i: usize, j: usize, result: LabelSet) -> Vec<LabelSet> {
+    let mut next: Vec<LabelSet> = current
+        .iter()
+        .enumerate()
+        .filter(|&(k, _)| k != i && k != j)
+        .map(|(_, s)| s.clone())
+        .collect();
+    next.push(result);
+    next
+}
+
+fn greedy(problem: &PathProblem) -> Vec<(usize, usize)> {
+    let mut current = problem.operands.clone();
+    let mut pairs = Vec::new();
+    while current.len() > 1 {
+        let mut best: Option<((i128, u128), usize, usize, LabelSet)> = None;
+        for i in 0..current.len() {
+            for j in i + 1..current.len() {
+                let (result, flops) = problem.pair_cost(&current, i, j);
+                // Prefer the pair that removes the most memory, then the cheapest one
+                let removed = problem.size_of(&result) as i128
+                    - problem.size_of(&current[i]) as i128
+                    - problem.size_of(&current[j]) as i128;
+                let key = (removed, flops);
+                if best.as_ref().is_none_or(|(k, ..)| key < *k) {
+                    best = Some((key, i, j, result));
+                }
+            }
+        }
+        let (_, i, j, result) = best.expect("at least two operands remain");
+        current = apply_step(&current, i, j, result);
+        pairs.push((i, j));
+    }
+    pairs
+}
+
+fn optimal(problem: &PathProblem) -> Vec<(usize, usize)> {
+    fn search(
+        problem: &PathProblem,
+        current: Vec<LabelSet>,
+        cost: u128,
+        pairs: &mut Vec<(usize, usize)>,
+        best: &mut (u128, Vec<(usize, usize)>),
+    ) {
+        if cost >= best.0 {
+            return;
+        }
+        if current.len() == 1 {
+            *best = (cost, pairs.clone());
+            return;
+        }
+        for i in 0..current.len() {
+            for j in i + 1..current.len() {
+                let (result, flops) = problem.pair_cost(&current, i, j);
+                let next = apply_step(&current, i, j, result);
+                pairs.push((i, j));
+                search(problem, next, cost + flops, pairs, best);
+                pairs.pop();
+            }
+        }
+    }
+
+    // Seed the bound with the greedy path so hopeless branches are pruned early
+    let seed = greedy(problem);
+    let seed_cost = evaluate(problem, &seed)
+        .map(|steps| steps.iter().map(|s| s.2).sum::<u128>())
+        .unwrap_or(u128::MAX);
+    let mut best = (seed_cost.saturating_add(1), seed);
+    search(
+        problem,
+        problem.operands.clone(),
+        0,
+        &mut Vec::new(),
+        &mut best,
+    );
+    best.1
+}
+
+fn sequential(problem: &PathProblem) -> Vec<(usize, usize)> {
+    // Contracting (0, 1) repeatedly folds in the next operand since results go to the end
+    let n = problem.operands.len();
+    (1..n)
+        .map(|step| if step == 1 { (0, 1) } else { (0, n - step) })
+        .collect()
+}
+
+/// Replays `pairs`, returning (left labels, right labels, flops, result labels) per step.
+fn evaluate(
+    problem: &PathProblem,
+    pairs: &[(usize, usize)],
+) -> Result<Vec<(LabelSet, LabelSet, u128, LabelSet)>, EinsumError> {
+    let mut current = problem.operands.clone();
+    let mut steps = Vec::with_capacity(pairs.len());
+    for &(i, j) in pairs {
+        if i == j || i >= current.len() || j >= current.len() {
+            return Err(EinsumError::InvalidPath {
+                reason: format!(
+                    "step ({}, {}) is not a valid pair of the {} remaining operands",
+                    i,
+                    j,
+                    current.len()
+                ),
+            });
+        }
+        let (result, flops) = problem.pair_cost(&current, i, j);
+        steps.push((
+            current[i].clone(),
+            current[j].clone(),
+            flops,
+            result.clone(),
+        ));
+        current = apply_step(&current, i, j, result);
+    }
+    if current.len() != 1 {
+        return Err(EinsumError::InvalidPath {
+            reason: format!(
+                "{} operands remain after the path, expected 1",
+                current.len()
+            ),
+        });
+    }
+    Ok(steps)
+}
+
+fn format_labels(labels: &[Label]) -> String {
+    labels.iter().map(|&l| label_name(l)).collect()
+}
+
+/// Builds the label sets of the path search from a parsed equation.
+///
+/// Labels that only appear in a single operand and not in the output are summed out before
+/// any pairwise contraction, so they are left out here.
+fn problem_from(parsed: &ParsedEquation) -> PathProblem<'_> {
+    let output: LabelSet = parsed.output.iter().copied().collect();
+    let operands = parsed
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(i, labels)| {
+            labels
+                .iter()
+                .filter(|l| {
+                    output.contains(l)
+                        || parsed
+                            .inputs
+                            .iter()
+                            .enumerate()
+                            .any(|(k, other)| k != i && other.contains(l))
+                })
+                .copied()
+                .collect()
+        })
+        .collect();
+    PathProblem {
+        operands,
+        output,
+        sizes: &parsed.sizes,
+    }
+}
+
+/// Computes the contraction path for `parsed` using `strategy`.
+pub(crate) fn compute_path(
+    equation: &str,
+    parsed: &ParsedEquation,
+    strategy: PathStrategy,
+) -> ContractionPath {
+    let problem = problem_from(parsed);
+    let n = problem.operands.len();
+    let pairs = match strategy {
+        _ if n < 2 => Vec::new(),
+        PathStrategy::Auto if n <= AUTO_OPTIMAL_LIMIT => optimal(&problem),
+        PathStrategy::Auto | PathStrategy::Greedy => greedy(&problem),
+        PathStrategy::Optimal => optimal(&problem),
+        PathStrategy::Sequential => sequential(&problem),
+    };
+    build_path(equation, parsed, &pairs).expect("generated paths are always valid")
+}
+
+/// Validates `pairs` against `parsed` and fills in the per-step statistics.
+pub(crate) fn build_path(
+    equation: &str,
+    parsed: &ParsedEquation,
+    pairs: &[(usize, usize)],
+) -> Result<ContractionPath, EinsumError> {
+    let problem = problem_from(parsed);
+    let evaluated = evaluate(&problem, pairs)?;
+
+    let all_labels: LabelSet = parsed.inputs.iter().flatten().copied().collect();
+    let naive_flops = problem.size_of(&all_labels);
+
+    let steps: Vec<ContractionStep> = pairs
+        .iter()
+        .zip(evaluated)
+        .map(|(&operands, (a, b, flops, result))| {
+            let a: Vec<Label> = a.into_iter().collect();
+            let b: Vec<Label> = b.into_iter().collect();
+            let result_vec: Vec<Label> = result.iter().copied().collect();
+            ContractionStep {
+                operands,
+                equation: format!(
+                    "{},{}->{}",
+                    format_labels(&a),
+                    format_labels(&b),
+                    format_labels(&result_vec)
+                ),
+                flops,
+                intermediate_size: problem.size_of(&result),
+            }
+        })
+        .collect();
+
+    let optimized_flops = if steps.is_empty() {
+        naive_flops
+    } else {
+        steps.iter().map(|s| s.flops).sum()
+    };
+    let largest_intermediate = steps.iter().map(|s| s.intermediate_size).max().unwrap_or(0);
+
+    Ok(ContractionPath {
+        equation: equation.chars().filter(|c| !c.is_whitespace()).collect(),
+        steps,
+        naive_flops,
+        optimized_flops,
+        largest_intermediate,
+    })
+}
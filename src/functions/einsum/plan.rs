@@ -0,0 +1,205 @@
+use super::EinsumError;
+use super::parse::{Label, parse_equation};
+use ndarray::{ArrayViewD, ArrayViewMutD, LinalgScalar};
+use std::ops::Range;
+
+/// An einsum equation prepared for operands of fixed shapes, which evaluates without allocating.
+///
+/// [`einsum_ndarray_dyn`](super::einsum_ndarray_dyn) parses the equation, chooses a contraction
+/// order and allocates intermediate arrays on every call. A plan parses once, and
+/// [`execute_into`](EinsumPlan::execute_into) then sums the products for each output element
+/// directly into a caller-provided array, keeping its loop counters in buffers the plan owns.
+/// That suits the small contractions repeated in a decoding loop; for large contractions the
+/// batched matrix products of `einsum_ndarray_dyn` are faster.
+#[derive(Debug, Clone)]
+pub struct EinsumPlan {
+    shapes: Vec<Vec<usize>>,
+    output_shape: Vec<usize>,
+    /// Size of every label, the output labels first and then the summed ones.
+    sizes: Vec<usize>,
+    /// Number of output labels at the start of `sizes`.
+    outputs: usize,
+    /// Position in `sizes` of the label of each operand axis.
+    slots: Vec<Vec<usize>>,
+    // Scratch space reused by every evaluation
+    strides: Vec<isize>,
+    offsets: Vec<isize>,
+    inner: Vec<isize>,
+    counters: Vec<usize>,
+}
+
+impl EinsumPlan {
+    /// Prepares `equation` for operands of the given shapes.
+    ///
+    /// # Arguments
+    ///
+    /// * `equation` - The einsum equation, with the same syntax as
+    ///   [`einsum_ndarray_dyn`](super::einsum_ndarray_dyn).
+    /// * `shapes` - The shape of each operand.
+    ///
+    /// # Returns
+    ///
+    /// The plan, or an `EinsumError` if the equation is malformed or does not match the shapes.
+    pub fn new(equation: &str, shapes: &[&[usize]]) -> Result<Self, EinsumError> {
+        let parsed = parse_equation(equation, shapes)?;
+        let mut labels: Vec<Label> = parsed.output.clone();
+        for &label in parsed.inputs.iter().flatten() {
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
+        }
+        let sizes: Vec<usize> = labels.iter().map(|label| parsed.sizes[label]).collect();
+        let slots = parsed
+            .inputs
+            .iter()
+            .map(|operand| {
+                operand
+                    .iter()
+                    .map(|label| labels.iter().position(|l| l == label).unwrap())
+                    .collect()
+            })
+            .collect();
+
+        Ok(EinsumPlan {
+            shapes: shapes.iter().map(|shape| shape.to_vec()).collect(),
+            output_shape: sizes[..parsed.output.len()].to_vec(),
+            outputs: parsed.output.len(),
+            slots,
+            strides: vec![0; shapes.len() * labels.len()],
+            offsets: vec![0; shapes.len()],
+            inner: vec![0; shapes.len()],
+            counters: vec![0; labels.len()],
+            sizes,
+        })
+    }
+
+    /// Shape of the result, which the output passed to
+    /// [`execute_into`](EinsumPlan::execute_into) must have.
+    pub fn output_shape(&self) -> &[usize] {
+        &self.output_shape
+    }
+
+    /// Evaluates the equation into `out`.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where to write the result. Must have the plan's
+    ///   [`output_shape`](EinsumPlan::output_shape); any layout works.
+    /// * `tensors` - Views of the operands, with the shapes the plan was prepared for and any
+    ///   strides.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` once `out` is written, or an `EinsumError` if an operand or `out` has another
+    /// shape than planned.
+    pub fn execute_into<A>(
+        &mut self,
+        out: &mut ArrayViewMutD<'_, A>,
+        tensors: &[ArrayViewD<'_, A>],
+    ) -> Result<(), EinsumError>
+    where
+        A: LinalgScalar,
+    {
+        if tensors.len() != self.shapes.len() {
+            return Err(EinsumError::OperandCountMismatch {
+                terms: self.shapes.len(),
+                operands: tensors.len(),
+            });
+        }
+        for (operand, (tensor, shape)) in tensors.iter().zip(&self.shapes).enumerate() {
+            if tensor.shape() != shape.as_slice() {
+                return Err(EinsumError::OperandShapeMismatch {
+                    operand,
+                    expected: shape.clone(),
+                    found: tensor.shape().to_vec(),
+                });
+            }
+        }
+        if out.shape() != self.output_shape.as_slice() {
+            return Err(EinsumError::OutputShapeMismatch {
+                expected: self.output_shape.clone(),
+                found: out.shape().to_vec(),
+            });
+        }
+
+        let Self {
+            sizes,
+            outputs,
+            slots,
+            strides,
+            offsets,
+            inner,
+            counters,
+            ..
+        } = self;
+        let labels = sizes.len();
+        // How far each operand moves in memory when a label advances. Repeated labels add their
+        // strides, which walks the diagonal, and broadcast size-1 axes do not move.
+        strides.fill(0);
+        for (k, (tensor, slots)) in tensors.iter().zip(slots.iter()).enumerate() {
+            for ((&slot, &size), &stride) in slots.iter().zip(tensor.shape()).zip(tensor.strides())
+            {
+                if size != 1 {
+                    strides[k * labels + slot] += stride;
+                }
+            }
+        }
+        offsets.fill(0);
+        counters.fill(0);
+        let summed = *outputs..labels;
+        let nothing_summed = sizes[summed.clone()].contains(&0);
+
+        // `out` iterates in logical order, which is the order of the output counters
+        for value in out.iter_mut() {
+            let mut sum = A::zero();
+            if !nothing_summed {
+                inner.copy_from_slice(offsets);
+                loop {
+                    let mut product = A::one();
+                    for (tensor, &offset) in tensors.iter().zip(inner.iter()) {
+                        // SAFETY: each counter stays below the size of its label, which every
+                        // axis carrying the label has unless it is a broadcast axis of size 1
+                        // with stride 0, so the offset addresses an element of `tensor`.
+                        product = product * unsafe { *tensor.as_ptr().offset(offset) };
+                    }
+                    sum = sum + product;
+                    if !advance(counters, sizes, strides, summed.clone(), inner) {
+                        break;
+                    }
+                }
+            }
+            *value = sum;
+            advance(counters, sizes, strides, 0..*outputs, offsets);
+        }
+        Ok(())
+    }
+}
+
+/// Steps the counters of the labels in `range` to the next position in row-major order, moving
+/// every operand's offset along. Returns `false`, with those counters back at zero, after the
+/// last position.
+fn advance(
+    counters: &mut [usize],
+    sizes: &[usize],
+    strides: &[isize],
+    range: Range<usize>,
+    offsets: &mut [isize],
+) -> bool {
+    let labels = sizes.len();
+    for slot in range.rev() {
+        counters[slot] += 1;
+        let wrapped = counters[slot] == sizes[slot];
+        for (k, offset) in offsets.iter_mut().enumerate() {
+            let stride = strides[k * labels + slot];
+            *offset += stride;
+            if wrapped {
+                *offset -= stride * sizes[slot] as isize;
+            }
+        }
+        if !wrapped {
+            return true;
+        }
+        counters[slot] = 0;
+    }
+    false
+}
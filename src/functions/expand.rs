@@ -1,5 +1,4 @@
-use ndarray::{Array, ArrayD, IxDyn, NdFloat};
-use std::convert::TryFrom;
+use ndarray::{ArrayD, ArrayViewMutD, IxDyn};
 
 use ndarray::prelude::*;
 
@@ -24,22 +23,16 @@ pub fn expand_at_dim<A>(
     size: usize,
 ) -> Result<ArrayD<A>, &'static str>
 where
-    A: NdFloat + Clone,
+    A: Clone,
 {
     // Check that dimension is valid
     if dim > input.ndim() {
         return Err("Dimension index out of bounds");
     }
 
-    // First perform the equivalent of unsqueeze
-    let mut new_shape = input.shape().to_vec();
-    new_shape.insert(dim, 1);
-
-    // Reshape to add the new dimension
-    let unsqueezed = match input.clone().into_shape(IxDyn(&new_shape)) {
-        Ok(arr) => arr,
-        Err(_) => return Err("Failed to reshape tensor for unsqueeze operation"),
-    };
+    // First perform the equivalent of unsqueeze. Inserting an axis into a view works for any
+    // memory layout, unlike reshaping.
+    let unsqueezed = input.view().insert_axis(Axis(dim));
 
     // Now build shape for the expansion
     let mut expanded_shape = unsqueezed.shape().to_vec();
@@ -54,3 +47,47 @@ where
     // Create an owned array from the view
     Ok(view.to_owned())
 }
+
+/// Like [`expand_at_dim`], but writes the expanded tensor into `out` instead of allocating.
+///
+/// # Arguments
+///
+/// * `out` - Where to write the result. Its size along `dim` is the size to expand to, and its
+///   other dimensions must match `input`
+/// * `input` - The input ArrayD to expand
+/// * `dim` - The dimension at which to insert and expand
+///
+/// # Returns
+///
+/// `Ok(())` once `out` is written, or an error if `dim` or the shape of `out` is invalid
+pub fn expand_at_dim_into<A>(
+    out: &mut ArrayViewMutD<'_, A>,
+    input: &ArrayD<A>,
+    dim: usize,
+) -> Result<(), &'static str>
+where
+    A: Clone,
+{
+    if dim > input.ndim() {
+        return Err("Dimension index out of bounds");
+    }
+
+    let unsqueezed = input.view().insert_axis(Axis(dim));
+    let matches = out.ndim() == unsqueezed.ndim()
+        && out
+            .shape()
+            .iter()
+            .zip(unsqueezed.shape())
+            .enumerate()
+            .all(|(i, (o, u))| i == dim || o == u);
+    if !matches {
+        return Err("Output shape does not match the expanded shape");
+    }
+
+    let view = match unsqueezed.broadcast(out.raw_dim()) {
+        Some(view) => view,
+        None => return Err("Failed to broadcast tensor for expand operation"),
+    };
+    out.assign(&view);
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::functions::dim::normalize_dim;
+use ndarray::{ArrayD, Axis};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum FlipError {
+    #[error("Dimension index {dim} is out of bounds for array with {ndim} dimensions")]
+    InvalidDimension { dim: isize, ndim: usize },
+
+    #[error("Dimension {dim} appears more than once in dims")]
+    RepeatedDimension { dim: usize },
+}
+
+/// Reverses the order of elements along the given dimensions.
+///
+/// Mimics the behavior of `torch.flip(input, dims)`.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `dims`: The dimensions to flip. Negative values wrap around.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: A new array with the same shape as `input`, in standard layout.
+/// * `Err(FlipError)`: If a dimension is out of bounds or repeated.
+pub fn flip<A>(input: &ArrayD<A>, dims: &[isize]) -> Result<ArrayD<A>, FlipError>
+where
+    A: Clone,
+{
+    let ndim = input.ndim();
+    let mut seen = vec![false; ndim];
+    let mut view = input.view();
+
+    for &dim in dims {
+        let d = normalize_dim(dim, ndim).ok_or(FlipError::InvalidDimension { dim, ndim })?;
+        if seen[d] {
+            return Err(FlipError::RepeatedDimension { dim: d });
+        }
+        seen[d] = true;
+        // Inverting an axis only negates its stride, the copy happens once below.
+        view.invert_axis(Axis(d));
+    }
+
+    Ok(view.as_standard_layout().into_owned())
+}
@@ -1,4 +1,5 @@
-use ndarray::{Array, ArrayD, Axis, Dimension, IxDyn, NdIndex};
+use crate::functions::dim::normalize_dim;
+use ndarray::{Array, ArrayD, ArrayViewMutD, Axis, Dimension, IxDyn, NdIndex};
 use num_traits::{NumCast, Zero};
 use std::fmt::Debug;
 use std::mem::MaybeUninit;
@@ -50,6 +51,12 @@ pub enum GatherError {
         index_value: String,
     },
 
+    #[error("Output has shape {found:?} but the index has shape {expected:?}")]
+    OutputShapeMismatch {
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+
     #[error("Internal error during array creation: {0}")]
     InternalShapeError(String), // For errors from Array::from_shape_vec etc.
 }
@@ -145,41 +152,9 @@ where
         }
     }
 
-    // --- Validate and normalize dimension ---
-    let dim_usize = if dim >= 0 {
-        let d = dim as usize;
-        if d >= input_ndim {
-            return Err(GatherError::InvalidDimension {
-                dim,
-                ndim: input_ndim,
-            });
-        }
-        d
-    } else {
-        // Handle negative dim
-        let resolved_dim = input_ndim as isize + dim;
-        if resolved_dim < 0 {
-            return Err(GatherError::InvalidDimension {
-                dim,
-                ndim: input_ndim,
-            });
-        }
-        resolved_dim as usize
-    };
-
-    // --- Validate shapes ---
+    // --- Validate dimension and shapes ---
     let input_shape = input.shape();
-    let index_shape = index.shape();
-
-    for k in 0..input_ndim {
-        if k != dim_usize && input_shape[k] < index_shape[k] {
-            return Err(GatherError::ShapeMismatch {
-                axis: k,
-                input_size: input_shape[k],
-                index_size: index_shape[k],
-            });
-        }
-    }
+    let dim_usize = check_shapes(input_shape, index.shape(), dim)?;
 
     let input_dim_size = input_shape[dim_usize];
     // If the dimension we gather along is size 0, we can only succeed if the index
@@ -218,36 +193,15 @@ where
             }
         };
 
-        // 2. Check for negative index, use the slice for error reporting
-        if gather_idx_val < Ix::zero() {
-            return Err(GatherError::NegativeIndex {
-                coords: current_coords_slice.to_vec(), // Clone slice into Vec
-                index_value: format!("{:?}", gather_idx_val),
-            });
-        }
-
-        // 3. Cast the index value to usize for array indexing, use slice for error
-        let gather_idx_usize: usize = match NumCast::from(gather_idx_val) {
-            Some(idx) => idx,
-            None => {
-                return Err(GatherError::IndexCastError {
-                    coords: current_coords_slice.to_vec(), // Clone slice into Vec
-                    index_value: format!("{:?}", gather_idx_val),
-                });
-            }
-        };
-
-        // 4. Check if the index is within the bounds, use slice for error
-        if gather_idx_usize >= input_dim_size {
-            return Err(GatherError::IndexOutOfBounds {
-                coords: current_coords_slice.to_vec(), // Clone slice into Vec
-                index_value: format!("{:?}", gather_idx_val), // Report original index value
-                dim: dim_usize,
-                dim_size: input_dim_size,
-            });
-        }
+        // 2. Check the index value and convert it to a position along `dim_usize`
+        let gather_idx_usize = resolve_index(
+            gather_idx_val,
+            current_coords_slice,
+            dim_usize,
+            input_dim_size,
+        )?;
 
-        // 5. Construct the coordinates to access the `input` array using the slice
+        // 3. Construct the coordinates to access the `input` array using the slice
         // Check length just in case (should match input_ndim)
         if current_coords_slice.len() != input_ndim {
             panic!(
@@ -262,7 +216,7 @@ where
         // Replace the coordinate at `dim_usize` with the gathered index
         input_coords_buffer[dim_usize] = gather_idx_usize;
 
-        // 6. Get the value from the `input` array using the buffer (which is &[usize])
+        // 4. Get the value from the `input` array using the buffer (which is &[usize])
         let value = match input.get(&input_coords_buffer[..]) {
             // Pass slice from buffer
             Some(v) => v.clone(), // Clone the value from input
@@ -274,7 +228,7 @@ where
             }
         };
 
-        // 7. Write the gathered value to the output array
+        // 5. Write the gathered value to the output array
         *output_elem_uninit = MaybeUninit::new(value);
     }
 
@@ -285,3 +239,111 @@ where
 
     Ok(final_output)
 }
+
+/// Like [`gather`], but writes the gathered values into `out` instead of allocating a new array.
+///
+/// # Arguments
+///
+/// * `out`: Where to write the result. Must have the same shape as `index`; any layout works,
+///   so it may be a view into a larger buffer.
+/// * `input`: The source array.
+/// * `dim`: The dimension along which to gather. Negative values wrap around.
+/// * `index`: The positions to gather along `dim`, with the same constraints as for [`gather`].
+///
+/// # Returns
+///
+/// * `Ok(())`: If every element of `out` was written.
+/// * `Err(GatherError)`: If the shapes or an index are invalid. `out` may then be partially
+///   written.
+pub fn gather_into<T, Ix>(
+    out: &mut ArrayViewMutD<'_, T>,
+    input: &ArrayD<T>,
+    dim: isize,
+    index: &ArrayD<Ix>,
+) -> Result<(), GatherError>
+where
+    T: Clone + Debug,
+    Ix: NumCast + PartialOrd + Zero + Copy + Debug,
+{
+    if out.shape() != index.shape() {
+        return Err(GatherError::OutputShapeMismatch {
+            expected: index.shape().to_vec(),
+            found: out.shape().to_vec(),
+        });
+    }
+    if input.ndim() == 0 {
+        // `gather` handles the scalar rules; the temporary holds a single element
+        out.assign(&gather(input, dim, index)?);
+        return Ok(());
+    }
+    let dim = check_shapes(input.shape(), index.shape(), dim)?;
+    let dim_size = input.shape()[dim];
+
+    // Both iterate in logical order, and `out` has the shape of `index`
+    for ((coords, &value), out_elem) in index.indexed_iter().zip(out.iter_mut()) {
+        let position = resolve_index(value, coords.slice(), dim, dim_size)?;
+        let mut source = coords;
+        source[dim] = position;
+        *out_elem = input[&source].clone();
+    }
+    Ok(())
+}
+
+/// Checks that `input` and `index` have the same number of dimensions and that `index` is no
+/// larger than `input` outside `dim`, returning `dim` resolved against the input.
+fn check_shapes(
+    input_shape: &[usize],
+    index_shape: &[usize],
+    dim: isize,
+) -> Result<usize, GatherError> {
+    let ndim = input_shape.len();
+    if ndim != index_shape.len() {
+        return Err(GatherError::DimensionMismatch {
+            input_ndim: ndim,
+            index_ndim: index_shape.len(),
+        });
+    }
+    let dim_usize = normalize_dim(dim, ndim).ok_or(GatherError::InvalidDimension { dim, ndim })?;
+    for k in 0..ndim {
+        if k != dim_usize && input_shape[k] < index_shape[k] {
+            return Err(GatherError::ShapeMismatch {
+                axis: k,
+                input_size: input_shape[k],
+                index_size: index_shape[k],
+            });
+        }
+    }
+    Ok(dim_usize)
+}
+
+/// Converts the index value found at `coords` into a position along `dim`, which has
+/// `dim_size` entries.
+fn resolve_index<Ix>(
+    value: Ix,
+    coords: &[usize],
+    dim: usize,
+    dim_size: usize,
+) -> Result<usize, GatherError>
+where
+    Ix: NumCast + PartialOrd + Zero + Copy + Debug,
+{
+    if value < Ix::zero() {
+        return Err(GatherError::NegativeIndex {
+            coords: coords.to_vec(),
+            index_value: format!("{:?}", value),
+        });
+    }
+    let position: usize = NumCast::from(value).ok_or_else(|| GatherError::IndexCastError {
+        coords: coords.to_vec(),
+        index_value: format!("{:?}", value),
+    })?;
+    if position >= dim_size {
+        return Err(GatherError::IndexOutOfBounds {
+            coords: coords.to_vec(),
+            index_value: format!("{:?}", value),
+            dim,
+            dim_size,
+        });
+    }
+    Ok(position)
+}
@@ -0,0 +1,57 @@
+use crate::functions::dim::normalize_dim;
+use ndarray::{ArrayD, Axis};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum IndexSelectError {
+    #[error("Dimension index {dim} is out of bounds for array with {ndim} dimensions")]
+    InvalidDimension { dim: isize, ndim: usize },
+
+    #[error("Index {index} is out of bounds for dimension {dim} with size {dim_size}")]
+    IndexOutOfBounds {
+        index: i64,
+        dim: usize,
+        dim_size: usize,
+    },
+}
+
+/// Selects the entries at `index` along `dim`, in the order given.
+///
+/// Mimics the behavior of `torch.index_select(input, dim, index)`. Indices may repeat, which is
+/// how beam search duplicates the cache rows of a beam that several new beams continue from.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `dim`: The dimension to select along. Negative values wrap around.
+/// * `index`: Positions along `dim`, each in `0..input.shape()[dim]`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: An array shaped like `input` except that `dim` has `index.len()` entries.
+/// * `Err(IndexSelectError)`: If `dim` is out of bounds or an index is out of range.
+pub fn index_select<A>(
+    input: &ArrayD<A>,
+    dim: isize,
+    index: &[i64],
+) -> Result<ArrayD<A>, IndexSelectError>
+where
+    A: Clone,
+{
+    let ndim = input.ndim();
+    let d = normalize_dim(dim, ndim).ok_or(IndexSelectError::InvalidDimension { dim, ndim })?;
+    let dim_size = input.len_of(Axis(d));
+    let positions = index
+        .iter()
+        .map(|&i| {
+            usize::try_from(i).ok().filter(|&i| i < dim_size).ok_or(
+                IndexSelectError::IndexOutOfBounds {
+                    index: i,
+                    dim: d,
+                    dim_size,
+                },
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(input.select(Axis(d), &positions))
+}
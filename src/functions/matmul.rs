@@ -0,0 +1,403 @@
+use crate::functions::upcast::{HalfFloat, downcast, upcast};
+use ndarray::linalg::general_mat_mul;
+use ndarray::{
+    Array3, ArrayD, ArrayView, ArrayView3, ArrayViewD, Axis, Dimension, Ix2, Ix3, IxDyn,
+    LinalgScalar, Zip,
+};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MatmulError {
+    #[error("Both arguments to matmul need to be at least 1D, got {left_ndim}D and {right_ndim}D")]
+    ZeroDimensional { left_ndim: usize, right_ndim: usize },
+
+    #[error("Expected a {expected}D array for {argument}, got shape {shape:?}")]
+    WrongDimensions {
+        argument: &'static str,
+        expected: usize,
+        shape: Vec<usize>,
+    },
+
+    #[error("Inner dimensions do not match: {left_shape:?} @ {right_shape:?}")]
+    InnerDimensionMismatch {
+        left_shape: Vec<usize>,
+        right_shape: Vec<usize>,
+    },
+
+    #[error("Batch dimensions {left:?} and {right:?} can not be broadcast together")]
+    BatchBroadcast { left: Vec<usize>, right: Vec<usize> },
+
+    #[error("Input of shape {input:?} can not be broadcast to the product shape {product:?}")]
+    InputBroadcast {
+        input: Vec<usize>,
+        product: Vec<usize>,
+    },
+}
+
+/// Matrix product of two arrays following the broadcasting rules of `torch.matmul`.
+///
+/// * 1D @ 1D is a dot product returning a 0D array.
+/// * 2D @ 2D is a matrix product.
+/// * A 1D left operand is treated as a row vector and a 1D right operand as a column vector;
+///   the added dimension is removed from the result.
+/// * For higher dimensions the last two axes are the matrices and all leading axes are batch
+///   axes, which are broadcast against each other.
+///
+/// Each matrix product goes through `ndarray`'s `general_mat_mul`, which dispatches to the
+/// optimized `matrixmultiply` GEMM kernels for `f32` and `f64`. Batches run in parallel with rayon.
+///
+/// # Arguments
+///
+/// * `a`: The left operand.
+/// * `b`: The right operand.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The product.
+/// * `Err(MatmulError)`: If an operand is 0D, the inner dimensions differ or the batch
+///   dimensions can not be broadcast.
+pub fn matmul<A>(a: &ArrayD<A>, b: &ArrayD<A>) -> Result<ArrayD<A>, MatmulError>
+where
+    A: LinalgScalar + Send + Sync,
+{
+    let (left_ndim, right_ndim) = (a.ndim(), b.ndim());
+    if left_ndim == 0 || right_ndim == 0 {
+        return Err(MatmulError::ZeroDimensional {
+            left_ndim,
+            right_ndim,
+        });
+    }
+
+    // Promote vectors to matrices, remembering which axes to drop afterwards
+    let a_view = if left_ndim == 1 {
+        a.view().insert_axis(Axis(0))
+    } else {
+        a.view()
+    };
+    let b_view = if right_ndim == 1 {
+        b.view().insert_axis(Axis(1))
+    } else {
+        b.view()
+    };
+
+    let (n, k) = last_two(&a_view);
+    let (k2, p) = last_two(&b_view);
+    if k != k2 {
+        return Err(MatmulError::InnerDimensionMismatch {
+            left_shape: a.shape().to_vec(),
+            right_shape: b.shape().to_vec(),
+        });
+    }
+
+    let left_batch = &a_view.shape()[..a_view.ndim() - 2];
+    let right_batch = &b_view.shape()[..b_view.ndim() - 2];
+    let batch =
+        broadcast_shapes(left_batch, right_batch).ok_or_else(|| MatmulError::BatchBroadcast {
+            left: left_batch.to_vec(),
+            right: right_batch.to_vec(),
+        })?;
+    let batch_len: usize = batch.iter().product();
+
+    let a3 = stack_matrices(&a_view, &batch, (n, k));
+    let b3 = stack_matrices(&b_view, &batch, (k, p));
+    let mut out = Array3::<A>::zeros((batch_len, n, p));
+    batched_gemm(A::one(), a3.view(), b3.view(), A::zero(), &mut out);
+
+    let mut out_shape = batch;
+    if left_ndim > 1 {
+        out_shape.push(n);
+    }
+    if right_ndim > 1 {
+        out_shape.push(p);
+    }
+    Ok(out
+        .into_shape(IxDyn(&out_shape))
+        .expect("product has batch * n * p elements"))
+}
+
+/// Batched matrix product of two 3D arrays with equal batch size, like `torch.bmm`.
+///
+/// # Arguments
+///
+/// * `a`: Array of shape `(b, n, m)`.
+/// * `b`: Array of shape `(b, m, p)`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: Array of shape `(b, n, p)`.
+/// * `Err(MatmulError)`: If the operands are not 3D or their shapes do not line up.
+pub fn bmm<A>(a: &ArrayD<A>, b: &ArrayD<A>) -> Result<ArrayD<A>, MatmulError>
+where
+    A: LinalgScalar + Send + Sync,
+{
+    baddbmm_impl(None, a, b, A::zero(), A::one())
+}
+
+/// Computes `beta * input + alpha * (batch1 @ batch2)`, like `torch.baddbmm`.
+///
+/// # Arguments
+///
+/// * `input`: Array broadcastable to `(b, n, p)`. Ignored when `beta` is zero, so NaNs in it
+///   do not propagate, matching PyTorch.
+/// * `batch1`: Array of shape `(b, n, m)`.
+/// * `batch2`: Array of shape `(b, m, p)`.
+/// * `beta`: Multiplier for `input`.
+/// * `alpha`: Multiplier for the batched product.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: Array of shape `(b, n, p)`.
+/// * `Err(MatmulError)`: If the shapes do not line up or `input` can not be broadcast.
+pub fn baddbmm<A>(
+    input: &ArrayD<A>,
+    batch1: &ArrayD<A>,
+    batch2: &ArrayD<A>,
+    beta: A,
+    alpha: A,
+) -> Result<ArrayD<A>, MatmulError>
+where
+    A: LinalgScalar + Send + Sync,
+{
+    baddbmm_impl(Some(input), batch1, batch2, beta, alpha)
+}
+
+/// Computes `beta * input + alpha * (mat1 @ mat2)`, like `torch.addmm`.
+///
+/// # Arguments
+///
+/// * `input`: Array broadcastable to `(n, p)`. Ignored when `beta` is zero.
+/// * `mat1`: Array of shape `(n, m)`.
+/// * `mat2`: Array of shape `(m, p)`.
+/// * `beta`: Multiplier for `input`.
+/// * `alpha`: Multiplier for the matrix product.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: Array of shape `(n, p)`.
+/// * `Err(MatmulError)`: If the shapes do not line up or `input` can not be broadcast.
+pub fn addmm<A>(
+    input: &ArrayD<A>,
+    mat1: &ArrayD<A>,
+    mat2: &ArrayD<A>,
+    beta: A,
+    alpha: A,
+) -> Result<ArrayD<A>, MatmulError>
+where
+    A: LinalgScalar + Send + Sync,
+{
+    let m1 = as_dims::<A, Ix2>(mat1, "mat1")?;
+    let m2 = as_dims::<A, Ix2>(mat2, "mat2")?;
+    if m1.ncols() != m2.nrows() {
+        return Err(MatmulError::InnerDimensionMismatch {
+            left_shape: mat1.shape().to_vec(),
+            right_shape: mat2.shape().to_vec(),
+        });
+    }
+
+    let product_shape = [m1.nrows(), m2.ncols()];
+    let mut out = initial_output(input, &product_shape, beta)?
+        .into_dimensionality::<Ix2>()
+        .expect("output was created with two dimensions");
+    general_mat_mul(alpha, &m1, &m2, beta, &mut out);
+    Ok(out.into_dyn())
+}
+
+/// [`matmul`] for half-precision operands: widens them to `f32`, multiplies with the `f32`
+/// GEMM kernels and rounds the product back to `A` once.
+///
+/// # Arguments
+///
+/// * `a`: The left `f16` or `bf16` operand.
+/// * `b`: The right operand.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The product.
+/// * `Err(MatmulError)`: As for [`matmul`].
+pub fn matmul_upcast<A>(a: &ArrayD<A>, b: &ArrayD<A>) -> Result<ArrayD<A>, MatmulError>
+where
+    A: HalfFloat,
+{
+    Ok(downcast(&matmul(&upcast(a), &upcast(b))?))
+}
+
+/// [`bmm`] for half-precision operands, accumulating in `f32`. See [`matmul_upcast`].
+pub fn bmm_upcast<A>(a: &ArrayD<A>, b: &ArrayD<A>) -> Result<ArrayD<A>, MatmulError>
+where
+    A: HalfFloat,
+{
+    Ok(downcast(&bmm(&upcast(a), &upcast(b))?))
+}
+
+/// [`baddbmm`] for half-precision operands, accumulating in `f32`. The scalars are taken as
+/// `f32`, like PyTorch does for half tensors, so they are not rounded before use.
+pub fn baddbmm_upcast<A>(
+    input: &ArrayD<A>,
+    batch1: &ArrayD<A>,
+    batch2: &ArrayD<A>,
+    beta: f32,
+    alpha: f32,
+) -> Result<ArrayD<A>, MatmulError>
+where
+    A: HalfFloat,
+{
+    let product = baddbmm(
+        &upcast(input),
+        &upcast(batch1),
+        &upcast(batch2),
+        beta,
+        alpha,
+    )?;
+    Ok(downcast(&product))
+}
+
+/// [`addmm`] for half-precision operands, accumulating in `f32`. See [`baddbmm_upcast`].
+pub fn addmm_upcast<A>(
+    input: &ArrayD<A>,
+    mat1: &ArrayD<A>,
+    mat2: &ArrayD<A>,
+    beta: f32,
+    alpha: f32,
+) -> Result<ArrayD<A>, MatmulError>
+where
+    A: HalfFloat,
+{
+    let product = addmm(&upcast(input), &upcast(mat1), &upcast(mat2), beta, alpha)?;
+    Ok(downcast(&product))
+}
+
+fn baddbmm_impl<A>(
+    input: Option<&ArrayD<A>>,
+    batch1: &ArrayD<A>,
+    batch2: &ArrayD<A>,
+    beta: A,
+    alpha: A,
+) -> Result<ArrayD<A>, MatmulError>
+where
+    A: LinalgScalar + Send + Sync,
+{
+    let b1 = as_dims::<A, Ix3>(batch1, "batch1")?;
+    let b2 = as_dims::<A, Ix3>(batch2, "batch2")?;
+    let (batches, n, m) = b1.dim();
+    let (batches2, m2, p) = b2.dim();
+    if batches != batches2 || m != m2 {
+        return Err(MatmulError::InnerDimensionMismatch {
+            left_shape: batch1.shape().to_vec(),
+            right_shape: batch2.shape().to_vec(),
+        });
+    }
+
+    let product_shape = [batches, n, p];
+    let (mut out, beta) = match input {
+        Some(input) => (initial_output(input, &product_shape, beta)?, beta),
+        None => (ArrayD::zeros(IxDyn(&product_shape)), A::zero()),
+    };
+    let mut out3 = out
+        .view_mut()
+        .into_dimensionality::<Ix3>()
+        .expect("output was created with three dimensions");
+    Zip::from(out3.outer_iter_mut())
+        .and(b1.outer_iter())
+        .and(b2.outer_iter())
+        .par_for_each(|mut c, a, b| general_mat_mul(alpha, &a, &b, beta, &mut c));
+    Ok(out)
+}
+
+/// Returns `input` broadcast to `product_shape` when `beta` is non-zero, zeros otherwise.
+fn initial_output<A>(
+    input: &ArrayD<A>,
+    product_shape: &[usize],
+    beta: A,
+) -> Result<ArrayD<A>, MatmulError>
+where
+    A: LinalgScalar,
+{
+    let broadcast =
+        input
+            .broadcast(IxDyn(product_shape))
+            .ok_or_else(|| MatmulError::InputBroadcast {
+                input: input.shape().to_vec(),
+                product: product_shape.to_vec(),
+            })?;
+    if beta.is_zero() {
+        Ok(ArrayD::zeros(IxDyn(product_shape)))
+    } else {
+        Ok(broadcast.to_owned())
+    }
+}
+
+/// Views `array` with a fixed number of dimensions, reporting `argument` on mismatch.
+fn as_dims<'a, A, D>(
+    array: &'a ArrayD<A>,
+    argument: &'static str,
+) -> Result<ArrayView<'a, A, D>, MatmulError>
+where
+    D: Dimension,
+{
+    array
+        .view()
+        .into_dimensionality::<D>()
+        .map_err(|_| MatmulError::WrongDimensions {
+            argument,
+            expected: D::NDIM.unwrap_or(0),
+            shape: array.shape().to_vec(),
+        })
+}
+
+/// Returns the sizes of the last two axes, which hold the matrices.
+fn last_two<A>(view: &ArrayViewD<'_, A>) -> (usize, usize) {
+    let shape = view.shape();
+    (shape[shape.len() - 2], shape[shape.len() - 1])
+}
+
+/// Broadcasts two shapes against each other, aligning them on the right.
+pub(crate) fn broadcast_shapes(left: &[usize], right: &[usize]) -> Option<Vec<usize>> {
+    let ndim = left.len().max(right.len());
+    let mut shape = Vec::with_capacity(ndim);
+    for i in 0..ndim {
+        let l = if i + left.len() >= ndim {
+            left[i + left.len() - ndim]
+        } else {
+            1
+        };
+        let r = if i + right.len() >= ndim {
+            right[i + right.len() - ndim]
+        } else {
+            1
+        };
+        shape.push(match (l, r) {
+            (l, r) if l == r => l,
+            (1, r) => r,
+            (l, 1) => l,
+            _ => return None,
+        });
+    }
+    Some(shape)
+}
+
+/// Broadcasts the batch axes of `view` to `batch` and flattens them into one leading axis.
+fn stack_matrices<A>(view: &ArrayViewD<'_, A>, batch: &[usize], matrix: (usize, usize)) -> Array3<A>
+where
+    A: Clone,
+{
+    let mut full_shape = batch.to_vec();
+    full_shape.extend([matrix.0, matrix.1]);
+    let batch_len: usize = batch.iter().product();
+    view.broadcast(IxDyn(&full_shape))
+        .expect("batch shape was computed by broadcasting")
+        .as_standard_layout()
+        .into_owned()
+        .into_shape((batch_len, matrix.0, matrix.1))
+        .expect("broadcast operand has batch * rows * cols elements")
+}
+
+/// Runs `c[i] = alpha * a[i] @ b[i] + beta * c[i]` for every batch in parallel.
+fn batched_gemm<A>(alpha: A, a: ArrayView3<'_, A>, b: ArrayView3<'_, A>, beta: A, c: &mut Array3<A>)
+where
+    A: LinalgScalar + Send + Sync,
+{
+    Zip::from(c.outer_iter_mut())
+        .and(a.outer_iter())
+        .and(b.outer_iter())
+        .par_for_each(|mut c, a, b| general_mat_mul(alpha, &a, &b, beta, &mut c));
+}
@@ -1,18 +1,29 @@
-use ndarray::{Array, ArrayBase, ArrayView, Axis, Data, Dimension, Ix1, IxDyn, RemoveAxis};
-use std::cmp::Ordering;
+use super::argmax::first_max;
+use super::dim::{is_reduced_shape, reduced_shape};
+use ndarray::{Array, ArrayBase, ArrayView, ArrayViewMutD, Axis, Data, Dimension, Ix1, RemoveAxis};
 use std::fmt::Debug;
+use thiserror::Error;
 
 // filepath: /media/john/Tertiary/Projects/ML/RustOps/src/functions/max.rs
 
 /// Error types for the max function.
-#[derive(Debug, PartialEq)]
+#[derive(Error, Debug, PartialEq)]
 pub enum MaxError {
     /// The input array is empty (when finding the overall max).
+    #[error("Cannot find the maximum of an empty array")]
     EmptyInput,
     /// The specified dimension has size 0.
+    #[error("Dimension {0} has size 0")]
     ZeroDimSize(usize), // Contains the axis index
     /// The specified axis index is out of bounds.
+    #[error("Axis {0} is out of bounds")]
     InvalidAxis(usize), // Contains the axis index
+    /// An output of [`max_into`] does not have the reduced shape.
+    #[error("Output has shape {found:?}, expected {expected:?}")]
+    OutputShapeMismatch {
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
 }
 
 /// Finds the maximum values and their indices along a given dimension.
@@ -34,7 +45,7 @@ pub enum MaxError {
 /// # Type Parameters
 ///
 /// * `A`: The element type of the array. Must implement `PartialOrd` for comparison and `Copy`
-///        for efficient processing within closures.
+///   for efficient processing within closures.
 /// * `S`: The data storage type (e.g., `OwnedRepr<A>`, `ViewRepr<&'a A>`).
 /// * `D`: The dimension type of the input array.
 ///
@@ -45,10 +56,11 @@ pub enum MaxError {
 /// from `usize` to `i64` could theoretically panic on 32-bit systems if the index exceeds
 /// `i64::MAX`, although this is highly unlikely for typical array dimensions.
 ///
-/// # NaN Handling Note
+/// # Ties and NaN
 ///
-/// This implementation uses `partial_cmp`. If the input contains NaN values, the behavior might
-/// differ slightly from PyTorch's `max`, which has specific NaN propagation/handling rules.
+/// Like PyTorch, the index of the first maximal value is returned, and NaN propagates: a lane
+/// containing NaN has NaN as its maximum, at the index of its first NaN.
+#[allow(clippy::type_complexity)]
 pub fn max<A, S, D>(
     input: &ArrayBase<S, D>,
     dim: usize,
@@ -70,29 +82,70 @@ where
         return Err(MaxError::ZeroDimSize(dim));
     }
 
-    if input.len() == 0 {
-        return Err(MaxError::EmptyInput);
-    }
-
-    // Create arrays to hold the max values and indices
+    // Other axes may be empty, which simply gives empty results
     let max_values = input.map_axis(axis, |view: ArrayView<A, Ix1>| {
-        view.fold(
-            view[0],
-            |max_val, &val| {
-                if val > max_val { val } else { max_val }
-            },
-        )
+        first_max(view.iter().copied()).unwrap().1 // Safe due to dim_size > 0 check
     });
 
     let max_indices = input.map_axis(axis, |view: ArrayView<A, Ix1>| {
-        let (idx, _) = view
-            .iter()
-            .copied()
-            .enumerate()
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(Ordering::Less))
-            .unwrap(); // Safe due to dim_size > 0 check
-        idx as i64
+        first_max(view.iter().copied()).unwrap().0 as i64
     });
 
     Ok((max_values, max_indices))
 }
+
+/// Like [`max`], but writes the maximum values and their indices into `values` and `indices`
+/// instead of allocating.
+///
+/// # Arguments
+///
+/// * `values`: Where to write the maximum values.
+/// * `indices`: Where to write the indices (as `i64`) of the maximum values.
+/// * `input`: The input array.
+/// * `dim`: The dimension along which to find the maximum values and indices.
+///
+/// Both outputs must have the shape of `input` with `dim` removed, or kept with size 1 as
+/// `keepdim=True` would give.
+///
+/// # Returns
+///
+/// * `Ok(())`: Once both outputs are written.
+/// * `Err(MaxError)`: If `dim` is invalid, has size 0, or an output has the wrong shape.
+pub fn max_into<A, S, D>(
+    values: &mut ArrayViewMutD<'_, A>,
+    indices: &mut ArrayViewMutD<'_, i64>,
+    input: &ArrayBase<S, D>,
+    dim: usize,
+) -> Result<(), MaxError>
+where
+    A: PartialOrd + Copy,
+    S: Data<Elem = A>,
+    D: Dimension + RemoveAxis,
+{
+    if dim >= input.ndim() {
+        return Err(MaxError::InvalidAxis(dim));
+    }
+    if input.shape()[dim] == 0 {
+        return Err(MaxError::ZeroDimSize(dim));
+    }
+    for found in [values.shape(), indices.shape()] {
+        if !is_reduced_shape(input.shape(), dim, found) {
+            return Err(MaxError::OutputShapeMismatch {
+                expected: reduced_shape(input.shape(), dim),
+                found: found.to_vec(),
+            });
+        }
+    }
+
+    // Lanes along `dim` come in the logical order of the remaining axes, like the outputs
+    for ((value, index), lane) in values
+        .iter_mut()
+        .zip(indices.iter_mut())
+        .zip(input.lanes(Axis(dim)))
+    {
+        let (idx, max_value) = first_max(lane.iter().copied()).unwrap(); // Safe due to dim_size > 0 check
+        *value = max_value;
+        *index = idx as i64;
+    }
+    Ok(())
+}
@@ -2,13 +2,24 @@ pub mod abs;
 pub mod argmax;
 pub mod einsum;
 pub mod expand;
+pub mod flip;
 pub mod gather;
+pub mod index_select;
+pub mod matmul;
 pub mod max;
 pub mod ones;
+pub mod pad;
 pub mod rearrange;
 pub mod reduce;
+pub mod repeat_interleave;
 pub mod reshape;
+pub mod roll;
 pub mod scatter;
 pub mod slicing;
 pub mod sort;
+pub mod tile;
 pub mod transpose;
+pub mod tril;
+pub mod upcast;
+
+pub(crate) mod dim;
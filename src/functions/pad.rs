@@ -0,0 +1,168 @@
+use ndarray::{Array, ArrayD, IxDyn};
+use thiserror::Error;
+
+/// Padding modes supported by [`pad`], following `torch.nn.functional.pad`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PadMode<A> {
+    /// Pads with a constant value.
+    Constant(A),
+    /// Pads with the reflection of the input, not repeating the edge element.
+    Reflect,
+    /// Pads by repeating the edge element.
+    Replicate,
+    /// Pads by wrapping around to the opposite edge.
+    Circular,
+}
+
+impl<A> PadMode<A> {
+    fn name(&self) -> &'static str {
+        match self {
+            PadMode::Constant(_) => "constant",
+            PadMode::Reflect => "reflect",
+            PadMode::Replicate => "replicate",
+            PadMode::Circular => "circular",
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PadError {
+    #[error("Padding was given for {pads} dimensions, but the input only has {ndim}")]
+    TooManyPadDimensions { pads: usize, ndim: usize },
+
+    #[error(
+        "Padding ({before}, {after}) on dimension {dim} of size {size} gives a negative output size"
+    )]
+    NegativeOutputSize {
+        dim: usize,
+        size: usize,
+        before: isize,
+        after: isize,
+    },
+
+    #[error(
+        "Padding ({before}, {after}) is too large for {mode} mode on dimension {dim} of size {size}"
+    )]
+    PaddingTooLarge {
+        mode: &'static str,
+        dim: usize,
+        size: usize,
+        before: isize,
+        after: isize,
+    },
+}
+
+/// Pads the trailing dimensions of `input`.
+///
+/// Mimics `torch.nn.functional.pad(input, pad, mode, value)`. The padding is given as one
+/// `(before, after)` pair per dimension starting from the **last** dimension, so `&[(1, 2)]`
+/// pads only the last dimension and `&[(1, 1), (2, 2)]` pads the last two. Negative amounts
+/// crop elements from that side instead.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `pads`: `(before, after)` amounts, starting from the last dimension.
+/// * `mode`: How the padded region is filled.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The padded array.
+/// * `Err(PadError)`: If there are more pad pairs than dimensions, the output size would be
+///   negative, or the padding is too large for the chosen mode.
+///
+/// # Mode Constraints
+///
+/// As in PyTorch, `Reflect` requires each pad to be smaller than the dimension size,
+/// `Circular` requires it to be at most the dimension size, and `Reflect`/`Replicate`
+/// require the padded dimension to be non-empty.
+pub fn pad<A>(
+    input: &ArrayD<A>,
+    pads: &[(isize, isize)],
+    mode: PadMode<A>,
+) -> Result<ArrayD<A>, PadError>
+where
+    A: Clone,
+{
+    let ndim = input.ndim();
+    if pads.len() > ndim {
+        return Err(PadError::TooManyPadDimensions {
+            pads: pads.len(),
+            ndim,
+        });
+    }
+
+    // For every dimension, map each output position to a source position.
+    // `None` marks positions filled with the constant value.
+    let mut source_maps: Vec<Vec<Option<usize>>> = input
+        .shape()
+        .iter()
+        .map(|&size| (0..size).map(Some).collect())
+        .collect();
+
+    for (i, &(before, after)) in pads.iter().enumerate() {
+        let dim = ndim - 1 - i;
+        let size = input.shape()[dim];
+        let out_size = size as isize + before + after;
+        if out_size < 0 {
+            return Err(PadError::NegativeOutputSize {
+                dim,
+                size,
+                before,
+                after,
+            });
+        }
+
+        let too_large = match mode {
+            PadMode::Constant(_) => false,
+            PadMode::Reflect => before >= size as isize || after >= size as isize,
+            PadMode::Replicate => size == 0 && (before > 0 || after > 0),
+            PadMode::Circular => before > size as isize || after > size as isize,
+        };
+        if too_large {
+            return Err(PadError::PaddingTooLarge {
+                mode: mode.name(),
+                dim,
+                size,
+                before,
+                after,
+            });
+        }
+
+        source_maps[dim] = (0..out_size)
+            .map(|o| source_position(o - before, size, &mode))
+            .collect();
+    }
+
+    let out_shape: Vec<usize> = source_maps.iter().map(|m| m.len()).collect();
+    let mut source_index = vec![0; ndim];
+    Ok(Array::from_shape_fn(IxDyn(&out_shape), |idx| {
+        for (axis, slot) in source_index.iter_mut().enumerate() {
+            match source_maps[axis][idx[axis]] {
+                Some(p) => *slot = p,
+                None => {
+                    if let PadMode::Constant(value) = &mode {
+                        return value.clone();
+                    }
+                    unreachable!("only constant padding leaves unmapped positions");
+                }
+            }
+        }
+        input[&source_index[..]].clone()
+    }))
+}
+
+/// Maps a (possibly out-of-range) position `p` along a dimension of `size` to the source
+/// position for the given mode. Sizes have already been validated against the mode.
+fn source_position<A>(p: isize, size: usize, mode: &PadMode<A>) -> Option<usize> {
+    let n = size as isize;
+    if (0..n).contains(&p) {
+        return Some(p as usize);
+    }
+    match mode {
+        PadMode::Constant(_) => None,
+        PadMode::Reflect => Some(if p < 0 { -p } else { 2 * (n - 1) - p } as usize),
+        PadMode::Replicate => Some(p.clamp(0, n - 1) as usize),
+        PadMode::Circular => Some(p.rem_euclid(n) as usize),
+    }
+}
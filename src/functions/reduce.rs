@@ -1,6 +1,7 @@
-use crate::functions::einsum::einsum_ndarray_dyn;
+use crate::functions::einsum::{EinsumError, EinsumPlan, einsum_ndarray_dyn};
 use crate::functions::ones::ones;
-use ndarray::{Array, ArrayD, Axis, Dimension, IntoDimension, IxDyn};
+use crate::functions::upcast::{HalfFloat, downcast, upcast};
+use ndarray::{Array, ArrayD, ArrayViewD, ArrayViewMutD, Axis, Dimension, IntoDimension, IxDyn};
 
 /// Performs a reduction operation similar to einsum but with an array of ones.
 ///
@@ -12,7 +13,7 @@ use ndarray::{Array, ArrayD, Axis, Dimension, IntoDimension, IxDyn};
 /// # Returns
 ///
 /// A reduced array according to the specified equation.
-pub fn reduce(input: &ArrayD<f32>, equation: &str) -> Result<ArrayD<f32>, &'static str> {
+pub fn reduce(input: &ArrayD<f32>, equation: &str) -> Result<ArrayD<f32>, EinsumError> {
     // Create an array of ones with the same shape as the input
     let ones_array = ones(input.shape());
 
@@ -20,6 +21,53 @@ pub fn reduce(input: &ArrayD<f32>, equation: &str) -> Result<ArrayD<f32>, &'stat
     einsum_ndarray_dyn(equation, &tensors)
 }
 
+/// Like [`reduce`], but writes the result into `out` instead of allocating.
+///
+/// The ones operand is a broadcast view of a single element, so no input-sized array is
+/// created. The equation is parsed on every call, like [`einsum_into`](super::einsum::einsum_into).
+///
+/// # Arguments
+///
+/// * `out` - Where to write the result. Must have the shape of the result; any layout works.
+/// * `input` - The input array to reduce.
+/// * `equation` - The einsum-like equation representing the reduction pattern.
+///
+/// # Returns
+///
+/// `Ok(())` once `out` is written, or an `EinsumError` if the equation does not match `input`
+/// or `out`.
+pub fn reduce_into(
+    out: &mut ArrayViewMutD<'_, f32>,
+    input: &ArrayD<f32>,
+    equation: &str,
+) -> Result<(), EinsumError> {
+    let one = [1.0f32];
+    let one = ArrayViewD::from_shape(IxDyn(&[]), &one).expect("one element fits a scalar");
+    let ones = one
+        .broadcast(input.shape())
+        .expect("a scalar broadcasts to any shape");
+    let mut plan = EinsumPlan::new(equation, &[input.shape(), input.shape()])?;
+    plan.execute_into(out, &[input.view(), ones])
+}
+
+/// [`reduce`] for half-precision input: sums in `f32` and rounds the result back to `A` once,
+/// so long reductions do not accumulate rounding error.
+///
+/// # Arguments
+///
+/// * `input` - The `f16` or `bf16` array to reduce.
+/// * `equation` - The einsum-like equation representing the reduction pattern.
+///
+/// # Returns
+///
+/// A reduced array according to the specified equation.
+pub fn reduce_upcast<A: HalfFloat>(
+    input: &ArrayD<A>,
+    equation: &str,
+) -> Result<ArrayD<A>, EinsumError> {
+    Ok(downcast(&reduce(&upcast(input), equation)?))
+}
+
 /// Helper function to create an ArrayD from a vector and shape
 pub fn array_from_shape_vec(shape: &[usize], data: Vec<f32>) -> ArrayD<f32> {
     Array::from_shape_vec(IxDyn(shape), data)
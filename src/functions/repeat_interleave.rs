@@ -0,0 +1,129 @@
+use crate::functions::dim::normalize_dim;
+use ndarray::{Array1, ArrayD, Axis};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RepeatInterleaveError {
+    #[error("Dimension index {dim} is out of bounds for array with {ndim} dimensions")]
+    InvalidDimension { dim: isize, ndim: usize },
+
+    #[error("repeats must be a 0-D or 1-D array, got {ndim} dimensions")]
+    InvalidRepeatsShape { ndim: usize },
+
+    #[error("repeats must have length 1 or {dim_size}, got {len}")]
+    RepeatsLengthMismatch { len: usize, dim_size: usize },
+
+    #[error("repeats can not be negative, got {value} at position {position}")]
+    NegativeRepeat { position: usize, value: i64 },
+}
+
+/// Repeats each element of `input` `repeats` times along `dim`.
+///
+/// Mimics `torch.repeat_interleave(input, repeats: int, dim)`. When `dim` is `None`, the input
+/// is flattened first and a 1-D array is returned.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `repeats`: How many times each element is repeated.
+/// * `dim`: The dimension along which to repeat. Negative values wrap around.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The repeated array.
+/// * `Err(RepeatInterleaveError)`: If `dim` is out of bounds.
+pub fn repeat_interleave<A>(
+    input: &ArrayD<A>,
+    repeats: usize,
+    dim: Option<isize>,
+) -> Result<ArrayD<A>, RepeatInterleaveError>
+where
+    A: Clone,
+{
+    let (source, axis) = resolve_source(input, dim)?;
+    let dim_size = source.shape()[axis];
+    let counts = vec![repeats; dim_size];
+    Ok(select_repeated(&source, axis, &counts))
+}
+
+/// Repeats each element of `input` along `dim` by the matching entry of `repeats`.
+///
+/// Mimics `torch.repeat_interleave(input, repeats: Tensor, dim)`. `repeats` must hold either
+/// a single count, which is broadcast to every element, or one count per element along `dim`.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `repeats`: A 0-D or 1-D array of non-negative repeat counts.
+/// * `dim`: The dimension along which to repeat. `None` flattens the input first.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The repeated array.
+/// * `Err(RepeatInterleaveError)`: If `repeats` has the wrong shape or a negative entry,
+///   or `dim` is out of bounds.
+pub fn repeat_interleave_tensor<A>(
+    input: &ArrayD<A>,
+    repeats: &ArrayD<i64>,
+    dim: Option<isize>,
+) -> Result<ArrayD<A>, RepeatInterleaveError>
+where
+    A: Clone,
+{
+    if repeats.ndim() > 1 {
+        return Err(RepeatInterleaveError::InvalidRepeatsShape {
+            ndim: repeats.ndim(),
+        });
+    }
+
+    let (source, axis) = resolve_source(input, dim)?;
+    let dim_size = source.shape()[axis];
+
+    let mut counts = Vec::with_capacity(repeats.len());
+    for (position, &value) in repeats.iter().enumerate() {
+        if value < 0 {
+            return Err(RepeatInterleaveError::NegativeRepeat { position, value });
+        }
+        counts.push(value as usize);
+    }
+
+    let counts = match counts.len() {
+        1 => vec![counts[0]; dim_size],
+        len if len == dim_size => counts,
+        len => return Err(RepeatInterleaveError::RepeatsLengthMismatch { len, dim_size }),
+    };
+
+    Ok(select_repeated(&source, axis, &counts))
+}
+
+/// Returns the array to repeat over (flattened when `dim` is `None`) and the resolved axis.
+fn resolve_source<A>(
+    input: &ArrayD<A>,
+    dim: Option<isize>,
+) -> Result<(ArrayD<A>, usize), RepeatInterleaveError>
+where
+    A: Clone,
+{
+    match dim {
+        None => Ok((input.iter().cloned().collect::<Array1<A>>().into_dyn(), 0)),
+        Some(dim) => {
+            let ndim = input.ndim();
+            let axis = normalize_dim(dim, ndim)
+                .ok_or(RepeatInterleaveError::InvalidDimension { dim, ndim })?;
+            Ok((input.clone(), axis))
+        }
+    }
+}
+
+/// Builds the repeated index list along `axis` and selects it.
+fn select_repeated<A>(source: &ArrayD<A>, axis: usize, counts: &[usize]) -> ArrayD<A>
+where
+    A: Clone,
+{
+    let indices: Vec<usize> = counts
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &count)| std::iter::repeat_n(i, count))
+        .collect();
+    source.select(Axis(axis), &indices)
+}
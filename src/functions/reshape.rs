@@ -1,14 +1,17 @@
-use ndarray::{Array, ArrayBase, Data, Dimension, IxDyn, ShapeBuilder};
+use ndarray::{Array, ArrayBase, ArrayViewMutD, Data, Dimension, IxDyn};
 use std::fmt::Debug;
+use thiserror::Error;
 
 // filepath: /media/john/Tertiary/Projects/ML/RustOps/src/functions/reshape.rs
 
 /// Error types for the reshape function.
-#[derive(Debug, PartialEq)]
+#[derive(Error, Debug, PartialEq)]
 pub enum ReshapeError {
     /// The new shape is incompatible with the input array's size.
+    #[error("The new shape is incompatible with the size of the input")]
     IncompatibleShape,
     /// The -1 (inferred dimension) appears more than once in the shape.
+    #[error("Only one dimension of the new shape can be inferred (-1)")]
     MultipleInferredDimensions,
 }
 
@@ -40,7 +43,48 @@ where
     S: Data<Elem = A>,
     D: Dimension,
 {
-    let original_size = input.len();
+    let new_shape = infer_shape(input.len(), shape)?;
+
+    // Perform the reshape. `iter` visits elements in logical (row-major) order whatever the
+    // memory layout, which `into_shape` would not do for permuted or strided inputs.
+    Ok(Array::from_shape_vec(
+        IxDyn(&new_shape),
+        input.iter().cloned().collect(),
+    )?)
+}
+
+/// Like [`reshape`], but copies the elements of `input` into `out`, whose shape is the new shape.
+///
+/// # Arguments
+///
+/// * `out`: Where to write the result. Must have as many elements as `input`; any layout works.
+/// * `input`: The input array.
+///
+/// # Returns
+///
+/// * `Ok(())`: Once `out` holds the elements of `input` in logical (row-major) order.
+/// * `Err(ReshapeError)`: If the element counts differ.
+pub fn reshape_into<A, S, D>(
+    out: &mut ArrayViewMutD<'_, A>,
+    input: &ArrayBase<S, D>,
+) -> Result<(), ReshapeError>
+where
+    A: Clone,
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    if out.len() != input.len() {
+        return Err(ReshapeError::IncompatibleShape);
+    }
+    for (target, value) in out.iter_mut().zip(input.iter()) {
+        target.clone_from(value);
+    }
+    Ok(())
+}
+
+/// Resolves a `torch.reshape` style shape, where one entry may be `-1`, for an array of `len`
+/// elements.
+pub(crate) fn infer_shape(len: usize, shape: &[i64]) -> Result<Vec<usize>, ReshapeError> {
     let mut new_shape = Vec::with_capacity(shape.len());
     let mut inferred_index = None;
     let mut known_size = 1;
@@ -70,22 +114,17 @@ where
         }
 
         // Infer the dimension
-        if original_size % known_size != 0 {
+        if !len.is_multiple_of(known_size) {
             return Err(ReshapeError::IncompatibleShape);
         }
 
-        new_shape[idx] = original_size / known_size;
-    } else if known_size != original_size {
+        new_shape[idx] = len / known_size;
+    } else if known_size != len {
         // If no dimension needs to be inferred, check that the total size matches
         return Err(ReshapeError::IncompatibleShape);
     }
 
-    // Perform the reshape
-    let reshaped = input.to_owned().into_shape(IxDyn(&new_shape))?;
-    Ok(Array::from_shape_vec(
-        IxDyn(&new_shape),
-        reshaped.iter().cloned().collect(),
-    )?)
+    Ok(new_shape)
 }
 
 // Provide a conversion from ndarray's ShapeError to our ReshapeError
@@ -0,0 +1,91 @@
+use crate::functions::dim::normalize_dim;
+use ndarray::{ArrayD, Axis, IxDyn, Slice, concatenate};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RollError {
+    #[error("Dimension index {dim} is out of bounds for array with {ndim} dimensions")]
+    InvalidDimension { dim: isize, ndim: usize },
+
+    #[error("shifts and dims must have the same length (shifts: {shifts}, dims: {dims})")]
+    LengthMismatch { shifts: usize, dims: usize },
+
+    #[error("A single shift is required when no dims are given, got {shifts}")]
+    FlattenedShiftCount { shifts: usize },
+
+    #[error("Internal error during array creation: {0}")]
+    InternalShapeError(String),
+}
+
+/// Rolls the array along the given dimensions, wrapping elements around the edges.
+///
+/// Mimics the behavior of `torch.roll(input, shifts, dims)`. If `dims` is empty the array is
+/// flattened, rolled by the single entry of `shifts`, and restored to its original shape.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `shifts`: The number of places to shift for each entry of `dims`. Negative shifts roll
+///   towards lower indices.
+/// * `dims`: The dimensions to roll. Negative values wrap around.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: A new array with the same shape as `input`.
+/// * `Err(RollError)`: If the arguments are inconsistent or a dimension is out of bounds.
+pub fn roll<A>(input: &ArrayD<A>, shifts: &[isize], dims: &[isize]) -> Result<ArrayD<A>, RollError>
+where
+    A: Clone,
+{
+    if dims.is_empty() {
+        if shifts.len() != 1 {
+            return Err(RollError::FlattenedShiftCount {
+                shifts: shifts.len(),
+            });
+        }
+        let flat: ArrayD<A> = input
+            .iter()
+            .cloned()
+            .collect::<ndarray::Array1<A>>()
+            .into_dyn();
+        let rolled = roll_axis(&flat, shifts[0], 0)?;
+        return rolled
+            .into_shape(IxDyn(input.shape()))
+            .map_err(|e| RollError::InternalShapeError(e.to_string()));
+    }
+
+    if shifts.len() != dims.len() {
+        return Err(RollError::LengthMismatch {
+            shifts: shifts.len(),
+            dims: dims.len(),
+        });
+    }
+
+    let ndim = input.ndim();
+    let mut output = input.clone();
+    for (&shift, &dim) in shifts.iter().zip(dims) {
+        let d = normalize_dim(dim, ndim).ok_or(RollError::InvalidDimension { dim, ndim })?;
+        output = roll_axis(&output, shift, d)?;
+    }
+    Ok(output)
+}
+
+/// Rolls a single axis by concatenating its tail in front of its head.
+fn roll_axis<A>(input: &ArrayD<A>, shift: isize, axis: usize) -> Result<ArrayD<A>, RollError>
+where
+    A: Clone,
+{
+    let size = input.shape()[axis];
+    if size == 0 {
+        return Ok(input.clone());
+    }
+    let shift = shift.rem_euclid(size as isize);
+    if shift == 0 {
+        return Ok(input.clone());
+    }
+
+    let split = size as isize - shift;
+    let tail = input.slice_axis(Axis(axis), Slice::from(split..));
+    let head = input.slice_axis(Axis(axis), Slice::from(..split));
+    concatenate(Axis(axis), &[tail, head]).map_err(|e| RollError::InternalShapeError(e.to_string()))
+}
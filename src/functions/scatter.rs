@@ -1,4 +1,4 @@
-use ndarray::{Array, ArrayD, Axis, Dimension, IxDyn, NdIndex};
+use ndarray::{Array, ArrayD, ArrayViewMutD, Axis, Dimension, IxDyn, NdIndex};
 use num_traits::{NumCast, Zero};
 use std::fmt::Debug;
 // We don't need MaybeUninit here as we modify an existing array.
@@ -104,6 +104,33 @@ pub fn scatter<T, Ix>(
     index: &ArrayD<Ix>,
     source: &ArrayD<T>,
 ) -> Result<(), ScatterError>
+where
+    T: Clone + Debug,
+    Ix: NumCast + PartialOrd + Zero + Copy + Debug,
+{
+    scatter_into(&mut target.view_mut(), dim, index, source)
+}
+
+/// Like [`scatter`], but writes into a mutable view, so the target can be a slice of a larger
+/// buffer. Does not allocate unless it fails or the arrays have more than four dimensions.
+///
+/// # Arguments
+///
+/// * `target`: The view to scatter values into. Modified in place.
+/// * `dim`: The dimension along which to scatter. Negative values wrap around.
+/// * `index`: Where in `target` to write each value of `source`, as for [`scatter`].
+/// * `source`: The values to scatter into `target`.
+///
+/// # Returns
+///
+/// * `Ok(())`: Indicates the scatter operation completed successfully.
+/// * `Err(ScatterError)`: An error indicating why the scatter operation failed.
+pub fn scatter_into<T, Ix>(
+    target: &mut ArrayViewMutD<'_, T>,
+    dim: isize,
+    index: &ArrayD<Ix>,
+    source: &ArrayD<T>,
+) -> Result<(), ScatterError>
 where
     T: Clone + Debug,
     Ix: NumCast + PartialOrd + Zero + Copy + Debug,
@@ -249,8 +276,9 @@ where
         }
 
         // --- Prepare for iteration ---
-        // Reusable buffer for constructing target coordinates
-        let mut target_coords_buffer = vec![0; target_ndim];
+        // Reusable buffer for constructing target coordinates, stored inline for up to four
+        // dimensions
+        let mut target_coords_buffer = IxDyn::zeros(target_ndim);
 
         // --- Iterate and Scatter ---
         // Iterate through the indices of the `index` array (and `source` array)
@@ -312,19 +340,20 @@ where
                 )));
             }
             // Copy current multi-dimensional index from the slice
-            target_coords_buffer.copy_from_slice(current_coords_slice);
+            target_coords_buffer
+                .slice_mut()
+                .copy_from_slice(current_coords_slice);
             // Replace the coordinate at `dim_usize` with the scatter index
             target_coords_buffer[dim_usize] = scatter_idx_usize;
 
             // 6. Get a mutable reference to the element in the `target` array
-            let target_elem_ref = match target.get_mut(&target_coords_buffer[..]) {
-                // Pass slice from buffer
+            let target_elem_ref = match target.get_mut(&target_coords_buffer) {
                 Some(elem_ref) => elem_ref,
                 None => {
                     // This should be unreachable if bounds checks passed
                     return Err(ScatterError::InternalError(format!(
                         "Internal error: Failed to get mutable reference from target at {:?} despite bounds checks.",
-                        target_coords_buffer
+                        target_coords_buffer.slice()
                     )));
                 }
             };
@@ -1,4 +1,6 @@
-use ndarray::{Array, ArrayD, Axis, IxDyn, NdFloat, SliceInfo, SliceInfoElem};
+use ndarray::{
+    Array, ArrayD, ArrayViewMutD, Axis, IxDyn, NdFloat, Slice, SliceInfo, SliceInfoElem,
+};
 use ndarray::{ShapeError, prelude::*};
 use std::convert::TryFrom;
 
@@ -140,3 +142,75 @@ where
     // 5. Convert view to owned and return Ok
     Ok(view.to_owned())
 }
+
+/// Like [`slice_last_dim`], but writes the slice into `out` instead of allocating.
+///
+/// # Arguments
+///
+/// * `out` - Where to write the result. Must have the shape of `input` with the last dimension
+///   reduced to 1 (or 0 if it is empty).
+/// * `input` - The input ArrayD to slice. Must have at least 3 dimensions.
+///
+/// # Returns
+///
+/// `Ok(())` once `out` is written, or an error if the input tensor has fewer than 3 dimensions
+/// or `out` has the wrong shape.
+pub fn slice_last_dim_into<A>(
+    out: &mut ArrayViewMutD<'_, A>,
+    input: &ArrayD<A>,
+) -> Result<(), &'static str>
+where
+    A: Clone,
+{
+    let ndim = input.ndim();
+    if ndim < 3 {
+        return Err("Input tensor must have at least 3 dimensions for slice '[:, :, -1:]'");
+    }
+
+    // Slicing a single axis needs no `SliceInfo`, which would have to be allocated
+    let last_dim_size = input.shape()[ndim - 1];
+    let view = input.slice_axis(
+        Axis(ndim - 1),
+        Slice::from(last_dim_size.saturating_sub(1)..),
+    );
+    if out.shape() != view.shape() {
+        return Err("Output shape does not match the slice");
+    }
+    out.assign(&view);
+    Ok(())
+}
+
+/// Like [`slice_second_dim`], but writes the slice into `out` instead of allocating.
+///
+/// # Arguments
+///
+/// * `out` - Where to write the result. Must have the shape of `input` with the second
+///   dimension reduced to `amount` (or its size, if smaller).
+/// * `input` - The input ArrayD to slice. Must have at least 2 dimensions.
+/// * `amount` - The number of elements to take from the second dimension.
+///
+/// # Returns
+///
+/// `Ok(())` once `out` is written, or an error if the input tensor has fewer than 2 dimensions
+/// or `out` has the wrong shape.
+pub fn slice_second_dim_into<A>(
+    out: &mut ArrayViewMutD<'_, A>,
+    input: &ArrayD<A>,
+    amount: usize,
+) -> Result<(), &'static str>
+where
+    A: Clone,
+{
+    let ndim = input.ndim();
+    if ndim < 2 {
+        return Err("Input tensor must have at least 2 dimensions for slice '[:, :amount]'");
+    }
+
+    let effective_amount = std::cmp::min(amount, input.shape()[1]);
+    let view = input.slice_axis(Axis(1), Slice::from(0..effective_amount));
+    if out.shape() != view.shape() {
+        return Err("Output shape does not match the slice");
+    }
+    out.assign(&view);
+    Ok(())
+}
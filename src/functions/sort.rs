@@ -1,4 +1,4 @@
-use ndarray::{ArrayD, Axis, IxDyn};
+use ndarray::{ArrayD, Axis};
 use std::cmp::Ordering;
 
 /// Sorts each slice along the last dimension of the ArrayD in place.
@@ -12,19 +12,19 @@ use std::cmp::Ordering;
 ///
 /// * `A` - The element type of the array. Must implement `PartialOrd` and `Clone`.
 ///
+/// # NaN Handling
+///
+/// Like `torch.sort`, NaN values are ordered after every other value.
+///
 /// # Panics
 ///
-/// Panics if the array is 0-dimensional.
-/// Panics if `partial_cmp` returns `None` during sorting (e.g., comparing NaN values
-/// without a specific NaN handling strategy). The current implementation treats
-/// elements causing `None` as equal for sorting purposes.
+/// Does not panic; a 0-dimensional array is left unchanged.
 pub fn sort_last_dim<A>(arr: &mut ArrayD<A>)
 where
     A: PartialOrd + Clone,
 {
     let ndim = arr.ndim();
     if ndim == 0 {
-        eprintln!("Warning: Cannot sort a 0-dimensional array.");
         return;
     }
 
@@ -43,21 +43,26 @@ where
         // Each lane is a contiguous view along the last axis
         if let Some(slice) = lane.as_slice_mut() {
             // Sort the slice using partial_cmp
-            slice.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            slice.sort_unstable_by(nan_last);
         } else {
             // Fallback for non-contiguous views
-            eprintln!(
-                "Warning: Could not get mutable slice for lane (shape={:?}, strides={:?}). Using slower copy-based sort.",
-                lane.shape(),
-                lane.strides()
-            );
-
             let mut temp_vec: Vec<A> = lane.iter().cloned().collect();
-            temp_vec.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+            temp_vec.sort_unstable_by(nan_last);
 
-            for (view_elem, sorted_elem) in lane.iter_mut().zip(temp_vec.into_iter()) {
+            for (view_elem, sorted_elem) in lane.iter_mut().zip(temp_vec) {
                 *view_elem = sorted_elem;
             }
         }
     }
 }
+
+/// A total order for `PartialOrd` values that places NaN (the only value not equal to itself)
+/// last. Treating NaN as equal to everything is not a total order, which sorting may panic on.
+fn nan_last<A: PartialOrd>(a: &A, b: &A) -> Ordering {
+    match (a.partial_cmp(a).is_none(), b.partial_cmp(b).is_none()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+    }
+}
@@ -0,0 +1,56 @@
+use ndarray::{Array, ArrayD, IxDyn};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TileError {
+    #[error("Internal error during array creation: {0}")]
+    InternalShapeError(String),
+}
+
+/// Constructs an array by repeating `input` the number of times given by `reps`.
+///
+/// Mimics the behavior of `torch.tile(input, reps)`. If `reps` has fewer entries than `input`
+/// has dimensions, it is padded with leading ones. If it has more, `input` is treated as having
+/// extra leading dimensions of size one.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `reps`: The number of repetitions along each dimension.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The tiled array, with shape `input.shape()[i] * reps[i]`.
+/// * `Err(TileError)`: If the intermediate reshape fails.
+pub fn tile<A>(input: &ArrayD<A>, reps: &[usize]) -> Result<ArrayD<A>, TileError>
+where
+    A: Clone,
+{
+    let ndim = input.ndim().max(reps.len());
+
+    // Align input shape and reps on the trailing dimensions
+    let mut in_shape = vec![1; ndim - input.ndim()];
+    in_shape.extend_from_slice(input.shape());
+    let mut full_reps = vec![1; ndim - reps.len()];
+    full_reps.extend_from_slice(reps);
+
+    // `into_shape` only accepts contiguous layouts, so copy a transposed or sliced input first
+    let aligned = input
+        .as_standard_layout()
+        .into_shape(IxDyn(&in_shape))
+        .map_err(|e| TileError::InternalShapeError(e.to_string()))?;
+
+    let out_shape: Vec<usize> = in_shape
+        .iter()
+        .zip(&full_reps)
+        .map(|(&size, &rep)| size * rep)
+        .collect();
+
+    let mut source_index = vec![0; ndim];
+    Ok(Array::from_shape_fn(IxDyn(&out_shape), |idx| {
+        for (axis, slot) in source_index.iter_mut().enumerate() {
+            *slot = idx[axis] % in_shape[axis];
+        }
+        aligned[&source_index[..]].clone()
+    }))
+}
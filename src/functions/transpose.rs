@@ -1,4 +1,4 @@
-use ndarray::{ArrayD, Axis, IxDyn};
+use ndarray::{ArrayD, ArrayViewMutD};
 
 /// Transposes an ndarray ArrayD by swapping the specified dimensions.
 ///
@@ -11,7 +11,7 @@ use ndarray::{ArrayD, Axis, IxDyn};
 /// # Returns
 ///
 /// A new transposed ArrayD
-pub fn transpose_dims(arr: &ArrayD<f32>, dim1: usize, dim2: usize) -> ArrayD<f32> {
+pub fn transpose_dims<A: Clone>(arr: &ArrayD<A>, dim1: usize, dim2: usize) -> ArrayD<A> {
     let ndim = arr.ndim();
     let mut axes: Vec<usize> = (0..ndim).collect();
 
@@ -29,10 +29,49 @@ pub fn transpose_dims(arr: &ArrayD<f32>, dim1: usize, dim2: usize) -> ArrayD<f32
 
     // 1. Create a view with permuted axes. This doesn't copy data yet,
     //    just changes how the existing data is interpreted (shape and strides).
-    let permuted_view = arr.clone().permuted_axes(axes);
+    let permuted_view = arr.view().permuted_axes(axes);
 
-    // 2. Create a new owned array from the view. `.to_owned()` on a view
-    //    creates a new array with its own data buffer, arranged in the
-    //    standard (C-order) layout corresponding to the view's shape.
-    permuted_view.as_standard_layout().to_owned()
+    // 2. Create a new owned array from the view, arranged in the standard
+    //    (C-order) layout corresponding to the view's shape.
+    permuted_view.as_standard_layout().into_owned()
+}
+
+/// Like [`transpose_dims`], but writes the transposed array into `out` instead of allocating.
+///
+/// # Arguments
+///
+/// * `out` - Where to write the result. Must have the shape of `arr` with `dim1` and `dim2`
+///   swapped
+/// * `arr` - The input array to transpose
+/// * `dim1` - First dimension to swap
+/// * `dim2` - Second dimension to swap
+///
+/// # Panics
+///
+/// If a dimension is out of bounds or `out` has the wrong shape.
+pub fn transpose_dims_into<A: Clone>(
+    out: &mut ArrayViewMutD<'_, A>,
+    arr: &ArrayD<A>,
+    dim1: usize,
+    dim2: usize,
+) {
+    let ndim = arr.ndim();
+    if dim1 >= ndim || dim2 >= ndim {
+        panic!(
+            "Dimensions out of bounds: dim1={}, dim2={}, ndim={}",
+            dim1, dim2, ndim
+        );
+    }
+
+    // Swapping the axes of a view only swaps its shape and strides
+    let mut view = arr.view();
+    view.swap_axes(dim1, dim2);
+    if out.shape() != view.shape() {
+        panic!(
+            "Output shape {:?} does not match the transposed shape {:?}",
+            out.shape(),
+            view.shape()
+        );
+    }
+    out.assign(&view);
 }
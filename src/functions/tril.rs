@@ -0,0 +1,75 @@
+use ndarray::ArrayD;
+use num_traits::Zero;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TriangularError {
+    #[error("Input must have at least 2 dimensions, got {ndim}")]
+    TooFewDimensions { ndim: usize },
+}
+
+/// Returns the lower triangular part of the last two dimensions of `input`.
+///
+/// Mimics `torch.tril(input, diagonal)`. Elements above the selected diagonal are set to zero;
+/// leading dimensions are treated as a batch.
+///
+/// # Arguments
+///
+/// * `input`: The input array with at least 2 dimensions.
+/// * `diagonal`: The diagonal to consider. `0` is the main diagonal, positive values include
+///   diagonals above it and negative values exclude diagonals below it.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: A new array with the same shape as `input`.
+/// * `Err(TriangularError)`: If `input` has fewer than 2 dimensions.
+pub fn tril<A>(input: &ArrayD<A>, diagonal: isize) -> Result<ArrayD<A>, TriangularError>
+where
+    A: Clone + Zero,
+{
+    mask_triangle(input, |row, col| col - row <= diagonal)
+}
+
+/// Returns the upper triangular part of the last two dimensions of `input`.
+///
+/// Mimics `torch.triu(input, diagonal)`. Elements below the selected diagonal are set to zero;
+/// leading dimensions are treated as a batch.
+///
+/// # Arguments
+///
+/// * `input`: The input array with at least 2 dimensions.
+/// * `diagonal`: The diagonal to consider. `0` is the main diagonal, positive values exclude
+///   diagonals above it and negative values include diagonals below it.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: A new array with the same shape as `input`.
+/// * `Err(TriangularError)`: If `input` has fewer than 2 dimensions.
+pub fn triu<A>(input: &ArrayD<A>, diagonal: isize) -> Result<ArrayD<A>, TriangularError>
+where
+    A: Clone + Zero,
+{
+    mask_triangle(input, |row, col| col - row >= diagonal)
+}
+
+/// Zeroes every element of the trailing matrices whose (row, col) position fails `keep`.
+fn mask_triangle<A, F>(input: &ArrayD<A>, keep: F) -> Result<ArrayD<A>, TriangularError>
+where
+    A: Clone + Zero,
+    F: Fn(isize, isize) -> bool,
+{
+    let ndim = input.ndim();
+    if ndim < 2 {
+        return Err(TriangularError::TooFewDimensions { ndim });
+    }
+
+    let mut output = input.clone();
+    for (idx, elem) in output.indexed_iter_mut() {
+        let row = idx[ndim - 2] as isize;
+        let col = idx[ndim - 1] as isize;
+        if !keep(row, col) {
+            *elem = A::zero();
+        }
+    }
+    Ok(output)
+}
@@ -0,0 +1,78 @@
+//! Half-precision element types and their conversion to and from `f32`.
+//!
+//! `half::f16` and `half::bf16` are plain storage types: they are `Copy` and `PartialOrd`, so
+//! the ops that only move or compare elements (gather, scatter, reshape, transpose, expand,
+//! slicing, max/argmax, sort) accept them directly, but they have no arithmetic that
+//! `LinalgScalar` or `NdFloat` could build on. Ops that accumulate therefore get `*_upcast`
+//! variants that widen the operands to `f32`, compute there and round the result back once,
+//! which is also how PyTorch runs half-precision matrix products and sums on the CPU.
+
+use half::{bf16, f16};
+use ndarray::{Array, ArrayBase, Data, Dimension};
+use std::fmt::Debug;
+
+/// A 16-bit float element that is computed on in `f32`.
+pub trait HalfFloat: Copy + PartialOrd + Debug + Send + Sync + 'static {
+    /// Widens the value to `f32`, which is exact.
+    fn to_f32(self) -> f32;
+
+    /// Rounds an `f32` to the nearest representable value.
+    fn from_f32(value: f32) -> Self;
+}
+
+impl HalfFloat for f16 {
+    fn to_f32(self) -> f32 {
+        f16::to_f32(self)
+    }
+
+    fn from_f32(value: f32) -> Self {
+        f16::from_f32(value)
+    }
+}
+
+impl HalfFloat for bf16 {
+    fn to_f32(self) -> f32 {
+        bf16::to_f32(self)
+    }
+
+    fn from_f32(value: f32) -> Self {
+        bf16::from_f32(value)
+    }
+}
+
+/// Widens every element of a half-precision array to `f32`, like `tensor.float()`.
+///
+/// # Arguments
+///
+/// * `input`: Array of `f16` or `bf16` elements, in any memory layout.
+///
+/// # Returns
+///
+/// An `f32` array of the same shape.
+pub fn upcast<A, S, D>(input: &ArrayBase<S, D>) -> Array<f32, D>
+where
+    A: HalfFloat,
+    S: Data<Elem = A>,
+    D: Dimension,
+{
+    input.mapv(A::to_f32)
+}
+
+/// Rounds every element of an `f32` array to half precision, like `tensor.half()` or
+/// `tensor.bfloat16()`.
+///
+/// # Arguments
+///
+/// * `input`: Array of `f32` elements.
+///
+/// # Returns
+///
+/// An array of the same shape whose elements are the nearest `A` values.
+pub fn downcast<A, S, D>(input: &ArrayBase<S, D>) -> Array<A, D>
+where
+    A: HalfFloat,
+    S: Data<Elem = f32>,
+    D: Dimension,
+{
+    input.mapv(A::from_f32)
+}
@@ -0,0 +1,362 @@
+//! Named-tensor bundles stored as `.npz` archives (a zip of `.npy` files), as written by
+//! `numpy.savez` and `numpy.savez_compressed`.
+
+use super::{Dtype, Element, Tensor};
+use half::f16;
+use ndarray::ArrayD;
+use ndarray_npy::{ReadNpyError, ReadNpyExt, WriteNpyError, WriteNpyExt};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::ops::Range;
+use std::path::Path;
+use thiserror::Error;
+use zip::result::ZipError;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Suffix numpy gives every array in an archive; it is not part of the key.
+const NPY_SUFFIX: &str = ".npy";
+
+#[derive(Error, Debug)]
+pub enum BundleError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid zip archive: {0}")]
+    Zip(#[from] ZipError),
+
+    #[error("Failed to read array {key}: {source}")]
+    ReadNpy { key: String, source: ReadNpyError },
+
+    #[error("Failed to write array {key}: {source}")]
+    WriteNpy { key: String, source: WriteNpyError },
+
+    #[error("Array {key} does not have a valid npy header")]
+    InvalidHeader { key: String },
+
+    #[error("Array {key} has unsupported npy type descriptor {descr}")]
+    UnsupportedDtype { key: String, descr: String },
+
+    #[error("Tensors of dtype {dtype} cannot be written to npy")]
+    UnsupportedWrite { dtype: Dtype },
+
+    #[error("Bundle has no tensor named {key}")]
+    MissingKey { key: String },
+
+    #[error("Tensor {key} has dtype {found}, expected {expected}")]
+    DtypeMismatch {
+        key: String,
+        expected: Dtype,
+        found: Dtype,
+    },
+}
+
+/// How the arrays of a written archive are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Uncompressed, like `numpy.savez`.
+    #[default]
+    Stored,
+    /// Deflate-compressed, like `numpy.savez_compressed`.
+    Deflated,
+}
+
+/// A set of named tensors, typically all inputs and outputs of one reference case.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Bundle {
+    tensors: HashMap<String, Tensor>,
+}
+
+impl Bundle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads every array of the `.npz` archive at `path`, detecting each dtype from its header.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: Path to a `.npz` archive.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Bundle)`: The tensors keyed by their archive name without the `.npy` suffix.
+    /// * `Err(BundleError)`: If the archive cannot be read or holds an unsupported dtype.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, BundleError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Reads a `.npz` archive from any seekable reader. See [`Bundle::open`].
+    pub fn read<R: Read + Seek>(reader: R) -> Result<Self, BundleError> {
+        let mut archive = ZipArchive::new(reader)?;
+        let mut tensors = HashMap::with_capacity(archive.len());
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index)?;
+            let name = entry.name();
+            let key = name.strip_suffix(NPY_SUFFIX).unwrap_or(name).to_string();
+            let mut bytes = Vec::with_capacity(entry.size() as usize);
+            entry.read_to_end(&mut bytes)?;
+            let tensor = decode_npy(&key, &bytes)?;
+            tensors.insert(key, tensor);
+        }
+        Ok(Self { tensors })
+    }
+
+    /// Writes the bundle as a `.npz` archive that `numpy.load` can read.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: Destination path.
+    /// * `compression`: Whether to deflate the arrays.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())`: If the archive was written.
+    /// * `Err(BundleError)`: If writing fails or a tensor has a dtype npy cannot hold.
+    pub fn save<P: AsRef<Path>>(
+        &self,
+        path: P,
+        compression: Compression,
+    ) -> Result<(), BundleError> {
+        let file = self.write(BufWriter::new(File::create(path)?), compression)?;
+        file.into_inner().map_err(|e| e.into_error())?;
+        Ok(())
+    }
+
+    /// Writes the bundle as a `.npz` archive to any seekable writer and returns the writer.
+    pub fn write<W: Write + Seek>(
+        &self,
+        writer: W,
+        compression: Compression,
+    ) -> Result<W, BundleError> {
+        let method = match compression {
+            Compression::Stored => CompressionMethod::Stored,
+            Compression::Deflated => CompressionMethod::Deflated,
+        };
+        let options = FileOptions::default().compression_method(method);
+        let mut zip = ZipWriter::new(writer);
+        // Sorted keys keep the archive byte-for-byte reproducible
+        let mut keys: Vec<&String> = self.tensors.keys().collect();
+        keys.sort();
+        for key in keys {
+            let bytes = encode_npy(key, &self.tensors[key])?;
+            zip.start_file(format!("{key}{NPY_SUFFIX}"), options)?;
+            zip.write_all(&bytes)?;
+        }
+        let mut writer = zip.finish()?;
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    /// Adds or replaces the tensor called `key`.
+    pub fn insert<K: Into<String>, T: Into<Tensor>>(
+        &mut self,
+        key: K,
+        tensor: T,
+    ) -> Option<Tensor> {
+        self.tensors.insert(key.into(), tensor.into())
+    }
+
+    /// Borrows the array called `key`.
+    ///
+    /// # Type Parameters
+    ///
+    /// * `A`: Element type, which must match the stored dtype.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&ArrayD<A>)`: The array.
+    /// * `Err(BundleError)`: If there is no tensor called `key` or it has another dtype.
+    pub fn get<A: Element>(&self, key: &str) -> Result<&ArrayD<A>, BundleError> {
+        let tensor = self.tensor(key)?;
+        tensor
+            .as_array::<A>()
+            .ok_or_else(|| BundleError::DtypeMismatch {
+                key: key.to_string(),
+                expected: A::DTYPE,
+                found: tensor.dtype(),
+            })
+    }
+
+    /// Borrows the tensor called `key`, whatever its dtype.
+    pub fn tensor(&self, key: &str) -> Result<&Tensor, BundleError> {
+        self.tensors
+            .get(key)
+            .ok_or_else(|| BundleError::MissingKey {
+                key: key.to_string(),
+            })
+    }
+
+    /// Keys of all tensors, in sorted order.
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.tensors.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    pub fn len(&self) -> usize {
+        self.tensors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tensors.is_empty()
+    }
+
+    pub fn into_tensors(self) -> HashMap<String, Tensor> {
+        self.tensors
+    }
+}
+
+impl From<HashMap<String, Tensor>> for Bundle {
+    fn from(tensors: HashMap<String, Tensor>) -> Self {
+        Self { tensors }
+    }
+}
+
+/// Reads every array of the `.npz` archive at `path`. Shorthand for [`Bundle::open`].
+pub fn read_npz<P: AsRef<Path>>(path: P) -> Result<HashMap<String, Tensor>, BundleError> {
+    Ok(Bundle::open(path)?.into_tensors())
+}
+
+/// Reads a single `.npy` file, detecting its dtype from the header like [`Bundle::open`] does.
+///
+/// # Arguments
+///
+/// * `path`: Path to a `.npy` file.
+///
+/// # Returns
+///
+/// * `Ok(Tensor)`: The array, wrapped in the variant matching its dtype.
+/// * `Err(BundleError)`: If the file cannot be read or holds an unsupported dtype.
+pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<Tensor, BundleError> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path)?;
+    decode_npy(&path.display().to_string(), &bytes)
+}
+
+/// Writes a single tensor as a `.npy` file that `numpy.load` can read.
+///
+/// # Arguments
+///
+/// * `path`: Destination path.
+/// * `tensor`: The tensor to write. `f16` is stored as numpy's `float16`; `bf16` has no numpy
+///   dtype and is rejected.
+///
+/// # Returns
+///
+/// * `Ok(())`: If the file was written.
+/// * `Err(BundleError)`: If writing fails or the tensor has a dtype npy cannot hold.
+pub fn save_npy<P: AsRef<Path>>(path: P, tensor: &Tensor) -> Result<(), BundleError> {
+    let path = path.as_ref();
+    let bytes = encode_npy(&path.display().to_string(), tensor)?;
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Decodes one `.npy` file, picking the element type from its header.
+fn decode_npy(key: &str, bytes: &[u8]) -> Result<Tensor, BundleError> {
+    let descr = npy_descr(bytes).ok_or_else(|| BundleError::InvalidHeader {
+        key: key.to_string(),
+    })?;
+    let read_error = |source| BundleError::ReadNpy {
+        key: key.to_string(),
+        source,
+    };
+    // The first character is the byte order ('<', '>', '|' or '='), which ndarray-npy handles
+    let tensor = match descr.get(1..) {
+        Some("f8") => Tensor::F64(ArrayD::read_npy(bytes).map_err(read_error)?),
+        Some("f4") => Tensor::F32(ArrayD::read_npy(bytes).map_err(read_error)?),
+        // ndarray-npy has no float16 support, so read the bit patterns as uint16
+        Some("f2") => {
+            let bytes = with_descr(bytes, &format!("{}u2", &descr[..1])).ok_or_else(|| {
+                BundleError::InvalidHeader {
+                    key: key.to_string(),
+                }
+            })?;
+            let bits = ArrayD::<u16>::read_npy(bytes.as_slice()).map_err(read_error)?;
+            Tensor::F16(bits.mapv(f16::from_bits))
+        }
+        Some("i8") => Tensor::I64(ArrayD::read_npy(bytes).map_err(read_error)?),
+        Some("b1") => Tensor::Bool(ArrayD::read_npy(bytes).map_err(read_error)?),
+        _ => {
+            return Err(BundleError::UnsupportedDtype {
+                key: key.to_string(),
+                descr,
+            });
+        }
+    };
+    Ok(tensor)
+}
+
+/// Encodes one tensor as a `.npy` file.
+fn encode_npy(key: &str, tensor: &Tensor) -> Result<Vec<u8>, BundleError> {
+    let mut bytes = Vec::new();
+    let written = match tensor {
+        Tensor::F64(a) => a.write_npy(&mut bytes),
+        Tensor::F32(a) => a.write_npy(&mut bytes),
+        Tensor::I64(a) => a.write_npy(&mut bytes),
+        Tensor::Bool(a) => a.write_npy(&mut bytes),
+        // Written as uint16 bit patterns, then relabelled as numpy's float16
+        Tensor::F16(a) => a.mapv(f16::to_bits).write_npy(&mut bytes),
+        Tensor::BF16(_) => {
+            return Err(BundleError::UnsupportedWrite {
+                dtype: tensor.dtype(),
+            });
+        }
+    };
+    written.map_err(|source| BundleError::WriteNpy {
+        key: key.to_string(),
+        source,
+    })?;
+    if let Tensor::F16(_) = tensor {
+        let descr = npy_descr(&bytes).expect("ndarray-npy writes a valid header");
+        bytes = with_descr(&bytes, &format!("{}f2", &descr[..1]))
+            .expect("float16 has a descriptor as long as uint16");
+    }
+    Ok(bytes)
+}
+
+/// Extracts the `descr` entry from the header dictionary of a `.npy` file.
+pub(crate) fn npy_descr(bytes: &[u8]) -> Option<String> {
+    let range = descr_range(bytes)?;
+    Some(std::str::from_utf8(&bytes[range]).ok()?.to_string())
+}
+
+/// Copies a `.npy` file with its `descr` replaced by one of the same length, which keeps the
+/// header size and alignment intact.
+fn with_descr(bytes: &[u8], descr: &str) -> Option<Vec<u8>> {
+    let range = descr_range(bytes)?;
+    if range.len() != descr.len() {
+        return None;
+    }
+    let mut bytes = bytes.to_vec();
+    bytes[range].copy_from_slice(descr.as_bytes());
+    Some(bytes)
+}
+
+/// Locates the value of the `descr` entry in the header dictionary of a `.npy` file.
+fn descr_range(bytes: &[u8]) -> Option<Range<usize>> {
+    let rest = bytes.strip_prefix(b"\x93NUMPY")?;
+    let (&major, rest) = rest.split_first()?;
+    let (_minor, rest) = rest.split_first()?;
+    let (length, rest) = match major {
+        1 => (
+            u16::from_le_bytes(rest.get(..2)?.try_into().ok()?) as usize,
+            &rest[2..],
+        ),
+        2 | 3 => (
+            u32::from_le_bytes(rest.get(..4)?.try_into().ok()?) as usize,
+            &rest[4..],
+        ),
+        _ => return None,
+    };
+    let header_start = bytes.len() - rest.len();
+    let header = std::str::from_utf8(rest.get(..length)?).ok()?;
+    let value = header.split_once("'descr'")?.1.trim_start();
+    let value = value.strip_prefix(':')?.trim_start();
+    let quote = value.chars().next().filter(|&c| c == '\'' || c == '"')?;
+    let value = &value[1..];
+    let start = header_start + header.len() - value.len();
+    Some(start..start + value.find(quote)?)
+}
@@ -0,0 +1,8 @@
+//! Reading and writing named tensors in the file formats PyTorch models are shipped in.
+
+pub mod bundle;
+pub mod pt;
+pub mod safetensors;
+mod tensor;
+
+pub use tensor::{Dtype, Element, Tensor};
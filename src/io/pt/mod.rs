@@ -0,0 +1,235 @@
+//! Checkpoints written by `torch.save`: a zip archive holding a pickled object (`data.pkl`)
+//! whose tensors point at raw storages stored as separate entries (`data/<key>`).
+//!
+//! The pickle is run by a restricted interpreter that only knows the globals a `state_dict`
+//! needs (`_rebuild_tensor_v2`, the typed storage classes and `OrderedDict`), so untrusted
+//! checkpoints cannot execute code; any other global is rejected with
+//! [`PtError::UnsafeGlobal`].
+
+mod pickle;
+
+use super::{Dtype, Element, Tensor};
+use ndarray::{ArrayD, Dimension, IxDyn};
+use pickle::{TensorRef, Value};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+use thiserror::Error;
+use zip::ZipArchive;
+use zip::result::ZipError;
+
+/// Name of the pickled object inside the archive's top-level directory.
+const PICKLE_ENTRY: &str = "data.pkl";
+
+#[derive(Error, Debug)]
+pub enum PtError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid zip archive: {0}")]
+    Zip(#[from] ZipError),
+
+    #[error(
+        "Archive has no data.pkl entry; checkpoints in the legacy (pre-1.6) format are not supported"
+    )]
+    MissingPickle,
+
+    #[error("Archive is missing storage {key}")]
+    MissingStorage { key: String },
+
+    #[error("Invalid pickle at byte {offset}: {reason}")]
+    Pickle { offset: usize, reason: String },
+
+    #[error("Unsupported pickle opcode {opcode:#04x} at byte {offset}")]
+    UnsupportedOpcode { opcode: u8, offset: usize },
+
+    #[error("Refusing to load global {module}.{name}")]
+    UnsafeGlobal { module: String, name: String },
+
+    #[error("Storage type torch.{storage} is not supported")]
+    UnsupportedStorage { storage: String },
+
+    #[error("Checkpoint does not contain a dict of tensors")]
+    NotADict,
+
+    #[error("Tensor {name} reads outside its storage of {len} elements")]
+    OutOfBounds { name: String, len: usize },
+
+    #[error("Storage {key} has {len} bytes, which is not a multiple of the {dtype} element size")]
+    InvalidStorageLength {
+        key: String,
+        len: usize,
+        dtype: Dtype,
+    },
+
+    #[error("Storage {key} holds a value that is not a valid {dtype}")]
+    InvalidValue { key: String, dtype: Dtype },
+
+    #[error("Unsupported byte order {0}")]
+    UnsupportedByteOrder(String),
+}
+
+/// Loads the tensors of a checkpoint written by `torch.save`, typically a `state_dict`.
+///
+/// Nested dicts (e.g. `{"model": model.state_dict(), "epoch": 3}`) are flattened with
+/// `.`-joined keys, and entries that are not tensors are skipped. Tensors keep the dtype of
+/// their storage and are copied out in logical (row-major) order, so views with arbitrary
+/// strides and storage offsets load as the values PyTorch would show.
+///
+/// # Arguments
+///
+/// * `path`: Path to a `.pt`/`.pth` file written with the default zip serialization.
+///
+/// # Returns
+///
+/// * `Ok(HashMap<String, Tensor>)`: The tensors keyed by their (flattened) dict keys.
+/// * `Err(PtError)`: If the archive is malformed, references a global outside the allowlist,
+///   or uses an unsupported storage type.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<HashMap<String, Tensor>, PtError> {
+    read(BufReader::new(File::open(path)?))
+}
+
+/// Loads a checkpoint from any seekable reader. See [`load`].
+pub fn read<R: Read + Seek>(reader: R) -> Result<HashMap<String, Tensor>, PtError> {
+    let mut archive = ZipArchive::new(reader)?;
+    // Entries live under a single top-level directory whose name varies between writers
+    let prefix = archive
+        .file_names()
+        .find_map(|name| name.strip_suffix(PICKLE_ENTRY))
+        .filter(|prefix| prefix.is_empty() || prefix.ends_with('/'))
+        .ok_or(PtError::MissingPickle)?
+        .to_string();
+
+    let big_endian = match read_entry(&mut archive, &format!("{prefix}byteorder"))? {
+        None => false,
+        Some(order) => match String::from_utf8_lossy(&order).trim() {
+            "little" => false,
+            "big" => true,
+            other => return Err(PtError::UnsupportedByteOrder(other.to_string())),
+        },
+    };
+
+    let pickle = read_entry(&mut archive, &format!("{prefix}{PICKLE_ENTRY}"))?
+        .ok_or(PtError::MissingPickle)?;
+    let mut refs = Vec::new();
+    match pickle::unpickle(&pickle)? {
+        Value::Dict(items) => collect_tensors(items, "", &mut refs),
+        _ => return Err(PtError::NotADict),
+    }
+
+    let mut storages: HashMap<String, Vec<u8>> = HashMap::new();
+    let mut tensors = HashMap::with_capacity(refs.len());
+    for (name, tensor) in refs {
+        let key = &tensor.storage.key;
+        if !storages.contains_key(key) {
+            let bytes = read_entry(&mut archive, &format!("{prefix}data/{key}"))?
+                .ok_or_else(|| PtError::MissingStorage { key: key.clone() })?;
+            storages.insert(key.clone(), bytes);
+        }
+        let bytes = &storages[key];
+        let tensor = match tensor.storage.dtype {
+            Dtype::F64 => materialize::<f64>(&name, &tensor, bytes, big_endian)?,
+            Dtype::F32 => materialize::<f32>(&name, &tensor, bytes, big_endian)?,
+            Dtype::F16 => materialize::<half::f16>(&name, &tensor, bytes, big_endian)?,
+            Dtype::BF16 => materialize::<half::bf16>(&name, &tensor, bytes, big_endian)?,
+            Dtype::I64 => materialize::<i64>(&name, &tensor, bytes, big_endian)?,
+            Dtype::Bool => materialize::<bool>(&name, &tensor, bytes, big_endian)?,
+        };
+        tensors.insert(name, tensor);
+    }
+    Ok(tensors)
+}
+
+/// Reads the archive entry called `name`, or `None` if there is no such entry.
+fn read_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<Vec<u8>>, PtError> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut bytes)?;
+    Ok(Some(bytes))
+}
+
+/// Walks string-keyed dicts depth first, collecting tensors under `.`-joined keys.
+fn collect_tensors(items: Vec<(Value, Value)>, prefix: &str, out: &mut Vec<(String, TensorRef)>) {
+    for (key, value) in items {
+        let Value::Str(key) = key else { continue };
+        let name = if prefix.is_empty() {
+            key
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match value {
+            Value::Tensor(tensor) => out.push((name, tensor)),
+            Value::Dict(items) => collect_tensors(items, &name, out),
+            _ => {}
+        }
+    }
+}
+
+/// Copies the strided view `tensor` out of its raw storage `bytes`.
+fn materialize<A: Element>(
+    name: &str,
+    tensor: &TensorRef,
+    bytes: &[u8],
+    big_endian: bool,
+) -> Result<Tensor, PtError> {
+    let key = &tensor.storage.key;
+    let size = A::DTYPE.size();
+    if !bytes.len().is_multiple_of(size) {
+        return Err(PtError::InvalidStorageLength {
+            key: key.clone(),
+            len: bytes.len(),
+            dtype: A::DTYPE,
+        });
+    }
+    let storage = bytes
+        .chunks_exact(size)
+        .map(|chunk| {
+            if big_endian {
+                let mut le = chunk.to_vec();
+                le.reverse();
+                A::from_le_bytes(&le)
+            } else {
+                A::from_le_bytes(chunk)
+            }
+        })
+        .collect::<Option<Vec<A>>>()
+        .ok_or_else(|| PtError::InvalidValue {
+            key: key.clone(),
+            dtype: A::DTYPE,
+        })?;
+
+    let out_of_bounds = || PtError::OutOfBounds {
+        name: name.to_string(),
+        len: storage.len(),
+    };
+    if !tensor.shape.contains(&0) {
+        let last = tensor
+            .shape
+            .iter()
+            .zip(&tensor.stride)
+            .try_fold(tensor.offset, |acc, (&n, &s)| {
+                (n - 1)
+                    .checked_mul(s)
+                    .and_then(|step| acc.checked_add(step))
+            })
+            .ok_or_else(out_of_bounds)?;
+        if last >= storage.len() {
+            return Err(out_of_bounds());
+        }
+    }
+    let array = ArrayD::from_shape_fn(IxDyn(&tensor.shape), |index| {
+        let position = (0..index.ndim())
+            .map(|axis| index[axis] * tensor.stride[axis])
+            .sum::<usize>();
+        storage[tensor.offset + position]
+    });
+    Ok(A::wrap(array))
+}
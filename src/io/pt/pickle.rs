@@ -0,0 +1,480 @@
+//! A restricted pickle interpreter covering the opcodes `torch.save` emits for state dicts.
+//!
+//! Only a fixed allowlist of globals can be referenced, so loading a checkpoint can never
+//! run code the way `pickle.load` can.
+
+use super::PtError;
+use crate::io::Dtype;
+use std::collections::HashMap;
+
+/// A value on the pickle stack.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Value {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Tuple(Vec<Value>),
+    List(Vec<Value>),
+    Dict(Vec<(Value, Value)>),
+    Global(Global),
+    Storage(StorageRef),
+    Tensor(TensorRef),
+}
+
+/// The globals a checkpoint may reference.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Global {
+    OrderedDict,
+    RebuildTensorV2,
+    RebuildParameter,
+    StorageType(Dtype),
+}
+
+/// A storage persisted as the archive entry `data/<key>`.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct StorageRef {
+    pub(super) key: String,
+    pub(super) dtype: Dtype,
+}
+
+/// A strided view into a storage, as built by `torch._utils._rebuild_tensor_v2`.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct TensorRef {
+    pub(super) storage: StorageRef,
+    pub(super) offset: usize,
+    pub(super) shape: Vec<usize>,
+    pub(super) stride: Vec<usize>,
+}
+
+const PROTO: u8 = 0x80;
+const FRAME: u8 = 0x95;
+const STOP: u8 = b'.';
+const MARK: u8 = b'(';
+const GLOBAL: u8 = b'c';
+const STACK_GLOBAL: u8 = 0x93;
+const REDUCE: u8 = b'R';
+const BUILD: u8 = b'b';
+const BINPERSID: u8 = b'Q';
+const NONE: u8 = b'N';
+const NEWTRUE: u8 = 0x88;
+const NEWFALSE: u8 = 0x89;
+const BININT: u8 = b'J';
+const BININT1: u8 = b'K';
+const BININT2: u8 = b'M';
+const LONG1: u8 = 0x8a;
+const BINFLOAT: u8 = b'G';
+const SHORT_BINUNICODE: u8 = 0x8c;
+const BINUNICODE: u8 = b'X';
+const BINUNICODE8: u8 = 0x8d;
+const SHORT_BINBYTES: u8 = b'C';
+const BINBYTES: u8 = b'B';
+const EMPTY_TUPLE: u8 = b')';
+const TUPLE: u8 = b't';
+const TUPLE1: u8 = 0x85;
+const TUPLE2: u8 = 0x86;
+const TUPLE3: u8 = 0x87;
+const EMPTY_LIST: u8 = b']';
+const LIST: u8 = b'l';
+const APPEND: u8 = b'a';
+const APPENDS: u8 = b'e';
+const EMPTY_DICT: u8 = b'}';
+const DICT: u8 = b'd';
+const SETITEM: u8 = b's';
+const SETITEMS: u8 = b'u';
+const BINPUT: u8 = b'q';
+const LONG_BINPUT: u8 = b'r';
+const MEMOIZE: u8 = 0x94;
+const BINGET: u8 = b'h';
+const LONG_BINGET: u8 = b'j';
+
+/// Runs the pickle program in `data` and returns the value it builds.
+pub(super) fn unpickle(data: &[u8]) -> Result<Value, PtError> {
+    Machine {
+        data,
+        pos: 0,
+        stack: Vec::new(),
+        marks: Vec::new(),
+        memo: HashMap::new(),
+    }
+    .run()
+}
+
+struct Machine<'a> {
+    data: &'a [u8],
+    pos: usize,
+    stack: Vec<Value>,
+    marks: Vec<usize>,
+    memo: HashMap<u32, Value>,
+}
+
+impl<'a> Machine<'a> {
+    fn run(mut self) -> Result<Value, PtError> {
+        loop {
+            let at = self.pos;
+            let opcode = self.read_u8()?;
+            match opcode {
+                PROTO => {
+                    self.read_u8()?;
+                }
+                FRAME => {
+                    self.take(8)?;
+                }
+                STOP => return self.pop(),
+                MARK => self.marks.push(self.stack.len()),
+                GLOBAL => {
+                    let module = self.read_line()?;
+                    let name = self.read_line()?;
+                    let global = resolve_global(&module, &name)?;
+                    self.stack.push(Value::Global(global));
+                }
+                STACK_GLOBAL => {
+                    let name = self.pop_str()?;
+                    let module = self.pop_str()?;
+                    let global = resolve_global(&module, &name)?;
+                    self.stack.push(Value::Global(global));
+                }
+                REDUCE => {
+                    let args = self.pop()?;
+                    let callable = self.pop()?;
+                    let value = self.call(callable, args)?;
+                    self.stack.push(value);
+                }
+                BUILD => {
+                    // `state` only carries attributes such as `_metadata`, which are dropped
+                    self.pop()?;
+                    match self.top()? {
+                        Value::Dict(_) | Value::Tensor(_) => {}
+                        _ => return Err(self.error("BUILD on an unsupported object")),
+                    }
+                }
+                BINPERSID => {
+                    let pid = self.pop()?;
+                    let storage = self.persistent_load(pid)?;
+                    self.stack.push(storage);
+                }
+                NONE => self.stack.push(Value::None),
+                NEWTRUE => self.stack.push(Value::Bool(true)),
+                NEWFALSE => self.stack.push(Value::Bool(false)),
+                BININT => {
+                    let v = i32::from_le_bytes(self.take_array()?);
+                    self.stack.push(Value::Int(v as i64));
+                }
+                BININT1 => {
+                    let v = self.read_u8()?;
+                    self.stack.push(Value::Int(v as i64));
+                }
+                BININT2 => {
+                    let v = u16::from_le_bytes(self.take_array()?);
+                    self.stack.push(Value::Int(v as i64));
+                }
+                LONG1 => {
+                    let n = self.read_u8()? as usize;
+                    let bytes = self.take(n)?;
+                    if n > 8 {
+                        return Err(self.error("integer does not fit in 64 bits"));
+                    }
+                    // Little-endian two's complement, sign-extended to 64 bits
+                    let mut buf = if bytes.last().is_some_and(|&b| b & 0x80 != 0) {
+                        [0xff; 8]
+                    } else {
+                        [0; 8]
+                    };
+                    buf[..n].copy_from_slice(bytes);
+                    self.stack.push(Value::Int(i64::from_le_bytes(buf)));
+                }
+                BINFLOAT => {
+                    let v = f64::from_be_bytes(self.take_array()?);
+                    self.stack.push(Value::Float(v));
+                }
+                SHORT_BINUNICODE | BINUNICODE | BINUNICODE8 => {
+                    let n = self.read_length(opcode == SHORT_BINUNICODE, opcode == BINUNICODE8)?;
+                    let bytes = self.take(n)?;
+                    let s = std::str::from_utf8(bytes)
+                        .map_err(|_| self.error("string is not valid UTF-8"))?;
+                    self.stack.push(Value::Str(s.to_string()));
+                }
+                SHORT_BINBYTES | BINBYTES => {
+                    let n = self.read_length(opcode == SHORT_BINBYTES, false)?;
+                    let bytes = self.take(n)?.to_vec();
+                    self.stack.push(Value::Bytes(bytes));
+                }
+                EMPTY_TUPLE => self.stack.push(Value::Tuple(Vec::new())),
+                TUPLE => {
+                    let items = self.pop_mark()?;
+                    self.stack.push(Value::Tuple(items));
+                }
+                TUPLE1 | TUPLE2 | TUPLE3 => {
+                    let n = (opcode - TUPLE1 + 1) as usize;
+                    if self.stack.len() < n {
+                        return Err(self.error("stack underflow"));
+                    }
+                    let items = self.stack.split_off(self.stack.len() - n);
+                    self.stack.push(Value::Tuple(items));
+                }
+                EMPTY_LIST => self.stack.push(Value::List(Vec::new())),
+                LIST => {
+                    let items = self.pop_mark()?;
+                    self.stack.push(Value::List(items));
+                }
+                APPEND => {
+                    let item = self.pop()?;
+                    self.extend_list(vec![item])?;
+                }
+                APPENDS => {
+                    let items = self.pop_mark()?;
+                    self.extend_list(items)?;
+                }
+                EMPTY_DICT => self.stack.push(Value::Dict(Vec::new())),
+                DICT => {
+                    let items = self.pop_mark()?;
+                    let pairs = self.pairs(items)?;
+                    self.stack.push(Value::Dict(pairs));
+                }
+                SETITEM => {
+                    let value = self.pop()?;
+                    let key = self.pop()?;
+                    self.extend_dict(vec![(key, value)])?;
+                }
+                SETITEMS => {
+                    let items = self.pop_mark()?;
+                    let pairs = self.pairs(items)?;
+                    self.extend_dict(pairs)?;
+                }
+                BINPUT => {
+                    let index = self.read_u8()? as u32;
+                    self.memo.insert(index, self.top()?.clone());
+                }
+                LONG_BINPUT => {
+                    let index = u32::from_le_bytes(self.take_array()?);
+                    self.memo.insert(index, self.top()?.clone());
+                }
+                MEMOIZE => {
+                    let index = self.memo.len() as u32;
+                    self.memo.insert(index, self.top()?.clone());
+                }
+                BINGET | LONG_BINGET => {
+                    let index = if opcode == BINGET {
+                        self.read_u8()? as u32
+                    } else {
+                        u32::from_le_bytes(self.take_array()?)
+                    };
+                    let value = self
+                        .memo
+                        .get(&index)
+                        .cloned()
+                        .ok_or_else(|| self.error("reference to an unknown memo entry"))?;
+                    self.stack.push(value);
+                }
+                _ => return Err(PtError::UnsupportedOpcode { opcode, offset: at }),
+            }
+        }
+    }
+
+    fn call(&self, callable: Value, args: Value) -> Result<Value, PtError> {
+        let (Value::Global(global), Value::Tuple(args)) = (callable, args) else {
+            return Err(self.error("REDUCE expects a global and an argument tuple"));
+        };
+        match (global, args.as_slice()) {
+            (Global::OrderedDict, []) => Ok(Value::Dict(Vec::new())),
+            (
+                Global::RebuildTensorV2,
+                [
+                    Value::Storage(storage),
+                    Value::Int(offset),
+                    Value::Tuple(shape),
+                    Value::Tuple(stride),
+                    ..,
+                ],
+            ) => {
+                let offset =
+                    usize::try_from(*offset).map_err(|_| self.error("negative storage offset"))?;
+                let shape = self.usizes(shape)?;
+                let stride = self.usizes(stride)?;
+                if shape.len() != stride.len() {
+                    return Err(self.error("tensor size and stride have different lengths"));
+                }
+                Ok(Value::Tensor(TensorRef {
+                    storage: storage.clone(),
+                    offset,
+                    shape,
+                    stride,
+                }))
+            }
+            (Global::RebuildParameter, [tensor @ Value::Tensor(_), ..]) => Ok(tensor.clone()),
+            (global, _) => Err(self.error(&format!("unsupported call to {global:?}"))),
+        }
+    }
+
+    /// Resolves `('storage', storage_type, key, location, numel)` persistent ids.
+    fn persistent_load(&self, pid: Value) -> Result<Value, PtError> {
+        match pid {
+            Value::Tuple(items) => match items.as_slice() {
+                [
+                    Value::Str(kind),
+                    Value::Global(Global::StorageType(dtype)),
+                    Value::Str(key),
+                    ..,
+                ] if kind == "storage" => Ok(Value::Storage(StorageRef {
+                    key: key.clone(),
+                    dtype: *dtype,
+                })),
+                _ => Err(self.error("unsupported persistent id")),
+            },
+            _ => Err(self.error("persistent id is not a tuple")),
+        }
+    }
+
+    fn usizes(&self, values: &[Value]) -> Result<Vec<usize>, PtError> {
+        values
+            .iter()
+            .map(|v| match v {
+                Value::Int(i) if *i >= 0 => Ok(*i as usize),
+                _ => Err(self.error("expected a tuple of non-negative integers")),
+            })
+            .collect()
+    }
+
+    fn extend_list(&mut self, items: Vec<Value>) -> Result<(), PtError> {
+        match self.stack.last_mut() {
+            Some(Value::List(list)) => {
+                list.extend(items);
+                Ok(())
+            }
+            _ => Err(self.error("APPEND target is not a list")),
+        }
+    }
+
+    fn extend_dict(&mut self, pairs: Vec<(Value, Value)>) -> Result<(), PtError> {
+        match self.stack.last_mut() {
+            Some(Value::Dict(dict)) => {
+                for (key, value) in pairs {
+                    // Later assignments to the same key win, as in Python
+                    match dict.iter_mut().find(|(k, _)| *k == key) {
+                        Some(entry) => entry.1 = value,
+                        None => dict.push((key, value)),
+                    }
+                }
+                Ok(())
+            }
+            _ => Err(self.error("SETITEM target is not a dict")),
+        }
+    }
+
+    fn pairs(&self, items: Vec<Value>) -> Result<Vec<(Value, Value)>, PtError> {
+        if !items.len().is_multiple_of(2) {
+            return Err(self.error("odd number of dict items"));
+        }
+        let mut pairs = Vec::with_capacity(items.len() / 2);
+        let mut items = items.into_iter();
+        while let (Some(k), Some(v)) = (items.next(), items.next()) {
+            pairs.push((k, v));
+        }
+        Ok(pairs)
+    }
+
+    fn pop(&mut self) -> Result<Value, PtError> {
+        self.stack
+            .pop()
+            .ok_or_else(|| self.error("stack underflow"))
+    }
+
+    fn top(&self) -> Result<&Value, PtError> {
+        self.stack
+            .last()
+            .ok_or_else(|| self.error("stack underflow"))
+    }
+
+    fn pop_str(&mut self) -> Result<String, PtError> {
+        match self.pop()? {
+            Value::Str(s) => Ok(s),
+            _ => Err(self.error("expected a string")),
+        }
+    }
+
+    fn pop_mark(&mut self) -> Result<Vec<Value>, PtError> {
+        let mark = self.marks.pop().ok_or_else(|| self.error("missing MARK"))?;
+        if mark > self.stack.len() {
+            return Err(self.error("stack underflow"));
+        }
+        Ok(self.stack.split_off(mark))
+    }
+
+    fn read_u8(&mut self) -> Result<u8, PtError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_length(&mut self, short: bool, long: bool) -> Result<usize, PtError> {
+        let n = if short {
+            self.read_u8()? as u64
+        } else if long {
+            u64::from_le_bytes(self.take_array()?)
+        } else {
+            u32::from_le_bytes(self.take_array()?) as u64
+        };
+        usize::try_from(n).map_err(|_| self.error("length does not fit in memory"))
+    }
+
+    fn read_line(&mut self) -> Result<String, PtError> {
+        let rest = &self.data[self.pos..];
+        let end = rest
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| self.error("unterminated line"))?;
+        let line = std::str::from_utf8(&rest[..end])
+            .map_err(|_| self.error("line is not valid UTF-8"))?
+            .to_string();
+        self.pos += end + 1;
+        Ok(line)
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], PtError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| self.error("unexpected end of data"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], PtError> {
+        Ok(self.take(N)?.try_into().expect("took exactly N bytes"))
+    }
+
+    fn error(&self, reason: &str) -> PtError {
+        PtError::Pickle {
+            offset: self.pos,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+fn resolve_global(module: &str, name: &str) -> Result<Global, PtError> {
+    let global = match (module, name) {
+        ("collections", "OrderedDict") => Global::OrderedDict,
+        ("torch._utils", "_rebuild_tensor_v2") => Global::RebuildTensorV2,
+        ("torch._utils", "_rebuild_parameter") => Global::RebuildParameter,
+        ("torch", "DoubleStorage") => Global::StorageType(Dtype::F64),
+        ("torch", "FloatStorage") => Global::StorageType(Dtype::F32),
+        ("torch", "HalfStorage") => Global::StorageType(Dtype::F16),
+        ("torch", "BFloat16Storage") => Global::StorageType(Dtype::BF16),
+        ("torch", "LongStorage") => Global::StorageType(Dtype::I64),
+        ("torch", "BoolStorage") => Global::StorageType(Dtype::Bool),
+        ("torch", storage) if storage.ends_with("Storage") => {
+            return Err(PtError::UnsupportedStorage {
+                storage: storage.to_string(),
+            });
+        }
+        _ => {
+            return Err(PtError::UnsafeGlobal {
+                module: module.to_string(),
+                name: name.to_string(),
+            });
+        }
+    };
+    Ok(global)
+}
@@ -0,0 +1,488 @@
+//! Reader and writer for the [safetensors](https://github.com/huggingface/safetensors) format.
+//!
+//! A file is an 8 byte little-endian header length, a JSON header mapping tensor names to
+//! their dtype, shape and byte range, and then the raw little-endian tensor data.
+
+use super::{Dtype, Element, Tensor};
+use memmap2::Mmap;
+use ndarray::{ArrayD, ArrayViewD, IxDyn};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::path::Path;
+use thiserror::Error;
+
+/// Headers larger than this are rejected before parsing, like the reference implementation.
+const MAX_HEADER_SIZE: usize = 100_000_000;
+
+/// Key of the optional string-to-string metadata map in the header.
+const METADATA_KEY: &str = "__metadata__";
+
+#[derive(Error, Debug)]
+pub enum SafetensorsError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Header length {length} does not fit in a file of {file_size} bytes")]
+    InvalidHeaderLength { length: u64, file_size: usize },
+
+    #[error("Header is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid header: {reason}")]
+    InvalidHeader { reason: String },
+
+    #[error("Tensor {name} has unsupported dtype {dtype}")]
+    UnsupportedDtype { name: String, dtype: String },
+
+    #[error(
+        "Tensor {name} has data offsets {start}..{end}, but its shape and dtype need {expected} bytes within a {buffer} byte buffer"
+    )]
+    InvalidOffsets {
+        name: String,
+        start: usize,
+        end: usize,
+        expected: usize,
+        buffer: usize,
+    },
+
+    #[error(
+        "Tensor data does not cover the data buffer contiguously (gap or overlap at byte {offset})"
+    )]
+    NonContiguous { offset: usize },
+
+    #[error("No tensor named {name}")]
+    MissingTensor { name: String },
+
+    #[error("Tensor {name} has dtype {found}, expected {expected}")]
+    DtypeMismatch {
+        name: String,
+        expected: Dtype,
+        found: Dtype,
+    },
+
+    #[error("Tensor {name} is not aligned for a zero-copy {dtype} view")]
+    Misaligned { name: String, dtype: Dtype },
+
+    #[error("Tensor {name} contains bytes that are not a valid {dtype}")]
+    InvalidValue { name: String, dtype: Dtype },
+}
+
+/// Location and type of one tensor, as described by the header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TensorInfo {
+    pub dtype: Dtype,
+    pub shape: Vec<usize>,
+    /// Byte range of the tensor relative to the start of the data buffer.
+    pub data_offsets: (usize, usize),
+}
+
+enum Storage {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl Storage {
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Storage::Mapped(mmap) => mmap,
+            Storage::Owned(bytes) => bytes,
+        }
+    }
+}
+
+/// A parsed safetensors file whose tensors can be viewed in place or copied out.
+pub struct SafeTensors {
+    storage: Storage,
+    data_start: usize,
+    tensors: BTreeMap<String, TensorInfo>,
+    metadata: BTreeMap<String, String>,
+}
+
+impl SafeTensors {
+    /// Memory-maps and parses the file at `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: Path to a `.safetensors` file.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SafeTensors)`: The parsed file, with tensor data left in the mapping.
+    /// * `Err(SafetensorsError)`: If the file cannot be read or its header is invalid.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, SafetensorsError> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is only read; like every mmap-based loader we rely on the file
+        // not being truncated or modified while it is open.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Self::parse(Storage::Mapped(mmap))
+    }
+
+    /// Parses a safetensors file held in memory.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, SafetensorsError> {
+        Self::parse(Storage::Owned(bytes))
+    }
+
+    fn parse(storage: Storage) -> Result<Self, SafetensorsError> {
+        let bytes = storage.bytes();
+        let file_size = bytes.len();
+        let length_bytes: [u8; 8] = bytes.get(..8).and_then(|b| b.try_into().ok()).ok_or(
+            SafetensorsError::InvalidHeaderLength {
+                length: 0,
+                file_size,
+            },
+        )?;
+        let length = u64::from_le_bytes(length_bytes);
+        let header_end = usize::try_from(length)
+            .ok()
+            .filter(|&n| n <= MAX_HEADER_SIZE)
+            .and_then(|n| n.checked_add(8))
+            .filter(|&end| end <= file_size)
+            .ok_or(SafetensorsError::InvalidHeaderLength { length, file_size })?;
+
+        let header: Map<String, Value> = serde_json::from_slice(&bytes[8..header_end])?;
+        let buffer = file_size - header_end;
+        let mut tensors = BTreeMap::new();
+        let mut metadata = BTreeMap::new();
+        for (name, entry) in header {
+            if name == METADATA_KEY {
+                metadata = parse_metadata(entry)?;
+                continue;
+            }
+            let info = parse_info(&name, &entry)?;
+            let (start, end) = info.data_offsets;
+            let expected = info
+                .shape
+                .iter()
+                .try_fold(info.dtype.size(), |acc, &d| acc.checked_mul(d));
+            if expected != end.checked_sub(start) || end > buffer {
+                return Err(SafetensorsError::InvalidOffsets {
+                    name,
+                    start,
+                    end,
+                    expected: expected.unwrap_or(usize::MAX),
+                    buffer,
+                });
+            }
+            tensors.insert(name, info);
+        }
+
+        // The tensors must tile the data buffer exactly, without holes or overlaps
+        let mut ranges: Vec<(usize, usize)> = tensors.values().map(|i| i.data_offsets).collect();
+        ranges.sort_unstable();
+        let mut offset = 0;
+        for (start, end) in ranges {
+            if start != offset {
+                return Err(SafetensorsError::NonContiguous { offset });
+            }
+            offset = end;
+        }
+        if offset != buffer {
+            return Err(SafetensorsError::NonContiguous { offset });
+        }
+
+        Ok(Self {
+            storage,
+            data_start: header_end,
+            tensors,
+            metadata,
+        })
+    }
+
+    /// Names of all tensors in the file, in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.tensors.keys().map(String::as_str)
+    }
+
+    /// Dtype, shape and byte range of the tensor called `name`.
+    pub fn info(&self, name: &str) -> Option<&TensorInfo> {
+        self.tensors.get(name)
+    }
+
+    /// The free-form `__metadata__` map of the header.
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Views the tensor called `name` in place, without copying.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: Name of the tensor.
+    ///
+    /// # Type Parameters
+    ///
+    /// * `A`: Element type, which must match the stored dtype.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ArrayViewD<A>)`: A view into the file's bytes.
+    /// * `Err(SafetensorsError)`: If the tensor is missing, has another dtype, is not aligned
+    ///   for `A` (only possible for files read with [`SafeTensors::from_bytes`]), or holds invalid
+    ///   bool bytes. Big-endian targets always need [`SafeTensors::array`] instead.
+    pub fn view<A: Element>(&self, name: &str) -> Result<ArrayViewD<'_, A>, SafetensorsError> {
+        let (info, bytes) = self.raw::<A>(name)?;
+        let misaligned = !(bytes.as_ptr() as usize).is_multiple_of(std::mem::align_of::<A>());
+        if misaligned || cfg!(target_endian = "big") {
+            return Err(SafetensorsError::Misaligned {
+                name: name.to_string(),
+                dtype: A::DTYPE,
+            });
+        }
+        if A::DTYPE == Dtype::Bool && bytes.iter().any(|&b| b > 1) {
+            return Err(SafetensorsError::InvalidValue {
+                name: name.to_string(),
+                dtype: A::DTYPE,
+            });
+        }
+        let len = bytes.len() / A::DTYPE.size();
+        // SAFETY: `raw` checked that A matches the stored dtype, so the byte range holds `len`
+        // little-endian values of A's size; alignment and (for bool) validity were checked above,
+        // and every bit pattern is valid for the remaining element types.
+        let slice = unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const A, len) };
+        Ok(ArrayViewD::from_shape(IxDyn(&info.shape), slice).expect("offsets were validated"))
+    }
+
+    /// Copies the tensor called `name` into an owned array of element type `A`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ArrayD<A>)`: The decoded array.
+    /// * `Err(SafetensorsError)`: If the tensor is missing, has another dtype or holds invalid bool bytes.
+    pub fn array<A: Element>(&self, name: &str) -> Result<ArrayD<A>, SafetensorsError> {
+        let (info, bytes) = self.raw::<A>(name)?;
+        let values = bytes
+            .chunks_exact(A::DTYPE.size())
+            .map(A::from_le_bytes)
+            .collect::<Option<Vec<A>>>()
+            .ok_or_else(|| SafetensorsError::InvalidValue {
+                name: name.to_string(),
+                dtype: A::DTYPE,
+            })?;
+        Ok(ArrayD::from_shape_vec(IxDyn(&info.shape), values).expect("offsets were validated"))
+    }
+
+    /// Copies the tensor called `name` into a [`Tensor`] of its stored dtype.
+    pub fn tensor(&self, name: &str) -> Result<Tensor, SafetensorsError> {
+        let info = self
+            .info(name)
+            .ok_or_else(|| SafetensorsError::MissingTensor {
+                name: name.to_string(),
+            })?;
+        Ok(match info.dtype {
+            Dtype::F64 => Tensor::F64(self.array(name)?),
+            Dtype::F32 => Tensor::F32(self.array(name)?),
+            Dtype::F16 => Tensor::F16(self.array(name)?),
+            Dtype::BF16 => Tensor::BF16(self.array(name)?),
+            Dtype::I64 => Tensor::I64(self.array(name)?),
+            Dtype::Bool => Tensor::Bool(self.array(name)?),
+        })
+    }
+
+    /// Copies every tensor in the file into a map keyed by name.
+    pub fn tensors(&self) -> Result<HashMap<String, Tensor>, SafetensorsError> {
+        self.names()
+            .map(|name| Ok((name.to_string(), self.tensor(name)?)))
+            .collect()
+    }
+
+    fn raw<A: Element>(&self, name: &str) -> Result<(&TensorInfo, &[u8]), SafetensorsError> {
+        let info = self
+            .info(name)
+            .ok_or_else(|| SafetensorsError::MissingTensor {
+                name: name.to_string(),
+            })?;
+        if info.dtype != A::DTYPE {
+            return Err(SafetensorsError::DtypeMismatch {
+                name: name.to_string(),
+                expected: A::DTYPE,
+                found: info.dtype,
+            });
+        }
+        let (start, end) = info.data_offsets;
+        let bytes = &self.storage.bytes()[self.data_start + start..self.data_start + end];
+        Ok((info, bytes))
+    }
+}
+
+/// Loads every tensor of the safetensors file at `path`.
+///
+/// # Arguments
+///
+/// * `path`: Path to a `.safetensors` file.
+///
+/// # Returns
+///
+/// * `Ok(HashMap<String, Tensor>)`: The tensors keyed by name.
+/// * `Err(SafetensorsError)`: If the file cannot be read or is invalid.
+pub fn load<P: AsRef<Path>>(path: P) -> Result<HashMap<String, Tensor>, SafetensorsError> {
+    SafeTensors::open(path)?.tensors()
+}
+
+/// Serializes named tensors to the safetensors format.
+///
+/// Like the reference implementation, tensors are laid out by decreasing element size and
+/// then by name, and the header is padded with spaces to a multiple of 8 bytes, so every
+/// tensor is aligned when the result is memory-mapped.
+///
+/// # Arguments
+///
+/// * `tensors`: `(name, tensor)` pairs. Names must be unique and not `__metadata__`.
+/// * `metadata`: Optional free-form metadata stored in the header.
+///
+/// # Returns
+///
+/// * `Ok(Vec<u8>)`: The file contents.
+/// * `Err(SafetensorsError)`: If a name is repeated or reserved.
+pub fn serialize<'a, I, K>(
+    tensors: I,
+    metadata: Option<&BTreeMap<String, String>>,
+) -> Result<Vec<u8>, SafetensorsError>
+where
+    I: IntoIterator<Item = (K, &'a Tensor)>,
+    K: AsRef<str>,
+{
+    let mut entries: Vec<(String, &Tensor)> = tensors
+        .into_iter()
+        .map(|(name, tensor)| (name.as_ref().to_string(), tensor))
+        .collect();
+    entries.sort_by(|(a, x), (b, y)| {
+        y.dtype()
+            .size()
+            .cmp(&x.dtype().size())
+            .then_with(|| a.cmp(b))
+    });
+
+    let mut header = Map::new();
+    if let Some(metadata) = metadata {
+        let map = metadata
+            .iter()
+            .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+            .collect();
+        header.insert(METADATA_KEY.to_string(), Value::Object(map));
+    }
+    let mut data = Vec::new();
+    for (name, tensor) in &entries {
+        let start = data.len();
+        write_data(tensor, &mut data);
+        let info = serde_json::json!({
+            "dtype": dtype_name(tensor.dtype()),
+            "shape": tensor.shape(),
+            "data_offsets": [start, data.len()],
+        });
+        if name == METADATA_KEY || header.insert(name.clone(), info).is_some() {
+            return Err(SafetensorsError::InvalidHeader {
+                reason: format!("tensor name {name} is repeated or reserved"),
+            });
+        }
+    }
+
+    let mut header = serde_json::to_vec(&Value::Object(header))?;
+    header.resize(header.len().next_multiple_of(8), b' ');
+    let mut out = Vec::with_capacity(8 + header.len() + data.len());
+    out.extend_from_slice(&(header.len() as u64).to_le_bytes());
+    out.extend_from_slice(&header);
+    out.extend_from_slice(&data);
+    Ok(out)
+}
+
+/// Writes named tensors to a safetensors file at `path`. See [`serialize`] for the layout.
+pub fn save<'a, P, I, K>(
+    path: P,
+    tensors: I,
+    metadata: Option<&BTreeMap<String, String>>,
+) -> Result<(), SafetensorsError>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = (K, &'a Tensor)>,
+    K: AsRef<str>,
+{
+    std::fs::write(path, serialize(tensors, metadata)?)?;
+    Ok(())
+}
+
+fn write_data(tensor: &Tensor, out: &mut Vec<u8>) {
+    fn write<A: Element>(array: &ArrayD<A>, out: &mut Vec<u8>) {
+        out.reserve(array.len() * A::DTYPE.size());
+        // Logical (row-major) order, whatever the memory layout
+        for value in array.iter() {
+            value.write_le_bytes(out);
+        }
+    }
+    match tensor {
+        Tensor::F64(a) => write(a, out),
+        Tensor::F32(a) => write(a, out),
+        Tensor::F16(a) => write(a, out),
+        Tensor::BF16(a) => write(a, out),
+        Tensor::I64(a) => write(a, out),
+        Tensor::Bool(a) => write(a, out),
+    }
+}
+
+fn dtype_name(dtype: Dtype) -> &'static str {
+    match dtype {
+        Dtype::F64 => "F64",
+        Dtype::F32 => "F32",
+        Dtype::F16 => "F16",
+        Dtype::BF16 => "BF16",
+        Dtype::I64 => "I64",
+        Dtype::Bool => "BOOL",
+    }
+}
+
+fn parse_info(name: &str, entry: &Value) -> Result<TensorInfo, SafetensorsError> {
+    let invalid = |what: &str| SafetensorsError::InvalidHeader {
+        reason: format!("tensor {name} has a missing or malformed {what}"),
+    };
+    let dtype_str = entry
+        .get("dtype")
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid("dtype"))?;
+    let dtype = match dtype_str {
+        "F64" => Dtype::F64,
+        "F32" => Dtype::F32,
+        "F16" => Dtype::F16,
+        "BF16" => Dtype::BF16,
+        "I64" => Dtype::I64,
+        "BOOL" => Dtype::Bool,
+        other => {
+            return Err(SafetensorsError::UnsupportedDtype {
+                name: name.to_string(),
+                dtype: other.to_string(),
+            });
+        }
+    };
+    let usizes = |key: &str| -> Option<Vec<usize>> {
+        entry
+            .get(key)?
+            .as_array()?
+            .iter()
+            .map(|v| v.as_u64().and_then(|v| usize::try_from(v).ok()))
+            .collect()
+    };
+    let shape = usizes("shape").ok_or_else(|| invalid("shape"))?;
+    let offsets = usizes("data_offsets")
+        .filter(|o| o.len() == 2)
+        .ok_or_else(|| invalid("data_offsets"))?;
+    Ok(TensorInfo {
+        dtype,
+        shape,
+        data_offsets: (offsets[0], offsets[1]),
+    })
+}
+
+fn parse_metadata(entry: Value) -> Result<BTreeMap<String, String>, SafetensorsError> {
+    let invalid = || SafetensorsError::InvalidHeader {
+        reason: format!("{METADATA_KEY} must map strings to strings"),
+    };
+    match entry {
+        Value::Object(map) => map
+            .into_iter()
+            .map(|(k, v)| match v {
+                Value::String(s) => Ok((k, s)),
+                _ => Err(invalid()),
+            })
+            .collect(),
+        _ => Err(invalid()),
+    }
+}
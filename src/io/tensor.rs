@@ -0,0 +1,191 @@
+use half::{bf16, f16};
+use ndarray::ArrayD;
+use std::fmt;
+
+/// Element types a [`Tensor`] can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dtype {
+    F64,
+    F32,
+    F16,
+    BF16,
+    I64,
+    Bool,
+}
+
+impl Dtype {
+    /// Size of one element in bytes.
+    pub fn size(self) -> usize {
+        match self {
+            Dtype::F64 | Dtype::I64 => 8,
+            Dtype::F32 => 4,
+            Dtype::F16 | Dtype::BF16 => 2,
+            Dtype::Bool => 1,
+        }
+    }
+}
+
+impl fmt::Display for Dtype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Dtype::F64 => "f64",
+            Dtype::F32 => "f32",
+            Dtype::F16 => "f16",
+            Dtype::BF16 => "bf16",
+            Dtype::I64 => "i64",
+            Dtype::Bool => "bool",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A dynamically typed array, as loaded from a file holding tensors of mixed dtypes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tensor {
+    F64(ArrayD<f64>),
+    F32(ArrayD<f32>),
+    F16(ArrayD<f16>),
+    BF16(ArrayD<bf16>),
+    I64(ArrayD<i64>),
+    Bool(ArrayD<bool>),
+}
+
+impl Tensor {
+    pub fn dtype(&self) -> Dtype {
+        match self {
+            Tensor::F64(_) => Dtype::F64,
+            Tensor::F32(_) => Dtype::F32,
+            Tensor::F16(_) => Dtype::F16,
+            Tensor::BF16(_) => Dtype::BF16,
+            Tensor::I64(_) => Dtype::I64,
+            Tensor::Bool(_) => Dtype::Bool,
+        }
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        match self {
+            Tensor::F64(a) => a.shape(),
+            Tensor::F32(a) => a.shape(),
+            Tensor::F16(a) => a.shape(),
+            Tensor::BF16(a) => a.shape(),
+            Tensor::I64(a) => a.shape(),
+            Tensor::Bool(a) => a.shape(),
+        }
+    }
+
+    /// Returns the array if the tensor holds elements of type `A`.
+    pub fn into_array<A: Element>(self) -> Option<ArrayD<A>> {
+        A::unwrap(self)
+    }
+
+    /// Borrows the array if the tensor holds elements of type `A`.
+    pub fn as_array<A: Element>(&self) -> Option<&ArrayD<A>> {
+        A::unwrap_ref(self)
+    }
+}
+
+/// Element types that can be stored in a [`Tensor`] and serialized as little-endian bytes.
+pub trait Element: Copy + Send + Sync + 'static + private::Sealed {
+    const DTYPE: Dtype;
+
+    /// Decodes one element from `Self::DTYPE.size()` little-endian bytes.
+    /// Returns `None` for byte patterns that are not valid values (e.g. a bool other than 0 or 1).
+    fn from_le_bytes(bytes: &[u8]) -> Option<Self>;
+
+    /// Appends the little-endian encoding of `self` to `out`.
+    fn write_le_bytes(&self, out: &mut Vec<u8>);
+
+    fn wrap(array: ArrayD<Self>) -> Tensor;
+
+    fn unwrap(tensor: Tensor) -> Option<ArrayD<Self>>;
+
+    fn unwrap_ref(tensor: &Tensor) -> Option<&ArrayD<Self>>;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_element {
+    ($type:ty, $variant:ident, |$bytes:ident| $decode:expr, |$value:ident| $encode:expr) => {
+        impl private::Sealed for $type {}
+
+        impl Element for $type {
+            const DTYPE: Dtype = Dtype::$variant;
+
+            fn from_le_bytes($bytes: &[u8]) -> Option<Self> {
+                $decode
+            }
+
+            fn write_le_bytes(&self, out: &mut Vec<u8>) {
+                let $value = *self;
+                out.extend_from_slice(&$encode);
+            }
+
+            fn wrap(array: ArrayD<Self>) -> Tensor {
+                Tensor::$variant(array)
+            }
+
+            fn unwrap(tensor: Tensor) -> Option<ArrayD<Self>> {
+                match tensor {
+                    Tensor::$variant(array) => Some(array),
+                    _ => None,
+                }
+            }
+
+            fn unwrap_ref(tensor: &Tensor) -> Option<&ArrayD<Self>> {
+                match tensor {
+                    Tensor::$variant(array) => Some(array),
+                    _ => None,
+                }
+            }
+        }
+
+        impl From<ArrayD<$type>> for Tensor {
+            fn from(array: ArrayD<$type>) -> Self {
+                Tensor::$variant(array)
+            }
+        }
+    };
+}
+
+impl_element!(
+    f64,
+    F64,
+    |b| Some(f64::from_le_bytes(b.try_into().ok()?)),
+    |v| v.to_le_bytes()
+);
+impl_element!(
+    f32,
+    F32,
+    |b| Some(f32::from_le_bytes(b.try_into().ok()?)),
+    |v| v.to_le_bytes()
+);
+impl_element!(
+    f16,
+    F16,
+    |b| Some(f16::from_le_bytes(b.try_into().ok()?)),
+    |v| v.to_le_bytes()
+);
+impl_element!(
+    bf16,
+    BF16,
+    |b| Some(bf16::from_le_bytes(b.try_into().ok()?)),
+    |v| v.to_le_bytes()
+);
+impl_element!(
+    i64,
+    I64,
+    |b| Some(i64::from_le_bytes(b.try_into().ok()?)),
+    |v| v.to_le_bytes()
+);
+impl_element!(
+    bool,
+    Bool,
+    |b| match b {
+        [0] => Some(false),
+        [1] => Some(true),
+        _ => None,
+    },
+    |v| [v as u8]
+);
@@ -0,0 +1,122 @@
+use super::JniError;
+use super::sys::{JNI_FALSE, JNIEnv, jlong, jlongArray, jobject, jsize, jstring};
+use std::ffi::{CStr, CString, c_void};
+
+/// Checked access to the JNI functions the bindings use.
+pub(super) struct Env {
+    raw: *mut JNIEnv,
+}
+
+/// Calls a JNI function, failing with [`JniError::MissingFunction`] if the table lacks it.
+macro_rules! call {
+    ($env:expr, $name:ident($($arg:expr),*)) => {{
+        let env = $env;
+        // SAFETY: Env::new guarantees a valid function table
+        let function = unsafe { (**env.raw).$name }
+            .ok_or(JniError::MissingFunction(stringify!($name)))?;
+        // SAFETY: the arguments come from the JVM or were checked by the caller
+        unsafe { function(env.raw, $($arg),*) }
+    }};
+}
+
+impl Env {
+    /// # Safety
+    ///
+    /// `raw` must be the `JNIEnv` passed to the current native method call.
+    pub(super) unsafe fn new(raw: *mut JNIEnv) -> Self {
+        Env { raw }
+    }
+
+    /// Fails with [`JniError::Pending`] if a JNI call left a Java exception behind.
+    fn check_exception(&self) -> Result<(), JniError> {
+        if call!(self, exception_check()) == JNI_FALSE {
+            Ok(())
+        } else {
+            Err(JniError::Pending)
+        }
+    }
+
+    /// Throws a new exception of class `class`, e.g. `java/lang/IllegalArgumentException`.
+    pub(super) fn throw(&self, class: &str, message: &str) -> Result<(), JniError> {
+        let class = CString::new(class).expect("class names contain no NUL bytes");
+        let message = CString::new(message.replace('\0', "")).expect("NUL bytes were removed");
+        let class_ref = call!(self, find_class(class.as_ptr()));
+        if class_ref.is_null() {
+            // FindClass has already thrown NoClassDefFoundError
+            return Err(JniError::Pending);
+        }
+        call!(self, throw_new(class_ref, message.as_ptr()));
+        call!(self, delete_local_ref(class_ref));
+        Ok(())
+    }
+
+    /// Copies a Java `long[]`.
+    pub(super) fn long_array(
+        &self,
+        array: jlongArray,
+        argument: &'static str,
+    ) -> Result<Vec<i64>, JniError> {
+        if array.is_null() {
+            return Err(JniError::NullArgument(argument));
+        }
+        let len = call!(self, get_array_length(array));
+        let mut values = vec![0; len.max(0) as usize];
+        call!(
+            self,
+            get_long_array_region(array, 0, len, values.as_mut_ptr())
+        );
+        self.check_exception()?;
+        Ok(values)
+    }
+
+    /// Creates a Java `long[]` holding `values`.
+    pub(super) fn new_long_array(&self, values: &[i64]) -> Result<jlongArray, JniError> {
+        let len = jsize::try_from(values.len())
+            .map_err(|_| JniError::invalid("values", "too many values for a Java array"))?;
+        let array = call!(self, new_long_array(len));
+        if array.is_null() {
+            // OutOfMemoryError is pending
+            return Err(JniError::Pending);
+        }
+        call!(self, set_long_array_region(array, 0, len, values.as_ptr()));
+        self.check_exception()?;
+        Ok(array)
+    }
+
+    /// Copies a Java `String`.
+    pub(super) fn string(
+        &self,
+        string: jstring,
+        argument: &'static str,
+    ) -> Result<String, JniError> {
+        if string.is_null() {
+            return Err(JniError::NullArgument(argument));
+        }
+        let chars = call!(self, get_string_utf_chars(string, std::ptr::null_mut()));
+        if chars.is_null() {
+            return Err(JniError::Pending);
+        }
+        // SAFETY: GetStringUTFChars returns a NUL-terminated string
+        let copied = unsafe { CStr::from_ptr(chars) }.to_str().map(str::to_owned);
+        call!(self, release_string_utf_chars(string, chars));
+        // Modified UTF-8 only differs from UTF-8 for NUL and supplementary characters
+        copied.map_err(|err| JniError::invalid(argument, err))
+    }
+
+    /// The address and capacity in elements of a direct `java.nio` buffer.
+    pub(super) fn direct_buffer(
+        &self,
+        buffer: jobject,
+        argument: &'static str,
+    ) -> Result<(*mut c_void, usize), JniError> {
+        if buffer.is_null() {
+            return Err(JniError::NullArgument(argument));
+        }
+        let address = call!(self, get_direct_buffer_address(buffer));
+        let capacity: jlong = call!(self, get_direct_buffer_capacity(buffer));
+        if address.is_null() || capacity < 0 {
+            return Err(JniError::NotDirect(argument));
+        }
+        Ok((address, capacity as usize))
+    }
+}
@@ -0,0 +1,166 @@
+//! JNI bindings for calling the ops from Kotlin or Java, enabled by the `jni` feature.
+//!
+//! The native methods belong to `com.rustops.RustOps`, declared in
+//! `java/com/rustops/RustOps.java`. Tensors are passed to Java as `long` handles that must be
+//! released with `RustOps.free`. Data moves through direct `java.nio` buffers in native byte
+//! order, e.g. `ByteBuffer.allocateDirect(n * 4).order(ByteOrder.nativeOrder()).asFloatBuffer()`.
+//!
+//! Errors are thrown as Java exceptions: `NullPointerException` for null arguments,
+//! `IndexOutOfBoundsException` for out of range indices, `UnsupportedOperationException` for
+//! unsupported dtypes and `IllegalArgumentException` for everything else the ops reject.
+
+mod env;
+mod natives;
+pub mod sys;
+
+pub use natives::*;
+
+use crate::functions::argmax::ArgmaxError;
+use crate::functions::einsum::EinsumError;
+use crate::functions::gather::GatherError;
+use crate::functions::reshape::ReshapeError;
+use crate::functions::scatter::ScatterError;
+use crate::io::{Dtype, Tensor};
+use crate::nn::functional::FunctionalError;
+use env::Env;
+use std::fmt::Display;
+use std::panic::{self, AssertUnwindSafe};
+use sys::{JNIEnv, jlong};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+enum JniError {
+    #[error("Tensor handle `{0}` is null")]
+    NullHandle(&'static str),
+
+    #[error("Argument `{0}` is null")]
+    NullArgument(&'static str),
+
+    #[error("Argument `{0}` must be a direct buffer")]
+    NotDirect(&'static str),
+
+    #[error("Invalid argument `{argument}`: {reason}")]
+    InvalidArgument {
+        argument: &'static str,
+        reason: String,
+    },
+
+    #[error("Buffer `{argument}` holds {capacity} elements but {required} are needed")]
+    BufferSize {
+        argument: &'static str,
+        required: usize,
+        capacity: usize,
+    },
+
+    #[error("Argument `{argument}` must have dtype {expected}, found {found}")]
+    DtypeMismatch {
+        argument: &'static str,
+        expected: Dtype,
+        found: Dtype,
+    },
+
+    #[error("{op} does not support tensors of dtype {dtype}")]
+    UnsupportedDtype { op: &'static str, dtype: Dtype },
+
+    #[error(transparent)]
+    Gather(#[from] GatherError),
+
+    #[error(transparent)]
+    Scatter(#[from] ScatterError),
+
+    #[error(transparent)]
+    Einsum(#[from] EinsumError),
+
+    #[error(transparent)]
+    Functional(#[from] FunctionalError),
+
+    #[error(transparent)]
+    Reshape(#[from] ReshapeError),
+
+    #[error(transparent)]
+    Argmax(#[from] ArgmaxError),
+
+    #[error("Internal panic: {0}")]
+    Panic(String),
+
+    #[error("The JNI function table has no `{0}`")]
+    MissingFunction(&'static str),
+
+    /// A Java exception is already pending, so no other may be thrown.
+    #[error("A Java exception is pending")]
+    Pending,
+}
+
+impl JniError {
+    fn invalid(argument: &'static str, reason: impl Display) -> Self {
+        JniError::InvalidArgument {
+            argument,
+            reason: reason.to_string(),
+        }
+    }
+
+    /// The Java exception class thrown for this error.
+    fn exception_class(&self) -> &'static str {
+        match self {
+            JniError::NullHandle(_) | JniError::NullArgument(_) => "java/lang/NullPointerException",
+            JniError::Gather(
+                GatherError::IndexOutOfBounds { .. } | GatherError::NegativeIndex { .. },
+            )
+            | JniError::Scatter(
+                ScatterError::IndexOutOfBounds { .. } | ScatterError::NegativeIndex { .. },
+            ) => "java/lang/IndexOutOfBoundsException",
+            JniError::UnsupportedDtype { .. } => "java/lang/UnsupportedOperationException",
+            JniError::Panic(_) | JniError::MissingFunction(_) | JniError::Pending => {
+                "java/lang/IllegalStateException"
+            }
+            _ => "java/lang/IllegalArgumentException",
+        }
+    }
+}
+
+/// Runs the body of a native method, throwing any error as a Java exception and returning
+/// `default` in that case. Java ignores the return value when an exception is pending.
+///
+/// # Safety
+///
+/// `env` must be the `JNIEnv` passed to the native method.
+unsafe fn run<T>(
+    env: *mut JNIEnv,
+    default: T,
+    body: impl FnOnce(&Env) -> Result<T, JniError>,
+) -> T {
+    // SAFETY: upheld by the caller
+    let env = unsafe { Env::new(env) };
+    let result = panic::catch_unwind(AssertUnwindSafe(|| body(&env))).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(JniError::Panic(message))
+    });
+    match result {
+        Ok(value) => value,
+        Err(JniError::Pending) => default,
+        Err(err) => {
+            // If even throwing fails there is nothing left to report the error with
+            let _ = env.throw(err.exception_class(), &err.to_string());
+            default
+        }
+    }
+}
+
+/// Hands a tensor to Java as a handle.
+fn into_handle(tensor: Tensor) -> jlong {
+    Box::into_raw(Box::new(tensor)) as jlong
+}
+
+/// Borrows the tensor behind a handle.
+///
+/// # Safety
+///
+/// `handle` must be zero or a live handle returned by [`into_handle`].
+unsafe fn tensor_ref<'a>(handle: jlong, argument: &'static str) -> Result<&'a Tensor, JniError> {
+    // SAFETY: upheld by the caller
+    unsafe { (handle as *const Tensor).as_ref() }.ok_or(JniError::NullHandle(argument))
+}
@@ -0,0 +1,475 @@
+//! The native methods of `com.rustops.RustOps`.
+//!
+//! # Safety
+//!
+//! These functions are only meant to be called by a JVM through the declarations in
+//! `RustOps.java`: `env` must be the current thread's `JNIEnv`, object arguments must be
+//! local or global references, and tensor arguments must be zero or live handles.
+
+use super::sys::{JNI_FALSE, JNIEnv, jboolean, jclass, jlong, jlongArray, jobject, jstring};
+use super::{Env, JniError, into_handle, run, tensor_ref};
+use crate::functions::argmax::argmax;
+use crate::functions::dim::normalize_dim;
+use crate::functions::einsum::einsum_ndarray_dyn;
+use crate::functions::gather::gather;
+use crate::functions::rearrange::rearrange_batch_mems_flag;
+use crate::functions::reshape::reshape;
+use crate::functions::scatter::scatter;
+use crate::io::{Element, Tensor};
+use crate::nn::functional::softmax;
+use ndarray::{ArrayD, IxDyn};
+use std::ptr;
+
+/// Reads a tensor of `shape` from the start of a direct buffer.
+fn from_buffer<A: Element>(
+    env: &Env,
+    buffer: jobject,
+    shape: jlongArray,
+) -> Result<jlong, JniError> {
+    let shape = env
+        .long_array(shape, "shape")?
+        .into_iter()
+        .map(|dim| {
+            usize::try_from(dim)
+                .map_err(|_| JniError::invalid("shape", format!("{dim} is negative")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let numel = shape
+        .iter()
+        .try_fold(1usize, |acc, &dim| acc.checked_mul(dim))
+        .ok_or_else(|| JniError::invalid("shape", "element count overflows"))?;
+    let (address, capacity) = env.direct_buffer(buffer, "data")?;
+    if capacity < numel {
+        return Err(JniError::BufferSize {
+            argument: "data",
+            required: numel,
+            capacity,
+        });
+    }
+    let address = address as *const A;
+    let values = (0..numel)
+        // SAFETY: the buffer holds `capacity >= numel` elements of A, and float and long
+        // buffers hold no invalid bit patterns
+        .map(|position| unsafe { address.add(position).read_unaligned() })
+        .collect();
+    let array = ArrayD::from_shape_vec(IxDyn(&shape), values).expect("length matches shape");
+    Ok(into_handle(A::wrap(array)))
+}
+
+/// Copies a tensor to the start of a direct buffer.
+fn to_buffer<A: Element>(env: &Env, tensor: &Tensor, buffer: jobject) -> Result<(), JniError> {
+    let array = tensor
+        .as_array::<A>()
+        .ok_or_else(|| JniError::DtypeMismatch {
+            argument: "tensor",
+            expected: A::DTYPE,
+            found: tensor.dtype(),
+        })?;
+    let (address, capacity) = env.direct_buffer(buffer, "out")?;
+    if capacity < array.len() {
+        return Err(JniError::BufferSize {
+            argument: "out",
+            required: array.len(),
+            capacity,
+        });
+    }
+    let address = address as *mut A;
+    for (position, &value) in array.iter().enumerate() {
+        // SAFETY: the buffer holds `capacity >= len` elements
+        unsafe { address.add(position).write_unaligned(value) };
+    }
+    Ok(())
+}
+
+fn dim_arg(dim: jlong) -> Result<isize, JniError> {
+    isize::try_from(dim).map_err(|_| JniError::invalid("dim", format!("{dim} is out of range")))
+}
+
+fn index_arg(tensor: &Tensor) -> Result<&ArrayD<i64>, JniError> {
+    tensor
+        .as_array::<i64>()
+        .ok_or_else(|| JniError::DtypeMismatch {
+            argument: "index",
+            expected: i64::DTYPE,
+            found: tensor.dtype(),
+        })
+}
+
+fn unsupported(op: &'static str, tensor: &Tensor) -> JniError {
+    JniError::UnsupportedDtype {
+        op,
+        dtype: tensor.dtype(),
+    }
+}
+
+/// `static native long fromFloatBuffer(FloatBuffer data, long[] shape)`
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_fromFloatBuffer(
+    env: *mut JNIEnv,
+    _class: jclass,
+    data: jobject,
+    shape: jlongArray,
+) -> jlong {
+    // SAFETY: upheld by the JVM
+    unsafe { run(env, 0, |env| from_buffer::<f32>(env, data, shape)) }
+}
+
+/// `static native long fromLongBuffer(LongBuffer data, long[] shape)`
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_fromLongBuffer(
+    env: *mut JNIEnv,
+    _class: jclass,
+    data: jobject,
+    shape: jlongArray,
+) -> jlong {
+    // SAFETY: upheld by the JVM
+    unsafe { run(env, 0, |env| from_buffer::<i64>(env, data, shape)) }
+}
+
+/// `static native void free(long tensor)`. Does nothing for a zero handle.
+///
+/// # Safety
+///
+/// See the [module documentation](self). The handle must not be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_free(
+    _env: *mut JNIEnv,
+    _class: jclass,
+    tensor: jlong,
+) {
+    if tensor != 0 {
+        // SAFETY: handles are created by into_handle
+        drop(unsafe { Box::from_raw(tensor as *mut Tensor) });
+    }
+}
+
+/// `static native long[] shape(long tensor)`
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_shape(
+    env: *mut JNIEnv,
+    _class: jclass,
+    tensor: jlong,
+) -> jlongArray {
+    // SAFETY: upheld by the JVM
+    unsafe {
+        run(env, ptr::null_mut(), |env| {
+            let shape: Vec<i64> = tensor_ref(tensor, "tensor")?
+                .shape()
+                .iter()
+                .map(|&dim| dim as i64)
+                .collect();
+            env.new_long_array(&shape)
+        })
+    }
+}
+
+/// `static native void toFloatBuffer(long tensor, FloatBuffer out)`
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_toFloatBuffer(
+    env: *mut JNIEnv,
+    _class: jclass,
+    tensor: jlong,
+    out: jobject,
+) {
+    // SAFETY: upheld by the JVM
+    unsafe {
+        run(env, (), |env| {
+            to_buffer::<f32>(env, tensor_ref(tensor, "tensor")?, out)
+        })
+    }
+}
+
+/// `static native void toLongBuffer(long tensor, LongBuffer out)`
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_toLongBuffer(
+    env: *mut JNIEnv,
+    _class: jclass,
+    tensor: jlong,
+    out: jobject,
+) {
+    // SAFETY: upheld by the JVM
+    unsafe {
+        run(env, (), |env| {
+            to_buffer::<i64>(env, tensor_ref(tensor, "tensor")?, out)
+        })
+    }
+}
+
+/// `static native long gather(long input, long dim, long index)`
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_gather(
+    env: *mut JNIEnv,
+    _class: jclass,
+    input: jlong,
+    dim: jlong,
+    index: jlong,
+) -> jlong {
+    // SAFETY: upheld by the JVM
+    unsafe {
+        run(env, 0, |_| {
+            let input = tensor_ref(input, "input")?;
+            let index = index_arg(tensor_ref(index, "index")?)?;
+            let dim = dim_arg(dim)?;
+            let result = match input {
+                Tensor::F32(a) => Tensor::from(gather(a, dim, index)?),
+                Tensor::I64(a) => Tensor::from(gather(a, dim, index)?),
+                other => return Err(unsupported("gather", other)),
+            };
+            Ok(into_handle(result))
+        })
+    }
+}
+
+/// `static native long scatter(long target, long dim, long index, long src)`. Returns a new
+/// tensor; `target` is not modified.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_scatter(
+    env: *mut JNIEnv,
+    _class: jclass,
+    target: jlong,
+    dim: jlong,
+    index: jlong,
+    src: jlong,
+) -> jlong {
+    // SAFETY: upheld by the JVM
+    unsafe {
+        run(env, 0, |_| {
+            let target = tensor_ref(target, "target")?;
+            let index = index_arg(tensor_ref(index, "index")?)?;
+            let src = tensor_ref(src, "src")?;
+            let dim = dim_arg(dim)?;
+            let mismatch = || JniError::DtypeMismatch {
+                argument: "src",
+                expected: target.dtype(),
+                found: src.dtype(),
+            };
+            let result = match (target, src) {
+                (Tensor::F32(a), Tensor::F32(b)) => {
+                    let mut result = a.clone();
+                    scatter(&mut result, dim, index, b)?;
+                    Tensor::from(result)
+                }
+                (Tensor::I64(a), Tensor::I64(b)) => {
+                    let mut result = a.clone();
+                    scatter(&mut result, dim, index, b)?;
+                    Tensor::from(result)
+                }
+                (Tensor::F32(_) | Tensor::I64(_), _) => return Err(mismatch()),
+                (other, _) => return Err(unsupported("scatter", other)),
+            };
+            Ok(into_handle(result))
+        })
+    }
+}
+
+/// `static native long argmax(long input, long dim, boolean keepdim)`. The result holds longs.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_argmax(
+    env: *mut JNIEnv,
+    _class: jclass,
+    input: jlong,
+    dim: jlong,
+    keepdim: jboolean,
+) -> jlong {
+    // SAFETY: upheld by the JVM
+    unsafe {
+        run(env, 0, |_| {
+            let input = tensor_ref(input, "input")?;
+            let ndim = input.shape().len();
+            let dim = normalize_dim(dim_arg(dim)?, ndim).ok_or_else(|| {
+                JniError::invalid(
+                    "dim",
+                    format!("{dim} is out of range for {ndim} dimensions"),
+                )
+            })?;
+            argmax_handle(input, Some(dim), keepdim != JNI_FALSE)
+        })
+    }
+}
+
+/// `static native long argmaxAll(long input)`: the index into the flattened input, as a
+/// 0-dimensional tensor of longs.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_argmaxAll(
+    env: *mut JNIEnv,
+    _class: jclass,
+    input: jlong,
+) -> jlong {
+    // SAFETY: upheld by the JVM
+    unsafe {
+        run(env, 0, |_| {
+            argmax_handle(tensor_ref(input, "input")?, None, false)
+        })
+    }
+}
+
+fn argmax_handle(input: &Tensor, dim: Option<usize>, keepdim: bool) -> Result<jlong, JniError> {
+    let indices = match input {
+        Tensor::F32(a) => argmax(a, dim, keepdim)?,
+        Tensor::I64(a) => argmax(a, dim, keepdim)?,
+        other => return Err(unsupported("argmax", other)),
+    };
+    Ok(into_handle(Tensor::from(indices)))
+}
+
+/// `static native long einsum(String equation, long[] operands)`. All operands must share
+/// one dtype.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_einsum(
+    env: *mut JNIEnv,
+    _class: jclass,
+    equation: jstring,
+    operands: jlongArray,
+) -> jlong {
+    // SAFETY: upheld by the JVM
+    unsafe {
+        run(env, 0, |env| {
+            let equation = env.string(equation, "equation")?;
+            let operands = env
+                .long_array(operands, "operands")?
+                .into_iter()
+                .map(|handle| tensor_ref(handle, "operands"))
+                .collect::<Result<Vec<_>, _>>()?;
+            let Some(first) = operands.first() else {
+                return Err(JniError::invalid(
+                    "operands",
+                    "at least one operand is required",
+                ));
+            };
+            let result = match first {
+                Tensor::F32(_) => {
+                    Tensor::from(einsum_ndarray_dyn(&equation, &same::<f32>(&operands)?)?)
+                }
+                Tensor::I64(_) => {
+                    Tensor::from(einsum_ndarray_dyn(&equation, &same::<i64>(&operands)?)?)
+                }
+                other => return Err(unsupported("einsum", other)),
+            };
+            Ok(into_handle(result))
+        })
+    }
+}
+
+fn same<'a, A: Element>(operands: &[&'a Tensor]) -> Result<Vec<&'a ArrayD<A>>, JniError> {
+    operands
+        .iter()
+        .map(|tensor| {
+            tensor
+                .as_array::<A>()
+                .ok_or_else(|| JniError::DtypeMismatch {
+                    argument: "operands",
+                    expected: A::DTYPE,
+                    found: tensor.dtype(),
+                })
+        })
+        .collect()
+}
+
+/// `static native long reshape(long input, long[] shape)`. One entry of `shape` may be -1.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_reshape(
+    env: *mut JNIEnv,
+    _class: jclass,
+    input: jlong,
+    shape: jlongArray,
+) -> jlong {
+    // SAFETY: upheld by the JVM
+    unsafe {
+        run(env, 0, |env| {
+            let input = tensor_ref(input, "input")?;
+            let shape = env.long_array(shape, "shape")?;
+            let result = match input {
+                Tensor::F32(a) => Tensor::from(reshape(a, &shape)?),
+                Tensor::I64(a) => Tensor::from(reshape(a, &shape)?),
+                other => return Err(unsupported("reshape", other)),
+            };
+            Ok(into_handle(result))
+        })
+    }
+}
+
+/// `static native long rearrangeBatchMemsFlag(long input)`: `batch mems flag -> mems (batch
+/// flag)` on a float tensor.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_rearrangeBatchMemsFlag(
+    env: *mut JNIEnv,
+    _class: jclass,
+    input: jlong,
+) -> jlong {
+    // SAFETY: upheld by the JVM
+    unsafe {
+        run(env, 0, |_| match tensor_ref(input, "input")? {
+            Tensor::F32(a) => Ok(into_handle(Tensor::from(rearrange_batch_mems_flag(a)?))),
+            other => Err(unsupported("rearrangeBatchMemsFlag", other)),
+        })
+    }
+}
+
+/// `static native long softmax(long input, long dim)` on a float tensor.
+///
+/// # Safety
+///
+/// See the [module documentation](self).
+#[unsafe(no_mangle)]
+pub unsafe extern "system" fn Java_com_rustops_RustOps_softmax(
+    env: *mut JNIEnv,
+    _class: jclass,
+    input: jlong,
+    dim: jlong,
+) -> jlong {
+    // SAFETY: upheld by the JVM
+    unsafe {
+        run(env, 0, |_| match tensor_ref(input, "input")? {
+            Tensor::F32(a) => Ok(into_handle(Tensor::from(softmax(a, dim_arg(dim)?)?))),
+            other => Err(unsupported("softmax", other)),
+        })
+    }
+}
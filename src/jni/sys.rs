@@ -0,0 +1,96 @@
+//! The subset of `jni.h` used by the bindings.
+//!
+//! Only the entries of the JNI function table that the bindings call are typed; the rest are
+//! kept as opaque padding so that every typed entry sits at its index from `jni.h`.
+#![allow(non_camel_case_types)]
+
+use std::ffi::{c_char, c_void};
+
+pub type jint = i32;
+pub type jlong = i64;
+pub type jboolean = u8;
+pub type jsize = jint;
+
+pub type jobject = *mut c_void;
+pub type jclass = jobject;
+pub type jstring = jobject;
+pub type jarray = jobject;
+pub type jlongArray = jarray;
+
+pub const JNI_FALSE: jboolean = 0;
+pub const JNI_TRUE: jboolean = 1;
+
+/// What native methods receive as their first argument: a pointer to the function table.
+pub type JNIEnv = *const JNINativeInterface;
+
+/// Padding for table entries the bindings do not call.
+type Unused<const N: usize> = [*const c_void; N];
+
+/// The JNI function table, `struct JNINativeInterface_` in `jni.h`.
+#[repr(C)]
+pub struct JNINativeInterface {
+    _unused_0: Unused<6>,
+    pub find_class: Option<unsafe extern "system" fn(*mut JNIEnv, *const c_char) -> jclass>,
+    _unused_7: Unused<7>,
+    pub throw_new: Option<unsafe extern "system" fn(*mut JNIEnv, jclass, *const c_char) -> jint>,
+    _unused_15: Unused<8>,
+    pub delete_local_ref: Option<unsafe extern "system" fn(*mut JNIEnv, jobject)>,
+    _unused_24: Unused<145>,
+    pub get_string_utf_chars:
+        Option<unsafe extern "system" fn(*mut JNIEnv, jstring, *mut jboolean) -> *const c_char>,
+    pub release_string_utf_chars:
+        Option<unsafe extern "system" fn(*mut JNIEnv, jstring, *const c_char)>,
+    pub get_array_length: Option<unsafe extern "system" fn(*mut JNIEnv, jarray) -> jsize>,
+    _unused_172: Unused<8>,
+    pub new_long_array: Option<unsafe extern "system" fn(*mut JNIEnv, jsize) -> jlongArray>,
+    _unused_181: Unused<23>,
+    pub get_long_array_region:
+        Option<unsafe extern "system" fn(*mut JNIEnv, jlongArray, jsize, jsize, *mut jlong)>,
+    _unused_205: Unused<7>,
+    pub set_long_array_region:
+        Option<unsafe extern "system" fn(*mut JNIEnv, jlongArray, jsize, jsize, *const jlong)>,
+    _unused_213: Unused<15>,
+    pub exception_check: Option<unsafe extern "system" fn(*mut JNIEnv) -> jboolean>,
+    _unused_229: Unused<1>,
+    pub get_direct_buffer_address:
+        Option<unsafe extern "system" fn(*mut JNIEnv, jobject) -> *mut c_void>,
+    pub get_direct_buffer_capacity:
+        Option<unsafe extern "system" fn(*mut JNIEnv, jobject) -> jlong>,
+    _unused_232: Unused<2>,
+}
+
+// The table has 234 entries as of JNI 9
+const _: () = assert!(
+    std::mem::size_of::<JNINativeInterface>() == 234 * std::mem::size_of::<*const c_void>()
+);
+
+impl JNINativeInterface {
+    /// A table without any functions, for building test doubles of a JVM.
+    pub fn empty() -> Self {
+        const NULL: *const c_void = std::ptr::null();
+        JNINativeInterface {
+            _unused_0: [NULL; 6],
+            find_class: None,
+            _unused_7: [NULL; 7],
+            throw_new: None,
+            _unused_15: [NULL; 8],
+            delete_local_ref: None,
+            _unused_24: [NULL; 145],
+            get_string_utf_chars: None,
+            release_string_utf_chars: None,
+            get_array_length: None,
+            _unused_172: [NULL; 8],
+            new_long_array: None,
+            _unused_181: [NULL; 23],
+            get_long_array_region: None,
+            _unused_205: [NULL; 7],
+            set_long_array_region: None,
+            _unused_213: [NULL; 15],
+            exception_check: None,
+            _unused_229: [NULL; 1],
+            get_direct_buffer_address: None,
+            get_direct_buffer_capacity: None,
+            _unused_232: [NULL; 2],
+        }
+    }
+}
@@ -0,0 +1,467 @@
+use super::LazyError;
+use super::plan::{Plan, compile};
+use crate::functions::dim::normalize_dim;
+use crate::functions::matmul::broadcast_shapes;
+use crate::functions::reshape::infer_shape;
+use ndarray::ArrayD;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum UnaryOp {
+    Abs,
+    Neg,
+    Exp,
+    Ln,
+    Sqrt,
+    Relu,
+    Tanh,
+    Sigmoid,
+    AddScalar(f32),
+    MulScalar(f32),
+}
+
+impl UnaryOp {
+    pub(super) fn apply(self, x: f32) -> f32 {
+        match self {
+            UnaryOp::Abs => x.abs(),
+            UnaryOp::Neg => -x,
+            UnaryOp::Exp => x.exp(),
+            UnaryOp::Ln => x.ln(),
+            UnaryOp::Sqrt => x.sqrt(),
+            UnaryOp::Relu => x.max(0.0),
+            UnaryOp::Tanh => x.tanh(),
+            UnaryOp::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            UnaryOp::AddScalar(c) => x + c,
+            UnaryOp::MulScalar(c) => x * c,
+        }
+    }
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnaryOp::Abs => write!(f, "abs"),
+            UnaryOp::Neg => write!(f, "neg"),
+            UnaryOp::Exp => write!(f, "exp"),
+            UnaryOp::Ln => write!(f, "ln"),
+            UnaryOp::Sqrt => write!(f, "sqrt"),
+            UnaryOp::Relu => write!(f, "relu"),
+            UnaryOp::Tanh => write!(f, "tanh"),
+            UnaryOp::Sigmoid => write!(f, "sigmoid"),
+            UnaryOp::AddScalar(c) => write!(f, "add {c}"),
+            UnaryOp::MulScalar(c) => write!(f, "mul {c}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl BinaryOp {
+    pub(super) fn apply(self, a: f32, b: f32) -> f32 {
+        match self {
+            BinaryOp::Add => a + b,
+            BinaryOp::Sub => a - b,
+            BinaryOp::Mul => a * b,
+            BinaryOp::Div => a / b,
+        }
+    }
+}
+
+impl fmt::Display for BinaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BinaryOp::Add => "add",
+            BinaryOp::Sub => "sub",
+            BinaryOp::Mul => "mul",
+            BinaryOp::Div => "div",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum ReduceOp {
+    Sum,
+    Mean,
+    Max,
+}
+
+impl ReduceOp {
+    pub(super) fn identity(self) -> f32 {
+        match self {
+            ReduceOp::Sum | ReduceOp::Mean => 0.0,
+            ReduceOp::Max => f32::NEG_INFINITY,
+        }
+    }
+
+    pub(super) fn combine(self, acc: f32, x: f32) -> f32 {
+        match self {
+            ReduceOp::Sum | ReduceOp::Mean => acc + x,
+            // Like `torch.amax`, NaN wins
+            ReduceOp::Max => {
+                if x.is_nan() || x > acc {
+                    x
+                } else {
+                    acc
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for ReduceOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReduceOp::Sum => "sum",
+            ReduceOp::Mean => "mean",
+            ReduceOp::Max => "amax",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Operations that only change how the elements of their input are indexed.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum ViewOp {
+    Permute(Vec<usize>),
+    Unsqueeze(usize),
+    Expand(Vec<usize>),
+    Reshape(Vec<usize>),
+}
+
+pub(super) enum Op {
+    Input(Rc<ArrayD<f32>>),
+    Unary(UnaryOp, usize),
+    Binary(BinaryOp, usize, usize),
+    View(ViewOp, usize),
+    Reduce {
+        op: ReduceOp,
+        axes: Vec<usize>,
+        input: usize,
+    },
+}
+
+pub(super) struct Node {
+    pub(super) op: Op,
+    pub(super) shape: Vec<usize>,
+}
+
+/// Records operations on its expressions without evaluating them.
+///
+/// Nodes are appended in recording order, which is a topological order of the graph.
+#[derive(Default)]
+pub struct Graph {
+    nodes: RefCell<Vec<Node>>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an expression for a concrete array.
+    ///
+    /// Inputs not in standard layout are copied into it once, so kernels can read every input
+    /// through plain strides.
+    pub fn input(&self, value: ArrayD<f32>) -> Expr<'_> {
+        let value = if value.is_standard_layout() {
+            value
+        } else {
+            value.as_standard_layout().into_owned()
+        };
+        let shape = value.shape().to_vec();
+        self.push(Op::Input(Rc::new(value)), shape)
+    }
+
+    /// Number of recorded nodes, inputs included.
+    pub fn len(&self) -> usize {
+        self.nodes.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.borrow().is_empty()
+    }
+
+    fn push(&self, op: Op, shape: Vec<usize>) -> Expr<'_> {
+        let mut nodes = self.nodes.borrow_mut();
+        nodes.push(Node { op, shape });
+        Expr {
+            graph: self,
+            index: nodes.len() - 1,
+        }
+    }
+}
+
+/// A value recorded on a [`Graph`], evaluated only when [`Expr::eval`] is called.
+///
+/// Copying is cheap and yields the same expression.
+#[derive(Clone, Copy)]
+pub struct Expr<'g> {
+    graph: &'g Graph,
+    index: usize,
+}
+
+impl<'g> Expr<'g> {
+    pub fn shape(&self) -> Vec<usize> {
+        self.graph.nodes.borrow()[self.index].shape.clone()
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.graph.nodes.borrow()[self.index].shape.len()
+    }
+
+    pub fn graph(&self) -> &'g Graph {
+        self.graph
+    }
+
+    /// Lowers this expression and everything it depends on into fused kernels.
+    pub fn plan(&self) -> Plan {
+        compile(&self.graph.nodes.borrow(), self.index)
+    }
+
+    /// Computes the value of this expression. Shorthand for `self.plan().eval()`.
+    pub fn eval(&self) -> ArrayD<f32> {
+        self.plan().eval()
+    }
+
+    /// Element-wise absolute value. Mimics `torch.abs`.
+    pub fn abs(&self) -> Expr<'g> {
+        self.unary(UnaryOp::Abs)
+    }
+
+    /// Element-wise negation. Mimics `torch.neg`.
+    pub fn neg(&self) -> Expr<'g> {
+        self.unary(UnaryOp::Neg)
+    }
+
+    /// Element-wise exponential. Mimics `torch.exp`.
+    pub fn exp(&self) -> Expr<'g> {
+        self.unary(UnaryOp::Exp)
+    }
+
+    /// Element-wise natural logarithm. Mimics `torch.log`.
+    pub fn ln(&self) -> Expr<'g> {
+        self.unary(UnaryOp::Ln)
+    }
+
+    /// Element-wise square root. Mimics `torch.sqrt`.
+    pub fn sqrt(&self) -> Expr<'g> {
+        self.unary(UnaryOp::Sqrt)
+    }
+
+    /// Element-wise `max(x, 0)`. Mimics `torch.relu`.
+    pub fn relu(&self) -> Expr<'g> {
+        self.unary(UnaryOp::Relu)
+    }
+
+    /// Element-wise hyperbolic tangent. Mimics `torch.tanh`.
+    pub fn tanh(&self) -> Expr<'g> {
+        self.unary(UnaryOp::Tanh)
+    }
+
+    /// Element-wise logistic function. Mimics `torch.sigmoid`.
+    pub fn sigmoid(&self) -> Expr<'g> {
+        self.unary(UnaryOp::Sigmoid)
+    }
+
+    /// Adds `value` to every element. Mimics `tensor + value`.
+    pub fn add_scalar(&self, value: f32) -> Expr<'g> {
+        self.unary(UnaryOp::AddScalar(value))
+    }
+
+    /// Multiplies every element by `value`. Mimics `tensor * value`.
+    pub fn mul_scalar(&self, value: f32) -> Expr<'g> {
+        self.unary(UnaryOp::MulScalar(value))
+    }
+
+    /// Element-wise `self + other` with broadcasting. Mimics `torch.add`.
+    pub fn add(&self, other: &Expr<'g>) -> Result<Expr<'g>, LazyError> {
+        self.binary(other, BinaryOp::Add)
+    }
+
+    /// Element-wise `self - other` with broadcasting. Mimics `torch.sub`.
+    pub fn sub(&self, other: &Expr<'g>) -> Result<Expr<'g>, LazyError> {
+        self.binary(other, BinaryOp::Sub)
+    }
+
+    /// Element-wise `self * other` with broadcasting. Mimics `torch.mul`.
+    pub fn mul(&self, other: &Expr<'g>) -> Result<Expr<'g>, LazyError> {
+        self.binary(other, BinaryOp::Mul)
+    }
+
+    /// Element-wise `self / other` with broadcasting. Mimics `torch.div`.
+    pub fn div(&self, other: &Expr<'g>) -> Result<Expr<'g>, LazyError> {
+        self.binary(other, BinaryOp::Div)
+    }
+
+    /// Inserts a dimension of size 1 at `dim`. Mimics `torch.unsqueeze`.
+    pub fn unsqueeze(&self, dim: usize) -> Result<Expr<'g>, LazyError> {
+        let mut shape = self.shape();
+        if dim > shape.len() {
+            return Err(LazyError::InvalidDimension {
+                dim: dim as isize,
+                ndim: shape.len(),
+            });
+        }
+        shape.insert(dim, 1);
+        Ok(self.view(ViewOp::Unsqueeze(dim), shape))
+    }
+
+    /// Broadcasts to `shape`, prepending dimensions and repeating those of size 1. Mimics
+    /// `torch.Tensor.expand`.
+    pub fn expand(&self, shape: &[usize]) -> Result<Expr<'g>, LazyError> {
+        let current = self.shape();
+        let lead = shape.len().wrapping_sub(current.len());
+        let valid = shape.len() >= current.len()
+            && current
+                .iter()
+                .zip(&shape[lead..])
+                .all(|(&from, &to)| from == to || from == 1);
+        if !valid {
+            return Err(LazyError::InvalidExpand {
+                shape: current,
+                target: shape.to_vec(),
+            });
+        }
+        Ok(self.view(ViewOp::Expand(shape.to_vec()), shape.to_vec()))
+    }
+
+    /// Inserts a dimension at `dim` and repeats the input `size` times along it. See
+    /// [`crate::functions::expand::expand_at_dim`].
+    pub fn expand_at_dim(&self, dim: usize, size: usize) -> Result<Expr<'g>, LazyError> {
+        let unsqueezed = self.unsqueeze(dim)?;
+        let mut shape = unsqueezed.shape();
+        shape[dim] = size;
+        unsqueezed.expand(&shape)
+    }
+
+    /// Reorders the dimensions so that dimension `i` of the result is `axes[i]` of the input.
+    /// Mimics `torch.permute`.
+    pub fn permute(&self, axes: &[usize]) -> Result<Expr<'g>, LazyError> {
+        let current = self.shape();
+        let ndim = current.len();
+        let mut seen = vec![false; ndim];
+        let valid = axes.len() == ndim
+            && axes
+                .iter()
+                .all(|&axis| axis < ndim && !std::mem::replace(&mut seen[axis], true));
+        if !valid {
+            return Err(LazyError::InvalidPermutation {
+                axes: axes.to_vec(),
+                ndim,
+            });
+        }
+        let shape = axes.iter().map(|&axis| current[axis]).collect();
+        Ok(self.view(ViewOp::Permute(axes.to_vec()), shape))
+    }
+
+    /// Swaps dimensions `dim0` and `dim1`. Mimics `torch.transpose`.
+    pub fn transpose(&self, dim0: usize, dim1: usize) -> Result<Expr<'g>, LazyError> {
+        let ndim = self.ndim();
+        if let Some(&dim) = [dim0, dim1].iter().find(|&&dim| dim >= ndim) {
+            return Err(LazyError::InvalidDimension {
+                dim: dim as isize,
+                ndim,
+            });
+        }
+        let mut axes: Vec<usize> = (0..ndim).collect();
+        axes.swap(dim0, dim1);
+        self.permute(&axes)
+    }
+
+    /// Reshapes to `shape`, where one entry may be `-1`. Mimics `torch.reshape`.
+    pub fn reshape(&self, shape: &[i64]) -> Result<Expr<'g>, LazyError> {
+        let len = self.shape().iter().product();
+        let shape = infer_shape(len, shape)?;
+        Ok(self.view(ViewOp::Reshape(shape.clone()), shape))
+    }
+
+    /// Sums over `dims`, or over every dimension if `dims` is empty. Mimics
+    /// `torch.sum(input, dims, keepdim)`.
+    pub fn sum(&self, dims: &[isize], keepdim: bool) -> Result<Expr<'g>, LazyError> {
+        self.reduce(ReduceOp::Sum, dims, keepdim)
+    }
+
+    /// Averages over `dims`, or over every dimension if `dims` is empty. Mimics
+    /// `torch.mean(input, dims, keepdim)`.
+    pub fn mean(&self, dims: &[isize], keepdim: bool) -> Result<Expr<'g>, LazyError> {
+        self.reduce(ReduceOp::Mean, dims, keepdim)
+    }
+
+    /// Takes the maximum over `dims`, or over every dimension if `dims` is empty. Mimics
+    /// `torch.amax(input, dims, keepdim)`, including that NaN propagates.
+    pub fn amax(&self, dims: &[isize], keepdim: bool) -> Result<Expr<'g>, LazyError> {
+        self.reduce(ReduceOp::Max, dims, keepdim)
+    }
+
+    fn unary(&self, op: UnaryOp) -> Expr<'g> {
+        self.graph.push(Op::Unary(op, self.index), self.shape())
+    }
+
+    fn binary(&self, other: &Expr<'g>, op: BinaryOp) -> Result<Expr<'g>, LazyError> {
+        if !std::ptr::eq(self.graph, other.graph) {
+            return Err(LazyError::DifferentGraphs);
+        }
+        let (left, right) = (self.shape(), other.shape());
+        let shape = broadcast_shapes(&left, &right).ok_or(LazyError::Broadcast { left, right })?;
+        Ok(self
+            .graph
+            .push(Op::Binary(op, self.index, other.index), shape))
+    }
+
+    fn view(&self, op: ViewOp, shape: Vec<usize>) -> Expr<'g> {
+        self.graph.push(Op::View(op, self.index), shape)
+    }
+
+    fn reduce(&self, op: ReduceOp, dims: &[isize], keepdim: bool) -> Result<Expr<'g>, LazyError> {
+        let current = self.shape();
+        let ndim = current.len();
+        let mut axes = Vec::with_capacity(dims.len());
+        for &dim in dims {
+            let axis = normalize_dim(dim, ndim).ok_or(LazyError::InvalidDimension { dim, ndim })?;
+            if axes.contains(&axis) {
+                return Err(LazyError::RepeatedDimension { dim: axis });
+            }
+            axes.push(axis);
+        }
+        if axes.is_empty() {
+            axes = (0..ndim).collect();
+        }
+        axes.sort_unstable();
+        if op == ReduceOp::Max
+            && let Some(&dim) = axes.iter().find(|&&axis| current[axis] == 0)
+        {
+            return Err(LazyError::EmptyReduction { dim });
+        }
+        let shape = (0..ndim)
+            .filter_map(|axis| match axes.contains(&axis) {
+                false => Some(current[axis]),
+                true if keepdim => Some(1),
+                true => None,
+            })
+            .collect();
+        let reduce = Op::Reduce {
+            op,
+            axes,
+            input: self.index,
+        };
+        Ok(self.graph.push(reduce, shape))
+    }
+}
+
+impl fmt::Debug for Expr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Expr")
+            .field("index", &self.index)
+            .field("shape", &self.shape())
+            .finish()
+    }
+}
@@ -0,0 +1,66 @@
+//! Deferred evaluation of element-wise chains with operator fusion.
+//!
+//! Operations on [`Expr`]s only record nodes on a [`Graph`]. [`Expr::plan`] lowers the graph
+//! into as few kernels as possible: element-wise and broadcasting ops fuse into a single pass
+//! over memory, `reshape`, `transpose`, `permute`, `unsqueeze` and `expand` only change the
+//! strides each kernel reads its inputs with, and a reduction consumes its fused input
+//! directly. [`Plan::eval`] then allocates nothing but the kernel outputs, so
+//! `abs -> expand -> mul -> sum` touches each input once and materializes only the result.
+//! Printing a [`Plan`] dumps its kernels.
+//!
+//! ```
+//! use RustOps::lazy::Graph;
+//! use ndarray::array;
+//!
+//! let graph = Graph::new();
+//! let x = graph.input(array![1.0f32, -2.0, 3.0].into_dyn());
+//! let w = graph.input(array![[1.0f32], [2.0]].into_dyn());
+//! let y = x.abs().expand_at_dim(0, 2).unwrap().mul(&w).unwrap();
+//! let plan = y.sum(&[1], false).unwrap().plan();
+//! assert_eq!(plan.num_kernels(), 1);
+//! assert_eq!(plan.eval(), array![6.0f32, 12.0].into_dyn());
+//! ```
+//!
+//! A node read by several others is computed once into its own buffer unless it is an input
+//! or a view of one, and a reshape that strides cannot express (such as flattening a
+//! transposed result) materializes its input first.
+
+mod graph;
+mod plan;
+
+pub use graph::{Expr, Graph};
+pub use plan::Plan;
+
+use crate::functions::reshape::ReshapeError;
+use thiserror::Error;
+
+/// Error type for recording operations on a [`Graph`].
+#[derive(Error, Debug, PartialEq)]
+pub enum LazyError {
+    #[error("Expressions recorded on different graphs cannot be combined")]
+    DifferentGraphs,
+
+    #[error("Shapes {left:?} and {right:?} cannot be broadcast together")]
+    Broadcast { left: Vec<usize>, right: Vec<usize> },
+
+    #[error("Dimension {dim} is out of bounds for a tensor with {ndim} dimensions")]
+    InvalidDimension { dim: isize, ndim: usize },
+
+    #[error("Dimension {dim} appears more than once")]
+    RepeatedDimension { dim: usize },
+
+    #[error("Cannot expand shape {shape:?} to {target:?}")]
+    InvalidExpand {
+        shape: Vec<usize>,
+        target: Vec<usize>,
+    },
+
+    #[error("{axes:?} is not a permutation of the {ndim} dimensions")]
+    InvalidPermutation { axes: Vec<usize>, ndim: usize },
+
+    #[error("Cannot take the maximum over dimension {dim}, which has size 0")]
+    EmptyReduction { dim: usize },
+
+    #[error(transparent)]
+    Reshape(#[from] ReshapeError),
+}
@@ -0,0 +1,546 @@
+use super::graph::{BinaryOp, Node, Op, ReduceOp, UnaryOp, ViewOp};
+use ndarray::{ArrayD, IxDyn};
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// Storage a kernel reads from: a graph input or the output of an earlier kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Buffer {
+    Input(usize),
+    Kernel(usize),
+}
+
+impl fmt::Display for Buffer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Buffer::Input(i) => write!(f, "input{i}"),
+            Buffer::Kernel(k) => write!(f, "kernel{k}"),
+        }
+    }
+}
+
+/// A strided read of a standard-layout buffer: element `index` is at
+/// `sum(index[d] * strides[d])`.
+#[derive(Debug, Clone)]
+struct Leaf {
+    buffer: Buffer,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+}
+
+impl Leaf {
+    fn contiguous(buffer: Buffer, shape: &[usize]) -> Self {
+        Leaf {
+            buffer,
+            shape: shape.to_vec(),
+            strides: contiguous_strides(shape),
+        }
+    }
+
+    /// Applies a view op by changing the strides, or returns `false` if strides cannot
+    /// express it.
+    fn apply(&mut self, view: &ViewOp) -> bool {
+        match view {
+            ViewOp::Permute(axes) => {
+                self.shape = axes.iter().map(|&axis| self.shape[axis]).collect();
+                self.strides = axes.iter().map(|&axis| self.strides[axis]).collect();
+            }
+            ViewOp::Unsqueeze(dim) => {
+                self.shape.insert(*dim, 1);
+                self.strides.insert(*dim, 0);
+            }
+            ViewOp::Expand(shape) => self.broadcast(shape),
+            ViewOp::Reshape(shape) => match reshape_strides(&self.shape, &self.strides, shape) {
+                Some(strides) => {
+                    self.shape = shape.clone();
+                    self.strides = strides;
+                }
+                None => return false,
+            },
+        }
+        true
+    }
+
+    /// Prepends dimensions and repeats those of size 1 to reach `shape`, with stride 0.
+    fn broadcast(&mut self, shape: &[usize]) {
+        let lead = shape.len() - self.shape.len();
+        let mut strides = vec![0; lead];
+        // Dimensions of size 1 may be stretched, so they read the same element throughout
+        for (&from, &stride) in self.shape.iter().zip(&self.strides) {
+            strides.push(if from == 1 { 0 } else { stride });
+        }
+        self.shape = shape.to_vec();
+        self.strides = strides;
+    }
+}
+
+/// A fused element-wise expression whose leaves all have the expression's shape.
+enum Fused {
+    Load(Leaf),
+    Unary(UnaryOp, Box<Fused>),
+    Binary(BinaryOp, Box<Fused>, Box<Fused>),
+}
+
+impl Fused {
+    fn for_each_leaf(&mut self, f: &mut impl FnMut(&mut Leaf) -> bool) -> bool {
+        match self {
+            Fused::Load(leaf) => f(leaf),
+            Fused::Unary(_, input) => input.for_each_leaf(f),
+            Fused::Binary(_, left, right) => left.for_each_leaf(f) && right.for_each_leaf(f),
+        }
+    }
+}
+
+/// One step of a kernel, applied to a whole row of the iteration shape at once.
+#[derive(Debug, Clone, Copy)]
+enum Instr {
+    Load {
+        leaf: usize,
+        dst: usize,
+    },
+    Unary {
+        op: UnaryOp,
+        reg: usize,
+    },
+    /// Stores the result in `lhs`.
+    Binary {
+        op: BinaryOp,
+        lhs: usize,
+        rhs: usize,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Reduction {
+    op: ReduceOp,
+    axes: Vec<usize>,
+}
+
+/// A single pass over `shape` that evaluates `instrs` row by row and either stores the result
+/// or folds it into `out_shape` with `reduction`.
+#[derive(Debug, Clone)]
+struct Kernel {
+    shape: Vec<usize>,
+    leaves: Vec<Leaf>,
+    instrs: Vec<Instr>,
+    result: usize,
+    reduction: Option<Reduction>,
+    out_shape: Vec<usize>,
+}
+
+impl Kernel {
+    fn new(
+        expr: Fused,
+        shape: Vec<usize>,
+        reduction: Option<Reduction>,
+        out_shape: Vec<usize>,
+    ) -> Self {
+        let mut kernel = Kernel {
+            shape,
+            leaves: Vec::new(),
+            instrs: Vec::new(),
+            result: 0,
+            reduction,
+            out_shape,
+        };
+        kernel.result = kernel.emit(expr);
+        kernel
+    }
+
+    /// Appends the instructions computing `expr` and returns the register holding it. Every
+    /// load gets its own register and operations write into their left operand, so the
+    /// operands of a binary op never share a register.
+    fn emit(&mut self, expr: Fused) -> usize {
+        match expr {
+            Fused::Load(leaf) => {
+                let dst = self.leaves.len();
+                self.leaves.push(leaf);
+                self.instrs.push(Instr::Load { leaf: dst, dst });
+                dst
+            }
+            Fused::Unary(op, input) => {
+                let reg = self.emit(*input);
+                self.instrs.push(Instr::Unary { op, reg });
+                reg
+            }
+            Fused::Binary(op, left, right) => {
+                let lhs = self.emit(*left);
+                let rhs = self.emit(*right);
+                self.instrs.push(Instr::Binary { op, lhs, rhs });
+                lhs
+            }
+        }
+    }
+
+    fn run(&self, inputs: &[Rc<ArrayD<f32>>], outputs: &[ArrayD<f32>]) -> ArrayD<f32> {
+        let data: Vec<&[f32]> = self
+            .leaves
+            .iter()
+            .map(|leaf| {
+                let array = match leaf.buffer {
+                    Buffer::Input(i) => &*inputs[i],
+                    Buffer::Kernel(k) => &outputs[k],
+                };
+                array.as_slice().expect("buffers are in standard layout")
+            })
+            .collect();
+
+        // Rows run along the last dimension; a 0-dimensional shape is a single row of one
+        let ndim = self.shape.len();
+        let lead = ndim.saturating_sub(1);
+        let row_len = self.shape.last().copied().unwrap_or(1);
+        let len: usize = self.shape.iter().product();
+        let rows = len.checked_div(row_len).unwrap_or(0);
+
+        // Reduced dimensions get stride 0, so every row adds into the elements it reduces to
+        let out_strides = match &self.reduction {
+            None => contiguous_strides(&self.shape),
+            Some(reduction) => {
+                let kept: Vec<usize> = (0..ndim)
+                    .map(|axis| match reduction.axes.contains(&axis) {
+                        true => 1,
+                        false => self.shape[axis],
+                    })
+                    .collect();
+                let mut strides = contiguous_strides(&kept);
+                for &axis in &reduction.axes {
+                    strides[axis] = 0;
+                }
+                strides
+            }
+        };
+        let out_step = out_strides.last().copied().unwrap_or(0);
+        let identity = self.reduction.as_ref().map_or(0.0, |r| r.op.identity());
+        let mut out = vec![identity; self.out_shape.iter().product()];
+
+        let mut registers = vec![vec![0.0f32; row_len]; self.leaves.len()];
+        let mut index = vec![0usize; lead];
+        for _ in 0..rows {
+            for instr in &self.instrs {
+                match *instr {
+                    Instr::Load { leaf, dst } => {
+                        let strides = &self.leaves[leaf].strides;
+                        let base = dot(&index, strides);
+                        let step = strides.last().copied().unwrap_or(0);
+                        for (j, x) in registers[dst].iter_mut().enumerate() {
+                            *x = data[leaf][base + j * step];
+                        }
+                    }
+                    Instr::Unary { op, reg } => {
+                        for x in registers[reg].iter_mut() {
+                            *x = op.apply(*x);
+                        }
+                    }
+                    Instr::Binary { op, lhs, rhs } => {
+                        let right = std::mem::take(&mut registers[rhs]);
+                        for (x, &y) in registers[lhs].iter_mut().zip(&right) {
+                            *x = op.apply(*x, y);
+                        }
+                        registers[rhs] = right;
+                    }
+                }
+            }
+
+            let row = &registers[self.result];
+            let base = dot(&index, &out_strides);
+            match &self.reduction {
+                None => out[base..base + row_len].copy_from_slice(row),
+                Some(reduction) => {
+                    for (j, &x) in row.iter().enumerate() {
+                        let slot = &mut out[base + j * out_step];
+                        *slot = reduction.op.combine(*slot, x);
+                    }
+                }
+            }
+
+            for d in (0..lead).rev() {
+                index[d] += 1;
+                if index[d] < self.shape[d] {
+                    break;
+                }
+                index[d] = 0;
+            }
+        }
+
+        if let Some(reduction) = &self.reduction
+            && reduction.op == ReduceOp::Mean
+        {
+            let count: usize = reduction
+                .axes
+                .iter()
+                .map(|&axis| self.shape[axis])
+                .product();
+            for x in &mut out {
+                *x /= count as f32;
+            }
+        }
+        ArrayD::from_shape_vec(IxDyn(&self.out_shape), out).expect("output has out_shape elements")
+    }
+}
+
+/// The kernels computing one expression, in execution order. The last one yields the result.
+///
+/// Displaying a plan dumps its inputs and, for every kernel, the shape it iterates over, the
+/// reduction it performs and its per-row instructions, with `%n` naming row registers.
+#[derive(Debug, Clone)]
+pub struct Plan {
+    inputs: Vec<Rc<ArrayD<f32>>>,
+    kernels: Vec<Kernel>,
+}
+
+impl Plan {
+    /// Number of passes over memory, i.e. arrays allocated, needed to evaluate the plan.
+    pub fn num_kernels(&self) -> usize {
+        self.kernels.len()
+    }
+
+    /// Runs every kernel and returns the output of the last one.
+    pub fn eval(&self) -> ArrayD<f32> {
+        let mut outputs = Vec::with_capacity(self.kernels.len());
+        for kernel in &self.kernels {
+            let output = kernel.run(&self.inputs, &outputs);
+            outputs.push(output);
+        }
+        outputs.pop().expect("a plan has at least one kernel")
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, input) in self.inputs.iter().enumerate() {
+            writeln!(f, "input{i}: {:?}", input.shape())?;
+        }
+        for (k, kernel) in self.kernels.iter().enumerate() {
+            write!(f, "kernel{k}: {:?}", kernel.shape)?;
+            if let Some(reduction) = &kernel.reduction {
+                write!(f, " {} over {:?}", reduction.op, reduction.axes)?;
+            }
+            writeln!(f, " -> {:?}", kernel.out_shape)?;
+            for instr in &kernel.instrs {
+                match *instr {
+                    Instr::Load { leaf, dst } => {
+                        let leaf = &kernel.leaves[leaf];
+                        writeln!(
+                            f,
+                            "  %{dst} = load {} strides {:?}",
+                            leaf.buffer, leaf.strides
+                        )?;
+                    }
+                    Instr::Unary { op, reg } => writeln!(f, "  %{reg} = {op} %{reg}")?,
+                    Instr::Binary { op, lhs, rhs } => {
+                        writeln!(f, "  %{lhs} = {op} %{lhs}, %{rhs}")?
+                    }
+                }
+            }
+            writeln!(f, "  store %{}", kernel.result)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lowers the graph reachable from `root` into a [`Plan`].
+pub(super) fn compile(nodes: &[Node], root: usize) -> Plan {
+    let mut lowering = Lowering {
+        nodes,
+        uses: count_uses(nodes, root),
+        inputs: Vec::new(),
+        input_ids: HashMap::new(),
+        materialized: HashMap::new(),
+        kernels: Vec::new(),
+    };
+    lowering.materialize(root);
+    Plan {
+        inputs: lowering.inputs,
+        kernels: lowering.kernels,
+    }
+}
+
+/// Counts how many edges of the graph reachable from `root` point at every node.
+fn count_uses(nodes: &[Node], root: usize) -> Vec<usize> {
+    let mut uses = vec![0; nodes.len()];
+    let mut visited = vec![false; nodes.len()];
+    let mut stack = vec![root];
+    visited[root] = true;
+    while let Some(id) = stack.pop() {
+        let children = match &nodes[id].op {
+            Op::Input(_) => vec![],
+            Op::Unary(_, a) | Op::View(_, a) | Op::Reduce { input: a, .. } => vec![*a],
+            Op::Binary(_, a, b) => vec![*a, *b],
+        };
+        for child in children {
+            uses[child] += 1;
+            if !std::mem::replace(&mut visited[child], true) {
+                stack.push(child);
+            }
+        }
+    }
+    uses
+}
+
+struct Lowering<'a> {
+    nodes: &'a [Node],
+    uses: Vec<usize>,
+    inputs: Vec<Rc<ArrayD<f32>>>,
+    input_ids: HashMap<usize, usize>,
+    materialized: HashMap<usize, usize>,
+    kernels: Vec<Kernel>,
+}
+
+impl Lowering<'_> {
+    /// Returns the kernel computing node `id`, adding it and the kernels it reads first.
+    fn materialize(&mut self, id: usize) -> usize {
+        if let Some(&kernel) = self.materialized.get(&id) {
+            return kernel;
+        }
+        let nodes = self.nodes;
+        let node = &nodes[id];
+        let kernel = match &node.op {
+            Op::Reduce {
+                op, axes, input, ..
+            } => Kernel::new(
+                self.lower(*input),
+                nodes[*input].shape.clone(),
+                Some(Reduction {
+                    op: *op,
+                    axes: axes.clone(),
+                }),
+                node.shape.clone(),
+            ),
+            _ => Kernel::new(
+                self.lower_node(id),
+                node.shape.clone(),
+                None,
+                node.shape.clone(),
+            ),
+        };
+        self.kernels.push(kernel);
+        let index = self.kernels.len() - 1;
+        self.materialized.insert(id, index);
+        index
+    }
+
+    /// Lowers node `id` as an operand of a fused expression.
+    ///
+    /// Reductions, and computed nodes read more than once, are kernel boundaries: they are
+    /// materialized once and read back, rather than recomputed by every reader.
+    fn lower(&mut self, id: usize) -> Fused {
+        let boundary = match self.nodes[id].op {
+            Op::Reduce { .. } => true,
+            _ => self.uses[id] > 1 && !self.is_free(id),
+        };
+        match boundary {
+            true => self.load(id),
+            false => self.lower_node(id),
+        }
+    }
+
+    /// Lowers node `id` itself into a fused expression.
+    fn lower_node(&mut self, id: usize) -> Fused {
+        let nodes = self.nodes;
+        let node = &nodes[id];
+        match &node.op {
+            Op::Input(value) => {
+                let next = self.inputs.len();
+                let input = *self.input_ids.entry(id).or_insert(next);
+                if input == next {
+                    self.inputs.push(Rc::clone(value));
+                }
+                Fused::Load(Leaf::contiguous(Buffer::Input(input), &node.shape))
+            }
+            Op::Unary(op, input) => Fused::Unary(*op, Box::new(self.lower(*input))),
+            Op::Binary(op, left, right) => {
+                let mut left = self.lower(*left);
+                let mut right = self.lower(*right);
+                for operand in [&mut left, &mut right] {
+                    operand.for_each_leaf(&mut |leaf| {
+                        leaf.broadcast(&node.shape);
+                        true
+                    });
+                }
+                Fused::Binary(*op, Box::new(left), Box::new(right))
+            }
+            Op::View(view, input) => {
+                let mut expr = self.lower(*input);
+                if !expr.for_each_leaf(&mut |leaf| leaf.apply(view)) {
+                    // A contiguous buffer can always be reshaped
+                    expr = self.load(*input);
+                    expr.for_each_leaf(&mut |leaf| leaf.apply(view));
+                }
+                expr
+            }
+            Op::Reduce { .. } => unreachable!("reductions are always materialized"),
+        }
+    }
+
+    fn load(&mut self, id: usize) -> Fused {
+        let kernel = self.materialize(id);
+        Fused::Load(Leaf::contiguous(
+            Buffer::Kernel(kernel),
+            &self.nodes[id].shape,
+        ))
+    }
+
+    /// Whether node `id` is an input or a view of one, which costs nothing to read twice.
+    fn is_free(&self, id: usize) -> bool {
+        match &self.nodes[id].op {
+            Op::Input(_) => true,
+            Op::View(_, input) => self.is_free(*input),
+            _ => false,
+        }
+    }
+}
+
+fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for d in (0..shape.len().saturating_sub(1)).rev() {
+        strides[d] = strides[d + 1] * shape[d + 1];
+    }
+    strides
+}
+
+fn dot(index: &[usize], strides: &[usize]) -> usize {
+    index.iter().zip(strides).map(|(i, s)| i * s).sum()
+}
+
+/// Computes strides that view the elements of `shape`/`strides` in row-major order as
+/// `new_shape`, or `None` if there are none. Follows `at::detail::computeStride`: dimensions
+/// are grouped into chunks that are contiguous with each other, and each chunk must split
+/// into whole dimensions of the new shape.
+fn reshape_strides(shape: &[usize], strides: &[usize], new_shape: &[usize]) -> Option<Vec<usize>> {
+    if shape.iter().product::<usize>() == 0 {
+        return Some(contiguous_strides(new_shape));
+    }
+    let mut new_strides = vec![0; new_shape.len()];
+    let mut view_d = new_shape.len() as isize - 1;
+    let mut chunk_base_stride = strides.last().copied().unwrap_or(1);
+    let mut tensor_numel = 1;
+    let mut view_numel = 1;
+    for tensor_d in (0..shape.len()).rev() {
+        tensor_numel *= shape[tensor_d];
+        let chunk_ends = tensor_d == 0
+            || (shape[tensor_d - 1] != 1
+                && strides[tensor_d - 1] != tensor_numel * chunk_base_stride);
+        if chunk_ends {
+            while view_d >= 0 && (view_numel < tensor_numel || new_shape[view_d as usize] == 1) {
+                new_strides[view_d as usize] = view_numel * chunk_base_stride;
+                view_numel *= new_shape[view_d as usize];
+                view_d -= 1;
+            }
+            if view_numel != tensor_numel {
+                return None;
+            }
+            if tensor_d > 0 {
+                chunk_base_stride = strides[tensor_d - 1];
+                tensor_numel = 1;
+                view_numel = 1;
+            }
+        }
+    }
+    // Trailing dimensions of size 1 of the new shape are left when the old shape is 0-dim
+    while view_d >= 0 && new_shape[view_d as usize] == 1 {
+        new_strides[view_d as usize] = 1;
+        view_d -= 1;
+    }
+    (view_d == -1).then_some(new_strides)
+}
@@ -1 +1,16 @@
+pub mod autograd;
+pub mod ffi;
 pub mod functions;
+pub mod io;
+#[cfg(feature = "jni")]
+pub mod jni;
+pub mod lazy;
+pub mod linalg;
+pub mod nn;
+pub mod onnx;
+pub mod pool;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod quantization;
+pub mod rng;
+pub mod sampling;
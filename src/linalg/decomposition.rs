@@ -0,0 +1,435 @@
+use super::{Batched, LinalgError, assemble};
+use ndarray::{Array1, Array2, ArrayD, ArrayView2, Axis, NdFloat, s};
+
+/// Maximum number of Jacobi sweeps before giving up on convergence.
+const MAX_SWEEPS: usize = 100;
+
+/// Which factors [`qr`] returns, mirroring the `mode` argument of `torch.linalg.qr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QrMode {
+    /// `Q` of shape `(*, m, k)` and `R` of shape `(*, k, n)` with `k = min(m, n)`.
+    #[default]
+    Reduced,
+    /// `Q` of shape `(*, m, m)` and `R` of shape `(*, m, n)`.
+    Complete,
+    /// Only `R` of shape `(*, k, n)`; `Q` is returned empty.
+    R,
+}
+
+/// Computes the Cholesky decomposition of a batch of symmetric positive-definite matrices.
+///
+/// Mimics `torch.linalg.cholesky(a, upper)`. Only the lower triangle of `a` is read.
+///
+/// # Arguments
+///
+/// * `a`: Input of shape `(*, n, n)`.
+/// * `upper`: Return the upper triangular factor `L^T` instead of `L`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: `L` with `a = L @ L^T` (or `L^T` when `upper`), of shape `(*, n, n)`.
+/// * `Err(LinalgError)`: If `a` is not a batch of square matrices or a matrix is not positive-definite.
+pub fn cholesky<A>(a: &ArrayD<A>, upper: bool) -> Result<ArrayD<A>, LinalgError>
+where
+    A: NdFloat,
+{
+    let batched = Batched::square(a.view())?;
+    let n = batched.rows();
+    let mut factors = Vec::with_capacity(batched.matrices.len_of(Axis(0)));
+    for (batch, m) in batched.iter().enumerate() {
+        let l =
+            cholesky_lower(m).map_err(|order| LinalgError::NotPositiveDefinite { batch, order })?;
+        factors.push(if upper { l.reversed_axes() } else { l });
+    }
+    Ok(assemble(&batched.batch, &[n, n], &factors))
+}
+
+/// Computes the QR decomposition of a batch of matrices with Householder reflections.
+///
+/// Mimics `torch.linalg.qr(a, mode)`, including LAPACK's sign convention for `R`.
+///
+/// # Arguments
+///
+/// * `a`: Input of shape `(*, m, n)`.
+/// * `mode`: Which factors to return, see [`QrMode`].
+///
+/// # Returns
+///
+/// * `Ok((ArrayD<A>, ArrayD<A>))`: The orthogonal factor `Q` and upper triangular factor `R`.
+/// * `Err(LinalgError)`: If `a` has fewer than 2 dimensions.
+pub fn qr<A>(a: &ArrayD<A>, mode: QrMode) -> Result<(ArrayD<A>, ArrayD<A>), LinalgError>
+where
+    A: NdFloat,
+{
+    let batched = Batched::new(a.view())?;
+    let (m, n) = (batched.rows(), batched.cols());
+    let k = m.min(n);
+    let (mut qs, mut rs) = (Vec::new(), Vec::new());
+    for matrix in batched.iter() {
+        let (q, r) = householder_qr(matrix);
+        match mode {
+            QrMode::Complete => {
+                qs.push(q);
+                rs.push(r);
+            }
+            QrMode::Reduced | QrMode::R => {
+                qs.push(q.slice(s![.., ..k]).to_owned());
+                rs.push(r.slice(s![..k, ..]).to_owned());
+            }
+        }
+    }
+    let q = match mode {
+        QrMode::Complete => assemble(&batched.batch, &[m, m], &qs),
+        QrMode::Reduced => assemble(&batched.batch, &[m, k], &qs),
+        QrMode::R => ArrayD::zeros(ndarray::IxDyn(&[0])),
+    };
+    let r_rows = if mode == QrMode::Complete { m } else { k };
+    Ok((q, assemble(&batched.batch, &[r_rows, n], &rs)))
+}
+
+/// Computes the singular value decomposition of a batch of matrices.
+///
+/// Mimics `torch.linalg.svd(a, full_matrices)` using one-sided Jacobi rotations. Singular
+/// values are returned in descending order. Singular vectors are only unique up to sign.
+///
+/// # Arguments
+///
+/// * `a`: Input of shape `(*, m, n)`.
+/// * `full_matrices`: Return square `U` and `Vh` instead of the reduced factors.
+///
+/// # Returns
+///
+/// * `Ok((U, S, Vh))`: With `a = U @ diag(S) @ Vh`. For `k = min(m, n)`, `U` has shape
+///   `(*, m, k)` (or `(*, m, m)`), `S` has shape `(*, k)` and `Vh` has shape `(*, k, n)` (or `(*, n, n)`).
+/// * `Err(LinalgError)`: If `a` has fewer than 2 dimensions or the iteration does not converge.
+#[allow(clippy::type_complexity)]
+pub fn svd<A>(
+    a: &ArrayD<A>,
+    full_matrices: bool,
+) -> Result<(ArrayD<A>, ArrayD<A>, ArrayD<A>), LinalgError>
+where
+    A: NdFloat,
+{
+    let batched = Batched::new(a.view())?;
+    let (m, n) = (batched.rows(), batched.cols());
+    let k = m.min(n);
+    let (mut us, mut ss, mut vhs) = (Vec::new(), Vec::new(), Vec::new());
+    for (batch, matrix) in batched.iter().enumerate() {
+        let (u, sigma, vh) =
+            jacobi_svd(matrix, full_matrices).ok_or(LinalgError::NoConvergence { batch })?;
+        us.push(u);
+        ss.push(sigma);
+        vhs.push(vh);
+    }
+    let (u_cols, vh_rows) = if full_matrices { (m, n) } else { (k, k) };
+    Ok((
+        assemble(&batched.batch, &[m, u_cols], &us),
+        assemble(&batched.batch, &[k], &ss),
+        assemble(&batched.batch, &[vh_rows, n], &vhs),
+    ))
+}
+
+/// Computes the eigendecomposition of a batch of symmetric matrices.
+///
+/// Mimics `torch.linalg.eigh(a, UPLO)` using cyclic Jacobi rotations. Eigenvalues are
+/// returned in ascending order; eigenvectors are only unique up to sign.
+///
+/// # Arguments
+///
+/// * `a`: Input of shape `(*, n, n)`.
+/// * `upper`: Read the upper triangle of `a` (`UPLO="U"`) instead of the lower one.
+///
+/// # Returns
+///
+/// * `Ok((ArrayD<A>, ArrayD<A>))`: Eigenvalues of shape `(*, n)` and eigenvectors as the
+///   columns of a `(*, n, n)` array.
+/// * `Err(LinalgError)`: If `a` is not a batch of square matrices or the iteration does not converge.
+pub fn eigh<A>(a: &ArrayD<A>, upper: bool) -> Result<(ArrayD<A>, ArrayD<A>), LinalgError>
+where
+    A: NdFloat,
+{
+    let batched = Batched::square(a.view())?;
+    let n = batched.rows();
+    let (mut values, mut vectors) = (Vec::new(), Vec::new());
+    for (batch, matrix) in batched.iter().enumerate() {
+        let symmetric = Array2::from_shape_fn((n, n), |(i, j)| {
+            let (lo, hi) = if i > j { (i, j) } else { (j, i) };
+            if upper {
+                matrix[[hi, lo]]
+            } else {
+                matrix[[lo, hi]]
+            }
+        });
+        let (w, v) = jacobi_eigh(symmetric).ok_or(LinalgError::NoConvergence { batch })?;
+        values.push(w);
+        vectors.push(v);
+    }
+    Ok((
+        assemble(&batched.batch, &[n], &values),
+        assemble(&batched.batch, &[n, n], &vectors),
+    ))
+}
+
+/// Lower Cholesky factor of the lower triangle of `a`, or the order of the failing leading minor.
+pub(super) fn cholesky_lower<A>(a: ArrayView2<'_, A>) -> Result<Array2<A>, usize>
+where
+    A: NdFloat,
+{
+    let n = a.nrows();
+    let mut l = Array2::<A>::zeros((n, n));
+    for j in 0..n {
+        let mut diag = a[[j, j]];
+        for k in 0..j {
+            diag -= l[[j, k]] * l[[j, k]];
+        }
+        if diag.is_nan() || diag <= A::zero() {
+            return Err(j + 1);
+        }
+        let pivot = diag.sqrt();
+        l[[j, j]] = pivot;
+        for i in j + 1..n {
+            let mut value = a[[i, j]];
+            for k in 0..j {
+                value -= l[[i, k]] * l[[j, k]];
+            }
+            l[[i, j]] = value / pivot;
+        }
+    }
+    Ok(l)
+}
+
+/// Full Householder QR: returns `Q` of shape `(m, m)` and `R` of shape `(m, n)`.
+///
+/// Like LAPACK's `geqrf`, a column that is already zero below the diagonal is left alone,
+/// and otherwise the diagonal of `R` gets the opposite sign of the pivot.
+pub(super) fn householder_qr<A>(a: ArrayView2<'_, A>) -> (Array2<A>, Array2<A>)
+where
+    A: NdFloat,
+{
+    let (m, n) = a.dim();
+    let mut r = a.to_owned();
+    let mut q = Array2::<A>::eye(m);
+    for j in 0..m.min(n) {
+        let tail = r
+            .slice(s![j + 1.., j])
+            .fold(A::zero(), |acc, &x| acc + x * x);
+        if tail == A::zero() {
+            continue;
+        }
+        let x0 = r[[j, j]];
+        let norm = (x0 * x0 + tail).sqrt();
+        let alpha = if x0 >= A::zero() { -norm } else { norm };
+        let mut v = r.slice(s![j.., j]).to_owned();
+        v[0] -= alpha;
+        let v_norm = v.dot(&v).sqrt();
+        v.mapv_inplace(|x| x / v_norm);
+
+        // R <- H R and Q <- Q H with H = I - 2 v v^T
+        let two = A::one() + A::one();
+        let mut block = r.slice_mut(s![j.., ..]);
+        let proj = v.dot(&block);
+        for (i, mut row) in block.outer_iter_mut().enumerate() {
+            row.scaled_add(-two * v[i], &proj);
+        }
+        let mut block = q.slice_mut(s![.., j..]);
+        let proj = block.dot(&v);
+        for (i, mut row) in block.outer_iter_mut().enumerate() {
+            row.scaled_add(-two * proj[i], &v);
+        }
+        r[[j, j]] = alpha;
+        r.slice_mut(s![j + 1.., j]).fill(A::zero());
+    }
+    (q, r)
+}
+
+/// One-sided Jacobi SVD. Returns `(U, S, Vh)` in descending order of `S`, or `None` if the
+/// rotations do not converge.
+pub(super) fn jacobi_svd<A>(
+    a: ArrayView2<'_, A>,
+    full_matrices: bool,
+) -> Option<(Array2<A>, Array1<A>, Array2<A>)>
+where
+    A: NdFloat,
+{
+    let (m, n) = a.dim();
+    if m < n {
+        // a^T = U' S V'^T, so a = V' S U'^T
+        let (u, sigma, vh) = jacobi_svd(a.t(), full_matrices)?;
+        return Some((vh.reversed_axes(), sigma, u.reversed_axes()));
+    }
+
+    let mut u = a.to_owned();
+    let mut v = Array2::<A>::eye(n);
+    let mut converged = false;
+    for _ in 0..MAX_SWEEPS {
+        let mut rotated = false;
+        for p in 0..n {
+            for q in p + 1..n {
+                let (up, uq) = (u.column(p), u.column(q));
+                let alpha = up.dot(&up);
+                let beta = uq.dot(&uq);
+                let gamma = up.dot(&uq);
+                if gamma == A::zero() || gamma.abs() <= A::epsilon() * (alpha * beta).sqrt() {
+                    continue;
+                }
+                rotated = true;
+                let (c, s) = jacobi_rotation(alpha, beta, gamma);
+                rotate_columns(&mut u, p, q, c, s);
+                rotate_columns(&mut v, p, q, c, s);
+            }
+        }
+        if !rotated {
+            converged = true;
+            break;
+        }
+    }
+    if !converged {
+        return None;
+    }
+
+    let mut sigma: Vec<A> = u.columns().into_iter().map(|c| c.dot(&c).sqrt()).collect();
+    for (mut column, &s) in u.columns_mut().into_iter().zip(sigma.iter()) {
+        if s > A::zero() {
+            column.mapv_inplace(|x| x / s);
+        }
+    }
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| {
+        sigma[j]
+            .partial_cmp(&sigma[i])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let u = u.select(Axis(1), &order);
+    let vh = v.select(Axis(1), &order).reversed_axes();
+    sigma = order.iter().map(|&i| sigma[i]).collect();
+
+    let u_cols = if full_matrices { m } else { n };
+    let u = orthonormal_completion(u, &sigma, u_cols);
+    Some((u, Array1::from(sigma), vh))
+}
+
+/// Cyclic Jacobi eigendecomposition of a symmetric matrix, eigenvalues ascending.
+fn jacobi_eigh<A>(mut a: Array2<A>) -> Option<(Array1<A>, Array2<A>)>
+where
+    A: NdFloat,
+{
+    let n = a.nrows();
+    let mut v = Array2::<A>::eye(n);
+    let scale = a.iter().fold(A::zero(), |acc, &x| acc + x * x).sqrt();
+    let mut converged = false;
+    for _ in 0..MAX_SWEEPS {
+        let mut off = A::zero();
+        for p in 0..n {
+            for q in p + 1..n {
+                off += a[[p, q]] * a[[p, q]];
+            }
+        }
+        if off.sqrt() <= A::epsilon() * scale {
+            converged = true;
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[[p, q]] == A::zero() {
+                    continue;
+                }
+                let (c, s) = jacobi_rotation(a[[p, p]], a[[q, q]], a[[p, q]]);
+                // a <- J^T a J
+                rotate_columns(&mut a, p, q, c, s);
+                for k in 0..n {
+                    let (apk, aqk) = (a[[p, k]], a[[q, k]]);
+                    a[[p, k]] = c * apk - s * aqk;
+                    a[[q, k]] = s * apk + c * aqk;
+                }
+                rotate_columns(&mut v, p, q, c, s);
+            }
+        }
+    }
+    if !converged {
+        return None;
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| {
+        a[[i, i]]
+            .partial_cmp(&a[[j, j]])
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let values = order.iter().map(|&i| a[[i, i]]).collect();
+    Some((values, v.select(Axis(1), &order)))
+}
+
+/// Rotation `(cos, sin)` that zeroes the off-diagonal entry of `[[alpha, gamma], [gamma, beta]]`.
+fn jacobi_rotation<A>(alpha: A, beta: A, gamma: A) -> (A, A)
+where
+    A: NdFloat,
+{
+    let two = A::one() + A::one();
+    let zeta = (beta - alpha) / (two * gamma);
+    let t = zeta.signum() / (zeta.abs() + (A::one() + zeta * zeta).sqrt());
+    let c = A::one() / (A::one() + t * t).sqrt();
+    (c, c * t)
+}
+
+/// Replaces columns `p` and `q` of `x` by `c * x_p - s * x_q` and `s * x_p + c * x_q`.
+fn rotate_columns<A>(x: &mut Array2<A>, p: usize, q: usize, c: A, s: A)
+where
+    A: NdFloat,
+{
+    for mut row in x.outer_iter_mut() {
+        let (xp, xq) = (row[p], row[q]);
+        row[p] = c * xp - s * xq;
+        row[q] = s * xp + c * xq;
+    }
+}
+
+/// Extends the columns of `u` that belong to non-zero singular values to `cols` orthonormal
+/// columns, replacing the zero columns and appending new ones from the standard basis.
+fn orthonormal_completion<A>(u: Array2<A>, sigma: &[A], cols: usize) -> Array2<A>
+where
+    A: NdFloat,
+{
+    let m = u.nrows();
+    let mut basis: Vec<Array1<A>> = Vec::with_capacity(cols);
+    let mut missing = Vec::new();
+    for (j, column) in u.columns().into_iter().enumerate() {
+        if sigma[j] > A::zero() {
+            basis.push(column.to_owned());
+        } else {
+            missing.push(j);
+            basis.push(Array1::zeros(m));
+        }
+    }
+    missing.extend(basis.len()..cols);
+    basis.resize(cols, Array1::zeros(m));
+
+    let half = A::from(0.5).unwrap();
+    let mut candidate = 0;
+    for slot in missing {
+        while candidate < m {
+            let mut e = Array1::<A>::zeros(m);
+            e[candidate] = A::one();
+            candidate += 1;
+            // Two rounds of Gram-Schmidt keep the result orthogonal to working precision
+            for _ in 0..2 {
+                for (j, b) in basis.iter().enumerate() {
+                    if j != slot {
+                        let proj = b.dot(&e);
+                        e.scaled_add(-proj, b);
+                    }
+                }
+            }
+            let norm = e.dot(&e).sqrt();
+            if norm > half {
+                basis[slot] = e.mapv(|x| x / norm);
+                break;
+            }
+        }
+    }
+
+    let mut out = Array2::<A>::zeros((m, cols));
+    for (mut column, b) in out.columns_mut().into_iter().zip(basis.iter()) {
+        column.assign(b);
+    }
+    out
+}
@@ -0,0 +1,99 @@
+//! Linear-algebra routines mirroring `torch.linalg`.
+//!
+//! Every function accepts inputs of shape `(*, m, n)` and loops over the leading batch
+//! dimensions. The factorizations are implemented directly on top of ndarray.
+
+mod decomposition;
+mod norm;
+mod solve;
+
+pub use decomposition::{QrMode, cholesky, eigh, qr, svd};
+pub use norm::{MatrixNorm, matrix_norm};
+pub use solve::{Lstsq, det, inv, lstsq, pinv, slogdet, solve};
+
+use ndarray::{Array, Array3, ArrayD, ArrayView2, ArrayViewD, Dimension, IxDyn};
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LinalgError {
+    #[error("Expected a tensor with at least 2 dimensions, got shape {shape:?}")]
+    TooFewDimensions { shape: Vec<usize> },
+
+    #[error("Expected a batch of square matrices, got shape {shape:?}")]
+    NotSquare { shape: Vec<usize> },
+
+    #[error("Matrix at batch index {batch} is singular")]
+    Singular { batch: usize },
+
+    #[error(
+        "Matrix at batch index {batch} is not positive-definite (the leading minor of order {order} is not positive-definite)"
+    )]
+    NotPositiveDefinite { batch: usize, order: usize },
+
+    #[error("Incompatible shapes {left:?} and {right:?}")]
+    ShapeMismatch { left: Vec<usize>, right: Vec<usize> },
+
+    #[error("Batch dimensions of shapes {left:?} and {right:?} cannot be broadcast together")]
+    BatchBroadcast { left: Vec<usize>, right: Vec<usize> },
+
+    #[error("Iterative solver did not converge for the matrix at batch index {batch}")]
+    NoConvergence { batch: usize },
+}
+
+/// A stack of matrices flattened to `(batch, rows, cols)`, remembering the batch shape.
+pub(crate) struct Batched<A> {
+    pub(crate) batch: Vec<usize>,
+    pub(crate) matrices: Array3<A>,
+}
+
+impl<A: Clone> Batched<A> {
+    pub(crate) fn new(a: ArrayViewD<'_, A>) -> Result<Self, LinalgError> {
+        if a.ndim() < 2 {
+            return Err(LinalgError::TooFewDimensions {
+                shape: a.shape().to_vec(),
+            });
+        }
+        let (batch, matrix) = a.shape().split_at(a.ndim() - 2);
+        let shape = (batch.iter().product(), matrix[0], matrix[1]);
+        let batch = batch.to_vec();
+        let matrices = a
+            .as_standard_layout()
+            .into_owned()
+            .into_shape(shape)
+            .expect("standard layout arrays can be reshaped");
+        Ok(Self { batch, matrices })
+    }
+
+    pub(crate) fn square(a: ArrayViewD<'_, A>) -> Result<Self, LinalgError> {
+        let batched = Self::new(a)?;
+        if batched.rows() != batched.cols() {
+            return Err(LinalgError::NotSquare {
+                shape: [batched.batch.as_slice(), &[batched.rows(), batched.cols()]].concat(),
+            });
+        }
+        Ok(batched)
+    }
+
+    pub(crate) fn rows(&self) -> usize {
+        self.matrices.shape()[1]
+    }
+
+    pub(crate) fn cols(&self) -> usize {
+        self.matrices.shape()[2]
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = ArrayView2<'_, A>> {
+        self.matrices.outer_iter()
+    }
+}
+
+/// Concatenates per-matrix results of shape `inner` into an array of shape `batch + inner`.
+pub(crate) fn assemble<A, D>(batch: &[usize], inner: &[usize], items: &[Array<A, D>]) -> ArrayD<A>
+where
+    A: Clone,
+    D: Dimension,
+{
+    let data: Vec<A> = items.iter().flat_map(|x| x.iter().cloned()).collect();
+    ArrayD::from_shape_vec(IxDyn(&[batch, inner].concat()), data)
+        .expect("every item has the inner shape")
+}
@@ -0,0 +1,80 @@
+use super::decomposition::jacobi_svd;
+use super::{Batched, LinalgError, assemble};
+use ndarray::{ArrayD, Axis, NdFloat};
+
+/// The `ord` argument of [`matrix_norm`], mirroring `torch.linalg.matrix_norm`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatrixNorm {
+    /// `'fro'`: square root of the sum of squared entries.
+    #[default]
+    Frobenius,
+    /// `'nuc'`: sum of the singular values.
+    Nuclear,
+    /// `inf`: maximum absolute row sum.
+    Inf,
+    /// `-inf`: minimum absolute row sum.
+    NegInf,
+    /// `1`: maximum absolute column sum.
+    One,
+    /// `-1`: minimum absolute column sum.
+    NegOne,
+    /// `2`: largest singular value.
+    Two,
+    /// `-2`: smallest singular value.
+    NegTwo,
+}
+
+/// Computes a matrix norm over the last two dimensions.
+///
+/// Mimics `torch.linalg.matrix_norm(a, ord, keepdim=keepdim)`.
+///
+/// # Arguments
+///
+/// * `a`: Input of shape `(*, m, n)`.
+/// * `ord`: The norm to compute, see [`MatrixNorm`].
+/// * `keepdim`: Keep the reduced dimensions with size 1.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The norms, of shape `(*)` or `(*, 1, 1)` with `keepdim`.
+/// * `Err(LinalgError)`: If `a` has fewer than 2 dimensions or the SVD does not converge.
+pub fn matrix_norm<A>(
+    a: &ArrayD<A>,
+    ord: MatrixNorm,
+    keepdim: bool,
+) -> Result<ArrayD<A>, LinalgError>
+where
+    A: NdFloat,
+{
+    let batched = Batched::new(a.view())?;
+    let mut norms = Vec::new();
+    for (batch, matrix) in batched.iter().enumerate() {
+        let abs = matrix.mapv(|x| x.abs());
+        let extreme = |sums: ndarray::Array1<A>, largest: bool| {
+            sums.iter()
+                .copied()
+                .reduce(|acc, x| if (x > acc) == largest { x } else { acc })
+        };
+        let norm = match ord {
+            MatrixNorm::Frobenius => {
+                Some(matrix.iter().fold(A::zero(), |acc, &x| acc + x * x).sqrt())
+            }
+            MatrixNorm::Inf => extreme(abs.sum_axis(Axis(1)), true),
+            MatrixNorm::NegInf => extreme(abs.sum_axis(Axis(1)), false),
+            MatrixNorm::One => extreme(abs.sum_axis(Axis(0)), true),
+            MatrixNorm::NegOne => extreme(abs.sum_axis(Axis(0)), false),
+            MatrixNorm::Nuclear | MatrixNorm::Two | MatrixNorm::NegTwo => {
+                let (_, sigma, _) =
+                    jacobi_svd(matrix, false).ok_or(LinalgError::NoConvergence { batch })?;
+                match ord {
+                    MatrixNorm::Nuclear => Some(sigma.sum()),
+                    MatrixNorm::Two => sigma.first().copied(),
+                    _ => sigma.last().copied(),
+                }
+            }
+        };
+        norms.push(ndarray::arr0(norm.unwrap_or(A::zero())));
+    }
+    let inner: &[usize] = if keepdim { &[1, 1] } else { &[] };
+    Ok(assemble(&batched.batch, inner, &norms))
+}
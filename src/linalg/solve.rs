@@ -0,0 +1,398 @@
+use super::decomposition::jacobi_svd;
+use super::{Batched, LinalgError, assemble};
+use crate::functions::matmul::broadcast_shapes;
+use ndarray::{Array0, Array2, ArrayD, ArrayView2, ArrayViewD, Axis, IxDyn, NdFloat};
+
+/// Result of [`lstsq`], mirroring the named tuple returned by `torch.linalg.lstsq`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lstsq<A> {
+    /// Least-squares solution of shape `(*, n, k)`.
+    pub solution: ArrayD<A>,
+    /// Squared residual norms of shape `(*, k)` when `m > n` and every matrix has full rank,
+    /// empty otherwise.
+    pub residuals: ArrayD<A>,
+    /// Effective rank of each matrix, of shape `(*)`.
+    pub rank: ArrayD<i64>,
+    /// Singular values of each matrix in descending order, of shape `(*, min(m, n))`.
+    pub singular_values: ArrayD<A>,
+}
+
+/// Computes the inverse of a batch of square matrices.
+///
+/// Mimics `torch.linalg.inv(a)`, using an LU decomposition with partial pivoting.
+///
+/// # Arguments
+///
+/// * `a`: Input of shape `(*, n, n)`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The inverses, of shape `(*, n, n)`.
+/// * `Err(LinalgError)`: If `a` is not a batch of square matrices or a matrix is singular.
+pub fn inv<A>(a: &ArrayD<A>) -> Result<ArrayD<A>, LinalgError>
+where
+    A: NdFloat,
+{
+    let batched = Batched::square(a.view())?;
+    let n = batched.rows();
+    let mut inverses = Vec::new();
+    for (batch, matrix) in batched.iter().enumerate() {
+        let lu = Lu::new(matrix);
+        if lu.singular {
+            return Err(LinalgError::Singular { batch });
+        }
+        inverses.push(lu.solve(Array2::eye(n)));
+    }
+    Ok(assemble(&batched.batch, &[n, n], &inverses))
+}
+
+/// Computes the determinant of a batch of square matrices.
+///
+/// Mimics `torch.linalg.det(a)`.
+///
+/// # Arguments
+///
+/// * `a`: Input of shape `(*, n, n)`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The determinants, of shape `(*)`.
+/// * `Err(LinalgError)`: If `a` is not a batch of square matrices.
+pub fn det<A>(a: &ArrayD<A>) -> Result<ArrayD<A>, LinalgError>
+where
+    A: NdFloat,
+{
+    let batched = Batched::square(a.view())?;
+    let dets: Vec<Array0<A>> = batched
+        .iter()
+        .map(|matrix| {
+            let lu = Lu::new(matrix);
+            let product = lu.lu.diag().fold(A::one(), |acc, &x| acc * x);
+            ndarray::arr0(lu.sign * product)
+        })
+        .collect();
+    Ok(assemble(&batched.batch, &[], &dets))
+}
+
+/// Computes the sign and natural log of the absolute determinant of a batch of square matrices.
+///
+/// Mimics `torch.linalg.slogdet(a)`. Singular matrices give sign `0` and `-inf`.
+///
+/// # Arguments
+///
+/// * `a`: Input of shape `(*, n, n)`.
+///
+/// # Returns
+///
+/// * `Ok((ArrayD<A>, ArrayD<A>))`: The signs and log absolute determinants, both of shape `(*)`.
+/// * `Err(LinalgError)`: If `a` is not a batch of square matrices.
+pub fn slogdet<A>(a: &ArrayD<A>) -> Result<(ArrayD<A>, ArrayD<A>), LinalgError>
+where
+    A: NdFloat,
+{
+    let batched = Batched::square(a.view())?;
+    let (mut signs, mut logs) = (Vec::new(), Vec::new());
+    for matrix in batched.iter() {
+        let lu = Lu::new(matrix);
+        if lu.singular {
+            signs.push(ndarray::arr0(A::zero()));
+            logs.push(ndarray::arr0(A::neg_infinity()));
+            continue;
+        }
+        let (sign, log) = lu.lu.diag().fold((lu.sign, A::zero()), |(sign, log), &x| {
+            (sign * x.signum(), log + x.abs().ln())
+        });
+        signs.push(ndarray::arr0(sign));
+        logs.push(ndarray::arr0(log));
+    }
+    Ok((
+        assemble(&batched.batch, &[], &signs),
+        assemble(&batched.batch, &[], &logs),
+    ))
+}
+
+/// Solves the square system `a @ x = b` for a batch of matrices.
+///
+/// Mimics `torch.linalg.solve(a, b)`. `b` is treated as a batch of vectors when it is 1D or
+/// when its shape equals `a.shape()[..a.ndim() - 1]`; otherwise it has shape `(*, n, k)`. The
+/// batch dimensions of `a` and `b` broadcast.
+///
+/// # Arguments
+///
+/// * `a`: Coefficients of shape `(*, n, n)`.
+/// * `b`: Right-hand sides of shape `(*, n, k)` or `(*, n)`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The solution, with the broadcast batch shape and the shape of `b`'s matrices.
+/// * `Err(LinalgError)`: If the shapes are incompatible or a matrix is singular.
+pub fn solve<A>(a: &ArrayD<A>, b: &ArrayD<A>) -> Result<ArrayD<A>, LinalgError>
+where
+    A: NdFloat,
+{
+    if a.ndim() < 2 || b.ndim() < 1 {
+        return Err(LinalgError::TooFewDimensions {
+            shape: if a.ndim() < 2 { a.shape() } else { b.shape() }.to_vec(),
+        });
+    }
+    // Promote a vector right-hand side to a single-column matrix before broadcasting
+    let vector = b.ndim() == 1 || b.shape() == &a.shape()[..a.ndim() - 1];
+    let b_matrix = if vector {
+        b.view().insert_axis(Axis(b.ndim()))
+    } else {
+        b.view()
+    };
+    let (a, rhs) = broadcast_batch(a.view(), b_matrix)?;
+    let (n, k) = (rhs.rows(), rhs.cols());
+    if a.rows() != a.cols() {
+        return Err(LinalgError::NotSquare {
+            shape: a.matrices.shape()[1..].to_vec(),
+        });
+    }
+    if a.rows() != n {
+        return Err(LinalgError::ShapeMismatch {
+            left: a.matrices.shape()[1..].to_vec(),
+            right: b.shape().to_vec(),
+        });
+    }
+
+    let mut solutions = Vec::new();
+    for (index, (matrix, rhs)) in a.iter().zip(rhs.iter()).enumerate() {
+        let lu = Lu::new(matrix);
+        if lu.singular {
+            return Err(LinalgError::Singular { batch: index });
+        }
+        solutions.push(lu.solve(rhs.to_owned()));
+    }
+    let inner: &[usize] = if vector { &[n] } else { &[n, k] };
+    Ok(assemble(&a.batch, inner, &solutions))
+}
+
+/// Computes the Moore-Penrose pseudo-inverse of a batch of matrices.
+///
+/// Mimics `torch.linalg.pinv(a, rtol=rtol)` via the singular value decomposition. Singular
+/// values below `rtol` times the largest one are treated as zero.
+///
+/// # Arguments
+///
+/// * `a`: Input of shape `(*, m, n)`.
+/// * `rtol`: Relative cutoff, `eps * max(m, n)` when `None` as in PyTorch.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The pseudo-inverses, of shape `(*, n, m)`.
+/// * `Err(LinalgError)`: If `a` has fewer than 2 dimensions or the SVD does not converge.
+pub fn pinv<A>(a: &ArrayD<A>, rtol: Option<A>) -> Result<ArrayD<A>, LinalgError>
+where
+    A: NdFloat,
+{
+    let batched = Batched::new(a.view())?;
+    let (m, n) = (batched.rows(), batched.cols());
+    let rtol = rtol.unwrap_or_else(|| default_rtol(m, n));
+    let mut inverses = Vec::new();
+    for (batch, matrix) in batched.iter().enumerate() {
+        let (u, sigma, vh) =
+            jacobi_svd(matrix, false).ok_or(LinalgError::NoConvergence { batch })?;
+        let cutoff = rtol * sigma.first().copied().unwrap_or(A::zero());
+        // pinv = V diag(1 / sigma) U^T over the retained singular values
+        let mut v = vh.reversed_axes();
+        for (mut column, &s) in v.columns_mut().into_iter().zip(sigma.iter()) {
+            let inverse = if s > cutoff { A::one() / s } else { A::zero() };
+            column.mapv_inplace(|x| x * inverse);
+        }
+        inverses.push(v.dot(&u.t()));
+    }
+    Ok(assemble(&batched.batch, &[n, m], &inverses))
+}
+
+/// Computes a least-squares solution of `a @ x = b` for a batch of matrices.
+///
+/// Mimics `torch.linalg.lstsq(a, b, rcond, driver="gelsd")`: the minimum-norm solution is
+/// computed from the singular value decomposition of `a`, so rank-deficient systems are
+/// supported. The batch dimensions of `a` and `b` broadcast.
+///
+/// # Arguments
+///
+/// * `a`: Coefficients of shape `(*, m, n)`.
+/// * `b`: Right-hand sides of shape `(*, m, k)`.
+/// * `rcond`: Relative cutoff for small singular values, `eps * max(m, n)` when `None`.
+///
+/// # Returns
+///
+/// * `Ok(Lstsq<A>)`: The solution together with residuals, ranks and singular values.
+/// * `Err(LinalgError)`: If the shapes are incompatible or the SVD does not converge.
+pub fn lstsq<A>(a: &ArrayD<A>, b: &ArrayD<A>, rcond: Option<A>) -> Result<Lstsq<A>, LinalgError>
+where
+    A: NdFloat,
+{
+    if a.ndim() < 2 || b.ndim() < 2 {
+        return Err(LinalgError::TooFewDimensions {
+            shape: if a.ndim() < 2 { a.shape() } else { b.shape() }.to_vec(),
+        });
+    }
+    let (coefficients, rhs) = broadcast_batch(a.view(), b.view())?;
+    let batch = coefficients.batch.clone();
+    let (m, n, k) = (coefficients.rows(), coefficients.cols(), rhs.cols());
+    if rhs.rows() != m {
+        return Err(LinalgError::ShapeMismatch {
+            left: a.shape().to_vec(),
+            right: b.shape().to_vec(),
+        });
+    }
+    let rcond = rcond.unwrap_or_else(|| default_rtol(m, n));
+
+    let (mut solutions, mut residuals, mut ranks, mut singular_values) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    let mut full_rank = true;
+    for (index, (matrix, rhs)) in coefficients.iter().zip(rhs.iter()).enumerate() {
+        let (u, sigma, vh) =
+            jacobi_svd(matrix, false).ok_or(LinalgError::NoConvergence { batch: index })?;
+        let cutoff = rcond * sigma.first().copied().unwrap_or(A::zero());
+        let rank = sigma.iter().filter(|&&s| s > cutoff).count();
+        full_rank &= rank == n;
+
+        // x = V diag(1 / sigma) U^T b over the first `rank` singular values
+        let mut projected = u.t().dot(&rhs);
+        for (i, mut row) in projected.outer_iter_mut().enumerate() {
+            let inverse = if i < rank {
+                A::one() / sigma[i]
+            } else {
+                A::zero()
+            };
+            row.mapv_inplace(|x| x * inverse);
+        }
+        let solution = vh.t().dot(&projected);
+        let error = &matrix.dot(&solution) - &rhs;
+        residuals.push(error.map_axis(Axis(0), |column| column.dot(&column)));
+        ranks.push(ndarray::arr0(rank as i64));
+        solutions.push(solution);
+        singular_values.push(sigma);
+    }
+
+    let residuals = if m > n && full_rank {
+        assemble(&batch, &[k], &residuals)
+    } else {
+        ArrayD::zeros(IxDyn(&[0]))
+    };
+    Ok(Lstsq {
+        solution: assemble(&batch, &[n, k], &solutions),
+        residuals,
+        rank: assemble(&batch, &[], &ranks),
+        singular_values: assemble(&batch, &[m.min(n)], &singular_values),
+    })
+}
+
+/// LU decomposition with partial pivoting, `P a = L U`, with `L` and `U` packed in `lu`.
+struct Lu<A> {
+    lu: Array2<A>,
+    /// Row `i` of `P a` is row `perm[i]` of `a`.
+    perm: Vec<usize>,
+    /// Determinant of `P`.
+    sign: A,
+    /// Whether a pivot was exactly zero.
+    singular: bool,
+}
+
+impl<A> Lu<A>
+where
+    A: NdFloat,
+{
+    fn new(a: ArrayView2<'_, A>) -> Self {
+        let n = a.nrows();
+        let mut lu = a.to_owned();
+        let mut perm: Vec<usize> = (0..n).collect();
+        let mut sign = A::one();
+        let mut singular = false;
+        for k in 0..n {
+            let pivot = (k..n)
+                .max_by(|&i, &j| {
+                    lu[[i, k]]
+                        .abs()
+                        .partial_cmp(&lu[[j, k]].abs())
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap();
+            if lu[[pivot, k]] == A::zero() {
+                singular = true;
+                continue;
+            }
+            if pivot != k {
+                for j in 0..n {
+                    lu.swap([k, j], [pivot, j]);
+                }
+                perm.swap(k, pivot);
+                sign = -sign;
+            }
+            for i in k + 1..n {
+                let factor = lu[[i, k]] / lu[[k, k]];
+                lu[[i, k]] = factor;
+                for j in k + 1..n {
+                    let update = factor * lu[[k, j]];
+                    lu[[i, j]] -= update;
+                }
+            }
+        }
+        Self {
+            lu,
+            perm,
+            sign,
+            singular,
+        }
+    }
+
+    /// Solves `a x = b` for every column of `b`. Requires a non-singular decomposition.
+    fn solve(&self, b: Array2<A>) -> Array2<A> {
+        let n = self.lu.nrows();
+        let mut x = b.select(Axis(0), &self.perm);
+        for mut column in x.columns_mut() {
+            for i in 0..n {
+                let mut value = column[i];
+                for j in 0..i {
+                    value -= self.lu[[i, j]] * column[j];
+                }
+                column[i] = value;
+            }
+            for i in (0..n).rev() {
+                let mut value = column[i];
+                for j in i + 1..n {
+                    value -= self.lu[[i, j]] * column[j];
+                }
+                column[i] = value / self.lu[[i, i]];
+            }
+        }
+        x
+    }
+}
+
+fn default_rtol<A: NdFloat>(m: usize, n: usize) -> A {
+    A::epsilon() * A::from(m.max(n)).unwrap()
+}
+
+/// Broadcasts the batch dimensions of two stacks of matrices against each other.
+fn broadcast_batch<A>(
+    left: ArrayViewD<'_, A>,
+    right: ArrayViewD<'_, A>,
+) -> Result<(Batched<A>, Batched<A>), LinalgError>
+where
+    A: Clone,
+{
+    for shape in [left.shape(), right.shape()] {
+        if shape.len() < 2 {
+            return Err(LinalgError::TooFewDimensions {
+                shape: shape.to_vec(),
+            });
+        }
+    }
+    let error = || LinalgError::BatchBroadcast {
+        left: left.shape().to_vec(),
+        right: right.shape().to_vec(),
+    };
+    let (lb, lm) = left.shape().split_at(left.ndim() - 2);
+    let (rb, rm) = right.shape().split_at(right.ndim() - 2);
+    let batch = broadcast_shapes(lb, rb).ok_or_else(error)?;
+    let left_shape = [batch.as_slice(), lm].concat();
+    let right_shape = [batch.as_slice(), rm].concat();
+    let l = left.broadcast(IxDyn(&left_shape)).ok_or_else(error)?;
+    let r = right.broadcast(IxDyn(&right_shape)).ok_or_else(error)?;
+    Ok((Batched::new(l)?, Batched::new(r)?))
+}
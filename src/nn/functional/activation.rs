@@ -0,0 +1,77 @@
+use super::FunctionalError;
+use crate::functions::dim::normalize_dim;
+use ndarray::{ArrayD, Axis, NdFloat};
+
+/// Applies softmax along `dim`.
+///
+/// Mimics `torch.nn.functional.softmax(input, dim)`. Each lane has its maximum subtracted
+/// before exponentiating, so large inputs do not overflow.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `dim`: The dimension to normalize over. Negative values wrap around.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: An array of the same shape whose lanes along `dim` sum to one.
+/// * `Err(FunctionalError)`: If `dim` is out of bounds.
+pub fn softmax<A>(input: &ArrayD<A>, dim: isize) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let axis = resolve_dim(input, dim)?;
+    let mut output = input.to_owned();
+    for mut lane in output.lanes_mut(Axis(axis)) {
+        let max = lane_max(lane.iter().copied());
+        lane.mapv_inplace(|x| (x - max).exp());
+        let sum = lane.sum();
+        lane.mapv_inplace(|x| x / sum);
+    }
+    Ok(output)
+}
+
+/// Applies log-softmax along `dim`.
+///
+/// Mimics `torch.nn.functional.log_softmax(input, dim)`, computed as
+/// `x - max - ln(sum(exp(x - max)))` for numerical stability.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `dim`: The dimension to normalize over. Negative values wrap around.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: An array of the same shape holding log-probabilities.
+/// * `Err(FunctionalError)`: If `dim` is out of bounds.
+pub fn log_softmax<A>(input: &ArrayD<A>, dim: isize) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let axis = resolve_dim(input, dim)?;
+    let mut output = input.to_owned();
+    for mut lane in output.lanes_mut(Axis(axis)) {
+        let max = lane_max(lane.iter().copied());
+        let log_sum = lane
+            .iter()
+            .fold(A::zero(), |acc, &x| acc + (x - max).exp())
+            .ln();
+        lane.mapv_inplace(|x| x - max - log_sum);
+    }
+    Ok(output)
+}
+
+fn resolve_dim<A>(input: &ArrayD<A>, dim: isize) -> Result<usize, FunctionalError> {
+    let ndim = input.ndim();
+    normalize_dim(dim, ndim).ok_or(FunctionalError::InvalidDimension { dim, ndim })
+}
+
+/// Maximum of a lane. A lane of only `-inf` yields `-inf`, so softmax gives NaN like PyTorch.
+fn lane_max<A, I>(values: I) -> A
+where
+    A: NdFloat,
+    I: Iterator<Item = A>,
+{
+    values.fold(A::neg_infinity(), |max, x| if x > max { x } else { max })
+}
@@ -0,0 +1,350 @@
+use super::FunctionalError;
+use crate::functions::matmul::broadcast_shapes;
+use ndarray::linalg::general_mat_mul;
+use ndarray::{
+    Array2, Array3, ArrayD, ArrayView2, ArrayView3, ArrayViewD, ArrayViewMut2, Axis, IxDyn,
+};
+use ndarray::{NdFloat, Zip, s};
+
+/// Number of query rows processed together by one block of the attention kernel.
+const QUERY_BLOCK: usize = 64;
+/// Number of key rows scored at once; only a `QUERY_BLOCK x KEY_BLOCK` tile of scores is live.
+const KEY_BLOCK: usize = 128;
+
+/// Mask accepted by [`scaled_dot_product_attention`].
+#[derive(Debug, Clone, Copy)]
+pub enum AttentionMask<'a, A> {
+    /// `true` marks key positions that take part in attention, `false` ones that are masked out.
+    Boolean(&'a ArrayD<bool>),
+    /// Added to the scaled scores before the softmax, e.g. `-inf` to mask a position.
+    Additive(&'a ArrayD<A>),
+}
+
+/// Computes scaled dot-product attention `softmax(q @ k^T * scale + mask) @ v`.
+///
+/// Mimics `torch.nn.functional.scaled_dot_product_attention`. The batch dimensions of the
+/// operands and of the mask broadcast against each other. When the head dimension (`-3`) of
+/// `q` is a multiple of the one of `k` and `v`, each key/value head is shared by consecutive
+/// query heads, as with `enable_gqa=True`.
+///
+/// The kernel walks the keys in blocks and keeps a running max and sum per query row
+/// (an online softmax), so the full `(L, S)` score matrix is never materialized.
+///
+/// Rows where every key is masked out produce NaN, as in PyTorch.
+///
+/// # Arguments
+///
+/// * `q`: Queries of shape `(*, L, E)`.
+/// * `k`: Keys of shape `(*, S, E)`.
+/// * `v`: Values of shape `(*, S, Ev)`; its batch dimensions must equal those of `k`.
+/// * `attn_mask`: Optional mask broadcastable to `(*, L, S)`.
+/// * `is_causal`: Masks key `j` for query `i` when `j > i`. Cannot be combined with `attn_mask`.
+/// * `scale`: Score scaling factor, `1 / sqrt(E)` when `None`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The attention output of shape `(*, L, Ev)`.
+/// * `Err(FunctionalError)`: If the shapes are incompatible, or a mask is combined with `is_causal`.
+pub fn scaled_dot_product_attention<A>(
+    q: &ArrayD<A>,
+    k: &ArrayD<A>,
+    v: &ArrayD<A>,
+    attn_mask: Option<AttentionMask<'_, A>>,
+    is_causal: bool,
+    scale: Option<A>,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    if attn_mask.is_some() && is_causal {
+        return Err(FunctionalError::CausalWithMask);
+    }
+    let shape_error = || FunctionalError::AttentionShapeMismatch {
+        query: q.shape().to_vec(),
+        key: k.shape().to_vec(),
+        value: v.shape().to_vec(),
+    };
+    if q.ndim() < 2 || k.ndim() < 2 || v.ndim() < 2 {
+        return Err(shape_error());
+    }
+    let (q_batch, &[l, e]) = q.shape().split_at(q.ndim() - 2) else {
+        unreachable!()
+    };
+    let (k_batch, &[s, ek]) = k.shape().split_at(k.ndim() - 2) else {
+        unreachable!()
+    };
+    let (v_batch, &[sv, ev]) = v.shape().split_at(v.ndim() - 2) else {
+        unreachable!()
+    };
+    if e != ek || s != sv || k_batch != v_batch {
+        return Err(shape_error());
+    }
+
+    // Grouped-query attention: let the key/value head dimension stretch to the query heads
+    let gqa = match (q_batch.last(), k_batch.last()) {
+        (Some(&hq), Some(&hk)) if hq != hk && hk > 1 && hq % hk == 0 => Some(hq),
+        _ => None,
+    };
+    let mut kv_broadcast = k_batch.to_vec();
+    if let Some(hq) = gqa {
+        *kv_broadcast.last_mut().unwrap() = hq;
+    }
+    let batch_error = || FunctionalError::AttentionBroadcast {
+        query: q.shape().to_vec(),
+        key: k.shape().to_vec(),
+    };
+    let mut batch = broadcast_shapes(q_batch, &kv_broadcast).ok_or_else(batch_error)?;
+
+    let mask = match attn_mask {
+        None => None,
+        Some(mask) => {
+            let mask_shape = match mask {
+                AttentionMask::Boolean(m) => m.shape(),
+                AttentionMask::Additive(m) => m.shape(),
+            };
+            let mask_error = || FunctionalError::AttentionMaskShape {
+                mask: mask_shape.to_vec(),
+                scores: [batch.as_slice(), &[l, s]].concat(),
+            };
+            if mask_shape.len() < 2 {
+                return Err(mask_error());
+            }
+            let (mask_batch, &[ml, ms]) = mask_shape.split_at(mask_shape.len() - 2) else {
+                unreachable!()
+            };
+            if !(ml == l || ml == 1) || !(ms == s || ms == 1) {
+                return Err(mask_error());
+            }
+            batch = broadcast_shapes(&batch, mask_batch).ok_or_else(mask_error)?;
+            Some(mask)
+        }
+    };
+
+    let q_index = BatchIndex::new(q_batch, &batch, false);
+    let kv_index = BatchIndex::new(k_batch, &batch, gqa.is_some());
+    let q = q.as_standard_layout();
+    let k = k.as_standard_layout();
+    let v = v.as_standard_layout();
+    let q3 = flatten_batch(q.view(), l, e);
+    let k3 = flatten_batch(k.view(), s, e);
+    let v3 = flatten_batch(v.view(), s, ev);
+
+    let bool_mask;
+    let additive_mask;
+    let mask = match mask {
+        None => None,
+        Some(AttentionMask::Boolean(m)) => {
+            bool_mask = m.as_standard_layout();
+            Some(BlockMask::Boolean(MaskView::new(bool_mask.view(), &batch)))
+        }
+        Some(AttentionMask::Additive(m)) => {
+            additive_mask = m.as_standard_layout();
+            Some(BlockMask::Additive(MaskView::new(
+                additive_mask.view(),
+                &batch,
+            )))
+        }
+    };
+
+    let scale = scale.unwrap_or_else(|| A::one() / A::from(e).unwrap().sqrt());
+    let batch_len: usize = batch.iter().product();
+    let mut out = Array3::<A>::zeros((batch_len, l, ev));
+    Zip::indexed(out.outer_iter_mut()).par_for_each(|b, out_b| {
+        let index = unravel(b, &batch);
+        let qi = q3.index_axis(Axis(0), q_index.flat(&index));
+        let kvi = kv_index.flat(&index);
+        let ki = k3.index_axis(Axis(0), kvi);
+        let vi = v3.index_axis(Axis(0), kvi);
+        let mask = mask.as_ref().map(|m| m.select(&index));
+        attend(qi, ki, vi, mask, is_causal, scale, out_b);
+    });
+
+    let out_shape = [batch.as_slice(), &[l, ev]].concat();
+    Ok(out
+        .into_shape(IxDyn(&out_shape))
+        .expect("output holds batch * L * Ev elements"))
+}
+
+/// Blocked online-softmax attention for a single `(L, E)` query matrix.
+fn attend<A>(
+    q: ArrayView2<'_, A>,
+    k: ArrayView2<'_, A>,
+    v: ArrayView2<'_, A>,
+    mask: Option<BlockMask<ArrayView2<'_, bool>, ArrayView2<'_, A>>>,
+    is_causal: bool,
+    scale: A,
+    mut out: ArrayViewMut2<'_, A>,
+) where
+    A: NdFloat,
+{
+    let (l, s) = (q.nrows(), k.nrows());
+    let mut scores = Array2::<A>::zeros((QUERY_BLOCK.min(l), KEY_BLOCK.min(s)));
+
+    for q0 in (0..l).step_by(QUERY_BLOCK) {
+        let q1 = (q0 + QUERY_BLOCK).min(l);
+        let rows = q1 - q0;
+        let q_block = q.slice(s![q0..q1, ..]);
+        let mut row_max = vec![A::neg_infinity(); rows];
+        let mut row_sum = vec![A::zero(); rows];
+        let mut acc = out.slice_mut(s![q0..q1, ..]);
+        acc.fill(A::zero());
+
+        for k0 in (0..s).step_by(KEY_BLOCK) {
+            // Every later key block lies entirely above the diagonal
+            if is_causal && k0 >= q1 {
+                break;
+            }
+            let k1 = (k0 + KEY_BLOCK).min(s);
+            let mut tile = scores.slice_mut(s![..rows, ..k1 - k0]);
+            general_mat_mul(
+                scale,
+                &q_block,
+                &k.slice(s![k0..k1, ..]).t(),
+                A::zero(),
+                &mut tile,
+            );
+
+            for (i, mut row) in tile.outer_iter_mut().enumerate() {
+                let qi = q0 + i;
+                for (j, x) in row.iter_mut().enumerate() {
+                    let kj = k0 + j;
+                    if is_causal && kj > qi {
+                        *x = A::neg_infinity();
+                        continue;
+                    }
+                    match &mask {
+                        Some(BlockMask::Boolean(m)) if !m[broadcast_index(m, qi, kj)] => {
+                            *x = A::neg_infinity()
+                        }
+                        Some(BlockMask::Additive(m)) => *x += m[broadcast_index(m, qi, kj)],
+                        _ => {}
+                    }
+                }
+
+                let block_max = row
+                    .iter()
+                    .fold(A::neg_infinity(), |max, &x| if x > max { x } else { max });
+                let new_max = if block_max > row_max[i] {
+                    block_max
+                } else {
+                    row_max[i]
+                };
+                if new_max == A::neg_infinity() {
+                    // Nothing attended to yet; contribute nothing to the accumulator
+                    row.fill(A::zero());
+                    continue;
+                }
+                let correction = (row_max[i] - new_max).exp();
+                row.mapv_inplace(|x| (x - new_max).exp());
+                row_sum[i] = row_sum[i] * correction + row.sum();
+                row_max[i] = new_max;
+                acc.row_mut(i).mapv_inplace(|x| x * correction);
+            }
+
+            general_mat_mul(
+                A::one(),
+                &tile,
+                &v.slice(s![k0..k1, ..]),
+                A::one(),
+                &mut acc,
+            );
+        }
+
+        for (mut row, &sum) in acc.outer_iter_mut().zip(row_sum.iter()) {
+            row.mapv_inplace(|x| x / sum);
+        }
+    }
+}
+
+/// Index into a mask whose rows or columns may have size 1.
+fn broadcast_index<T>(mask: &ArrayView2<'_, T>, row: usize, col: usize) -> [usize; 2] {
+    [row.min(mask.nrows() - 1), col.min(mask.ncols() - 1)]
+}
+
+enum BlockMask<B, F> {
+    Boolean(B),
+    Additive(F),
+}
+
+impl<'a, A> BlockMask<MaskView<'a, bool>, MaskView<'a, A>> {
+    fn select(&self, index: &[usize]) -> BlockMask<ArrayView2<'_, bool>, ArrayView2<'_, A>> {
+        match self {
+            BlockMask::Boolean(m) => BlockMask::Boolean(m.select(index)),
+            BlockMask::Additive(m) => BlockMask::Additive(m.select(index)),
+        }
+    }
+}
+
+/// A mask flattened to `(mask batch, rows, cols)` with its batch axes mapped onto the output
+/// batch. Rows and columns are either full size or 1, in which case they broadcast.
+struct MaskView<'a, T> {
+    view: ArrayView3<'a, T>,
+    index: BatchIndex,
+}
+
+impl<'a, T> MaskView<'a, T> {
+    fn new(mask: ArrayViewD<'a, T>, batch: &[usize]) -> Self {
+        let (mask_batch, matrix) = mask.shape().split_at(mask.ndim() - 2);
+        let index = BatchIndex::new(mask_batch, batch, false);
+        let (rows, cols) = (matrix[0], matrix[1]);
+        Self {
+            view: flatten_batch(mask, rows, cols),
+            index,
+        }
+    }
+
+    fn select(&self, index: &[usize]) -> ArrayView2<'_, T> {
+        self.view.index_axis(Axis(0), self.index.flat(index))
+    }
+}
+
+/// Maps an index into the broadcast batch shape to a flat index into an operand's batch.
+struct BatchIndex {
+    /// Row-major stride of each output batch axis in the operand, 0 where it is broadcast.
+    strides: Vec<usize>,
+    /// How many consecutive output indices share one operand index (grouped-query heads).
+    groups: Vec<usize>,
+}
+
+impl BatchIndex {
+    fn new(operand: &[usize], batch: &[usize], grouped_heads: bool) -> Self {
+        let offset = batch.len() - operand.len();
+        let mut strides = vec![0; batch.len()];
+        let mut groups = vec![1; batch.len()];
+        let mut stride = 1;
+        for d in (offset..batch.len()).rev() {
+            let size = operand[d - offset];
+            if size != 1 {
+                strides[d] = stride;
+                if grouped_heads && d == batch.len() - 1 {
+                    groups[d] = batch[d] / size;
+                }
+            }
+            stride *= size;
+        }
+        Self { strides, groups }
+    }
+
+    fn flat(&self, index: &[usize]) -> usize {
+        index
+            .iter()
+            .zip(self.strides.iter().zip(self.groups.iter()))
+            .map(|(&i, (&stride, &group))| i / group * stride)
+            .sum()
+    }
+}
+
+fn flatten_batch<T>(view: ArrayViewD<'_, T>, rows: usize, cols: usize) -> ArrayView3<'_, T> {
+    let batch = view.shape()[..view.ndim() - 2].iter().product::<usize>();
+    view.into_shape((batch, rows, cols))
+        .expect("standard layout views can be reshaped")
+}
+
+fn unravel(mut flat: usize, shape: &[usize]) -> Vec<usize> {
+    let mut index = vec![0; shape.len()];
+    for (i, &size) in shape.iter().enumerate().rev() {
+        index[i] = flat % size;
+        flat /= size;
+    }
+    index
+}
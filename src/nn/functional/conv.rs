@@ -0,0 +1,410 @@
+use super::FunctionalError;
+use ndarray::linalg::general_mat_mul;
+use ndarray::{Array2, Array4, ArrayD, ArrayView4, ArrayViewD, Axis, Ix4, NdFloat, Zip, s};
+
+/// Applies a 1D convolution over an input of shape `(N, C_in, L)` or `(C_in, L)`.
+///
+/// Mimics `torch.nn.functional.conv1d(input, weight, bias, stride, padding, dilation, groups)`.
+///
+/// # Arguments
+///
+/// * `input`: Input of shape `(N, C_in, L)` or unbatched `(C_in, L)`.
+/// * `weight`: Filters of shape `(C_out, C_in / groups, K)`.
+/// * `bias`: Optional bias of shape `(C_out,)`.
+/// * `stride`: Step between kernel applications.
+/// * `padding`: Implicit zero padding on both sides.
+/// * `dilation`: Spacing between kernel elements.
+/// * `groups`: Number of blocked connections from input to output channels.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: Output of shape `(N, C_out, L_out)` (or `(C_out, L_out)` when unbatched) with
+///   `L_out = (L + 2 * padding - dilation * (K - 1) - 1) / stride + 1`.
+/// * `Err(FunctionalError)`: If the shapes or parameters are invalid.
+pub fn conv1d<A>(
+    input: &ArrayD<A>,
+    weight: &ArrayD<A>,
+    bias: Option<&ArrayD<A>>,
+    stride: usize,
+    padding: usize,
+    dilation: usize,
+    groups: usize,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let (x, unbatched) = spatial_input(input, 1)?;
+    let w = spatial_weight(weight, 1)?;
+    let out = conv2d_impl(x, w, bias, [1, stride], [0, padding], [1, dilation], groups)?;
+    Ok(spatial_output(out, 1, unbatched))
+}
+
+/// Applies a 2D convolution over an input of shape `(N, C_in, H, W)` or `(C_in, H, W)`.
+///
+/// Mimics `torch.nn.functional.conv2d(input, weight, bias, stride, padding, dilation, groups)`.
+/// Each group is lowered to an im2col matrix and multiplied with the filters in a single GEMM.
+///
+/// # Arguments
+///
+/// * `input`: Input of shape `(N, C_in, H, W)` or unbatched `(C_in, H, W)`.
+/// * `weight`: Filters of shape `(C_out, C_in / groups, kH, kW)`.
+/// * `bias`: Optional bias of shape `(C_out,)`.
+/// * `stride`: Step between kernel applications, as `[height, width]`.
+/// * `padding`: Implicit zero padding on both sides, as `[height, width]`.
+/// * `dilation`: Spacing between kernel elements, as `[height, width]`.
+/// * `groups`: Number of blocked connections from input to output channels.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: Output of shape `(N, C_out, H_out, W_out)` (or without `N` when unbatched).
+/// * `Err(FunctionalError)`: If the shapes or parameters are invalid.
+pub fn conv2d<A>(
+    input: &ArrayD<A>,
+    weight: &ArrayD<A>,
+    bias: Option<&ArrayD<A>>,
+    stride: [usize; 2],
+    padding: [usize; 2],
+    dilation: [usize; 2],
+    groups: usize,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let (x, unbatched) = spatial_input(input, 2)?;
+    let w = spatial_weight(weight, 2)?;
+    let out = conv2d_impl(x, w, bias, stride, padding, dilation, groups)?;
+    Ok(spatial_output(out, 2, unbatched))
+}
+
+/// Applies a 1D transposed convolution over an input of shape `(N, C_in, L)` or `(C_in, L)`.
+///
+/// Mimics `torch.nn.functional.conv_transpose1d(input, weight, bias, stride, padding,
+/// output_padding, groups, dilation)`. The input is multiplied with the filters and the
+/// resulting columns are scattered back onto the output (col2im).
+///
+/// # Arguments
+///
+/// * `input`: Input of shape `(N, C_in, L)` or unbatched `(C_in, L)`.
+/// * `weight`: Filters of shape `(C_in, C_out / groups, K)`.
+/// * `bias`: Optional bias of shape `(C_out,)`.
+/// * `stride`: Step between kernel applications.
+/// * `padding`: Amount removed from both sides of the output.
+/// * `output_padding`: Extra size added to one side of the output, smaller than `stride` or `dilation`.
+/// * `groups`: Number of blocked connections from input to output channels.
+/// * `dilation`: Spacing between kernel elements.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: Output of shape `(N, C_out, L_out)` with
+///   `L_out = (L - 1) * stride - 2 * padding + dilation * (K - 1) + output_padding + 1`.
+/// * `Err(FunctionalError)`: If the shapes or parameters are invalid.
+#[allow(clippy::too_many_arguments)]
+pub fn conv_transpose1d<A>(
+    input: &ArrayD<A>,
+    weight: &ArrayD<A>,
+    bias: Option<&ArrayD<A>>,
+    stride: usize,
+    padding: usize,
+    output_padding: usize,
+    groups: usize,
+    dilation: usize,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let (x, unbatched) = spatial_input(input, 1)?;
+    let w = spatial_weight(weight, 1)?;
+    check_positive("stride", stride)?;
+    check_positive("dilation", dilation)?;
+    check_positive("groups", groups)?;
+    if output_padding >= stride && output_padding >= dilation {
+        return Err(FunctionalError::OutputPaddingTooLarge {
+            output_padding,
+            stride,
+            dilation,
+        });
+    }
+
+    let (n, c_in, _, length) = x.dim();
+    let (w_in, c_out_group, _, kernel) = w.dim();
+    if !c_in.is_multiple_of(groups) {
+        return Err(FunctionalError::GroupMismatch {
+            channels: c_in,
+            groups,
+        });
+    }
+    if w_in != c_in {
+        return Err(FunctionalError::ParameterShapeMismatch {
+            name: "weight",
+            expected: vec![c_in, c_out_group, kernel],
+            found: weight.shape().to_vec(),
+        });
+    }
+    let c_out = c_out_group * groups;
+    let full = (length.max(1) - 1) * stride + dilation * (kernel.max(1) - 1) + output_padding + 1;
+    if length == 0 || kernel == 0 || full <= 2 * padding {
+        return Err(FunctionalError::EmptyOutput {
+            input: input.shape().to_vec(),
+            weight: weight.shape().to_vec(),
+        });
+    }
+    let out_length = full - 2 * padding;
+    let bias = check_bias(bias, c_out)?;
+
+    let c_in_group = c_in / groups;
+    let w2 = w
+        .as_standard_layout()
+        .into_owned()
+        .into_shape((c_in, c_out_group * kernel))
+        .expect("weight holds C_in * C_out / groups * K elements");
+
+    let mut out = Array4::<A>::zeros((n, c_out, 1, out_length));
+    Zip::from(out.outer_iter_mut())
+        .and(x.outer_iter())
+        .par_for_each(|mut out_n, x_n| {
+            let x2 = x_n.index_axis(Axis(1), 0);
+            let mut cols = Array2::<A>::zeros((c_out_group * kernel, length));
+            for g in 0..groups {
+                let w_g = w2.slice(s![g * c_in_group..(g + 1) * c_in_group, ..]);
+                let x_g = x2.slice(s![g * c_in_group..(g + 1) * c_in_group, ..]);
+                general_mat_mul(A::one(), &w_g.t(), &x_g, A::zero(), &mut cols);
+                // col2im: scatter each (channel, tap, position) column entry to its output slot
+                for co in 0..c_out_group {
+                    let mut target = out_n.index_axis_mut(Axis(0), g * c_out_group + co);
+                    for k in 0..kernel {
+                        let row = cols.row(co * kernel + k);
+                        for (l, &value) in row.iter().enumerate() {
+                            let pos = (l * stride + k * dilation) as isize - padding as isize;
+                            if pos >= 0 && (pos as usize) < out_length {
+                                target[[0, pos as usize]] += value;
+                            }
+                        }
+                    }
+                }
+            }
+            if let Some(b) = &bias {
+                for (mut channel, &b) in out_n.outer_iter_mut().zip(b.iter()) {
+                    channel.mapv_inplace(|x| x + b);
+                }
+            }
+        });
+    Ok(spatial_output(out, 1, unbatched))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn conv2d_impl<A>(
+    x: ArrayView4<'_, A>,
+    w: ArrayView4<'_, A>,
+    bias: Option<&ArrayD<A>>,
+    stride: [usize; 2],
+    padding: [usize; 2],
+    dilation: [usize; 2],
+    groups: usize,
+) -> Result<Array4<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    for d in 0..2 {
+        check_positive("stride", stride[d])?;
+        check_positive("dilation", dilation[d])?;
+    }
+    check_positive("groups", groups)?;
+
+    let (n, c_in, h, width) = x.dim();
+    let (c_out, c_in_group, kh, kw) = w.dim();
+    if !c_in.is_multiple_of(groups) {
+        return Err(FunctionalError::GroupMismatch {
+            channels: c_in,
+            groups,
+        });
+    }
+    if !c_out.is_multiple_of(groups) {
+        return Err(FunctionalError::GroupMismatch {
+            channels: c_out,
+            groups,
+        });
+    }
+    if c_in_group * groups != c_in {
+        return Err(FunctionalError::ParameterShapeMismatch {
+            name: "weight",
+            expected: vec![c_out, c_in / groups, kh, kw],
+            found: w.shape().to_vec(),
+        });
+    }
+    let empty = || FunctionalError::EmptyOutput {
+        input: x.shape().to_vec(),
+        weight: w.shape().to_vec(),
+    };
+    let out_h = output_size(h, kh, stride[0], padding[0], dilation[0], false).ok_or_else(empty)?;
+    let out_w =
+        output_size(width, kw, stride[1], padding[1], dilation[1], false).ok_or_else(empty)?;
+    let bias = check_bias(bias, c_out)?;
+
+    let c_out_group = c_out / groups;
+    let patch = c_in_group * kh * kw;
+    let w2 = w
+        .as_standard_layout()
+        .into_owned()
+        .into_shape((c_out, patch))
+        .expect("weight holds C_out * C_in / groups * kH * kW elements");
+
+    let mut out = Array4::<A>::zeros((n, c_out, out_h, out_w));
+    Zip::from(out.outer_iter_mut())
+        .and(x.outer_iter())
+        .par_for_each(|out_n, x_n| {
+            let mut out2 = out_n
+                .into_shape((c_out, out_h * out_w))
+                .expect("output slices are contiguous");
+            let mut cols = Array2::<A>::zeros((patch, out_h * out_w));
+            for g in 0..groups {
+                // im2col: one row per (channel, kernel row, kernel column) of this group
+                for c in 0..c_in_group {
+                    let channel = x_n.index_axis(Axis(0), g * c_in_group + c);
+                    for i in 0..kh {
+                        for j in 0..kw {
+                            let mut row = cols.row_mut((c * kh + i) * kw + j);
+                            for oh in 0..out_h {
+                                let ih = (oh * stride[0] + i * dilation[0]) as isize
+                                    - padding[0] as isize;
+                                for ow in 0..out_w {
+                                    let iw = (ow * stride[1] + j * dilation[1]) as isize
+                                        - padding[1] as isize;
+                                    row[oh * out_w + ow] = if ih >= 0
+                                        && (ih as usize) < h
+                                        && iw >= 0
+                                        && (iw as usize) < width
+                                    {
+                                        channel[[ih as usize, iw as usize]]
+                                    } else {
+                                        A::zero()
+                                    };
+                                }
+                            }
+                        }
+                    }
+                }
+                let rows = g * c_out_group..(g + 1) * c_out_group;
+                let w_g = w2.slice(s![rows.clone(), ..]);
+                let mut out_g = out2.slice_mut(s![rows, ..]);
+                general_mat_mul(A::one(), &w_g, &cols, A::zero(), &mut out_g);
+            }
+            if let Some(b) = &bias {
+                for (mut channel, &b) in out2.outer_iter_mut().zip(b.iter()) {
+                    channel.mapv_inplace(|x| x + b);
+                }
+            }
+        });
+    Ok(out)
+}
+
+/// Number of windows along one spatial dimension, following PyTorch's shape rules.
+///
+/// With `ceil_mode` the last window may run past the input, but must start inside the
+/// input or the left padding. Returns `None` if no window fits.
+pub(super) fn output_size(
+    input: usize,
+    kernel: usize,
+    stride: usize,
+    padding: usize,
+    dilation: usize,
+    ceil_mode: bool,
+) -> Option<usize> {
+    let span = (dilation * (kernel.checked_sub(1)?) + 1) as isize;
+    let room = (input + 2 * padding) as isize - span;
+    if room < 0 {
+        return None;
+    }
+    let room = room as usize;
+    let mut size = if ceil_mode {
+        room.div_ceil(stride) + 1
+    } else {
+        room / stride + 1
+    };
+    if ceil_mode && (size - 1) * stride >= input + padding {
+        size -= 1;
+    }
+    Some(size)
+}
+
+/// Views `input` as `(N, C, H, W)`, inserting `H = 1` for one spatial dimension and `N = 1`
+/// for unbatched inputs. Returns the view and whether the input was unbatched.
+pub(super) fn spatial_input<A>(
+    input: &ArrayD<A>,
+    spatial: usize,
+) -> Result<(ArrayView4<'_, A>, bool), FunctionalError> {
+    let ndim = input.ndim();
+    if ndim != spatial + 1 && ndim != spatial + 2 {
+        return Err(FunctionalError::WrongDimensions {
+            argument: "input",
+            expected: if spatial == 1 { "2D or 3D" } else { "3D or 4D" },
+            shape: input.shape().to_vec(),
+        });
+    }
+    let unbatched = ndim == spatial + 1;
+    let mut view = input.view();
+    if unbatched {
+        view = view.insert_axis(Axis(0));
+    }
+    if spatial == 1 {
+        view = view.insert_axis(Axis(2));
+    }
+    Ok((
+        view.into_dimensionality::<Ix4>()
+            .expect("input was checked to have four dimensions"),
+        unbatched,
+    ))
+}
+
+/// Undoes [`spatial_input`] on an `(N, C, H, W)` result.
+pub(super) fn spatial_output<A>(out: Array4<A>, spatial: usize, unbatched: bool) -> ArrayD<A> {
+    let mut out = out.into_dyn();
+    if spatial == 1 {
+        out = out.remove_axis(Axis(2));
+    }
+    if unbatched {
+        out = out.remove_axis(Axis(0));
+    }
+    out
+}
+
+fn spatial_weight<A>(
+    weight: &ArrayD<A>,
+    spatial: usize,
+) -> Result<ArrayView4<'_, A>, FunctionalError> {
+    if weight.ndim() != spatial + 2 {
+        return Err(FunctionalError::WrongDimensions {
+            argument: "weight",
+            expected: if spatial == 1 { "3D" } else { "4D" },
+            shape: weight.shape().to_vec(),
+        });
+    }
+    let mut view: ArrayViewD<'_, A> = weight.view();
+    if spatial == 1 {
+        view = view.insert_axis(Axis(2));
+    }
+    Ok(view
+        .into_dimensionality::<Ix4>()
+        .expect("weight was checked to have four dimensions"))
+}
+
+fn check_bias<A>(
+    bias: Option<&ArrayD<A>>,
+    channels: usize,
+) -> Result<Option<ArrayViewD<'_, A>>, FunctionalError> {
+    match bias {
+        Some(b) if b.shape() != [channels] => Err(FunctionalError::ParameterShapeMismatch {
+            name: "bias",
+            expected: vec![channels],
+            found: b.shape().to_vec(),
+        }),
+        Some(b) => Ok(Some(b.view())),
+        None => Ok(None),
+    }
+}
+
+pub(super) fn check_positive(name: &'static str, value: usize) -> Result<(), FunctionalError> {
+    if value == 0 {
+        return Err(FunctionalError::NonPositiveParameter { name });
+    }
+    Ok(())
+}
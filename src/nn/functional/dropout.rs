@@ -0,0 +1,32 @@
+use super::FunctionalError;
+use ndarray::{ArrayD, NdFloat};
+
+/// Applies dropout in evaluation mode.
+///
+/// Mimics `torch.nn.functional.dropout(input, p, training=False)`, which returns the input
+/// unchanged. Training mode would need a random mask and is rejected.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `p`: Probability of an element being zeroed, in `[0, 1]`.
+/// * `training`: Must be `false`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: A copy of `input`.
+/// * `Err(FunctionalError)`: If `p` is outside `[0, 1]` or `training` is `true`.
+pub fn dropout<A>(input: &ArrayD<A>, p: A, training: bool) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    if !(p >= A::zero() && p <= A::one()) {
+        return Err(FunctionalError::InvalidProbability {
+            p: format!("{:?}", p),
+        });
+    }
+    if training {
+        return Err(FunctionalError::TrainingModeUnsupported);
+    }
+    Ok(input.to_owned())
+}
@@ -0,0 +1,47 @@
+use super::FunctionalError;
+use ndarray::{ArrayD, Axis, Dimension, Ix2, IxDyn};
+
+/// Looks up rows of an embedding table.
+///
+/// Mimics `torch.nn.functional.embedding(ids, table)`.
+///
+/// # Arguments
+///
+/// * `ids`: Indices into the table, of any shape.
+/// * `table`: A 2D array of shape `(num_embeddings, embedding_dim)`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: Array of shape `ids.shape() + [embedding_dim]`.
+/// * `Err(FunctionalError)`: If `table` is not 2D or an index is negative or out of range.
+pub fn embedding<A>(ids: &ArrayD<i64>, table: &ArrayD<A>) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: Clone,
+{
+    let table2 = table.view().into_dimensionality::<Ix2>().map_err(|_| {
+        FunctionalError::TooFewDimensions {
+            expected: 2,
+            shape: table.shape().to_vec(),
+        }
+    })?;
+    let (num_embeddings, embedding_dim) = table2.dim();
+
+    let mut rows = Vec::with_capacity(ids.len());
+    for (coords, &index) in ids.indexed_iter() {
+        if index < 0 || index as usize >= num_embeddings {
+            return Err(FunctionalError::EmbeddingIndexOutOfBounds {
+                coords: coords.slice().to_vec(),
+                index,
+                num_embeddings,
+            });
+        }
+        rows.push(index as usize);
+    }
+
+    let mut out_shape = ids.shape().to_vec();
+    out_shape.push(embedding_dim);
+    Ok(table2
+        .select(Axis(0), &rows)
+        .into_shape(IxDyn(&out_shape))
+        .expect("selected rows hold ids.len() * embedding_dim elements"))
+}
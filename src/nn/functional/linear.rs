@@ -0,0 +1,75 @@
+use super::FunctionalError;
+use ndarray::linalg::general_mat_mul;
+use ndarray::{Array2, ArrayD, Ix1, Ix2, IxDyn, NdFloat};
+
+/// Applies an affine transformation `x @ weight.T + bias`.
+///
+/// Mimics `torch.nn.functional.linear(x, weight, bias)`.
+///
+/// # Arguments
+///
+/// * `x`: Input of shape `(*, in_features)`.
+/// * `weight`: Weight of shape `(out_features, in_features)`.
+/// * `bias`: Optional bias of shape `(out_features,)`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: Output of shape `(*, out_features)`.
+/// * `Err(FunctionalError)`: If the shapes do not line up.
+pub fn linear<A>(
+    x: &ArrayD<A>,
+    weight: &ArrayD<A>,
+    bias: Option<&ArrayD<A>>,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    if x.ndim() == 0 {
+        return Err(FunctionalError::TooFewDimensions {
+            expected: 1,
+            shape: x.shape().to_vec(),
+        });
+    }
+    let in_features = x.shape()[x.ndim() - 1];
+    let w = weight
+        .view()
+        .into_dimensionality::<Ix2>()
+        .ok()
+        .filter(|w| w.ncols() == in_features);
+    let w = w.ok_or_else(|| FunctionalError::ParameterShapeMismatch {
+        name: "weight",
+        expected: vec![weight.shape().first().copied().unwrap_or(0), in_features],
+        found: weight.shape().to_vec(),
+    })?;
+    let out_features = w.nrows();
+
+    // Dividing `x.len()` by `in_features` would lose the rows when there are no features
+    let rows = x.shape()[..x.ndim() - 1].iter().product::<usize>();
+    let x2 = x
+        .as_standard_layout()
+        .into_owned()
+        .into_shape((rows, in_features))
+        .map_err(|e| FunctionalError::InternalShapeError(e.to_string()))?;
+
+    let mut out = Array2::<A>::zeros((rows, out_features));
+    general_mat_mul(A::one(), &x2, &w.t(), A::zero(), &mut out);
+
+    if let Some(bias) = bias {
+        let b = bias
+            .view()
+            .into_dimensionality::<Ix1>()
+            .ok()
+            .filter(|b| b.len() == out_features)
+            .ok_or_else(|| FunctionalError::ParameterShapeMismatch {
+                name: "bias",
+                expected: vec![out_features],
+                found: bias.shape().to_vec(),
+            })?;
+        out += &b;
+    }
+
+    let mut out_shape = x.shape().to_vec();
+    out_shape[x.ndim() - 1] = out_features;
+    out.into_shape(IxDyn(&out_shape))
+        .map_err(|e| FunctionalError::InternalShapeError(e.to_string()))
+}
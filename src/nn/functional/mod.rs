@@ -0,0 +1,117 @@
+//! Functional neural-network ops, mirroring `torch.nn.functional`.
+
+mod activation;
+mod attention;
+mod conv;
+mod dropout;
+mod embedding;
+mod linear;
+mod normalization;
+mod pooling;
+
+pub use activation::{log_softmax, softmax};
+pub use attention::{AttentionMask, scaled_dot_product_attention};
+pub use conv::{conv_transpose1d, conv1d, conv2d};
+pub use dropout::dropout;
+pub use embedding::embedding;
+pub use linear::linear;
+pub use normalization::{group_norm, layer_norm, rms_norm};
+pub use pooling::{
+    adaptive_avg_pool1d, adaptive_avg_pool2d, avg_pool1d, avg_pool2d, max_pool1d, max_pool2d,
+};
+
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum FunctionalError {
+    #[error("Dimension index {dim} is out of bounds for array with {ndim} dimensions")]
+    InvalidDimension { dim: isize, ndim: usize },
+
+    #[error("Expected input with at least {expected} dimensions, got shape {shape:?}")]
+    TooFewDimensions { expected: usize, shape: Vec<usize> },
+
+    #[error(
+        "Normalized shape {normalized_shape:?} does not match the trailing dimensions of input shape {input_shape:?}"
+    )]
+    NormalizedShapeMismatch {
+        normalized_shape: Vec<usize>,
+        input_shape: Vec<usize>,
+    },
+
+    #[error("Expected {name} to have shape {expected:?}, got {found:?}")]
+    ParameterShapeMismatch {
+        name: &'static str,
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+
+    #[error("Expected {argument} to be {expected}, got shape {shape:?}")]
+    WrongDimensions {
+        argument: &'static str,
+        expected: &'static str,
+        shape: Vec<usize>,
+    },
+
+    #[error("Expected {name} to be positive")]
+    NonPositiveParameter { name: &'static str },
+
+    #[error("Output size is too small for input of shape {input:?} and kernel {weight:?}")]
+    EmptyOutput {
+        input: Vec<usize>,
+        weight: Vec<usize>,
+    },
+
+    #[error("Padding {padding} should be at most half of the kernel size {kernel_size}")]
+    PoolPaddingTooLarge { padding: usize, kernel_size: usize },
+
+    #[error(
+        "Output padding {output_padding} must be smaller than either stride {stride} or dilation {dilation}"
+    )]
+    OutputPaddingTooLarge {
+        output_padding: usize,
+        stride: usize,
+        dilation: usize,
+    },
+
+    #[error("Number of channels ({channels}) is not divisible by the number of groups ({groups})")]
+    GroupMismatch { channels: usize, groups: usize },
+
+    #[error("Dropout probability has to be between 0 and 1, got {p}")]
+    InvalidProbability { p: String },
+
+    #[error(
+        "Dropout in training mode needs a random number generator, which this op does not take"
+    )]
+    TrainingModeUnsupported,
+
+    #[error("Incompatible attention shapes: query {query:?}, key {key:?}, value {value:?}")]
+    AttentionShapeMismatch {
+        query: Vec<usize>,
+        key: Vec<usize>,
+        value: Vec<usize>,
+    },
+
+    #[error("Query batch shape of {query:?} cannot be broadcast with key shape {key:?}")]
+    AttentionBroadcast { query: Vec<usize>, key: Vec<usize> },
+
+    #[error("Attention mask of shape {mask:?} cannot be broadcast to scores of shape {scores:?}")]
+    AttentionMaskShape {
+        mask: Vec<usize>,
+        scores: Vec<usize>,
+    },
+
+    #[error("An explicit attention mask cannot be combined with is_causal")]
+    CausalWithMask,
+
+    #[error(
+        "Index {index} at {coords:?} is out of range for an embedding table with {num_embeddings} rows"
+    )]
+    EmbeddingIndexOutOfBounds {
+        coords: Vec<usize>,
+        index: i64,
+        num_embeddings: usize,
+    },
+
+    #[error("Internal error during array creation: {0}")]
+    InternalShapeError(String),
+}
@@ -0,0 +1,227 @@
+use super::FunctionalError;
+use ndarray::{ArrayD, ArrayViewD, Axis, IxDyn, NdFloat};
+
+/// Applies layer normalization over the trailing `normalized_shape` dimensions.
+///
+/// Mimics `torch.nn.functional.layer_norm(input, normalized_shape, weight, bias, eps)`,
+/// using the biased variance.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `normalized_shape`: The trailing shape to normalize over.
+/// * `weight`: Optional elementwise scale of shape `normalized_shape`.
+/// * `bias`: Optional elementwise offset of shape `normalized_shape`.
+/// * `eps`: Value added to the variance for numerical stability (PyTorch uses `1e-5`).
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The normalized array, with the same shape as `input`.
+/// * `Err(FunctionalError)`: If `normalized_shape` or the parameters do not match `input`.
+pub fn layer_norm<A>(
+    input: &ArrayD<A>,
+    normalized_shape: &[usize],
+    weight: Option<&ArrayD<A>>,
+    bias: Option<&ArrayD<A>>,
+    eps: A,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    check_normalized_shape(input, normalized_shape)?;
+    let weight = check_parameter("weight", weight, normalized_shape)?;
+    let bias = check_parameter("bias", bias, normalized_shape)?;
+
+    let mut output = normalize_rows(input, normalized_shape.iter().product(), |row| {
+        let n = A::from(row.len()).unwrap();
+        let mean = row.iter().fold(A::zero(), |acc, &x| acc + x) / n;
+        let var = row
+            .iter()
+            .fold(A::zero(), |acc, &x| acc + (x - mean) * (x - mean))
+            / n;
+        (mean, (var + eps).sqrt())
+    });
+    apply_affine(&mut output, weight, bias);
+    Ok(output)
+}
+
+/// Applies root mean square normalization over the trailing `normalized_shape` dimensions.
+///
+/// Mimics `torch.nn.functional.rms_norm(input, normalized_shape, weight, eps)`:
+/// `x / sqrt(mean(x^2) + eps) * weight`.
+///
+/// # Arguments
+///
+/// * `input`: The input array.
+/// * `normalized_shape`: The trailing shape to normalize over.
+/// * `weight`: Optional elementwise scale of shape `normalized_shape`.
+/// * `eps`: Value added to the mean square. `None` uses the machine epsilon of `A`, like PyTorch.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The normalized array, with the same shape as `input`.
+/// * `Err(FunctionalError)`: If `normalized_shape` or `weight` do not match `input`.
+pub fn rms_norm<A>(
+    input: &ArrayD<A>,
+    normalized_shape: &[usize],
+    weight: Option<&ArrayD<A>>,
+    eps: Option<A>,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    check_normalized_shape(input, normalized_shape)?;
+    let weight = check_parameter("weight", weight, normalized_shape)?;
+    let eps = eps.unwrap_or_else(A::epsilon);
+
+    let mut output = normalize_rows(input, normalized_shape.iter().product(), |row| {
+        let n = A::from(row.len()).unwrap();
+        let mean_square = row.iter().fold(A::zero(), |acc, &x| acc + x * x) / n;
+        (A::zero(), (mean_square + eps).sqrt())
+    });
+    apply_affine(&mut output, weight, None);
+    Ok(output)
+}
+
+/// Applies group normalization to an input of shape `(N, C, *)`.
+///
+/// Mimics `torch.nn.functional.group_norm(input, num_groups, weight, bias, eps)`. Channels are
+/// split into `num_groups` groups and each group is normalized together with all spatial
+/// positions; `weight` and `bias` are applied per channel.
+///
+/// # Arguments
+///
+/// * `input`: The input array of shape `(N, C, *)`.
+/// * `num_groups`: Number of groups, must divide `C`.
+/// * `weight`: Optional per-channel scale of shape `(C,)`.
+/// * `bias`: Optional per-channel offset of shape `(C,)`.
+/// * `eps`: Value added to the variance for numerical stability.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The normalized array, with the same shape as `input`.
+/// * `Err(FunctionalError)`: If `input` has fewer than 2 dimensions, `num_groups` does not
+///   divide `C`, or the parameters have the wrong shape.
+pub fn group_norm<A>(
+    input: &ArrayD<A>,
+    num_groups: usize,
+    weight: Option<&ArrayD<A>>,
+    bias: Option<&ArrayD<A>>,
+    eps: A,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    if input.ndim() < 2 {
+        return Err(FunctionalError::TooFewDimensions {
+            expected: 2,
+            shape: input.shape().to_vec(),
+        });
+    }
+    let channels = input.shape()[1];
+    if num_groups == 0 || !channels.is_multiple_of(num_groups) {
+        return Err(FunctionalError::GroupMismatch {
+            channels,
+            groups: num_groups,
+        });
+    }
+    let weight = check_parameter("weight", weight, &[channels])?;
+    let bias = check_parameter("bias", bias, &[channels])?;
+
+    // Each (sample, group) pair is one contiguous row in standard layout
+    let group_len = input.len() / (input.shape()[0] * num_groups).max(1);
+    let mut output = normalize_rows(input, group_len, |row| {
+        let n = A::from(row.len()).unwrap();
+        let mean = row.iter().fold(A::zero(), |acc, &x| acc + x) / n;
+        let var = row
+            .iter()
+            .fold(A::zero(), |acc, &x| acc + (x - mean) * (x - mean))
+            / n;
+        (mean, (var + eps).sqrt())
+    });
+
+    if weight.is_some() || bias.is_some() {
+        for mut sample in output.axis_iter_mut(Axis(0)) {
+            for (c, mut channel) in sample.axis_iter_mut(Axis(0)).enumerate() {
+                let scale = weight.as_ref().map_or(A::one(), |w| w[c]);
+                let shift = bias.as_ref().map_or(A::zero(), |b| b[c]);
+                channel.mapv_inplace(|x| x * scale + shift);
+            }
+        }
+    }
+    Ok(output)
+}
+
+fn check_normalized_shape<A>(
+    input: &ArrayD<A>,
+    normalized_shape: &[usize],
+) -> Result<(), FunctionalError> {
+    let ndim = input.ndim();
+    if normalized_shape.is_empty()
+        || normalized_shape.len() > ndim
+        || input.shape()[ndim - normalized_shape.len()..] != *normalized_shape
+    {
+        return Err(FunctionalError::NormalizedShapeMismatch {
+            normalized_shape: normalized_shape.to_vec(),
+            input_shape: input.shape().to_vec(),
+        });
+    }
+    Ok(())
+}
+
+fn check_parameter<'a, A>(
+    name: &'static str,
+    parameter: Option<&'a ArrayD<A>>,
+    expected: &[usize],
+) -> Result<Option<ArrayViewD<'a, A>>, FunctionalError> {
+    match parameter {
+        Some(p) if p.shape() != expected => Err(FunctionalError::ParameterShapeMismatch {
+            name,
+            expected: expected.to_vec(),
+            found: p.shape().to_vec(),
+        }),
+        Some(p) => Ok(Some(p.view())),
+        None => Ok(None),
+    }
+}
+
+/// Splits `input` (in standard layout) into rows of `row_len` elements and maps each row to
+/// `(x - shift) / scale` with the `(shift, scale)` returned by `stats`.
+fn normalize_rows<A, F>(input: &ArrayD<A>, row_len: usize, stats: F) -> ArrayD<A>
+where
+    A: NdFloat,
+    F: Fn(&[A]) -> (A, A),
+{
+    let mut output = input.as_standard_layout().into_owned();
+    if row_len == 0 {
+        return output;
+    }
+    let data = output
+        .as_slice_mut()
+        .expect("standard layout arrays are contiguous");
+    for row in data.chunks_mut(row_len) {
+        let (shift, scale) = stats(row);
+        for x in row.iter_mut() {
+            *x = (*x - shift) / scale;
+        }
+    }
+    output
+}
+
+/// Applies an optional elementwise `weight` and `bias` broadcast over the trailing dimensions.
+fn apply_affine<A>(
+    output: &mut ArrayD<A>,
+    weight: Option<ArrayViewD<'_, A>>,
+    bias: Option<ArrayViewD<'_, A>>,
+) where
+    A: NdFloat,
+{
+    if let Some(w) = weight {
+        let w = w.broadcast(IxDyn(output.shape())).unwrap();
+        *output *= &w;
+    }
+    if let Some(b) = bias {
+        let b = b.broadcast(IxDyn(output.shape())).unwrap();
+        *output += &b;
+    }
+}
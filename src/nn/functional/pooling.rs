@@ -0,0 +1,380 @@
+use super::FunctionalError;
+use super::conv::{check_positive, output_size, spatial_input, spatial_output};
+use ndarray::{Array4, ArrayD, ArrayView4, NdFloat, Zip};
+
+/// Applies 1D max pooling over an input of shape `(N, C, L)` or `(C, L)`.
+///
+/// Mimics `torch.nn.functional.max_pool1d(input, kernel_size, stride, padding, dilation, ceil_mode)`.
+/// Padded positions never win the max.
+///
+/// # Arguments
+///
+/// * `input`: Input of shape `(N, C, L)` or unbatched `(C, L)`.
+/// * `kernel_size`: Size of the pooling window.
+/// * `stride`: Step between windows, `kernel_size` when `None`.
+/// * `padding`: Implicit `-inf` padding on both sides, at most half the kernel size.
+/// * `dilation`: Spacing between window elements.
+/// * `ceil_mode`: Use ceil instead of floor when computing the output size.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The pooled array.
+/// * `Err(FunctionalError)`: If the shapes or parameters are invalid.
+pub fn max_pool1d<A>(
+    input: &ArrayD<A>,
+    kernel_size: usize,
+    stride: Option<usize>,
+    padding: usize,
+    dilation: usize,
+    ceil_mode: bool,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let (x, unbatched) = spatial_input(input, 1)?;
+    let window = Window::new(
+        [1, kernel_size],
+        [Some(1), stride],
+        [0, padding],
+        [1, dilation],
+        ceil_mode,
+    )?;
+    Ok(spatial_output(max_pool(x, &window)?, 1, unbatched))
+}
+
+/// Applies 2D max pooling over an input of shape `(N, C, H, W)` or `(C, H, W)`.
+///
+/// Mimics `torch.nn.functional.max_pool2d(input, kernel_size, stride, padding, dilation, ceil_mode)`.
+///
+/// # Arguments
+///
+/// * `input`: Input of shape `(N, C, H, W)` or unbatched `(C, H, W)`.
+/// * `kernel_size`: Size of the pooling window, as `[height, width]`.
+/// * `stride`: Step between windows, `kernel_size` when `None`.
+/// * `padding`: Implicit `-inf` padding on both sides, at most half the kernel size.
+/// * `dilation`: Spacing between window elements.
+/// * `ceil_mode`: Use ceil instead of floor when computing the output size.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The pooled array.
+/// * `Err(FunctionalError)`: If the shapes or parameters are invalid.
+pub fn max_pool2d<A>(
+    input: &ArrayD<A>,
+    kernel_size: [usize; 2],
+    stride: Option<[usize; 2]>,
+    padding: [usize; 2],
+    dilation: [usize; 2],
+    ceil_mode: bool,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let (x, unbatched) = spatial_input(input, 2)?;
+    let stride = stride.map_or([None, None], |s| [Some(s[0]), Some(s[1])]);
+    let window = Window::new(kernel_size, stride, padding, dilation, ceil_mode)?;
+    Ok(spatial_output(max_pool(x, &window)?, 2, unbatched))
+}
+
+/// Applies 1D average pooling over an input of shape `(N, C, L)` or `(C, L)`.
+///
+/// Mimics `torch.nn.functional.avg_pool1d(input, kernel_size, stride, padding, ceil_mode,
+/// count_include_pad)`.
+///
+/// # Arguments
+///
+/// * `input`: Input of shape `(N, C, L)` or unbatched `(C, L)`.
+/// * `kernel_size`: Size of the pooling window.
+/// * `stride`: Step between windows, `kernel_size` when `None`.
+/// * `padding`: Implicit zero padding on both sides, at most half the kernel size.
+/// * `ceil_mode`: Use ceil instead of floor when computing the output size.
+/// * `count_include_pad`: Whether padded zeros count towards the divisor.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The pooled array.
+/// * `Err(FunctionalError)`: If the shapes or parameters are invalid.
+pub fn avg_pool1d<A>(
+    input: &ArrayD<A>,
+    kernel_size: usize,
+    stride: Option<usize>,
+    padding: usize,
+    ceil_mode: bool,
+    count_include_pad: bool,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let (x, unbatched) = spatial_input(input, 1)?;
+    let window = Window::new(
+        [1, kernel_size],
+        [Some(1), stride],
+        [0, padding],
+        [1, 1],
+        ceil_mode,
+    )?;
+    Ok(spatial_output(
+        avg_pool(x, &window, count_include_pad)?,
+        1,
+        unbatched,
+    ))
+}
+
+/// Applies 2D average pooling over an input of shape `(N, C, H, W)` or `(C, H, W)`.
+///
+/// Mimics `torch.nn.functional.avg_pool2d(input, kernel_size, stride, padding, ceil_mode,
+/// count_include_pad)`.
+///
+/// # Arguments
+///
+/// * `input`: Input of shape `(N, C, H, W)` or unbatched `(C, H, W)`.
+/// * `kernel_size`: Size of the pooling window, as `[height, width]`.
+/// * `stride`: Step between windows, `kernel_size` when `None`.
+/// * `padding`: Implicit zero padding on both sides, at most half the kernel size.
+/// * `ceil_mode`: Use ceil instead of floor when computing the output size.
+/// * `count_include_pad`: Whether padded zeros count towards the divisor.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The pooled array.
+/// * `Err(FunctionalError)`: If the shapes or parameters are invalid.
+pub fn avg_pool2d<A>(
+    input: &ArrayD<A>,
+    kernel_size: [usize; 2],
+    stride: Option<[usize; 2]>,
+    padding: [usize; 2],
+    ceil_mode: bool,
+    count_include_pad: bool,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let (x, unbatched) = spatial_input(input, 2)?;
+    let stride = stride.map_or([None, None], |s| [Some(s[0]), Some(s[1])]);
+    let window = Window::new(kernel_size, stride, padding, [1, 1], ceil_mode)?;
+    Ok(spatial_output(
+        avg_pool(x, &window, count_include_pad)?,
+        2,
+        unbatched,
+    ))
+}
+
+/// Applies 1D adaptive average pooling over an input of shape `(N, C, L)` or `(C, L)`.
+///
+/// Mimics `torch.nn.functional.adaptive_avg_pool1d(input, output_size)`. Output position `i`
+/// averages input positions `floor(i * L / out)` up to `ceil((i + 1) * L / out)`.
+///
+/// # Arguments
+///
+/// * `input`: Input of shape `(N, C, L)` or unbatched `(C, L)`.
+/// * `output_size`: The target length.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The pooled array.
+/// * `Err(FunctionalError)`: If the input has the wrong number of dimensions or `output_size` is 0.
+pub fn adaptive_avg_pool1d<A>(
+    input: &ArrayD<A>,
+    output_size: usize,
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let (x, unbatched) = spatial_input(input, 1)?;
+    check_positive("output_size", output_size)?;
+    Ok(spatial_output(
+        adaptive_avg_pool(x, [1, output_size]),
+        1,
+        unbatched,
+    ))
+}
+
+/// Applies 2D adaptive average pooling over an input of shape `(N, C, H, W)` or `(C, H, W)`.
+///
+/// Mimics `torch.nn.functional.adaptive_avg_pool2d(input, output_size)`.
+///
+/// # Arguments
+///
+/// * `input`: Input of shape `(N, C, H, W)` or unbatched `(C, H, W)`.
+/// * `output_size`: The target size, as `[height, width]`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<A>)`: The pooled array.
+/// * `Err(FunctionalError)`: If the input has the wrong number of dimensions or `output_size` has a 0.
+pub fn adaptive_avg_pool2d<A>(
+    input: &ArrayD<A>,
+    output_size: [usize; 2],
+) -> Result<ArrayD<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let (x, unbatched) = spatial_input(input, 2)?;
+    check_positive("output_size", output_size[0])?;
+    check_positive("output_size", output_size[1])?;
+    Ok(spatial_output(
+        adaptive_avg_pool(x, output_size),
+        2,
+        unbatched,
+    ))
+}
+
+/// Validated pooling window parameters for the `(H, W)` dimensions.
+struct Window {
+    kernel: [usize; 2],
+    stride: [usize; 2],
+    padding: [usize; 2],
+    dilation: [usize; 2],
+    ceil_mode: bool,
+}
+
+impl Window {
+    fn new(
+        kernel: [usize; 2],
+        stride: [Option<usize>; 2],
+        padding: [usize; 2],
+        dilation: [usize; 2],
+        ceil_mode: bool,
+    ) -> Result<Self, FunctionalError> {
+        let stride = [
+            stride[0].unwrap_or(kernel[0]),
+            stride[1].unwrap_or(kernel[1]),
+        ];
+        for d in 0..2 {
+            check_positive("kernel_size", kernel[d])?;
+            check_positive("stride", stride[d])?;
+            check_positive("dilation", dilation[d])?;
+            if padding[d] > kernel[d] / 2 {
+                return Err(FunctionalError::PoolPaddingTooLarge {
+                    padding: padding[d],
+                    kernel_size: kernel[d],
+                });
+            }
+        }
+        Ok(Self {
+            kernel,
+            stride,
+            padding,
+            dilation,
+            ceil_mode,
+        })
+    }
+
+    fn output_shape<A>(&self, x: &ArrayView4<'_, A>) -> Result<(usize, usize), FunctionalError> {
+        let (_, _, h, w) = x.dim();
+        let size = |d: usize, input: usize| {
+            output_size(
+                input,
+                self.kernel[d],
+                self.stride[d],
+                self.padding[d],
+                self.dilation[d],
+                self.ceil_mode,
+            )
+        };
+        match (size(0, h), size(1, w)) {
+            (Some(oh), Some(ow)) => Ok((oh, ow)),
+            _ => Err(FunctionalError::EmptyOutput {
+                input: x.shape().to_vec(),
+                weight: self.kernel.to_vec(),
+            }),
+        }
+    }
+
+    /// Start of window `o` along dimension `d`, relative to the unpadded input.
+    fn start(&self, d: usize, o: usize) -> isize {
+        (o * self.stride[d]) as isize - self.padding[d] as isize
+    }
+}
+
+fn max_pool<A>(x: ArrayView4<'_, A>, window: &Window) -> Result<Array4<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let (n, c, h, w) = x.dim();
+    let (out_h, out_w) = window.output_shape(&x)?;
+    let mut out = Array4::<A>::zeros((n, c, out_h, out_w));
+    Zip::indexed(&mut out).par_for_each(|(b, ch, oh, ow), y| {
+        let mut max = A::neg_infinity();
+        for i in 0..window.kernel[0] {
+            let ih = window.start(0, oh) + (i * window.dilation[0]) as isize;
+            if ih < 0 || ih as usize >= h {
+                continue;
+            }
+            for j in 0..window.kernel[1] {
+                let iw = window.start(1, ow) + (j * window.dilation[1]) as isize;
+                if iw < 0 || iw as usize >= w {
+                    continue;
+                }
+                let value = x[[b, ch, ih as usize, iw as usize]];
+                // NaN propagates, like PyTorch
+                if value > max || value.is_nan() {
+                    max = value;
+                }
+                if max.is_nan() {
+                    break;
+                }
+            }
+        }
+        *y = max;
+    });
+    Ok(out)
+}
+
+fn avg_pool<A>(
+    x: ArrayView4<'_, A>,
+    window: &Window,
+    count_include_pad: bool,
+) -> Result<Array4<A>, FunctionalError>
+where
+    A: NdFloat,
+{
+    let (n, c, h, w) = x.dim();
+    let (out_h, out_w) = window.output_shape(&x)?;
+    let mut out = Array4::<A>::zeros((n, c, out_h, out_w));
+    Zip::indexed(&mut out).par_for_each(|(b, ch, oh, ow), y| {
+        // Window bounds clipped to the padded input, then to the input itself
+        let h0 = window.start(0, oh);
+        let w0 = window.start(1, ow);
+        let h1 = (h0 + window.kernel[0] as isize).min((h + window.padding[0]) as isize);
+        let w1 = (w0 + window.kernel[1] as isize).min((w + window.padding[1]) as isize);
+        let padded_count = (h1 - h0) * (w1 - w0);
+        let (h0, w0) = (h0.max(0) as usize, w0.max(0) as usize);
+        let (h1, w1) = (h1.min(h as isize) as usize, w1.min(w as isize) as usize);
+
+        let mut sum = A::zero();
+        for ih in h0..h1 {
+            for iw in w0..w1 {
+                sum += x[[b, ch, ih, iw]];
+            }
+        }
+        let count = if count_include_pad {
+            padded_count as usize
+        } else {
+            (h1.saturating_sub(h0)) * (w1.saturating_sub(w0))
+        };
+        *y = sum / A::from(count).unwrap();
+    });
+    Ok(out)
+}
+
+fn adaptive_avg_pool<A>(x: ArrayView4<'_, A>, output_size: [usize; 2]) -> Array4<A>
+where
+    A: NdFloat,
+{
+    let (n, c, h, w) = x.dim();
+    let [out_h, out_w] = output_size;
+    let mut out = Array4::<A>::zeros((n, c, out_h, out_w));
+    Zip::indexed(&mut out).par_for_each(|(b, ch, oh, ow), y| {
+        let (h0, h1) = (oh * h / out_h, ((oh + 1) * h).div_ceil(out_h));
+        let (w0, w1) = (ow * w / out_w, ((ow + 1) * w).div_ceil(out_w));
+        let mut sum = A::zero();
+        for ih in h0..h1 {
+            for iw in w0..w1 {
+                sum += x[[b, ch, ih, iw]];
+            }
+        }
+        *y = sum / A::from((h1 - h0) * (w1 - w0)).unwrap();
+    });
+    out
+}
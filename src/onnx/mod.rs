@@ -0,0 +1,74 @@
+//! Loading ONNX models and running them on the CPU with the ops in [`crate::functions`].
+//!
+//! [`Model::open`] decodes a `ModelProto` with the schema subset in `proto.rs`, converts the
+//! initializers into [`Tensor`](crate::io::Tensor)s and sorts the nodes topologically. Models
+//! using ops the interpreter does not implement are rejected up front with
+//! [`OnnxError::UnsupportedOps`], which names every missing op. [`Model::run`] then evaluates
+//! the graph node by node, dropping intermediate values after their last use.
+//!
+//! Supported ops: `Abs`, `ArgMax`, `Constant`, `Einsum`, `Expand`, `Gather`,
+//! `GatherElements`, `Identity`, `ReduceMax`, `Reshape`, `ScatterElements`, `Slice` and
+//! `Transpose`. Tensors stored outside the model file, and element types other than `float`,
+//! `double`, `float16`, `bfloat16`, `int64` and `bool`, are not supported.
+
+mod model;
+mod ops;
+mod proto;
+mod tensor;
+
+pub use model::Model;
+
+use crate::io::Dtype;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum OnnxError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid ONNX protobuf: {0}")]
+    Decode(#[from] prost::DecodeError),
+
+    #[error("The model has no graph")]
+    MissingGraph,
+
+    #[error("Unsupported ONNX ops: {}", ops.join(", "))]
+    UnsupportedOps { ops: Vec<String> },
+
+    #[error("Tensor {name:?} has unsupported data type {data_type}")]
+    UnsupportedDataType { name: String, data_type: i32 },
+
+    #[error("Tensor {name:?} is stored in an external file, which is not supported")]
+    ExternalData { name: String },
+
+    #[error("Tensor {name:?} is invalid: {reason}")]
+    InvalidTensor { name: String, reason: String },
+
+    #[error("Nodes {nodes:?} read values that no node, input or initializer produces")]
+    UnresolvedInputs { nodes: Vec<String> },
+
+    #[error("Graph output {name:?} is not produced by any node, input or initializer")]
+    MissingOutput { name: String },
+
+    #[error("Graph input {name:?} was not provided")]
+    MissingInput { name: String },
+
+    #[error("Node {node:?} is missing attribute {attribute:?}")]
+    MissingAttribute { node: String, attribute: String },
+
+    #[error("Node {node:?} has invalid attribute {attribute:?}: {reason}")]
+    InvalidAttribute {
+        node: String,
+        attribute: String,
+        reason: String,
+    },
+
+    #[error("Node {node:?} has an invalid input: {reason}")]
+    InvalidInput { node: String, reason: String },
+
+    #[error("Node {node:?} does not support dtype {dtype}")]
+    UnsupportedDtype { node: String, dtype: Dtype },
+
+    #[error("Node {node:?} failed: {message}")]
+    OpFailed { node: String, message: String },
+}
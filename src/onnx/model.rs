@@ -0,0 +1,236 @@
+use super::OnnxError;
+use super::ops::{SUPPORTED_OPS, run_node};
+use super::proto::{ModelProto, NodeProto};
+use super::tensor::to_tensor;
+use crate::io::Tensor;
+use prost::Message;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// An ONNX model ready to run.
+#[derive(Debug, Clone)]
+pub struct Model {
+    /// Nodes in an order where every node comes after the nodes it reads.
+    nodes: Vec<NodeProto>,
+    /// Display name of every node, for errors.
+    names: Vec<String>,
+    initializers: HashMap<String, Tensor>,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+    /// Index of the last node reading each value, so it can be dropped afterwards.
+    last_use: HashMap<String, usize>,
+    opset: i64,
+}
+
+impl Model {
+    /// Reads and prepares the model stored at `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: Path to an `.onnx` file.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Model)`: The model, ready to [`run`](Model::run).
+    /// * `Err(OnnxError)`: If the file cannot be read, is not a valid model or uses unsupported
+    ///   ops or data types.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, OnnxError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Prepares a model from the bytes of a serialized `ModelProto`. See [`Model::open`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, OnnxError> {
+        let model = ModelProto::decode(bytes)?;
+        let graph = model.graph.ok_or(OnnxError::MissingGraph)?;
+        // Nodes of the default domain follow its opset; other domains are not supported
+        let opset = model
+            .opset_import
+            .iter()
+            .find(|o| o.domain.is_empty() || o.domain == "ai.onnx")
+            .map_or(1, |o| o.version);
+
+        let mut unsupported: Vec<String> = Vec::new();
+        for node in &graph.node {
+            let name = match node.domain.as_str() {
+                "" | "ai.onnx" => node.op_type.clone(),
+                domain => format!("{domain}.{}", node.op_type),
+            };
+            if (name != node.op_type || !SUPPORTED_OPS.contains(&name.as_str()))
+                && !unsupported.contains(&name)
+            {
+                unsupported.push(name);
+            }
+        }
+        if !unsupported.is_empty() {
+            return Err(OnnxError::UnsupportedOps { ops: unsupported });
+        }
+
+        let initializers = graph
+            .initializer
+            .iter()
+            .map(|t| Ok((t.name.clone(), to_tensor(t)?)))
+            .collect::<Result<HashMap<_, _>, OnnxError>>()?;
+        // Before IR version 4 initializers were listed among the inputs as well
+        let inputs: Vec<String> = graph
+            .input
+            .iter()
+            .map(|v| v.name.clone())
+            .filter(|name| !initializers.contains_key(name))
+            .collect();
+        let outputs = graph.output.iter().map(|v| v.name.clone()).collect();
+
+        let names: Vec<String> = graph
+            .node
+            .iter()
+            .enumerate()
+            .map(|(i, node)| match node.name.is_empty() {
+                true => format!("{}_{i}", node.op_type),
+                false => node.name.clone(),
+            })
+            .collect();
+        let order = topological_order(&graph.node, &initializers, &inputs, &names)?;
+        let produced: HashSet<&str> = graph
+            .node
+            .iter()
+            .flat_map(|node| node.output.iter())
+            .chain(&inputs)
+            .map(String::as_str)
+            .collect();
+        if let Some(name) = graph.output.iter().map(|v| &v.name).find(|name| {
+            !produced.contains(name.as_str()) && !initializers.contains_key(name.as_str())
+        }) {
+            return Err(OnnxError::MissingOutput { name: name.clone() });
+        }
+        let nodes: Vec<NodeProto> = order.iter().map(|&i| graph.node[i].clone()).collect();
+        let names = order.iter().map(|&i| names[i].clone()).collect();
+
+        let mut last_use = HashMap::new();
+        for (i, node) in nodes.iter().enumerate() {
+            for input in &node.input {
+                last_use.insert(input.clone(), i);
+            }
+        }
+        Ok(Model {
+            nodes,
+            names,
+            initializers,
+            inputs,
+            outputs,
+            last_use,
+            opset,
+        })
+    }
+
+    /// Names of the graph inputs that must be passed to [`run`](Model::run).
+    pub fn inputs(&self) -> &[String] {
+        &self.inputs
+    }
+
+    /// Names of the graph outputs returned by [`run`](Model::run).
+    pub fn outputs(&self) -> &[String] {
+        &self.outputs
+    }
+
+    /// Version of the default ONNX opset the model was exported with.
+    pub fn opset(&self) -> i64 {
+        self.opset
+    }
+
+    pub fn initializer(&self, name: &str) -> Option<&Tensor> {
+        self.initializers.get(name)
+    }
+
+    /// Runs the graph.
+    ///
+    /// # Arguments
+    ///
+    /// * `inputs`: A value for every name in [`inputs`](Model::inputs). Values for names that
+    ///   are initializers override them, like ONNX Runtime does.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(HashMap<String, Tensor>)`: The value of every graph output.
+    /// * `Err(OnnxError)`: If an input is missing or a node fails.
+    pub fn run(
+        &self,
+        inputs: HashMap<String, Tensor>,
+    ) -> Result<HashMap<String, Tensor>, OnnxError> {
+        if let Some(name) = self.inputs.iter().find(|name| !inputs.contains_key(*name)) {
+            return Err(OnnxError::MissingInput { name: name.clone() });
+        }
+        let keep: HashSet<&str> = self.outputs.iter().map(String::as_str).collect();
+        let mut values = inputs;
+        for (i, (node, name)) in self.nodes.iter().zip(&self.names).enumerate() {
+            let outputs = {
+                let args: Vec<Option<&Tensor>> = node
+                    .input
+                    .iter()
+                    .map(|input| match input.is_empty() {
+                        // An empty name marks an omitted optional input
+                        true => None,
+                        false => values.get(input).or_else(|| self.initializers.get(input)),
+                    })
+                    .collect();
+                run_node(node, name, self.opset, &args)?
+            };
+            for input in &node.input {
+                if self.last_use.get(input) == Some(&i) && !keep.contains(input.as_str()) {
+                    values.remove(input);
+                }
+            }
+            for (output, value) in node.output.iter().zip(outputs) {
+                if !output.is_empty() {
+                    values.insert(output.clone(), value);
+                }
+            }
+        }
+
+        Ok(self
+            .outputs
+            .iter()
+            .map(|name| {
+                let value = values
+                    .get(name)
+                    .or_else(|| self.initializers.get(name))
+                    .expect("outputs are checked when loading");
+                (name.clone(), value.clone())
+            })
+            .collect())
+    }
+}
+
+/// Orders the nodes so that each comes after the producers of its inputs, keeping the file
+/// order where possible.
+fn topological_order(
+    nodes: &[NodeProto],
+    initializers: &HashMap<String, Tensor>,
+    inputs: &[String],
+    names: &[String],
+) -> Result<Vec<usize>, OnnxError> {
+    let mut available: HashSet<&str> = initializers.keys().map(String::as_str).collect();
+    available.extend(inputs.iter().map(String::as_str));
+    available.insert("");
+
+    let mut order = Vec::with_capacity(nodes.len());
+    let mut pending: Vec<usize> = (0..nodes.len()).collect();
+    while !pending.is_empty() {
+        let before = pending.len();
+        pending.retain(|&i| {
+            let ready = nodes[i]
+                .input
+                .iter()
+                .all(|input| available.contains(input.as_str()));
+            if ready {
+                order.push(i);
+                available.extend(nodes[i].output.iter().map(String::as_str));
+            }
+            !ready
+        });
+        if pending.len() == before {
+            return Err(OnnxError::UnresolvedInputs {
+                nodes: pending.iter().map(|&i| names[i].clone()).collect(),
+            });
+        }
+    }
+    Ok(order)
+}
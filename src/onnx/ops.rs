@@ -0,0 +1,542 @@
+use super::OnnxError;
+use super::proto::{AttributeProto, NodeProto, attribute_type};
+use super::tensor::to_tensor;
+use crate::functions::abs::abs_ndarray;
+use crate::functions::argmax::argmax;
+use crate::functions::dim::normalize_dim;
+use crate::functions::einsum::einsum_ndarray_dyn;
+use crate::functions::gather::gather;
+use crate::functions::index_select::index_select;
+use crate::functions::matmul::broadcast_shapes;
+use crate::functions::max::max;
+use crate::functions::reshape::reshape;
+use crate::functions::scatter::scatter;
+use crate::io::{Dtype, Element, Tensor};
+use half::{bf16, f16};
+use ndarray::{ArrayD, Axis, IxDyn, LinalgScalar};
+
+/// Op types [`run_node`] implements, in the default ONNX domain.
+pub(super) const SUPPORTED_OPS: &[&str] = &[
+    "Abs",
+    "ArgMax",
+    "Constant",
+    "Einsum",
+    "Expand",
+    "Gather",
+    "GatherElements",
+    "Identity",
+    "ReduceMax",
+    "Reshape",
+    "ScatterElements",
+    "Slice",
+    "Transpose",
+];
+
+/// Applies `$body` to the array inside `$tensor` whatever its element type, wrapping the
+/// resulting array back into a [`Tensor`].
+macro_rules! with_array {
+    ($tensor:expr, $array:ident => $body:expr) => {
+        match $tensor {
+            Tensor::F64($array) => Tensor::from($body),
+            Tensor::F32($array) => Tensor::from($body),
+            Tensor::F16($array) => Tensor::from($body),
+            Tensor::BF16($array) => Tensor::from($body),
+            Tensor::I64($array) => Tensor::from($body),
+            Tensor::Bool($array) => Tensor::from($body),
+        }
+    };
+}
+
+/// Evaluates a single node.
+///
+/// # Arguments
+///
+/// * `node`: The node to evaluate. Its op type must be one of [`SUPPORTED_OPS`].
+/// * `name`: The name used for the node in errors.
+/// * `opset`: Version of the default opset, which decides between the attribute and input
+///   forms of ops such as `Slice` and `ReduceMax`.
+/// * `inputs`: The values of the node's inputs in order, `None` for omitted optional inputs.
+///
+/// # Returns
+///
+/// * `Ok(Vec<Tensor>)`: The values of the node's outputs in order.
+/// * `Err(OnnxError)`: If an input or attribute is invalid or the op fails.
+pub(super) fn run_node(
+    node: &NodeProto,
+    name: &str,
+    opset: i64,
+    inputs: &[Option<&Tensor>],
+) -> Result<Vec<Tensor>, OnnxError> {
+    let cx = Node {
+        proto: node,
+        name,
+        opset,
+        inputs,
+    };
+    let output = match node.op_type.as_str() {
+        "Abs" => abs(&cx)?,
+        "ArgMax" => arg_max(&cx)?,
+        "Constant" => constant(&cx)?,
+        "Einsum" => einsum(&cx)?,
+        "Expand" => expand(&cx)?,
+        "Gather" => gather_op(&cx)?,
+        "GatherElements" => gather_elements(&cx)?,
+        "Identity" => cx.input(0)?.clone(),
+        "ReduceMax" => reduce_max(&cx)?,
+        "Reshape" => reshape_op(&cx)?,
+        "ScatterElements" => scatter_elements(&cx)?,
+        "Slice" => slice(&cx)?,
+        "Transpose" => transpose(&cx)?,
+        other => unreachable!("op {other} is rejected when loading the model"),
+    };
+    Ok(vec![output])
+}
+
+/// A node being evaluated, with helpers to read its inputs and attributes.
+struct Node<'a> {
+    proto: &'a NodeProto,
+    name: &'a str,
+    opset: i64,
+    inputs: &'a [Option<&'a Tensor>],
+}
+
+impl<'a> Node<'a> {
+    fn optional(&self, index: usize) -> Option<&'a Tensor> {
+        self.inputs.get(index).copied().flatten()
+    }
+
+    fn input(&self, index: usize) -> Result<&'a Tensor, OnnxError> {
+        self.optional(index)
+            .ok_or_else(|| self.invalid_input(format!("input {index} is required")))
+    }
+
+    /// The input at `index`, which must have the same element type as `like`.
+    fn input_like<A: Element>(
+        &self,
+        index: usize,
+        _like: &ArrayD<A>,
+    ) -> Result<&'a ArrayD<A>, OnnxError> {
+        self.typed_input(index)
+    }
+
+    fn typed_input<A: Element>(&self, index: usize) -> Result<&'a ArrayD<A>, OnnxError> {
+        let tensor = self.input(index)?;
+        tensor.as_array().ok_or_else(|| {
+            self.invalid_input(format!(
+                "input {index} has dtype {}, expected {}",
+                tensor.dtype(),
+                A::DTYPE
+            ))
+        })
+    }
+
+    /// An `int64` input holding a list of integers, such as a shape or a list of axes.
+    fn ints_input(&self, index: usize) -> Result<Option<Vec<i64>>, OnnxError> {
+        match self.optional(index) {
+            None => Ok(None),
+            Some(_) => Ok(Some(
+                self.typed_input::<i64>(index)?.iter().copied().collect(),
+            )),
+        }
+    }
+
+    fn attribute(&self, name: &str, kind: i32) -> Result<Option<&'a AttributeProto>, OnnxError> {
+        match self.proto.attribute.iter().find(|a| a.name == name) {
+            Some(attribute) if attribute.r#type != kind => Err(self.invalid_attribute(
+                name,
+                format!("has type {}, expected {kind}", attribute.r#type),
+            )),
+            attribute => Ok(attribute),
+        }
+    }
+
+    fn int_attribute(&self, name: &str, default: i64) -> Result<i64, OnnxError> {
+        Ok(self
+            .attribute(name, attribute_type::INT)?
+            .map_or(default, |a| a.i))
+    }
+
+    fn ints_attribute(&self, name: &str) -> Result<Option<Vec<i64>>, OnnxError> {
+        Ok(self
+            .attribute(name, attribute_type::INTS)?
+            .map(|a| a.ints.clone()))
+    }
+
+    fn string_attribute(&self, name: &str) -> Result<Option<String>, OnnxError> {
+        self.attribute(name, attribute_type::STRING)?
+            .map(|a| {
+                String::from_utf8(a.s.clone())
+                    .map_err(|_| self.invalid_attribute(name, "is not valid UTF-8".to_string()))
+            })
+            .transpose()
+    }
+
+    /// Resolves a possibly negative `axis` attribute against `ndim` dimensions.
+    fn axis(&self, attribute: &str, axis: i64, ndim: usize) -> Result<usize, OnnxError> {
+        normalize_dim(axis as isize, ndim).ok_or_else(|| {
+            self.invalid_attribute(
+                attribute,
+                format!("axis {axis} is out of range for {ndim} dimensions"),
+            )
+        })
+    }
+
+    fn invalid_input(&self, reason: String) -> OnnxError {
+        OnnxError::InvalidInput {
+            node: self.name.to_string(),
+            reason,
+        }
+    }
+
+    fn invalid_attribute(&self, attribute: &str, reason: String) -> OnnxError {
+        OnnxError::InvalidAttribute {
+            node: self.name.to_string(),
+            attribute: attribute.to_string(),
+            reason,
+        }
+    }
+
+    fn missing_attribute(&self, attribute: &str) -> OnnxError {
+        OnnxError::MissingAttribute {
+            node: self.name.to_string(),
+            attribute: attribute.to_string(),
+        }
+    }
+
+    fn unsupported_dtype(&self, dtype: Dtype) -> OnnxError {
+        OnnxError::UnsupportedDtype {
+            node: self.name.to_string(),
+            dtype,
+        }
+    }
+
+    fn failed(&self, message: impl ToString) -> OnnxError {
+        OnnxError::OpFailed {
+            node: self.name.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Makes negative indices count from the end of a dimension of `size`, as ONNX allows.
+fn wrap_indices(indices: &ArrayD<i64>, size: usize) -> ArrayD<i64> {
+    indices.mapv(|i| if i < 0 { i + size as i64 } else { i })
+}
+
+fn abs(cx: &Node) -> Result<Tensor, OnnxError> {
+    Ok(match cx.input(0)? {
+        Tensor::F64(a) => Tensor::from(abs_ndarray(a)),
+        Tensor::F32(a) => Tensor::from(abs_ndarray(a)),
+        // Clearing the sign bit, as half types do not implement `num_traits::Float`
+        Tensor::F16(a) => Tensor::from(a.mapv(|v| f16::from_bits(v.to_bits() & 0x7fff))),
+        Tensor::BF16(a) => Tensor::from(a.mapv(|v| bf16::from_bits(v.to_bits() & 0x7fff))),
+        Tensor::I64(a) => Tensor::from(a.mapv(i64::wrapping_abs)),
+        Tensor::Bool(_) => return Err(cx.unsupported_dtype(Dtype::Bool)),
+    })
+}
+
+fn arg_max(cx: &Node) -> Result<Tensor, OnnxError> {
+    let data = cx.input(0)?;
+    let axis = cx.axis("axis", cx.int_attribute("axis", 0)?, data.shape().len())?;
+    let keepdims = cx.int_attribute("keepdims", 1)? != 0;
+    if cx.int_attribute("select_last_index", 0)? != 0 {
+        return Err(cx.invalid_attribute(
+            "select_last_index",
+            "only the first maximal index is supported".to_string(),
+        ));
+    }
+    Ok(with_array!(data, a => argmax(a, Some(axis), keepdims)
+        .map_err(|e| cx.failed(format!("{e:?}")))?))
+}
+
+fn constant(cx: &Node) -> Result<Tensor, OnnxError> {
+    let attribute = cx
+        .proto
+        .attribute
+        .first()
+        .ok_or_else(|| cx.missing_attribute("value"))?;
+    let kind = match attribute.name.as_str() {
+        "value" => attribute_type::TENSOR,
+        "value_float" => attribute_type::FLOAT,
+        "value_floats" => attribute_type::FLOATS,
+        "value_int" => attribute_type::INT,
+        "value_ints" => attribute_type::INTS,
+        other => {
+            return Err(cx.invalid_attribute(other, "is not supported".to_string()));
+        }
+    };
+    let attribute = cx
+        .attribute(&attribute.name, kind)?
+        .expect("the attribute was just found");
+    Ok(match kind {
+        attribute_type::TENSOR => {
+            let proto = attribute
+                .t
+                .as_ref()
+                .ok_or_else(|| cx.missing_attribute("value"))?;
+            to_tensor(proto)?
+        }
+        attribute_type::FLOAT => Tensor::from(ndarray::arr0(attribute.f).into_dyn()),
+        attribute_type::FLOATS => Tensor::from(
+            ArrayD::from_shape_vec(IxDyn(&[attribute.floats.len()]), attribute.floats.clone())
+                .expect("the shape matches the values"),
+        ),
+        attribute_type::INT => Tensor::from(ndarray::arr0(attribute.i).into_dyn()),
+        _ => Tensor::from(
+            ArrayD::from_shape_vec(IxDyn(&[attribute.ints.len()]), attribute.ints.clone())
+                .expect("the shape matches the values"),
+        ),
+    })
+}
+
+fn einsum(cx: &Node) -> Result<Tensor, OnnxError> {
+    let equation = cx
+        .string_attribute("equation")?
+        .ok_or_else(|| cx.missing_attribute("equation"))?;
+    match cx.input(0)? {
+        Tensor::F64(_) => einsum_typed::<f64>(cx, &equation),
+        Tensor::F32(_) => einsum_typed::<f32>(cx, &equation),
+        Tensor::I64(_) => einsum_typed::<i64>(cx, &equation),
+        other => Err(cx.unsupported_dtype(other.dtype())),
+    }
+}
+
+fn einsum_typed<A: Element + LinalgScalar>(cx: &Node, equation: &str) -> Result<Tensor, OnnxError> {
+    let operands = (0..cx.inputs.len())
+        .map(|i| cx.typed_input::<A>(i))
+        .collect::<Result<Vec<_>, _>>()?;
+    let result = einsum_ndarray_dyn(equation, &operands).map_err(|e| cx.failed(e))?;
+    Ok(A::wrap(result))
+}
+
+fn expand(cx: &Node) -> Result<Tensor, OnnxError> {
+    let data = cx.input(0)?;
+    let shape = cx
+        .ints_input(1)?
+        .ok_or_else(|| cx.invalid_input("input 1 is required".to_string()))?
+        .iter()
+        .map(|&d| usize::try_from(d))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| cx.invalid_input("shape has a negative dimension".to_string()))?;
+    // Unlike `torch.expand`, ONNX broadcasts in both directions
+    let target = broadcast_shapes(data.shape(), &shape).ok_or_else(|| {
+        cx.invalid_input(format!(
+            "shape {:?} cannot be broadcast with {shape:?}",
+            data.shape()
+        ))
+    })?;
+    Ok(with_array!(data, a => a
+        .broadcast(IxDyn(&target))
+        .expect("the target shape is a broadcast of the input's")
+        .to_owned()))
+}
+
+/// ONNX `Gather`: `np.take(data, indices, axis)`, so the indices may have any shape.
+fn gather_op(cx: &Node) -> Result<Tensor, OnnxError> {
+    let data = cx.input(0)?;
+    let indices = cx.typed_input::<i64>(1)?;
+    let shape = data.shape();
+    let axis = cx.axis("axis", cx.int_attribute("axis", 0)?, shape.len())?;
+    let flat: Vec<i64> = wrap_indices(indices, shape[axis]).iter().copied().collect();
+    let out_shape: Vec<i64> = shape[..axis]
+        .iter()
+        .chain(indices.shape())
+        .chain(&shape[axis + 1..])
+        .map(|&d| d as i64)
+        .collect();
+    Ok(with_array!(data, a => {
+        let selected = index_select(a, axis as isize, &flat).map_err(|e| cx.failed(e))?;
+        reshape(&selected, &out_shape).expect("the element count is unchanged")
+    }))
+}
+
+fn gather_elements(cx: &Node) -> Result<Tensor, OnnxError> {
+    let data = cx.input(0)?;
+    let axis = cx.axis("axis", cx.int_attribute("axis", 0)?, data.shape().len())?;
+    let indices = wrap_indices(cx.typed_input::<i64>(1)?, data.shape()[axis]);
+    Ok(with_array!(data, a => gather(a, axis as isize, &indices).map_err(|e| cx.failed(e))?))
+}
+
+fn reduce_max(cx: &Node) -> Result<Tensor, OnnxError> {
+    let data = cx.input(0)?;
+    let ndim = data.shape().len();
+    let keepdims = cx.int_attribute("keepdims", 1)? != 0;
+    // Since opset 18 the axes are an optional input instead of an attribute
+    let axes = match cx.opset >= 18 {
+        true => cx.ints_input(1)?,
+        false => cx.ints_attribute("axes")?,
+    }
+    .unwrap_or_default();
+    let mut axes = if axes.is_empty() {
+        if cx.int_attribute("noop_with_empty_axes", 0)? != 0 {
+            return Ok(data.clone());
+        }
+        (0..ndim).collect()
+    } else {
+        axes.iter()
+            .map(|&axis| cx.axis("axes", axis, ndim))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    axes.sort_unstable();
+    axes.dedup();
+
+    Ok(with_array!(data, a => {
+        let mut result = a.clone();
+        // From the last axis so the remaining ones keep their positions
+        for &axis in axes.iter().rev() {
+            let (values, _) = max(&result, axis).map_err(|e| cx.failed(format!("{e:?}")))?;
+            result = match keepdims {
+                true => values.insert_axis(Axis(axis)),
+                false => values,
+            };
+        }
+        result
+    }))
+}
+
+fn reshape_op(cx: &Node) -> Result<Tensor, OnnxError> {
+    let data = cx.input(0)?;
+    let shape = match cx.opset >= 5 {
+        true => cx.ints_input(1)?,
+        false => cx.ints_attribute("shape")?,
+    }
+    .ok_or_else(|| cx.missing_attribute("shape"))?;
+    // A 0 copies the input's dimension unless `allowzero` asks for an empty dimension
+    let allow_zero = cx.int_attribute("allowzero", 0)? != 0;
+    let shape = shape
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| match (d, allow_zero) {
+            (0, false) => data.shape().get(i).map(|&d| d as i64).ok_or_else(|| {
+                cx.invalid_input(format!("shape copies dimension {i}, which the input lacks"))
+            }),
+            _ => Ok(d),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(with_array!(data, a => reshape(a, &shape).map_err(|e| cx.failed(format!("{e:?}")))?))
+}
+
+fn scatter_elements(cx: &Node) -> Result<Tensor, OnnxError> {
+    let data = cx.input(0)?;
+    let axis = cx.axis("axis", cx.int_attribute("axis", 0)?, data.shape().len())?;
+    if let Some(reduction) = cx.string_attribute("reduction")?
+        && reduction != "none"
+    {
+        return Err(cx.invalid_attribute(
+            "reduction",
+            format!("{reduction:?} is not supported, only \"none\""),
+        ));
+    }
+    let indices = wrap_indices(cx.typed_input::<i64>(1)?, data.shape()[axis]);
+    Ok(with_array!(data, a => {
+        let updates = cx.input_like(2, a)?;
+        let mut result = a.clone();
+        scatter(&mut result, axis as isize, &indices, updates).map_err(|e| cx.failed(e))?;
+        result
+    }))
+}
+
+fn slice(cx: &Node) -> Result<Tensor, OnnxError> {
+    let data = cx.input(0)?;
+    let ndim = data.shape().len();
+    // Since opset 10 the bounds are inputs instead of attributes, and steps were added
+    let (starts, ends, axes, steps) = match cx.opset >= 10 {
+        true => (
+            cx.ints_input(1)?
+                .ok_or_else(|| cx.invalid_input("input 1 is required".to_string()))?,
+            cx.ints_input(2)?
+                .ok_or_else(|| cx.invalid_input("input 2 is required".to_string()))?,
+            cx.ints_input(3)?,
+            cx.ints_input(4)?,
+        ),
+        false => (
+            cx.ints_attribute("starts")?
+                .ok_or_else(|| cx.missing_attribute("starts"))?,
+            cx.ints_attribute("ends")?
+                .ok_or_else(|| cx.missing_attribute("ends"))?,
+            cx.ints_attribute("axes")?,
+            None,
+        ),
+    };
+    let axes = axes.unwrap_or_else(|| (0..starts.len() as i64).collect());
+    let steps = steps.unwrap_or_else(|| vec![1; starts.len()]);
+    if ends.len() != starts.len() || axes.len() != starts.len() || steps.len() != starts.len() {
+        return Err(
+            cx.invalid_input("starts, ends, axes and steps must have the same length".to_string())
+        );
+    }
+
+    let mut selections = Vec::with_capacity(starts.len());
+    for (((&start, &end), &axis), &step) in starts.iter().zip(&ends).zip(&axes).zip(&steps) {
+        let axis = cx.axis("axes", axis, ndim)?;
+        if step == 0 {
+            return Err(cx.invalid_input("steps must not be 0".to_string()));
+        }
+        let size = data.shape()[axis] as i64;
+        if size == 0 {
+            selections.push((axis, Vec::new()));
+            continue;
+        }
+        let resolve = |v: i64| if v < 0 { v.saturating_add(size) } else { v };
+        // Out of range bounds are clamped, differently for each direction
+        let (start, end) = match step > 0 {
+            true => (resolve(start).clamp(0, size), resolve(end).clamp(0, size)),
+            false => (
+                resolve(start).clamp(0, size - 1),
+                resolve(end).clamp(-1, size - 1),
+            ),
+        };
+        let mut positions = Vec::new();
+        let mut i = start;
+        while (step > 0 && i < end) || (step < 0 && i > end) {
+            positions.push(i);
+            // A step past the end of the axis may not fit in an i64
+            match i.checked_add(step) {
+                Some(next) => i = next,
+                None => break,
+            }
+        }
+        selections.push((axis, positions));
+    }
+
+    Ok(with_array!(data, a => {
+        let mut result = a.clone();
+        for (axis, positions) in &selections {
+            result = index_select(&result, *axis as isize, positions).map_err(|e| cx.failed(e))?;
+        }
+        result
+    }))
+}
+
+fn transpose(cx: &Node) -> Result<Tensor, OnnxError> {
+    let data = cx.input(0)?;
+    let ndim = data.shape().len();
+    let perm = match cx.ints_attribute("perm")? {
+        // The default reverses the axes
+        None => (0..ndim).rev().collect(),
+        Some(perm) => {
+            let mut seen = vec![false; ndim];
+            let axes = perm
+                .iter()
+                .map(|&axis| normalize_dim(axis as isize, ndim))
+                .collect::<Option<Vec<_>>>()
+                .filter(|axes| {
+                    axes.len() == ndim
+                        && axes
+                            .iter()
+                            .all(|&axis| !std::mem::replace(&mut seen[axis], true))
+                });
+            axes.ok_or_else(|| {
+                cx.invalid_attribute(
+                    "perm",
+                    format!("{perm:?} is not a permutation of {ndim} axes"),
+                )
+            })?
+        }
+    };
+    Ok(with_array!(data, a => a
+        .view()
+        .permuted_axes(IxDyn(&perm))
+        .as_standard_layout()
+        .into_owned()))
+}
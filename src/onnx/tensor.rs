@@ -0,0 +1,103 @@
+use super::OnnxError;
+use super::proto::{EXTERNAL, TensorProto, data_type};
+use crate::io::{Element, Tensor};
+use half::{bf16, f16};
+use ndarray::{ArrayD, IxDyn};
+
+/// Converts a `TensorProto` into a [`Tensor`].
+///
+/// Values are read from `raw_data` when present, as little-endian bytes, and otherwise from the
+/// typed field ONNX stores the data type in: `float_data`, `double_data`, `int64_data`, or
+/// `int32_data` holding the bits of `float16`, `bfloat16` and `bool` values.
+pub(super) fn to_tensor(proto: &TensorProto) -> Result<Tensor, OnnxError> {
+    let invalid = |reason: String| OnnxError::InvalidTensor {
+        name: proto.name.clone(),
+        reason,
+    };
+    if proto.data_location == EXTERNAL {
+        return Err(OnnxError::ExternalData {
+            name: proto.name.clone(),
+        });
+    }
+    let shape = proto
+        .dims
+        .iter()
+        .map(|&d| usize::try_from(d).map_err(|_| invalid(format!("negative dimension {d}"))))
+        .collect::<Result<Vec<_>, _>>()?;
+    let len = shape.iter().product();
+    let raw = (!proto.raw_data.is_empty()).then_some(proto.raw_data.as_slice());
+
+    let tensor = match proto.data_type {
+        data_type::FLOAT => array(&shape, from_raw_or(raw, len, || proto.float_data.clone())),
+        data_type::DOUBLE => array(&shape, from_raw_or(raw, len, || proto.double_data.clone())),
+        data_type::INT64 => array(&shape, from_raw_or(raw, len, || proto.int64_data.clone())),
+        data_type::BOOL => array(
+            &shape,
+            from_raw_or(raw, len, || {
+                proto.int32_data.iter().map(|&v| v != 0).collect()
+            }),
+        ),
+        data_type::FLOAT16 => array(
+            &shape,
+            from_raw_or(raw, len, || {
+                proto
+                    .int32_data
+                    .iter()
+                    .map(|&v| f16::from_bits(v as u16))
+                    .collect()
+            }),
+        ),
+        data_type::BFLOAT16 => array(
+            &shape,
+            from_raw_or(raw, len, || {
+                proto
+                    .int32_data
+                    .iter()
+                    .map(|&v| bf16::from_bits(v as u16))
+                    .collect()
+            }),
+        ),
+        other => {
+            return Err(OnnxError::UnsupportedDataType {
+                name: proto.name.clone(),
+                data_type: other,
+            });
+        }
+    };
+    tensor.map_err(invalid)
+}
+
+/// Decodes `raw` if given, otherwise takes the values of the typed field, checking that there
+/// are `len` of them.
+fn from_raw_or<A: Element>(
+    raw: Option<&[u8]>,
+    len: usize,
+    typed: impl FnOnce() -> Vec<A>,
+) -> Result<Vec<A>, String> {
+    let values = match raw {
+        Some(raw) => {
+            let size = A::DTYPE.size();
+            if raw.len() != len * size {
+                return Err(format!(
+                    "raw_data has {} bytes, expected {}",
+                    raw.len(),
+                    len * size
+                ));
+            }
+            raw.chunks_exact(size)
+                .map(A::from_le_bytes)
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| format!("raw_data holds invalid {} values", A::DTYPE))?
+        }
+        None => typed(),
+    };
+    if values.len() != len {
+        return Err(format!("has {} values, expected {len}", values.len()));
+    }
+    Ok(values)
+}
+
+fn array<A: Element>(shape: &[usize], values: Result<Vec<A>, String>) -> Result<Tensor, String> {
+    let array = ArrayD::from_shape_vec(IxDyn(shape), values?).map_err(|e| e.to_string())?;
+    Ok(A::wrap(array))
+}
@@ -0,0 +1,105 @@
+//! Reusable output buffers for the `*_into` ops.
+//!
+//! The ops in [`crate::functions`] return a new array on every call, which in a per-token
+//! inference loop means allocating and freeing the same shapes over and over. A [`BufferPool`]
+//! keeps the buffers a step is done with, keyed by shape, and hands them out again on the next
+//! step, where the `*_into` variants (`gather_into`, `einsum_into` or an
+//! [`EinsumPlan`](crate::functions::einsum::EinsumPlan), `max_into`, ...) write into them. Once
+//! every shape has been seen, a loop written this way performs no heap allocations, provided
+//! its arrays have at most four dimensions: `ndarray` stores larger shapes on the heap.
+
+use ndarray::{ArrayD, IxDyn};
+use num_traits::Zero;
+use std::collections::HashMap;
+
+/// A pool of arrays keyed by shape.
+///
+/// # Examples
+///
+/// ```
+/// use RustOps::functions::transpose::transpose_dims_into;
+/// use RustOps::pool::BufferPool;
+/// use ndarray::ArrayD;
+///
+/// let mut pool = BufferPool::new();
+/// let x = ArrayD::from_shape_vec(vec![2, 3], (0..6).map(|v| v as f32).collect()).unwrap();
+/// for _ in 0..3 {
+///     let mut out = pool.take(&[3, 2]);
+///     transpose_dims_into(&mut out.view_mut(), &x, 0, 1);
+///     assert_eq!(out, x.t());
+///     pool.recycle(out);
+/// }
+/// // Only the first iteration allocated
+/// assert_eq!(pool.allocations(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BufferPool<A> {
+    free: HashMap<Vec<usize>, Vec<ArrayD<A>>>,
+    allocations: usize,
+}
+
+impl<A> BufferPool<A> {
+    pub fn new() -> Self {
+        BufferPool {
+            free: HashMap::new(),
+            allocations: 0,
+        }
+    }
+
+    /// Returns a buffer to the pool so a later [`take`](BufferPool::take) of its shape can
+    /// reuse it.
+    ///
+    /// Recycling a shape for the first time allocates the pool's entry for it; after that,
+    /// recycling does not allocate.
+    pub fn recycle(&mut self, buffer: ArrayD<A>) {
+        match self.free.get_mut(buffer.shape()) {
+            Some(buffers) => buffers.push(buffer),
+            None => {
+                self.free.insert(buffer.shape().to_vec(), vec![buffer]);
+            }
+        }
+    }
+
+    /// Number of buffers [`take`](BufferPool::take) had to allocate because none of the
+    /// requested shape was free.
+    pub fn allocations(&self) -> usize {
+        self.allocations
+    }
+
+    /// Number of buffers waiting to be reused.
+    pub fn available(&self) -> usize {
+        self.free.values().map(Vec::len).sum()
+    }
+
+    /// Drops every free buffer.
+    pub fn clear(&mut self) {
+        self.free.clear();
+    }
+}
+
+impl<A: Clone + Zero> BufferPool<A> {
+    /// Hands out a buffer of `shape`, reusing a recycled one when there is one.
+    ///
+    /// # Arguments
+    ///
+    /// * `shape`: The shape of the buffer.
+    ///
+    /// # Returns
+    ///
+    /// An array of `shape`. A reused buffer keeps whatever its last user wrote and the layout it
+    /// was recycled with; a new one is zeroed and in standard layout. The `*_into` ops
+    /// overwrite every element, so either works as their output.
+    pub fn take(&mut self, shape: &[usize]) -> ArrayD<A> {
+        if let Some(buffer) = self.free.get_mut(shape).and_then(Vec::pop) {
+            return buffer;
+        }
+        self.allocations += 1;
+        ArrayD::zeros(IxDyn(shape))
+    }
+}
+
+impl<A> Default for BufferPool<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
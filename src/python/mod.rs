@@ -0,0 +1,258 @@
+//! Python bindings, enabled by the `python` feature.
+//!
+//! Builds the `rustops` extension module, with one function per op in [`crate::functions`]
+//! that takes and returns NumPy arrays, so reference scripts can compare the ops against
+//! PyTorch directly:
+//!
+//! ```python
+//! import numpy as np, rustops, torch
+//! x = np.random.randn(4, 5).astype(np.float32)
+//! idx = x.argmax(-1)[:, None]
+//! assert np.array_equal(rustops.gather(x, -1, idx), torch.gather(torch.from_numpy(x), -1, torch.from_numpy(idx)).numpy())
+//! ```
+//!
+//! Arrays of dtype float64, float32, float16, int64 and bool are accepted. C-contiguous
+//! inputs are borrowed without copying by ops that work on views (argmax, einsum, max,
+//! reshape); the others copy their inputs into owned arrays. Results are always handed to
+//! NumPy without copying. float16 matrix products, einsums and reductions are computed in
+//! float32 and rounded back once.
+//!
+//! Errors are raised as Python exceptions: `IndexError` for out of range gather and scatter
+//! indices, `TypeError` for unsupported or mismatched dtypes and non-array arguments, and
+//! `ValueError` for everything else the ops reject.
+
+mod ops;
+
+use crate::functions::gather::GatherError;
+use crate::functions::scatter::ScatterError;
+use half::f16;
+use ndarray::{ArrayD, ArrayViewD, CowArray, IxDyn};
+use numpy::{PyArray1, PyArrayMethods, PyReadonlyArrayDyn, PyUntypedArray, PyUntypedArrayMethods};
+use pyo3::exceptions::{PyIndexError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use std::fmt::Display;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+enum PythonError {
+    #[error("Argument `{argument}` must be a NumPy array, got {type_name}")]
+    NotAnArray {
+        argument: &'static str,
+        type_name: String,
+    },
+
+    #[error(
+        "Argument `{argument}` has dtype {dtype}; supported dtypes are float64, float32, float16, int64 and bool"
+    )]
+    UnknownDtype {
+        argument: &'static str,
+        dtype: String,
+    },
+
+    #[error("Argument `{argument}` must have dtype {expected}, found {found}")]
+    DtypeMismatch {
+        argument: &'static str,
+        expected: &'static str,
+        found: &'static str,
+    },
+
+    #[error("{op} does not support arrays of dtype {dtype}")]
+    UnsupportedDtype {
+        op: &'static str,
+        dtype: &'static str,
+    },
+
+    #[error("Invalid argument `{argument}`: {reason}")]
+    InvalidArgument {
+        argument: &'static str,
+        reason: String,
+    },
+
+    #[error(transparent)]
+    Gather(#[from] GatherError),
+
+    #[error(transparent)]
+    Scatter(#[from] ScatterError),
+
+    #[error("{op} failed: {message}")]
+    Op { op: &'static str, message: String },
+}
+
+impl PythonError {
+    fn invalid(argument: &'static str, reason: impl Display) -> Self {
+        PythonError::InvalidArgument {
+            argument,
+            reason: reason.to_string(),
+        }
+    }
+
+    fn op(op: &'static str, message: impl Display) -> Self {
+        PythonError::Op {
+            op,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<PythonError> for PyErr {
+    fn from(err: PythonError) -> PyErr {
+        let message = err.to_string();
+        match err {
+            PythonError::Gather(
+                GatherError::IndexOutOfBounds { .. } | GatherError::NegativeIndex { .. },
+            )
+            | PythonError::Scatter(
+                ScatterError::IndexOutOfBounds { .. } | ScatterError::NegativeIndex { .. },
+            ) => PyIndexError::new_err(message),
+            PythonError::NotAnArray { .. }
+            | PythonError::UnknownDtype { .. }
+            | PythonError::DtypeMismatch { .. }
+            | PythonError::UnsupportedDtype { .. } => PyTypeError::new_err(message),
+            _ => PyValueError::new_err(message),
+        }
+    }
+}
+
+/// A NumPy array argument, borrowed read-only for the duration of a call.
+enum PyTensor<'py> {
+    F64(PyReadonlyArrayDyn<'py, f64>),
+    F32(PyReadonlyArrayDyn<'py, f32>),
+    F16(PyReadonlyArrayDyn<'py, f16>),
+    I64(PyReadonlyArrayDyn<'py, i64>),
+    Bool(PyReadonlyArrayDyn<'py, bool>),
+}
+
+impl<'py> PyTensor<'py> {
+    fn extract(object: &Bound<'py, PyAny>, argument: &'static str) -> Result<Self, PythonError> {
+        let Ok(array) = object.cast::<PyUntypedArray>() else {
+            return Err(PythonError::NotAnArray {
+                argument,
+                type_name: object
+                    .get_type()
+                    .name()
+                    .map_or_else(|_| "an unknown type".to_string(), |name| name.to_string()),
+            });
+        };
+        if let Ok(array) = object.extract() {
+            Ok(PyTensor::F64(array))
+        } else if let Ok(array) = object.extract() {
+            Ok(PyTensor::F32(array))
+        } else if let Ok(array) = object.extract() {
+            Ok(PyTensor::F16(array))
+        } else if let Ok(array) = object.extract() {
+            Ok(PyTensor::I64(array))
+        } else if let Ok(array) = object.extract() {
+            Ok(PyTensor::Bool(array))
+        } else {
+            Err(PythonError::UnknownDtype {
+                argument,
+                dtype: array.dtype().to_string(),
+            })
+        }
+    }
+
+    fn dtype(&self) -> &'static str {
+        match self {
+            PyTensor::F64(_) => f64::NAME,
+            PyTensor::F32(_) => f32::NAME,
+            PyTensor::F16(_) => f16::NAME,
+            PyTensor::I64(_) => i64::NAME,
+            PyTensor::Bool(_) => bool::NAME,
+        }
+    }
+}
+
+/// Element types that can cross the boundary.
+trait ArrayElement: numpy::Element + Clone {
+    /// The NumPy name of the dtype.
+    const NAME: &'static str;
+
+    fn array<'a, 'py>(tensor: &'a PyTensor<'py>) -> Option<&'a PyReadonlyArrayDyn<'py, Self>>;
+}
+
+macro_rules! array_element {
+    ($($ty:ty => $variant:ident, $name:literal;)+) => {$(
+        impl ArrayElement for $ty {
+            const NAME: &'static str = $name;
+
+            fn array<'a, 'py>(
+                tensor: &'a PyTensor<'py>,
+            ) -> Option<&'a PyReadonlyArrayDyn<'py, Self>> {
+                match tensor {
+                    PyTensor::$variant(array) => Some(array),
+                    _ => None,
+                }
+            }
+        }
+    )+};
+}
+
+array_element! {
+    f64 => F64, "float64";
+    f32 => F32, "float32";
+    f16 => F16, "float16";
+    i64 => I64, "int64";
+    bool => Bool, "bool";
+}
+
+/// The array inside `tensor`, which must have dtype `A`.
+fn typed<'a, 'py, A: ArrayElement>(
+    tensor: &'a PyTensor<'py>,
+    argument: &'static str,
+) -> Result<&'a PyReadonlyArrayDyn<'py, A>, PythonError> {
+    A::array(tensor).ok_or(PythonError::DtypeMismatch {
+        argument,
+        expected: A::NAME,
+        found: tensor.dtype(),
+    })
+}
+
+/// Borrows the data of a C-contiguous array and copies any other layout.
+fn view<'a, A: ArrayElement>(array: &'a PyReadonlyArrayDyn<'_, A>) -> CowArray<'a, A, IxDyn> {
+    let shape = IxDyn(array.shape());
+    match array.as_slice() {
+        Ok(data) if array.is_c_contiguous() => ArrayViewD::from_shape(shape, data)
+            .expect("a C-contiguous array matches its shape")
+            .into(),
+        _ => ArrayD::from_shape_vec(shape, array.as_array().iter().cloned().collect())
+            .expect("length matches shape")
+            .into(),
+    }
+}
+
+/// Copies an array argument into an owned array, for ops that need one.
+fn owned<A: ArrayElement>(array: &PyReadonlyArrayDyn<'_, A>) -> ArrayD<A> {
+    view(array).into_owned()
+}
+
+/// Hands an array to NumPy without copying its data.
+fn to_numpy<'py, A: ArrayElement>(
+    py: Python<'py>,
+    array: ArrayD<A>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let shape = array.shape().to_vec();
+    let data = if array.is_empty() {
+        Vec::new()
+    } else if array.is_standard_layout() {
+        // The elements are contiguous but may start past the beginning of the buffer
+        let (start, len) = (array.as_ptr() as usize, array.len());
+        let mut data = array.into_raw_vec();
+        let offset = (start - data.as_ptr() as usize)
+            .checked_div(size_of::<A>())
+            .unwrap_or(0);
+        data.drain(..offset);
+        data.truncate(len);
+        data
+    } else {
+        array.iter().cloned().collect()
+    };
+    Ok(PyArray1::from_vec(py, data).reshape(shape)?.into_any())
+}
+
+/// The `rustops` extension module. Importing it fails with `ImportError` if NumPy is missing.
+#[pymodule]
+pub fn rustops(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    // The array checks need NumPy's C API, which panics if NumPy cannot be imported
+    module.py().import("numpy")?;
+    ops::register(module)
+}
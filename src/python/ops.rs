@@ -0,0 +1,687 @@
+//! One Python function per op in [`crate::functions`].
+//!
+//! Arguments follow the matching PyTorch functions where there is one, and dimensions may be
+//! negative to count from the end wherever the Rust op accepts that. Ops that modify their
+//! input in Rust, like `scatter` and `sort_last_dim`, return a new array instead.
+
+use super::{ArrayElement, PyTensor, PythonError, owned, to_numpy, typed, view};
+use crate::functions;
+use crate::functions::dim::normalize_dim;
+use crate::functions::einsum::PathStrategy;
+use crate::functions::pad::PadMode;
+use crate::functions::upcast::{downcast, upcast};
+use half::f16;
+use ndarray::{ArrayD, CowArray, IxDyn, LinalgScalar};
+use num_traits::NumCast;
+use numpy::{PyReadonlyArrayDyn, PyUntypedArrayMethods};
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+
+type PyArrayResult<'py> = PyResult<Bound<'py, PyAny>>;
+
+/// Applies `$body` to the array inside `$tensor`, which must hold one of the listed dtypes,
+/// and hands the resulting array to NumPy.
+macro_rules! map_dtypes {
+    ($op:expr, $py:expr, $tensor:expr, [$($variant:ident),+], |$array:ident| $body:expr) => {
+        match &$tensor {
+            $(PyTensor::$variant($array) => to_numpy($py, $body),)+
+            #[allow(unreachable_patterns)]
+            other => Err(PythonError::UnsupportedDtype {
+                op: $op,
+                dtype: other.dtype(),
+            }
+            .into()),
+        }
+    };
+    ($op:expr, $py:expr, $tensor:expr, any, |$array:ident| $body:expr) => {
+        map_dtypes!($op, $py, $tensor, [F64, F32, F16, I64, Bool], |$array| $body)
+    };
+    // float16 runs `$half_body`, which computes in float32 through the `*_upcast` variants
+    (
+        $op:expr,
+        $py:expr,
+        $tensor:expr,
+        [$($variant:ident),+],
+        |$array:ident| $body:expr,
+        upcast |$half:ident| $half_body:expr
+    ) => {
+        match &$tensor {
+            PyTensor::F16($half) => to_numpy($py, $half_body),
+            _ => map_dtypes!($op, $py, $tensor, [$($variant),+], |$array| $body),
+        }
+    };
+}
+
+fn scalar<A: NumCast>(value: f64, argument: &'static str) -> Result<A, PythonError> {
+    A::from(value).ok_or_else(|| PythonError::invalid(argument, format!("{value} is out of range")))
+}
+
+fn dim_arg(dim: isize, ndim: usize, argument: &'static str) -> Result<usize, PythonError> {
+    normalize_dim(dim, ndim).ok_or_else(|| {
+        PythonError::invalid(
+            argument,
+            format!("dimension {dim} is out of range for {ndim} dimensions"),
+        )
+    })
+}
+
+fn operand_list<'py>(operands: &Bound<'py, PyTuple>) -> Result<Vec<PyTensor<'py>>, PythonError> {
+    if operands.is_empty() {
+        return Err(PythonError::invalid(
+            "operands",
+            "at least one operand is required",
+        ));
+    }
+    operands
+        .iter()
+        .map(|operand| PyTensor::extract(&operand, "operands"))
+        .collect()
+}
+
+/// Views of the operands, which must all have dtype `A`.
+fn operand_views<'a, A: ArrayElement>(
+    operands: &'a [PyTensor<'_>],
+) -> Result<Vec<CowArray<'a, A, IxDyn>>, PythonError> {
+    operands
+        .iter()
+        .map(|operand| typed(operand, "operands").map(view))
+        .collect()
+}
+
+fn einsum_typed<A: ArrayElement + LinalgScalar>(
+    equation: &str,
+    operands: &[PyTensor<'_>],
+) -> Result<ArrayD<A>, PythonError> {
+    let arrays = operand_views::<A>(operands)?;
+    let views: Vec<_> = arrays.iter().map(CowArray::view).collect();
+    functions::einsum::einsum_views(equation, &views, PathStrategy::Auto)
+        .map_err(|err| PythonError::op("einsum", err))
+}
+
+fn einsum_named_typed<A: ArrayElement + LinalgScalar>(
+    pattern: &str,
+    operands: &[PyTensor<'_>],
+) -> Result<ArrayD<A>, PythonError> {
+    let arrays: Vec<ArrayD<A>> = operand_views::<A>(operands)?
+        .into_iter()
+        .map(CowArray::into_owned)
+        .collect();
+    let arrays: Vec<&ArrayD<A>> = arrays.iter().collect();
+    functions::einsum::einsum_named(pattern, &arrays)
+        .map_err(|err| PythonError::op("einsum_named", err))
+}
+
+/// Einsum over float16 operands, computed in float32 and rounded back once.
+fn einsum_half(equation: &str, operands: &[PyTensor<'_>]) -> Result<ArrayD<f16>, PythonError> {
+    let arrays: Vec<ArrayD<f32>> = operand_views::<f16>(operands)?.iter().map(upcast).collect();
+    let views: Vec<_> = arrays.iter().map(ArrayD::view).collect();
+    functions::einsum::einsum_views(equation, &views, PathStrategy::Auto)
+        .map(|result| downcast(&result))
+        .map_err(|err| PythonError::op("einsum", err))
+}
+
+/// Named einsum over float16 operands, computed in float32 and rounded back once.
+fn einsum_named_half(pattern: &str, operands: &[PyTensor<'_>]) -> Result<ArrayD<f16>, PythonError> {
+    let arrays: Vec<ArrayD<f32>> = operand_views::<f16>(operands)?.iter().map(upcast).collect();
+    let arrays: Vec<&ArrayD<f32>> = arrays.iter().collect();
+    functions::einsum::einsum_named(pattern, &arrays)
+        .map(|result| downcast(&result))
+        .map_err(|err| PythonError::op("einsum_named", err))
+}
+
+/// `abs(x)`: elementwise absolute value of a float array.
+#[pyfunction]
+pub(super) fn abs<'py>(py: Python<'py>, x: &Bound<'py, PyAny>) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("abs", py, x, [F64, F32], |a| {
+        functions::abs::abs_ndarray(&owned(a))
+    })
+}
+
+/// `argmax(x, dim=None, keepdim=False)`: mimics `torch.argmax`, returning int64 indices.
+#[pyfunction]
+#[pyo3(signature = (x, dim=None, keepdim=false))]
+pub(super) fn argmax<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    dim: Option<isize>,
+    keepdim: bool,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("argmax", py, x, any, |a| {
+        let dim = dim.map(|dim| dim_arg(dim, a.ndim(), "dim")).transpose()?;
+        functions::argmax::argmax(&view(a), dim, keepdim)
+            .map_err(|err| PythonError::op("argmax", format!("{err:?}")))?
+    })
+}
+
+/// `einsum(equation, *operands)`: mimics `torch.einsum`. The operands must share one dtype.
+#[pyfunction]
+#[pyo3(signature = (equation, *operands))]
+pub(super) fn einsum<'py>(
+    py: Python<'py>,
+    equation: &str,
+    operands: &Bound<'py, PyTuple>,
+) -> PyArrayResult<'py> {
+    let operands = operand_list(operands)?;
+    match &operands[0] {
+        PyTensor::F64(_) => to_numpy(py, einsum_typed::<f64>(equation, &operands)?),
+        PyTensor::F32(_) => to_numpy(py, einsum_typed::<f32>(equation, &operands)?),
+        PyTensor::I64(_) => to_numpy(py, einsum_typed::<i64>(equation, &operands)?),
+        PyTensor::F16(_) => to_numpy(py, einsum_half(equation, &operands)?),
+        other => Err(PythonError::UnsupportedDtype {
+            op: "einsum",
+            dtype: other.dtype(),
+        }
+        .into()),
+    }
+}
+
+/// `einsum_named(pattern, *operands)`: einsum with einops-style named axes, e.g.
+/// `"batch head q d, batch head k d -> batch head q k"`.
+#[pyfunction]
+#[pyo3(signature = (pattern, *operands))]
+pub(super) fn einsum_named<'py>(
+    py: Python<'py>,
+    pattern: &str,
+    operands: &Bound<'py, PyTuple>,
+) -> PyArrayResult<'py> {
+    let operands = operand_list(operands)?;
+    match &operands[0] {
+        PyTensor::F64(_) => to_numpy(py, einsum_named_typed::<f64>(pattern, &operands)?),
+        PyTensor::F32(_) => to_numpy(py, einsum_named_typed::<f32>(pattern, &operands)?),
+        PyTensor::I64(_) => to_numpy(py, einsum_named_typed::<i64>(pattern, &operands)?),
+        PyTensor::F16(_) => to_numpy(py, einsum_named_half(pattern, &operands)?),
+        other => Err(PythonError::UnsupportedDtype {
+            op: "einsum_named",
+            dtype: other.dtype(),
+        }
+        .into()),
+    }
+}
+
+/// `expand(x, dim, size)`: inserts a dimension of `size` copies at `dim`, like
+/// `x.unsqueeze(dim).expand(...)`.
+#[pyfunction]
+pub(super) fn expand<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    dim: usize,
+    size: usize,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("expand", py, x, any, |a| {
+        functions::expand::expand_at_dim(&owned(a), dim, size)
+            .map_err(|err| PythonError::op("expand", err))?
+    })
+}
+
+/// `flip(x, dims)`: mimics `torch.flip`.
+#[pyfunction]
+pub(super) fn flip<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    dims: Vec<isize>,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("flip", py, x, any, |a| {
+        functions::flip::flip(&owned(a), &dims).map_err(|err| PythonError::op("flip", err))?
+    })
+}
+
+/// `gather(x, dim, index)`: mimics `torch.gather`. `index` must be an int64 array.
+#[pyfunction]
+pub(super) fn gather<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    dim: isize,
+    index: &Bound<'py, PyAny>,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    let index = owned(typed::<i64>(&PyTensor::extract(index, "index")?, "index")?);
+    map_dtypes!("gather", py, x, any, |a| {
+        functions::gather::gather(&owned(a), dim, &index).map_err(PythonError::Gather)?
+    })
+}
+
+/// `matmul(a, b)`: mimics `torch.matmul`.
+#[pyfunction]
+pub(super) fn matmul<'py>(
+    py: Python<'py>,
+    a: &Bound<'py, PyAny>,
+    b: &Bound<'py, PyAny>,
+) -> PyArrayResult<'py> {
+    let (a, b) = (PyTensor::extract(a, "a")?, PyTensor::extract(b, "b")?);
+    map_dtypes!(
+        "matmul",
+        py,
+        a,
+        [F64, F32, I64],
+        |a| {
+            functions::matmul::matmul(&owned(a), &owned(typed(&b, "b")?))
+                .map_err(|err| PythonError::op("matmul", err))?
+        },
+        upcast | a | {
+            functions::matmul::matmul_upcast(&owned(a), &owned(typed(&b, "b")?))
+                .map_err(|err| PythonError::op("matmul", err))?
+        }
+    )
+}
+
+/// `bmm(a, b)`: mimics `torch.bmm`.
+#[pyfunction]
+pub(super) fn bmm<'py>(
+    py: Python<'py>,
+    a: &Bound<'py, PyAny>,
+    b: &Bound<'py, PyAny>,
+) -> PyArrayResult<'py> {
+    let (a, b) = (PyTensor::extract(a, "a")?, PyTensor::extract(b, "b")?);
+    map_dtypes!(
+        "bmm",
+        py,
+        a,
+        [F64, F32, I64],
+        |a| {
+            functions::matmul::bmm(&owned(a), &owned(typed(&b, "b")?))
+                .map_err(|err| PythonError::op("bmm", err))?
+        },
+        upcast | a | {
+            functions::matmul::bmm_upcast(&owned(a), &owned(typed(&b, "b")?))
+                .map_err(|err| PythonError::op("bmm", err))?
+        }
+    )
+}
+
+/// `baddbmm(input, batch1, batch2, *, beta=1.0, alpha=1.0)`: mimics `torch.baddbmm`.
+#[pyfunction]
+#[pyo3(signature = (input, batch1, batch2, *, beta=1.0, alpha=1.0))]
+pub(super) fn baddbmm<'py>(
+    py: Python<'py>,
+    input: &Bound<'py, PyAny>,
+    batch1: &Bound<'py, PyAny>,
+    batch2: &Bound<'py, PyAny>,
+    beta: f64,
+    alpha: f64,
+) -> PyArrayResult<'py> {
+    let input = PyTensor::extract(input, "input")?;
+    let batch1 = PyTensor::extract(batch1, "batch1")?;
+    let batch2 = PyTensor::extract(batch2, "batch2")?;
+    map_dtypes!(
+        "baddbmm",
+        py,
+        input,
+        [F64, F32],
+        |a| {
+            functions::matmul::baddbmm(
+                &owned(a),
+                &owned(typed(&batch1, "batch1")?),
+                &owned(typed(&batch2, "batch2")?),
+                scalar(beta, "beta")?,
+                scalar(alpha, "alpha")?,
+            )
+            .map_err(|err| PythonError::op("baddbmm", err))?
+        },
+        upcast | a | {
+            functions::matmul::baddbmm_upcast(
+                &owned(a),
+                &owned(typed(&batch1, "batch1")?),
+                &owned(typed(&batch2, "batch2")?),
+                scalar(beta, "beta")?,
+                scalar(alpha, "alpha")?,
+            )
+            .map_err(|err| PythonError::op("baddbmm", err))?
+        }
+    )
+}
+
+/// `addmm(input, mat1, mat2, *, beta=1.0, alpha=1.0)`: mimics `torch.addmm`.
+#[pyfunction]
+#[pyo3(signature = (input, mat1, mat2, *, beta=1.0, alpha=1.0))]
+pub(super) fn addmm<'py>(
+    py: Python<'py>,
+    input: &Bound<'py, PyAny>,
+    mat1: &Bound<'py, PyAny>,
+    mat2: &Bound<'py, PyAny>,
+    beta: f64,
+    alpha: f64,
+) -> PyArrayResult<'py> {
+    let input = PyTensor::extract(input, "input")?;
+    let mat1 = PyTensor::extract(mat1, "mat1")?;
+    let mat2 = PyTensor::extract(mat2, "mat2")?;
+    map_dtypes!(
+        "addmm",
+        py,
+        input,
+        [F64, F32],
+        |a| {
+            functions::matmul::addmm(
+                &owned(a),
+                &owned(typed(&mat1, "mat1")?),
+                &owned(typed(&mat2, "mat2")?),
+                scalar(beta, "beta")?,
+                scalar(alpha, "alpha")?,
+            )
+            .map_err(|err| PythonError::op("addmm", err))?
+        },
+        upcast | a | {
+            functions::matmul::addmm_upcast(
+                &owned(a),
+                &owned(typed(&mat1, "mat1")?),
+                &owned(typed(&mat2, "mat2")?),
+                scalar(beta, "beta")?,
+                scalar(alpha, "alpha")?,
+            )
+            .map_err(|err| PythonError::op("addmm", err))?
+        }
+    )
+}
+
+/// `max(x, dim)`: mimics `torch.max(x, dim)`, returning a `(values, indices)` tuple.
+#[pyfunction]
+pub(super) fn max<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    dim: isize,
+) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+    fn parts<'py, A: ArrayElement + PartialOrd + Copy>(
+        py: Python<'py>,
+        array: &PyReadonlyArrayDyn<'_, A>,
+        dim: isize,
+    ) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+        let dim = dim_arg(dim, array.ndim(), "dim")?;
+        let (values, indices) = functions::max::max(&view(array), dim)
+            .map_err(|err| PythonError::op("max", format!("{err:?}")))?;
+        Ok((to_numpy(py, values)?, to_numpy(py, indices)?))
+    }
+
+    match &PyTensor::extract(x, "x")? {
+        PyTensor::F64(a) => parts(py, a, dim),
+        PyTensor::F32(a) => parts(py, a, dim),
+        PyTensor::F16(a) => parts(py, a, dim),
+        PyTensor::I64(a) => parts(py, a, dim),
+        PyTensor::Bool(a) => parts(py, a, dim),
+    }
+}
+
+/// `ones(shape)`: a float32 array of ones.
+#[pyfunction]
+pub(super) fn ones(py: Python<'_>, shape: Vec<usize>) -> PyArrayResult<'_> {
+    to_numpy(py, functions::ones::ones(IxDyn(&shape)))
+}
+
+/// `pad(x, pad, mode="constant", value=0.0)`: mimics `torch.nn.functional.pad`, with `pad`
+/// holding `(before, after)` amounts starting from the last dimension.
+#[pyfunction]
+#[pyo3(signature = (x, pad, mode="constant", value=0.0))]
+pub(super) fn pad<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    pad: Vec<isize>,
+    mode: &str,
+    value: f64,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    if !pad.len().is_multiple_of(2) {
+        return Err(PythonError::invalid("pad", "pad must hold (before, after) pairs").into());
+    }
+    let pairs: Vec<(isize, isize)> = pad.chunks(2).map(|pair| (pair[0], pair[1])).collect();
+    if !["constant", "reflect", "replicate", "circular"].contains(&mode) {
+        return Err(PythonError::invalid("mode", format!("unknown pad mode {mode:?}")).into());
+    }
+    map_dtypes!("pad", py, x, [F64, F32, I64], |a| {
+        let mode = match mode {
+            "constant" => PadMode::Constant(scalar(value, "value")?),
+            "reflect" => PadMode::Reflect,
+            "replicate" => PadMode::Replicate,
+            _ => PadMode::Circular,
+        };
+        functions::pad::pad(&owned(a), &pairs, mode).map_err(|err| PythonError::op("pad", err))?
+    })
+}
+
+/// `rearrange_batch_mems_flag(x)`: `batch mems flag -> mems (batch flag)` on a float32 array.
+#[pyfunction]
+pub(super) fn rearrange_batch_mems_flag<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("rearrange_batch_mems_flag", py, x, [F32], |a| {
+        functions::rearrange::rearrange_batch_mems_flag(&owned(a))
+            .map_err(|err| PythonError::op("rearrange_batch_mems_flag", format!("{err:?}")))?
+    })
+}
+
+/// `reduce(x, equation)`: sums a float32 array by contracting it with an array of ones, e.g.
+/// `"abc,abc->a"`.
+#[pyfunction]
+pub(super) fn reduce<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    equation: &str,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!(
+        "reduce",
+        py,
+        x,
+        [F32],
+        |a| {
+            functions::reduce::reduce(&owned(a), equation)
+                .map_err(|err| PythonError::op("reduce", err))?
+        },
+        upcast | a | {
+            functions::reduce::reduce_upcast(&owned(a), equation)
+                .map_err(|err| PythonError::op("reduce", err))?
+        }
+    )
+}
+
+/// `repeat_interleave(x, repeats, dim=None)`: mimics `torch.repeat_interleave`. `repeats` is
+/// an int or an int64 array.
+#[pyfunction]
+#[pyo3(signature = (x, repeats, dim=None))]
+pub(super) fn repeat_interleave<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    repeats: &Bound<'py, PyAny>,
+    dim: Option<isize>,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    if let Ok(repeats) = repeats.extract::<i64>() {
+        let repeats = usize::try_from(repeats)
+            .map_err(|_| PythonError::invalid("repeats", format!("{repeats} is negative")))?;
+        return map_dtypes!("repeat_interleave", py, x, any, |a| {
+            functions::repeat_interleave::repeat_interleave(&owned(a), repeats, dim)
+                .map_err(|err| PythonError::op("repeat_interleave", err))?
+        });
+    }
+    let repeats = owned(typed::<i64>(
+        &PyTensor::extract(repeats, "repeats")?,
+        "repeats",
+    )?);
+    map_dtypes!("repeat_interleave", py, x, any, |a| {
+        functions::repeat_interleave::repeat_interleave_tensor(&owned(a), &repeats, dim)
+            .map_err(|err| PythonError::op("repeat_interleave", err))?
+    })
+}
+
+/// `reshape(x, shape)`: mimics `torch.reshape`. One entry of `shape` may be -1.
+#[pyfunction]
+pub(super) fn reshape<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    shape: Vec<i64>,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("reshape", py, x, any, |a| {
+        functions::reshape::reshape(&view(a), &shape)
+            .map_err(|err| PythonError::op("reshape", format!("{err:?}")))?
+    })
+}
+
+/// `roll(x, shifts, dims)`: mimics `torch.roll`; an empty `dims` rolls the flattened array.
+#[pyfunction]
+pub(super) fn roll<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    shifts: Vec<isize>,
+    dims: Vec<isize>,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("roll", py, x, any, |a| {
+        functions::roll::roll(&owned(a), &shifts, &dims)
+            .map_err(|err| PythonError::op("roll", err))?
+    })
+}
+
+/// `scatter(x, dim, index, src)`: mimics `torch.scatter`, returning a new array. `index`
+/// must be an int64 array and `src` must have the dtype of `x`.
+#[pyfunction]
+pub(super) fn scatter<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    dim: isize,
+    index: &Bound<'py, PyAny>,
+    src: &Bound<'py, PyAny>,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    let index = owned(typed::<i64>(&PyTensor::extract(index, "index")?, "index")?);
+    let src = PyTensor::extract(src, "src")?;
+    map_dtypes!("scatter", py, x, any, |a| {
+        let mut result = owned(a);
+        functions::scatter::scatter(&mut result, dim, &index, &owned(typed(&src, "src")?))
+            .map_err(PythonError::Scatter)?;
+        result
+    })
+}
+
+/// `slice_last_dim(x)`: `x[..., 1:]`.
+#[pyfunction]
+pub(super) fn slice_last_dim<'py>(py: Python<'py>, x: &Bound<'py, PyAny>) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("slice_last_dim", py, x, any, |a| {
+        functions::slicing::slice_last_dim(&owned(a))
+            .map_err(|err| PythonError::op("slice_last_dim", err))?
+    })
+}
+
+/// `slice_second_dim(x, amount)`: `x[:, :amount]`.
+#[pyfunction]
+pub(super) fn slice_second_dim<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    amount: usize,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("slice_second_dim", py, x, any, |a| {
+        functions::slicing::slice_second_dim(&owned(a), amount)
+            .map_err(|err| PythonError::op("slice_second_dim", err))?
+    })
+}
+
+/// `sort_last_dim(x)`: the values of `torch.sort(x, dim=-1)`, returning a new array.
+#[pyfunction]
+pub(super) fn sort_last_dim<'py>(py: Python<'py>, x: &Bound<'py, PyAny>) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("sort_last_dim", py, x, any, |a| {
+        if a.ndim() == 0 {
+            return Err(
+                PythonError::op("sort_last_dim", "cannot sort a 0-dimensional array").into(),
+            );
+        }
+        let mut sorted = owned(a);
+        functions::sort::sort_last_dim(&mut sorted);
+        sorted
+    })
+}
+
+/// `tile(x, reps)`: mimics `torch.tile`.
+#[pyfunction]
+pub(super) fn tile<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    reps: Vec<usize>,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("tile", py, x, any, |a| {
+        functions::tile::tile(&owned(a), &reps).map_err(|err| PythonError::op("tile", err))?
+    })
+}
+
+/// `transpose(x, dim0, dim1)`: mimics `torch.transpose` on a float32 array.
+#[pyfunction]
+pub(super) fn transpose<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    dim0: isize,
+    dim1: isize,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("transpose", py, x, any, |a| {
+        let (dim0, dim1) = (
+            dim_arg(dim0, a.ndim(), "dim0")?,
+            dim_arg(dim1, a.ndim(), "dim1")?,
+        );
+        functions::transpose::transpose_dims(&owned(a), dim0, dim1)
+    })
+}
+
+/// `tril(x, diagonal=0)`: mimics `torch.tril`.
+#[pyfunction]
+#[pyo3(signature = (x, diagonal=0))]
+pub(super) fn tril<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    diagonal: isize,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("tril", py, x, [F64, F32, I64], |a| {
+        functions::tril::tril(&owned(a), diagonal).map_err(|err| PythonError::op("tril", err))?
+    })
+}
+
+/// `triu(x, diagonal=0)`: mimics `torch.triu`.
+#[pyfunction]
+#[pyo3(signature = (x, diagonal=0))]
+pub(super) fn triu<'py>(
+    py: Python<'py>,
+    x: &Bound<'py, PyAny>,
+    diagonal: isize,
+) -> PyArrayResult<'py> {
+    let x = PyTensor::extract(x, "x")?;
+    map_dtypes!("triu", py, x, [F64, F32, I64], |a| {
+        functions::tril::triu(&owned(a), diagonal).map_err(|err| PythonError::op("triu", err))?
+    })
+}
+
+pub(super) fn register(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(abs, module)?)?;
+    module.add_function(wrap_pyfunction!(argmax, module)?)?;
+    module.add_function(wrap_pyfunction!(einsum, module)?)?;
+    module.add_function(wrap_pyfunction!(einsum_named, module)?)?;
+    module.add_function(wrap_pyfunction!(expand, module)?)?;
+    module.add_function(wrap_pyfunction!(flip, module)?)?;
+    module.add_function(wrap_pyfunction!(gather, module)?)?;
+    module.add_function(wrap_pyfunction!(matmul, module)?)?;
+    module.add_function(wrap_pyfunction!(bmm, module)?)?;
+    module.add_function(wrap_pyfunction!(baddbmm, module)?)?;
+    module.add_function(wrap_pyfunction!(addmm, module)?)?;
+    module.add_function(wrap_pyfunction!(max, module)?)?;
+    module.add_function(wrap_pyfunction!(ones, module)?)?;
+    module.add_function(wrap_pyfunction!(pad, module)?)?;
+    module.add_function(wrap_pyfunction!(rearrange_batch_mems_flag, module)?)?;
+    module.add_function(wrap_pyfunction!(reduce, module)?)?;
+    module.add_function(wrap_pyfunction!(repeat_interleave, module)?)?;
+    module.add_function(wrap_pyfunction!(reshape, module)?)?;
+    module.add_function(wrap_pyfunction!(roll, module)?)?;
+    module.add_function(wrap_pyfunction!(scatter, module)?)?;
+    module.add_function(wrap_pyfunction!(slice_last_dim, module)?)?;
+    module.add_function(wrap_pyfunction!(slice_second_dim, module)?)?;
+    module.add_function(wrap_pyfunction!(sort_last_dim, module)?)?;
+    module.add_function(wrap_pyfunction!(tile, module)?)?;
+    module.add_function(wrap_pyfunction!(transpose, module)?)?;
+    module.add_function(wrap_pyfunction!(tril, module)?)?;
+    module.add_function(wrap_pyfunction!(triu, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use super::{QParams, QTensor, QuantizationError, Quantized, check_qparams};
+use ndarray::{Array2, ArrayD, ArrayView1, ArrayView2, Ix1, Ix2, IxDyn};
+
+/// Applies a quantized linear layer, `y = x @ weight.T + bias`, on quantized operands.
+///
+/// Mimics `torch.ao.nn.quantized.functional.linear(x, weight, bias, scale, zero_point)`: the
+/// products of `x - x_zero_point` and `weight - w_zero_point` are accumulated in `i32`, the
+/// bias is added in units of `x_scale * w_scale`, and the sum is requantized to
+/// `(scale, zero_point)` with ties rounded to even, like FBGEMM's `ReQuantizeForFloat`. An
+/// `i32` accumulator holds at least 33025 products of 8-bit values.
+///
+/// # Arguments
+///
+/// * `x`: Input of shape `(*, in_features)`, quantized per tensor.
+/// * `weight`: Weight of shape `(out_features, in_features)`, quantized per tensor or per
+///   channel along axis 0.
+/// * `bias`: Optional real bias of shape `(out_features)`.
+/// * `scale`: Scale of the output.
+/// * `zero_point`: Zero point of the output, in the range of `O`.
+///
+/// # Returns
+///
+/// The output of shape `(*, out_features)`, quantized per tensor.
+pub fn qlinear<X, W, O>(
+    x: &QTensor<X>,
+    weight: &QTensor<W>,
+    bias: Option<&ArrayD<f32>>,
+    scale: f32,
+    zero_point: i32,
+) -> Result<QTensor<O>, QuantizationError>
+where
+    X: Quantized,
+    W: Quantized,
+    O: Quantized,
+{
+    let (x_shape, w_shape) = (x.shape(), weight.shape());
+    if x_shape.is_empty() || w_shape.len() != 2 || w_shape[1] != x_shape[x_shape.len() - 1] {
+        return Err(QuantizationError::ShapeMismatch {
+            left: x_shape.to_vec(),
+            right: w_shape.to_vec(),
+        });
+    }
+    if matches!(weight.qparams(), QParams::PerChannel { axis, .. } if *axis != 0) {
+        return Err(QuantizationError::UnsupportedQParams {
+            argument: "weight",
+            expected: "per tensor or per channel along axis 0",
+        });
+    }
+    if let Some(bias) = bias
+        && bias.shape() != [w_shape[0]]
+    {
+        return Err(QuantizationError::ShapeMismatch {
+            left: w_shape.to_vec(),
+            right: bias.shape().to_vec(),
+        });
+    }
+    let weight_view = weight
+        .int_repr()
+        .view()
+        .into_dimensionality::<Ix2>()
+        .unwrap();
+    let values = requantized_product(
+        x,
+        weight_view,
+        weight.qparams(),
+        bias.map(|bias| bias.view().into_dimensionality::<Ix1>().unwrap()),
+        scale,
+        zero_point,
+    )?;
+    let mut shape = x_shape[..x_shape.len() - 1].to_vec();
+    shape.push(w_shape[0]);
+    let values = values.into_shape(IxDyn(&shape)).unwrap();
+    QTensor::from_parts(values, QParams::PerTensor { scale, zero_point })
+}
+
+/// Multiplies two quantized matrices, `y = a @ b`, accumulating in `i32`.
+///
+/// The integer counterpart of `torch.mm` on dequantized operands, requantized to
+/// `(scale, zero_point)` like [`qlinear`].
+///
+/// # Arguments
+///
+/// * `a`: Matrix of shape `(n, k)`, quantized per tensor.
+/// * `b`: Matrix of shape `(k, m)`, quantized per tensor or per channel along axis 1.
+/// * `scale`: Scale of the output.
+/// * `zero_point`: Zero point of the output, in the range of `O`.
+///
+/// # Returns
+///
+/// The product of shape `(n, m)`, quantized per tensor.
+pub fn qmatmul<A, B, O>(
+    a: &QTensor<A>,
+    b: &QTensor<B>,
+    scale: f32,
+    zero_point: i32,
+) -> Result<QTensor<O>, QuantizationError>
+where
+    A: Quantized,
+    B: Quantized,
+    O: Quantized,
+{
+    let (a_shape, b_shape) = (a.shape(), b.shape());
+    if a_shape.len() != 2 || b_shape.len() != 2 || a_shape[1] != b_shape[0] {
+        return Err(QuantizationError::ShapeMismatch {
+            left: a_shape.to_vec(),
+            right: b_shape.to_vec(),
+        });
+    }
+    if matches!(b.qparams(), QParams::PerChannel { axis, .. } if *axis != 1) {
+        return Err(QuantizationError::UnsupportedQParams {
+            argument: "b",
+            expected: "per tensor or per channel along axis 1",
+        });
+    }
+    // Columns of `b` are the output channels, like the rows of a linear weight
+    let columns = b.int_repr().view().into_dimensionality::<Ix2>().unwrap();
+    let values = requantized_product(a, columns.t(), b.qparams(), None, scale, zero_point)?;
+    QTensor::from_parts(values.into_dyn(), QParams::PerTensor { scale, zero_point })
+}
+
+/// Computes `x @ weight.T` on zero-point-shifted integers and requantizes it, where the rows of
+/// `weight` are output channels and `x` is flattened to `(rows, in_features)`.
+fn requantized_product<X, W, O>(
+    x: &QTensor<X>,
+    weight: ArrayView2<'_, W>,
+    w_qparams: &QParams,
+    bias: Option<ArrayView1<'_, f32>>,
+    scale: f32,
+    zero_point: i32,
+) -> Result<Array2<O>, QuantizationError>
+where
+    X: Quantized,
+    W: Quantized,
+    O: Quantized,
+{
+    check_qparams::<O>(scale, zero_point)?;
+    let QParams::PerTensor {
+        scale: x_scale,
+        zero_point: x_zero_point,
+    } = *x.qparams()
+    else {
+        return Err(QuantizationError::UnsupportedQParams {
+            argument: "input",
+            expected: "per tensor",
+        });
+    };
+    let in_features = weight.ncols();
+    let rows = x.shape()[..x.shape().len() - 1].iter().product();
+    let shifted: Vec<i32> = x
+        .int_repr()
+        .iter()
+        .map(|v| v.to_i32() - x_zero_point)
+        .collect();
+    let x = Array2::from_shape_vec((rows, in_features), shifted).unwrap();
+    let mut w = weight.mapv(W::to_i32);
+    for (channel, mut row) in w.rows_mut().into_iter().enumerate() {
+        let (_, w_zero_point) = w_qparams.channel(channel);
+        row -= w_zero_point;
+    }
+
+    let accumulators = x.dot(&w.t());
+    let mut out = Array2::from_elem(accumulators.raw_dim(), O::from_i32(0));
+    for (channel, (mut out, accumulators)) in out
+        .columns_mut()
+        .into_iter()
+        .zip(accumulators.columns())
+        .enumerate()
+    {
+        let (w_scale, _) = w_qparams.channel(channel);
+        let act_times_w_scale = x_scale * w_scale;
+        let multiplier = act_times_w_scale / scale;
+        let bias = bias.map_or(0.0, |bias| bias[channel]) / act_times_w_scale;
+        for (out, &acc) in out.iter_mut().zip(accumulators) {
+            let rounded = ((acc as f32 + bias) * multiplier).round_ties_even() as i64;
+            let value = (rounded + zero_point as i64).clamp(O::QMIN as i64, O::QMAX as i64);
+            *out = O::from_i32(value as i32);
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,145 @@
+//! Int8 post-training quantization mirroring `torch.ao.quantization`.
+//!
+//! A [`QTensor`] stores 8-bit integers together with the affine parameters that map them back
+//! to real values, `x = (q - zero_point) * scale`, either for the whole tensor or per channel
+//! along one axis. Observers collect activation ranges during calibration and turn them into
+//! those parameters, and [`qlinear`] / [`qmatmul`] multiply quantized operands with `i32`
+//! accumulation before requantizing the result.
+//!
+//! Rounding follows ATen's reference kernels (`quantize_val`, `calculate_qparams`), which is
+//! also what the ARM kernels used on mobile compute: ties round to even, and the zero point is
+//! added after rounding. FBGEMM on x86 adds the zero point before rounding, so an exact tie with
+//! an odd zero point can differ by one there.
+
+mod linear;
+mod observer;
+mod qtensor;
+
+pub use linear::{qlinear, qmatmul};
+pub use observer::{
+    MinMaxObserver, Observer, PerChannelMinMaxObserver, PercentileObserver, QScheme,
+};
+pub use qtensor::{
+    QParams, QTensor, dequantize, quantize, quantize_per_channel, quantize_per_tensor,
+};
+
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum QuantizationError {
+    #[error("Scale must be positive and finite, got {scale}")]
+    InvalidScale { scale: f32 },
+
+    #[error("Zero point {zero_point} is outside the {dtype} range [{qmin}, {qmax}]")]
+    ZeroPointOutOfRange {
+        zero_point: i32,
+        dtype: &'static str,
+        qmin: i32,
+        qmax: i32,
+    },
+
+    #[error("Axis {axis} is out of range for a tensor with {ndim} dimensions")]
+    InvalidAxis { axis: usize, ndim: usize },
+
+    #[error(
+        "Expected {channels} per-channel parameters along axis {axis}, got {scales} scales and {zero_points} zero points"
+    )]
+    ChannelCountMismatch {
+        axis: usize,
+        channels: usize,
+        scales: usize,
+        zero_points: usize,
+    },
+
+    #[error("Observer has seen {expected} channels along axis {axis}, got a batch with {actual}")]
+    ChannelCountChanged {
+        axis: usize,
+        expected: usize,
+        actual: usize,
+    },
+
+    #[error("Incompatible shapes {left:?} and {right:?}")]
+    ShapeMismatch { left: Vec<usize>, right: Vec<usize> },
+
+    #[error("{argument} must be quantized {expected}")]
+    UnsupportedQParams {
+        argument: &'static str,
+        expected: &'static str,
+    },
+
+    #[error("Observer has not seen any values")]
+    NoObservations,
+
+    #[error("Percentile must be in (50, 100], got {percentile}")]
+    InvalidPercentile { percentile: f32 },
+}
+
+/// An 8-bit integer element of a quantized tensor, like `torch.qint8` and `torch.quint8`.
+pub trait Quantized: Copy + PartialEq + std::fmt::Debug + Send + Sync + 'static {
+    /// Smallest representable value.
+    const QMIN: i32;
+    /// Largest representable value.
+    const QMAX: i32;
+    /// Name of the matching PyTorch dtype, used in error messages.
+    const NAME: &'static str;
+
+    /// Widens the value to `i32`, which is exact.
+    fn to_i32(self) -> i32;
+
+    /// Narrows a value in `[QMIN, QMAX]`; callers clamp first.
+    fn from_i32(value: i32) -> Self;
+}
+
+impl Quantized for i8 {
+    const QMIN: i32 = i8::MIN as i32;
+    const QMAX: i32 = i8::MAX as i32;
+    const NAME: &'static str = "qint8";
+
+    fn to_i32(self) -> i32 {
+        self as i32
+    }
+
+    fn from_i32(value: i32) -> Self {
+        value as i8
+    }
+}
+
+impl Quantized for u8 {
+    const QMIN: i32 = u8::MIN as i32;
+    const QMAX: i32 = u8::MAX as i32;
+    const NAME: &'static str = "quint8";
+
+    fn to_i32(self) -> i32 {
+        self as i32
+    }
+
+    fn from_i32(value: i32) -> Self {
+        value as u8
+    }
+}
+
+/// Checks that a scale / zero point pair is usable for elements of type `Q`.
+pub(crate) fn check_qparams<Q: Quantized>(
+    scale: f32,
+    zero_point: i32,
+) -> Result<(), QuantizationError> {
+    if !(scale.is_finite() && scale > 0.0) {
+        return Err(QuantizationError::InvalidScale { scale });
+    }
+    if !(Q::QMIN..=Q::QMAX).contains(&zero_point) {
+        return Err(QuantizationError::ZeroPointOutOfRange {
+            zero_point,
+            dtype: Q::NAME,
+            qmin: Q::QMIN,
+            qmax: Q::QMAX,
+        });
+    }
+    Ok(())
+}
+
+/// Quantizes one value like ATen's `quantize_val`: `clamp(round(x * (1 / scale)) + zero_point)`
+/// with ties rounded to even, all in `f32`.
+pub(crate) fn quantize_val<Q: Quantized>(value: f32, inv_scale: f32, zero_point: i32) -> Q {
+    let rounded = (value * inv_scale).round_ties_even() as i64 + zero_point as i64;
+    Q::from_i32(rounded.clamp(Q::QMIN as i64, Q::QMAX as i64) as i32)
+}
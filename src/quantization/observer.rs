@@ -0,0 +1,249 @@
+use super::{QParams, QuantizationError, Quantized};
+use ndarray::{ArrayD, Axis};
+use std::marker::PhantomData;
+
+/// How an observed range is mapped to quantization parameters, like `torch.qscheme`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QScheme {
+    /// Covers `[min, max]` exactly, with a zero point where zero lands
+    /// (`torch.per_tensor_affine` / `torch.per_channel_affine`).
+    Affine,
+    /// Covers `[-max(|min|, |max|), max(|min|, |max|)]`, with zero point 0 for `qint8` and 128
+    /// for `quint8` (`torch.per_tensor_symmetric` / `torch.per_channel_symmetric`).
+    Symmetric,
+}
+
+/// Collects statistics of calibration data and turns them into quantization parameters.
+pub trait Observer {
+    /// Records the values of one calibration batch, like calling the observer module.
+    fn observe(&mut self, x: &ArrayD<f32>) -> Result<(), QuantizationError>;
+
+    /// Computes parameters from everything observed so far, like `calculate_qparams()`.
+    fn qparams(&self) -> Result<QParams, QuantizationError>;
+}
+
+/// Scale and zero point for the range `[min_val, max_val]`, following
+/// `_ObserverBase._calculate_qparams`. The range is first widened to include zero, and the
+/// scale is at least `f32::EPSILON`.
+fn calculate_qparams<Q: Quantized>(qscheme: QScheme, min_val: f32, max_val: f32) -> (f32, i32) {
+    let (quant_min, quant_max) = (Q::QMIN, Q::QMAX);
+    let min_val_neg = min_val.min(0.0);
+    let max_val_pos = max_val.max(0.0);
+    match qscheme {
+        QScheme::Symmetric => {
+            let max_val_pos = (-min_val_neg).max(max_val_pos);
+            let scale = (max_val_pos / ((quant_max - quant_min) as f32 / 2.0)).max(f32::EPSILON);
+            let zero_point = if quant_min == 0 { 128 } else { 0 };
+            (scale, zero_point)
+        }
+        QScheme::Affine => {
+            let scale =
+                ((max_val_pos - min_val_neg) / (quant_max - quant_min) as f32).max(f32::EPSILON);
+            let zero_point = quant_min - (min_val_neg / scale).round_ties_even() as i32;
+            (scale, zero_point.clamp(quant_min, quant_max))
+        }
+    }
+}
+
+/// Tracks the running minimum and maximum of everything observed.
+///
+/// Mimics `torch.ao.quantization.MinMaxObserver(dtype, qscheme)`, with the dtype given by `Q`.
+#[derive(Debug, Clone)]
+pub struct MinMaxObserver<Q> {
+    qscheme: QScheme,
+    range: Option<(f32, f32)>,
+    dtype: PhantomData<Q>,
+}
+
+impl<Q: Quantized> MinMaxObserver<Q> {
+    pub fn new(qscheme: QScheme) -> Self {
+        Self {
+            qscheme,
+            range: None,
+            dtype: PhantomData,
+        }
+    }
+
+    /// The observed `(min, max)`, if any values were seen.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.range
+    }
+}
+
+impl<Q: Quantized> Observer for MinMaxObserver<Q> {
+    fn observe(&mut self, x: &ArrayD<f32>) -> Result<(), QuantizationError> {
+        if let Some(batch) = min_max(x.iter()) {
+            self.range = Some(match self.range {
+                Some((min, max)) => (min.min(batch.0), max.max(batch.1)),
+                None => batch,
+            });
+        }
+        Ok(())
+    }
+
+    fn qparams(&self) -> Result<QParams, QuantizationError> {
+        let (min_val, max_val) = self.range.ok_or(QuantizationError::NoObservations)?;
+        let (scale, zero_point) = calculate_qparams::<Q>(self.qscheme, min_val, max_val);
+        Ok(QParams::PerTensor { scale, zero_point })
+    }
+}
+
+/// Tracks the running minimum and maximum of every index along a channel axis.
+///
+/// Mimics `torch.ao.quantization.PerChannelMinMaxObserver(ch_axis, dtype, qscheme)`. Every
+/// observed batch must have the same number of channels.
+#[derive(Debug, Clone)]
+pub struct PerChannelMinMaxObserver<Q> {
+    qscheme: QScheme,
+    axis: usize,
+    ranges: Vec<(f32, f32)>,
+    dtype: PhantomData<Q>,
+}
+
+impl<Q: Quantized> PerChannelMinMaxObserver<Q> {
+    pub fn new(qscheme: QScheme, axis: usize) -> Self {
+        Self {
+            qscheme,
+            axis,
+            ranges: Vec::new(),
+            dtype: PhantomData,
+        }
+    }
+
+    /// The observed `(min, max)` of each channel, empty if nothing was seen.
+    pub fn ranges(&self) -> &[(f32, f32)] {
+        &self.ranges
+    }
+}
+
+impl<Q: Quantized> Observer for PerChannelMinMaxObserver<Q> {
+    fn observe(&mut self, x: &ArrayD<f32>) -> Result<(), QuantizationError> {
+        if self.axis >= x.ndim() {
+            return Err(QuantizationError::InvalidAxis {
+                axis: self.axis,
+                ndim: x.ndim(),
+            });
+        }
+        let channels = x.len_of(Axis(self.axis));
+        if !self.ranges.is_empty() && self.ranges.len() != channels {
+            return Err(QuantizationError::ChannelCountChanged {
+                axis: self.axis,
+                expected: self.ranges.len(),
+                actual: channels,
+            });
+        }
+        // A batch with an empty or all-NaN channel carries no range information
+        let batch: Option<Vec<_>> = x
+            .axis_iter(Axis(self.axis))
+            .map(|lane| min_max(lane.iter()))
+            .collect();
+        let Some(batch) = batch.filter(|batch| !batch.is_empty()) else {
+            return Ok(());
+        };
+        if self.ranges.is_empty() {
+            self.ranges = batch;
+        } else {
+            for ((min, max), (batch_min, batch_max)) in self.ranges.iter_mut().zip(batch) {
+                *min = min.min(batch_min);
+                *max = max.max(batch_max);
+            }
+        }
+        Ok(())
+    }
+
+    fn qparams(&self) -> Result<QParams, QuantizationError> {
+        if self.ranges.is_empty() {
+            return Err(QuantizationError::NoObservations);
+        }
+        let (scales, zero_points) = self
+            .ranges
+            .iter()
+            .map(|&(min_val, max_val)| calculate_qparams::<Q>(self.qscheme, min_val, max_val))
+            .unzip();
+        Ok(QParams::PerChannel {
+            scales,
+            zero_points,
+            axis: self.axis,
+        })
+    }
+}
+
+/// Clips the observed range to a percentile, so that rare outliers do not stretch the scale.
+///
+/// The range is `[quantile(1 - p / 100), quantile(p / 100)]` of every value observed, with
+/// quantiles interpolated linearly like `torch.quantile`, and is then mapped to parameters like
+/// [`MinMaxObserver`]. All observed values are kept until `qparams` is called, which is fine for
+/// the few hundred batches of a calibration run.
+#[derive(Debug, Clone)]
+pub struct PercentileObserver<Q> {
+    qscheme: QScheme,
+    percentile: f32,
+    values: Vec<f32>,
+    dtype: PhantomData<Q>,
+}
+
+impl<Q: Quantized> PercentileObserver<Q> {
+    /// # Arguments
+    ///
+    /// * `qscheme`: How the clipped range is mapped to parameters.
+    /// * `percentile`: Upper percentile to keep, in `(50, 100]`; 100 keeps the full range.
+    ///
+    /// # Returns
+    ///
+    /// The observer, or an error if `percentile` is out of range.
+    pub fn new(qscheme: QScheme, percentile: f32) -> Result<Self, QuantizationError> {
+        if !(percentile > 50.0 && percentile <= 100.0) {
+            return Err(QuantizationError::InvalidPercentile { percentile });
+        }
+        Ok(Self {
+            qscheme,
+            percentile,
+            values: Vec::new(),
+            dtype: PhantomData,
+        })
+    }
+
+    /// The clipped `(min, max)`, if any values were seen.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        if self.values.is_empty() {
+            return None;
+        }
+        let mut sorted = self.values.clone();
+        sorted.sort_by(f32::total_cmp);
+        let q = self.percentile / 100.0;
+        Some((quantile(&sorted, 1.0 - q), quantile(&sorted, q)))
+    }
+}
+
+impl<Q: Quantized> Observer for PercentileObserver<Q> {
+    fn observe(&mut self, x: &ArrayD<f32>) -> Result<(), QuantizationError> {
+        self.values.extend(x.iter().filter(|v| !v.is_nan()));
+        Ok(())
+    }
+
+    fn qparams(&self) -> Result<QParams, QuantizationError> {
+        let (min_val, max_val) = self.range().ok_or(QuantizationError::NoObservations)?;
+        let (scale, zero_point) = calculate_qparams::<Q>(self.qscheme, min_val, max_val);
+        Ok(QParams::PerTensor { scale, zero_point })
+    }
+}
+
+/// Minimum and maximum of the values that are not NaN, so that a stray NaN does not poison the
+/// range of every later batch.
+fn min_max<'a>(values: impl Iterator<Item = &'a f32>) -> Option<(f32, f32)> {
+    values
+        .filter(|v| !v.is_nan())
+        .fold(None, |range, &v| match range {
+            Some((min, max)) => Some((v.min(min), v.max(max))),
+            None => Some((v, v)),
+        })
+}
+
+/// Quantile `q` of non-empty sorted values, interpolating linearly between neighbours.
+fn quantile(sorted: &[f32], q: f32) -> f32 {
+    let position = q * (sorted.len() - 1) as f32;
+    let lower = position.floor() as usize;
+    let upper = (lower + 1).min(sorted.len() - 1);
+    let weight = position - lower as f32;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * weight
+}
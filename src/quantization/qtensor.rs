@@ -0,0 +1,234 @@
+use super::{QuantizationError, Quantized, check_qparams, quantize_val};
+use ndarray::{ArrayD, Axis, Zip};
+
+/// Affine quantization parameters, like `q_scale()` / `q_zero_point()` and their per-channel
+/// counterparts.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QParams {
+    /// One scale and zero point for every element, like `torch.per_tensor_affine`.
+    PerTensor { scale: f32, zero_point: i32 },
+    /// One scale and zero point per index along `axis`, like `torch.per_channel_affine`.
+    PerChannel {
+        scales: Vec<f32>,
+        zero_points: Vec<i32>,
+        axis: usize,
+    },
+}
+
+impl QParams {
+    /// Scale and zero point that apply to channel `channel`; per-tensor parameters apply to
+    /// every channel.
+    pub(crate) fn channel(&self, channel: usize) -> (f32, i32) {
+        match self {
+            QParams::PerTensor { scale, zero_point } => (*scale, *zero_point),
+            QParams::PerChannel {
+                scales,
+                zero_points,
+                ..
+            } => (scales[channel], zero_points[channel]),
+        }
+    }
+
+    /// Validates the parameters for elements of type `Q` stored in an array of shape `shape`.
+    fn check<Q: Quantized>(&self, shape: &[usize]) -> Result<(), QuantizationError> {
+        match self {
+            QParams::PerTensor { scale, zero_point } => check_qparams::<Q>(*scale, *zero_point),
+            QParams::PerChannel {
+                scales,
+                zero_points,
+                axis,
+            } => {
+                if *axis >= shape.len() {
+                    return Err(QuantizationError::InvalidAxis {
+                        axis: *axis,
+                        ndim: shape.len(),
+                    });
+                }
+                if scales.len() != shape[*axis] || zero_points.len() != shape[*axis] {
+                    return Err(QuantizationError::ChannelCountMismatch {
+                        axis: *axis,
+                        channels: shape[*axis],
+                        scales: scales.len(),
+                        zero_points: zero_points.len(),
+                    });
+                }
+                scales
+                    .iter()
+                    .zip(zero_points)
+                    .try_for_each(|(&scale, &zero_point)| check_qparams::<Q>(scale, zero_point))
+            }
+        }
+    }
+}
+
+/// A quantized tensor: 8-bit integer values and the parameters that map them to reals.
+///
+/// Mimics the quantized tensors returned by `torch.quantize_per_tensor` and
+/// `torch.quantize_per_channel`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QTensor<Q> {
+    values: ArrayD<Q>,
+    qparams: QParams,
+}
+
+impl<Q: Quantized> QTensor<Q> {
+    /// Wraps already quantized values, like `torch._make_per_tensor_quantized_tensor`.
+    ///
+    /// # Arguments
+    ///
+    /// * `values`: The integer representation.
+    /// * `qparams`: Parameters mapping `values` to reals.
+    ///
+    /// # Returns
+    ///
+    /// The quantized tensor, or an error if the parameters are invalid for `Q` or, for
+    /// per-channel parameters, do not match the size of the channel axis.
+    pub fn from_parts(values: ArrayD<Q>, qparams: QParams) -> Result<Self, QuantizationError> {
+        qparams.check::<Q>(values.shape())?;
+        Ok(Self { values, qparams })
+    }
+
+    /// The integer representation, like `tensor.int_repr()`.
+    pub fn int_repr(&self) -> &ArrayD<Q> {
+        &self.values
+    }
+
+    /// The quantization parameters.
+    pub fn qparams(&self) -> &QParams {
+        &self.qparams
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        self.values.shape()
+    }
+
+    /// Maps the values back to reals, like `tensor.dequantize()`.
+    pub fn dequantize(&self) -> ArrayD<f32> {
+        dequantize(self)
+    }
+}
+
+/// Quantizes `x` with a single scale and zero point.
+///
+/// Mimics `torch.quantize_per_tensor(x, scale, zero_point, dtype)`, with `dtype` given by `Q`:
+/// `i8` for `torch.qint8` and `u8` for `torch.quint8`.
+///
+/// # Arguments
+///
+/// * `x`: Values to quantize.
+/// * `scale`: Step between consecutive quantized values. Must be positive.
+/// * `zero_point`: Quantized value that represents zero. Must be in the range of `Q`.
+///
+/// # Returns
+///
+/// The quantized tensor, where each value is `clamp(round(x / scale) + zero_point)` with ties
+/// rounded to even.
+pub fn quantize_per_tensor<Q: Quantized>(
+    x: &ArrayD<f32>,
+    scale: f32,
+    zero_point: i32,
+) -> Result<QTensor<Q>, QuantizationError> {
+    quantize(x, QParams::PerTensor { scale, zero_point })
+}
+
+/// Quantizes `x` with a scale and zero point per index along `axis`.
+///
+/// Mimics `torch.quantize_per_channel(x, scales, zero_points, axis, dtype)`.
+///
+/// # Arguments
+///
+/// * `x`: Values to quantize.
+/// * `scales`: One positive scale per channel.
+/// * `zero_points`: One zero point per channel, in the range of `Q`.
+/// * `axis`: Channel axis of `x`.
+///
+/// # Returns
+///
+/// The quantized tensor, or an error if the parameters do not match `x.shape()[axis]`.
+pub fn quantize_per_channel<Q: Quantized>(
+    x: &ArrayD<f32>,
+    scales: &[f32],
+    zero_points: &[i32],
+    axis: usize,
+) -> Result<QTensor<Q>, QuantizationError> {
+    let qparams = QParams::PerChannel {
+        scales: scales.to_vec(),
+        zero_points: zero_points.to_vec(),
+        axis,
+    };
+    quantize(x, qparams)
+}
+
+/// Quantizes `x` with parameters from an observer or an existing tensor.
+///
+/// # Arguments
+///
+/// * `x`: Values to quantize.
+/// * `qparams`: Per-tensor or per-channel parameters.
+///
+/// # Returns
+///
+/// The quantized tensor, or an error if the parameters are invalid for `x`.
+pub fn quantize<Q: Quantized>(
+    x: &ArrayD<f32>,
+    qparams: QParams,
+) -> Result<QTensor<Q>, QuantizationError> {
+    qparams.check::<Q>(x.shape())?;
+    let values = match &qparams {
+        QParams::PerTensor { scale, zero_point } => {
+            let inv_scale = 1.0 / scale;
+            x.mapv(|v| quantize_val(v, inv_scale, *zero_point))
+        }
+        QParams::PerChannel { axis, .. } => {
+            let mut values = ArrayD::from_elem(x.raw_dim(), Q::from_i32(0));
+            for (channel, (mut out, lane)) in values
+                .axis_iter_mut(Axis(*axis))
+                .zip(x.axis_iter(Axis(*axis)))
+                .enumerate()
+            {
+                let (scale, zero_point) = qparams.channel(channel);
+                let inv_scale = 1.0 / scale;
+                Zip::from(&mut out)
+                    .and(&lane)
+                    .for_each(|q, &v| *q = quantize_val(v, inv_scale, zero_point));
+            }
+            values
+        }
+    };
+    Ok(QTensor { values, qparams })
+}
+
+/// Maps quantized values back to reals as `(q - zero_point) * scale`.
+///
+/// Mimics `torch.dequantize(q)`.
+///
+/// # Arguments
+///
+/// * `q`: The quantized tensor.
+///
+/// # Returns
+///
+/// An `f32` array of the same shape.
+pub fn dequantize<Q: Quantized>(q: &QTensor<Q>) -> ArrayD<f32> {
+    let dequantize_val =
+        |v: Q, scale: f32, zero_point: i32| (v.to_i32() - zero_point) as f32 * scale;
+    match &q.qparams {
+        QParams::PerTensor { scale, zero_point } => {
+            q.values.mapv(|v| dequantize_val(v, *scale, *zero_point))
+        }
+        QParams::PerChannel { axis, .. } => {
+            let mut out = ArrayD::zeros(q.values.raw_dim());
+            for (channel, (mut out, lane)) in out
+                .axis_iter_mut(Axis(*axis))
+                .zip(q.values.axis_iter(Axis(*axis)))
+                .enumerate()
+            {
+                let (scale, zero_point) = q.qparams.channel(channel);
+                Zip::from(&mut out)
+                    .and(&lane)
+                    .for_each(|x, &v| *x = dequantize_val(v, scale, zero_point));
+            }
+            out
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use super::{Generator, RandomFloat, RngError};
+use ndarray::{Array1, ArrayD, IxDyn};
+use std::f64::consts::PI;
+
+/// Returns a tensor of values drawn uniformly from `[0, 1)`.
+///
+/// Mimics `torch.rand(shape, generator=generator)` for `float32` and `float64`.
+///
+/// # Arguments
+///
+/// * `shape`: Shape of the result.
+/// * `generator`: Generator to draw from.
+///
+/// # Returns
+///
+/// A tensor of the given shape.
+pub fn rand<A: RandomFloat>(shape: &[usize], generator: &mut Generator) -> ArrayD<A> {
+    let mut x = ArrayD::zeros(IxDyn(shape));
+    uniform_(&mut x, A::zero(), A::one(), generator);
+    x
+}
+
+/// Fills `x` in place with values drawn uniformly from `[from, to)`.
+///
+/// Mimics `x.uniform_(from, to, generator=generator)`. Elements are filled in logical order,
+/// which is PyTorch's order for contiguous tensors.
+///
+/// # Arguments
+///
+/// * `x`: Tensor to fill.
+/// * `from`: Lower bound.
+/// * `to`: Upper bound.
+/// * `generator`: Generator to draw from.
+pub fn uniform_<A: RandomFloat>(x: &mut ArrayD<A>, from: A, to: A, generator: &mut Generator) {
+    for value in x.iter_mut() {
+        *value = A::standard_uniform(generator) * (to - from) + from;
+    }
+}
+
+/// Returns a tensor of values drawn from the standard normal distribution.
+///
+/// Mimics `torch.randn(shape, generator=generator)` for `float32` and `float64`.
+///
+/// # Arguments
+///
+/// * `shape`: Shape of the result.
+/// * `generator`: Generator to draw from.
+///
+/// # Returns
+///
+/// A tensor of the given shape.
+pub fn randn<A: RandomFloat>(shape: &[usize], generator: &mut Generator) -> ArrayD<A> {
+    let mut x = ArrayD::zeros(IxDyn(shape));
+    normal_(&mut x, 0.0, 1.0, generator);
+    x
+}
+
+/// Fills `x` in place with values drawn from a normal distribution.
+///
+/// Mimics `x.normal_(mean, std, generator=generator)`. Like ATen's `normal_kernel`, contiguous
+/// tensors of at least 16 elements are filled with uniforms first and transformed 16 at a time
+/// with Box-Muller; smaller or non-contiguous tensors draw one value at a time, each pair of
+/// uniforms giving two values.
+///
+/// # Arguments
+///
+/// * `x`: Tensor to fill.
+/// * `mean`: Mean of the distribution.
+/// * `std`: Standard deviation of the distribution.
+/// * `generator`: Generator to draw from.
+pub fn normal_<A: RandomFloat>(x: &mut ArrayD<A>, mean: f64, std: f64, generator: &mut Generator) {
+    if x.len() >= 16
+        && let Some(data) = x.as_slice_mut()
+    {
+        let (mean, std) = (A::from(mean).unwrap(), A::from(std).unwrap());
+        normal_fill(data, mean, std, generator);
+        return;
+    }
+    for value in x.iter_mut() {
+        *value = A::from(normal_double(generator, mean, std)).unwrap();
+    }
+}
+
+/// Draws from `at::normal_distribution<double>`, handing out the cached sine branch of the
+/// previous pair first.
+fn normal_double(generator: &mut Generator, mean: f64, std: f64) -> f64 {
+    if let Some(value) = generator.take_double_normal() {
+        return value * std + mean;
+    }
+    let u1 = f64::standard_uniform(generator);
+    let u2 = f64::standard_uniform(generator);
+    let radius = (-2.0 * (-u2).ln_1p()).sqrt();
+    let theta = 2.0 * PI * u1;
+    generator.set_double_normal(radius * theta.sin());
+    radius * theta.cos() * std + mean
+}
+
+/// ATen's `normal_fill`: every element gets a uniform, blocks of 16 are transformed, and a
+/// ragged tail is redrawn as the last 16 elements and transformed again.
+fn normal_fill<A: RandomFloat>(data: &mut [A], mean: A, std: A, generator: &mut Generator) {
+    for value in data.iter_mut() {
+        *value = A::standard_uniform(generator);
+    }
+    let size = data.len();
+    for start in (0..=size - 16).step_by(16) {
+        normal_fill_16(&mut data[start..start + 16], mean, std);
+    }
+    if !size.is_multiple_of(16) {
+        let tail = &mut data[size - 16..];
+        for value in tail.iter_mut() {
+            *value = A::standard_uniform(generator);
+        }
+        normal_fill_16(tail, mean, std);
+    }
+}
+
+fn normal_fill_16<A: RandomFloat>(data: &mut [A], mean: A, std: A) {
+    let two = A::from(2.0).unwrap();
+    for j in 0..8 {
+        // [0, 1) -> (0, 1] for the logarithm
+        let u1 = A::one() - data[j];
+        let u2 = data[j + 8];
+        let radius = (-two * u1.ln()).sqrt();
+        let theta = A::from(2.0 * PI * u2.to_f64().unwrap()).unwrap();
+        data[j] = radius * theta.cos() * std + mean;
+        data[j + 8] = radius * theta.sin() * std + mean;
+    }
+}
+
+/// Returns a tensor of integers drawn uniformly from `[low, high)`.
+///
+/// Mimics `torch.randint(low, high, shape, generator=generator)`. Ranges below `2^32` use one
+/// 32-bit draw per element, larger ranges a 64-bit draw, reduced modulo the range.
+///
+/// # Arguments
+///
+/// * `low`: Lowest value, inclusive.
+/// * `high`: Highest value, exclusive.
+/// * `shape`: Shape of the result.
+/// * `generator`: Generator to draw from.
+///
+/// # Returns
+///
+/// An `i64` tensor of the given shape, or an error if `low >= high`.
+pub fn randint(
+    low: i64,
+    high: i64,
+    shape: &[usize],
+    generator: &mut Generator,
+) -> Result<ArrayD<i64>, RngError> {
+    if low >= high {
+        return Err(RngError::EmptyRange { low, high });
+    }
+    let range = high.wrapping_sub(low) as u64;
+    let mut x = ArrayD::zeros(IxDyn(shape));
+    for value in x.iter_mut() {
+        let bits = if range >= 1 << 32 {
+            generator.random64()
+        } else {
+            generator.random() as u64
+        };
+        *value = (bits % range).wrapping_add(low as u64) as i64;
+    }
+    Ok(x)
+}
+
+/// Returns a random permutation of `0..n`.
+///
+/// Mimics `torch.randperm(n, generator=generator)`, including its switch from a forward
+/// Fisher-Yates shuffle with 32-bit draws to the inside-out variant with 64-bit draws for
+/// `n >= 2^32 / 20`.
+///
+/// # Arguments
+///
+/// * `n`: Length of the permutation.
+/// * `generator`: Generator to draw from.
+///
+/// # Returns
+///
+/// A 1-D `i64` tensor holding each of `0..n` once.
+pub fn randperm(n: usize, generator: &mut Generator) -> ArrayD<i64> {
+    let mut r = Array1::<i64>::zeros(n);
+    if (n as u64) < u32::MAX as u64 / 20 {
+        for (i, value) in r.iter_mut().enumerate() {
+            *value = i as i64;
+        }
+        for i in 0..n.saturating_sub(1) {
+            let z = generator.random() as usize % (n - i);
+            r.swap(i, z + i);
+        }
+    } else {
+        for i in 0..n {
+            let z = (generator.random64() % (i as u64 + 1)) as usize;
+            r[i] = r[z];
+            r[z] = i as i64;
+        }
+    }
+    r.into_dyn()
+}
@@ -0,0 +1,63 @@
+use super::mt19937::Mt19937;
+
+/// Seed of PyTorch's default CPU generator before any call to `manual_seed`.
+pub const DEFAULT_SEED: u64 = 67_280_421_310_721;
+
+/// A random number generator producing the same stream as PyTorch's CPU generator.
+///
+/// Mimics `torch.Generator()`: an mt19937 engine plus the cached second value of the last
+/// Box-Muller pair, which `torch.randn` on small tensors hands out before drawing again.
+/// Seeding resets both, so `Generator::new(s)` behaves like `torch.manual_seed(s)`.
+#[derive(Clone)]
+pub struct Generator {
+    engine: Mt19937,
+    seed: u64,
+    next_double_normal: Option<f64>,
+}
+
+impl Generator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            engine: Mt19937::new(seed),
+            seed,
+            next_double_normal: None,
+        }
+    }
+
+    /// Reseeds the generator, like `generator.manual_seed(seed)`.
+    pub fn manual_seed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    /// The seed the generator was last seeded with, like `generator.initial_seed()`.
+    pub fn initial_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns 32 random bits, like `CPUGeneratorImpl::random()`.
+    pub fn random(&mut self) -> u32 {
+        self.engine.next_u32()
+    }
+
+    /// Returns 64 random bits from two draws, the first one in the high half, like
+    /// `CPUGeneratorImpl::random64()`.
+    pub fn random64(&mut self) -> u64 {
+        let hi = self.random() as u64;
+        let lo = self.random() as u64;
+        (hi << 32) | lo
+    }
+
+    pub(crate) fn take_double_normal(&mut self) -> Option<f64> {
+        self.next_double_normal.take()
+    }
+
+    pub(crate) fn set_double_normal(&mut self, value: f64) {
+        self.next_double_normal = Some(value);
+    }
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEED)
+    }
+}
@@ -0,0 +1,68 @@
+//! Random tensors that reproduce PyTorch's CPU generator.
+//!
+//! [`Generator`] runs the same mt19937 engine as `torch.Generator`, and every sampling function
+//! consumes its bits in the same order and with the same transforms as the serial ATen CPU
+//! kernels, so `Generator::new(s)` followed by `rand(&[2, 3], &mut g)` gives exactly the values
+//! of `torch.manual_seed(s); torch.rand(2, 3)`. Tests can therefore regenerate their inputs
+//! instead of loading fixtures.
+//!
+//! Two kernels have vectorized variants in x86 builds of PyTorch: `randn` on `float32`
+//! tensors of 16 or more elements uses AVX2 `log`/`cos` approximations, which can differ from
+//! the scalar kernel reproduced here in the last bit, and `exponential_` (used by
+//! `multinomial` without replacement) draws from MKL's own stream. ARM builds, including
+//! Android, use the scalar kernels throughout.
+
+mod distributions;
+mod generator;
+mod mt19937;
+mod sampling;
+
+pub use distributions::{normal_, rand, randint, randn, randperm, uniform_};
+pub use generator::{DEFAULT_SEED, Generator};
+pub use sampling::{bernoulli, multinomial};
+
+use ndarray::NdFloat;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum RngError {
+    #[error("randint expects low < high, got low = {low} and high = {high}")]
+    EmptyRange { low: i64, high: i64 },
+
+    #[error("Invalid probability {value}, expected a value in [0, 1]")]
+    InvalidProbability { value: f64 },
+
+    #[error("Invalid multinomial distribution: {reason}")]
+    InvalidDistribution { reason: &'static str },
+
+    #[error("multinomial expects a 1-D or 2-D probability tensor, got shape {shape:?}")]
+    InvalidShape { shape: Vec<usize> },
+
+    #[error("Cannot draw {samples} samples from {categories} categories {reason}")]
+    InvalidSampleCount {
+        samples: usize,
+        categories: usize,
+        reason: &'static str,
+    },
+}
+
+/// A floating-point element that can be sampled uniformly from a [`Generator`].
+pub trait RandomFloat: NdFloat {
+    /// Draws a value in `[0, 1)` like `at::uniform_real_distribution<T>(0, 1)`: `f32` uses the
+    /// low 24 bits of one 32-bit draw, `f64` the low 53 bits of a 64-bit draw.
+    fn standard_uniform(generator: &mut Generator) -> Self;
+}
+
+impl RandomFloat for f32 {
+    fn standard_uniform(generator: &mut Generator) -> Self {
+        (generator.random() & ((1 << f32::MANTISSA_DIGITS) - 1)) as f32
+            * (1.0 / (1u32 << f32::MANTISSA_DIGITS) as f32)
+    }
+}
+
+impl RandomFloat for f64 {
+    fn standard_uniform(generator: &mut Generator) -> Self {
+        (generator.random64() & ((1 << f64::MANTISSA_DIGITS) - 1)) as f64
+            * (1.0 / (1u64 << f64::MANTISSA_DIGITS) as f64)
+    }
+}
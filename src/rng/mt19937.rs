@@ -0,0 +1,50 @@
+//! The 32-bit Mersenne Twister, bit-for-bit like `at::mt19937` and `std::mt19937`.
+
+const N: usize = 624;
+const M: usize = 397;
+const MATRIX_A: u32 = 0x9908_b0df;
+const UPPER_MASK: u32 = 0x8000_0000;
+const LOWER_MASK: u32 = 0x7fff_ffff;
+
+#[derive(Clone)]
+pub(crate) struct Mt19937 {
+    state: [u32; N],
+    next: usize,
+}
+
+impl Mt19937 {
+    /// Seeds the engine from the low 32 bits of `seed`, like `at::mt19937(seed)`.
+    pub(crate) fn new(seed: u64) -> Self {
+        let mut state = [0u32; N];
+        state[0] = seed as u32;
+        for j in 1..N {
+            let previous = state[j - 1];
+            state[j] = 1_812_433_253u32
+                .wrapping_mul(previous ^ (previous >> 30))
+                .wrapping_add(j as u32);
+        }
+        Self { state, next: N }
+    }
+
+    /// Returns the next 32 random bits.
+    pub(crate) fn next_u32(&mut self) -> u32 {
+        if self.next == N {
+            self.twist();
+        }
+        let mut y = self.state[self.next];
+        self.next += 1;
+        y ^= y >> 11;
+        y ^= (y << 7) & 0x9d2c_5680;
+        y ^= (y << 15) & 0xefc6_0000;
+        y ^ (y >> 18)
+    }
+
+    fn twist(&mut self) {
+        for i in 0..N {
+            let y = (self.state[i] & UPPER_MASK) | (self.state[(i + 1) % N] & LOWER_MASK);
+            let mag = if y & 1 == 1 { MATRIX_A } else { 0 };
+            self.state[i] = self.state[(i + M) % N] ^ (y >> 1) ^ mag;
+        }
+        self.next = 0;
+    }
+}
@@ -0,0 +1,155 @@
+use super::{Generator, RandomFloat, RngError};
+use ndarray::{Array2, ArrayD, Axis, Ix2};
+use std::cmp::Ordering;
+
+/// Draws 0 or 1 for every element, 1 with the probability stored in that element.
+///
+/// Mimics `torch.bernoulli(p, generator=generator)`: each element compares one uniform of the
+/// same precision as `p` against its probability.
+///
+/// # Arguments
+///
+/// * `p`: Probabilities in `[0, 1]`.
+/// * `generator`: Generator to draw from.
+///
+/// # Returns
+///
+/// A tensor of zeros and ones with the shape and element type of `p`, or an error if a
+/// probability is outside `[0, 1]`.
+pub fn bernoulli<A: RandomFloat>(
+    p: &ArrayD<A>,
+    generator: &mut Generator,
+) -> Result<ArrayD<A>, RngError> {
+    if let Some(&value) = p.iter().find(|&&v| !(v >= A::zero() && v <= A::one())) {
+        return Err(RngError::InvalidProbability {
+            value: value.to_f64().unwrap(),
+        });
+    }
+    // Draws follow the logical order, which is PyTorch's order for contiguous tensors
+    let draws = p.iter().map(|&p| {
+        if A::standard_uniform(generator) < p {
+            A::one()
+        } else {
+            A::zero()
+        }
+    });
+    Ok(ArrayD::from_shape_vec(p.raw_dim(), draws.collect()).unwrap())
+}
+
+/// Draws category indices from the distributions given by the rows of `probs`.
+///
+/// Mimics `torch.multinomial(probs, num_samples, replacement, generator=generator)`. Rows need
+/// not sum to one. Like ATen, a single sample or sampling without replacement uses the
+/// exponential race `topk(p / q)` with `q ~ Exp(1)`, while several samples with replacement
+/// search the normalized cumulative distribution with one `float64` uniform per sample.
+///
+/// # Arguments
+///
+/// * `probs`: Non-negative weights of shape `(categories)` or `(rows, categories)`.
+/// * `num_samples`: Number of samples per row.
+/// * `replacement`: Whether a category can be drawn more than once.
+/// * `generator`: Generator to draw from.
+///
+/// # Returns
+///
+/// An `i64` tensor of shape `(num_samples)` or `(rows, num_samples)`, or an error if a row is
+/// not a valid distribution or has too few categories to sample without replacement.
+pub fn multinomial<A: RandomFloat>(
+    probs: &ArrayD<A>,
+    num_samples: usize,
+    replacement: bool,
+    generator: &mut Generator,
+) -> Result<ArrayD<i64>, RngError> {
+    let rows = match probs.ndim() {
+        1 => probs.view().insert_axis(Axis(0)),
+        2 => probs.view(),
+        _ => {
+            return Err(RngError::InvalidShape {
+                shape: probs.shape().to_vec(),
+            });
+        }
+    };
+    let rows = rows.into_dimensionality::<Ix2>().unwrap();
+    let categories = rows.ncols();
+    if categories == 0 {
+        return Err(RngError::InvalidDistribution {
+            reason: "no categories",
+        });
+    }
+    if num_samples == 0 {
+        return Err(RngError::InvalidSampleCount {
+            samples: num_samples,
+            categories,
+            reason: "(at least one is required)",
+        });
+    }
+    if !replacement && num_samples > categories {
+        return Err(RngError::InvalidSampleCount {
+            samples: num_samples,
+            categories,
+            reason: "without replacement",
+        });
+    }
+    if rows.iter().any(|v| !(v.is_finite() && *v >= A::zero())) {
+        return Err(RngError::InvalidDistribution {
+            reason: "probability tensor contains either inf, nan or element < 0",
+        });
+    }
+    if rows.rows().into_iter().any(|row| row.sum() <= A::zero()) {
+        return Err(RngError::InvalidDistribution {
+            reason: "sum of probabilities <= 0",
+        });
+    }
+
+    let mut result = Array2::<i64>::zeros((rows.nrows(), num_samples));
+    if !replacement || num_samples == 1 {
+        // Exponentials for every element are drawn before any row is ranked
+        let q = Array2::from_shape_fn(rows.raw_dim(), |_| {
+            let u = f64::standard_uniform(generator);
+            A::from(-(-u).ln_1p()).unwrap()
+        });
+        for ((row, q), mut out) in rows.rows().into_iter().zip(q.rows()).zip(result.rows_mut()) {
+            let keys: Vec<A> = row.iter().zip(q).map(|(&p, &q)| p / q).collect();
+            let mut order: Vec<usize> = (0..categories).collect();
+            order.sort_by(|&a, &b| keys[b].partial_cmp(&keys[a]).unwrap_or(Ordering::Equal));
+            for (out, &index) in out.iter_mut().zip(&order) {
+                *out = index as i64;
+            }
+        }
+    } else {
+        for (row, mut out) in rows.rows().into_iter().zip(result.rows_mut()) {
+            let mut sum = A::zero();
+            let mut cumulative: Vec<A> = row
+                .iter()
+                .map(|&p| {
+                    sum += p;
+                    sum
+                })
+                .collect();
+            for value in cumulative.iter_mut() {
+                *value /= sum;
+            }
+            cumulative[categories - 1] = A::one();
+            for out in out.iter_mut() {
+                let u = f64::standard_uniform(generator);
+                // ATen's binary search, which stays well defined if rounding left the
+                // normalized sums a step above the final 1
+                let (mut left, mut right) = (0, categories);
+                while left < right {
+                    let mid = left + (right - left) / 2;
+                    if cumulative[mid].to_f64().unwrap() < u {
+                        left = mid + 1;
+                    } else {
+                        right = mid;
+                    }
+                }
+                *out = left as i64;
+            }
+        }
+    }
+    Ok(if probs.ndim() == 1 {
+        result.index_axis_move(Axis(0), 0).into_dyn()
+    } else {
+        result.into_dyn()
+    })
+}
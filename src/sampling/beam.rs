@@ -0,0 +1,283 @@
+use super::SamplingError;
+use crate::functions::index_select::index_select;
+use ndarray::{Array2, ArrayD, Ix2};
+
+/// A finished sequence of beam search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hypothesis {
+    /// Generated token ids, ending with the end-of-sequence token if one was produced.
+    pub tokens: Vec<i64>,
+    /// Summed log probability divided by `tokens.len() ^ length_penalty`.
+    pub score: f32,
+}
+
+/// The beams chosen by one [`BeamSearch::step`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeamStep {
+    /// For every new beam, the row of the previous `(batch * num_beams)` rows it continues.
+    pub beam_indices: Vec<i64>,
+    /// For every new beam, the token appended to it.
+    pub tokens: Vec<i64>,
+}
+
+impl BeamStep {
+    /// Reorders a per-beam cache, such as the keys and values of an attention layer, so that
+    /// row `i` along `dim` holds the state of new beam `i`.
+    ///
+    /// Mimics `cache.index_select(dim, beam_idx)` as done by `_reorder_cache` in Hugging Face
+    /// `transformers`.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache`: A cache whose `dim` has `batch * num_beams` entries.
+    /// * `dim`: The beam dimension. Negative values wrap around.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ArrayD<A>)`: The reordered cache.
+    /// * `Err(SamplingError)`: If `dim` is out of bounds or too short.
+    pub fn reorder_cache<A: Clone>(
+        &self,
+        cache: &ArrayD<A>,
+        dim: isize,
+    ) -> Result<ArrayD<A>, SamplingError> {
+        Ok(index_select(cache, dim, &self.beam_indices)?)
+    }
+}
+
+/// Bookkeeping for batched beam search.
+///
+/// Mimics `BeamSearchScorer` of Hugging Face `transformers` with `early_stopping=False`. Every
+/// sequence of the batch has `num_beams` running beams, stored as rows
+/// `batch * num_beams + beam`. Each [`step`](Self::step) takes the log probabilities of the
+/// next token for every row, keeps the `2 * num_beams` best continuations of each sequence,
+/// moves those ending in the end-of-sequence token to the finished hypotheses, and keeps the
+/// best `num_beams` others running. A sequence is done once it has `num_beams` finished
+/// hypotheses and no running beam can beat the worst of them; its rows are then padded with
+/// the end-of-sequence token (or 0) and their scores ignored.
+#[derive(Debug, Clone)]
+pub struct BeamSearch {
+    batch_size: usize,
+    num_beams: usize,
+    length_penalty: f32,
+    eos_token_id: Option<i64>,
+    scores: Vec<f32>,
+    sequences: Vec<Vec<i64>>,
+    finished: Vec<Vec<Hypothesis>>,
+    done: Vec<bool>,
+}
+
+impl BeamSearch {
+    /// Starts a beam search with empty beams.
+    ///
+    /// Only the first beam of each sequence starts with score 0; the others start at `-1e9`
+    /// so the first step does not pick the same continuation `num_beams` times.
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_size`: Number of sequences searched at once.
+    /// * `num_beams`: Number of beams per sequence.
+    /// * `length_penalty`: Exponent of the length that finished scores are divided by; values
+    ///   above 0 favour longer sequences.
+    /// * `eos_token_id`: Token that finishes a beam, if any.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BeamSearch)`: The search state.
+    /// * `Err(SamplingError)`: If `batch_size` or `num_beams` is zero.
+    pub fn new(
+        batch_size: usize,
+        num_beams: usize,
+        length_penalty: f32,
+        eos_token_id: Option<i64>,
+    ) -> Result<Self, SamplingError> {
+        if batch_size == 0 || num_beams == 0 {
+            return Err(SamplingError::InvalidBeamCount {
+                batch_size,
+                num_beams,
+            });
+        }
+        let rows = batch_size * num_beams;
+        let scores = (0..rows)
+            .map(|row| if row % num_beams == 0 { 0.0 } else { -1e9 })
+            .collect();
+        Ok(Self {
+            batch_size,
+            num_beams,
+            length_penalty,
+            eos_token_id,
+            scores,
+            sequences: vec![Vec::new(); rows],
+            finished: vec![Vec::new(); batch_size],
+            done: vec![false; batch_size],
+        })
+    }
+
+    /// Advances every beam by one token.
+    ///
+    /// # Arguments
+    ///
+    /// * `log_probs`: Log probabilities of the next token, of shape
+    ///   `(batch * num_beams, vocab)`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BeamStep)`: The rows the new beams continue and the tokens appended to them.
+    /// * `Err(SamplingError)`: If `log_probs` has the wrong shape.
+    pub fn step(&mut self, log_probs: &ArrayD<f32>) -> Result<BeamStep, SamplingError> {
+        let rows = self.batch_size * self.num_beams;
+        let log_probs = match log_probs.view().into_dimensionality::<Ix2>() {
+            Ok(view) if view.nrows() == rows && view.ncols() > 0 => view,
+            _ => {
+                return Err(SamplingError::ShapeMismatch {
+                    expected: vec![rows, log_probs.shape().last().copied().unwrap_or(0)],
+                    actual: log_probs.shape().to_vec(),
+                });
+            }
+        };
+        let cur_len = self.sequences[0].len() + 1;
+        let pad = self.eos_token_id.unwrap_or(0);
+
+        let mut beam_indices = Vec::with_capacity(rows);
+        let mut tokens = Vec::with_capacity(rows);
+        let mut scores = Vec::with_capacity(rows);
+        for batch in 0..self.batch_size {
+            let first = batch * self.num_beams;
+            if !self.done[batch] {
+                let mut candidates: Vec<(f32, usize, usize)> = (0..self.num_beams)
+                    .flat_map(|beam| {
+                        let row = first + beam;
+                        let score = self.scores[row];
+                        log_probs
+                            .row(row)
+                            .iter()
+                            .enumerate()
+                            .map(move |(token, &lp)| (score + lp, row, token))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect();
+                // Stable, so ties keep the lower beam and token
+                candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+                candidates.truncate(2 * self.num_beams);
+
+                let mut next = Vec::with_capacity(self.num_beams);
+                for (rank, &(score, row, token)) in candidates.iter().enumerate() {
+                    if Some(token as i64) == self.eos_token_id {
+                        if rank < self.num_beams {
+                            let mut hypothesis = self.sequences[row].clone();
+                            hypothesis.push(token as i64);
+                            self.add_hypothesis(batch, hypothesis, score);
+                        }
+                    } else {
+                        next.push((score, row, token));
+                        if next.len() == self.num_beams {
+                            break;
+                        }
+                    }
+                }
+
+                if next.len() == self.num_beams {
+                    let best = next[0].0;
+                    self.done[batch] = self.finished[batch].len() == self.num_beams
+                        && self.worst_finished(batch) >= best / self.penalty(cur_len);
+                    for (score, row, token) in next {
+                        beam_indices.push(row as i64);
+                        tokens.push(token as i64);
+                        scores.push(score);
+                    }
+                    continue;
+                }
+                // Only a vocabulary smaller than two tokens leaves too few running beams
+                self.done[batch] = true;
+            }
+            for row in first..first + self.num_beams {
+                beam_indices.push(row as i64);
+                tokens.push(pad);
+                scores.push(0.0);
+            }
+        }
+
+        self.sequences = beam_indices
+            .iter()
+            .zip(&tokens)
+            .map(|(&row, &token)| {
+                let mut sequence = self.sequences[row as usize].clone();
+                sequence.push(token);
+                sequence
+            })
+            .collect();
+        self.scores = scores;
+        Ok(BeamStep {
+            beam_indices,
+            tokens,
+        })
+    }
+
+    /// Returns whether every sequence of the batch is done.
+    pub fn is_done(&self) -> bool {
+        self.done.iter().all(|&done| done)
+    }
+
+    /// Returns the running beams, of shape `(batch * num_beams, steps)`.
+    pub fn sequences(&self) -> ArrayD<i64> {
+        let steps = self.sequences[0].len();
+        Array2::from_shape_fn((self.sequences.len(), steps), |(row, step)| {
+            self.sequences[row][step]
+        })
+        .into_dyn()
+    }
+
+    /// Returns the summed log probabilities of the running beams.
+    pub fn scores(&self) -> &[f32] {
+        &self.scores
+    }
+
+    /// Ends the search and returns the best `num_beams` hypotheses of every sequence, best
+    /// first. Running beams of sequences that are not done compete with the finished ones.
+    pub fn finalize(mut self) -> Vec<Vec<Hypothesis>> {
+        for batch in 0..self.batch_size {
+            if self.done[batch] {
+                continue;
+            }
+            for row in batch * self.num_beams..(batch + 1) * self.num_beams {
+                let sequence = std::mem::take(&mut self.sequences[row]);
+                self.add_hypothesis(batch, sequence, self.scores[row]);
+            }
+        }
+        let mut finished = self.finished;
+        for hypotheses in &mut finished {
+            hypotheses.sort_by(|a, b| b.score.total_cmp(&a.score));
+        }
+        finished
+    }
+
+    fn penalty(&self, length: usize) -> f32 {
+        (length.max(1) as f32).powf(self.length_penalty)
+    }
+
+    fn worst_finished(&self, batch: usize) -> f32 {
+        self.finished[batch]
+            .iter()
+            .map(|h| h.score)
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    /// Adds a hypothesis, dropping the worst one if there are more than `num_beams`.
+    fn add_hypothesis(&mut self, batch: usize, tokens: Vec<i64>, sum_log_probs: f32) {
+        let score = sum_log_probs / self.penalty(tokens.len());
+        if self.finished[batch].len() == self.num_beams && score <= self.worst_finished(batch) {
+            return;
+        }
+        let hypotheses = &mut self.finished[batch];
+        hypotheses.push(Hypothesis { tokens, score });
+        if hypotheses.len() > self.num_beams {
+            let worst = hypotheses
+                .iter()
+                .enumerate()
+                .min_by(|a, b| a.1.score.total_cmp(&b.1.score))
+                .map(|(i, _)| i)
+                .unwrap();
+            hypotheses.remove(worst);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Token selection for autoregressive decoding.
+//!
+//! Every function takes logits of shape `(*, vocab)`, usually `(batch, vocab)` for the last
+//! position of each sequence. The logit processors mirror the ones of Hugging Face
+//! `transformers` (`RepetitionPenaltyLogitsProcessor`, `TemperatureLogitsWarper`,
+//! `TopKLogitsWarper`, `TopPLogitsWarper`): filtered entries are set to `-inf`, so a later
+//! softmax gives them zero probability. [`sample`] chains them in the same order as
+//! `generate(do_sample=True)` and draws with [`crate::rng::multinomial`], so a seeded
+//! [`crate::rng::Generator`] picks the tokens PyTorch would. [`BeamSearch`] keeps the running
+//! beams and finished hypotheses of beam search and reports which cache rows to keep.
+
+mod beam;
+mod processors;
+mod sample;
+
+pub use beam::{BeamSearch, BeamStep, Hypothesis};
+pub use processors::{repetition_penalty, temperature, top_k, top_p};
+pub use sample::{SamplingConfig, greedy, multinomial_sample, sample};
+
+use crate::functions::argmax::ArgmaxError;
+use crate::functions::index_select::IndexSelectError;
+use crate::nn::functional::FunctionalError;
+use crate::rng::RngError;
+use ndarray::ArrayD;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum SamplingError {
+    #[error("Expected logits of shape (*, vocab) with a non-empty vocabulary, got shape {shape:?}")]
+    InvalidLogits { shape: Vec<usize> },
+
+    #[error("Temperature must be positive and finite, got {temperature}")]
+    InvalidTemperature { temperature: f32 },
+
+    #[error("top_k must be at least 1")]
+    InvalidTopK,
+
+    #[error("top_p must be in [0, 1], got {top_p}")]
+    InvalidTopP { top_p: f32 },
+
+    #[error("min_tokens_to_keep must be at least 1")]
+    InvalidMinTokensToKeep,
+
+    #[error("Repetition penalty must be positive and finite, got {penalty}")]
+    InvalidPenalty { penalty: f32 },
+
+    #[error("Expected shape {expected:?}, got {actual:?}")]
+    ShapeMismatch {
+        expected: Vec<usize>,
+        actual: Vec<usize>,
+    },
+
+    #[error("Token {token} is out of range for a vocabulary of {vocab}")]
+    TokenOutOfRange { token: i64, vocab: usize },
+
+    #[error("A repetition penalty is configured but no previous tokens were given")]
+    MissingPreviousTokens,
+
+    #[error(
+        "Beam search needs at least one sequence and one beam, got {batch_size} and {num_beams}"
+    )]
+    InvalidBeamCount { batch_size: usize, num_beams: usize },
+
+    #[error("Internal error during array creation: {0}")]
+    InternalShapeError(String),
+
+    #[error(transparent)]
+    Rng(#[from] RngError),
+
+    #[error(transparent)]
+    Argmax(#[from] ArgmaxError),
+
+    #[error(transparent)]
+    IndexSelect(#[from] IndexSelectError),
+
+    #[error(transparent)]
+    Functional(#[from] FunctionalError),
+}
+
+/// Returns the vocabulary size of `(*, vocab)` logits, checking that there is one.
+pub(crate) fn vocab_size(logits: &ArrayD<f32>) -> Result<usize, SamplingError> {
+    match logits.shape().last() {
+        Some(&vocab) if vocab > 0 => Ok(vocab),
+        _ => Err(SamplingError::InvalidLogits {
+            shape: logits.shape().to_vec(),
+        }),
+    }
+}
@@ -0,0 +1,168 @@
+use super::{SamplingError, vocab_size};
+use ndarray::{ArrayD, Axis};
+
+/// Divides the logits by `temperature`, sharpening the distribution below 1 and flattening it
+/// above.
+///
+/// Mimics `transformers.TemperatureLogitsWarper(temperature)`.
+///
+/// # Arguments
+///
+/// * `logits`: Logits of shape `(*, vocab)`.
+/// * `temperature`: Positive temperature.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<f32>)`: The scaled logits.
+/// * `Err(SamplingError)`: If the temperature is not positive or the logits have no vocabulary.
+pub fn temperature(logits: &ArrayD<f32>, temperature: f32) -> Result<ArrayD<f32>, SamplingError> {
+    vocab_size(logits)?;
+    if !(temperature.is_finite() && temperature > 0.0) {
+        return Err(SamplingError::InvalidTemperature { temperature });
+    }
+    Ok(logits.mapv(|x| x / temperature))
+}
+
+/// Keeps the `k` largest logits of every row and sets the others to `-inf`.
+///
+/// Mimics `transformers.TopKLogitsWarper(top_k)`: entries tied with the `k`-th largest value
+/// are kept as well, and `k` larger than the vocabulary keeps everything.
+///
+/// # Arguments
+///
+/// * `logits`: Logits of shape `(*, vocab)`.
+/// * `k`: Number of entries to keep per row.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<f32>)`: The filtered logits.
+/// * `Err(SamplingError)`: If `k` is zero or the logits have no vocabulary.
+pub fn top_k(logits: &ArrayD<f32>, k: usize) -> Result<ArrayD<f32>, SamplingError> {
+    let vocab = vocab_size(logits)?;
+    if k == 0 {
+        return Err(SamplingError::InvalidTopK);
+    }
+    let mut output = logits.to_owned();
+    if k >= vocab {
+        return Ok(output);
+    }
+    let last = Axis(logits.ndim() - 1);
+    for mut row in output.lanes_mut(last) {
+        let mut sorted = row.to_vec();
+        sorted.sort_by(|a, b| b.total_cmp(a));
+        let threshold = sorted[k - 1];
+        row.mapv_inplace(|x| if x < threshold { f32::NEG_INFINITY } else { x });
+    }
+    Ok(output)
+}
+
+/// Keeps the smallest set of most likely entries whose probabilities sum to more than `p`
+/// (nucleus sampling), setting the others to `-inf`.
+///
+/// Mimics `transformers.TopPLogitsWarper(top_p, min_tokens_to_keep)`: entries are sorted by
+/// ascending logit, and those whose cumulative probability is at most `1 - p` are removed,
+/// except for the `min_tokens_to_keep` most likely ones.
+///
+/// # Arguments
+///
+/// * `logits`: Logits of shape `(*, vocab)`.
+/// * `p`: Probability mass to keep, in `[0, 1]`.
+/// * `min_tokens_to_keep`: Minimum number of entries kept per row, at least 1.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<f32>)`: The filtered logits.
+/// * `Err(SamplingError)`: If `p` or `min_tokens_to_keep` is out of range or the logits have no
+///   vocabulary.
+pub fn top_p(
+    logits: &ArrayD<f32>,
+    p: f32,
+    min_tokens_to_keep: usize,
+) -> Result<ArrayD<f32>, SamplingError> {
+    let vocab = vocab_size(logits)?;
+    if !(0.0..=1.0).contains(&p) {
+        return Err(SamplingError::InvalidTopP { top_p: p });
+    }
+    if min_tokens_to_keep == 0 {
+        return Err(SamplingError::InvalidMinTokensToKeep);
+    }
+    let mut output = logits.to_owned();
+    let last = Axis(logits.ndim() - 1);
+    for mut row in output.lanes_mut(last) {
+        let mut order: Vec<usize> = (0..vocab).collect();
+        order.sort_by(|&a, &b| row[a].total_cmp(&row[b]));
+        let max = row[order[vocab - 1]];
+        let exps: Vec<f32> = order.iter().map(|&i| (row[i] - max).exp()).collect();
+        let sum: f32 = exps.iter().sum();
+        let mut cumulative = 0.0;
+        let removable = vocab.saturating_sub(min_tokens_to_keep);
+        for (&i, e) in order.iter().zip(exps).take(removable) {
+            cumulative += e / sum;
+            // The cumulative sum only grows, so no later entry is removed either
+            if cumulative > 1.0 - p {
+                break;
+            }
+            row[i] = f32::NEG_INFINITY;
+        }
+    }
+    Ok(output)
+}
+
+/// Discourages tokens that already appear in each sequence.
+///
+/// Mimics `transformers.RepetitionPenaltyLogitsProcessor(penalty)`: the logit of every token
+/// present in the row's previous tokens is divided by `penalty` if positive and multiplied by
+/// it if negative, once however often the token occurs.
+///
+/// # Arguments
+///
+/// * `logits`: Logits of shape `(*, vocab)`.
+/// * `previous_tokens`: Token ids of shape `(*, seq)`, with the same leading shape as `logits`.
+/// * `penalty`: Positive penalty; values above 1 discourage repetition.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<f32>)`: The penalized logits.
+/// * `Err(SamplingError)`: If the penalty is not positive, the shapes do not match or a token
+///   is outside the vocabulary.
+pub fn repetition_penalty(
+    logits: &ArrayD<f32>,
+    previous_tokens: &ArrayD<i64>,
+    penalty: f32,
+) -> Result<ArrayD<f32>, SamplingError> {
+    let vocab = vocab_size(logits)?;
+    if !(penalty.is_finite() && penalty > 0.0) {
+        return Err(SamplingError::InvalidPenalty { penalty });
+    }
+    let leading = &logits.shape()[..logits.ndim() - 1];
+    if previous_tokens.ndim() != logits.ndim()
+        || &previous_tokens.shape()[..previous_tokens.ndim() - 1] != leading
+    {
+        let mut expected = leading.to_vec();
+        expected.push(previous_tokens.shape().last().copied().unwrap_or(0));
+        return Err(SamplingError::ShapeMismatch {
+            expected,
+            actual: previous_tokens.shape().to_vec(),
+        });
+    }
+    let mut output = logits.to_owned();
+    let last = Axis(logits.ndim() - 1);
+    for (mut row, tokens) in output
+        .lanes_mut(last)
+        .into_iter()
+        .zip(previous_tokens.lanes(last))
+    {
+        let mut seen = vec![false; vocab];
+        for &token in tokens {
+            let index = usize::try_from(token)
+                .ok()
+                .filter(|&i| i < vocab)
+                .ok_or(SamplingError::TokenOutOfRange { token, vocab })?;
+            if !std::mem::replace(&mut seen[index], true) {
+                let x = row[index];
+                row[index] = if x < 0.0 { x * penalty } else { x / penalty };
+            }
+        }
+    }
+    Ok(output)
+}
@@ -0,0 +1,120 @@
+use super::processors::{repetition_penalty, temperature, top_k, top_p};
+use super::{SamplingError, vocab_size};
+use crate::functions::argmax::argmax;
+use crate::nn::functional::softmax;
+use crate::rng::{Generator, multinomial};
+use ndarray::{ArrayD, IxDyn};
+
+/// Settings for [`sample`]. Filters left at `None` are skipped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SamplingConfig {
+    pub temperature: f32,
+    pub top_k: Option<usize>,
+    pub top_p: Option<f32>,
+    pub repetition_penalty: Option<f32>,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            temperature: 1.0,
+            top_k: None,
+            top_p: None,
+            repetition_penalty: None,
+        }
+    }
+}
+
+/// Picks the most likely token of every row.
+///
+/// Mimics `logits.argmax(dim=-1)`: ties go to the lowest token id, and a NaN logit wins over
+/// every number.
+///
+/// # Arguments
+///
+/// * `logits`: Logits of shape `(*, vocab)`.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<i64>)`: Token ids of shape `(*)`.
+/// * `Err(SamplingError)`: If the logits have no vocabulary.
+pub fn greedy(logits: &ArrayD<f32>) -> Result<ArrayD<i64>, SamplingError> {
+    vocab_size(logits)?;
+    Ok(argmax(logits, Some(logits.ndim() - 1), false)?)
+}
+
+/// Draws one token per row from the softmax of the logits.
+///
+/// Mimics `torch.multinomial(torch.softmax(logits, dim=-1), 1, generator=generator)` followed
+/// by dropping the sample dimension, so the same seed picks the same tokens as PyTorch.
+///
+/// # Arguments
+///
+/// * `logits`: Logits of shape `(*, vocab)`. Entries at `-inf` are never drawn.
+/// * `generator`: Generator to draw from.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<i64>)`: Token ids of shape `(*)`.
+/// * `Err(SamplingError)`: If the logits have no vocabulary or a row has no finite entry.
+pub fn multinomial_sample(
+    logits: &ArrayD<f32>,
+    generator: &mut Generator,
+) -> Result<ArrayD<i64>, SamplingError> {
+    let vocab = vocab_size(logits)?;
+    let leading = logits.shape()[..logits.ndim() - 1].to_vec();
+    let probs = softmax(logits, -1)?;
+    // softmax keeps the layout of the logits, and both reshapes must walk the rows in C order
+    let rows = probs
+        .as_standard_layout()
+        .into_owned()
+        .into_shape((leading.iter().product::<usize>(), vocab))
+        .map_err(|e| SamplingError::InternalShapeError(e.to_string()))?
+        .into_dyn();
+    let tokens = multinomial(&rows, 1, false, generator)?;
+    tokens
+        .into_shape(IxDyn(&leading))
+        .map_err(|e| SamplingError::InternalShapeError(e.to_string()))
+}
+
+/// Applies the filters of `config` and draws one token per row.
+///
+/// Mimics `generate(do_sample=True)` of Hugging Face `transformers` for a single step: the
+/// repetition penalty, temperature, top-k and top-p are applied in that order before
+/// [`multinomial_sample`].
+///
+/// # Arguments
+///
+/// * `logits`: Logits of shape `(*, vocab)`.
+/// * `previous_tokens`: Token ids of shape `(*, seq)` generated so far. Required when
+///   `config` has a repetition penalty, ignored otherwise.
+/// * `config`: The filters to apply.
+/// * `generator`: Generator to draw from.
+///
+/// # Returns
+///
+/// * `Ok(ArrayD<i64>)`: Token ids of shape `(*)`.
+/// * `Err(SamplingError)`: If a setting is out of range, the shapes do not match, or a
+///   repetition penalty is configured without previous tokens.
+pub fn sample(
+    logits: &ArrayD<f32>,
+    previous_tokens: Option<&ArrayD<i64>>,
+    config: &SamplingConfig,
+    generator: &mut Generator,
+) -> Result<ArrayD<i64>, SamplingError> {
+    let mut logits = logits.to_owned();
+    if let Some(penalty) = config.repetition_penalty {
+        let previous_tokens = previous_tokens.ok_or(SamplingError::MissingPreviousTokens)?;
+        logits = repetition_penalty(&logits, previous_tokens, penalty)?;
+    }
+    if config.temperature != 1.0 {
+        logits = temperature(&logits, config.temperature)?;
+    }
+    if let Some(k) = config.top_k {
+        logits = top_k(&logits, k)?;
+    }
+    if let Some(p) = config.top_p {
+        logits = top_p(&logits, p, 1)?;
+    }
+    multinomial_sample(&logits, generator)
+}
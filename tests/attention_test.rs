@@ -0,0 +1,170 @@
+mod common;
+
+use RustOps::nn::functional::{AttentionMask, FunctionalError, scaled_dot_product_attention};
+use approx::assert_abs_diff_eq;
+use common::uniform;
+use ndarray::{ArrayD, Axis, IxDyn, array};
+
+/// Materializes the full score matrix for one (L, E) x (S, E) head.
+fn naive_head(
+    q: ArrayD<f64>,
+    k: ArrayD<f64>,
+    v: ArrayD<f64>,
+    bias: impl Fn(usize, usize) -> f64,
+    scale: f64,
+) -> ArrayD<f64> {
+    let (l, s, ev) = (q.shape()[0], k.shape()[0], v.shape()[1]);
+    let mut out = ArrayD::zeros(IxDyn(&[l, ev]));
+    for i in 0..l {
+        let scores: Vec<f64> = (0..s)
+            .map(|j| {
+                let dot: f64 = (0..q.shape()[1]).map(|d| q[[i, d]] * k[[j, d]]).sum();
+                dot * scale + bias(i, j)
+            })
+            .collect();
+        let max = scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = scores.iter().map(|x| (x - max).exp()).collect();
+        let total: f64 = weights.iter().sum();
+        for c in 0..ev {
+            out[[i, c]] = (0..s).map(|j| weights[j] * v[[j, c]]).sum::<f64>() / total;
+        }
+    }
+    out
+}
+
+#[test]
+fn test_attention_matches_naive_across_blocks() {
+    let (l, s, e) = (130, 300, 8);
+    let q = uniform::<f64>(&[2, l, e], 1);
+    let k = uniform::<f64>(&[2, s, e], 2);
+    let v = uniform::<f64>(&[2, s, 5], 3);
+    let additive = uniform::<f64>(&[l, s], 4);
+    let boolean = uniform::<f64>(&[l, s], 5).mapv(|x| x > -0.5);
+
+    let plain = scaled_dot_product_attention(&q, &k, &v, None, false, Some(0.5)).unwrap();
+    let causal = scaled_dot_product_attention(&q, &k, &v, None, true, None).unwrap();
+    let added = scaled_dot_product_attention(
+        &q,
+        &k,
+        &v,
+        Some(AttentionMask::Additive(&additive)),
+        false,
+        None,
+    )
+    .unwrap();
+    let masked = scaled_dot_product_attention(
+        &q,
+        &k,
+        &v,
+        Some(AttentionMask::Boolean(&boolean)),
+        false,
+        None,
+    )
+    .unwrap();
+
+    let default_scale = 1.0 / (e as f64).sqrt();
+    for b in 0..2 {
+        let head = |x: &ArrayD<f64>| x.index_axis(Axis(0), b).to_owned();
+        let (qb, kb, vb) = (head(&q), head(&k), head(&v));
+        let expected = naive_head(qb.clone(), kb.clone(), vb.clone(), |_, _| 0.0, 0.5);
+        assert_abs_diff_eq!(head(&plain), expected, epsilon = 1e-10);
+
+        let expected = naive_head(
+            qb.clone(),
+            kb.clone(),
+            vb.clone(),
+            |i, j| if j > i { f64::NEG_INFINITY } else { 0.0 },
+            default_scale,
+        );
+        assert_abs_diff_eq!(head(&causal), expected, epsilon = 1e-10);
+
+        let expected = naive_head(
+            qb.clone(),
+            kb.clone(),
+            vb.clone(),
+            |i, j| additive[[i, j]],
+            default_scale,
+        );
+        assert_abs_diff_eq!(head(&added), expected, epsilon = 1e-10);
+
+        let expected = naive_head(
+            qb,
+            kb,
+            vb,
+            |i, j| {
+                if boolean[[i, j]] {
+                    0.0
+                } else {
+                    f64::NEG_INFINITY
+                }
+            },
+            default_scale,
+        );
+        assert_abs_diff_eq!(head(&masked), expected, epsilon = 1e-10);
+    }
+}
+
+#[test]
+fn test_attention_grouped_query_heads() {
+    let q = uniform::<f64>(&[1, 4, 3, 2], 7);
+    let k = uniform::<f64>(&[1, 2, 5, 2], 8);
+    let v = uniform::<f64>(&[1, 2, 5, 3], 9);
+    let out = scaled_dot_product_attention(&q, &k, &v, None, false, None).unwrap();
+    assert_eq!(out.shape(), &[1, 4, 3, 3]);
+
+    let scale = 1.0 / 2f64.sqrt();
+    for h in 0..4 {
+        let pick = |x: &ArrayD<f64>, head: usize| {
+            x.index_axis(Axis(0), 0)
+                .index_axis(Axis(0), head)
+                .to_owned()
+        };
+        let expected = naive_head(
+            pick(&q, h),
+            pick(&k, h / 2),
+            pick(&v, h / 2),
+            |_, _| 0.0,
+            scale,
+        );
+        assert_abs_diff_eq!(pick(&out, h), expected, epsilon = 1e-12);
+    }
+}
+
+#[test]
+fn test_attention_fully_masked_rows_and_errors() {
+    let q = array![[1.0f64, 0.0], [0.0, 1.0]].into_dyn();
+    let mask = array![[true, false], [false, false]].into_dyn();
+    let out =
+        scaled_dot_product_attention(&q, &q, &q, Some(AttentionMask::Boolean(&mask)), false, None)
+            .unwrap();
+    assert_eq!(
+        out.index_axis(Axis(0), 0)
+            .iter()
+            .copied()
+            .collect::<Vec<_>>(),
+        vec![1.0, 0.0]
+    );
+    assert!(out.index_axis(Axis(0), 1).iter().all(|x| x.is_nan()));
+
+    assert_eq!(
+        scaled_dot_product_attention(&q, &q, &q, Some(AttentionMask::Boolean(&mask)), true, None),
+        Err(FunctionalError::CausalWithMask)
+    );
+    let k = ArrayD::<f64>::zeros(IxDyn(&[3, 3]));
+    assert!(matches!(
+        scaled_dot_product_attention(&q, &k, &k, None, false, None),
+        Err(FunctionalError::AttentionShapeMismatch { .. })
+    ));
+    let bad_mask = ArrayD::from_elem(IxDyn(&[3, 2]), true);
+    assert!(matches!(
+        scaled_dot_product_attention(
+            &q,
+            &q,
+            &q,
+            Some(AttentionMask::Boolean(&bad_mask)),
+            false,
+            None
+        ),
+        Err(FunctionalError::AttentionMaskShape { .. })
+    ));
+}
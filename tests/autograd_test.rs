@@ -0,0 +1,323 @@
+use RustOps::autograd::{AutogradError, Tape, Var, einsum};
+use approx::assert_abs_diff_eq;
+use ndarray::{ArrayD, IxDyn, array};
+
+/// Deterministic, well-spread test values.
+fn sample(shape: &[usize], seed: f32) -> ArrayD<f32> {
+    let len: usize = shape.iter().product();
+    let data = (0..len)
+        .map(|k| ((k as f32) * 0.37 + seed).sin() * 2.0)
+        .collect();
+    ArrayD::from_shape_vec(IxDyn(shape), data).unwrap()
+}
+
+/// Values bounded away from zero, for divisors and `abs`.
+fn away_from_zero(shape: &[usize], seed: f32) -> ArrayD<f32> {
+    sample(shape, seed).mapv(|x| if x < 0.0 { x - 0.5 } else { x + 0.5 })
+}
+
+/// Reduces `out` to a scalar with distinct weights per element, so every output element
+/// contributes a different amount and mistakes in routing gradients are caught.
+fn weighted_loss<'t>(out: &Var<'t>) -> Var<'t> {
+    let weights = sample(out.shape(), 0.5).mapv(|w| w + 3.0);
+    out.mul(&out.tape().var(weights)).unwrap().sum()
+}
+
+/// Compares the gradients from `backward` with central finite differences of `f`.
+fn gradcheck(inputs: &[ArrayD<f32>], f: impl for<'t> Fn(&[Var<'t>]) -> Var<'t>) {
+    let loss_at = |inputs: &[ArrayD<f32>]| {
+        let tape = Tape::new();
+        let vars: Vec<Var> = inputs.iter().map(|x| tape.var(x.clone())).collect();
+        *weighted_loss(&f(&vars)).value().first().unwrap()
+    };
+
+    let tape = Tape::new();
+    let vars: Vec<Var> = inputs.iter().map(|x| tape.var(x.clone())).collect();
+    let grads = weighted_loss(&f(&vars)).backward();
+
+    let eps = 1e-2;
+    for (input, var) in vars.iter().enumerate() {
+        let analytic = grads.wrt(var);
+        assert_eq!(analytic.shape(), var.shape(), "input {input}");
+        for (position, &analytic) in analytic.iter().enumerate() {
+            let mut plus = inputs.to_vec();
+            let mut minus = inputs.to_vec();
+            plus[input].as_slice_mut().unwrap()[position] += eps;
+            minus[input].as_slice_mut().unwrap()[position] -= eps;
+            let numeric = (loss_at(&plus) - loss_at(&minus)) / (2.0 * eps);
+            assert!(
+                (analytic - numeric).abs() <= 1e-2 * numeric.abs().max(1.0),
+                "input {input} element {position}: analytic {analytic}, numeric {numeric}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_broadcasting_binary_ops_gradcheck() {
+    let inputs = [
+        sample(&[2, 3], 0.0),
+        sample(&[3], 1.0),
+        away_from_zero(&[2, 1], 2.0),
+    ];
+    gradcheck(&inputs, |v| v[0].add(&v[1]).unwrap());
+    gradcheck(&inputs, |v| v[1].sub(&v[0]).unwrap());
+    gradcheck(&inputs, |v| v[0].mul(&v[2]).unwrap());
+    gradcheck(&inputs, |v| v[1].div(&v[2]).unwrap());
+    gradcheck(&inputs, |v| {
+        let product = v[0].mul(&v[1]).unwrap();
+        product.div(&v[2]).unwrap().sub(&v[1]).unwrap()
+    });
+}
+
+#[test]
+fn test_binary_ops_mismatched_shapes() {
+    let tape = Tape::new();
+    let a = tape.var(ArrayD::zeros(IxDyn(&[2, 3])));
+    let b = tape.var(ArrayD::zeros(IxDyn(&[2])));
+    match a.add(&b) {
+        Err(AutogradError::Broadcast { left, right }) => {
+            assert_eq!(left, vec![2, 3]);
+            assert_eq!(right, vec![2]);
+        }
+        other => panic!("expected a broadcast error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_repeated_use_accumulates_gradient() {
+    let tape = Tape::new();
+    let x = tape.var(array![1.0f32, -2.0, 3.0].into_dyn());
+    let y = x.mul(&x).unwrap().add(&x).unwrap().sum();
+    let grads = y.backward();
+    assert_eq!(grads.wrt(&x), array![3.0f32, -3.0, 7.0].into_dyn());
+}
+
+#[test]
+fn test_unused_variable_has_no_gradient() {
+    let tape = Tape::new();
+    let x = tape.var(array![1.0f32, 2.0].into_dyn());
+    let unused = tape.var(array![5.0f32].into_dyn());
+    let grads = x.sum().backward();
+    assert!(grads.get(&unused).is_none());
+    assert_eq!(grads.wrt(&unused), array![0.0f32].into_dyn());
+    assert_eq!(grads.get(&x), Some(&array![1.0f32, 1.0].into_dyn()));
+}
+
+#[test]
+fn test_variables_from_different_tapes() {
+    let first = Tape::new();
+    let second = Tape::new();
+    let a = first.var(array![1.0f32].into_dyn());
+    let b = second.var(array![1.0f32].into_dyn());
+    assert!(matches!(a.mul(&b), Err(AutogradError::DifferentTapes)));
+    assert!(matches!(
+        einsum("i,i->", &[&a, &b]),
+        Err(AutogradError::DifferentTapes)
+    ));
+}
+
+#[test]
+fn test_abs_gradient() {
+    gradcheck(&[away_from_zero(&[3, 4], 0.3)], |v| v[0].abs());
+
+    // Like PyTorch, the gradient at zero is zero
+    let tape = Tape::new();
+    let x = tape.var(array![-2.0f32, 0.0, 3.0].into_dyn());
+    let grads = x.abs().sum().backward();
+    assert_eq!(grads.wrt(&x), array![-1.0f32, 0.0, 1.0].into_dyn());
+}
+
+#[test]
+fn test_gather_scatter_adds_gradient() {
+    let tape = Tape::new();
+    let x = tape.var(sample(&[2, 3], 0.0));
+    let index = array![[0i64, 0, 2], [1, 1, 1]].into_dyn();
+    let grads = x.gather(-1, &index).unwrap().sum().backward();
+    assert_eq!(
+        grads.wrt(&x),
+        array![[2.0f32, 0.0, 1.0], [0.0, 3.0, 0.0]].into_dyn()
+    );
+
+    let index = array![[2i64, 0], [1, 1], [0, 2]].into_dyn();
+    gradcheck(&[sample(&[3, 4], 1.0)], |v| v[0].gather(0, &index).unwrap());
+}
+
+#[test]
+fn test_gather_invalid_index() {
+    let tape = Tape::new();
+    let x = tape.var(sample(&[2, 3], 0.0));
+    let index = array![[3i64], [0]].into_dyn();
+    assert!(matches!(x.gather(1, &index), Err(AutogradError::Gather(_))));
+}
+
+#[test]
+fn test_scatter_gradient() {
+    let tape = Tape::new();
+    let target = tape.var(ArrayD::ones(IxDyn(&[2, 3])));
+    let src = tape.var(array![[5.0f32], [6.0]].into_dyn());
+    let index = array![[2i64], [0]].into_dyn();
+    let out = target.scatter(1, &index, &src).unwrap();
+    assert_eq!(
+        out.value(),
+        &array![[1.0f32, 1.0, 5.0], [6.0, 1.0, 1.0]].into_dyn()
+    );
+    let grads = out.sum().backward();
+    // Overwritten positions no longer depend on the target
+    assert_eq!(
+        grads.wrt(&target),
+        array![[1.0f32, 1.0, 0.0], [0.0, 1.0, 1.0]].into_dyn()
+    );
+    assert_eq!(grads.wrt(&src), array![[1.0f32], [1.0]].into_dyn());
+
+    let index = array![[3i64, 0], [1, 2], [0, 1]].into_dyn();
+    gradcheck(&[sample(&[3, 4], 0.0), sample(&[3, 2], 1.0)], |v| {
+        v[0].scatter(-1, &index, &v[1]).unwrap()
+    });
+}
+
+#[test]
+fn test_einsum_gradcheck() {
+    let cases: [(&str, Vec<&[usize]>); 9] = [
+        ("ij,jk->ik", vec![&[2, 3], &[3, 4]]),
+        ("bij,bjk->bik", vec![&[2, 2, 3], &[2, 3, 2]]),
+        ("ij,jk", vec![&[2, 3], &[3, 2]]),
+        ("ij->j", vec![&[3, 4]]),
+        ("ij->", vec![&[2, 3]]),
+        ("ij->ji", vec![&[2, 3]]),
+        ("i,j->ij", vec![&[3], &[2]]),
+        ("ij,jk,kl->il", vec![&[2, 3], &[3, 2], &[2, 2]]),
+        ("bfmd,bfd->bfm", vec![&[1, 2, 3, 2], &[1, 2, 2]]),
+    ];
+    for (equation, shapes) in cases {
+        let inputs: Vec<ArrayD<f32>> = shapes
+            .iter()
+            .enumerate()
+            .map(|(i, shape)| sample(shape, i as f32))
+            .collect();
+        gradcheck(&inputs, |v| {
+            let operands: Vec<&Var> = v.iter().collect();
+            einsum(equation, &operands).unwrap()
+        });
+    }
+}
+
+#[test]
+fn test_einsum_broadcast_labels_gradcheck() {
+    // Labels may have size 1 in one operand, which is broadcast like NumPy
+    let inputs = [sample(&[1, 3], 0.0), sample(&[2, 3], 1.0)];
+    gradcheck(&inputs, |v| einsum("ij,ij->ij", &[&v[0], &v[1]]).unwrap());
+}
+
+#[test]
+fn test_einsum_unsupported_equations() {
+    let tape = Tape::new();
+    let square = tape.var(sample(&[3, 3], 0.0));
+    assert!(matches!(
+        einsum("ii->i", &[&square]),
+        Err(AutogradError::UnsupportedEquation { .. })
+    ));
+    assert!(matches!(
+        einsum("...j->...j", &[&square]),
+        Err(AutogradError::UnsupportedEquation { .. })
+    ));
+    assert!(matches!(
+        einsum("ij,jk->ik", &[&square]),
+        Err(AutogradError::Einsum(_))
+    ));
+}
+
+#[test]
+fn test_shape_ops_gradcheck() {
+    let inputs = [sample(&[2, 3, 2], 0.0)];
+    gradcheck(&inputs, |v| v[0].reshape(&[-1, 4]).unwrap());
+    gradcheck(&inputs, |v| v[0].transpose(0, 2).unwrap());
+    gradcheck(&inputs, |v| v[0].expand(1, 4).unwrap());
+    gradcheck(&inputs, |v| {
+        let moved = v[0].transpose(1, 2).unwrap();
+        moved.reshape(&[4, 3]).unwrap().expand(0, 2).unwrap()
+    });
+}
+
+#[test]
+fn test_expand_sums_gradient() {
+    let tape = Tape::new();
+    let x = tape.var(array![1.0f32, 2.0].into_dyn());
+    let grads = x.expand(0, 3).unwrap().sum().backward();
+    assert_eq!(grads.wrt(&x), array![3.0f32, 3.0].into_dyn());
+}
+
+#[test]
+fn test_transpose_invalid_dimension() {
+    let tape = Tape::new();
+    let x = tape.var(sample(&[2, 3], 0.0));
+    assert!(matches!(
+        x.transpose(0, 2),
+        Err(AutogradError::InvalidDimension { dim: 2, ndim: 2 })
+    ));
+}
+
+#[test]
+fn test_slicing_pads_gradient() {
+    let tape = Tape::new();
+    let x = tape.var(sample(&[2, 3, 2], 0.0));
+    let last = x.slice_last_dim().unwrap();
+    let grads = last.sum().backward();
+    let expected = ArrayD::from_shape_fn(IxDyn(&[2, 3, 2]), |i| i[2] as f32);
+    assert_eq!(grads.wrt(&x), expected);
+
+    let first = x.slice_second_dim(2).unwrap();
+    let grads = first.sum().backward();
+    let expected = ArrayD::from_shape_fn(IxDyn(&[2, 3, 2]), |i| (i[1] < 2) as u8 as f32);
+    assert_eq!(grads.wrt(&x), expected);
+
+    let inputs = [sample(&[2, 3, 2], 1.0)];
+    gradcheck(&inputs, |v| v[0].slice_last_dim().unwrap());
+    gradcheck(&inputs, |v| v[0].slice_second_dim(1).unwrap());
+    assert!(matches!(
+        tape.var(sample(&[2, 3], 0.0)).slice_last_dim(),
+        Err(AutogradError::Slice(_))
+    ));
+}
+
+#[test]
+fn test_max_routes_gradient_to_index() {
+    let tape = Tape::new();
+    let x = tape.var(array![[1.0f32, 5.0, 2.0], [7.0, 0.0, 7.0]].into_dyn());
+    let (values, indices) = x.max(1).unwrap();
+    assert_eq!(values.value(), &array![5.0f32, 7.0].into_dyn());
+    assert_eq!(indices, array![1i64, 0].into_dyn());
+
+    let grads = weighted_loss(&values).backward();
+    let weights = sample(&[2], 0.5).mapv(|w| w + 3.0);
+    assert_abs_diff_eq!(
+        grads.wrt(&x),
+        array![[0.0f32, weights[0], 0.0], [weights[1], 0.0, 0.0]].into_dyn(),
+        epsilon = 1e-6
+    );
+
+    // Distinct values, so small perturbations never change which element is the maximum
+    let distinct = ArrayD::from_shape_fn(IxDyn(&[3, 4]), |i| ((i[0] * 5 + i[1] * 7) % 12) as f32);
+    gradcheck(std::slice::from_ref(&distinct), |v| v[0].max(0).unwrap().0);
+    gradcheck(&[distinct], |v| v[0].max(1).unwrap().0);
+}
+
+#[test]
+fn test_argmax_is_not_recorded() {
+    let tape = Tape::new();
+    let x = tape.var(array![[1.0f32, 5.0], [7.0, 0.0]].into_dyn());
+    let recorded = tape.len();
+    assert_eq!(
+        x.argmax(Some(1), true).unwrap(),
+        array![[1i64], [0]].into_dyn()
+    );
+    assert_eq!(
+        x.argmax(None, false).unwrap(),
+        ndarray::arr0(2i64).into_dyn()
+    );
+    assert_eq!(tape.len(), recorded);
+    assert!(matches!(
+        x.argmax(Some(2), false),
+        Err(AutogradError::Argmax(_))
+    ));
+}
@@ -0,0 +1,135 @@
+use RustOps::io::bundle::{Bundle, BundleError, Compression, load_npy, read_npz, save_npy};
+use RustOps::io::{Dtype, Tensor};
+use half::{bf16, f16};
+use ndarray::{ArrayD, IxDyn, array};
+use std::io::Cursor;
+
+fn sample_bundle() -> Bundle {
+    let mut bundle = Bundle::new();
+    bundle.insert("x", array![[1.0f32, 2.0], [3.0, 4.0]].into_dyn());
+    bundle.insert("indices", array![[0i64], [1]].into_dyn());
+    bundle.insert("mask", array![true, false, true].into_dyn());
+    bundle.insert("scale", ArrayD::from_elem(IxDyn(&[]), 0.25f64));
+    // Non-standard layout is written in logical order
+    bundle.insert(
+        "xt",
+        array![[1.0f32, 2.0], [3.0, 4.0]].reversed_axes().into_dyn(),
+    );
+    bundle
+}
+
+#[test]
+fn test_bundle_round_trip_in_memory() {
+    let bundle = sample_bundle();
+    for compression in [Compression::Stored, Compression::Deflated] {
+        let bytes = bundle
+            .write(Cursor::new(Vec::new()), compression)
+            .unwrap()
+            .into_inner();
+        let loaded = Bundle::read(Cursor::new(bytes)).unwrap();
+        assert_eq!(loaded, bundle);
+        assert_eq!(loaded.keys(), vec!["indices", "mask", "scale", "x", "xt"]);
+        assert_eq!(loaded.tensor("mask").unwrap().dtype(), Dtype::Bool);
+        assert_eq!(
+            loaded.get::<f32>("xt").unwrap(),
+            array![[1.0f32, 3.0], [2.0, 4.0]].into_dyn()
+        );
+    }
+}
+
+#[test]
+fn test_bundle_file_and_errors() {
+    let bundle = sample_bundle();
+    let path = std::env::temp_dir().join(format!("rustops_bundle_{}.npz", std::process::id()));
+    bundle.save(&path, Compression::Deflated).unwrap();
+    let tensors = read_npz(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(tensors.len(), 5);
+    assert_eq!(
+        tensors["indices"],
+        Tensor::I64(array![[0i64], [1]].into_dyn())
+    );
+
+    let loaded = Bundle::from(tensors);
+    assert!(matches!(
+        loaded.get::<f64>("x"),
+        Err(BundleError::DtypeMismatch {
+            expected: Dtype::F64,
+            found: Dtype::F32,
+            ..
+        })
+    ));
+    assert!(matches!(
+        loaded.get::<f32>("missing"),
+        Err(BundleError::MissingKey { .. })
+    ));
+
+    let mut brain = Bundle::new();
+    brain.insert("h", array![bf16::ONE].into_dyn());
+    assert!(matches!(
+        brain.write(Cursor::new(Vec::new()), Compression::Stored),
+        Err(BundleError::UnsupportedWrite { dtype: Dtype::BF16 })
+    ));
+    assert!(matches!(
+        Bundle::read(Cursor::new(b"not a zip".to_vec())),
+        Err(BundleError::Zip(_))
+    ));
+}
+
+/// A `.npy` file as `numpy.save` writes it: a version 1.0 header padded to 64 bytes.
+fn numpy_file(descr: &str, shape: &str, data: &[u8]) -> Vec<u8> {
+    let mut header = format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': {shape}, }}");
+    while (10 + header.len() + 1) % 64 != 0 {
+        header.push(' ');
+    }
+    header.push('\n');
+    let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+    bytes.extend((header.len() as u16).to_le_bytes());
+    bytes.extend(header.as_bytes());
+    bytes.extend(data);
+    bytes
+}
+
+#[test]
+fn test_npy_half_precision() {
+    let values = array![[1.0f32, -2.5, 65504.0], [0.1, 1e-7, f32::INFINITY]];
+    let half = values.mapv(f16::from_f32).into_dyn();
+
+    // Written by numpy, in either byte order
+    let dir = std::env::temp_dir();
+    let little: Vec<u8> = half.iter().flat_map(|v| v.to_le_bytes()).collect();
+    let big: Vec<u8> = half.iter().flat_map(|v| v.to_be_bytes()).collect();
+    for (descr, data) in [("<f2", little), (">f2", big)] {
+        let path = dir.join(format!("rustops_half_{}.npy", std::process::id()));
+        std::fs::write(&path, numpy_file(descr, "(2, 3)", &data)).unwrap();
+        let loaded = load_npy(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, Tensor::F16(half.clone()), "{descr}");
+    }
+
+    // Round trips through save_npy and through bundles, in any layout
+    let path = dir.join(format!("rustops_half_saved_{}.npy", std::process::id()));
+    let transposed = Tensor::F16(half.clone().reversed_axes());
+    save_npy(&path, &transposed).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    assert!(String::from_utf8_lossy(&bytes[..64]).contains("'descr': '<f2'"));
+    assert_eq!(load_npy(&path).unwrap(), transposed);
+    std::fs::remove_file(&path).unwrap();
+
+    let mut bundle = Bundle::new();
+    bundle.insert("h", half.clone());
+    bundle.insert("x", values.into_dyn());
+    let bytes = bundle
+        .write(Cursor::new(Vec::new()), Compression::Deflated)
+        .unwrap()
+        .into_inner();
+    let loaded = Bundle::read(Cursor::new(bytes)).unwrap();
+    assert_eq!(loaded, bundle);
+    assert_eq!(loaded.get::<f16>("h").unwrap(), half);
+
+    assert!(matches!(
+        save_npy(&path, &Tensor::BF16(array![bf16::ONE].into_dyn())),
+        Err(BundleError::UnsupportedWrite { dtype: Dtype::BF16 })
+    ));
+    assert!(!path.exists());
+}
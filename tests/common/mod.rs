@@ -0,0 +1,11 @@
+//! Helpers shared by the integration tests.
+
+use RustOps::rng::{Generator, RandomFloat, uniform_};
+use ndarray::{ArrayD, IxDyn};
+
+/// Values drawn uniformly from `[-1, 1)` by a generator seeded with `seed`.
+pub fn uniform<A: RandomFloat>(shape: &[usize], seed: u64) -> ArrayD<A> {
+    let mut x = ArrayD::zeros(IxDyn(shape));
+    uniform_(&mut x, -A::one(), A::one(), &mut Generator::new(seed));
+    x
+}
@@ -0,0 +1,468 @@
+//! Data-driven conformance harness.
+//!
+//! `reference/*.py` record every case they generate in `data/manifest.json` (see
+//! `reference/util/manifest.py`): the op name, its non-tensor arguments, the fixture files of its
+//! inputs and expected outputs, and the tolerance to compare with. [`run_manifest`] dispatches
+//! each case to the runner registered for its op in [`registry`] and reports per-output error.
+//! Cases whose fixtures have not been generated are skipped rather than failed, so the suite
+//! can run on checkouts without PyTorch.
+//!
+//! npy has no bfloat16 dtype, so bfloat16 cases store their tensors as float32, which holds
+//! every bfloat16 value exactly, and set the case's `dtype` to `"bfloat16"`; the harness casts
+//! their float32 fixtures back before running the case.
+
+mod registry;
+
+pub use registry::registry;
+
+use RustOps::io::bundle::{Bundle, load_npy};
+use RustOps::io::{Dtype, Tensor};
+use half::bf16;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Name of the manifest inside the fixture directory.
+pub const MANIFEST: &str = "manifest.json";
+
+/// Marks a fixture stored as an array inside an npz bundle: `"<bundle>.npz/<key>"`.
+const BUNDLE_SEPARATOR: &str = ".npz/";
+
+/// Runs one case: receives the case (for its arguments) and its loaded inputs, and returns the
+/// computed outputs keyed by the same roles as the case's expected outputs.
+pub type Runner = fn(&Case, &Inputs) -> Result<HashMap<String, Tensor>, String>;
+
+/// One entry of the manifest.
+#[derive(Debug, Clone)]
+pub struct Case {
+    pub name: String,
+    pub op: String,
+    pub args: Map<String, Value>,
+    pub inputs: BTreeMap<String, String>,
+    pub outputs: BTreeMap<String, String>,
+    pub atol: f64,
+    pub rtol: f64,
+    /// Dtype the float32 fixtures are cast to, for dtypes npy cannot store.
+    pub dtype: Option<Dtype>,
+}
+
+impl Case {
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let object = value.as_object().ok_or("case is not an object")?;
+        let string = |key: &str| {
+            object
+                .get(key)
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| format!("case is missing string field {key:?}"))
+        };
+        let name = string("name")?;
+        let files = |key: &str| -> Result<BTreeMap<String, String>, String> {
+            object
+                .get(key)
+                .and_then(Value::as_object)
+                .ok_or_else(|| format!("case {name} is missing object field {key:?}"))?
+                .iter()
+                .map(|(role, file)| match file.as_str() {
+                    Some(file) => Ok((role.clone(), file.to_string())),
+                    None => Err(format!("case {name}: fixture {role} is not a file name")),
+                })
+                .collect()
+        };
+        let tolerance = |key: &str| object.get(key).and_then(Value::as_f64).unwrap_or(0.0);
+        let dtype = match object.get("dtype").map(|dtype| (dtype, dtype.as_str())) {
+            None | Some((_, Some("float32"))) => None,
+            Some((_, Some("bfloat16"))) => Some(Dtype::BF16),
+            Some((dtype, _)) => return Err(format!("case {name}: unsupported dtype {dtype}")),
+        };
+        Ok(Self {
+            op: string("op")?,
+            args: object
+                .get("args")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default(),
+            inputs: files("inputs")?,
+            outputs: files("outputs")?,
+            atol: tolerance("atol"),
+            rtol: tolerance("rtol"),
+            dtype,
+            name,
+        })
+    }
+
+    fn arg(&self, key: &str) -> Result<&Value, String> {
+        self.args
+            .get(key)
+            .ok_or_else(|| format!("missing argument {key:?}"))
+    }
+
+    pub fn arg_i64(&self, key: &str) -> Result<i64, String> {
+        self.arg(key)?
+            .as_i64()
+            .ok_or_else(|| format!("argument {key:?} is not an integer"))
+    }
+
+    pub fn arg_usize(&self, key: &str) -> Result<usize, String> {
+        self.arg(key)?
+            .as_u64()
+            .map(|v| v as usize)
+            .ok_or_else(|| format!("argument {key:?} is not a non-negative integer"))
+    }
+
+    /// A non-negative integer argument that may be `null` (Python `None`).
+    pub fn arg_opt_usize(&self, key: &str) -> Result<Option<usize>, String> {
+        match self.arg(key)? {
+            Value::Null => Ok(None),
+            _ => self.arg_usize(key).map(Some),
+        }
+    }
+
+    /// An integer argument that may be `null` (Python `None`).
+    pub fn arg_opt_i64(&self, key: &str) -> Result<Option<i64>, String> {
+        match self.arg(key)? {
+            Value::Null => Ok(None),
+            _ => self.arg_i64(key).map(Some),
+        }
+    }
+
+    pub fn arg_f64(&self, key: &str) -> Result<f64, String> {
+        self.arg(key)?
+            .as_f64()
+            .ok_or_else(|| format!("argument {key:?} is not a number"))
+    }
+
+    /// A number argument that may be `null` (Python `None`).
+    pub fn arg_opt_f64(&self, key: &str) -> Result<Option<f64>, String> {
+        match self.arg(key)? {
+            Value::Null => Ok(None),
+            _ => self.arg_f64(key).map(Some),
+        }
+    }
+
+    pub fn arg_bool(&self, key: &str) -> Result<bool, String> {
+        self.arg(key)?
+            .as_bool()
+            .ok_or_else(|| format!("argument {key:?} is not a bool"))
+    }
+
+    pub fn arg_str(&self, key: &str) -> Result<&str, String> {
+        self.arg(key)?
+            .as_str()
+            .ok_or_else(|| format!("argument {key:?} is not a string"))
+    }
+
+    pub fn arg_i64s(&self, key: &str) -> Result<Vec<i64>, String> {
+        self.arg(key)?
+            .as_array()
+            .and_then(|values| values.iter().map(Value::as_i64).collect())
+            .ok_or_else(|| format!("argument {key:?} is not a list of integers"))
+    }
+
+    pub fn arg_usizes(&self, key: &str) -> Result<Vec<usize>, String> {
+        self.arg(key)?
+            .as_array()
+            .and_then(|values| {
+                values
+                    .iter()
+                    .map(|v| v.as_u64().map(|v| v as usize))
+                    .collect()
+            })
+            .ok_or_else(|| format!("argument {key:?} is not a list of non-negative integers"))
+    }
+
+    /// A list of two non-negative integers, the sizes of 2D ops.
+    pub fn arg_pair(&self, key: &str) -> Result<[usize; 2], String> {
+        self.arg_usizes(key)?
+            .try_into()
+            .map_err(|_| format!("argument {key:?} is not a pair"))
+    }
+
+    /// A pair argument that may be `null` (Python `None`).
+    pub fn arg_opt_pair(&self, key: &str) -> Result<Option<[usize; 2]>, String> {
+        match self.arg(key)? {
+            Value::Null => Ok(None),
+            _ => self.arg_pair(key).map(Some),
+        }
+    }
+}
+
+/// The loaded input tensors of a case.
+pub struct Inputs(HashMap<String, Tensor>);
+
+impl Inputs {
+    /// Borrows the input `role`, whatever its dtype.
+    pub fn tensor(&self, role: &str) -> Result<&Tensor, String> {
+        self.0
+            .get(role)
+            .ok_or_else(|| format!("missing input {role:?}"))
+    }
+
+    /// Borrows the input `role` as an array of element type `A`.
+    pub fn get<A: RustOps::io::Element>(&self, role: &str) -> Result<&ndarray::ArrayD<A>, String> {
+        let tensor = self.tensor(role)?;
+        tensor.as_array::<A>().ok_or_else(|| {
+            format!(
+                "input {role:?} has dtype {}, expected {}",
+                tensor.dtype(),
+                A::DTYPE
+            )
+        })
+    }
+
+    /// Like [`Inputs::get`], for inputs that only some cases of an op have.
+    pub fn get_opt<A: RustOps::io::Element>(
+        &self,
+        role: &str,
+    ) -> Result<Option<&ndarray::ArrayD<A>>, String> {
+        match self.0.contains_key(role) {
+            true => self.get(role).map(Some),
+            false => Ok(None),
+        }
+    }
+}
+
+/// Error statistics of one output compared against its expected value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputReport {
+    pub role: String,
+    pub max_abs: f64,
+    pub max_rel: f64,
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// Every output is within tolerance.
+    Passed(Vec<OutputReport>),
+    /// Some output is out of tolerance or has the wrong dtype or shape.
+    Failed(String),
+    /// The case could not run, e.g. because its fixtures have not been generated.
+    Skipped(String),
+}
+
+/// Outcomes of all cases of a manifest, in manifest order.
+#[derive(Debug, Default)]
+pub struct Summary {
+    pub outcomes: Vec<(String, Outcome)>,
+}
+
+impl Summary {
+    pub fn failures(&self) -> Vec<&(String, Outcome)> {
+        self.outcomes
+            .iter()
+            .filter(|(_, outcome)| matches!(outcome, Outcome::Failed(_)))
+            .collect()
+    }
+
+    pub fn count(&self, f: fn(&Outcome) -> bool) -> usize {
+        self.outcomes.iter().filter(|(_, o)| f(o)).count()
+    }
+}
+
+/// Reads the manifest in `dir`, or `None` if no reference script has written one.
+pub fn load_manifest(dir: &Path) -> Result<Option<Vec<Case>>, String> {
+    let path = dir.join(MANIFEST);
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("{}: {e}", path.display())),
+    };
+    let json: Value =
+        serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+    json.get("cases")
+        .and_then(Value::as_array)
+        .ok_or_else(|| format!("{}: missing \"cases\" list", path.display()))?
+        .iter()
+        .map(Case::from_json)
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+/// Runs every case of the manifest in `dir`, printing one line per case and output.
+pub fn run_manifest(dir: &Path, registry: &HashMap<&'static str, Runner>) -> Summary {
+    let mut summary = Summary::default();
+    let cases = match load_manifest(dir) {
+        Ok(Some(cases)) => cases,
+        Ok(None) => {
+            println!(
+                "SKIP all: no {} in {}; run the scripts in reference/ to generate fixtures",
+                MANIFEST,
+                dir.display()
+            );
+            return summary;
+        }
+        Err(e) => {
+            summary
+                .outcomes
+                .push((MANIFEST.to_string(), Outcome::Failed(e)));
+            return summary;
+        }
+    };
+    for case in &cases {
+        let outcome = run_case(dir, case, registry);
+        match &outcome {
+            Outcome::Passed(reports) => {
+                for report in reports {
+                    println!(
+                        "PASS {} [{}] {}: max_abs={:.3e} max_rel={:.3e}",
+                        case.name, case.op, report.role, report.max_abs, report.max_rel
+                    );
+                }
+            }
+            Outcome::Failed(reason) => println!("FAIL {} [{}]: {reason}", case.name, case.op),
+            Outcome::Skipped(reason) => println!("SKIP {} [{}]: {reason}", case.name, case.op),
+        }
+        summary.outcomes.push((case.name.clone(), outcome));
+    }
+    summary
+}
+
+/// Loads the fixtures of `case`, runs it and compares every output.
+pub fn run_case(dir: &Path, case: &Case, registry: &HashMap<&'static str, Runner>) -> Outcome {
+    let Some(runner) = registry.get(case.op.as_str()) else {
+        return Outcome::Failed(format!("no runner registered for op {:?}", case.op));
+    };
+    let missing: Vec<String> = case
+        .inputs
+        .values()
+        .chain(case.outputs.values())
+        .map(|file| fixture_path(dir, file))
+        .filter(|path| !path.exists())
+        .map(|path| path.display().to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Outcome::Skipped(format!("missing fixture {}", missing.join(", ")));
+    }
+
+    let mut bundles = HashMap::new();
+    let mut load = |files: &BTreeMap<String, String>| -> Result<HashMap<String, Tensor>, String> {
+        files
+            .iter()
+            .map(|(role, file)| Ok((role.clone(), load_fixture(dir, file, &mut bundles)?)))
+            .collect()
+    };
+    let (inputs, expected) = match (load(&case.inputs), load(&case.outputs)) {
+        (Ok(inputs), Ok(expected)) => (cast(inputs, case.dtype), cast(expected, case.dtype)),
+        (Err(e), _) | (_, Err(e)) => return Outcome::Failed(e),
+    };
+
+    let mut outputs = match runner(case, &Inputs(inputs)) {
+        Ok(outputs) => outputs,
+        Err(e) => return Outcome::Failed(e),
+    };
+    let mut reports = Vec::with_capacity(expected.len());
+    for (role, expected) in &expected {
+        let Some(actual) = outputs.remove(role) else {
+            return Outcome::Failed(format!("runner produced no output {role:?}"));
+        };
+        match compare(role, &actual, expected, case.atol, case.rtol) {
+            Ok(report) if report.passed => reports.push(report),
+            Ok(report) => {
+                return Outcome::Failed(format!(
+                    "{role}: max_abs={:.3e} max_rel={:.3e} exceeds atol={:e} rtol={:e}",
+                    report.max_abs, report.max_rel, case.atol, case.rtol
+                ));
+            }
+            Err(e) => return Outcome::Failed(e),
+        }
+    }
+    Outcome::Passed(reports)
+}
+
+/// Casts the float32 fixtures of a case to the dtype they stand in for.
+fn cast(tensors: HashMap<String, Tensor>, dtype: Option<Dtype>) -> HashMap<String, Tensor> {
+    if dtype != Some(Dtype::BF16) {
+        return tensors;
+    }
+    tensors
+        .into_iter()
+        .map(|(role, tensor)| match tensor {
+            Tensor::F32(a) => (role, Tensor::BF16(a.mapv(bf16::from_f32))),
+            other => (role, other),
+        })
+        .collect()
+}
+
+fn fixture_path(dir: &Path, file: &str) -> PathBuf {
+    match file.split_once(BUNDLE_SEPARATOR) {
+        Some((bundle, _)) => dir.join(format!("{bundle}.npz")),
+        None => dir.join(file),
+    }
+}
+
+fn load_fixture(
+    dir: &Path,
+    file: &str,
+    bundles: &mut HashMap<PathBuf, Bundle>,
+) -> Result<Tensor, String> {
+    let path = fixture_path(dir, file);
+    let Some((_, key)) = file.split_once(BUNDLE_SEPARATOR) else {
+        return load_npy(&path).map_err(|e| format!("{file}: {e}"));
+    };
+    if !bundles.contains_key(&path) {
+        let bundle = Bundle::open(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        bundles.insert(path.clone(), bundle);
+    }
+    bundles[&path]
+        .tensor(key)
+        .cloned()
+        .map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Compares like `torch.allclose(actual, expected, rtol, atol, equal_nan=True)`, with exact
+/// dtype and shape matching.
+fn compare(
+    role: &str,
+    actual: &Tensor,
+    expected: &Tensor,
+    atol: f64,
+    rtol: f64,
+) -> Result<OutputReport, String> {
+    if actual.dtype() != expected.dtype() {
+        return Err(format!(
+            "{role}: dtype {} does not match expected {}",
+            actual.dtype(),
+            expected.dtype()
+        ));
+    }
+    if actual.shape() != expected.shape() {
+        return Err(format!(
+            "{role}: shape {:?} does not match expected {:?}",
+            actual.shape(),
+            expected.shape()
+        ));
+    }
+    let mut report = OutputReport {
+        role: role.to_string(),
+        max_abs: 0.0,
+        max_rel: 0.0,
+        passed: true,
+    };
+    for (a, e) in to_f64(actual).into_iter().zip(to_f64(expected)) {
+        if a.is_nan() && e.is_nan() || a == e {
+            continue;
+        }
+        let abs = (a - e).abs();
+        report.max_abs = report.max_abs.max(abs);
+        if e != 0.0 {
+            report.max_rel = report.max_rel.max(abs / e.abs());
+        }
+        // A lone NaN fails the case
+        if abs.is_nan() || abs > atol + rtol * e.abs() {
+            report.passed = false;
+        }
+    }
+    Ok(report)
+}
+
+fn to_f64(tensor: &Tensor) -> Vec<f64> {
+    match tensor {
+        Tensor::F64(a) => a.iter().copied().collect(),
+        Tensor::F32(a) => a.iter().map(|&v| v as f64).collect(),
+        Tensor::F16(a) => a.iter().map(|v| v.to_f64()).collect(),
+        Tensor::BF16(a) => a.iter().map(|v| v.to_f64()).collect(),
+        Tensor::I64(a) => a.iter().map(|&v| v as f64).collect(),
+        Tensor::Bool(a) => a.iter().map(|&v| v as u8 as f64).collect(),
+    }
+}
@@ -0,0 +1,332 @@
+//! Maps manifest op names to the functions under test.
+//!
+//! The runners of `RustOps::functions` live here; those of the other modules live in the
+//! submodule named after the module.
+
+use super::{Case, Inputs, Runner};
+use RustOps::functions::pad::PadMode;
+use RustOps::functions::{
+    abs, argmax, einsum, expand, flip, gather, index_select, matmul, max, ones, pad, rearrange,
+    reduce, repeat_interleave, reshape, roll, scatter, slicing, sort, tile, transpose, tril,
+};
+use RustOps::io::Tensor;
+use ndarray::IxDyn;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// Runs `$body` with `$x` bound to the float input `$role`, whichever of the float dtypes the
+/// reference cases are generated in it has.
+macro_rules! with_float {
+    ($inputs:expr, $role:literal, |$x:ident| $body:expr) => {
+        match $inputs.tensor($role)? {
+            Tensor::F32($x) => $body,
+            Tensor::F16($x) => $body,
+            Tensor::BF16($x) => $body,
+            other => Err(format!(
+                "input {:?} has unsupported dtype {}",
+                $role,
+                other.dtype()
+            )),
+        }
+    };
+}
+
+mod linalg;
+mod nn;
+mod quantization;
+mod rng;
+
+/// Every op the harness can run, keyed by the `op` field of manifest cases.
+pub fn registry() -> HashMap<&'static str, Runner> {
+    let mut registry = HashMap::from([
+        ("abs", run_abs as Runner),
+        ("argmax", run_argmax),
+        ("baddbmm", run_baddbmm),
+        ("einsum", run_einsum),
+        ("einsum_named", run_einsum_named),
+        ("expand", run_expand),
+        ("flip", run_flip),
+        ("gather", run_gather),
+        ("index_select", run_index_select),
+        ("matmul", run_matmul),
+        ("max", run_max),
+        ("ones", run_ones),
+        ("pad", run_pad),
+        ("rearrange_batch_mems_flag", run_rearrange_batch_mems_flag),
+        ("reduce", run_reduce),
+        ("repeat_interleave", run_repeat_interleave),
+        ("reshape", run_reshape),
+        ("roll", run_roll),
+        ("scatter", run_scatter),
+        ("slice_last_dim", run_slice_last_dim),
+        ("slice_second_dim", run_slice_second_dim),
+        ("sort_last_dim", run_sort_last_dim),
+        ("tile", run_tile),
+        ("transpose", run_transpose),
+        ("tril", run_tril),
+        ("triu", run_triu),
+    ]);
+    registry.extend(linalg::runners());
+    registry.extend(nn::runners());
+    registry.extend(quantization::runners());
+    registry.extend(rng::runners());
+    registry
+}
+
+type Outputs = Result<HashMap<String, Tensor>, String>;
+
+/// Not every op error implements `Display`, so failures are reported with `Debug`.
+fn describe<E: Debug>(error: E) -> String {
+    format!("{error:?}")
+}
+
+fn single<T: Into<Tensor>>(role: &str, output: T) -> Outputs {
+    Ok(HashMap::from([(role.to_string(), output.into())]))
+}
+
+/// Manifest lists are integers, the functions under test take dimensions and shifts as `isize`.
+fn isizes(values: Vec<i64>) -> Vec<isize> {
+    values.into_iter().map(|v| v as isize).collect()
+}
+
+fn run_abs(_case: &Case, inputs: &Inputs) -> Outputs {
+    single("y", abs::abs_ndarray(inputs.get::<f32>("x")?))
+}
+
+fn run_argmax(case: &Case, inputs: &Inputs) -> Outputs {
+    let (dim, keepdim) = (case.arg_opt_usize("dim")?, case.arg_bool("keepdim")?);
+    with_float!(inputs, "x", |x| single(
+        "y",
+        argmax::argmax(x, dim, keepdim).map_err(describe)?
+    ))
+}
+
+fn run_baddbmm(case: &Case, inputs: &Inputs) -> Outputs {
+    let (beta, alpha) = (case.arg_f64("beta")? as f32, case.arg_f64("alpha")? as f32);
+    let (batch1, batch2) = (inputs.get::<f32>("batch1")?, inputs.get("batch2")?);
+    let y = matmul::baddbmm(inputs.get("input")?, batch1, batch2, beta, alpha);
+    single("y", y.map_err(describe)?)
+}
+
+/// Half-precision operands are contracted in f32, like PyTorch does on the CPU.
+fn run_einsum(case: &Case, inputs: &Inputs) -> Outputs {
+    let equation = case.arg_str("equation")?;
+    match inputs.tensor("x")? {
+        Tensor::F16(x) => {
+            let z = einsum::einsum_upcast(equation, &[x, inputs.get("y")?]).map_err(describe)?;
+            single("z", z)
+        }
+        Tensor::BF16(x) => {
+            let z = einsum::einsum_upcast(equation, &[x, inputs.get("y")?]).map_err(describe)?;
+            single("z", z)
+        }
+        _ => {
+            let tensors = [inputs.get::<f32>("x")?, inputs.get::<f32>("y")?];
+            let z = einsum::einsum_ndarray_dyn(equation, &tensors).map_err(describe)?;
+            single("z", z)
+        }
+    }
+}
+
+fn run_einsum_named(case: &Case, inputs: &Inputs) -> Outputs {
+    let pattern = case.arg_str("pattern")?;
+    let tensors = [inputs.get::<f32>("x")?, inputs.get::<f32>("y")?];
+    single(
+        "z",
+        einsum::einsum_named(pattern, &tensors).map_err(describe)?,
+    )
+}
+
+fn run_expand(case: &Case, inputs: &Inputs) -> Outputs {
+    let (dim, size) = (case.arg_usize("dim")?, case.arg_usize("size")?);
+    with_float!(inputs, "x", |x| single(
+        "y",
+        expand::expand_at_dim(x, dim, size)?
+    ))
+}
+
+fn run_flip(case: &Case, inputs: &Inputs) -> Outputs {
+    let dims = isizes(case.arg_i64s("dims")?);
+    single(
+        "y",
+        flip::flip(inputs.get::<f32>("x")?, &dims).map_err(describe)?,
+    )
+}
+
+fn run_gather(case: &Case, inputs: &Inputs) -> Outputs {
+    let dim = case.arg_i64("dim")? as isize;
+    let indices = inputs.get::<i64>("indices")?;
+    with_float!(inputs, "x", |x| single(
+        "y",
+        gather::gather(x, dim, indices).map_err(describe)?
+    ))
+}
+
+fn run_index_select(case: &Case, inputs: &Inputs) -> Outputs {
+    let dim = case.arg_i64("dim")? as isize;
+    let index = inputs.get::<i64>("index")?;
+    let index = index.as_slice().ok_or("index is not contiguous")?;
+    let y = index_select::index_select(inputs.get::<f32>("x")?, dim, index);
+    single("y", y.map_err(describe)?)
+}
+
+fn run_matmul(_case: &Case, inputs: &Inputs) -> Outputs {
+    match inputs.tensor("x")? {
+        Tensor::F16(x) => single(
+            "z",
+            matmul::matmul_upcast(x, inputs.get("y")?).map_err(describe)?,
+        ),
+        Tensor::BF16(x) => single(
+            "z",
+            matmul::matmul_upcast(x, inputs.get("y")?).map_err(describe)?,
+        ),
+        _ => single(
+            "z",
+            matmul::matmul(inputs.get::<f32>("x")?, inputs.get("y")?).map_err(describe)?,
+        ),
+    }
+}
+
+fn run_max(case: &Case, inputs: &Inputs) -> Outputs {
+    let dim = case.arg_usize("dim")?;
+    with_float!(inputs, "x", |x| {
+        let (values, indices) = max::max(x, dim).map_err(describe)?;
+        Ok(HashMap::from([
+            ("values".to_string(), values.into()),
+            ("indices".to_string(), indices.into()),
+        ]))
+    })
+}
+
+fn run_ones(case: &Case, _inputs: &Inputs) -> Outputs {
+    single("y", ones::ones(IxDyn(&case.arg_usizes("shape")?)))
+}
+
+/// The pad list is flat and starts from the last dimension, like `torch.nn.functional.pad`.
+fn run_pad(case: &Case, inputs: &Inputs) -> Outputs {
+    let flat = case.arg_i64s("pad")?;
+    if flat.len() % 2 != 0 {
+        return Err(format!("pad list {flat:?} has an odd length"));
+    }
+    let pads: Vec<(isize, isize)> = flat
+        .chunks(2)
+        .map(|pair| (pair[0] as isize, pair[1] as isize))
+        .collect();
+    let mode = match case.arg_str("mode")? {
+        "constant" => PadMode::Constant(case.arg_f64("value")? as f32),
+        "reflect" => PadMode::Reflect,
+        "replicate" => PadMode::Replicate,
+        "circular" => PadMode::Circular,
+        other => return Err(format!("unsupported pad mode {other:?}")),
+    };
+    single(
+        "y",
+        pad::pad(inputs.get::<f32>("x")?, &pads, mode).map_err(describe)?,
+    )
+}
+
+fn run_rearrange_batch_mems_flag(_case: &Case, inputs: &Inputs) -> Outputs {
+    let y = rearrange::rearrange_batch_mems_flag(inputs.get("x")?);
+    single("y", y.map_err(describe)?)
+}
+
+fn run_reduce(case: &Case, inputs: &Inputs) -> Outputs {
+    let equation = case.arg_str("equation")?;
+    single(
+        "y",
+        reduce::reduce(inputs.get("x")?, equation).map_err(describe)?,
+    )
+}
+
+/// Repeats by the `repeats` input when the case has one, like
+/// `torch.repeat_interleave(x, repeats_tensor, dim)`, and by the `repeats` argument otherwise.
+fn run_repeat_interleave(case: &Case, inputs: &Inputs) -> Outputs {
+    let dim = case.arg_opt_i64("dim")?.map(|dim| dim as isize);
+    let x = inputs.get::<f32>("x")?;
+    let y = match inputs.get_opt::<i64>("repeats")? {
+        Some(repeats) => repeat_interleave::repeat_interleave_tensor(x, repeats, dim),
+        None => repeat_interleave::repeat_interleave(x, case.arg_usize("repeats")?, dim),
+    };
+    single("y", y.map_err(describe)?)
+}
+
+fn run_reshape(case: &Case, inputs: &Inputs) -> Outputs {
+    let shape = case.arg_i64s("shape")?;
+    with_float!(inputs, "x", |x| single(
+        "y",
+        reshape::reshape(x, &shape).map_err(describe)?
+    ))
+}
+
+fn run_roll(case: &Case, inputs: &Inputs) -> Outputs {
+    let shifts = isizes(case.arg_i64s("shifts")?);
+    let dims = isizes(case.arg_i64s("dims")?);
+    single(
+        "y",
+        roll::roll(inputs.get::<f32>("x")?, &shifts, &dims).map_err(describe)?,
+    )
+}
+
+fn run_scatter(case: &Case, inputs: &Inputs) -> Outputs {
+    let dim = case.arg_i64("dim")? as isize;
+    let indices = inputs.get::<i64>("indices")?;
+    with_float!(inputs, "target", |target| {
+        let mut target = target.clone();
+        scatter::scatter(&mut target, dim, indices, inputs.get("src")?).map_err(describe)?;
+        single("y", target)
+    })
+}
+
+fn run_slice_last_dim(_case: &Case, inputs: &Inputs) -> Outputs {
+    single(
+        "y",
+        slicing::slice_last_dim(inputs.get::<f32>("x")?).map_err(describe)?,
+    )
+}
+
+fn run_slice_second_dim(case: &Case, inputs: &Inputs) -> Outputs {
+    let amount = case.arg_usize("amount")?;
+    let y = slicing::slice_second_dim(inputs.get::<f32>("x")?, amount);
+    single("y", y.map_err(describe)?)
+}
+
+/// `sort_last_dim` sorts in place, so the runner sorts a copy of the input.
+fn run_sort_last_dim(_case: &Case, inputs: &Inputs) -> Outputs {
+    with_float!(inputs, "x", |x| {
+        let mut y = x.clone();
+        sort::sort_last_dim(&mut y);
+        single("y", y)
+    })
+}
+
+fn run_tile(case: &Case, inputs: &Inputs) -> Outputs {
+    let reps = case.arg_usizes("reps")?;
+    single(
+        "y",
+        tile::tile(inputs.get::<f32>("x")?, &reps).map_err(describe)?,
+    )
+}
+
+fn run_transpose(case: &Case, inputs: &Inputs) -> Outputs {
+    let (dim0, dim1) = (case.arg_usize("dim0")?, case.arg_usize("dim1")?);
+    with_float!(inputs, "x", |x| single(
+        "y",
+        transpose::transpose_dims(x, dim0, dim1)
+    ))
+}
+
+fn run_tril(case: &Case, inputs: &Inputs) -> Outputs {
+    let diagonal = case.arg_i64("diagonal")? as isize;
+    single(
+        "y",
+        tril::tril(inputs.get::<f32>("x")?, diagonal).map_err(describe)?,
+    )
+}
+
+fn run_triu(case: &Case, inputs: &Inputs) -> Outputs {
+    let diagonal = case.arg_i64("diagonal")? as isize;
+    single(
+        "y",
+        tril::triu(inputs.get::<f32>("x")?, diagonal).map_err(describe)?,
+    )
+}
@@ -0,0 +1,88 @@
+//! Runners of `RustOps::linalg`.
+
+use super::super::{Case, Inputs, Runner};
+use super::{Outputs, describe, single};
+use RustOps::linalg::{
+    MatrixNorm, QrMode, cholesky, det, eigh, inv, lstsq, matrix_norm, pinv, qr, slogdet, solve, svd,
+};
+use std::collections::HashMap;
+
+pub(super) fn runners() -> [(&'static str, Runner); 11] {
+    [
+        ("cholesky", run_cholesky as Runner),
+        ("det", run_det),
+        ("eigvalsh", run_eigvalsh),
+        ("inv", run_inv),
+        ("lstsq", run_lstsq),
+        ("nuclear_norm", run_nuclear_norm),
+        ("pinv", run_pinv),
+        ("qr", run_qr),
+        ("slogdet", run_slogdet),
+        ("solve", run_solve),
+        ("svdvals", run_svdvals),
+    ]
+}
+
+fn run_cholesky(_case: &Case, inputs: &Inputs) -> Outputs {
+    single(
+        "y",
+        cholesky(inputs.get::<f64>("a")?, false).map_err(describe)?,
+    )
+}
+
+fn run_det(_case: &Case, inputs: &Inputs) -> Outputs {
+    single("y", det(inputs.get::<f64>("a")?).map_err(describe)?)
+}
+
+/// Like `torch.linalg.eigvalsh`: the eigenvalues of `eigh`, without the eigenvectors.
+fn run_eigvalsh(_case: &Case, inputs: &Inputs) -> Outputs {
+    let (values, _) = eigh(inputs.get::<f64>("a")?, false).map_err(describe)?;
+    single("y", values)
+}
+
+fn run_inv(_case: &Case, inputs: &Inputs) -> Outputs {
+    single("y", inv(inputs.get::<f64>("a")?).map_err(describe)?)
+}
+
+fn run_lstsq(_case: &Case, inputs: &Inputs) -> Outputs {
+    let result = lstsq(inputs.get::<f64>("a")?, inputs.get("b")?, None).map_err(describe)?;
+    single("y", result.solution)
+}
+
+fn run_nuclear_norm(_case: &Case, inputs: &Inputs) -> Outputs {
+    let norm = matrix_norm(inputs.get::<f64>("a")?, MatrixNorm::Nuclear, false);
+    single("y", norm.map_err(describe)?)
+}
+
+fn run_pinv(_case: &Case, inputs: &Inputs) -> Outputs {
+    single("y", pinv(inputs.get::<f64>("a")?, None).map_err(describe)?)
+}
+
+fn run_qr(_case: &Case, inputs: &Inputs) -> Outputs {
+    let (q, r) = qr(inputs.get::<f64>("a")?, QrMode::Reduced).map_err(describe)?;
+    Ok(HashMap::from([
+        ("q".to_string(), q.into()),
+        ("r".to_string(), r.into()),
+    ]))
+}
+
+fn run_slogdet(_case: &Case, inputs: &Inputs) -> Outputs {
+    let (sign, logabsdet) = slogdet(inputs.get::<f64>("a")?).map_err(describe)?;
+    Ok(HashMap::from([
+        ("sign".to_string(), sign.into()),
+        ("logabsdet".to_string(), logabsdet.into()),
+    ]))
+}
+
+fn run_solve(_case: &Case, inputs: &Inputs) -> Outputs {
+    single(
+        "y",
+        solve(inputs.get::<f64>("a")?, inputs.get("b")?).map_err(describe)?,
+    )
+}
+
+/// Like `torch.linalg.svdvals`: the singular values of `svd`, without the vectors.
+fn run_svdvals(_case: &Case, inputs: &Inputs) -> Outputs {
+    let (_, values, _) = svd(inputs.get::<f64>("a")?, false).map_err(describe)?;
+    single("y", values)
+}
@@ -0,0 +1,208 @@
+//! Runners of `RustOps::nn::functional`.
+
+use super::super::{Case, Inputs, Runner};
+use super::{Outputs, describe, single};
+use RustOps::io::Tensor;
+use RustOps::nn::functional::{
+    AttentionMask, adaptive_avg_pool1d, adaptive_avg_pool2d, avg_pool1d, avg_pool2d,
+    conv_transpose1d, conv1d, conv2d, embedding, group_norm, layer_norm, linear, log_softmax,
+    max_pool1d, max_pool2d, rms_norm, scaled_dot_product_attention, softmax,
+};
+
+pub(super) fn runners() -> [(&'static str, Runner); 17] {
+    [
+        ("adaptive_avg_pool1d", run_adaptive_avg_pool1d as Runner),
+        ("adaptive_avg_pool2d", run_adaptive_avg_pool2d),
+        ("attention", run_attention),
+        ("avg_pool1d", run_avg_pool1d),
+        ("avg_pool2d", run_avg_pool2d),
+        ("conv1d", run_conv1d),
+        ("conv2d", run_conv2d),
+        ("conv_transpose1d", run_conv_transpose1d),
+        ("embedding", run_embedding),
+        ("group_norm", run_group_norm),
+        ("layer_norm", run_layer_norm),
+        ("linear", run_linear),
+        ("log_softmax", run_log_softmax),
+        ("max_pool1d", run_max_pool1d),
+        ("max_pool2d", run_max_pool2d),
+        ("rms_norm", run_rms_norm),
+        ("softmax", run_softmax),
+    ]
+}
+
+fn run_adaptive_avg_pool1d(case: &Case, inputs: &Inputs) -> Outputs {
+    let output_size = case.arg_usize("output_size")?;
+    let y = adaptive_avg_pool1d(inputs.get::<f32>("x")?, output_size);
+    single("y", y.map_err(describe)?)
+}
+
+fn run_adaptive_avg_pool2d(case: &Case, inputs: &Inputs) -> Outputs {
+    let output_size = case.arg_pair("output_size")?;
+    let y = adaptive_avg_pool2d(inputs.get::<f32>("x")?, output_size);
+    single("y", y.map_err(describe)?)
+}
+
+/// A boolean `mask` input selects the keys to attend to, a float one is added to the scores.
+fn run_attention(case: &Case, inputs: &Inputs) -> Outputs {
+    let is_causal = case.arg_bool("is_causal")?;
+    let (q, k, v) = (inputs.get::<f32>("q")?, inputs.get("k")?, inputs.get("v")?);
+    let mask = match inputs.tensor("mask") {
+        Err(_) => None,
+        Ok(Tensor::Bool(mask)) => Some(AttentionMask::Boolean(mask)),
+        Ok(_) => Some(AttentionMask::Additive(inputs.get("mask")?)),
+    };
+    let y = scaled_dot_product_attention(q, k, v, mask, is_causal, None);
+    single("y", y.map_err(describe)?)
+}
+
+fn run_avg_pool1d(case: &Case, inputs: &Inputs) -> Outputs {
+    let y = avg_pool1d(
+        inputs.get::<f32>("x")?,
+        case.arg_usize("kernel_size")?,
+        case.arg_opt_usize("stride")?,
+        case.arg_usize("padding")?,
+        case.arg_bool("ceil_mode")?,
+        case.arg_bool("count_include_pad")?,
+    );
+    single("y", y.map_err(describe)?)
+}
+
+fn run_avg_pool2d(case: &Case, inputs: &Inputs) -> Outputs {
+    let y = avg_pool2d(
+        inputs.get::<f32>("x")?,
+        case.arg_pair("kernel_size")?,
+        case.arg_opt_pair("stride")?,
+        case.arg_pair("padding")?,
+        case.arg_bool("ceil_mode")?,
+        case.arg_bool("count_include_pad")?,
+    );
+    single("y", y.map_err(describe)?)
+}
+
+fn run_conv1d(case: &Case, inputs: &Inputs) -> Outputs {
+    let y = conv1d(
+        inputs.get::<f32>("x")?,
+        inputs.get("weight")?,
+        inputs.get_opt("bias")?,
+        case.arg_usize("stride")?,
+        case.arg_usize("padding")?,
+        case.arg_usize("dilation")?,
+        case.arg_usize("groups")?,
+    );
+    single("y", y.map_err(describe)?)
+}
+
+fn run_conv2d(case: &Case, inputs: &Inputs) -> Outputs {
+    let y = conv2d(
+        inputs.get::<f32>("x")?,
+        inputs.get("weight")?,
+        inputs.get_opt("bias")?,
+        case.arg_pair("stride")?,
+        case.arg_pair("padding")?,
+        case.arg_pair("dilation")?,
+        case.arg_usize("groups")?,
+    );
+    single("y", y.map_err(describe)?)
+}
+
+fn run_conv_transpose1d(case: &Case, inputs: &Inputs) -> Outputs {
+    let y = conv_transpose1d(
+        inputs.get::<f32>("x")?,
+        inputs.get("weight")?,
+        inputs.get_opt("bias")?,
+        case.arg_usize("stride")?,
+        case.arg_usize("padding")?,
+        case.arg_usize("output_padding")?,
+        case.arg_usize("groups")?,
+        case.arg_usize("dilation")?,
+    );
+    single("y", y.map_err(describe)?)
+}
+
+fn run_embedding(_case: &Case, inputs: &Inputs) -> Outputs {
+    let y = embedding(inputs.get("ids")?, inputs.get::<f32>("table")?);
+    single("y", y.map_err(describe)?)
+}
+
+fn run_group_norm(case: &Case, inputs: &Inputs) -> Outputs {
+    let y = group_norm(
+        inputs.get::<f32>("x")?,
+        case.arg_usize("num_groups")?,
+        inputs.get_opt("weight")?,
+        inputs.get_opt("bias")?,
+        case.arg_f64("eps")? as f32,
+    );
+    single("y", y.map_err(describe)?)
+}
+
+fn run_layer_norm(case: &Case, inputs: &Inputs) -> Outputs {
+    let y = layer_norm(
+        inputs.get::<f32>("x")?,
+        &case.arg_usizes("normalized_shape")?,
+        inputs.get_opt("weight")?,
+        inputs.get_opt("bias")?,
+        case.arg_f64("eps")? as f32,
+    );
+    single("y", y.map_err(describe)?)
+}
+
+fn run_linear(_case: &Case, inputs: &Inputs) -> Outputs {
+    let y = linear(
+        inputs.get::<f32>("x")?,
+        inputs.get("weight")?,
+        inputs.get_opt("bias")?,
+    );
+    single("y", y.map_err(describe)?)
+}
+
+fn run_log_softmax(case: &Case, inputs: &Inputs) -> Outputs {
+    let dim = case.arg_i64("dim")? as isize;
+    single(
+        "y",
+        log_softmax(inputs.get::<f32>("x")?, dim).map_err(describe)?,
+    )
+}
+
+fn run_max_pool1d(case: &Case, inputs: &Inputs) -> Outputs {
+    let y = max_pool1d(
+        inputs.get::<f32>("x")?,
+        case.arg_usize("kernel_size")?,
+        case.arg_opt_usize("stride")?,
+        case.arg_usize("padding")?,
+        case.arg_usize("dilation")?,
+        case.arg_bool("ceil_mode")?,
+    );
+    single("y", y.map_err(describe)?)
+}
+
+fn run_max_pool2d(case: &Case, inputs: &Inputs) -> Outputs {
+    let y = max_pool2d(
+        inputs.get::<f32>("x")?,
+        case.arg_pair("kernel_size")?,
+        case.arg_opt_pair("stride")?,
+        case.arg_pair("padding")?,
+        case.arg_pair("dilation")?,
+        case.arg_bool("ceil_mode")?,
+    );
+    single("y", y.map_err(describe)?)
+}
+
+/// A `null` eps uses the machine epsilon of the dtype, like PyTorch.
+fn run_rms_norm(case: &Case, inputs: &Inputs) -> Outputs {
+    let y = rms_norm(
+        inputs.get::<f32>("x")?,
+        &case.arg_usizes("normalized_shape")?,
+        inputs.get_opt("weight")?,
+        case.arg_opt_f64("eps")?.map(|eps| eps as f32),
+    );
+    single("y", y.map_err(describe)?)
+}
+
+fn run_softmax(case: &Case, inputs: &Inputs) -> Outputs {
+    let dim = case.arg_i64("dim")? as isize;
+    single(
+        "y",
+        softmax(inputs.get::<f32>("x")?, dim).map_err(describe)?,
+    )
+}
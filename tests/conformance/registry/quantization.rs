@@ -0,0 +1,97 @@
+//! Runners of `RustOps::quantization`. npy has no 8-bit quantized dtypes, so integer
+//! representations and zero points are exchanged as int64.
+
+use super::super::{Case, Inputs, Runner};
+use super::{Outputs, describe};
+use RustOps::quantization::{
+    MinMaxObserver, Observer, PerChannelMinMaxObserver, QParams, QScheme, QTensor, qlinear,
+    quantize,
+};
+use ndarray::{ArrayD, IxDyn, arr0};
+use std::collections::HashMap;
+
+pub(super) fn runners() -> [(&'static str, Runner); 3] {
+    [
+        ("qlinear", run_qlinear as Runner),
+        ("quantize_min_max", run_quantize_min_max),
+        (
+            "quantize_per_channel_min_max",
+            run_quantize_per_channel_min_max,
+        ),
+    ]
+}
+
+fn observe<O: Observer>(mut observer: O, x: &ArrayD<f32>) -> Result<QParams, String> {
+    observer.observe(x).map_err(describe)?;
+    observer.qparams().map_err(describe)
+}
+
+/// Like `qF.linear` on a quint8 input quantized per tensor and qint8 weights quantized per
+/// channel along the output features.
+fn run_qlinear(case: &Case, inputs: &Inputs) -> Outputs {
+    let x_qparams = QParams::PerTensor {
+        scale: case.arg_f64("x_scale")? as f32,
+        zero_point: case.arg_i64("x_zero_point")? as i32,
+    };
+    let x_int = inputs.get::<i64>("x_int")?.mapv(|v| v as u8);
+    let xq = QTensor::from_parts(x_int, x_qparams).map_err(describe)?;
+    let w_qparams = QParams::PerChannel {
+        scales: inputs.get::<f32>("w_scales")?.iter().copied().collect(),
+        zero_points: inputs
+            .get::<i64>("w_zero_points")?
+            .iter()
+            .map(|&v| v as i32)
+            .collect(),
+        axis: 0,
+    };
+    let w_int = inputs.get::<i64>("w_int")?.mapv(|v| v as i8);
+    let wq = QTensor::from_parts(w_int, w_qparams).map_err(describe)?;
+
+    let (scale, zero_point) = (
+        case.arg_f64("y_scale")? as f32,
+        case.arg_i64("y_zero_point")? as i32,
+    );
+    let yq: QTensor<u8> =
+        qlinear(&xq, &wq, inputs.get_opt("b")?, scale, zero_point).map_err(describe)?;
+    Ok(HashMap::from([(
+        "y_int".to_string(),
+        yq.int_repr().mapv(i64::from).into(),
+    )]))
+}
+
+/// Calibrates a quint8 affine `MinMaxObserver` on `x` and quantizes `x` with its parameters.
+fn run_quantize_min_max(_case: &Case, inputs: &Inputs) -> Outputs {
+    let x = inputs.get::<f32>("x")?;
+    let qparams = observe(MinMaxObserver::<u8>::new(QScheme::Affine), x)?;
+    let QParams::PerTensor { scale, zero_point } = qparams else {
+        return Err(format!("expected per-tensor parameters, got {qparams:?}"));
+    };
+    let xq = quantize::<u8>(x, qparams).map_err(describe)?;
+    Ok(HashMap::from([
+        ("scale".to_string(), arr0(scale).into_dyn().into()),
+        (
+            "zero_point".to_string(),
+            arr0(i64::from(zero_point)).into_dyn().into(),
+        ),
+        ("int_repr".to_string(), xq.int_repr().mapv(i64::from).into()),
+    ]))
+}
+
+/// Calibrates a qint8 symmetric `PerChannelMinMaxObserver` on `x` along `axis` and quantizes `x`
+/// with its parameters.
+fn run_quantize_per_channel_min_max(case: &Case, inputs: &Inputs) -> Outputs {
+    let x = inputs.get::<f32>("x")?;
+    let axis = case.arg_usize("axis")?;
+    let observer = PerChannelMinMaxObserver::<i8>::new(QScheme::Symmetric, axis);
+    let qparams = observe(observer, x)?;
+    let QParams::PerChannel { scales, .. } = &qparams else {
+        return Err(format!("expected per-channel parameters, got {qparams:?}"));
+    };
+    let scales =
+        ArrayD::from_shape_vec(IxDyn(&[scales.len()]), scales.clone()).map_err(describe)?;
+    let xq = quantize::<i8>(x, qparams).map_err(describe)?;
+    Ok(HashMap::from([
+        ("scales".to_string(), scales.into()),
+        ("int_repr".to_string(), xq.int_repr().mapv(i64::from).into()),
+    ]))
+}
@@ -0,0 +1,118 @@
+//! Runners of `RustOps::rng`. Every case seeds a fresh generator with its `seed` argument, the
+//! way the reference script calls `torch.manual_seed` before each draw.
+
+use super::super::{Case, Inputs, Runner};
+use super::{Outputs, describe, single};
+use RustOps::io::Tensor;
+use RustOps::rng::{
+    Generator, bernoulli, multinomial, normal_, rand, randint, randn, randperm, uniform_,
+};
+use ndarray::{ArrayD, IxDyn};
+
+pub(super) fn runners() -> [(&'static str, Runner); 8] {
+    [
+        ("bernoulli", run_bernoulli as Runner),
+        ("multinomial", run_multinomial),
+        ("normal_", run_normal_),
+        ("rand", run_rand),
+        ("randint", run_randint),
+        ("randn", run_randn),
+        ("randperm", run_randperm),
+        ("uniform_", run_uniform_),
+    ]
+}
+
+fn generator(case: &Case) -> Result<Generator, String> {
+    Ok(Generator::new(case.arg_usize("seed")? as u64))
+}
+
+/// Runs `$body` with `$A` bound to the element type named by the `dtype` argument.
+macro_rules! with_dtype {
+    ($case:expr, |$A:ident| $body:expr) => {
+        match $case.arg_str("dtype")? {
+            "float32" => {
+                type $A = f32;
+                $body
+            }
+            "float64" => {
+                type $A = f64;
+                $body
+            }
+            other => Err(format!("unsupported dtype {other:?}")),
+        }
+    };
+}
+
+fn run_bernoulli(case: &Case, inputs: &Inputs) -> Outputs {
+    let mut generator = generator(case)?;
+    match inputs.tensor("p")? {
+        Tensor::F32(p) => single("y", bernoulli(p, &mut generator).map_err(describe)?),
+        Tensor::F64(p) => single("y", bernoulli(p, &mut generator).map_err(describe)?),
+        other => Err(format!(
+            "input \"p\" has unsupported dtype {}",
+            other.dtype()
+        )),
+    }
+}
+
+fn run_multinomial(case: &Case, inputs: &Inputs) -> Outputs {
+    let (num_samples, replacement) = (
+        case.arg_usize("num_samples")?,
+        case.arg_bool("replacement")?,
+    );
+    let weights = inputs.get::<f32>("weights")?;
+    let y = multinomial(weights, num_samples, replacement, &mut generator(case)?);
+    single("y", y.map_err(describe)?)
+}
+
+fn run_normal_(case: &Case, _inputs: &Inputs) -> Outputs {
+    let (shape, mean, std) = (
+        case.arg_usizes("shape")?,
+        case.arg_f64("mean")?,
+        case.arg_f64("std")?,
+    );
+    with_dtype!(case, |A| {
+        let mut x = ArrayD::<A>::zeros(IxDyn(&shape));
+        normal_(&mut x, mean, std, &mut generator(case)?);
+        single("y", x)
+    })
+}
+
+fn run_rand(case: &Case, _inputs: &Inputs) -> Outputs {
+    let shape = case.arg_usizes("shape")?;
+    with_dtype!(case, |A| single(
+        "y",
+        rand::<A>(&shape, &mut generator(case)?)
+    ))
+}
+
+fn run_randint(case: &Case, _inputs: &Inputs) -> Outputs {
+    let (low, high) = (case.arg_i64("low")?, case.arg_i64("high")?);
+    let y = randint(low, high, &case.arg_usizes("shape")?, &mut generator(case)?);
+    single("y", y.map_err(describe)?)
+}
+
+fn run_randn(case: &Case, _inputs: &Inputs) -> Outputs {
+    let shape = case.arg_usizes("shape")?;
+    with_dtype!(case, |A| single(
+        "y",
+        randn::<A>(&shape, &mut generator(case)?)
+    ))
+}
+
+fn run_randperm(case: &Case, _inputs: &Inputs) -> Outputs {
+    single("y", randperm(case.arg_usize("n")?, &mut generator(case)?))
+}
+
+fn run_uniform_(case: &Case, _inputs: &Inputs) -> Outputs {
+    let (shape, low, high) = (
+        case.arg_usizes("shape")?,
+        case.arg_f64("low")?,
+        case.arg_f64("high")?,
+    );
+    with_dtype!(case, |A| {
+        let mut x = ArrayD::<A>::zeros(IxDyn(&shape));
+        uniform_(&mut x, low as A, high as A, &mut generator(case)?);
+        single("y", x)
+    })
+}
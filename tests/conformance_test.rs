@@ -0,0 +1,110 @@
+mod conformance;
+
+use RustOps::io::bundle::{Bundle, Compression};
+use conformance::{Outcome, registry, run_manifest};
+use half::f16;
+use ndarray::array;
+use ndarray_npy::write_npy;
+use std::path::Path;
+
+#[test]
+fn test_reference_conformance() {
+    let summary = run_manifest(Path::new("data"), &registry());
+    println!(
+        "{} passed, {} failed, {} skipped",
+        summary.count(|o| matches!(o, Outcome::Passed(_))),
+        summary.failures().len(),
+        summary.count(|o| matches!(o, Outcome::Skipped(_))),
+    );
+    assert!(summary.failures().is_empty(), "{:#?}", summary.failures());
+}
+
+#[test]
+fn test_harness_outcomes() {
+    let dir = std::env::temp_dir().join(format!("rustops_conformance_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let x_half = array![[1.0f32, 5.0, 2.0], [7.0, 0.0, 3.0]];
+    let mut bundle = Bundle::new();
+    bundle.insert("x", array![[1.0f32, 5.0, 2.0], [7.0, 0.0, 3.0]].into_dyn());
+    bundle.insert("indices", array![[1i64], [0]].into_dyn());
+    bundle.insert("y", array![[5.0f32], [7.0]].into_dyn());
+    bundle.insert("y_close", array![[5.001f32], [7.0]].into_dyn());
+    bundle.insert("argmax", array![1i64, 0].into_dyn());
+    bundle.insert("x_half", x_half.mapv(f16::from_f32).into_dyn());
+    bundle.insert(
+        "y_half",
+        array![[f16::from_f32(5.0)], [f16::from_f32(7.0)]].into_dyn(),
+    );
+    // bfloat16 cases store float32 values that are exact in bfloat16
+    bundle.insert("x_bf16", x_half.into_dyn());
+    bundle
+        .save(dir.join("cases.npz"), Compression::Stored)
+        .unwrap();
+    // Plain npy files work as fixtures too
+    write_npy(dir.join("argmax.npy"), &array![1i64, 0]).unwrap();
+
+    let manifest = r#"{"cases": [
+        {"name": "exact", "op": "gather", "args": {"dim": -1},
+         "inputs": {"x": "cases.npz/x", "indices": "cases.npz/indices"},
+         "outputs": {"y": "cases.npz/y"}},
+        {"name": "within_tolerance", "op": "gather", "args": {"dim": 1},
+         "inputs": {"x": "cases.npz/x", "indices": "cases.npz/indices"},
+         "outputs": {"y": "cases.npz/y_close"}, "atol": 1e-2},
+        {"name": "out_of_tolerance", "op": "gather", "args": {"dim": 1},
+         "inputs": {"x": "cases.npz/x", "indices": "cases.npz/indices"},
+         "outputs": {"y": "cases.npz/y_close"}, "atol": 1e-4},
+        {"name": "wrong_dtype", "op": "gather", "args": {"dim": 1},
+         "inputs": {"x": "cases.npz/x", "indices": "cases.npz/indices"},
+         "outputs": {"y": "cases.npz/argmax"}},
+        {"name": "argmax_dim1", "op": "argmax", "args": {"dim": 1, "keepdim": false},
+         "inputs": {"x": "cases.npz/x"}, "outputs": {"y": "argmax.npy"}},
+        {"name": "half", "op": "gather", "args": {"dim": 1},
+         "inputs": {"x": "cases.npz/x_half", "indices": "cases.npz/indices"},
+         "outputs": {"y": "cases.npz/y_half"}},
+        {"name": "bfloat16", "op": "gather", "args": {"dim": 1}, "dtype": "bfloat16",
+         "inputs": {"x": "cases.npz/x_bf16", "indices": "cases.npz/indices"},
+         "outputs": {"y": "cases.npz/y"}},
+        {"name": "half_expected_as_float", "op": "gather", "args": {"dim": 1},
+         "inputs": {"x": "cases.npz/x_half", "indices": "cases.npz/indices"},
+         "outputs": {"y": "cases.npz/y"}},
+        {"name": "not_generated", "op": "gather", "args": {"dim": 1},
+         "inputs": {"x": "missing.npz/x", "indices": "cases.npz/indices"},
+         "outputs": {"y": "cases.npz/y"}},
+        {"name": "unknown_op", "op": "frobnicate", "args": {},
+         "inputs": {"x": "cases.npz/x"}, "outputs": {"y": "cases.npz/y"}}
+    ]}"#;
+    std::fs::write(dir.join(conformance::MANIFEST), manifest).unwrap();
+
+    let summary = run_manifest(&dir, &registry());
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let outcome = |name: &str| {
+        summary
+            .outcomes
+            .iter()
+            .find(|(case, _)| case == name)
+            .map(|(_, outcome)| outcome.clone())
+            .unwrap()
+    };
+    assert!(matches!(outcome("exact"), Outcome::Passed(r) if r[0].max_abs == 0.0));
+    match outcome("within_tolerance") {
+        Outcome::Passed(reports) => {
+            assert_eq!(reports[0].role, "y");
+            assert!((reports[0].max_abs - 1e-3).abs() < 1e-6);
+            assert!((reports[0].max_rel - 2e-4).abs() < 1e-6);
+        }
+        other => panic!("{other:?}"),
+    }
+    assert!(matches!(outcome("out_of_tolerance"), Outcome::Failed(e) if e.contains("max_abs")));
+    assert!(matches!(outcome("wrong_dtype"), Outcome::Failed(e) if e.contains("dtype")));
+    assert!(matches!(outcome("argmax_dim1"), Outcome::Passed(_)));
+    assert!(matches!(outcome("half"), Outcome::Passed(_)));
+    assert!(matches!(outcome("bfloat16"), Outcome::Passed(_)));
+    assert!(
+        matches!(outcome("half_expected_as_float"), Outcome::Failed(e) if e.contains("dtype f16"))
+    );
+    assert!(matches!(outcome("not_generated"), Outcome::Skipped(e) if e.contains("missing.npz")));
+    assert!(matches!(outcome("unknown_op"), Outcome::Failed(e) if e.contains("frobnicate")));
+    assert_eq!(summary.failures().len(), 4);
+}
@@ -0,0 +1,197 @@
+use RustOps::nn::functional::{
+    FunctionalError, adaptive_avg_pool1d, adaptive_avg_pool2d, avg_pool1d, avg_pool2d,
+    conv_transpose1d, conv1d, conv2d, max_pool1d, max_pool2d,
+};
+use approx::assert_abs_diff_eq;
+use ndarray::{Array, ArrayD, IxDyn, array};
+
+/// Direct (non-GEMM) 2D convolution used as an oracle.
+#[allow(clippy::too_many_arguments)]
+fn naive_conv2d(
+    x: &ArrayD<f64>,
+    w: &ArrayD<f64>,
+    stride: [usize; 2],
+    padding: [usize; 2],
+    dilation: [usize; 2],
+    groups: usize,
+    out_hw: [usize; 2],
+) -> ArrayD<f64> {
+    let (n, c_in) = (x.shape()[0], x.shape()[1]);
+    let (c_out, c_in_group, kh, kw) = (w.shape()[0], w.shape()[1], w.shape()[2], w.shape()[3]);
+    let c_out_group = c_out / groups;
+    let mut out = ArrayD::zeros(IxDyn(&[n, c_out, out_hw[0], out_hw[1]]));
+    for b in 0..n {
+        for o in 0..c_out {
+            let g = o / c_out_group;
+            for oh in 0..out_hw[0] {
+                for ow in 0..out_hw[1] {
+                    let mut sum = 0.0;
+                    for c in 0..c_in_group {
+                        for i in 0..kh {
+                            for j in 0..kw {
+                                let ih = (oh * stride[0] + i * dilation[0]) as isize
+                                    - padding[0] as isize;
+                                let iw = (ow * stride[1] + j * dilation[1]) as isize
+                                    - padding[1] as isize;
+                                if ih < 0
+                                    || iw < 0
+                                    || ih as usize >= x.shape()[2]
+                                    || iw as usize >= x.shape()[3]
+                                {
+                                    continue;
+                                }
+                                sum += x[[b, g * c_in_group + c, ih as usize, iw as usize]]
+                                    * w[[o, c, i, j]];
+                            }
+                        }
+                    }
+                    out[[b, o, oh, ow]] = sum;
+                }
+            }
+        }
+    }
+    assert_eq!(c_in, c_in_group * groups);
+    out
+}
+
+fn ramp(shape: &[usize], step: f64) -> ArrayD<f64> {
+    let mut k = 0.0;
+    Array::from_shape_simple_fn(IxDyn(shape), || {
+        k += step;
+        (k * 7.0) % 5.0 - 2.5
+    })
+}
+
+#[test]
+fn test_conv2d_matches_naive() {
+    let x = ramp(&[2, 4, 7, 9], 0.37);
+    let w = ramp(&[6, 2, 3, 2], 0.91);
+    let bias = array![0.5, -1.0, 0.0, 2.0, 1.0, -0.5].into_dyn();
+    let out = conv2d(&x, &w, Some(&bias), [2, 1], [1, 2], [2, 3], 2).unwrap();
+    // H: (7 + 2 - 2 * 2 - 1) / 2 + 1 = 3, W: (9 + 4 - 3 * 1 - 1) / 1 + 1 = 10
+    assert_eq!(out.shape(), &[2, 6, 3, 10]);
+    let mut expected = naive_conv2d(&x, &w, [2, 1], [1, 2], [2, 3], 2, [3, 10]);
+    for ((_, o, _, _), v) in expected
+        .view_mut()
+        .into_dimensionality::<ndarray::Ix4>()
+        .unwrap()
+        .indexed_iter_mut()
+    {
+        *v += bias[o];
+    }
+    assert_abs_diff_eq!(out, expected, epsilon = 1e-10);
+
+    // Unbatched input and conv1d share the same kernel
+    let single = conv2d(
+        &x.index_axis(ndarray::Axis(0), 1).to_owned(),
+        &w,
+        None,
+        [1, 1],
+        [0, 0],
+        [1, 1],
+        2,
+    )
+    .unwrap();
+    assert_eq!(single.shape(), &[6, 5, 8]);
+
+    let x1 = array![[[1.0, 2.0, 3.0, 4.0, 5.0]]].into_dyn();
+    let w1 = array![[[1.0, 0.0, -1.0]]].into_dyn();
+    assert_eq!(
+        conv1d(&x1, &w1, None, 1, 1, 1, 1).unwrap(),
+        array![[[-2.0, -2.0, -2.0, -2.0, 4.0]]].into_dyn()
+    );
+}
+
+#[test]
+fn test_conv_transpose1d_is_adjoint_of_conv1d() {
+    // <conv1d(x, w), y> == <x, conv_transpose1d(y, w)> for matching parameters
+    let x = ramp(&[2, 4, 11], 0.53);
+    let w = ramp(&[6, 2, 3], 0.29);
+    let (stride, padding, dilation, groups) = (2, 1, 2, 2);
+    let conv = conv1d(&x, &w, None, stride, padding, dilation, groups).unwrap();
+    let y = ramp(conv.shape(), 0.71);
+    // 11 = (L_conv - 1) * 2 - 2 + 2 * 2 + output_padding + 1
+    let output_padding = 11 - ((conv.shape()[2] - 1) * stride - 2 * padding + dilation * 2 + 1);
+    let back = conv_transpose1d(
+        &y,
+        &w,
+        None,
+        stride,
+        padding,
+        output_padding,
+        groups,
+        dilation,
+    )
+    .unwrap();
+    assert_eq!(back.shape(), x.shape());
+    let lhs: f64 = (&conv * &y).sum();
+    let rhs: f64 = (&x * &back).sum();
+    assert_abs_diff_eq!(lhs, rhs, epsilon = 1e-9);
+
+    let y = array![[[1.0, 2.0]]].into_dyn();
+    let w = array![[[1.0, 1.0, 1.0]]].into_dyn();
+    assert_eq!(
+        conv_transpose1d(&y, &w, None, 2, 0, 0, 1, 1).unwrap(),
+        array![[[1.0, 1.0, 3.0, 2.0, 2.0]]].into_dyn()
+    );
+    assert_eq!(
+        conv_transpose1d(&y, &w, None, 2, 0, 2, 1, 1),
+        Err(FunctionalError::OutputPaddingTooLarge {
+            output_padding: 2,
+            stride: 2,
+            dilation: 1
+        })
+    );
+}
+
+#[test]
+fn test_pooling_shapes_and_values() {
+    let x = array![[[1.0f64, 3.0, 2.0, 5.0, 4.0]]].into_dyn();
+    // ceil_mode adds a final window that starts inside the input
+    assert_eq!(
+        max_pool1d(&x, 2, None, 0, 1, true).unwrap(),
+        array![[[3.0, 5.0, 4.0]]].into_dyn()
+    );
+    assert_eq!(
+        max_pool1d(&x, 2, None, 0, 1, false).unwrap(),
+        array![[[3.0, 5.0]]].into_dyn()
+    );
+    assert_eq!(
+        avg_pool1d(&x, 2, None, 0, true, true).unwrap(),
+        array![[[2.0, 3.5, 4.0]]].into_dyn()
+    );
+    // Padding counts towards the divisor only with count_include_pad
+    assert_eq!(
+        avg_pool1d(&x, 3, Some(2), 1, false, true).unwrap(),
+        array![[[4.0 / 3.0, 10.0 / 3.0, 3.0]]].into_dyn()
+    );
+    assert_eq!(
+        avg_pool1d(&x, 3, Some(2), 1, false, false).unwrap(),
+        array![[[2.0, 10.0 / 3.0, 4.5]]].into_dyn()
+    );
+    assert_eq!(
+        adaptive_avg_pool1d(&x, 3).unwrap(),
+        array![[[2.0, 10.0 / 3.0, 4.5]]].into_dyn()
+    );
+
+    let x = Array::from_shape_fn(IxDyn(&[1, 4, 4]), |i| (i[1] * 4 + i[2]) as f64);
+    assert_eq!(
+        max_pool2d(&x, [2, 2], None, [0, 0], [1, 1], false).unwrap(),
+        array![[[5.0, 7.0], [13.0, 15.0]]].into_dyn()
+    );
+    assert_eq!(
+        avg_pool2d(&x, [2, 2], None, [0, 0], false, true).unwrap(),
+        array![[[2.5, 4.5], [10.5, 12.5]]].into_dyn()
+    );
+    assert_eq!(
+        adaptive_avg_pool2d(&x, [1, 1]).unwrap(),
+        array![[[7.5]]].into_dyn()
+    );
+    assert_eq!(
+        max_pool2d(&x, [2, 2], None, [2, 0], [1, 1], false),
+        Err(FunctionalError::PoolPaddingTooLarge {
+            padding: 2,
+            kernel_size: 2
+        })
+    );
+}
@@ -0,0 +1,90 @@
+use RustOps::functions::einsum::{EinsumError, einsum_named, einsum_ndarray_dyn};
+use ndarray::{Array, IxDyn};
+
+#[test]
+fn test_einsum_named_matches_subscripts() {
+    let x = Array::from_shape_fn(IxDyn(&[2, 3, 4, 5]), |idx| {
+        (idx[0] + 2 * idx[1] + 3 * idx[2] + 4 * idx[3]) as f64
+    });
+    let y = Array::from_shape_fn(IxDyn(&[2, 3, 5]), |idx| (idx[0] * idx[1] + idx[2]) as f64);
+
+    let named = einsum_named(
+        "batch fields memories dim, batch fields dim -> batch fields memories",
+        &[&x, &y],
+    )
+    .unwrap();
+    let lettered = einsum_ndarray_dyn("bfmd,bfd->bfm", &[&x, &y]).unwrap();
+    assert_eq!(named, lettered);
+
+    // Names sharing a first letter, like `hidden` and `h_mems`, stay distinct
+    let a = Array::from_shape_fn(IxDyn(&[2, 3, 4]), |idx| (idx[0] + idx[1] + idx[2]) as f64);
+    let b = Array::from_shape_fn(IxDyn(&[2, 4]), |idx| (idx[0] * 4 + idx[1]) as f64);
+    let named = einsum_named(
+        "hidden h_mems c_mems, hidden c_mems -> h_mems hidden",
+        &[&a, &b],
+    )
+    .unwrap();
+    let lettered = einsum_ndarray_dyn("uhc,uc->hu", &[&a, &b]).unwrap();
+    assert_eq!(named, lettered);
+}
+
+#[test]
+fn test_einsum_named_ellipsis() {
+    let x = Array::from_shape_fn(IxDyn(&[2, 3, 4]), |idx| (idx[0] + idx[1] * idx[2]) as f32);
+    let y = Array::from_shape_fn(IxDyn(&[4]), |idx| idx[0] as f32 + 1.0);
+
+    let named = einsum_named("... dim, dim -> ...", &[&x, &y]).unwrap();
+    let lettered = einsum_ndarray_dyn("...d,d->...", &[&x, &y]).unwrap();
+    assert_eq!(named, lettered);
+}
+
+#[test]
+fn test_einsum_named_errors() {
+    let x = Array::<f32, _>::zeros(IxDyn(&[2, 3]));
+    let y = Array::<f32, _>::zeros(IxDyn(&[2, 4]));
+
+    assert_eq!(
+        einsum_named("batch dim, batch dim -> batch", &[&x, &y]),
+        Err(EinsumError::AxisSizeMismatch {
+            axis: "dim".to_string(),
+            expected: 3,
+            found: 4
+        })
+    );
+    assert_eq!(
+        einsum_named("batch dim, batch other -> batch fields", &[&x, &y]),
+        Err(EinsumError::UnknownOutputAxis {
+            axis: "fields".to_string()
+        })
+    );
+    assert_eq!(
+        einsum_named("batch dim", &[&x]),
+        Err(EinsumError::MissingArrow)
+    );
+    assert_eq!(
+        einsum_named("batch dim extra -> batch", &[&x]),
+        Err(EinsumError::SubscriptCountMismatch {
+            operand: 0,
+            subscripts: 3,
+            ndim: 2
+        })
+    );
+    assert_eq!(
+        einsum_named("... ... x -> x", &[&x]),
+        Err(EinsumError::InvalidEllipsis {
+            term: "... ... x".to_string()
+        })
+    );
+    assert_eq!(
+        einsum_named("... x -> ... ...", &[&x]),
+        Err(EinsumError::InvalidEllipsis {
+            term: "... ...".to_string()
+        })
+    );
+    assert_eq!(
+        einsum_named("batch di-m -> batch", &[&x]),
+        Err(EinsumError::InvalidAxisName {
+            axis: "di-m".to_string()
+        })
+    );
+}
@@ -1,84 +1,300 @@
 use RustOps::functions::einsum;
 use approx::assert_abs_diff_eq;
-use ndarray::{Array, ArrayD, IxDyn};
-use ndarray_npy::read_npy;
+use ndarray::{Array, ArrayD, Dimension, IxDyn, array};
 
 #[test]
-fn test_einsum_ndarray_dyn_bfmd_bfd() {
-    // Test matrix multiplication as einsum: 'batch fields memories dim, batch fields dim -> batch fields memories'
-    let x_file = "data/einsum_batch_fields_memories_einsum_bfmd_bfd_x.npy";
-    let y_file = "data/einsum_batch_fields_memories_einsum_bfmd_bfd_y.npy";
-    let z_file = "data/einsum_batch_fields_memories_einsum_bfmd_bfd_z.npy";
+fn test_einsum_matmul_matches_dot() {
+    let a = Array::from_shape_fn((3, 4), |(i, j)| (i * 4 + j) as f64 * 0.5).into_dyn();
+    let b = Array::from_shape_fn((4, 5), |(i, j)| (i as f64) - (j as f64)).into_dyn();
+    let expected = a
+        .view()
+        .into_dimensionality::<ndarray::Ix2>()
+        .unwrap()
+        .dot(&b.view().into_dimensionality::<ndarray::Ix2>().unwrap())
+        .into_dyn();
 
-    let x: ArrayD<f32> = read_npy(x_file).unwrap();
-    let y: ArrayD<f32> = read_npy(y_file).unwrap();
-    let z: ArrayD<f32> = read_npy(z_file).unwrap();
+    let explicit = einsum::einsum_ndarray_dyn("ij,jk->ik", &[&a, &b]).unwrap();
+    let implicit = einsum::einsum_ndarray_dyn("ij,jk", &[&a, &b]).unwrap();
+    let transposed = einsum::einsum_ndarray_dyn("ij,jk->ki", &[&a, &b]).unwrap();
 
-    let tensors = [&x, &y];
-    let result = einsum::einsum_ndarray_dyn("bfmd,bfd->bfm", &tensors).unwrap();
+    assert_abs_diff_eq!(explicit, expected, epsilon = 1e-12);
+    assert_abs_diff_eq!(implicit, expected, epsilon = 1e-12);
+    assert_abs_diff_eq!(transposed, expected.t().to_owned(), epsilon = 1e-12);
+}
+
+#[test]
+fn test_einsum_integer_trace_diagonal_and_sum() {
+    let x = array![[1i64, 2, 3], [4, 5, 6], [7, 8, 9]].into_dyn();
+
+    let trace = einsum::einsum_ndarray_dyn("ii", &[&x]).unwrap();
+    assert_eq!(trace, ndarray::arr0(15i64).into_dyn());
+
+    let diagonal = einsum::einsum_ndarray_dyn("ii->i", &[&x]).unwrap();
+    assert_eq!(diagonal, array![1i64, 5, 9].into_dyn());
+
+    let column_sums = einsum::einsum_ndarray_dyn("ij->j", &[&x]).unwrap();
+    assert_eq!(column_sums, array![12i64, 15, 18].into_dyn());
+
+    let outer = einsum::einsum_ndarray_dyn(
+        "i,j->ij",
+        &[&array![1i64, 2].into_dyn(), &array![3i64, 4].into_dyn()],
+    )
+    .unwrap();
+    assert_eq!(outer, array![[3i64, 4], [6, 8]].into_dyn());
+}
+
+#[test]
+fn test_einsum_ellipsis_broadcasting() {
+    // Batched matmul where the batch dims broadcast: (2, 1, 2, 3) x (4, 3, 2)
+    let a = Array::from_shape_fn(IxDyn(&[2, 1, 2, 3]), |idx| {
+        (idx[0] * 6 + idx[2] * 3 + idx[3]) as f32
+    });
+    let b = Array::from_shape_fn(IxDyn(&[4, 3, 2]), |idx| {
+        (idx[0] + idx[1] * 2 + idx[2]) as f32
+    });
+
+    let result = einsum::einsum_ndarray_dyn("...ij,...jk->...ik", &[&a, &b]).unwrap();
+    assert_eq!(result.shape(), &[2, 4, 2, 2]);
+
+    for p in 0..2 {
+        for q in 0..4 {
+            for i in 0..2 {
+                for k in 0..2 {
+                    let expected: f32 = (0..3).map(|j| a[[p, 0, i, j]] * b[[q, j, k]]).sum();
+                    assert_abs_diff_eq!(result[[p, q, i, k]], expected, epsilon = 1e-4);
+                }
+            }
+        }
+    }
 
-    assert_abs_diff_eq!(result, z, epsilon = 1e-5);
+    // Implicit output puts the broadcast dimensions first
+    let implicit = einsum::einsum_ndarray_dyn("...ij,...jk", &[&a, &b]).unwrap();
+    assert_eq!(implicit, result);
 }
 
 #[test]
-fn test_einsum_ndarray_dyn_bhchmc_bhcc() {
-    // Test einsum: 'batch hidden children h_mems c_mems, batch hidden children c_mems -> batch hidden h_mems'
-    // note that since h_mems, hidden start with the same letter, we will use h for h_mems and c for c_mems
-    // u for hidden, and k for children
-    let x_file = "data/einsum_batch_hidden_children_mems_einsum_bhchmc_bhcc_x.npy";
-    let y_file = "data/einsum_batch_hidden_children_mems_einsum_bhchmc_bhcc_y.npy";
-    let z_file = "data/einsum_batch_hidden_children_mems_einsum_bhchmc_bhcc_z.npy";
-
-    let x: ArrayD<f32> = read_npy(x_file).unwrap();
-    let y: ArrayD<f32> = read_npy(y_file).unwrap();
-    let z: ArrayD<f32> = read_npy(z_file).unwrap();
-
-    let tensors = [&x, &y];
-    let result = einsum::einsum_ndarray_dyn("bukhc,bukc->buh", &tensors).unwrap();
-
-    assert_abs_diff_eq!(result, z, epsilon = 1e-5);
+fn test_einsum_paths_agree() {
+    let a = Array::from_shape_fn(IxDyn(&[3, 4]), |idx| (idx[0] + 2 * idx[1]) as f64);
+    let b = Array::from_shape_fn(IxDyn(&[4, 5]), |idx| (idx[0] * idx[1]) as f64 - 1.0);
+    let c = Array::from_shape_fn(IxDyn(&[5, 6]), |idx| (idx[0] as f64) * 0.25 + idx[1] as f64);
+    let d = Array::from_shape_fn(IxDyn(&[6, 3]), |idx| (idx[0] + idx[1]) as f64);
+    let equation = "ab,bc,cd,de->ae";
+    let shapes = [a.shape(), b.shape(), c.shape(), d.shape()];
+
+    let mut results = Vec::new();
+    for strategy in [
+        einsum::PathStrategy::Auto,
+        einsum::PathStrategy::Greedy,
+        einsum::PathStrategy::Optimal,
+        einsum::PathStrategy::Sequential,
+    ] {
+        let path = einsum::einsum_path(equation, &shapes, strategy).unwrap();
+        assert_eq!(path.steps.len(), 3);
+        assert!(path.optimized_flops <= path.naive_flops);
+        results.push(einsum::einsum_with_path(equation, &[&a, &b, &c, &d], &path.pairs()).unwrap());
+    }
+    for result in &results[1..] {
+        assert_abs_diff_eq!(*result, results[0], epsilon = 1e-9);
+    }
+
+    let optimal = einsum::einsum_path(equation, &shapes, einsum::PathStrategy::Optimal).unwrap();
+    let sequential =
+        einsum::einsum_path(equation, &shapes, einsum::PathStrategy::Sequential).unwrap();
+    assert!(optimal.optimized_flops <= sequential.optimized_flops);
+    let summary = optimal.to_string();
+    for step in &optimal.steps {
+        assert!(summary.contains(&step.equation));
+    }
+}
+
+/// Brute-force einsum over every label assignment, for explicit letter-only equations.
+fn naive_einsum(equation: &str, tensors: &[&ArrayD<f64>]) -> ArrayD<f64> {
+    let (lhs, rhs) = equation.split_once("->").unwrap();
+    let terms: Vec<Vec<char>> = lhs.split(',').map(|t| t.chars().collect()).collect();
+    let output: Vec<char> = rhs.chars().collect();
+
+    let mut labels: Vec<char> = Vec::new();
+    let mut sizes: Vec<usize> = Vec::new();
+    for (term, tensor) in terms.iter().zip(tensors) {
+        for (&c, &size) in term.iter().zip(tensor.shape()) {
+            if !labels.contains(&c) {
+                labels.push(c);
+                sizes.push(size);
+            }
+        }
+    }
+    let out_shape: Vec<usize> = output
+        .iter()
+        .map(|c| sizes[labels.iter().position(|l| l == c).unwrap()])
+        .collect();
+    let mut result = Array::zeros(IxDyn(&out_shape));
+
+    let total: usize = sizes.iter().product();
+    let mut assignment = vec![0; labels.len()];
+    for mut flat in 0..total {
+        for (slot, &size) in assignment.iter_mut().zip(&sizes).rev() {
+            *slot = flat % size;
+            flat /= size;
+        }
+        let value_of = |c: &char| assignment[labels.iter().position(|l| l == c).unwrap()];
+        let product: f64 = terms
+            .iter()
+            .zip(tensors)
+            .map(|(term, tensor)| tensor[&term.iter().map(value_of).collect::<Vec<_>>()[..]])
+            .product();
+        let out_index: Vec<usize> = output.iter().map(value_of).collect();
+        result[&out_index[..]] += product;
+    }
+    result
 }
 
 #[test]
-fn test_einsum_ndarray_dyn_bpcp_bpcpc() {
-    // Test einsum: 'batch parents children pdim, batch parents children pdim cdim -> batch parents children cdim
-    // note that since pdim, parents start with the same letter, we will use p for parent and d for pdim
-    // c for children, and k for cdim
-    let x_file = "data/einsum_batch_parents_children_pdim_cdim_einsum_bpcp_bpcpc_x.npy";
-    let y_file = "data/einsum_batch_parents_children_pdim_cdim_einsum_bpcp_bpcpc_y.npy";
-    let z_file = "data/einsum_batch_parents_children_pdim_cdim_einsum_bpcp_bpcpc_z.npy";
-    let x: ArrayD<f32> = read_npy(x_file).unwrap();
-    let y: ArrayD<f32> = read_npy(y_file).unwrap();
-    let z: ArrayD<f32> = read_npy(z_file).unwrap();
-    let tensors = [&x, &y];
-    let result = einsum::einsum_ndarray_dyn("bpcd,bpcdk->bpck", &tensors).unwrap();
-    assert_abs_diff_eq!(result, z, epsilon = 1e-5);
+fn test_einsum_reference_equations_match_naive() {
+    let cases: [(&str, &[usize], &[usize]); 5] = [
+        ("bfmd,bfd->bfm", &[2, 3, 4, 5], &[2, 3, 5]),
+        ("bukhc,bukc->buh", &[2, 3, 2, 4, 5], &[2, 3, 2, 5]),
+        ("bpcd,bpcdk->bpck", &[2, 3, 2, 4], &[2, 3, 2, 4, 3]),
+        ("ncmd,bnm->bncd", &[3, 2, 4, 5], &[2, 3, 4]),
+        ("bncd,bnm->ncmd", &[2, 3, 2, 4], &[2, 3, 5]),
+    ];
+
+    for (equation, x_shape, y_shape) in cases {
+        let x = Array::from_shape_fn(IxDyn(x_shape), |idx| {
+            idx.slice()
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| ((i + 1) * v) as f64)
+                .sum::<f64>()
+                .sin()
+        });
+        let y = Array::from_shape_fn(IxDyn(y_shape), |idx| {
+            idx.slice()
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| ((i + 2) * v) as f64)
+                .sum::<f64>()
+                .cos()
+        });
+
+        let expected = naive_einsum(equation, &[&x, &y]);
+        let result = einsum::einsum_ndarray_dyn(equation, &[&x, &y]).unwrap();
+        assert_abs_diff_eq!(result, expected, epsilon = 1e-10);
+    }
 }
 
 #[test]
-fn test_einsum_ndarray_dyn_ncmd_bnm() {
-    // Test einsum: 'nodes children_per_node memories dim, batch nodes memories -> batch nodes children_per_node dim'
-    let x_file = "data/einsum_nodes_children_memories_dim_einsum_ncmd_bnm_x.npy";
-    let y_file = "data/einsum_nodes_children_memories_dim_einsum_ncmd_bnm_y.npy";
-    let z_file = "data/einsum_nodes_children_memories_dim_einsum_ncmd_bnm_z.npy";
-    let x: ArrayD<f32> = read_npy(x_file).unwrap();
-    let y: ArrayD<f32> = read_npy(y_file).unwrap();
-    let z: ArrayD<f32> = read_npy(z_file).unwrap();
-    let tensors = [&x, &y];
-    let result = einsum::einsum_ndarray_dyn("ncmd,bnm->bncd", &tensors).unwrap();
-    assert_abs_diff_eq!(result, z, epsilon = 1e-5);
+fn test_einsum_errors() {
+    let a = Array::<f32, _>::zeros(IxDyn(&[2, 3]));
+    let b = Array::<f32, _>::zeros(IxDyn(&[4, 5]));
+
+    assert_eq!(
+        einsum::einsum_ndarray_dyn("ij,jk->ik", &[&a, &b]),
+        Err(einsum::EinsumError::SizeMismatch {
+            label: "j".to_string(),
+            expected: 3,
+            found: 4
+        })
+    );
+    assert_eq!(
+        einsum::einsum_ndarray_dyn("ij->ik", &[&a]),
+        Err(einsum::EinsumError::UnknownOutputLabel { label: 'k' })
+    );
+    assert_eq!(
+        einsum::einsum_ndarray_dyn("ijk->i", &[&a]),
+        Err(einsum::EinsumError::SubscriptCountMismatch {
+            operand: 0,
+            subscripts: 3,
+            ndim: 2
+        })
+    );
+    assert_eq!(
+        einsum::einsum_ndarray_dyn("ij,jk->ik", &[&a]),
+        Err(einsum::EinsumError::OperandCountMismatch {
+            terms: 2,
+            operands: 1
+        })
+    );
+    // The dimensions covered by `...` cannot be summed away implicitly
+    assert_eq!(
+        einsum::einsum_ndarray_dyn("...j->j", &[&a]),
+        Err(einsum::EinsumError::MissingOutputEllipsis {
+            term: "j".to_string(),
+            dims: 1
+        })
+    );
+    assert_eq!(einsum::einsum_ndarray_dyn("...ij->ij", &[&a]).unwrap(), a);
+    assert!(matches!(
+        einsum::einsum_with_path("ij,kl->ijkl", &[&a, &b], &[(0, 0)]),
+        Err(einsum::EinsumError::InvalidPath { .. })
+    ));
 }
 
 #[test]
-fn test_einsum_ndarray_dyn_bncd_bnm() {
-    // Test einsum: 'batch nodes children_per_node dim, batch nodes memories -> nodes children_per_node memories dim'
-    let x_file = "data/einsum_batch_nodes_children_dim_memories_einsum_bncd_bnm_x.npy";
-    let y_file = "data/einsum_batch_nodes_children_dim_memories_einsum_bncd_bnm_y.npy";
-    let z_file = "data/einsum_batch_nodes_children_dim_memories_einsum_bncd_bnm_z.npy";
-    let x: ArrayD<f32> = read_npy(x_file).unwrap();
-    let y: ArrayD<f32> = read_npy(y_file).unwrap();
-    let z: ArrayD<f32> = read_npy(z_file).unwrap();
-    let tensors = [&x, &y];
-    let result = einsum::einsum_ndarray_dyn("bncd,bnm->ncmd", &tensors).unwrap();
-    assert_abs_diff_eq!(result, z, epsilon = 1e-5);
+fn test_einsum_plan_matches_einsum() {
+    let a =
+        Array::from_shape_fn((2, 3, 4), |(i, j, k)| (i * 12 + j * 4 + k) as f64 - 7.0).into_dyn();
+    let b = Array::from_shape_fn((4, 3), |(i, j)| (i as f64) * 0.5 - j as f64).into_dyn();
+    let square = Array::from_shape_fn((3, 3), |(i, j)| (i * 3 + j) as f64).into_dyn();
+    let row = Array::from_shape_fn((1, 3), |(_, j)| j as f64 + 1.0).into_dyn();
+    let b_t = b.t().to_owned();
+
+    let cases: Vec<(&str, Vec<&ArrayD<f64>>)> = vec![
+        ("bij,jk->bik", vec![&a, &b]),
+        ("bij,kj->bki", vec![&a, &b_t]),
+        ("bij->", vec![&a]),
+        ("ii->i", vec![&square]),
+        ("ij,ij->ij", vec![&square, &row]),
+        ("...j,kj", vec![&a, &b_t]),
+    ];
+    for (equation, operands) in cases {
+        let expected = einsum::einsum_ndarray_dyn(equation, &operands).unwrap();
+        let mut out = ArrayD::zeros(IxDyn(expected.shape()));
+        einsum::einsum_into(&mut out.view_mut(), equation, &operands).unwrap();
+        assert_abs_diff_eq!(out, expected, epsilon = 1e-9);
+    }
+
+    // A plan can be reused with operands of any strides
+    let shapes: [&[usize]; 2] = [&[3, 2], &[3]];
+    let mut plan = einsum::EinsumPlan::new("ji,j->i", &shapes).unwrap();
+    assert_eq!(plan.output_shape(), &[2]);
+    let matrix = array![[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]];
+    let vector = array![1.0f32, 0.0, -1.0, 2.0, 0.5, 0.0];
+    let mut out = ArrayD::zeros(IxDyn(&[2]));
+    for _ in 0..2 {
+        plan.execute_into(
+            &mut out.view_mut(),
+            &[
+                matrix.t().into_dyn(),
+                vector.slice(ndarray::s![..;2]).into_dyn(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(out, array![0.5f32, 2.0].into_dyn());
+    }
+
+    assert_eq!(
+        plan.execute_into(
+            &mut out.view_mut(),
+            &[matrix.view().into_dyn(), vector.view().into_dyn()]
+        ),
+        Err(einsum::EinsumError::OperandShapeMismatch {
+            operand: 0,
+            expected: vec![3, 2],
+            found: vec![2, 3]
+        })
+    );
+    let mut wrong = ArrayD::zeros(IxDyn(&[3]));
+    assert_eq!(
+        plan.execute_into(
+            &mut wrong.view_mut(),
+            &[
+                matrix.t().into_dyn(),
+                vector.slice(ndarray::s![..;2]).into_dyn()
+            ]
+        ),
+        Err(einsum::EinsumError::OutputShapeMismatch {
+            expected: vec![2],
+            found: vec![3]
+        })
+    );
 }
@@ -0,0 +1,160 @@
+/*
+ * Exercises the C interface the way an embedding application would. Built and run by
+ * tests/ffi_test.rs; prints the first failed check and exits non-zero on failure.
+ */
+#include "rustops.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <string.h>
+
+static int failures = 0;
+
+#define CHECK(cond)                                                          \
+    do {                                                                     \
+        if (!(cond)) {                                                       \
+            const char *message = rustops_last_error_message();              \
+            fprintf(stderr, "%s:%d: check failed: %s (last error: %s)\n",    \
+                    __FILE__, __LINE__, #cond, message ? message : "none");  \
+            failures++;                                                      \
+            return;                                                          \
+        }                                                                    \
+    } while (0)
+
+static RustOpsTensor *f32_tensor(const float *data, const size_t *shape, size_t ndim) {
+    RustOpsTensor *tensor = NULL;
+    if (rustops_tensor_create(data, shape, ndim, RustOpsDtype_F32, &tensor) != RustOpsStatus_Ok) {
+        return NULL;
+    }
+    return tensor;
+}
+
+static void test_matmul(void) {
+    const float a_data[] = {1, 2, 3, 4, 5, 6};
+    const float b_data[] = {1, 0, 0, 1, 1, 1};
+    const size_t a_shape[] = {2, 3};
+    const size_t b_shape[] = {3, 2};
+    RustOpsTensor *a = f32_tensor(a_data, a_shape, 2);
+    RustOpsTensor *b = f32_tensor(b_data, b_shape, 2);
+    CHECK(a != NULL && b != NULL);
+
+    RustOpsTensor *c = NULL;
+    CHECK(rustops_matmul(a, b, &c) == RustOpsStatus_Ok);
+
+    size_t ndim = 0;
+    size_t shape[4] = {0};
+    int32_t dtype = -1;
+    CHECK(rustops_tensor_ndim(c, &ndim) == RustOpsStatus_Ok && ndim == 2);
+    CHECK(rustops_tensor_shape(c, shape, 4) == RustOpsStatus_Ok);
+    CHECK(shape[0] == 2 && shape[1] == 2);
+    CHECK(rustops_tensor_dtype(c, &dtype) == RustOpsStatus_Ok && dtype == RustOpsDtype_F32);
+
+    float result[4] = {0};
+    const float expected[4] = {4, 5, 10, 11};
+    CHECK(rustops_tensor_copy_data(c, result, sizeof(result)) == RustOpsStatus_Ok);
+    for (int i = 0; i < 4; i++) {
+        CHECK(fabsf(result[i] - expected[i]) < 1e-6f);
+    }
+
+    rustops_tensor_free(a);
+    rustops_tensor_free(b);
+    rustops_tensor_free(c);
+}
+
+static void test_einsum_and_max(void) {
+    const float x_data[] = {3, 1, 2, 0, 5, 4};
+    const size_t shape[] = {2, 3};
+    RustOpsTensor *x = f32_tensor(x_data, shape, 2);
+    CHECK(x != NULL);
+
+    const RustOpsTensor *operands[] = {x};
+    RustOpsTensor *transposed = NULL;
+    CHECK(rustops_einsum("ij->ji", operands, 1, &transposed) == RustOpsStatus_Ok);
+    size_t out_shape[2] = {0};
+    CHECK(rustops_tensor_shape(transposed, out_shape, 2) == RustOpsStatus_Ok);
+    CHECK(out_shape[0] == 3 && out_shape[1] == 2);
+
+    RustOpsTensor *values = NULL;
+    RustOpsTensor *indices = NULL;
+    CHECK(rustops_max(x, -1, &values, &indices) == RustOpsStatus_Ok);
+    float max_values[2] = {0};
+    int64_t max_indices[2] = {0};
+    CHECK(rustops_tensor_copy_data(values, max_values, sizeof(max_values)) == RustOpsStatus_Ok);
+    CHECK(rustops_tensor_copy_data(indices, max_indices, sizeof(max_indices)) == RustOpsStatus_Ok);
+    CHECK(max_values[0] == 3 && max_values[1] == 5);
+    CHECK(max_indices[0] == 0 && max_indices[1] == 1);
+
+    /* Gather the maxima back with the i64 indices */
+    const int64_t index_data[] = {0, 1};
+    const size_t index_shape[] = {2, 1};
+    RustOpsTensor *index = NULL;
+    CHECK(rustops_tensor_create(index_data, index_shape, 2, RustOpsDtype_I64, &index) ==
+          RustOpsStatus_Ok);
+    RustOpsTensor *gathered = NULL;
+    CHECK(rustops_gather(x, 1, index, &gathered) == RustOpsStatus_Ok);
+    float gathered_values[2] = {0};
+    CHECK(rustops_tensor_copy_data(gathered, gathered_values, sizeof(gathered_values)) ==
+          RustOpsStatus_Ok);
+    CHECK(gathered_values[0] == 3 && gathered_values[1] == 5);
+
+    rustops_tensor_free(x);
+    rustops_tensor_free(transposed);
+    rustops_tensor_free(values);
+    rustops_tensor_free(indices);
+    rustops_tensor_free(index);
+    rustops_tensor_free(gathered);
+}
+
+static void test_errors(void) {
+    const float data[] = {1, 2, 3};
+    const size_t shape[] = {3};
+    RustOpsTensor *x = f32_tensor(data, shape, 1);
+    CHECK(x != NULL);
+
+    /* Shapes that do not line up report the op failure */
+    RustOpsTensor *out = NULL;
+    const size_t bad_shape[] = {2, 2};
+    RustOpsTensor *y = f32_tensor((const float[]){1, 2, 3, 4}, bad_shape, 2);
+    CHECK(rustops_matmul(x, y, &out) == RustOpsStatus_OpFailed);
+    CHECK(out == NULL);
+    CHECK(strstr(rustops_last_error_message(), "matmul") != NULL);
+
+    /* The message survives successful calls and can be cleared */
+    size_t ndim = 0;
+    CHECK(rustops_tensor_ndim(x, &ndim) == RustOpsStatus_Ok);
+    CHECK(rustops_last_error_message() != NULL);
+    rustops_clear_last_error();
+    CHECK(rustops_last_error_message() == NULL);
+
+    CHECK(rustops_abs(NULL, &out) == RustOpsStatus_NullPointer);
+    CHECK(rustops_abs(x, NULL) == RustOpsStatus_NullPointer);
+    CHECK(rustops_tensor_create(data, shape, 1, 42, &out) == RustOpsStatus_InvalidArgument);
+    CHECK(rustops_transpose(x, 0, 1, &out) == RustOpsStatus_InvalidArgument);
+
+    /* Dtypes an op does not support */
+    const int64_t ints[] = {-1, 2};
+    const size_t int_shape[] = {2};
+    RustOpsTensor *z = NULL;
+    CHECK(rustops_tensor_create(ints, int_shape, 1, RustOpsDtype_I64, &z) == RustOpsStatus_Ok);
+    CHECK(rustops_abs(z, &out) == RustOpsStatus_UnsupportedDtype);
+
+    /* Output buffers that are too small */
+    float small[2];
+    CHECK(rustops_tensor_copy_data(x, small, sizeof(small)) == RustOpsStatus_BufferTooSmall);
+    CHECK(out == NULL);
+
+    rustops_tensor_free(x);
+    rustops_tensor_free(y);
+    rustops_tensor_free(z);
+    rustops_tensor_free(NULL);
+}
+
+int main(void) {
+    test_matmul();
+    test_einsum_and_max();
+    test_errors();
+    if (failures == 0) {
+        printf("ok\n");
+    }
+    return failures == 0 ? 0 : 1;
+}
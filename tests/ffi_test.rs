@@ -0,0 +1,424 @@
+use RustOps::ffi::*;
+use RustOps::functions::{flip::flip, pad::PadMode, pad::pad, tril::triu};
+use half::{bf16, f16};
+use ndarray::{ArrayD, IxDyn, array};
+use std::ffi::{CStr, c_void};
+use std::path::Path;
+use std::process::Command;
+use std::ptr;
+
+/// Owns a handle for the duration of a test.
+struct Handle(*mut RustOpsTensor);
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        unsafe { rustops_tensor_free(self.0) };
+    }
+}
+
+fn create<T: Copy>(data: &[T], shape: &[usize], dtype: RustOpsDtype) -> Handle {
+    let mut out = ptr::null_mut();
+    let status = unsafe {
+        rustops_tensor_create(
+            data.as_ptr() as *const c_void,
+            shape.as_ptr(),
+            shape.len(),
+            dtype as i32,
+            &mut out,
+        )
+    };
+    assert_eq!(status, RustOpsStatus::Ok, "{}", last_error());
+    Handle(out)
+}
+
+fn from_array(array: &ArrayD<f32>) -> Handle {
+    let data: Vec<f32> = array.iter().copied().collect();
+    create(&data, array.shape(), RustOpsDtype::F32)
+}
+
+/// Reads a tensor back, checking its dtype.
+fn read<T: Copy + Default>(handle: &Handle, dtype: RustOpsDtype) -> ArrayD<T> {
+    let mut code = -1;
+    let mut ndim = 0;
+    let mut numel = 0;
+    unsafe {
+        assert_eq!(rustops_tensor_dtype(handle.0, &mut code), RustOpsStatus::Ok);
+        assert_eq!(rustops_tensor_ndim(handle.0, &mut ndim), RustOpsStatus::Ok);
+        assert_eq!(
+            rustops_tensor_numel(handle.0, &mut numel),
+            RustOpsStatus::Ok
+        );
+    }
+    assert_eq!(code, dtype as i32);
+    let mut shape = vec![0usize; ndim];
+    let mut data = vec![T::default(); numel];
+    unsafe {
+        assert_eq!(
+            rustops_tensor_shape(handle.0, shape.as_mut_ptr(), ndim),
+            RustOpsStatus::Ok
+        );
+        assert_eq!(
+            rustops_tensor_copy_data(
+                handle.0,
+                data.as_mut_ptr() as *mut c_void,
+                numel * std::mem::size_of::<T>()
+            ),
+            RustOpsStatus::Ok
+        );
+    }
+    ArrayD::from_shape_vec(IxDyn(&shape), data).unwrap()
+}
+
+fn last_error() -> String {
+    let message = rustops_last_error_message();
+    if message.is_null() {
+        return "none".to_string();
+    }
+    unsafe { CStr::from_ptr(message) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Runs an entry point that writes one tensor to `out`.
+fn call(f: impl FnOnce(*mut *mut RustOpsTensor) -> RustOpsStatus) -> Result<Handle, RustOpsStatus> {
+    let mut out = ptr::null_mut();
+    match f(&mut out) {
+        RustOpsStatus::Ok => Ok(Handle(out)),
+        status => {
+            assert!(out.is_null(), "out must be untouched on failure");
+            Err(status)
+        }
+    }
+}
+
+#[test]
+fn test_tensor_roundtrip_every_dtype() {
+    let shape = [2, 2];
+    let handle = create(&[1.5f64, -2.0, 0.0, 4.0], &shape, RustOpsDtype::F64);
+    assert_eq!(
+        read::<f64>(&handle, RustOpsDtype::F64),
+        array![[1.5, -2.0], [0.0, 4.0]].into_dyn()
+    );
+    let handle = create(&[1i64, -2, 3, i64::MAX], &shape, RustOpsDtype::I64);
+    assert_eq!(
+        read::<i64>(&handle, RustOpsDtype::I64),
+        array![[1, -2], [3, i64::MAX]].into_dyn()
+    );
+    let halves = [half::f16::ONE, half::f16::NEG_ONE];
+    let handle = create(&halves, &[2], RustOpsDtype::F16);
+    assert_eq!(
+        read::<half::f16>(&handle, RustOpsDtype::F16)
+            .as_slice()
+            .unwrap(),
+        &halves
+    );
+    let handle = create(&[1u8, 0, 1], &[3], RustOpsDtype::Bool);
+    assert_eq!(
+        read::<u8>(&handle, RustOpsDtype::Bool),
+        array![1u8, 0, 1].into_dyn()
+    );
+
+    // A scalar needs neither a shape nor more than one element
+    let mut out = ptr::null_mut();
+    let value = 7.0f32;
+    let status = unsafe {
+        rustops_tensor_create(
+            &value as *const f32 as *const c_void,
+            ptr::null(),
+            0,
+            RustOpsDtype::F32 as i32,
+            &mut out,
+        )
+    };
+    assert_eq!(status, RustOpsStatus::Ok);
+    let scalar = Handle(out);
+    assert_eq!(
+        read::<f32>(&scalar, RustOpsDtype::F32),
+        ndarray::arr0(7.0).into_dyn()
+    );
+}
+
+#[test]
+fn test_tensor_create_rejects_bad_input() {
+    let bytes = [0u8, 2];
+    let status = call(|out| unsafe {
+        rustops_tensor_create(
+            bytes.as_ptr() as *const c_void,
+            [2usize].as_ptr(),
+            1,
+            RustOpsDtype::Bool as i32,
+            out,
+        )
+    });
+    assert_eq!(status.err(), Some(RustOpsStatus::InvalidArgument));
+    assert!(last_error().contains("not a bool"), "{}", last_error());
+
+    let status = call(|out| unsafe {
+        rustops_tensor_create(
+            ptr::null(),
+            [2usize].as_ptr(),
+            1,
+            RustOpsDtype::F32 as i32,
+            out,
+        )
+    });
+    assert_eq!(status.err(), Some(RustOpsStatus::NullPointer));
+    assert!(last_error().contains("`data`"), "{}", last_error());
+
+    let status = call(|out| unsafe {
+        rustops_tensor_create(ptr::null(), [usize::MAX, 2].as_ptr(), 2, 1, out)
+    });
+    assert_eq!(status.err(), Some(RustOpsStatus::InvalidArgument));
+}
+
+#[test]
+fn test_buffer_too_small() {
+    let x = create(&[1.0f32, 2.0, 3.0], &[3], RustOpsDtype::F32);
+    let mut shape = [0usize; 1];
+    let mut data = [0f32; 3];
+    unsafe {
+        assert_eq!(
+            rustops_tensor_shape(x.0, shape.as_mut_ptr(), 0),
+            RustOpsStatus::BufferTooSmall
+        );
+        assert_eq!(
+            rustops_tensor_copy_data(x.0, data.as_mut_ptr() as *mut c_void, 11),
+            RustOpsStatus::BufferTooSmall
+        );
+    }
+    assert!(last_error().contains("12"), "{}", last_error());
+}
+
+#[test]
+fn test_ops_match_rust_functions() {
+    let x = ArrayD::from_shape_fn(IxDyn(&[2, 3, 4]), |i| {
+        (i[0] * 12 + i[1] * 4 + i[2]) as f32 - 9.0
+    });
+    let handle = from_array(&x);
+
+    let flipped = call(|out| unsafe { rustops_flip(handle.0, [-1isize, 0].as_ptr(), 2, out) });
+    assert_eq!(
+        read::<f32>(&flipped.unwrap(), RustOpsDtype::F32),
+        flip(&x, &[-1, 0]).unwrap()
+    );
+
+    let pads = [1isize, 2, 0, 1];
+    let padded = call(|out| unsafe {
+        rustops_pad(
+            handle.0,
+            pads.as_ptr(),
+            pads.len(),
+            RustOpsPadMode::Constant as i32,
+            0.5,
+            out,
+        )
+    });
+    assert_eq!(
+        read::<f32>(&padded.unwrap(), RustOpsDtype::F32),
+        pad(&x, &[(1, 2), (0, 1)], PadMode::Constant(0.5)).unwrap()
+    );
+
+    let upper = call(|out| unsafe { rustops_triu(handle.0, 1, out) });
+    assert_eq!(
+        read::<f32>(&upper.unwrap(), RustOpsDtype::F32),
+        triu(&x, 1).unwrap()
+    );
+
+    let reshaped = call(|out| unsafe { rustops_reshape(handle.0, [4i64, -1].as_ptr(), 2, out) });
+    assert_eq!(
+        read::<f32>(&reshaped.unwrap(), RustOpsDtype::F32).shape(),
+        &[4, 6]
+    );
+
+    let sorted = call(|out| unsafe { rustops_sort_last_dim(handle.0, out) }).unwrap();
+    let sorted = read::<f32>(&sorted, RustOpsDtype::F32);
+    assert_eq!(sorted, x, "rows are already sorted");
+
+    let argmax = call(|out| unsafe { rustops_argmax(handle.0, false, 0, false, out) }).unwrap();
+    assert_eq!(
+        read::<i64>(&argmax, RustOpsDtype::I64),
+        ndarray::arr0(23).into_dyn()
+    );
+
+    let operands = [handle.0 as *const RustOpsTensor, handle.0];
+    let equation = c"bij,bkj->bik";
+    let product =
+        call(|out| unsafe { rustops_einsum(equation.as_ptr(), operands.as_ptr(), 2, out) });
+    assert_eq!(
+        read::<f32>(&product.unwrap(), RustOpsDtype::F32).shape(),
+        &[2, 3, 3]
+    );
+}
+
+#[test]
+fn test_half_precision_ops() {
+    let data = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let halves: Vec<f16> = data.iter().map(|&v| f16::from_f32(v)).collect();
+    let brains: Vec<bf16> = data.iter().map(|&v| bf16::from_f32(v)).collect();
+    let x = create(&halves, &[2, 3], RustOpsDtype::F16);
+    let xb = create(&brains, &[2, 3], RustOpsDtype::BF16);
+
+    let y = call(|out| unsafe { rustops_transpose(x.0, 0, 1, out) }).unwrap();
+    let expected = array![[1.0f32, 4.0], [2.0, 5.0], [3.0, 6.0]].into_dyn();
+    assert_eq!(
+        read::<f16>(&y, RustOpsDtype::F16).mapv(f16::to_f32),
+        expected
+    );
+    let y = call(|out| unsafe { rustops_expand(xb.0, 0, 2, out) }).unwrap();
+    assert_eq!(read::<bf16>(&y, RustOpsDtype::BF16).shape(), &[2, 2, 3]);
+
+    // Products and sums run in f32 and keep the input dtype
+    let t = call(|out| unsafe { rustops_transpose(x.0, 0, 1, out) }).unwrap();
+    let z = call(|out| unsafe { rustops_matmul(x.0, t.0, out) }).unwrap();
+    let expected = array![[14.0f32, 32.0], [32.0, 77.0]].into_dyn();
+    assert_eq!(
+        read::<f16>(&z, RustOpsDtype::F16).mapv(f16::to_f32),
+        expected
+    );
+    let operands = [xb.0 as *const RustOpsTensor, xb.0];
+    let z = call(|out| unsafe { rustops_einsum(c"ij,ij->i".as_ptr(), operands.as_ptr(), 2, out) })
+        .unwrap();
+    let expected = array![14.0f32, 77.0].into_dyn();
+    assert_eq!(
+        read::<bf16>(&z, RustOpsDtype::BF16).mapv(bf16::to_f32),
+        expected
+    );
+    let z = call(|out| unsafe { rustops_reduce(x.0, c"ab,ab->a".as_ptr(), out) }).unwrap();
+    let expected = array![6.0f32, 15.0].into_dyn();
+    assert_eq!(
+        read::<f16>(&z, RustOpsDtype::F16).mapv(f16::to_f32),
+        expected
+    );
+
+    let status = call(|out| unsafe { rustops_matmul(x.0, xb.0, out) });
+    assert_eq!(status.err(), Some(RustOpsStatus::UnsupportedDtype));
+}
+
+#[test]
+fn test_op_errors() {
+    let x = create(&[1.0f32, 2.0, 3.0, 4.0], &[2, 2], RustOpsDtype::F32);
+    let ints = create(&[1i64, 2], &[2], RustOpsDtype::I64);
+
+    let status = call(|out| unsafe { rustops_matmul(x.0, ints.0, out) });
+    assert_eq!(status.err(), Some(RustOpsStatus::UnsupportedDtype));
+    assert!(last_error().contains("`b`"), "{}", last_error());
+
+    let status = call(|out| unsafe { rustops_gather(x.0, 0, x.0, out) });
+    assert_eq!(status.err(), Some(RustOpsStatus::UnsupportedDtype));
+
+    let status = call(|out| unsafe { rustops_tile(ints.0, ptr::null(), 3, out) });
+    assert_eq!(status.err(), Some(RustOpsStatus::NullPointer));
+
+    let status = call(|out| unsafe { rustops_slice_last_dim(x.0, out) });
+    assert_eq!(status.err(), Some(RustOpsStatus::OpFailed));
+    assert!(
+        last_error().starts_with("slice_last_dim failed"),
+        "{}",
+        last_error()
+    );
+
+    let status = call(|out| unsafe { rustops_pad(x.0, [1isize].as_ptr(), 1, 0, 0.0, out) });
+    assert_eq!(status.err(), Some(RustOpsStatus::InvalidArgument));
+
+    let status = call(|out| unsafe { rustops_einsum(c"i".as_ptr(), ptr::null(), 0, out) });
+    assert_eq!(status.err(), Some(RustOpsStatus::InvalidArgument));
+
+    let invalid_utf8 = [0xffu8, 0];
+    let status = call(|out| unsafe {
+        rustops_reduce(x.0, invalid_utf8.as_ptr() as *const std::ffi::c_char, out)
+    });
+    assert_eq!(status.err(), Some(RustOpsStatus::InvalidArgument));
+}
+
+#[test]
+fn test_last_error_is_per_thread() {
+    rustops_clear_last_error();
+    let status = call(|out| unsafe { rustops_abs(ptr::null(), out) });
+    assert_eq!(status.err(), Some(RustOpsStatus::NullPointer));
+    std::thread::spawn(|| assert!(rustops_last_error_message().is_null()))
+        .join()
+        .unwrap();
+    assert!(!rustops_last_error_message().is_null());
+    rustops_clear_last_error();
+    assert!(rustops_last_error_message().is_null());
+}
+
+#[test]
+fn test_header_declares_every_export() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let header = std::fs::read_to_string(root.join("include/rustops.h")).unwrap();
+    let mut exported = Vec::new();
+    for file in ["mod.rs", "tensor.rs", "ops.rs"] {
+        let source = std::fs::read_to_string(root.join("src/ffi").join(file)).unwrap();
+        for line in source.lines() {
+            if let Some((_, rest)) = line.split_once("extern \"C\" fn ") {
+                exported.push(rest.split('(').next().unwrap().to_string());
+            }
+        }
+    }
+    assert!(exported.len() > 30, "found {exported:?}");
+    for name in &exported {
+        assert!(
+            header.contains(&format!(" {name}(")) || header.contains(&format!("*{name}(")),
+            "{name} is not declared in include/rustops.h"
+        );
+    }
+    let declared = header
+        .lines()
+        .filter(|line| !line.trim_start().starts_with(['*', '/']))
+        .filter_map(|line| line.split_once("rustops_"))
+        .filter_map(|(_, rest)| rest.split_once('('))
+        .filter(|(name, _)| !name.contains(' '))
+        .count();
+    assert_eq!(
+        declared,
+        exported.len(),
+        "the header declares functions that do not exist"
+    );
+}
+
+/// Compiles `tests/ffi/harness.c` against the shared library and runs it.
+#[test]
+#[cfg(target_os = "linux")]
+fn test_c_harness() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // Integration tests run from target/<profile>/deps, next to libRustOps.so
+    let exe = std::env::current_exe().unwrap();
+    let lib_dir = exe.parent().unwrap();
+    if !lib_dir.join("libRustOps.so").exists() {
+        panic!("libRustOps.so not found in {}", lib_dir.display());
+    }
+    let harness = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi_harness");
+    let compiled = Command::new("cc")
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror"])
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg(root.join("tests/ffi/harness.c"))
+        .arg("-L")
+        .arg(lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .args(["-lRustOps", "-lm", "-o"])
+        .arg(&harness)
+        .output();
+    let compiled = match compiled {
+        Ok(output) => output,
+        Err(err) => {
+            eprintln!("SKIP: no C compiler available ({err})");
+            return;
+        }
+    };
+    assert!(
+        compiled.status.success(),
+        "compiling the harness failed:\n{}",
+        String::from_utf8_lossy(&compiled.stderr)
+    );
+
+    let run = Command::new(&harness).output().unwrap();
+    assert!(
+        run.status.success(),
+        "harness failed:\n{}{}",
+        String::from_utf8_lossy(&run.stdout),
+        String::from_utf8_lossy(&run.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&run.stdout), "ok\n");
+}
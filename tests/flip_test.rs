@@ -0,0 +1,31 @@
+use RustOps::functions::flip::{FlipError, flip};
+use ndarray::array;
+
+#[test]
+fn test_flip_small() {
+    let x = array![[1, 2, 3], [4, 5, 6]].into_dyn();
+
+    assert_eq!(
+        flip(&x, &[0]).unwrap(),
+        array![[4, 5, 6], [1, 2, 3]].into_dyn()
+    );
+    assert_eq!(
+        flip(&x, &[-1]).unwrap(),
+        array![[3, 2, 1], [6, 5, 4]].into_dyn()
+    );
+    assert_eq!(flip(&x, &[]).unwrap(), x);
+}
+
+#[test]
+fn test_flip_errors() {
+    let x = array![[1.0f32, 2.0], [3.0, 4.0]].into_dyn();
+
+    assert_eq!(
+        flip(&x, &[2]),
+        Err(FlipError::InvalidDimension { dim: 2, ndim: 2 })
+    );
+    assert_eq!(
+        flip(&x, &[1, -1]),
+        Err(FlipError::RepeatedDimension { dim: 1 })
+    );
+}
@@ -0,0 +1,162 @@
+use RustOps::functions::argmax::argmax;
+use RustOps::functions::einsum::{einsum_ndarray_dyn, einsum_upcast};
+use RustOps::functions::expand::expand_at_dim;
+use RustOps::functions::gather::gather;
+use RustOps::functions::matmul::{
+    addmm, addmm_upcast, baddbmm, baddbmm_upcast, bmm, bmm_upcast, matmul, matmul_upcast,
+};
+use RustOps::functions::max::max;
+use RustOps::functions::reduce::{reduce, reduce_upcast};
+use RustOps::functions::reshape::reshape;
+use RustOps::functions::scatter::scatter;
+use RustOps::functions::slicing::{slice_last_dim, slice_second_dim};
+use RustOps::functions::sort::sort_last_dim;
+use RustOps::functions::transpose::transpose_dims;
+use RustOps::functions::upcast::{HalfFloat, downcast, upcast};
+use half::{bf16, f16};
+use ndarray::{Array, ArrayD, IxDyn, array};
+
+/// Deterministic values in [-4, 4) that are exact in both f16 and bf16.
+fn values(shape: &[usize]) -> ArrayD<f32> {
+    let len: usize = shape.iter().product();
+    let data = (0..len).map(|i| ((i * 37 + 11) % 64) as f32 / 8.0 - 4.0);
+    Array::from_shape_vec(IxDyn(shape), data.collect()).unwrap()
+}
+
+/// The element-generic ops give the same result on half-precision input as on the f32 values
+/// it holds, since they only move and compare elements.
+fn check_generic_ops<A: HalfFloat>() {
+    let x32 = values(&[2, 3, 4]);
+    let x: ArrayD<A> = downcast(&x32);
+    let same = |half: ArrayD<A>, single: ArrayD<f32>| assert_eq!(upcast(&half), single);
+
+    let index = array![[[2i64, 0], [1, 3], [0, 0]], [[3, 3], [2, 1], [1, 0]]].into_dyn();
+    same(
+        gather(&x, -1, &index).unwrap(),
+        gather(&x32, -1, &index).unwrap(),
+    );
+
+    let (mut target, mut target32) = (x.clone(), x32.clone());
+    let src32 = values(&[2, 3, 2]).mapv(|v| v * 0.5);
+    scatter(&mut target, 2, &index, &downcast(&src32)).unwrap();
+    scatter(&mut target32, 2, &index, &src32).unwrap();
+    same(target, target32);
+
+    same(
+        reshape(&x, &[4, -1]).unwrap(),
+        reshape(&x32, &[4, -1]).unwrap(),
+    );
+    same(transpose_dims(&x, 0, 2), transpose_dims(&x32, 0, 2));
+    same(
+        expand_at_dim(&x, 1, 3).unwrap(),
+        expand_at_dim(&x32, 1, 3).unwrap(),
+    );
+    same(slice_last_dim(&x).unwrap(), slice_last_dim(&x32).unwrap());
+    same(
+        slice_second_dim(&x, 2).unwrap(),
+        slice_second_dim(&x32, 2).unwrap(),
+    );
+
+    let (values, indices) = max(&x, 1).unwrap();
+    let (values32, indices32) = max(&x32, 1).unwrap();
+    same(values, values32);
+    assert_eq!(indices, indices32);
+    assert_eq!(
+        argmax(&x, Some(2), true).unwrap(),
+        argmax(&x32, Some(2), true).unwrap()
+    );
+    assert_eq!(
+        argmax(&x, None, false).unwrap(),
+        argmax(&x32, None, false).unwrap()
+    );
+
+    let (mut sorted, mut sorted32) = (x, x32);
+    sort_last_dim(&mut sorted);
+    sort_last_dim(&mut sorted32);
+    same(sorted, sorted32);
+}
+
+#[test]
+fn test_generic_ops_f16() {
+    check_generic_ops::<f16>();
+}
+
+#[test]
+fn test_generic_ops_bf16() {
+    check_generic_ops::<bf16>();
+}
+
+/// The upcast ops equal the f32 op on the widened operands, rounded once.
+fn check_upcast_ops<A: HalfFloat>() {
+    let (a32, b32, c32) = (values(&[2, 3, 4]), values(&[2, 4, 5]), values(&[2, 3, 5]));
+    let (a, b, c): (ArrayD<A>, ArrayD<A>, ArrayD<A>) =
+        (downcast(&a32), downcast(&b32), downcast(&c32));
+    let same = |half: ArrayD<A>, single: ArrayD<f32>| assert_eq!(half, downcast(&single));
+
+    same(
+        einsum_upcast("bij,bjk->bik", &[&a, &b]).unwrap(),
+        einsum_ndarray_dyn("bij,bjk->bik", &[&a32, &b32]).unwrap(),
+    );
+    same(matmul_upcast(&a, &b).unwrap(), matmul(&a32, &b32).unwrap());
+    same(bmm_upcast(&a, &b).unwrap(), bmm(&a32, &b32).unwrap());
+    same(
+        baddbmm_upcast(&c, &a, &b, 0.5, 2.0).unwrap(),
+        baddbmm(&c32, &a32, &b32, 0.5, 2.0).unwrap(),
+    );
+    let [a2, b2, c2] = [&a, &b, &c].map(|t| t.index_axis(ndarray::Axis(0), 0).to_owned());
+    let [a2_32, b2_32, c2_32] =
+        [&a32, &b32, &c32].map(|t| t.index_axis(ndarray::Axis(0), 0).to_owned());
+    same(
+        addmm_upcast(&c2, &a2, &b2, 1.0, -1.0).unwrap(),
+        addmm(&c2_32, &a2_32, &b2_32, 1.0, -1.0).unwrap(),
+    );
+    same(
+        reduce_upcast(&a, "abc,abc->a").unwrap(),
+        reduce(&a32, "abc,abc->a").unwrap(),
+    );
+
+    // Errors are those of the f32 op
+    assert_eq!(
+        matmul_upcast(&a, &a).unwrap_err(),
+        matmul(&a32, &a32).unwrap_err()
+    );
+    assert!(einsum_upcast("ij,jk->ik", &[&a, &b]).is_err());
+}
+
+#[test]
+fn test_upcast_ops_f16() {
+    check_upcast_ops::<f16>();
+}
+
+#[test]
+fn test_upcast_ops_bf16() {
+    check_upcast_ops::<bf16>();
+}
+
+#[test]
+fn test_upcast_accumulates_in_f32() {
+    // 4096 ones sum to 4096 in f32, but a running f16 sum stops growing at 2048, where adding
+    // one is a tie that rounds back to even
+    let ones = ArrayD::from_elem(IxDyn(&[4096]), f16::ONE);
+    assert_eq!(
+        reduce_upcast(&ones, "a,a->").unwrap(),
+        ArrayD::from_elem(IxDyn(&[]), f16::from_f32(4096.0))
+    );
+    let naive = ones.iter().fold(f16::ZERO, |sum, &v| sum + v);
+    assert_eq!(naive, f16::from_f32(2048.0));
+
+    let row = ArrayD::from_elem(IxDyn(&[1, 4096]), bf16::ONE);
+    let column = ArrayD::from_elem(IxDyn(&[4096, 1]), bf16::ONE);
+    assert_eq!(
+        matmul_upcast(&row, &column).unwrap(),
+        ArrayD::from_elem(IxDyn(&[1, 1]), bf16::from_f32(4096.0))
+    );
+
+    // Rounding happens once, on the result
+    let third = array![f16::from_f32(1.0 / 3.0)].into_dyn();
+    let three = array![f16::from_f32(3.0)].into_dyn();
+    assert_eq!(
+        einsum_upcast("i,i->", &[&third, &three]).unwrap()[[]],
+        f16::from_f32(f16::from_f32(1.0 / 3.0).to_f32() * 3.0)
+    );
+}
@@ -0,0 +1,39 @@
+use RustOps::functions::index_select::{IndexSelectError, index_select};
+use ndarray::array;
+
+#[test]
+fn test_index_select_small() {
+    let x = array![[1, 2, 3], [4, 5, 6]].into_dyn();
+
+    assert_eq!(
+        index_select(&x, 0, &[1, 1, 0]).unwrap(),
+        array![[4, 5, 6], [4, 5, 6], [1, 2, 3]].into_dyn()
+    );
+    assert_eq!(
+        index_select(&x, -1, &[2, 0]).unwrap(),
+        array![[3, 1], [6, 4]].into_dyn()
+    );
+    assert_eq!(index_select(&x, 1, &[]).unwrap().shape(), &[2, 0]);
+}
+
+#[test]
+fn test_index_select_errors() {
+    let x = array![[1, 2, 3], [4, 5, 6]].into_dyn();
+
+    assert_eq!(
+        index_select(&x, 2, &[0]),
+        Err(IndexSelectError::InvalidDimension { dim: 2, ndim: 2 })
+    );
+    assert_eq!(
+        index_select(&x, 0, &[2]),
+        Err(IndexSelectError::IndexOutOfBounds {
+            index: 2,
+            dim: 0,
+            dim_size: 2
+        })
+    );
+    assert!(matches!(
+        index_select(&x, 1, &[-1]),
+        Err(IndexSelectError::IndexOutOfBounds { index: -1, .. })
+    ));
+}
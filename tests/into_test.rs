@@ -0,0 +1,148 @@
+use RustOps::functions::argmax::{ArgmaxError, argmax, argmax_into};
+use RustOps::functions::einsum::EinsumError;
+use RustOps::functions::expand::{expand_at_dim, expand_at_dim_into};
+use RustOps::functions::gather::{GatherError, gather, gather_into};
+use RustOps::functions::max::{MaxError, max, max_into};
+use RustOps::functions::reduce::{reduce, reduce_into};
+use RustOps::functions::reshape::{ReshapeError, reshape, reshape_into};
+use RustOps::functions::scatter::{scatter, scatter_into};
+use RustOps::functions::slicing::{
+    slice_last_dim, slice_last_dim_into, slice_second_dim, slice_second_dim_into,
+};
+use RustOps::functions::transpose::{transpose_dims, transpose_dims_into};
+use RustOps::rng::{Generator, randn};
+use ndarray::{ArrayD, Axis, IxDyn, ShapeBuilder, array, s};
+
+fn random(shape: &[usize], seed: u64) -> ArrayD<f32> {
+    randn(shape, &mut Generator::new(seed))
+}
+
+#[test]
+fn test_gather_and_scatter_into() {
+    let input = random(&[3, 4], 0);
+    let index = array![[2i64, 0, 1, 1], [0, 2, 2, 0]].into_dyn();
+    let mut out = ArrayD::zeros(IxDyn(&[2, 4]));
+    gather_into(&mut out.view_mut(), &input, 0, &index).unwrap();
+    assert_eq!(out, gather(&input, 0, &index).unwrap());
+
+    // A column-major output is filled in logical order as well
+    let mut fortran = ArrayD::zeros(IxDyn(&[2, 4]).f());
+    gather_into(&mut fortran.view_mut(), &input, -2, &index).unwrap();
+    assert_eq!(fortran, out);
+
+    assert_eq!(
+        gather_into(
+            &mut ArrayD::zeros(IxDyn(&[4, 2])).view_mut(),
+            &input,
+            0,
+            &index
+        ),
+        Err(GatherError::OutputShapeMismatch {
+            expected: vec![2, 4],
+            found: vec![4, 2]
+        })
+    );
+    let bad = array![[3i64]].into_dyn();
+    assert!(matches!(
+        gather_into(
+            &mut ArrayD::zeros(IxDyn(&[1, 1])).view_mut(),
+            &input,
+            0,
+            &bad
+        ),
+        Err(GatherError::IndexOutOfBounds { .. })
+    ));
+
+    // Scattering into a slice of a larger buffer leaves the rest untouched
+    let source = random(&[1, 3], 1);
+    let scatter_index = array![[1i64, 0, 1]].into_dyn();
+    let mut expected = ArrayD::<f32>::ones(IxDyn(&[2, 3]));
+    scatter(&mut expected, 0, &scatter_index, &source).unwrap();
+    let mut buffer = ArrayD::<f32>::ones(IxDyn(&[4, 3]));
+    scatter_into(
+        &mut buffer.slice_mut(s![1..3, ..]).into_dyn(),
+        0,
+        &scatter_index,
+        &source,
+    )
+    .unwrap();
+    assert_eq!(buffer.slice(s![1..3, ..]).into_dyn(), expected);
+    assert!(buffer.index_axis(Axis(0), 0).iter().all(|&v| v == 1.0));
+}
+
+#[test]
+fn test_view_ops_into() {
+    let x = random(&[2, 3, 4], 2);
+
+    let mut transposed = ArrayD::zeros(IxDyn(&[4, 3, 2]));
+    transpose_dims_into(&mut transposed.view_mut(), &x, 0, 2);
+    assert_eq!(transposed, transpose_dims(&x, 0, 2));
+
+    let mut expanded = ArrayD::zeros(IxDyn(&[2, 5, 3, 4]));
+    expand_at_dim_into(&mut expanded.view_mut(), &x, 1).unwrap();
+    assert_eq!(expanded, expand_at_dim(&x, 1, 5).unwrap());
+    assert!(
+        expand_at_dim_into(&mut ArrayD::zeros(IxDyn(&[2, 5, 3, 3])).view_mut(), &x, 1).is_err()
+    );
+
+    // Reshaping copies in logical order, also from a transposed input
+    let mut flat = ArrayD::zeros(IxDyn(&[6, 4]));
+    reshape_into(&mut flat.view_mut(), &transposed).unwrap();
+    assert_eq!(flat, reshape(&transposed, &[6, 4]).unwrap());
+    assert_eq!(
+        reshape_into(&mut ArrayD::zeros(IxDyn(&[5])).view_mut(), &x),
+        Err(ReshapeError::IncompatibleShape)
+    );
+
+    let mut last = ArrayD::zeros(IxDyn(&[2, 3, 1]));
+    slice_last_dim_into(&mut last.view_mut(), &x).unwrap();
+    assert_eq!(last, slice_last_dim(&x).unwrap());
+    let mut second = ArrayD::zeros(IxDyn(&[2, 2, 4]));
+    slice_second_dim_into(&mut second.view_mut(), &x, 2).unwrap();
+    assert_eq!(second, slice_second_dim(&x, 2).unwrap());
+    // The amount is clamped like in `slice_second_dim`
+    let mut all = ArrayD::zeros(IxDyn(&[2, 3, 4]));
+    slice_second_dim_into(&mut all.view_mut(), &x, 10).unwrap();
+    assert_eq!(all, x);
+    assert!(slice_second_dim_into(&mut second.view_mut(), &x, 3).is_err());
+}
+
+#[test]
+fn test_reductions_into() {
+    let x = random(&[3, 4, 5], 3);
+    let (expected_values, expected_indices) = max(&x, 1).unwrap();
+
+    let mut values = ArrayD::zeros(IxDyn(&[3, 5]));
+    let mut indices = ArrayD::zeros(IxDyn(&[3, 5]));
+    max_into(&mut values.view_mut(), &mut indices.view_mut(), &x, 1).unwrap();
+    assert_eq!(values, expected_values);
+    assert_eq!(indices, expected_indices);
+
+    // keepdim shapes are accepted too
+    let mut kept = ArrayD::zeros(IxDyn(&[3, 1, 5]));
+    argmax_into(&mut kept.view_mut(), &x, 1).unwrap();
+    assert_eq!(kept, argmax(&x, Some(1), true).unwrap());
+
+    assert_eq!(
+        argmax_into(&mut ArrayD::zeros(IxDyn(&[3, 4])).view_mut(), &x, 1),
+        Err(ArgmaxError::OutputShapeMismatch {
+            expected: vec![3, 5],
+            found: vec![3, 4]
+        })
+    );
+    assert_eq!(
+        max_into(&mut values.view_mut(), &mut indices.view_mut(), &x, 3),
+        Err(MaxError::InvalidAxis(3))
+    );
+
+    let mut sums = ArrayD::zeros(IxDyn(&[5, 3]).f());
+    reduce_into(&mut sums.view_mut(), &x, "abc,abc->ca").unwrap();
+    assert_eq!(sums, reduce(&x, "abc,abc->ca").unwrap());
+    assert_eq!(
+        reduce_into(&mut sums.view_mut(), &x, "abc,abc->a"),
+        Err(EinsumError::OutputShapeMismatch {
+            expected: vec![3],
+            found: vec![5, 3]
+        })
+    );
+}
@@ -0,0 +1,77 @@
+import com.rustops.RustOps;
+import java.nio.ByteBuffer;
+import java.nio.ByteOrder;
+import java.nio.FloatBuffer;
+import java.nio.LongBuffer;
+
+/** Calls the native methods from a real JVM. Run by tests/jni_test.rs. */
+public class JniSmoke {
+    static FloatBuffer floats(float... values) {
+        FloatBuffer buffer = ByteBuffer.allocateDirect(values.length * 4)
+                .order(ByteOrder.nativeOrder())
+                .asFloatBuffer();
+        buffer.put(values);
+        return buffer;
+    }
+
+    static LongBuffer longs(long... values) {
+        LongBuffer buffer = ByteBuffer.allocateDirect(values.length * 8)
+                .order(ByteOrder.nativeOrder())
+                .asLongBuffer();
+        buffer.put(values);
+        return buffer;
+    }
+
+    static void check(boolean condition, String what) {
+        if (!condition) {
+            throw new AssertionError(what);
+        }
+    }
+
+    public static void main(String[] args) {
+        long x = RustOps.fromFloatBuffer(floats(1, 5, 2, 7, 0, 3), new long[] {2, 3});
+        long[] shape = RustOps.shape(x);
+        check(shape.length == 2 && shape[0] == 2 && shape[1] == 3, "shape");
+
+        long indices = RustOps.argmax(x, -1, true);
+        LongBuffer argmax = longs(0, 0);
+        RustOps.toLongBuffer(indices, argmax);
+        check(argmax.get(0) == 1 && argmax.get(1) == 0, "argmax");
+
+        long gathered = RustOps.gather(x, 1, indices);
+        FloatBuffer maxima = floats(0, 0);
+        RustOps.toFloatBuffer(gathered, maxima);
+        check(maxima.get(0) == 5 && maxima.get(1) == 7, "gather");
+
+        long product = RustOps.einsum("ij,kj->ik", new long[] {x, x});
+        check(RustOps.shape(product)[1] == 2, "einsum");
+
+        long probabilities = RustOps.softmax(x, 1);
+        FloatBuffer softmax = floats(0, 0, 0, 0, 0, 0);
+        RustOps.toFloatBuffer(probabilities, softmax);
+        check(Math.abs(softmax.get(0) + softmax.get(1) + softmax.get(2) - 1) < 1e-6, "softmax");
+
+        long bad = RustOps.fromLongBuffer(longs(5, 0), new long[] {2, 1});
+        try {
+            RustOps.gather(x, 1, bad);
+            check(false, "gather out of bounds");
+        } catch (IndexOutOfBoundsException expected) {
+            check(expected.getMessage().contains("out of bounds"), expected.getMessage());
+        }
+        try {
+            RustOps.reshape(x, new long[] {4, -1});
+            check(false, "reshape");
+        } catch (IllegalArgumentException expected) {
+        }
+        try {
+            RustOps.shape(0);
+            check(false, "null handle");
+        } catch (NullPointerException expected) {
+        }
+
+        for (long tensor : new long[] {x, indices, gathered, product, probabilities, bad}) {
+            RustOps.free(tensor);
+        }
+        System.out.println("ok");
+    }
+}
@@ -0,0 +1,528 @@
+#![cfg(feature = "jni")]
+
+use RustOps::jni::sys::{
+    JNI_FALSE, JNI_TRUE, JNIEnv, JNINativeInterface, jboolean, jclass, jlong, jobject, jsize,
+};
+use RustOps::jni::*;
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::ptr;
+
+/// The Java objects the mock JVM hands out.
+enum Object {
+    Class(CString),
+    String(CString),
+    LongArray(RefCell<Vec<i64>>),
+    DirectBuffer {
+        address: *mut c_void,
+        capacity: jlong,
+    },
+    HeapBuffer,
+}
+
+/// A JVM-free stand-in for the JNI environment. `env` must stay the first field: the mock
+/// functions cast the `JNIEnv` pointer they receive back to the `MockJvm`.
+#[repr(C)]
+struct MockJvm {
+    env: JNIEnv,
+    table: Box<JNINativeInterface>,
+    // Boxed so that object references stay valid as the list grows
+    #[allow(clippy::vec_box)]
+    objects: RefCell<Vec<Box<Object>>>,
+    thrown: RefCell<Option<(String, String)>>,
+}
+
+unsafe fn jvm<'a>(env: *mut JNIEnv) -> &'a MockJvm {
+    unsafe { &*(env as *const MockJvm) }
+}
+
+unsafe fn object<'a>(object: jobject) -> &'a Object {
+    unsafe { &*(object as *const Object) }
+}
+
+unsafe fn long_array<'a>(array: jobject) -> &'a RefCell<Vec<i64>> {
+    match unsafe { object(array) } {
+        Object::LongArray(values) => values,
+        _ => panic!("not a long[]"),
+    }
+}
+
+unsafe extern "system" fn find_class(env: *mut JNIEnv, name: *const c_char) -> jclass {
+    let name = unsafe { CStr::from_ptr(name) }.to_owned();
+    unsafe { jvm(env) }.add(Object::Class(name))
+}
+
+unsafe extern "system" fn throw_new(
+    env: *mut JNIEnv,
+    class: jclass,
+    message: *const c_char,
+) -> i32 {
+    let Object::Class(class) = (unsafe { object(class) }) else {
+        panic!("not a class");
+    };
+    let message = unsafe { CStr::from_ptr(message) };
+    *unsafe { jvm(env) }.thrown.borrow_mut() = Some((
+        class.to_str().unwrap().to_owned(),
+        message.to_str().unwrap().to_owned(),
+    ));
+    0
+}
+
+unsafe extern "system" fn delete_local_ref(_env: *mut JNIEnv, _object: jobject) {}
+
+unsafe extern "system" fn get_string_utf_chars(
+    _env: *mut JNIEnv,
+    string: jobject,
+    _is_copy: *mut jboolean,
+) -> *const c_char {
+    match unsafe { object(string) } {
+        Object::String(string) => string.as_ptr(),
+        _ => panic!("not a String"),
+    }
+}
+
+unsafe extern "system" fn release_string_utf_chars(
+    _env: *mut JNIEnv,
+    _string: jobject,
+    _chars: *const c_char,
+) {
+}
+
+unsafe extern "system" fn get_array_length(_env: *mut JNIEnv, array: jobject) -> jsize {
+    unsafe { long_array(array) }.borrow().len() as jsize
+}
+
+unsafe extern "system" fn new_long_array(env: *mut JNIEnv, len: jsize) -> jobject {
+    let values = vec![0; len as usize];
+    unsafe { jvm(env) }.add(Object::LongArray(RefCell::new(values)))
+}
+
+unsafe extern "system" fn get_long_array_region(
+    _env: *mut JNIEnv,
+    array: jobject,
+    start: jsize,
+    len: jsize,
+    buf: *mut jlong,
+) {
+    let values = unsafe { long_array(array) }.borrow();
+    let region = &values[start as usize..(start + len) as usize];
+    unsafe { ptr::copy_nonoverlapping(region.as_ptr(), buf, region.len()) };
+}
+
+unsafe extern "system" fn set_long_array_region(
+    _env: *mut JNIEnv,
+    array: jobject,
+    start: jsize,
+    len: jsize,
+    buf: *const jlong,
+) {
+    let mut values = unsafe { long_array(array) }.borrow_mut();
+    let region = &mut values[start as usize..(start + len) as usize];
+    unsafe { ptr::copy_nonoverlapping(buf, region.as_mut_ptr(), region.len()) };
+}
+
+unsafe extern "system" fn exception_check(env: *mut JNIEnv) -> jboolean {
+    if unsafe { jvm(env) }.thrown.borrow().is_some() {
+        JNI_TRUE
+    } else {
+        JNI_FALSE
+    }
+}
+
+unsafe extern "system" fn get_direct_buffer_address(
+    _env: *mut JNIEnv,
+    buffer: jobject,
+) -> *mut c_void {
+    match unsafe { object(buffer) } {
+        Object::DirectBuffer { address, .. } => *address,
+        _ => ptr::null_mut(),
+    }
+}
+
+unsafe extern "system" fn get_direct_buffer_capacity(_env: *mut JNIEnv, buffer: jobject) -> jlong {
+    match unsafe { object(buffer) } {
+        Object::DirectBuffer { capacity, .. } => *capacity,
+        _ => -1,
+    }
+}
+
+impl MockJvm {
+    fn new() -> Box<Self> {
+        let mut table = Box::new(JNINativeInterface::empty());
+        table.find_class = Some(find_class);
+        table.throw_new = Some(throw_new);
+        table.delete_local_ref = Some(delete_local_ref);
+        table.get_string_utf_chars = Some(get_string_utf_chars);
+        table.release_string_utf_chars = Some(release_string_utf_chars);
+        table.get_array_length = Some(get_array_length);
+        table.new_long_array = Some(new_long_array);
+        table.get_long_array_region = Some(get_long_array_region);
+        table.set_long_array_region = Some(set_long_array_region);
+        table.exception_check = Some(exception_check);
+        table.get_direct_buffer_address = Some(get_direct_buffer_address);
+        table.get_direct_buffer_capacity = Some(get_direct_buffer_capacity);
+        Self::with_table(table)
+    }
+
+    fn with_table(table: Box<JNINativeInterface>) -> Box<Self> {
+        Box::new(MockJvm {
+            env: &*table,
+            table,
+            objects: RefCell::new(Vec::new()),
+            thrown: RefCell::new(None),
+        })
+    }
+
+    fn env(&self) -> *mut JNIEnv {
+        &self.env as *const JNIEnv as *mut JNIEnv
+    }
+
+    fn add(&self, object: Object) -> jobject {
+        let mut object = Box::new(object);
+        let pointer = &mut *object as *mut Object as jobject;
+        self.objects.borrow_mut().push(object);
+        pointer
+    }
+
+    fn long_array(&self, values: &[i64]) -> jobject {
+        self.add(Object::LongArray(RefCell::new(values.to_vec())))
+    }
+
+    fn string(&self, value: &str) -> jobject {
+        self.add(Object::String(CString::new(value).unwrap()))
+    }
+
+    /// A direct buffer over `values`, which must outlive its use.
+    fn buffer<T>(&self, values: &mut [T]) -> jobject {
+        self.add(Object::DirectBuffer {
+            address: values.as_mut_ptr() as *mut c_void,
+            capacity: values.len() as jlong,
+        })
+    }
+
+    fn read_long_array(&self, array: jobject) -> Vec<i64> {
+        assert!(!array.is_null());
+        unsafe { long_array(array) }.borrow().clone()
+    }
+
+    fn take_thrown(&self) -> Option<(String, String)> {
+        self.thrown.borrow_mut().take()
+    }
+
+    /// Asserts that the last call succeeded.
+    fn ok(&self) {
+        assert_eq!(self.take_thrown(), None);
+    }
+
+    /// Asserts that the last call threw `class` and returns the message.
+    fn threw(&self, class: &str) -> String {
+        let (thrown, message) = self.take_thrown().expect("an exception was thrown");
+        assert_eq!(thrown, class, "{message}");
+        message
+    }
+
+    fn floats(&self, mut values: Vec<f32>, shape: &[i64]) -> jlong {
+        let data = self.buffer(&mut values);
+        let shape = self.long_array(shape);
+        let handle =
+            unsafe { Java_com_rustops_RustOps_fromFloatBuffer(self.env(), class(), data, shape) };
+        self.ok();
+        handle
+    }
+
+    fn longs(&self, mut values: Vec<i64>, shape: &[i64]) -> jlong {
+        let data = self.buffer(&mut values);
+        let shape = self.long_array(shape);
+        let handle =
+            unsafe { Java_com_rustops_RustOps_fromLongBuffer(self.env(), class(), data, shape) };
+        self.ok();
+        handle
+    }
+
+    fn shape(&self, tensor: jlong) -> Vec<i64> {
+        let shape = unsafe { Java_com_rustops_RustOps_shape(self.env(), class(), tensor) };
+        self.ok();
+        self.read_long_array(shape)
+    }
+
+    fn to_floats(&self, tensor: jlong) -> Vec<f32> {
+        let numel = self.shape(tensor).iter().product::<i64>() as usize;
+        let mut values = vec![f32::NAN; numel];
+        let out = self.buffer(&mut values);
+        unsafe { Java_com_rustops_RustOps_toFloatBuffer(self.env(), class(), tensor, out) };
+        self.ok();
+        values
+    }
+
+    fn to_longs(&self, tensor: jlong) -> Vec<i64> {
+        let numel = self.shape(tensor).iter().product::<i64>() as usize;
+        let mut values = vec![-1; numel];
+        let out = self.buffer(&mut values);
+        unsafe { Java_com_rustops_RustOps_toLongBuffer(self.env(), class(), tensor, out) };
+        self.ok();
+        values
+    }
+
+    fn free(&self, tensors: &[jlong]) {
+        for &tensor in tensors {
+            unsafe { Java_com_rustops_RustOps_free(self.env(), class(), tensor) };
+        }
+    }
+}
+
+/// The `RustOps` class reference passed to static native methods, unused by the bindings.
+fn class() -> jclass {
+    ptr::null_mut()
+}
+
+#[test]
+fn test_buffer_roundtrip() {
+    let jvm = MockJvm::new();
+    let x = jvm.floats(vec![1.0, -2.5, 3.0, 0.0, 7.25, -1.0], &[2, 3]);
+    assert_eq!(jvm.shape(x), vec![2, 3]);
+    assert_eq!(jvm.to_floats(x), vec![1.0, -2.5, 3.0, 0.0, 7.25, -1.0]);
+
+    let y = jvm.longs(vec![4, -5, i64::MAX], &[3]);
+    assert_eq!(jvm.shape(y), vec![3]);
+    assert_eq!(jvm.to_longs(y), vec![4, -5, i64::MAX]);
+    jvm.free(&[x, y, 0]);
+}
+
+#[test]
+fn test_from_buffer_ignores_extra_capacity() {
+    let jvm = MockJvm::new();
+    let x = jvm.floats(vec![1.0, 2.0, 3.0, 4.0, 5.0], &[2, 2]);
+    assert_eq!(jvm.to_floats(x), vec![1.0, 2.0, 3.0, 4.0]);
+    jvm.free(&[x]);
+}
+
+#[test]
+fn test_gather_and_scatter() {
+    let jvm = MockJvm::new();
+    let x = jvm.floats(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let index = jvm.longs(vec![2, 0, 1, 1], &[2, 2]);
+
+    let gathered = unsafe { Java_com_rustops_RustOps_gather(jvm.env(), class(), x, 1, index) };
+    jvm.ok();
+    assert_eq!(jvm.shape(gathered), vec![2, 2]);
+    assert_eq!(jvm.to_floats(gathered), vec![3.0, 1.0, 5.0, 5.0]);
+
+    let target = jvm.floats(vec![0.0; 6], &[2, 3]);
+    let src = jvm.floats(vec![10.0, 20.0, 30.0, 40.0], &[2, 2]);
+    let scattered =
+        unsafe { Java_com_rustops_RustOps_scatter(jvm.env(), class(), target, 1, index, src) };
+    jvm.ok();
+    assert_eq!(
+        jvm.to_floats(scattered),
+        vec![20.0, 0.0, 10.0, 0.0, 40.0, 0.0]
+    );
+    // The target is left untouched
+    assert_eq!(jvm.to_floats(target), vec![0.0; 6]);
+    jvm.free(&[x, index, gathered, target, src, scattered]);
+}
+
+#[test]
+fn test_argmax() {
+    let jvm = MockJvm::new();
+    let x = jvm.floats(vec![1.0, 5.0, 2.0, 7.0, 0.0, 7.0], &[2, 3]);
+
+    let rows = unsafe { Java_com_rustops_RustOps_argmax(jvm.env(), class(), x, -1, JNI_TRUE) };
+    jvm.ok();
+    assert_eq!(jvm.shape(rows), vec![2, 1]);
+    assert_eq!(jvm.to_longs(rows), vec![1, 0]);
+
+    let columns = unsafe { Java_com_rustops_RustOps_argmax(jvm.env(), class(), x, 0, JNI_FALSE) };
+    jvm.ok();
+    assert_eq!(jvm.to_longs(columns), vec![1, 0, 1]);
+
+    let all = unsafe { Java_com_rustops_RustOps_argmaxAll(jvm.env(), class(), x) };
+    jvm.ok();
+    assert_eq!(jvm.shape(all), Vec::<i64>::new());
+    assert_eq!(jvm.to_longs(all), vec![3]);
+
+    unsafe { Java_com_rustops_RustOps_argmax(jvm.env(), class(), x, 2, JNI_FALSE) };
+    jvm.threw("java/lang/IllegalArgumentException");
+    jvm.free(&[x, rows, columns, all]);
+}
+
+#[test]
+fn test_einsum() {
+    let jvm = MockJvm::new();
+    let a = jvm.floats(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]);
+    let b = jvm.floats(vec![1.0, 0.0, 0.0, 1.0, 1.0, 1.0], &[3, 2]);
+    let equation = jvm.string("ij,jk->ik");
+    let operands = jvm.long_array(&[a, b]);
+
+    let c = unsafe { Java_com_rustops_RustOps_einsum(jvm.env(), class(), equation, operands) };
+    jvm.ok();
+    assert_eq!(jvm.shape(c), vec![2, 2]);
+    assert_eq!(jvm.to_floats(c), vec![4.0, 5.0, 10.0, 11.0]);
+
+    let ints = jvm.longs(vec![1, 2, 3], &[3]);
+    let mixed = jvm.long_array(&[a, ints]);
+    let equation = jvm.string("ij,j->i");
+    unsafe { Java_com_rustops_RustOps_einsum(jvm.env(), class(), equation, mixed) };
+    let message = jvm.threw("java/lang/IllegalArgumentException");
+    assert!(message.contains("operands"), "{message}");
+    jvm.free(&[a, b, c, ints]);
+}
+
+#[test]
+fn test_reshape_and_rearrange() {
+    let jvm = MockJvm::new();
+    let values: Vec<f32> = (0..24).map(|v| v as f32).collect();
+    let x = jvm.floats(values.clone(), &[2, 3, 4]);
+
+    let shape = jvm.long_array(&[4, -1]);
+    let reshaped = unsafe { Java_com_rustops_RustOps_reshape(jvm.env(), class(), x, shape) };
+    jvm.ok();
+    assert_eq!(jvm.shape(reshaped), vec![4, 6]);
+    assert_eq!(jvm.to_floats(reshaped), values);
+
+    // batch=2 mems=3 flag=4 -> mems=3 (batch flag)=8
+    let rearranged =
+        unsafe { Java_com_rustops_RustOps_rearrangeBatchMemsFlag(jvm.env(), class(), x) };
+    jvm.ok();
+    assert_eq!(jvm.shape(rearranged), vec![3, 8]);
+    let out = jvm.to_floats(rearranged);
+    assert_eq!(&out[..8], &[0.0, 1.0, 2.0, 3.0, 12.0, 13.0, 14.0, 15.0]);
+
+    let shape = jvm.long_array(&[5, -1]);
+    unsafe { Java_com_rustops_RustOps_reshape(jvm.env(), class(), x, shape) };
+    jvm.threw("java/lang/IllegalArgumentException");
+    jvm.free(&[x, reshaped, rearranged]);
+}
+
+#[test]
+fn test_softmax() {
+    let jvm = MockJvm::new();
+    let x = jvm.floats(vec![1.0, 2.0, 3.0, 0.0, 0.0, 0.0], &[2, 3]);
+    let probabilities = unsafe { Java_com_rustops_RustOps_softmax(jvm.env(), class(), x, 1) };
+    jvm.ok();
+    let out = jvm.to_floats(probabilities);
+    for row in out.chunks(3) {
+        approx::assert_abs_diff_eq!(row.iter().sum::<f32>(), 1.0, epsilon = 1e-6);
+    }
+    assert!(out[0] < out[1] && out[1] < out[2]);
+    approx::assert_abs_diff_eq!(out[3], 1.0 / 3.0, epsilon = 1e-6);
+
+    let ints = jvm.longs(vec![1, 2], &[2]);
+    let result = unsafe { Java_com_rustops_RustOps_softmax(jvm.env(), class(), ints, 0) };
+    assert_eq!(result, 0);
+    jvm.threw("java/lang/UnsupportedOperationException");
+    jvm.free(&[x, probabilities, ints]);
+}
+
+#[test]
+fn test_exception_mapping() {
+    let jvm = MockJvm::new();
+    let x = jvm.floats(vec![1.0, 2.0, 3.0, 4.0], &[2, 2]);
+
+    assert!(unsafe { Java_com_rustops_RustOps_shape(jvm.env(), class(), 0) }.is_null());
+    jvm.threw("java/lang/NullPointerException");
+
+    let index = jvm.longs(vec![0, 2], &[2, 1]);
+    let result = unsafe { Java_com_rustops_RustOps_gather(jvm.env(), class(), x, 1, index) };
+    assert_eq!(result, 0);
+    let message = jvm.threw("java/lang/IndexOutOfBoundsException");
+    assert!(message.contains("out of bounds"), "{message}");
+
+    let negative = jvm.longs(vec![-1, 0], &[2, 1]);
+    unsafe { Java_com_rustops_RustOps_gather(jvm.env(), class(), x, 1, negative) };
+    jvm.threw("java/lang/IndexOutOfBoundsException");
+
+    // The index must hold longs
+    unsafe { Java_com_rustops_RustOps_gather(jvm.env(), class(), x, 1, x) };
+    jvm.threw("java/lang/IllegalArgumentException");
+
+    let heap = jvm.add(Object::HeapBuffer);
+    let shape = jvm.long_array(&[2]);
+    unsafe { Java_com_rustops_RustOps_fromFloatBuffer(jvm.env(), class(), heap, shape) };
+    let message = jvm.threw("java/lang/IllegalArgumentException");
+    assert!(message.contains("direct"), "{message}");
+
+    unsafe { Java_com_rustops_RustOps_fromFloatBuffer(jvm.env(), class(), ptr::null_mut(), shape) };
+    jvm.threw("java/lang/NullPointerException");
+
+    let mut small = vec![0.0f32; 3];
+    let out = jvm.buffer(&mut small);
+    unsafe { Java_com_rustops_RustOps_toFloatBuffer(jvm.env(), class(), x, out) };
+    jvm.threw("java/lang/IllegalArgumentException");
+
+    let mut longs = vec![0i64; 4];
+    let out = jvm.buffer(&mut longs);
+    unsafe { Java_com_rustops_RustOps_toLongBuffer(jvm.env(), class(), x, out) };
+    jvm.threw("java/lang/IllegalArgumentException");
+
+    let mut data = vec![0.0f32; 4];
+    let data = jvm.buffer(&mut data);
+    let negative_shape = jvm.long_array(&[-2, 2]);
+    unsafe { Java_com_rustops_RustOps_fromFloatBuffer(jvm.env(), class(), data, negative_shape) };
+    jvm.threw("java/lang/IllegalArgumentException");
+    jvm.free(&[x, index, negative]);
+}
+
+#[test]
+fn test_missing_functions_do_not_crash() {
+    // Without FindClass nothing can be thrown, so the call just returns the default
+    let jvm = MockJvm::with_table(Box::new(JNINativeInterface::empty()));
+    let shape = unsafe { Java_com_rustops_RustOps_shape(jvm.env(), class(), 0) };
+    assert!(shape.is_null());
+    assert_eq!(jvm.take_thrown(), None);
+}
+
+fn find_tool(name: &str) -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(home) = std::env::var_os("JAVA_HOME") {
+        candidates.push(Path::new(&home).join("bin").join(name));
+    }
+    candidates.push(PathBuf::from(name));
+    candidates.into_iter().find(|tool| {
+        Command::new(tool)
+            .arg("-version")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    })
+}
+
+#[test]
+fn test_real_jvm() {
+    let (Some(javac), Some(java)) = (find_tool("javac"), find_tool("java")) else {
+        eprintln!("SKIP: no JDK found");
+        return;
+    };
+    // The test binary sits next to the cdylib in target/<profile>/deps
+    let exe = std::env::current_exe().unwrap();
+    let deps = exe.parent().unwrap();
+    if !deps.join("libRustOps.so").exists() {
+        eprintln!("SKIP: libRustOps.so not found in {}", deps.display());
+        return;
+    }
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let classes = Path::new(env!("CARGO_TARGET_TMPDIR")).join("jni-classes");
+    let status = Command::new(javac)
+        .arg("-d")
+        .arg(&classes)
+        .arg(root.join("java/com/rustops/RustOps.java"))
+        .arg(root.join("tests/jni/JniSmoke.java"))
+        .status()
+        .unwrap();
+    assert!(status.success(), "javac failed");
+
+    let output = Command::new(java)
+        .arg(format!("-Djava.library.path={}", deps.display()))
+        .arg("-cp")
+        .arg(&classes)
+        .arg("JniSmoke")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ok");
+}
@@ -0,0 +1,244 @@
+use RustOps::functions::abs::abs_ndarray;
+use RustOps::functions::expand::expand_at_dim;
+use RustOps::functions::reduce::reduce;
+use RustOps::functions::reshape::ReshapeError;
+use RustOps::functions::transpose::transpose_dims;
+use RustOps::lazy::{Graph, LazyError};
+use RustOps::nn::functional::softmax;
+use RustOps::rng::{Generator, randn};
+use approx::assert_abs_diff_eq;
+use ndarray::{ArrayD, arr0, array};
+
+fn random(shape: &[usize], seed: u64) -> ArrayD<f32> {
+    randn(shape, &mut Generator::new(seed))
+}
+
+#[test]
+fn test_fused_chain_matches_eager() {
+    let x = random(&[3, 4], 0);
+    let w = random(&[5, 3, 4], 1);
+
+    // Eager: every step allocates
+    let expanded = expand_at_dim(&abs_ndarray(&x), 0, 5).unwrap();
+    let expected = reduce(&(&expanded * &w), "bij,bij->bi").unwrap();
+
+    let graph = Graph::new();
+    let (lx, lw) = (graph.input(x), graph.input(w));
+    let y = lx
+        .abs()
+        .expand_at_dim(0, 5)
+        .unwrap()
+        .mul(&lw)
+        .unwrap()
+        .sum(&[-1], false)
+        .unwrap();
+    let plan = y.plan();
+    assert_eq!(plan.num_kernels(), 1);
+    assert_abs_diff_eq!(plan.eval(), expected, epsilon = 1e-5);
+    assert_eq!(y.shape(), vec![5, 3]);
+}
+
+#[test]
+fn test_views_are_strides() {
+    let x = random(&[2, 3, 4], 2);
+    let b = random(&[4, 3], 3);
+
+    let graph = Graph::new();
+    let (lx, lb) = (graph.input(x.clone()), graph.input(b.clone()));
+    let y = lx.transpose(1, 2).unwrap().add(&lb).unwrap().relu();
+    let plan = y.plan();
+    assert_eq!(plan.num_kernels(), 1);
+    let expected = (&transpose_dims(&x, 1, 2) + &b).mapv(|v| v.max(0.0));
+    assert_eq!(plan.eval(), expected);
+
+    // Reshaping an input, and permuting the result, stay single pass as well
+    let z = lx
+        .reshape(&[6, -1])
+        .unwrap()
+        .permute(&[1, 0])
+        .unwrap()
+        .mul_scalar(2.0);
+    assert_eq!(z.plan().num_kernels(), 1);
+    let expected = x.to_shape((6, 4)).unwrap().t().mapv(|v| v * 2.0).into_dyn();
+    assert_eq!(z.eval(), expected);
+
+    // A bare view still produces an owned, standard-layout copy
+    let t = lx.transpose(0, 2).unwrap().eval();
+    assert_eq!(t, transpose_dims(&x, 0, 2));
+    assert!(t.is_standard_layout());
+}
+
+#[test]
+fn test_unexpressible_reshape_materializes() {
+    let x = random(&[3, 4], 4);
+    let graph = Graph::new();
+    let lx = graph.input(x.clone());
+    // Flattening a transposed result cannot be expressed with strides
+    let y = lx.exp().transpose(0, 1).unwrap().reshape(&[-1]).unwrap();
+    let plan = y.plan();
+    assert_eq!(plan.num_kernels(), 2);
+    let expected: Vec<f32> = transpose_dims(&x.mapv(f32::exp), 0, 1)
+        .iter()
+        .copied()
+        .collect();
+    assert_eq!(
+        plan.eval(),
+        ArrayD::from_shape_vec(vec![12], expected).unwrap()
+    );
+
+    // Flattening a broadcast input interleaves repeated and real elements
+    let row = graph.input(array![1.0f32, 2.0].into_dyn());
+    let flat = row.expand(&[3, 2]).unwrap().reshape(&[6]).unwrap();
+    assert_eq!(flat.plan().num_kernels(), 2);
+    assert_eq!(
+        flat.eval(),
+        array![1.0f32, 2.0, 1.0, 2.0, 1.0, 2.0].into_dyn()
+    );
+    // Merging only repeated dimensions keeps stride 0
+    let merged = row.expand(&[2, 3, 2]).unwrap().reshape(&[6, 2]).unwrap();
+    assert_eq!(merged.plan().num_kernels(), 1);
+    assert_eq!(merged.eval(), row.expand(&[6, 2]).unwrap().eval());
+}
+
+#[test]
+fn test_shared_nodes_and_softmax() {
+    let x = random(&[4, 6], 5);
+    let graph = Graph::new();
+    let lx = graph.input(x.clone());
+
+    // Inputs and their views may be read twice within one kernel
+    let square = lx.transpose(0, 1).unwrap();
+    let y = square.mul(&square).unwrap();
+    assert_eq!(y.plan().num_kernels(), 1);
+
+    // Computed nodes read twice are evaluated once
+    let e = lx.exp();
+    let y = e.mul(&e).unwrap();
+    assert_eq!(y.plan().num_kernels(), 2);
+    assert_abs_diff_eq!(y.eval(), x.mapv(|v| (2.0 * v).exp()), epsilon = 1e-4);
+
+    let max = lx.amax(&[-1], true).unwrap();
+    let e = lx.sub(&max).unwrap().exp();
+    let y = e.div(&e.sum(&[-1], true).unwrap()).unwrap();
+    let plan = y.plan();
+    // amax, the shared exponential, its sum and the division
+    assert_eq!(plan.num_kernels(), 4);
+    assert_abs_diff_eq!(plan.eval(), softmax(&x, -1).unwrap(), epsilon = 1e-6);
+}
+
+#[test]
+fn test_reductions() {
+    let x = array![[[1.0f32, -2.0], [3.0, 4.0]], [[5.0, 6.0], [-7.0, 8.0]]].into_dyn();
+    let graph = Graph::new();
+    let lx = graph.input(x);
+
+    assert_eq!(
+        lx.sum(&[0, 2], false).unwrap().eval(),
+        array![10.0f32, 8.0].into_dyn()
+    );
+    assert_eq!(
+        lx.mean(&[1], true).unwrap().eval(),
+        array![[[2.0f32, 1.0]], [[-1.0, 7.0]]].into_dyn()
+    );
+    assert_eq!(
+        lx.amax(&[-1, 0], false).unwrap().eval(),
+        array![6.0f32, 8.0].into_dyn()
+    );
+    assert_eq!(lx.sum(&[], false).unwrap().eval(), arr0(18.0f32).into_dyn());
+    assert_eq!(
+        lx.abs().sum(&[], true).unwrap().eval(),
+        array![[[36.0f32]]].into_dyn()
+    );
+
+    // NaN propagates through amax like torch.amax
+    let nan = graph.input(array![[1.0f32, f32::NAN], [2.0, 3.0]].into_dyn());
+    let m = nan.amax(&[1], false).unwrap().eval();
+    assert!(m[0].is_nan());
+    assert_eq!(m[1], 3.0);
+
+    // A reduction feeding an element-wise op is its own kernel
+    let centered = lx.sub(&lx.mean(&[], true).unwrap()).unwrap();
+    assert_eq!(centered.plan().num_kernels(), 2);
+    assert_eq!(centered.eval()[[0, 0, 0]], 1.0 - 18.0 / 8.0);
+}
+
+#[test]
+fn test_plan_dump() {
+    let graph = Graph::new();
+    let x = graph.input(ArrayD::zeros(vec![3]));
+    let w = graph.input(ArrayD::zeros(vec![2, 3]));
+    let y = x
+        .abs()
+        .expand_at_dim(0, 2)
+        .unwrap()
+        .mul(&w)
+        .unwrap()
+        .sum(&[1], false)
+        .unwrap();
+    let dump = y.plan().to_string();
+    assert_eq!(
+        dump,
+        "input0: [3]\n\
+         input1: [2, 3]\n\
+         kernel0: [2, 3] sum over [1] -> [2]\n  \
+         %0 = load input0 strides [0, 1]\n  \
+         %0 = abs %0\n  \
+         %1 = load input1 strides [3, 1]\n  \
+         %0 = mul %0, %1\n  \
+         store %0\n"
+    );
+}
+
+#[test]
+fn test_lazy_errors() {
+    let graph = Graph::new();
+    let x = graph.input(ArrayD::zeros(vec![2, 3]));
+    let y = graph.input(ArrayD::zeros(vec![4]));
+
+    assert_eq!(
+        x.add(&y).unwrap_err(),
+        LazyError::Broadcast {
+            left: vec![2, 3],
+            right: vec![4]
+        }
+    );
+    let other = Graph::new();
+    let z = other.input(ArrayD::zeros(vec![2, 3]));
+    assert_eq!(x.mul(&z).unwrap_err(), LazyError::DifferentGraphs);
+
+    assert_eq!(
+        x.transpose(0, 2).unwrap_err(),
+        LazyError::InvalidDimension { dim: 2, ndim: 2 }
+    );
+    assert_eq!(
+        x.permute(&[0, 0]).unwrap_err(),
+        LazyError::InvalidPermutation {
+            axes: vec![0, 0],
+            ndim: 2
+        }
+    );
+    assert_eq!(
+        x.expand(&[3, 3]).unwrap_err(),
+        LazyError::InvalidExpand {
+            shape: vec![2, 3],
+            target: vec![3, 3]
+        }
+    );
+    assert_eq!(
+        x.reshape(&[5, -1]).unwrap_err(),
+        LazyError::Reshape(ReshapeError::IncompatibleShape)
+    );
+    assert_eq!(
+        x.sum(&[1, -1], false).unwrap_err(),
+        LazyError::RepeatedDimension { dim: 1 }
+    );
+    let empty = graph.input(ArrayD::zeros(vec![2, 0]));
+    assert_eq!(
+        empty.amax(&[1], false).unwrap_err(),
+        LazyError::EmptyReduction { dim: 1 }
+    );
+    assert_eq!(
+        empty.sum(&[1], false).unwrap().eval(),
+        ArrayD::<f32>::zeros(vec![2])
+    );
+}
@@ -0,0 +1,198 @@
+mod common;
+
+use RustOps::linalg::{
+    LinalgError, MatrixNorm, QrMode, cholesky, det, eigh, inv, lstsq, matrix_norm, pinv, qr,
+    slogdet, solve, svd,
+};
+use approx::assert_abs_diff_eq;
+use common::uniform;
+use ndarray::{Array2, ArrayD, Axis, Ix1, Ix2, array};
+
+fn matrices(x: &ArrayD<f64>) -> Vec<Array2<f64>> {
+    let (m, n) = (x.shape()[x.ndim() - 2], x.shape()[x.ndim() - 1]);
+    x.to_shape((x.len() / (m * n).max(1), m, n))
+        .unwrap()
+        .outer_iter()
+        .map(|v| v.to_owned())
+        .collect()
+}
+
+fn assert_orthonormal_columns(q: &Array2<f64>) {
+    let gram = q.t().dot(q);
+    assert_abs_diff_eq!(gram, Array2::eye(q.ncols()), epsilon = 1e-10);
+}
+
+#[test]
+fn test_inverse_determinant_and_solve() {
+    let a = array![[4.0, 7.0], [2.0, 6.0]].into_dyn();
+    assert_abs_diff_eq!(
+        inv(&a).unwrap(),
+        array![[0.6, -0.7], [-0.2, 0.4]].into_dyn(),
+        epsilon = 1e-12
+    );
+    assert_abs_diff_eq!(
+        det(&a).unwrap(),
+        ndarray::arr0(10.0).into_dyn(),
+        epsilon = 1e-12
+    );
+
+    let singular = array![[[1.0, 2.0], [2.0, 4.0]], [[1.0, 0.0], [0.0, 1.0]]].into_dyn();
+    assert_eq!(inv(&singular), Err(LinalgError::Singular { batch: 0 }));
+    let (sign, log) = slogdet(&singular).unwrap();
+    assert_eq!(sign, array![0.0, 1.0].into_dyn());
+    assert_eq!(log, array![f64::NEG_INFINITY, 0.0].into_dyn());
+
+    // Batched A broadcast against a single vector right-hand side
+    let a = uniform::<f64>(&[3, 4, 4], 1) + Array2::<f64>::eye(4).into_dyn() * 3.0;
+    let b = uniform::<f64>(&[4], 2);
+    let x = solve(&a, &b).unwrap();
+    assert_eq!(x.shape(), &[3, 4]);
+    for (matrix, solution) in matrices(&a).iter().zip(x.outer_iter()) {
+        assert_abs_diff_eq!(
+            matrix.dot(&solution.into_dimensionality::<Ix1>().unwrap()),
+            b.view().into_dimensionality::<Ix1>().unwrap(),
+            epsilon = 1e-10
+        );
+    }
+    // A 0-d right-hand side has no rows to solve for
+    assert_eq!(
+        solve(
+            &Array2::<f64>::eye(2).into_dyn(),
+            &ndarray::arr0(1.0).into_dyn()
+        ),
+        Err(LinalgError::TooFewDimensions { shape: vec![] })
+    );
+    let inverse = inv(&a).unwrap();
+    for (matrix, inverse) in matrices(&a).iter().zip(matrices(&inverse)) {
+        assert_abs_diff_eq!(matrix.dot(&inverse), Array2::eye(4), epsilon = 1e-10);
+    }
+    let (sign, log) = slogdet(&a).unwrap();
+    assert_abs_diff_eq!(
+        &sign * &log.mapv(f64::exp),
+        det(&a).unwrap(),
+        epsilon = 1e-9
+    );
+}
+
+#[test]
+fn test_factorizations_reconstruct() {
+    let a = uniform::<f64>(&[2, 5, 3], 3);
+    for mode in [QrMode::Reduced, QrMode::Complete] {
+        let (q, r) = qr(&a, mode).unwrap();
+        for ((matrix, q), r) in matrices(&a).iter().zip(matrices(&q)).zip(matrices(&r)) {
+            assert_orthonormal_columns(&q);
+            assert_abs_diff_eq!(q.dot(&r), matrix, epsilon = 1e-12);
+            for i in 0..r.nrows() {
+                for j in 0..i.min(r.ncols()) {
+                    assert_eq!(r[[i, j]], 0.0);
+                }
+            }
+        }
+    }
+
+    for shape in [[2, 6, 4], [2, 3, 5]] {
+        let a = uniform::<f64>(&shape, 4);
+        for full_matrices in [false, true] {
+            let (u, s, vh) = svd(&a, full_matrices).unwrap();
+            let k = shape[1].min(shape[2]);
+            for ((matrix, u), (s, vh)) in matrices(&a)
+                .iter()
+                .zip(matrices(&u))
+                .zip(s.outer_iter().zip(matrices(&vh)))
+            {
+                assert_orthonormal_columns(&u);
+                assert_orthonormal_columns(&vh.t().to_owned());
+                let s = s.into_dimensionality::<Ix1>().unwrap();
+                assert_eq!(s.len(), k);
+                assert!(s.iter().zip(s.iter().skip(1)).all(|(a, b)| a >= b));
+                let us = &u.slice(ndarray::s![.., ..k]) * &s;
+                assert_abs_diff_eq!(
+                    us.dot(&vh.slice(ndarray::s![..k, ..])),
+                    matrix,
+                    epsilon = 1e-12
+                );
+            }
+        }
+    }
+
+    let base = uniform::<f64>(&[4, 4], 5)
+        .into_dimensionality::<Ix2>()
+        .unwrap();
+    let spd = (base.dot(&base.t()) + Array2::<f64>::eye(4) * 0.5).into_dyn();
+    let l = cholesky(&spd, false)
+        .unwrap()
+        .into_dimensionality::<Ix2>()
+        .unwrap();
+    assert_abs_diff_eq!(l.dot(&l.t()).into_dyn(), spd, epsilon = 1e-12);
+    let u = cholesky(&spd, true).unwrap();
+    assert_abs_diff_eq!(u, l.t().to_owned().into_dyn(), epsilon = 1e-15);
+    assert_eq!(
+        cholesky(&array![[1.0, 2.0], [2.0, 1.0]].into_dyn(), false),
+        Err(LinalgError::NotPositiveDefinite { batch: 0, order: 2 })
+    );
+
+    let (w, v) = eigh(&spd, false).unwrap();
+    let w = w.into_dimensionality::<Ix1>().unwrap();
+    let v = v.into_dimensionality::<Ix2>().unwrap();
+    assert_orthonormal_columns(&v);
+    assert!(w.iter().zip(w.iter().skip(1)).all(|(a, b)| a <= b));
+    let reconstructed = (&v * &w).dot(&v.t());
+    assert_abs_diff_eq!(reconstructed.into_dyn(), spd, epsilon = 1e-12);
+}
+
+#[test]
+fn test_pinv_lstsq_and_norms() {
+    // Rank-1 matrix: pinv is A^T / |A|_F^2
+    let a = array![[1.0, 2.0], [2.0, 4.0], [3.0, 6.0]].into_dyn();
+    let expected = a.t().mapv(|x| x / 70.0).into_dyn();
+    assert_abs_diff_eq!(pinv(&a, None).unwrap(), expected, epsilon = 1e-12);
+
+    let b = array![[1.0], [2.0], [3.0]].into_dyn();
+    let result = lstsq(&a, &b, None).unwrap();
+    assert_eq!(result.rank, ndarray::arr0(1i64).into_dyn());
+    assert_eq!(result.residuals.len(), 0);
+    assert_abs_diff_eq!(
+        result.solution,
+        array![[0.2], [0.4]].into_dyn(),
+        epsilon = 1e-12
+    );
+
+    let tall = uniform::<f64>(&[6, 3], 6);
+    let rhs = uniform::<f64>(&[6, 2], 7);
+    let result = lstsq(&tall, &rhs, None).unwrap();
+    let t = tall.view().into_dimensionality::<Ix2>().unwrap();
+    let x = result.solution.view().into_dimensionality::<Ix2>().unwrap();
+    let residual = &rhs.view().into_dimensionality::<Ix2>().unwrap() - &t.dot(&x);
+    // The residual is orthogonal to the column space
+    assert_abs_diff_eq!(t.t().dot(&residual), Array2::zeros((3, 2)), epsilon = 1e-12);
+    assert_abs_diff_eq!(
+        result.residuals,
+        residual.map_axis(Axis(0), |c| c.dot(&c)).into_dyn(),
+        epsilon = 1e-12
+    );
+
+    let m = array![[1.0, -2.0], [3.0, 4.0]].into_dyn();
+    let norm = |ord| {
+        matrix_norm(&m, ord, false)
+            .unwrap()
+            .first()
+            .copied()
+            .unwrap()
+    };
+    assert_abs_diff_eq!(norm(MatrixNorm::Frobenius), 30f64.sqrt(), epsilon = 1e-12);
+    assert_eq!(norm(MatrixNorm::One), 6.0);
+    assert_eq!(norm(MatrixNorm::NegOne), 4.0);
+    assert_eq!(norm(MatrixNorm::Inf), 7.0);
+    assert_eq!(norm(MatrixNorm::NegInf), 3.0);
+    // Singular values of m are sqrt(15 +- sqrt(125))
+    let (s1, s2) = ((15.0 + 125f64.sqrt()).sqrt(), (15.0 - 125f64.sqrt()).sqrt());
+    assert_abs_diff_eq!(norm(MatrixNorm::Two), s1, epsilon = 1e-12);
+    assert_abs_diff_eq!(norm(MatrixNorm::NegTwo), s2, epsilon = 1e-12);
+    assert_abs_diff_eq!(norm(MatrixNorm::Nuclear), s1 + s2, epsilon = 1e-12);
+    assert_eq!(
+        matrix_norm(&m, MatrixNorm::Frobenius, true)
+            .unwrap()
+            .shape(),
+        &[1, 1]
+    );
+}
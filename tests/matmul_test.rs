@@ -0,0 +1,103 @@
+use RustOps::functions::matmul::{MatmulError, addmm, baddbmm, bmm, matmul};
+use approx::assert_abs_diff_eq;
+use ndarray::{Array, IxDyn, array};
+
+#[test]
+fn test_matmul_vector_cases() {
+    let v = array![1i64, 2, 3].into_dyn();
+    let m = array![[1i64, 0], [0, 1], [1, 1]].into_dyn();
+
+    assert_eq!(matmul(&v, &v).unwrap(), ndarray::arr0(14i64).into_dyn());
+    assert_eq!(matmul(&v, &m).unwrap(), array![4i64, 5].into_dyn());
+    assert_eq!(
+        matmul(&m.t().to_owned(), &v).unwrap(),
+        array![4i64, 5].into_dyn()
+    );
+}
+
+#[test]
+fn test_matmul_broadcast_batches() {
+    let a = Array::from_shape_fn(IxDyn(&[2, 1, 3, 4]), |idx| {
+        (idx[0] * 12 + idx[2] * 4 + idx[3]) as f64
+    });
+    let b = Array::from_shape_fn(IxDyn(&[5, 4, 2]), |idx| {
+        (idx[0] as f64) - (idx[1] * 2 + idx[2]) as f64
+    });
+
+    let result = matmul(&a, &b).unwrap();
+    assert_eq!(result.shape(), &[2, 5, 3, 2]);
+    for p in 0..2 {
+        for q in 0..5 {
+            for i in 0..3 {
+                for k in 0..2 {
+                    let expected: f64 = (0..4).map(|j| a[[p, 0, i, j]] * b[[q, j, k]]).sum();
+                    assert_abs_diff_eq!(result[[p, q, i, k]], expected, epsilon = 1e-9);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bmm_addmm_baddbmm() {
+    let a = array![[[1.0f32, 2.0], [3.0, 4.0]], [[0.0, 1.0], [1.0, 0.0]]].into_dyn();
+    let b = array![[[1.0f32, 0.0], [0.0, 1.0]], [[2.0, 3.0], [4.0, 5.0]]].into_dyn();
+    let product = array![[[1.0f32, 2.0], [3.0, 4.0]], [[4.0, 5.0], [2.0, 3.0]]].into_dyn();
+    assert_eq!(bmm(&a, &b).unwrap(), product);
+
+    let bias = array![10.0f32, 20.0].into_dyn();
+    let expected = product.mapv(|x| 2.0 * x) + bias.mapv(|x| 0.5 * x);
+    assert_eq!(baddbmm(&bias, &a, &b, 0.5, 2.0).unwrap(), expected);
+
+    // beta = 0 ignores the input entirely, including NaNs
+    let nan_bias = array![f32::NAN, f32::NAN].into_dyn();
+    assert_eq!(baddbmm(&nan_bias, &a, &b, 0.0, 1.0).unwrap(), product);
+
+    let m1 = array![[1.0f32, 2.0], [3.0, 4.0]].into_dyn();
+    let m2 = array![[1.0f32], [1.0]].into_dyn();
+    let input = array![[1.0f32], [1.0]].into_dyn();
+    assert_eq!(
+        addmm(&input, &m1, &m2, 1.0, 1.0).unwrap(),
+        array![[4.0f32], [8.0]].into_dyn()
+    );
+}
+
+#[test]
+fn test_matmul_errors() {
+    let a = Array::<f32, _>::zeros(IxDyn(&[2, 3]));
+    let b = Array::<f32, _>::zeros(IxDyn(&[4, 5]));
+    let scalar = ndarray::arr0(1.0f32).into_dyn();
+
+    assert_eq!(
+        matmul(&a, &b),
+        Err(MatmulError::InnerDimensionMismatch {
+            left_shape: vec![2, 3],
+            right_shape: vec![4, 5]
+        })
+    );
+    assert_eq!(
+        matmul(&scalar, &a),
+        Err(MatmulError::ZeroDimensional {
+            left_ndim: 0,
+            right_ndim: 2
+        })
+    );
+
+    let x = Array::<f32, _>::zeros(IxDyn(&[2, 2, 3]));
+    let y = Array::<f32, _>::zeros(IxDyn(&[3, 3, 2]));
+    assert_eq!(
+        matmul(&x, &y),
+        Err(MatmulError::BatchBroadcast {
+            left: vec![2],
+            right: vec![3]
+        })
+    );
+    assert_eq!(
+        bmm(&a, &b),
+        Err(MatmulError::WrongDimensions {
+            argument: "batch1",
+            expected: 3,
+            shape: vec![2, 3]
+        })
+    );
+}
@@ -0,0 +1,111 @@
+use RustOps::nn::functional::{
+    FunctionalError, dropout, embedding, group_norm, layer_norm, linear, log_softmax, rms_norm,
+    softmax,
+};
+use approx::assert_abs_diff_eq;
+use ndarray::{ArrayD, Axis, IxDyn, array};
+
+#[test]
+fn test_softmax_is_stable_for_large_inputs() {
+    let x = array![[1000.0f64, 1001.0, 1002.0], [-1000.0, -1000.0, -1000.0]].into_dyn();
+    let y = softmax(&x, -1).unwrap();
+    let e = [1.0f64, 1f64.exp(), 2f64.exp()];
+    let total: f64 = e.iter().sum();
+    let expected = array![
+        [e[0] / total, e[1] / total, e[2] / total],
+        [1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0]
+    ]
+    .into_dyn();
+    assert_abs_diff_eq!(y, expected, epsilon = 1e-12);
+
+    let log_y = log_softmax(&x, 1).unwrap();
+    assert_abs_diff_eq!(log_y, expected.mapv(f64::ln), epsilon = 1e-12);
+    assert_eq!(
+        softmax(&x, 2),
+        Err(FunctionalError::InvalidDimension { dim: 2, ndim: 2 })
+    );
+}
+
+#[test]
+fn test_norms_small() {
+    let x = array![[1.0f64, 2.0, 3.0, 4.0], [2.0, 2.0, 2.0, 2.0]].into_dyn();
+    let y = layer_norm(&x, &[4], None, None, 0.0).unwrap();
+    let s = 1.25f64.sqrt();
+    assert_abs_diff_eq!(
+        y.index_axis(Axis(0), 0).to_owned(),
+        array![-1.5 / s, -0.5 / s, 0.5 / s, 1.5 / s].into_dyn(),
+        epsilon = 1e-12
+    );
+
+    let weight = array![1.0f64, 2.0, 1.0, 2.0].into_dyn();
+    let y = rms_norm(&x, &[4], Some(&weight), Some(0.0)).unwrap();
+    assert_abs_diff_eq!(
+        y.index_axis(Axis(0), 1).to_owned(),
+        array![1.0, 2.0, 1.0, 2.0].into_dyn(),
+        epsilon = 1e-12
+    );
+
+    assert!(matches!(
+        layer_norm(&x, &[3], None, None, 1e-5),
+        Err(FunctionalError::NormalizedShapeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_group_norm_groups_channels() {
+    // One sample, four channels of two elements, two groups
+    let x = array![[[0.0f64, 2.0], [4.0, 6.0], [1.0, 1.0], [3.0, 3.0]]].into_dyn();
+    let bias = array![0.0f64, 0.0, 10.0, 10.0].into_dyn();
+    let y = group_norm(&x, 2, None, Some(&bias), 0.0).unwrap();
+    let s = 5f64.sqrt();
+    let expected = array![[
+        [-3.0 / s, -1.0 / s],
+        [1.0 / s, 3.0 / s],
+        [9.0, 9.0],
+        [11.0, 11.0]
+    ]]
+    .into_dyn();
+    assert_abs_diff_eq!(y, expected, epsilon = 1e-12);
+    assert_eq!(
+        group_norm(&x, 3, None, None, 1e-5),
+        Err(FunctionalError::GroupMismatch {
+            channels: 4,
+            groups: 3
+        })
+    );
+}
+
+#[test]
+fn test_linear_embedding_and_dropout() {
+    let x = array![[1.0f64, 2.0], [3.0, 4.0]].into_dyn();
+    let weight = array![[1.0f64, 0.0], [0.0, 1.0], [1.0, 1.0]].into_dyn();
+    let bias = array![0.5f64, 0.0, -1.0].into_dyn();
+    assert_eq!(
+        linear(&x, &weight, Some(&bias)).unwrap(),
+        array![[1.5, 2.0, 2.0], [3.5, 4.0, 6.0]].into_dyn()
+    );
+    // Without input features every output is just the bias, as in PyTorch
+    let empty = ArrayD::<f64>::zeros(IxDyn(&[3, 0]));
+    assert_eq!(
+        linear(&empty, &ArrayD::zeros(IxDyn(&[4, 0])), None).unwrap(),
+        ArrayD::<f64>::zeros(IxDyn(&[3, 4]))
+    );
+
+    let ids = array![[2i64, 0], [1, 1]].into_dyn();
+    let y = embedding(&ids, &weight).unwrap();
+    assert_eq!(y.shape(), &[2, 2, 2]);
+    assert_eq!(
+        y,
+        array![[[1.0, 1.0], [1.0, 0.0]], [[0.0, 1.0], [0.0, 1.0]]].into_dyn()
+    );
+    assert!(matches!(
+        embedding(&array![3i64].into_dyn(), &weight),
+        Err(FunctionalError::EmbeddingIndexOutOfBounds { index: 3, .. })
+    ));
+
+    assert_eq!(dropout(&x, 0.5, false).unwrap(), x);
+    assert_eq!(
+        dropout(&x, 0.5, true),
+        Err(FunctionalError::TrainingModeUnsupported)
+    );
+}
@@ -0,0 +1,175 @@
+use RustOps::io::Tensor;
+use RustOps::onnx::{Model, OnnxError};
+use ndarray::{ArrayD, IxDyn, array};
+use std::collections::HashMap;
+
+// The models are written by reference/onnx_models.py
+fn open(name: &str) -> Model {
+    Model::open(format!("tests/onnx/{name}.onnx")).unwrap()
+}
+
+fn arange(shape: &[usize]) -> ArrayD<f32> {
+    let len = shape.iter().product();
+    ArrayD::from_shape_vec(IxDyn(shape), (0..len).map(|v| v as f32).collect()).unwrap()
+}
+
+fn run(model: &Model, inputs: Vec<(&str, Tensor)>) -> HashMap<String, Tensor> {
+    let inputs = inputs
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect();
+    model.run(inputs).unwrap()
+}
+
+#[test]
+fn test_gather_abs_reduce() {
+    let model = open("gather_abs_reduce");
+    assert_eq!(model.inputs(), ["x"]);
+    assert_eq!(model.outputs(), ["max", "argmax"]);
+    assert_eq!(model.opset(), 13);
+    assert_eq!(
+        model.initializer("rows"),
+        Some(&Tensor::from(array![2i64, -3].into_dyn()))
+    );
+
+    let x = array![
+        [1.0f32, -4.0, 3.0, -2.0],
+        [-5.0, 6.0, -7.0, 8.0],
+        [9.0, -10.0, 11.0, -12.0]
+    ];
+    let outputs = run(&model, vec![("x", Tensor::from(x.into_dyn()))]);
+    assert_eq!(outputs.len(), 2);
+    // Rows 2 and -3, that is 0, of |x|
+    assert_eq!(
+        outputs["max"],
+        Tensor::from(array![12.0f32, 4.0].into_dyn())
+    );
+    assert_eq!(
+        outputs["argmax"],
+        Tensor::from(array![[3i64], [1]].into_dyn())
+    );
+}
+
+#[test]
+fn test_reshape_transpose_einsum() {
+    let model = open("reshape_transpose_einsum");
+    let outputs = run(&model, vec![("x", Tensor::from(arange(&[2, 3, 2])))]);
+    // Row i of the transposed [2, 6] reshape is [i, i + 6], dotted with [0.5, -2]
+    let expected: Vec<f32> = (0..6)
+        .map(|i| 0.5 * i as f32 - 2.0 * (i + 6) as f32)
+        .collect();
+    assert_eq!(
+        outputs["y"],
+        Tensor::from(ArrayD::from_shape_vec(IxDyn(&[6]), expected).unwrap())
+    );
+}
+
+#[test]
+fn test_slice_scatter_out_of_order() {
+    let model = open("slice_scatter");
+    assert_eq!(model.opset(), 18);
+    let outputs = run(&model, vec![("data", Tensor::from(arange(&[4, 5])))]);
+    // Rows 3 and 1, columns 0, 2 and 4, with three entries overwritten
+    assert_eq!(
+        outputs["scattered"],
+        Tensor::from(array![[15.0f32, 20.0, 19.0], [10.0, 7.0, 30.0]].into_dyn())
+    );
+    assert_eq!(
+        outputs["picked"],
+        Tensor::from(array![[19.0f32, 15.0], [10.0, 10.0]].into_dyn())
+    );
+    assert_eq!(
+        outputs["row_max"],
+        Tensor::from(array![[19.0f32], [10.0]].into_dyn())
+    );
+}
+
+#[test]
+fn test_slice_negative_steps() {
+    let model = open("slice_reverse");
+    let outputs = run(&model, vec![("data", Tensor::from(arange(&[2, 3])))]);
+    assert_eq!(
+        outputs["reversed"],
+        Tensor::from(array![[5.0f32, 4.0, 3.0], [2.0, 1.0, 0.0]].into_dyn())
+    );
+
+    // Reversing an empty axis selects nothing instead of clamping to [0, -1]
+    for shape in [[0, 3], [2, 0]] {
+        let outputs = run(&model, vec![("data", Tensor::from(arange(&shape)))]);
+        assert_eq!(outputs["reversed"], Tensor::from(arange(&shape)));
+    }
+}
+
+#[test]
+fn test_slice_huge_steps() {
+    let model = open("slice_steps");
+    let ints = |v: i64| Tensor::from(array![v].into_dyn());
+    let slice = |start: i64, end: i64, step: i64| {
+        let outputs = run(
+            &model,
+            vec![
+                ("data", Tensor::from(arange(&[5]))),
+                ("starts", ints(start)),
+                ("ends", ints(end)),
+                ("axes", ints(0)),
+                ("steps", ints(step)),
+            ],
+        );
+        outputs["sliced"].clone()
+    };
+    assert_eq!(
+        slice(1, i64::MAX, i64::MAX),
+        Tensor::from(array![1.0f32].into_dyn())
+    );
+    assert_eq!(
+        slice(-1, i64::MIN, i64::MIN),
+        Tensor::from(array![4.0f32].into_dyn())
+    );
+}
+
+#[test]
+fn test_unsupported_ops_are_named() {
+    let err = Model::open("tests/onnx/unsupported.onnx").unwrap_err();
+    match &err {
+        OnnxError::UnsupportedOps { ops } => {
+            assert_eq!(ops, &["Relu", "Softmax", "com.microsoft.FusedMatMul"]);
+        }
+        other => panic!("unexpected error {other:?}"),
+    }
+    assert_eq!(
+        err.to_string(),
+        "Unsupported ONNX ops: Relu, Softmax, com.microsoft.FusedMatMul"
+    );
+}
+
+#[test]
+fn test_onnx_errors() {
+    let model = open("gather_abs_reduce");
+    assert!(matches!(
+        model.run(HashMap::new()),
+        Err(OnnxError::MissingInput { name }) if name == "x"
+    ));
+
+    // Gather checks the indices against the input
+    let short = Tensor::from(ArrayD::<f32>::zeros(IxDyn(&[2, 4])));
+    let err = model
+        .run(HashMap::from([("x".to_string(), short)]))
+        .unwrap_err();
+    assert!(matches!(err, OnnxError::OpFailed { node, .. } if node == "Gather_0"));
+
+    // Abs is only defined for numbers
+    let mask = Tensor::from(ArrayD::from_elem(IxDyn(&[3, 4]), true));
+    let err = model
+        .run(HashMap::from([("x".to_string(), mask)]))
+        .unwrap_err();
+    assert!(matches!(err, OnnxError::UnsupportedDtype { node, .. } if node == "Abs_1"));
+
+    assert!(matches!(
+        Model::from_bytes(&[0xff, 0xff]),
+        Err(OnnxError::Decode(_))
+    ));
+    assert!(matches!(
+        Model::open("tests/onnx/missing.onnx"),
+        Err(OnnxError::Io(_))
+    ));
+}
@@ -0,0 +1,67 @@
+use RustOps::functions::pad::{PadError, PadMode, pad};
+use ndarray::array;
+
+#[test]
+fn test_pad_modes_small() {
+    let x = array![[1, 2, 3], [4, 5, 6]].into_dyn();
+
+    assert_eq!(
+        pad(&x, &[(1, 1)], PadMode::Constant(0)).unwrap(),
+        array![[0, 1, 2, 3, 0], [0, 4, 5, 6, 0]].into_dyn()
+    );
+    assert_eq!(
+        pad(&x, &[(2, 1)], PadMode::Reflect).unwrap(),
+        array![[3, 2, 1, 2, 3, 2], [6, 5, 4, 5, 6, 5]].into_dyn()
+    );
+    assert_eq!(
+        pad(&x, &[(0, 0), (1, 0)], PadMode::Replicate).unwrap(),
+        array![[1, 2, 3], [1, 2, 3], [4, 5, 6]].into_dyn()
+    );
+    assert_eq!(
+        pad(&x, &[(1, 2)], PadMode::Circular).unwrap(),
+        array![[3, 1, 2, 3, 1, 2], [6, 4, 5, 6, 4, 5]].into_dyn()
+    );
+}
+
+#[test]
+fn test_pad_negative_crops() {
+    let x = array![[1, 2, 3], [4, 5, 6]].into_dyn();
+
+    assert_eq!(
+        pad(&x, &[(-1, 1)], PadMode::Constant(9)).unwrap(),
+        array![[2, 3, 9], [5, 6, 9]].into_dyn()
+    );
+    assert_eq!(
+        pad(&x, &[(0, 0), (0, -1)], PadMode::Constant(9)).unwrap(),
+        array![[1, 2, 3]].into_dyn()
+    );
+}
+
+#[test]
+fn test_pad_errors() {
+    let x = array![[1.0f32, 2.0, 3.0]].into_dyn();
+
+    assert_eq!(
+        pad(&x, &[(0, 0), (0, 0), (1, 1)], PadMode::Constant(0.0)),
+        Err(PadError::TooManyPadDimensions { pads: 3, ndim: 2 })
+    );
+    assert_eq!(
+        pad(&x, &[(3, 0)], PadMode::Reflect),
+        Err(PadError::PaddingTooLarge {
+            mode: "reflect",
+            dim: 1,
+            size: 3,
+            before: 3,
+            after: 0
+        })
+    );
+    assert_eq!(
+        pad(&x, &[(-2, -2)], PadMode::Constant(0.0)),
+        Err(PadError::NegativeOutputSize {
+            dim: 1,
+            size: 3,
+            before: -2,
+            after: -2
+        })
+    );
+}
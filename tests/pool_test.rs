@@ -0,0 +1,284 @@
+use RustOps::functions::argmax::{argmax, argmax_into};
+use RustOps::functions::einsum::{EinsumPlan, einsum_ndarray_dyn};
+use RustOps::functions::expand::{expand_at_dim, expand_at_dim_into};
+use RustOps::functions::gather::{gather, gather_into};
+use RustOps::functions::max::{max, max_into};
+use RustOps::functions::reshape::{reshape, reshape_into};
+use RustOps::functions::scatter::{scatter, scatter_into};
+use RustOps::functions::slicing::{slice_second_dim, slice_second_dim_into};
+use RustOps::functions::transpose::{transpose_dims, transpose_dims_into};
+use RustOps::pool::BufferPool;
+use RustOps::rng::{Generator, randn};
+use approx::assert_abs_diff_eq;
+use ndarray::{ArrayD, Axis, IxDyn};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// Counts the heap allocations of each thread, so tests running in parallel do not disturb
+/// each other.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count() {
+    // Fails only while the thread is being torn down
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count();
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        count();
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count();
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+const BATCH: usize = 2;
+const VOCAB: usize = 7;
+const HIDDEN: usize = 4;
+const WINDOW: usize = 5;
+
+/// Weights of a toy decoder: an embedding table shared with the output projection and a
+/// rolling cache of past hidden states.
+struct Decoder {
+    embedding: ArrayD<f32>,
+    cache: ArrayD<f32>,
+}
+
+impl Decoder {
+    fn new() -> Self {
+        let mut generator = Generator::new(0);
+        Decoder {
+            embedding: randn(&[VOCAB, HIDDEN], &mut generator),
+            cache: randn(&[BATCH, WINDOW, HIDDEN], &mut generator),
+        }
+    }
+}
+
+/// What one decoding step computes, for comparing the two implementations.
+struct StepOutput {
+    tokens: Vec<i64>,
+    best_position: Vec<i64>,
+    best_score: Vec<f32>,
+    flat_scores: Vec<f32>,
+}
+
+/// One step written with the allocating ops.
+fn step_allocating(decoder: &mut Decoder, tokens: &[i64], position: usize) -> StepOutput {
+    let rows = ArrayD::from_shape_fn(IxDyn(&[BATCH, HIDDEN]), |idx| tokens[idx[0]]);
+    let hidden = gather(&decoder.embedding, 0, &rows).unwrap();
+
+    let queries = expand_at_dim(&hidden, 1, WINDOW).unwrap();
+    let scores = einsum_ndarray_dyn("bwh,bwh->bw", &[&queries, &decoder.cache]).unwrap();
+    let flat = reshape(&transpose_dims(&scores, 0, 1), &[-1]).unwrap();
+    let recent = slice_second_dim(&scores, 3).unwrap();
+    let (best_score, best_position) = max(&recent, 1).unwrap();
+
+    let slot = ArrayD::from_elem(IxDyn(&[BATCH, 1, HIDDEN]), position as i64);
+    let update = reshape(&hidden, &[BATCH as i64, 1, HIDDEN as i64]).unwrap();
+    scatter(&mut decoder.cache, 1, &slot, &update).unwrap();
+
+    let logits = einsum_ndarray_dyn("bh,vh->bv", &[&hidden, &decoder.embedding]).unwrap();
+    StepOutput {
+        tokens: argmax(&logits, Some(1), false).unwrap().into_raw_vec(),
+        best_position: best_position.into_raw_vec(),
+        best_score: best_score.into_raw_vec(),
+        flat_scores: flat.into_raw_vec(),
+    }
+}
+
+/// The same step with the `*_into` ops, pooled buffers and prepared einsum plans.
+struct PooledStep {
+    floats: BufferPool<f32>,
+    ints: BufferPool<i64>,
+    scores: EinsumPlan,
+    logits: EinsumPlan,
+}
+
+impl PooledStep {
+    fn new() -> Self {
+        PooledStep {
+            floats: BufferPool::new(),
+            ints: BufferPool::new(),
+            scores: EinsumPlan::new(
+                "bwh,bwh->bw",
+                &[&[BATCH, WINDOW, HIDDEN], &[BATCH, WINDOW, HIDDEN]],
+            )
+            .unwrap(),
+            logits: EinsumPlan::new("bh,vh->bv", &[&[BATCH, HIDDEN], &[VOCAB, HIDDEN]]).unwrap(),
+        }
+    }
+
+    /// Runs a step, writing into the caller's output vectors.
+    fn step(
+        &mut self,
+        decoder: &mut Decoder,
+        tokens: &mut [i64],
+        position: usize,
+        best_position: &mut [i64],
+        best_score: &mut [f32],
+        flat_scores: &mut [f32],
+    ) {
+        let mut rows = self.ints.take(&[BATCH, HIDDEN]);
+        for (mut row, &token) in rows.axis_iter_mut(Axis(0)).zip(tokens.iter()) {
+            row.fill(token);
+        }
+        let mut hidden = self.floats.take(&[BATCH, HIDDEN]);
+        gather_into(&mut hidden.view_mut(), &decoder.embedding, 0, &rows).unwrap();
+
+        let mut queries = self.floats.take(&[BATCH, WINDOW, HIDDEN]);
+        expand_at_dim_into(&mut queries.view_mut(), &hidden, 1).unwrap();
+        let mut scores = self.floats.take(&[BATCH, WINDOW]);
+        self.scores
+            .execute_into(
+                &mut scores.view_mut(),
+                &[queries.view(), decoder.cache.view()],
+            )
+            .unwrap();
+        let mut transposed = self.floats.take(&[WINDOW, BATCH]);
+        transpose_dims_into(&mut transposed.view_mut(), &scores, 0, 1);
+        let mut flat = self.floats.take(&[WINDOW * BATCH]);
+        reshape_into(&mut flat.view_mut(), &transposed).unwrap();
+        let mut recent = self.floats.take(&[BATCH, 3]);
+        slice_second_dim_into(&mut recent.view_mut(), &scores, 3).unwrap();
+        let mut values = self.floats.take(&[BATCH]);
+        let mut positions = self.ints.take(&[BATCH]);
+        max_into(
+            &mut values.view_mut(),
+            &mut positions.view_mut(),
+            &recent,
+            1,
+        )
+        .unwrap();
+
+        let mut slot = self.ints.take(&[BATCH, 1, HIDDEN]);
+        slot.fill(position as i64);
+        let mut update = self.floats.take(&[BATCH, 1, HIDDEN]);
+        reshape_into(&mut update.view_mut(), &hidden).unwrap();
+        scatter_into(&mut decoder.cache.view_mut(), 1, &slot, &update).unwrap();
+
+        let mut logits = self.floats.take(&[BATCH, VOCAB]);
+        self.logits
+            .execute_into(
+                &mut logits.view_mut(),
+                &[hidden.view(), decoder.embedding.view()],
+            )
+            .unwrap();
+        let mut next = self.ints.take(&[BATCH]);
+        argmax_into(&mut next.view_mut(), &logits, 1).unwrap();
+
+        tokens.copy_from_slice(next.as_slice().unwrap());
+        best_position.copy_from_slice(positions.as_slice().unwrap());
+        best_score.copy_from_slice(values.as_slice().unwrap());
+        flat_scores.copy_from_slice(flat.as_slice().unwrap());
+
+        for buffer in [
+            hidden, queries, scores, transposed, flat, recent, values, update, logits,
+        ] {
+            self.floats.recycle(buffer);
+        }
+        for buffer in [rows, positions, slot, next] {
+            self.ints.recycle(buffer);
+        }
+    }
+}
+
+#[test]
+fn test_pooled_decoding_does_not_allocate() {
+    let mut eager = Decoder::new();
+    let mut pooled = Decoder::new();
+    let mut step = PooledStep::new();
+    let mut tokens = vec![3i64, 5];
+    let mut best_position = vec![0i64; BATCH];
+    let mut best_score = vec![0.0f32; BATCH];
+    let mut flat_scores = vec![0.0f32; WINDOW * BATCH];
+    let mut expected_tokens = tokens.clone();
+
+    for position in 0..12 {
+        let expected = step_allocating(&mut eager, &expected_tokens, position % WINDOW);
+        expected_tokens = expected.tokens.clone();
+
+        let before = allocations();
+        step.step(
+            &mut pooled,
+            &mut tokens,
+            position % WINDOW,
+            &mut best_position,
+            &mut best_score,
+            &mut flat_scores,
+        );
+        let allocated = allocations() - before;
+        // The first step fills the pools, after which every buffer is reused
+        if position == 0 {
+            assert!(allocated > 0);
+        } else {
+            assert_eq!(allocated, 0, "step {position} allocated");
+        }
+
+        // The plans sum in another order than the batched matrix products
+        assert_eq!(tokens, expected.tokens);
+        assert_eq!(best_position, expected.best_position);
+        assert_abs_diff_eq!(&best_score[..], &expected.best_score[..], epsilon = 1e-5);
+        assert_abs_diff_eq!(&flat_scores[..], &expected.flat_scores[..], epsilon = 1e-5);
+        assert_eq!(pooled.cache, eager.cache);
+    }
+    assert_eq!(step.floats.allocations(), 9);
+    assert_eq!(step.ints.allocations(), 4);
+}
+
+#[test]
+fn test_buffer_pool() {
+    let mut pool = BufferPool::<f32>::new();
+    assert_eq!(pool.available(), 0);
+
+    let a = pool.take(&[2, 3]);
+    assert_eq!(a, ArrayD::<f32>::zeros(IxDyn(&[2, 3])));
+    let mut b = pool.take(&[2, 3]);
+    b.fill(1.0);
+    let pointer = b.as_ptr();
+    pool.recycle(a);
+    pool.recycle(b);
+    assert_eq!(pool.available(), 2);
+    assert_eq!(pool.allocations(), 2);
+
+    // The last buffer recycled is the first reused, with its contents
+    let c = pool.take(&[2, 3]);
+    assert_eq!(c.as_ptr(), pointer);
+    assert!(c.iter().all(|&v| v == 1.0));
+    // Shapes with the same size are kept apart
+    let d = pool.take(&[3, 2]);
+    assert_eq!(d.shape(), &[3, 2]);
+    assert_eq!(pool.allocations(), 3);
+    assert_eq!(pool.available(), 1);
+
+    pool.recycle(c);
+    pool.recycle(d);
+    pool.clear();
+    assert_eq!(pool.available(), 0);
+    let before = allocations();
+    let scalar = pool.take(&[]);
+    assert_eq!(scalar.ndim(), 0);
+    assert!(allocations() > before);
+}
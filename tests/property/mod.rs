@@ -0,0 +1,118 @@
+//! Strategies for property tests: random shapes (including zero-sized axes) and arrays whose
+//! memory layout is randomly permuted, strided or reversed while their logical content stays
+//! the same, so ops are exercised on non-contiguous inputs as well.
+
+pub mod oracle;
+
+use ndarray::{ArrayD, Axis, IxDyn, Slice};
+use proptest::collection::vec;
+use proptest::prelude::*;
+use std::ops::RangeInclusive;
+
+/// Largest size of any axis; small so that zero-sized axes and ties are common.
+pub const MAX_AXIS: usize = 4;
+
+/// How the elements of a generated array are laid out in memory.
+#[derive(Debug, Clone)]
+pub enum Layout {
+    /// Row-major and contiguous.
+    Standard,
+    /// Contiguous in the axis order given, so strides are not decreasing.
+    Permuted(Vec<usize>),
+    /// Every other element of a buffer twice as long along the axis.
+    Strided(usize),
+    /// Negative stride along the axis.
+    Reversed(usize),
+}
+
+pub fn shape(ndim: RangeInclusive<usize>) -> impl Strategy<Value = Vec<usize>> {
+    vec(0..=MAX_AXIS, ndim)
+}
+
+pub fn layout(ndim: usize) -> BoxedStrategy<Layout> {
+    if ndim == 0 {
+        return Just(Layout::Standard).boxed();
+    }
+    prop_oneof![
+        Just(Layout::Standard),
+        Just((0..ndim).collect::<Vec<_>>())
+            .prop_shuffle()
+            .prop_map(Layout::Permuted),
+        (0..ndim).prop_map(Layout::Strided),
+        (0..ndim).prop_map(Layout::Reversed),
+    ]
+    .boxed()
+}
+
+/// Returns an owned array equal to `array` but stored with the given layout.
+pub fn with_layout<T: Clone + Default>(array: ArrayD<T>, layout: &Layout) -> ArrayD<T> {
+    match layout {
+        Layout::Standard => array,
+        Layout::Permuted(order) => {
+            let mut inverse = vec![0; order.len()];
+            for (i, &axis) in order.iter().enumerate() {
+                inverse[axis] = i;
+            }
+            array
+                .permuted_axes(order.clone())
+                .as_standard_layout()
+                .into_owned()
+                .permuted_axes(inverse)
+        }
+        &Layout::Strided(axis) => {
+            let mut shape = array.shape().to_vec();
+            shape[axis] *= 2;
+            let mut buffer = ArrayD::default(IxDyn(&shape));
+            let every_other = Slice::new(0, None, 2);
+            buffer
+                .slice_axis_mut(Axis(axis), every_other)
+                .assign(&array);
+            buffer.slice_axis_inplace(Axis(axis), every_other);
+            buffer
+        }
+        &Layout::Reversed(axis) => {
+            let mut reversed = array;
+            reversed.invert_axis(Axis(axis));
+            let mut array = reversed.as_standard_layout().into_owned();
+            array.invert_axis(Axis(axis));
+            array
+        }
+    }
+}
+
+/// An array of the given shape with elements drawn from `element` and a random layout.
+pub fn array_with<T, S>(shape: Vec<usize>, element: S) -> impl Strategy<Value = ArrayD<T>>
+where
+    T: Clone + Default + std::fmt::Debug,
+    S: Strategy<Value = T>,
+{
+    let len: usize = shape.iter().product();
+    (vec(element, len), layout(shape.len())).prop_map(move |(data, layout)| {
+        let array = ArrayD::from_shape_vec(IxDyn(&shape), data).unwrap();
+        with_layout(array, &layout)
+    })
+}
+
+/// Small integers, so that maxima are frequently tied.
+pub fn small_value() -> impl Strategy<Value = f32> {
+    (-3i8..=3).prop_map(f32::from)
+}
+
+/// Small integers with the occasional NaN.
+pub fn value_or_nan() -> impl Strategy<Value = f32> {
+    prop_oneof![9 => small_value(), 1 => Just(f32::NAN)]
+}
+
+/// A random `f32` array with rank in `ndim`, possibly empty and non-contiguous.
+pub fn tensor(ndim: RangeInclusive<usize>) -> impl Strategy<Value = ArrayD<f32>> {
+    shape(ndim).prop_flat_map(|shape| array_with(shape, small_value()))
+}
+
+/// Maps NaN to `None` so arrays containing NaN can be compared with `==`.
+pub fn nan_aware(array: &ArrayD<f32>) -> (Vec<usize>, Vec<Option<f32>>) {
+    let values = array
+        .iter()
+        .map(|&v| if v.is_nan() { None } else { Some(v) })
+        .collect();
+    (array.shape().to_vec(), values)
+}
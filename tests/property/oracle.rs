@@ -0,0 +1,252 @@
+//! Naive reference implementations, written with explicit row-major index arithmetic on flat
+//! vectors so that they share no code paths with the ops under test.
+
+use ndarray::{ArrayD, IxDyn};
+use std::cmp::Ordering;
+
+/// A row-major tensor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Naive<T> {
+    pub shape: Vec<usize>,
+    pub data: Vec<T>,
+}
+
+impl<T: Clone> Naive<T> {
+    pub fn from_array(array: &ArrayD<T>) -> Self {
+        // `iter` visits elements in logical order whatever the memory layout
+        Self {
+            shape: array.shape().to_vec(),
+            data: array.iter().cloned().collect(),
+        }
+    }
+
+    pub fn into_array(self) -> ArrayD<T> {
+        ArrayD::from_shape_vec(IxDyn(&self.shape), self.data).unwrap()
+    }
+}
+
+impl<T> Naive<T> {
+    fn from_fn(shape: Vec<usize>, mut f: impl FnMut(&[usize]) -> T) -> Self {
+        let data = (0..shape.iter().product())
+            .map(|flat| f(&unravel(flat, &shape)))
+            .collect();
+        Self { shape, data }
+    }
+
+    fn at(&self, index: &[usize]) -> &T {
+        &self.data[ravel(index, &self.shape)]
+    }
+}
+
+fn ravel(index: &[usize], shape: &[usize]) -> usize {
+    index.iter().zip(shape).fold(0, |flat, (&i, &n)| {
+        assert!(i < n, "index {index:?} out of bounds for {shape:?}");
+        flat * n + i
+    })
+}
+
+fn unravel(mut flat: usize, shape: &[usize]) -> Vec<usize> {
+    let mut index = vec![0; shape.len()];
+    for axis in (0..shape.len()).rev() {
+        index[axis] = flat % shape[axis];
+        flat /= shape[axis];
+    }
+    index
+}
+
+fn normalize(dim: isize, ndim: usize) -> usize {
+    if dim < 0 {
+        (dim + ndim as isize) as usize
+    } else {
+        dim as usize
+    }
+}
+
+/// `torch.gather`: `out[i][j][k] = x[i][index[i][j][k]][k]` for `dim == 1`.
+pub fn gather<T: Clone>(x: &Naive<T>, dim: isize, index: &Naive<i64>) -> Naive<T> {
+    let dim = normalize(dim, x.shape.len());
+    Naive::from_fn(index.shape.clone(), |i| {
+        let mut source = i.to_vec();
+        source[dim] = *index.at(i) as usize;
+        x.at(&source).clone()
+    })
+}
+
+/// `torch.Tensor.scatter_`: `target[i][index[i][j][k]][k] = src[i][j][k]` for `dim == 1`.
+pub fn scatter<T: Clone>(
+    target: &Naive<T>,
+    dim: isize,
+    index: &Naive<i64>,
+    src: &Naive<T>,
+) -> Naive<T> {
+    let dim = normalize(dim, target.shape.len());
+    let mut out = target.clone();
+    for flat in 0..index.data.len() {
+        let i = unravel(flat, &index.shape);
+        let mut destination = i.clone();
+        destination[dim] = index.data[flat] as usize;
+        let position = ravel(&destination, &out.shape);
+        out.data[position] = src.at(&i).clone();
+    }
+    out
+}
+
+/// Index of the first maximum, treating NaN as larger than everything like PyTorch.
+fn first_max<T: PartialOrd>(values: &[T]) -> usize {
+    let mut best = 0;
+    for (i, v) in values.iter().enumerate().skip(1) {
+        if total_cmp(v, &values[best]) == Ordering::Greater {
+            best = i;
+        }
+    }
+    best
+}
+
+/// Orders NaN (the only value not equal to itself) after every number.
+pub fn total_cmp<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    match (a.partial_cmp(a).is_none(), b.partial_cmp(b).is_none()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(b).unwrap(),
+    }
+}
+
+/// Lanes along `dim`: for every index of the other axes, the values along `dim`.
+fn reduce<T: Clone, U>(
+    x: &Naive<T>,
+    dim: usize,
+    keepdim: bool,
+    mut f: impl FnMut(&[T]) -> U,
+) -> Naive<U> {
+    let mut shape = x.shape.clone();
+    shape[dim] = 1;
+    let kept = Naive::<U>::from_fn(shape.clone(), |i| {
+        let lane: Vec<T> = (0..x.shape[dim])
+            .map(|k| {
+                let mut j = i.to_vec();
+                j[dim] = k;
+                x.at(&j).clone()
+            })
+            .collect();
+        f(&lane)
+    });
+    if !keepdim {
+        shape.remove(dim);
+    }
+    Naive {
+        shape,
+        data: kept.data,
+    }
+}
+
+/// `torch.argmax(x, dim, keepdim)`; `dim == None` argmaxes the flattened tensor.
+pub fn argmax<T: PartialOrd + Clone>(
+    x: &Naive<T>,
+    dim: Option<usize>,
+    keepdim: bool,
+) -> Naive<i64> {
+    match dim {
+        None => Naive {
+            shape: vec![],
+            data: vec![first_max(&x.data) as i64],
+        },
+        Some(dim) => reduce(x, dim, keepdim, |lane| first_max(lane) as i64),
+    }
+}
+
+/// `torch.max(x, dim)`, returning `(values, indices)`.
+pub fn max<T: PartialOrd + Clone>(x: &Naive<T>, dim: usize) -> (Naive<T>, Naive<i64>) {
+    let values = reduce(x, dim, false, |lane| lane[first_max(lane)].clone());
+    let indices = reduce(x, dim, false, |lane| first_max(lane) as i64);
+    (values, indices)
+}
+
+/// `torch.sort(x, dim=-1).values`, with NaN sorted last.
+pub fn sort_last_dim<T: PartialOrd + Clone>(x: &Naive<T>) -> Naive<T> {
+    let Some(&n) = x.shape.last() else {
+        return x.clone();
+    };
+    let mut out = x.clone();
+    if n > 0 {
+        for lane in out.data.chunks_mut(n) {
+            // Insertion sort, deliberately unlike the library's sort
+            for i in 1..lane.len() {
+                let mut j = i;
+                while j > 0 && total_cmp(&lane[j - 1], &lane[j]) == Ordering::Greater {
+                    lane.swap(j - 1, j);
+                    j -= 1;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// `torch.reshape`, where a single `-1` is inferred. `None` if the shape is incompatible.
+pub fn reshape<T: Clone>(x: &Naive<T>, shape: &[i64]) -> Option<Naive<T>> {
+    let known: i64 = shape.iter().filter(|&&d| d != -1).product();
+    let inferred = shape.iter().filter(|&&d| d == -1).count();
+    if shape.iter().any(|&d| d < -1) || inferred > 1 {
+        return None;
+    }
+    let len = x.data.len() as i64;
+    let shape: Vec<usize> = if inferred == 1 {
+        if known == 0 || len % known != 0 {
+            return None;
+        }
+        shape
+            .iter()
+            .map(|&d| if d == -1 { len / known } else { d } as usize)
+            .collect()
+    } else if known == len {
+        shape.iter().map(|&d| d as usize).collect()
+    } else {
+        return None;
+    };
+    Some(Naive {
+        shape,
+        data: x.data.clone(),
+    })
+}
+
+/// `x.transpose(dim0, dim1)`.
+pub fn transpose<T: Clone>(x: &Naive<T>, dim0: usize, dim1: usize) -> Naive<T> {
+    let mut shape = x.shape.clone();
+    shape.swap(dim0, dim1);
+    Naive::from_fn(shape, |i| {
+        let mut j = i.to_vec();
+        j.swap(dim0, dim1);
+        x.at(&j).clone()
+    })
+}
+
+/// `x.unsqueeze(dim).expand(..., size at dim, ...)`.
+pub fn expand<T: Clone>(x: &Naive<T>, dim: usize, size: usize) -> Naive<T> {
+    let mut shape = x.shape.clone();
+    shape.insert(dim, size);
+    Naive::from_fn(shape, |i| {
+        let mut j = i.to_vec();
+        j.remove(dim);
+        x.at(&j).clone()
+    })
+}
+
+/// `x[:, :, -1:]` generalised to any rank: the last element of the last axis, kept as size 1.
+pub fn slice_last_dim<T: Clone>(x: &Naive<T>) -> Naive<T> {
+    let last = x.shape.len() - 1;
+    let mut shape = x.shape.clone();
+    shape[last] = shape[last].min(1);
+    Naive::from_fn(shape, |i| {
+        let mut j = i.to_vec();
+        j[last] = x.shape[last] - 1;
+        x.at(&j).clone()
+    })
+}
+
+/// `x[:, :amount]`.
+pub fn slice_second_dim<T: Clone>(x: &Naive<T>, amount: usize) -> Naive<T> {
+    let mut shape = x.shape.clone();
+    shape[1] = shape[1].min(amount);
+    Naive::from_fn(shape, |i| x.at(i).clone())
+}
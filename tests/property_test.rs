@@ -0,0 +1,241 @@
+mod property;
+
+use RustOps::functions::{argmax, expand, gather, max, reshape, scatter, slicing, sort, transpose};
+use ndarray::{ArrayD, IxDyn};
+use property::oracle::{self, Naive};
+use property::{MAX_AXIS, array_with, nan_aware, shape, small_value, tensor, value_or_nan};
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// An input, a dim given either way round (`-ndim..ndim`), and a valid `int64` index tensor
+/// whose size along the dim is arbitrary and along the other axes is at most the input's.
+fn gather_case() -> impl Strategy<Value = (ArrayD<f32>, isize, ArrayD<i64>)> {
+    shape(1..=4)
+        .prop_flat_map(|shape| {
+            let ndim = shape.len() as isize;
+            (tensor_of(shape.clone()), -ndim..ndim, Just(shape))
+        })
+        .prop_flat_map(|(x, dim, shape)| {
+            let d = dim.rem_euclid(shape.len() as isize) as usize;
+            let index_shape: Vec<_> = shape
+                .iter()
+                .enumerate()
+                .map(|(k, &n)| match (k == d, shape[d]) {
+                    // Nothing can be gathered from an empty axis
+                    (true, 0) => Just(0).boxed(),
+                    (true, _) => (0..=MAX_AXIS).boxed(),
+                    (false, _) => (0..=n).boxed(),
+                })
+                .collect();
+            let bound = shape[d].max(1) as i64;
+            (Just(x), Just(dim), index_shape).prop_flat_map(move |(x, dim, index_shape)| {
+                (Just(x), Just(dim), array_with(index_shape, 0..bound))
+            })
+        })
+}
+
+fn tensor_of(shape: Vec<usize>) -> impl Strategy<Value = ArrayD<f32>> {
+    array_with(shape, small_value())
+}
+
+/// A target, a dim, an index with distinct entries along the dim (so the result does not
+/// depend on write order) and a source of the same shape as the index.
+fn scatter_case() -> impl Strategy<Value = (ArrayD<f32>, isize, ArrayD<i64>, ArrayD<f32>)> {
+    shape(1..=4)
+        .prop_flat_map(|shape| {
+            let ndim = shape.len() as isize;
+            let index_shape: Vec<_> = shape.iter().map(|&n| 0..=n).collect();
+            (tensor_of(shape), -ndim..ndim, index_shape)
+        })
+        .prop_flat_map(|(target, dim, index_shape)| {
+            let d = dim.rem_euclid(index_shape.len() as isize) as usize;
+            let size = target.shape()[d];
+            let lanes: usize = index_shape
+                .iter()
+                .enumerate()
+                .filter(|&(k, _)| k != d)
+                .map(|(_, &n)| n)
+                .product();
+            let keys = vec(any::<u32>(), lanes * size);
+            (
+                Just(target),
+                Just(dim),
+                Just(index_shape.clone()),
+                keys,
+                tensor_of(index_shape),
+            )
+        })
+        .prop_map(|(target, dim, index_shape, keys, src)| {
+            let d = dim.rem_euclid(index_shape.len() as isize) as usize;
+            let size = target.shape()[d];
+            // Each lane along `d` takes a prefix of a random permutation of 0..size
+            let mut lanes = keys.chunks(size.max(1)).map(|keys| {
+                let mut order: Vec<i64> = (0..size as i64).collect();
+                order.sort_by_key(|&i| keys[i as usize]);
+                order
+            });
+            let mut moved = index_shape.clone();
+            let n = moved.remove(d);
+            moved.push(n);
+            let mut data = Vec::new();
+            for _ in 0..moved.iter().take(moved.len() - 1).product::<usize>() {
+                data.extend(lanes.next().unwrap_or_default().into_iter().take(n));
+            }
+            // Built with the dim last, then moved into place
+            let mut axes: Vec<usize> = (0..index_shape.len()).collect();
+            let last = axes.pop().unwrap();
+            axes.insert(d, last);
+            let index = ArrayD::from_shape_vec(IxDyn(&moved), data)
+                .unwrap()
+                .permuted_axes(axes);
+            (target, dim, index, src)
+        })
+}
+
+proptest! {
+    #[test]
+    fn prop_gather_matches_oracle((x, dim, index) in gather_case()) {
+        let result = gather::gather(&x, dim, &index).unwrap();
+        let expected = oracle::gather(&Naive::from_array(&x), dim, &Naive::from_array(&index));
+        prop_assert_eq!(result, expected.into_array());
+    }
+
+    #[test]
+    fn prop_scatter_matches_oracle((target, dim, index, src) in scatter_case()) {
+        let mut result = target.clone();
+        scatter::scatter(&mut result, dim, &index, &src).unwrap();
+        let expected = oracle::scatter(
+            &Naive::from_array(&target),
+            dim,
+            &Naive::from_array(&index),
+            &Naive::from_array(&src),
+        );
+        prop_assert_eq!(result, expected.into_array());
+    }
+
+    #[test]
+    fn prop_argmax_matches_oracle(
+        (x, dim, keepdim) in tensor(0..=4).prop_flat_map(|x| {
+            let ndim = x.ndim();
+            (Just(x), proptest::option::of(0..ndim.max(1)), any::<bool>())
+        })
+    ) {
+        let dim = dim.filter(|&d| d < x.ndim());
+        let result = argmax::argmax(&x, dim, keepdim);
+        let empty = match dim {
+            None => x.is_empty(),
+            Some(d) => x.shape()[d] == 0,
+        };
+        if empty {
+            prop_assert!(result.is_err());
+        } else {
+            let keepdim = keepdim && dim.is_some();
+            let expected = oracle::argmax(&Naive::from_array(&x), dim, keepdim);
+            prop_assert_eq!(result.unwrap(), expected.into_array());
+        }
+    }
+
+    #[test]
+    fn prop_max_matches_oracle(
+        (x, dim) in tensor(1..=4).prop_flat_map(|x| {
+            let ndim = x.ndim();
+            (Just(x), 0..ndim)
+        })
+    ) {
+        let result = max::max(&x, dim);
+        if x.shape()[dim] == 0 {
+            prop_assert!(result.is_err());
+        } else {
+            let (values, indices) = result.unwrap();
+            let (expected_values, expected_indices) = oracle::max(&Naive::from_array(&x), dim);
+            prop_assert_eq!(values, expected_values.into_array());
+            prop_assert_eq!(indices, expected_indices.into_array());
+        }
+    }
+
+    #[test]
+    fn prop_sort_last_dim_matches_oracle(
+        x in shape(1..=4).prop_flat_map(|shape| array_with(shape, value_or_nan()))
+    ) {
+        let mut result = x.clone();
+        sort::sort_last_dim(&mut result);
+        let expected = oracle::sort_last_dim(&Naive::from_array(&x)).into_array();
+        prop_assert_eq!(nan_aware(&result), nan_aware(&expected));
+    }
+
+    #[test]
+    fn prop_reshape_matches_oracle(
+        (x, target) in tensor(0..=4).prop_flat_map(|x| {
+            let dims: Vec<i64> = x.shape().iter().map(|&n| n as i64).collect();
+            let ndim = dims.len();
+            // A permutation of the input's dims with optional merging and inference, which
+            // is always compatible, or a random shape, which usually is not
+            let compatible = (
+                Just(dims).prop_shuffle(),
+                proptest::option::of(0..ndim.max(1)),
+                proptest::option::of(0..ndim.max(1)),
+            )
+                .prop_map(move |(mut dims, merge, infer)| {
+                    if let Some(i) = merge.filter(|&i| i + 1 < dims.len()) {
+                        let merged = dims.remove(i + 1);
+                        dims[i] *= merged;
+                    }
+                    if let Some(i) = infer.filter(|&i| i < dims.len()) {
+                        dims[i] = -1;
+                    }
+                    dims
+                });
+            let random = vec(-1i64..=MAX_AXIS as i64, 0..=4);
+            (Just(x), prop_oneof![compatible, random])
+        })
+    ) {
+        let result = reshape::reshape(&x, &target);
+        match oracle::reshape(&Naive::from_array(&x), &target) {
+            Some(expected) => prop_assert_eq!(result.unwrap(), expected.into_array()),
+            None => prop_assert!(result.is_err(), "{:?} -> {:?}", x.shape(), target),
+        }
+    }
+
+    #[test]
+    fn prop_transpose_matches_oracle(
+        (x, dim0, dim1) in tensor(1..=4).prop_flat_map(|x| {
+            let ndim = x.ndim();
+            (Just(x), 0..ndim, 0..ndim)
+        })
+    ) {
+        let result = transpose::transpose_dims(&x, dim0, dim1);
+        let expected = oracle::transpose(&Naive::from_array(&x), dim0, dim1);
+        prop_assert_eq!(result, expected.into_array());
+    }
+
+    #[test]
+    fn prop_expand_matches_oracle(
+        (x, dim, size) in tensor(0..=4).prop_flat_map(|x| {
+            let ndim = x.ndim();
+            (Just(x), 0..=ndim, 0..=MAX_AXIS)
+        })
+    ) {
+        let result = expand::expand_at_dim(&x, dim, size).unwrap();
+        let expected = oracle::expand(&Naive::from_array(&x), dim, size);
+        prop_assert_eq!(result, expected.into_array());
+    }
+
+    #[test]
+    fn prop_slicing_matches_oracle(x in tensor(0..=5), amount in 0..=MAX_AXIS + 1) {
+        let naive = Naive::from_array(&x);
+        match slicing::slice_last_dim(&x) {
+            Ok(result) => {
+                prop_assert!(x.ndim() >= 3);
+                prop_assert_eq!(result, oracle::slice_last_dim(&naive).into_array());
+            }
+            Err(_) => prop_assert!(x.ndim() < 3),
+        }
+        match slicing::slice_second_dim(&x, amount) {
+            Ok(result) => {
+                prop_assert!(x.ndim() >= 2);
+                prop_assert_eq!(result, oracle::slice_second_dim(&naive, amount).into_array());
+            }
+            Err(_) => prop_assert!(x.ndim() < 2),
+        }
+    }
+}
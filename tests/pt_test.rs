@@ -0,0 +1,311 @@
+use RustOps::io::bundle::Bundle;
+use RustOps::io::pt::{PtError, load, read};
+use RustOps::io::{Dtype, Tensor};
+use half::{bf16, f16};
+use ndarray::{ArrayD, array};
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+use std::path::Path;
+use zip::write::{FileOptions, ZipWriter};
+
+#[test]
+fn test_pt_reference() {
+    // Like the conformance suite, a checkout without generated fixtures skips the comparison
+    if !Path::new("data/checkpoint.pt").exists() {
+        println!("SKIP: no data/checkpoint.pt; run reference/pt.py to generate it");
+        return;
+    }
+    let tensors = load("data/checkpoint.pt").unwrap();
+    let expected = Bundle::open("data/checkpoint.npz").unwrap();
+    assert_eq!(tensors.len(), expected.len());
+    for key in expected.keys() {
+        let tensor = &tensors[key];
+        let upcast = match tensor {
+            Tensor::F16(a) => Tensor::F32(a.mapv(f16::to_f32)),
+            Tensor::BF16(a) => Tensor::F32(a.mapv(bf16::to_f32)),
+            other => other.clone(),
+        };
+        assert_eq!(&upcast, expected.tensor(key).unwrap(), "tensor {key}");
+    }
+    assert_eq!(tensors["model.0.weight"].dtype(), Dtype::F32);
+    assert_eq!(tensors["model.1.num_batches_tracked"].dtype(), Dtype::I64);
+    assert_eq!(tensors["model.2.weight"].dtype(), Dtype::F16);
+}
+
+/// Emits the pickle opcodes `torch.save` produces, protocol 2.
+struct Pickle(Vec<u8>);
+
+impl Pickle {
+    fn new() -> Self {
+        Self(vec![0x80, 2])
+    }
+
+    fn op(&mut self, opcode: u8) -> &mut Self {
+        self.0.push(opcode);
+        self
+    }
+
+    fn global(&mut self, module: &str, name: &str) -> &mut Self {
+        self.0.push(b'c');
+        self.0
+            .extend_from_slice(format!("{module}\n{name}\n").as_bytes());
+        self
+    }
+
+    fn string(&mut self, s: &str) -> &mut Self {
+        self.0.push(b'X');
+        self.0.extend_from_slice(&(s.len() as u32).to_le_bytes());
+        self.0.extend_from_slice(s.as_bytes());
+        self
+    }
+
+    fn int(&mut self, i: i32) -> &mut Self {
+        self.0.push(b'J');
+        self.0.extend_from_slice(&i.to_le_bytes());
+        self
+    }
+
+    fn ints(&mut self, values: &[usize]) -> &mut Self {
+        self.op(b'(');
+        for &v in values {
+            self.int(v as i32);
+        }
+        self.op(b't')
+    }
+
+    /// `_rebuild_tensor_v2(storage, offset, shape, stride, False, OrderedDict())`
+    fn tensor(
+        &mut self,
+        storage: &str,
+        key: &str,
+        offset: usize,
+        shape: &[usize],
+        stride: &[usize],
+    ) -> &mut Self {
+        self.global("torch._utils", "_rebuild_tensor_v2").op(b'(');
+        self.op(b'(').string("storage").global("torch", storage);
+        self.string(key).string("cpu").int(0).op(b't').op(b'Q');
+        self.int(offset as i32).ints(shape).ints(stride).op(0x89);
+        self.global("collections", "OrderedDict").op(b')').op(b'R');
+        self.op(b't').op(b'R')
+    }
+
+    fn finish(&mut self) -> Vec<u8> {
+        self.op(b'.');
+        std::mem::take(&mut self.0)
+    }
+}
+
+fn archive(pickle: &[u8], storages: &[(&str, Vec<u8>)], byteorder: &str) -> Cursor<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    zip.start_file("checkpoint/data.pkl", options).unwrap();
+    zip.write_all(pickle).unwrap();
+    zip.start_file("checkpoint/byteorder", options).unwrap();
+    zip.write_all(byteorder.as_bytes()).unwrap();
+    for (key, bytes) in storages {
+        zip.start_file(format!("checkpoint/data/{key}"), options)
+            .unwrap();
+        zip.write_all(bytes).unwrap();
+    }
+    let mut cursor = zip.finish().unwrap();
+    cursor.set_position(0);
+    cursor
+}
+
+fn le_bytes<T: Copy, const N: usize>(values: &[T], encode: fn(T) -> [u8; N]) -> Vec<u8> {
+    values.iter().flat_map(|&v| encode(v)).collect()
+}
+
+fn sample_storages() -> Vec<(&'static str, Vec<u8>)> {
+    vec![
+        (
+            "0",
+            le_bytes(&[1.0f32, 2., 3., 4., 5., 6.], f32::to_le_bytes),
+        ),
+        ("1", le_bytes(&[7i64, 42], i64::to_le_bytes)),
+        ("2", vec![1, 0, 1]),
+        (
+            "3",
+            le_bytes(&[f16::from_f32(0.5), f16::from_f32(-2.0)], f16::to_le_bytes),
+        ),
+    ]
+}
+
+#[test]
+fn test_pt_state_dict() {
+    let mut p = Pickle::new();
+    // {"model": OrderedDict(...), "epoch": 3, "base": tensor}
+    p.op(b'}').op(b'(');
+    p.string("model");
+    p.global("collections", "OrderedDict")
+        .op(b')')
+        .op(b'R')
+        .op(b'(');
+    p.string("weight")
+        .tensor("FloatStorage", "0", 0, &[2, 3], &[3, 1]);
+    p.string("weight_t")
+        .tensor("FloatStorage", "0", 0, &[3, 2], &[1, 3]);
+    // nn.Parameter wraps the tensor in _rebuild_parameter
+    p.string("row")
+        .global("torch._utils", "_rebuild_parameter")
+        .op(b'(');
+    p.tensor("FloatStorage", "0", 3, &[3], &[1]);
+    p.op(0x88)
+        .global("collections", "OrderedDict")
+        .op(b')')
+        .op(b'R');
+    p.op(b't').op(b'R');
+    p.string("steps").tensor("LongStorage", "1", 1, &[], &[]);
+    p.op(b'u');
+    // state_dict() attaches `_metadata` through BUILD
+    p.op(b'}').string("_metadata").op(b'}').op(b's').op(b'b');
+    p.string("epoch").int(3);
+    p.string("mask").tensor("BoolStorage", "2", 0, &[3], &[1]);
+    p.string("half")
+        .tensor("HalfStorage", "3", 0, &[2, 1], &[1, 1]);
+    p.op(b'u');
+    let pickle = p.finish();
+
+    let tensors = read(archive(&pickle, &sample_storages(), "little")).unwrap();
+    let expected: HashMap<String, Tensor> = HashMap::from([
+        (
+            "model.weight".to_string(),
+            Tensor::F32(array![[1.0f32, 2., 3.], [4., 5., 6.]].into_dyn()),
+        ),
+        (
+            "model.weight_t".to_string(),
+            Tensor::F32(array![[1.0f32, 4.], [2., 5.], [3., 6.]].into_dyn()),
+        ),
+        (
+            "model.row".to_string(),
+            Tensor::F32(array![4.0f32, 5., 6.].into_dyn()),
+        ),
+        (
+            "model.steps".to_string(),
+            Tensor::I64(ArrayD::from_elem(vec![], 42)),
+        ),
+        (
+            "mask".to_string(),
+            Tensor::Bool(array![true, false, true].into_dyn()),
+        ),
+        (
+            "half".to_string(),
+            Tensor::F16(array![[f16::from_f32(0.5)], [f16::from_f32(-2.0)]].into_dyn()),
+        ),
+    ]);
+    assert_eq!(tensors, expected);
+}
+
+#[test]
+fn test_pt_big_endian() {
+    let mut p = Pickle::new();
+    p.op(b'}')
+        .string("x")
+        .tensor("DoubleStorage", "0", 0, &[2], &[1]);
+    p.op(b's');
+    let storages = [("0", le_bytes(&[1.5f64, -2.0], f64::to_be_bytes))];
+    let tensors = read(archive(&p.finish(), &storages, "big")).unwrap();
+    assert_eq!(tensors["x"], Tensor::F64(array![1.5, -2.0].into_dyn()));
+}
+
+#[test]
+fn test_pt_rejects_unsafe_globals() {
+    // The classic `os.system` payload must never be resolved, let alone called
+    let mut p = Pickle::new();
+    p.global("os", "system")
+        .string("echo pwned")
+        .op(0x85)
+        .op(b'R');
+    let err = read(archive(&p.finish(), &[], "little")).unwrap_err();
+    assert!(
+        matches!(&err, PtError::UnsafeGlobal { module, name } if module == "os" && name == "system"),
+        "{err:?}"
+    );
+
+    // Same through STACK_GLOBAL, which protocol 4 uses
+    let mut p = Pickle::new();
+    p.string("builtins").string("eval").op(0x93);
+    let err = read(archive(&p.finish(), &[], "little")).unwrap_err();
+    assert!(matches!(err, PtError::UnsafeGlobal { .. }), "{err:?}");
+
+    // Allowed globals cannot be smuggled in as persistent ids or called with other arguments
+    let mut p = Pickle::new();
+    p.global("torch._utils", "_rebuild_tensor_v2")
+        .string("x")
+        .op(0x85)
+        .op(b'R');
+    let err = read(archive(&p.finish(), &[], "little")).unwrap_err();
+    assert!(matches!(err, PtError::Pickle { .. }), "{err:?}");
+}
+
+#[test]
+fn test_pt_errors() {
+    let storages = sample_storages();
+
+    let mut p = Pickle::new();
+    p.op(b'}')
+        .string("x")
+        .tensor("IntStorage", "0", 0, &[2], &[1]);
+    let err = read(archive(&p.finish(), &storages, "little")).unwrap_err();
+    assert!(
+        matches!(&err, PtError::UnsupportedStorage { storage } if storage == "IntStorage"),
+        "{err:?}"
+    );
+
+    // A view that runs past the six elements of storage 0
+    let mut p = Pickle::new();
+    p.op(b'}')
+        .string("x")
+        .tensor("FloatStorage", "0", 2, &[2, 3], &[3, 1]);
+    p.op(b's');
+    let err = read(archive(&p.finish(), &storages, "little")).unwrap_err();
+    assert!(
+        matches!(err, PtError::OutOfBounds { len: 6, .. }),
+        "{err:?}"
+    );
+
+    let mut p = Pickle::new();
+    p.op(b'}')
+        .string("x")
+        .tensor("FloatStorage", "9", 0, &[1], &[1]);
+    p.op(b's');
+    let err = read(archive(&p.finish(), &storages, "little")).unwrap_err();
+    assert!(matches!(err, PtError::MissingStorage { .. }), "{err:?}");
+
+    // Bool storages only hold 0 or 1
+    let mut p = Pickle::new();
+    p.op(b'}')
+        .string("x")
+        .tensor("BoolStorage", "0", 0, &[1], &[1]);
+    p.op(b's');
+    let err = read(archive(&p.finish(), &storages, "little")).unwrap_err();
+    assert!(matches!(err, PtError::InvalidValue { .. }), "{err:?}");
+
+    let mut p = Pickle::new();
+    p.op(b']');
+    let err = read(archive(&p.finish(), &storages, "little")).unwrap_err();
+    assert!(matches!(err, PtError::NotADict), "{err:?}");
+
+    let mut p = Pickle::new();
+    p.op(b'}').op(0xff);
+    let err = read(archive(&p.finish(), &storages, "little")).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            PtError::UnsupportedOpcode {
+                opcode: 0xff,
+                offset: 3
+            }
+        ),
+        "{err:?}"
+    );
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("archive/version", FileOptions::default())
+        .unwrap();
+    zip.write_all(b"3\n").unwrap();
+    let mut cursor = zip.finish().unwrap();
+    cursor.set_position(0);
+    assert!(matches!(read(cursor), Err(PtError::MissingPickle)));
+}
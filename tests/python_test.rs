@@ -0,0 +1,167 @@
+#![cfg(feature = "python")]
+
+use RustOps::python::rustops;
+use pyo3::exceptions::PyImportError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::ffi::CStr;
+
+const OPS: [&str; 27] = [
+    "abs",
+    "argmax",
+    "einsum",
+    "einsum_named",
+    "expand",
+    "flip",
+    "gather",
+    "matmul",
+    "bmm",
+    "baddbmm",
+    "addmm",
+    "max",
+    "ones",
+    "pad",
+    "rearrange_batch_mems_flag",
+    "reduce",
+    "repeat_interleave",
+    "reshape",
+    "roll",
+    "scatter",
+    "slice_last_dim",
+    "slice_second_dim",
+    "sort_last_dim",
+    "tile",
+    "transpose",
+    "tril",
+    "triu",
+];
+
+/// Runs `code` with the module bound to `rustops` and NumPy to `np`. Skipped if NumPy is not
+/// installed.
+fn run_with_numpy(code: &CStr) {
+    Python::initialize();
+    Python::attach(|py| {
+        let Ok(numpy) = py.import("numpy") else {
+            eprintln!("SKIP: numpy is not installed");
+            return;
+        };
+        let globals = PyDict::new(py);
+        globals.set_item("np", numpy).unwrap();
+        globals
+            .set_item("rustops", pyo3::wrap_pymodule!(rustops)(py))
+            .unwrap();
+        if let Err(err) = py.run(code, Some(&globals), None) {
+            err.display(py);
+            panic!("{err}");
+        }
+    })
+}
+
+#[test]
+fn test_module_exports_every_op() {
+    Python::initialize();
+    Python::attach(|py| {
+        let module = PyModule::new(py, "rustops").unwrap();
+        if let Err(err) = rustops(&module) {
+            assert!(err.is_instance_of::<PyImportError>(py), "{err}");
+            assert!(py.import("numpy").is_err());
+            eprintln!("SKIP: numpy is not installed");
+            return;
+        }
+        for op in OPS {
+            let function = module
+                .getattr(op)
+                .unwrap_or_else(|_| panic!("missing {op}"));
+            assert!(function.is_callable(), "{op}");
+            let doc: String = function.getattr("__doc__").unwrap().extract().unwrap();
+            assert!(doc.contains(op), "{op}: {doc}");
+        }
+    });
+}
+
+#[test]
+fn test_ops() {
+    run_with_numpy(
+        c"
+x = np.array([[1.0, 5.0, 2.0], [7.0, 0.0, 3.0]], dtype=np.float32)
+idx = np.array([[1], [0]], dtype=np.int64)
+assert np.array_equal(rustops.argmax(x, -1, keepdim=True), idx)
+assert rustops.argmax(x).shape == () and rustops.argmax(x) == 3
+y = rustops.gather(x, -1, idx)
+assert y.dtype == np.float32 and np.array_equal(y, [[5.0], [7.0]])
+values, indices = rustops.max(x, 1)
+assert np.array_equal(values, [5.0, 7.0]) and np.array_equal(indices, [1, 0])
+s = rustops.scatter(np.zeros_like(x), 1, idx, np.ones((2, 1), dtype=np.float32))
+assert np.array_equal(s, [[0, 1, 0], [1, 0, 0]]) and not x[0, 1] == 0
+assert np.allclose(rustops.einsum('ij,kj->ik', x, x), x @ x.T)
+assert np.allclose(rustops.einsum_named('a b, c b -> a c', x, x), x @ x.T)
+assert np.allclose(rustops.matmul(x, x.T), x @ x.T)
+assert np.array_equal(rustops.reshape(x, [3, -1]), x.reshape(3, -1))
+assert np.array_equal(rustops.transpose(x, 0, -1), x.T)
+assert np.array_equal(rustops.flip(x, [0]), x[::-1])
+assert np.array_equal(rustops.roll(x, [1], [1]), np.roll(x, 1, 1))
+assert np.array_equal(rustops.tile(x, [2, 1]), np.tile(x, (2, 1)))
+assert np.array_equal(rustops.tril(x), np.tril(x)) and np.array_equal(rustops.triu(x, 1), np.triu(x, 1))
+assert np.array_equal(rustops.sort_last_dim(x), np.sort(x, -1))
+assert np.array_equal(rustops.repeat_interleave(x, 2, 0), np.repeat(x, 2, 0))
+assert np.array_equal(rustops.repeat_interleave(x, np.array([1, 0, 2]), 1), np.repeat(x, [1, 0, 2], 1))
+assert np.array_equal(rustops.pad(x, [1, 0], 'constant', 9.0), np.pad(x, ((0, 0), (1, 0)), constant_values=9))
+assert np.array_equal(rustops.pad(x, [1, 1], 'reflect'), np.pad(x, ((0, 0), (1, 1)), mode='reflect'))
+assert np.array_equal(rustops.slice_last_dim(x), x[..., 1:])
+assert np.array_equal(rustops.abs(-x), x) and rustops.ones([2, 3]).dtype == np.float32
+assert np.array_equal(rustops.expand(x, 0, 2), np.broadcast_to(x, (2, 2, 3)))
+assert np.array_equal(rustops.reduce(x, 'ab,ab->a'), x.sum(1))
+",
+    );
+}
+
+#[test]
+fn test_layouts_and_dtypes() {
+    run_with_numpy(
+        c"
+x = np.arange(24, dtype=np.float64).reshape(2, 3, 4)
+# Transposed, strided and Fortran-ordered inputs are copied in logical order
+for view in (x.transpose(2, 0, 1), x[:, ::2, ::-1], np.asfortranarray(x)):
+    assert np.array_equal(rustops.reshape(view, [-1]), view.reshape(-1))
+    assert np.array_equal(rustops.flip(view, [0]), view[::-1])
+assert rustops.flip(x, [0]).flags['C_CONTIGUOUS']
+for dtype in (np.float64, np.float32, np.float16, np.int64, np.bool_):
+    assert rustops.tile(x.astype(dtype), [1, 1, 1]).dtype == dtype
+assert rustops.reshape(np.zeros((0, 3), dtype=np.float32), [3, 0]).shape == (3, 0)
+# float16 products and sums are computed in float32 and rounded once
+h = np.full((1, 4096), 1, dtype=np.float16)
+assert rustops.matmul(h, h.T).dtype == np.float16 and rustops.matmul(h, h.T)[0, 0] == 4096
+assert rustops.einsum('ij,ij->', h, h) == 4096 and rustops.reduce(h, 'ab,ab->a')[0] == 4096
+assert rustops.transpose(h, 0, 1).dtype == np.float16 and rustops.expand(h, 0, 2).shape == (2, 1, 4096)
+",
+    );
+}
+
+#[test]
+fn test_errors_map_to_python_exceptions() {
+    run_with_numpy(
+        c"
+x = np.zeros((2, 3), dtype=np.float32)
+def raises(exception, f, *args, match=''):
+    try:
+        f(*args)
+    except exception as err:
+        assert match in str(err), str(err)
+    else:
+        raise AssertionError(f'{f.__name__} did not raise {exception.__name__}')
+raises(IndexError, rustops.gather, x, 1, np.array([[3], [0]]), match='out of bounds')
+raises(IndexError, rustops.scatter, x, 1, np.array([[-1], [0]]), np.ones((2, 1), dtype=np.float32))
+raises(TypeError, rustops.gather, x, 1, np.array([[0], [0]], dtype=np.int32), match='int32')
+raises(TypeError, rustops.gather, [[1.0]], 1, np.array([[0]]), match='list')
+raises(TypeError, rustops.matmul, x, x.T.astype(np.float64), match='float64')
+raises(TypeError, rustops.abs, x.astype(np.int64), match='abs')
+raises(ValueError, rustops.reshape, x, [4, -1])
+raises(ValueError, rustops.matmul, x, x)
+raises(ValueError, rustops.argmax, x, 2, match='dim')
+raises(ValueError, rustops.pad, x, [1], match='pairs')
+raises(ValueError, rustops.pad, x, [1, 1], 'mirror', match='mirror')
+raises(ValueError, rustops.repeat_interleave, x, -1, match='negative')
+raises(ValueError, rustops.einsum, 'ij')
+",
+    );
+}
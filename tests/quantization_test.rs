@@ -0,0 +1,277 @@
+mod common;
+
+use RustOps::nn::functional::linear;
+use RustOps::quantization::{
+    MinMaxObserver, Observer, PerChannelMinMaxObserver, PercentileObserver, QParams, QScheme,
+    QTensor, QuantizationError, dequantize, qlinear, qmatmul, quantize, quantize_per_channel,
+    quantize_per_tensor,
+};
+use approx::assert_abs_diff_eq;
+use common::uniform;
+use ndarray::{ArrayD, Axis, Ix2, IxDyn, array};
+
+fn observe<O: Observer>(mut observer: O, x: &ArrayD<f32>) -> QParams {
+    observer.observe(x).unwrap();
+    observer.qparams().unwrap()
+}
+
+#[test]
+fn test_quantize_rounding() {
+    let x = array![-1.0f32, 0.0, 0.25, 0.75, 1.0, 100.0, -100.0].into_dyn();
+    // Ties round to even and the result saturates, like `torch.quantize_per_tensor`
+    let q = quantize_per_tensor::<i8>(&x, 0.5, 0).unwrap();
+    assert_eq!(
+        q.int_repr(),
+        &array![-2i8, 0, 0, 2, 2, 127, -128].into_dyn()
+    );
+    let q = quantize_per_tensor::<u8>(&x, 0.5, 128).unwrap();
+    assert_eq!(
+        q.int_repr(),
+        &array![126u8, 128, 128, 130, 130, 255, 0].into_dyn()
+    );
+    // The zero point is added after rounding
+    let q = quantize_per_tensor::<u8>(&x, 0.5, 1).unwrap();
+    assert_eq!(q.int_repr()[2], 1);
+    assert_eq!(
+        dequantize(&q),
+        array![-0.5f32, 0.0, 0.0, 1.0, 1.0, 100.0, -0.5].into_dyn()
+    );
+}
+
+#[test]
+fn test_quantize_per_channel() {
+    let x = array![[0.1f32, -0.2, 0.3], [10.0, -20.0, 30.0]].into_dyn();
+    let q = quantize_per_channel::<i8>(&x, &[0.1, 10.0], &[0, 1], 0).unwrap();
+    assert_eq!(q.int_repr(), &array![[1i8, -2, 3], [2, -1, 4]].into_dyn());
+    assert_abs_diff_eq!(q.dequantize(), x, epsilon = 1e-6);
+
+    let q = quantize_per_channel::<i8>(&x, &[0.1, 0.1, 0.1], &[0; 3], 1).unwrap();
+    assert_eq!(
+        q.int_repr().index_axis(Axis(1), 0),
+        array![1i8, 100].into_dyn()
+    );
+}
+
+#[test]
+fn test_quantize_errors() {
+    let x = ArrayD::zeros(IxDyn(&[2, 3]));
+    assert_eq!(
+        quantize_per_tensor::<i8>(&x, 0.0, 0).unwrap_err(),
+        QuantizationError::InvalidScale { scale: 0.0 }
+    );
+    assert!(matches!(
+        quantize_per_tensor::<u8>(&x, 1.0, -1),
+        Err(QuantizationError::ZeroPointOutOfRange {
+            qmin: 0,
+            qmax: 255,
+            ..
+        })
+    ));
+    assert!(matches!(
+        quantize_per_channel::<i8>(&x, &[1.0; 3], &[0; 3], 0),
+        Err(QuantizationError::ChannelCountMismatch { channels: 2, .. })
+    ));
+    assert_eq!(
+        quantize_per_channel::<i8>(&x, &[1.0], &[0], 2).unwrap_err(),
+        QuantizationError::InvalidAxis { axis: 2, ndim: 2 }
+    );
+    let values = ArrayD::zeros(IxDyn(&[2]));
+    let qparams = QParams::PerTensor {
+        scale: f32::NAN,
+        zero_point: 0,
+    };
+    assert!(QTensor::<i8>::from_parts(values, qparams).is_err());
+}
+
+#[test]
+fn test_min_max_observer() {
+    let x = array![-1.0f32, 0.5, 3.0].into_dyn();
+    // Matches `MinMaxObserver(dtype, qscheme).calculate_qparams()`
+    let QParams::PerTensor { scale, zero_point } =
+        observe(MinMaxObserver::<u8>::new(QScheme::Affine), &x)
+    else {
+        panic!("expected per-tensor parameters");
+    };
+    assert_abs_diff_eq!(scale, 4.0 / 255.0);
+    assert_eq!(zero_point, 64);
+    assert_eq!(
+        observe(MinMaxObserver::<i8>::new(QScheme::Symmetric), &x),
+        QParams::PerTensor {
+            scale: 3.0 / 127.5,
+            zero_point: 0
+        }
+    );
+    assert_eq!(
+        observe(MinMaxObserver::<u8>::new(QScheme::Symmetric), &x),
+        QParams::PerTensor {
+            scale: 3.0 / 127.5,
+            zero_point: 128
+        }
+    );
+    // The range always includes zero, and the scale never collapses
+    let positive = array![1.0f32, 2.0].into_dyn();
+    let QParams::PerTensor { zero_point, .. } =
+        observe(MinMaxObserver::<u8>::new(QScheme::Affine), &positive)
+    else {
+        panic!("expected per-tensor parameters");
+    };
+    assert_eq!(zero_point, 0);
+    assert_eq!(
+        observe(
+            MinMaxObserver::<i8>::new(QScheme::Affine),
+            &ArrayD::zeros(IxDyn(&[4]))
+        ),
+        QParams::PerTensor {
+            scale: f32::EPSILON,
+            zero_point: -128
+        }
+    );
+
+    // Batches accumulate, and NaN is ignored
+    let mut observer = MinMaxObserver::<u8>::new(QScheme::Affine);
+    assert_eq!(observer.qparams(), Err(QuantizationError::NoObservations));
+    observer
+        .observe(&array![0.5f32, f32::NAN].into_dyn())
+        .unwrap();
+    observer.observe(&array![-2.0f32, 1.0].into_dyn()).unwrap();
+    assert_eq!(observer.range(), Some((-2.0, 1.0)));
+}
+
+#[test]
+fn test_per_channel_observer() {
+    let w = array![[0.5f32, -1.0], [2.0, 0.25], [0.0, 0.0]].into_dyn();
+    let mut observer = PerChannelMinMaxObserver::<i8>::new(QScheme::Symmetric, 0);
+    observer.observe(&w).unwrap();
+    assert_eq!(observer.ranges(), &[(-1.0, 0.5), (0.25, 2.0), (0.0, 0.0)]);
+    let QParams::PerChannel {
+        scales,
+        zero_points,
+        axis,
+    } = observer.qparams().unwrap()
+    else {
+        panic!("expected per-channel parameters");
+    };
+    assert_eq!(scales, vec![1.0 / 127.5, 2.0 / 127.5, f32::EPSILON]);
+    assert_eq!(zero_points, vec![0; 3]);
+    assert_eq!(axis, 0);
+
+    assert_eq!(
+        observer.observe(&ArrayD::zeros(IxDyn(&[2, 2]))),
+        Err(QuantizationError::ChannelCountChanged {
+            axis: 0,
+            expected: 3,
+            actual: 2
+        })
+    );
+    assert_eq!(
+        PerChannelMinMaxObserver::<i8>::new(QScheme::Affine, 2).observe(&w),
+        Err(QuantizationError::InvalidAxis { axis: 2, ndim: 2 })
+    );
+}
+
+#[test]
+fn test_percentile_observer() {
+    let mut x: Vec<f32> = (0..=1000).map(|i| i as f32 / 1000.0).collect();
+    x.push(1000.0);
+    let x = ArrayD::from_shape_vec(IxDyn(&[x.len()]), x).unwrap();
+
+    let mut min_max = MinMaxObserver::<u8>::new(QScheme::Affine);
+    min_max.observe(&x).unwrap();
+    assert_eq!(min_max.range(), Some((0.0, 1000.0)));
+
+    // The outlier is clipped instead of stretching the scale a thousandfold
+    let mut percentile = PercentileObserver::<u8>::new(QScheme::Affine, 99.9).unwrap();
+    percentile.observe(&x).unwrap();
+    let (low, high) = percentile.range().unwrap();
+    assert!(low > 0.0 && low < 0.002, "{low}");
+    assert!(high > 0.99 && high <= 1.0, "{high}");
+    let QParams::PerTensor { scale, .. } = percentile.qparams().unwrap() else {
+        panic!("expected per-tensor parameters");
+    };
+    assert_abs_diff_eq!(scale, high / 255.0);
+
+    let mut full = PercentileObserver::<u8>::new(QScheme::Affine, 100.0).unwrap();
+    full.observe(&x).unwrap();
+    assert_eq!(full.range(), min_max.range());
+    assert_eq!(
+        PercentileObserver::<i8>::new(QScheme::Symmetric, 50.0).unwrap_err(),
+        QuantizationError::InvalidPercentile { percentile: 50.0 }
+    );
+}
+
+#[test]
+fn test_qlinear_accuracy() {
+    let x = uniform::<f32>(&[2, 8, 64], 1);
+    let w = uniform::<f32>(&[16, 64], 2).mapv(|v| v * 0.1);
+    let b = uniform::<f32>(&[16], 3);
+    let expected = linear(&x, &w, Some(&b)).unwrap();
+
+    let xq = quantize::<u8>(&x, observe(MinMaxObserver::<u8>::new(QScheme::Affine), &x)).unwrap();
+    let w_qparams = observe(
+        PerChannelMinMaxObserver::<i8>::new(QScheme::Symmetric, 0),
+        &w,
+    );
+    let wq = quantize::<i8>(&w, w_qparams).unwrap();
+    let QParams::PerTensor { scale, zero_point } =
+        observe(MinMaxObserver::<u8>::new(QScheme::Affine), &expected)
+    else {
+        panic!("expected per-tensor parameters");
+    };
+    let yq: QTensor<u8> = qlinear(&xq, &wq, Some(&b), scale, zero_point).unwrap();
+    assert_eq!(yq.shape(), &[2, 8, 16]);
+
+    // The integer kernel agrees with requantizing the f32 product of the dequantized operands
+    let simulated = linear(&xq.dequantize(), &wq.dequantize(), Some(&b)).unwrap();
+    let simulated = quantize_per_tensor::<u8>(&simulated, scale, zero_point).unwrap();
+    for (&a, &b) in yq.int_repr().iter().zip(simulated.int_repr()) {
+        assert!(a.abs_diff(b) <= 1, "{a} vs {b}");
+    }
+
+    // and stays within a few quantization steps of the f32 layer
+    let error = (yq.dequantize() - &expected).mapv(f32::abs);
+    let max_error = error.iter().cloned().fold(0.0, f32::max);
+    assert!(max_error < 4.0 * scale, "{max_error} vs scale {scale}");
+}
+
+#[test]
+fn test_qmatmul() {
+    let a = uniform::<f32>(&[5, 32], 4);
+    let b = uniform::<f32>(&[32, 7], 5);
+    let (a2, b2) = (
+        a.view().into_dimensionality::<Ix2>(),
+        b.view().into_dimensionality::<Ix2>(),
+    );
+    let expected = a2.unwrap().dot(&b2.unwrap());
+
+    let aq = quantize_per_tensor::<i8>(&a, 1.0 / 127.0, 0).unwrap();
+    let bq = quantize::<i8>(
+        &b,
+        observe(
+            PerChannelMinMaxObserver::<i8>::new(QScheme::Symmetric, 1),
+            &b,
+        ),
+    )
+    .unwrap();
+    let yq: QTensor<i8> = qmatmul(&aq, &bq, 0.05, 0).unwrap();
+    assert_abs_diff_eq!(yq.dequantize(), expected.into_dyn(), epsilon = 0.05 * 2.0);
+
+    // Products accumulate in i32 without saturating: 1024 * 127 * 127 is far outside i16
+    let ones = ArrayD::from_elem(IxDyn(&[1, 1024]), 1.0f32);
+    let aq = quantize_per_tensor::<i8>(&ones, 1.0 / 127.0, 0).unwrap();
+    let bq = quantize_per_tensor::<i8>(&ones.t().to_owned(), 1.0 / 127.0, 0).unwrap();
+    let yq: QTensor<u8> = qmatmul(&aq, &bq, 8.0, 0).unwrap();
+    assert_eq!(yq.int_repr()[[0, 0]], 128);
+
+    assert!(matches!(
+        qmatmul::<i8, i8, i8>(&aq, &aq, 1.0, 0),
+        Err(QuantizationError::ShapeMismatch { .. })
+    ));
+    let per_row = quantize_per_channel::<i8>(&ones, &[1.0], &[0], 0).unwrap();
+    assert_eq!(
+        qmatmul::<i8, i8, i8>(&per_row, &bq, 1.0, 0).unwrap_err(),
+        QuantizationError::UnsupportedQParams {
+            argument: "input",
+            expected: "per tensor"
+        }
+    );
+}
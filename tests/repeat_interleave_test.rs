@@ -0,0 +1,47 @@
+use RustOps::functions::repeat_interleave::{
+    RepeatInterleaveError, repeat_interleave, repeat_interleave_tensor,
+};
+use ndarray::array;
+
+#[test]
+fn test_repeat_interleave_small() {
+    let x = array![[1, 2], [3, 4]].into_dyn();
+
+    assert_eq!(
+        repeat_interleave(&x, 2, Some(0)).unwrap(),
+        array![[1, 2], [1, 2], [3, 4], [3, 4]].into_dyn()
+    );
+    assert_eq!(
+        repeat_interleave(&x, 2, None).unwrap(),
+        array![1, 1, 2, 2, 3, 3, 4, 4].into_dyn()
+    );
+
+    let repeats = array![1i64, 2].into_dyn();
+    assert_eq!(
+        repeat_interleave_tensor(&x, &repeats, Some(-1)).unwrap(),
+        array![[1, 2, 2], [3, 4, 4]].into_dyn()
+    );
+}
+
+#[test]
+fn test_repeat_interleave_errors() {
+    let x = array![[1.0f32, 2.0], [3.0, 4.0]].into_dyn();
+
+    let repeats = array![1i64, 2, 3].into_dyn();
+    assert_eq!(
+        repeat_interleave_tensor(&x, &repeats, Some(0)),
+        Err(RepeatInterleaveError::RepeatsLengthMismatch {
+            len: 3,
+            dim_size: 2
+        })
+    );
+
+    let repeats = array![1i64, -2].into_dyn();
+    assert_eq!(
+        repeat_interleave_tensor(&x, &repeats, Some(0)),
+        Err(RepeatInterleaveError::NegativeRepeat {
+            position: 1,
+            value: -2
+        })
+    );
+}
@@ -0,0 +1,190 @@
+use RustOps::rng::{
+    DEFAULT_SEED, Generator, RngError, bernoulli, multinomial, normal_, rand, randint, randn,
+    randperm, uniform_,
+};
+use approx::assert_abs_diff_eq;
+use ndarray::{ArrayD, IxDyn, array};
+
+#[test]
+fn test_mt19937_stream() {
+    // The reference outputs of `std::mt19937`
+    let mut generator = Generator::new(5489);
+    assert_eq!(generator.random(), 3_499_211_612);
+    let last = (1..10_000).map(|_| generator.random()).last().unwrap();
+    assert_eq!(last, 4_123_659_995);
+
+    // `torch.manual_seed(0)` uses the low 32 bits, and `random64` puts the first draw high
+    let mut generator = Generator::new(0);
+    assert_eq!(generator.random(), 2_357_136_044);
+    assert_eq!(
+        generator.random64(),
+        (2_546_248_239u64 << 32) | 3_071_714_933
+    );
+    let mut wide = Generator::new(1 << 32);
+    assert_eq!(wide.random(), 2_357_136_044);
+    assert_eq!(wide.initial_seed(), 1 << 32);
+
+    generator.manual_seed(0);
+    assert_eq!(generator.random(), 2_357_136_044);
+    assert_eq!(Generator::default().initial_seed(), DEFAULT_SEED);
+}
+
+#[test]
+fn test_known_torch_values() {
+    let mut generator = Generator::new(0);
+    // torch.manual_seed(0); torch.rand(2, 3)
+    let x: ArrayD<f32> = rand(&[2, 3], &mut generator);
+    assert_eq!(x[[0, 0]], 8_325_804.0 / (1 << 24) as f32);
+    assert_abs_diff_eq!(
+        x,
+        array![[0.4963, 0.7682, 0.0885], [0.1320, 0.3074, 0.6341]].into_dyn(),
+        epsilon = 1e-4
+    );
+
+    // torch.manual_seed(0); torch.randn(2, 3)
+    generator.manual_seed(0);
+    let x: ArrayD<f32> = randn(&[2, 3], &mut generator);
+    assert_abs_diff_eq!(
+        x,
+        array![[1.5410, -0.2934, -2.1788], [0.5684, -1.0845, -1.3986]].into_dyn(),
+        epsilon = 1e-4
+    );
+
+    // torch.manual_seed(0); torch.randint(0, 10, (5,))
+    generator.manual_seed(0);
+    assert_eq!(
+        randint(0, 10, &[5], &mut generator).unwrap(),
+        array![4i64, 9, 3, 0, 3].into_dyn()
+    );
+}
+
+#[test]
+fn test_normal_cache_and_reseed() {
+    // A pair of uniforms yields two normals; the second is handed out by the next call
+    let mut generator = Generator::new(7);
+    let pair: ArrayD<f64> = randn(&[2], &mut generator);
+    generator.manual_seed(7);
+    let first: ArrayD<f64> = randn(&[1], &mut generator);
+    let second: ArrayD<f64> = randn(&[1], &mut generator);
+    assert_eq!(first[[0]], pair[[0]]);
+    assert_eq!(second[[0]], pair[[1]]);
+
+    // Reseeding drops the cached value
+    generator.manual_seed(7);
+    let _: ArrayD<f64> = randn(&[1], &mut generator);
+    generator.manual_seed(7);
+    let again: ArrayD<f64> = randn(&[1], &mut generator);
+    assert_eq!(again, first);
+}
+
+#[test]
+fn test_distribution_statistics() {
+    let mut generator = Generator::new(42);
+    for size in [15, 16, 4096, 4101] {
+        let mut x = ArrayD::<f32>::zeros(IxDyn(&[size]));
+        normal_(&mut x, 3.0, 2.0, &mut generator);
+        if size >= 4096 {
+            let mean = x.mean().unwrap();
+            let std = x.std(1.0);
+            assert!((mean - 3.0).abs() < 0.1, "{mean}");
+            assert!((std - 2.0).abs() < 0.1, "{std}");
+        }
+        assert!(x.iter().all(|v| v.is_finite()));
+    }
+
+    let mut x = ArrayD::<f64>::zeros(IxDyn(&[1000]));
+    uniform_(&mut x, -2.0, 3.0, &mut generator);
+    assert!(x.iter().all(|&v| (-2.0..3.0).contains(&v)));
+
+    let x = randint(-3, 4, &[1000], &mut generator).unwrap();
+    assert!(x.iter().all(|&v| (-3..4).contains(&v)));
+    assert!((-3..4).all(|v| x.iter().any(|&x| x == v)));
+    let wide = randint(0, 1 << 40, &[100], &mut generator).unwrap();
+    assert!(wide.iter().any(|&v| v >= 1 << 32));
+
+    let p = ArrayD::from_elem(IxDyn(&[10_000]), 0.25f32);
+    let draws = bernoulli(&p, &mut generator).unwrap();
+    assert!(draws.iter().all(|&v| v == 0.0 || v == 1.0));
+    assert!((draws.mean().unwrap() - 0.25).abs() < 0.02);
+}
+
+#[test]
+fn test_randperm() {
+    let mut generator = Generator::new(3);
+    let mut perm = randperm(50, &mut generator).into_raw_vec();
+    assert_ne!(perm, (0..50).collect::<Vec<i64>>());
+    perm.sort();
+    assert_eq!(perm, (0..50).collect::<Vec<i64>>());
+    assert_eq!(randperm(0, &mut generator).len(), 0);
+    assert_eq!(randperm(1, &mut generator), array![0i64].into_dyn());
+}
+
+#[test]
+fn test_multinomial() {
+    let mut generator = Generator::new(5);
+    let weights = array![[1.0f32, 0.0, 3.0], [0.0, 2.0, 0.0]].into_dyn();
+    let draws = multinomial(&weights, 4000, true, &mut generator).unwrap();
+    assert_eq!(draws.shape(), &[2, 4000]);
+    let first = draws.index_axis(ndarray::Axis(0), 0);
+    assert!(first.iter().all(|&v| v == 0 || v == 2));
+    let share = first.iter().filter(|&&v| v == 2).count() as f32 / 4000.0;
+    assert!((share - 0.75).abs() < 0.03, "{share}");
+    assert!(
+        draws
+            .index_axis(ndarray::Axis(0), 1)
+            .iter()
+            .all(|&v| v == 1)
+    );
+
+    // Without replacement every category appears once, zero weights last
+    let p = array![0.1f64, 0.0, 0.6, 0.3].into_dyn();
+    let mut draws = multinomial(&p, 4, false, &mut generator)
+        .unwrap()
+        .into_raw_vec();
+    assert_eq!(draws[3], 1);
+    draws.sort();
+    assert_eq!(draws, vec![0, 1, 2, 3]);
+    assert_eq!(
+        multinomial(&p, 1, true, &mut generator).unwrap().shape(),
+        &[1]
+    );
+
+    assert!(matches!(
+        multinomial(&p, 5, false, &mut generator),
+        Err(RngError::InvalidSampleCount { .. })
+    ));
+    let negative = array![0.5f64, -0.1].into_dyn();
+    assert!(matches!(
+        multinomial(&negative, 1, true, &mut generator),
+        Err(RngError::InvalidDistribution { .. })
+    ));
+    let zeros = ArrayD::<f64>::zeros(IxDyn(&[2, 3]));
+    assert!(matches!(
+        multinomial(&zeros, 1, true, &mut generator),
+        Err(RngError::InvalidDistribution { .. })
+    ));
+    assert_eq!(
+        multinomial(
+            &ArrayD::<f64>::zeros(IxDyn(&[1, 1, 2])),
+            1,
+            true,
+            &mut generator
+        ),
+        Err(RngError::InvalidShape {
+            shape: vec![1, 1, 2]
+        })
+    );
+}
+
+#[test]
+fn test_rng_errors() {
+    let mut generator = Generator::new(0);
+    assert_eq!(
+        randint(3, 3, &[2], &mut generator),
+        Err(RngError::EmptyRange { low: 3, high: 3 })
+    );
+    assert_eq!(
+        bernoulli(&array![0.5f64, 1.5].into_dyn(), &mut generator),
+        Err(RngError::InvalidProbability { value: 1.5 })
+    );
+}
@@ -0,0 +1,38 @@
+use RustOps::functions::roll::{RollError, roll};
+use ndarray::array;
+
+#[test]
+fn test_roll_small() {
+    let x = array![[1, 2, 3], [4, 5, 6]].into_dyn();
+
+    assert_eq!(
+        roll(&x, &[1], &[1]).unwrap(),
+        array![[3, 1, 2], [6, 4, 5]].into_dyn()
+    );
+    assert_eq!(
+        roll(&x, &[-1], &[-1]).unwrap(),
+        array![[2, 3, 1], [5, 6, 4]].into_dyn()
+    );
+    assert_eq!(
+        roll(&x, &[2], &[]).unwrap(),
+        array![[5, 6, 1], [2, 3, 4]].into_dyn()
+    );
+}
+
+#[test]
+fn test_roll_errors() {
+    let x = array![[1.0f32, 2.0], [3.0, 4.0]].into_dyn();
+
+    assert_eq!(
+        roll(&x, &[1, 2], &[0]),
+        Err(RollError::LengthMismatch { shifts: 2, dims: 1 })
+    );
+    assert_eq!(
+        roll(&x, &[1, 2], &[]),
+        Err(RollError::FlattenedShiftCount { shifts: 2 })
+    );
+    assert_eq!(
+        roll(&x, &[1], &[-3]),
+        Err(RollError::InvalidDimension { dim: -3, ndim: 2 })
+    );
+}
@@ -0,0 +1,195 @@
+use RustOps::io::safetensors::{SafeTensors, SafetensorsError, load, save, serialize};
+use RustOps::io::{Dtype, Tensor};
+use half::{bf16, f16};
+use ndarray::{ArrayD, IxDyn, array};
+use ndarray_npy::read_npy;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[test]
+fn test_safetensors_reference() {
+    // The fixtures are generated, not checked in; skip on a fresh clone
+    if !Path::new("data/weights.safetensors").exists() {
+        println!("SKIP: no data/weights.safetensors; run reference/safetensors.py to generate it");
+        return;
+    }
+    let file = SafeTensors::open("data/weights.safetensors").unwrap();
+    assert_eq!(
+        file.metadata().get("format").map(String::as_str),
+        Some("pt")
+    );
+
+    let weight: ArrayD<f32> = read_npy("data/weights_linear.weight.npy").unwrap();
+    assert_eq!(file.view::<f32>("linear.weight").unwrap(), weight);
+    let bias: ArrayD<f64> = read_npy("data/weights_linear.bias.npy").unwrap();
+    assert_eq!(file.view::<f64>("linear.bias").unwrap(), bias);
+    let embed: ArrayD<f32> = read_npy("data/weights_embed.weight.npy").unwrap();
+    assert_eq!(
+        file.view::<f16>("embed.weight").unwrap().mapv(f16::to_f32),
+        embed
+    );
+    let norm: ArrayD<f32> = read_npy("data/weights_norm.weight.npy").unwrap();
+    assert_eq!(
+        file.array::<bf16>("norm.weight")
+            .unwrap()
+            .mapv(bf16::to_f32),
+        norm
+    );
+    let ids: ArrayD<i64> = read_npy("data/weights_position_ids.npy").unwrap();
+    assert_eq!(file.view::<i64>("position_ids").unwrap(), ids);
+    let mask: ArrayD<bool> = read_npy("data/weights_mask.npy").unwrap();
+    assert_eq!(file.view::<bool>("mask").unwrap(), mask);
+    let scalar: ArrayD<f32> = read_npy("data/weights_scalar.npy").unwrap();
+    assert_eq!(file.array::<f32>("scalar").unwrap(), scalar);
+
+    // Writing the loaded tensors back out and reading them again is lossless
+    let tensors = file.tensors().unwrap();
+    let bytes = serialize(&tensors, Some(file.metadata())).unwrap();
+    assert_eq!(
+        SafeTensors::from_bytes(bytes).unwrap().tensors().unwrap(),
+        tensors
+    );
+}
+
+fn sample_tensors() -> BTreeMap<String, Tensor> {
+    BTreeMap::from([
+        (
+            "a".to_string(),
+            Tensor::F32(array![[1.0f32, -2.0, 3.5], [0.0, 4.25, -1.0]].into_dyn()),
+        ),
+        (
+            "b".to_string(),
+            Tensor::F16(array![f16::from_f32(0.5), f16::from_f32(-3.0)].into_dyn()),
+        ),
+        (
+            "c".to_string(),
+            Tensor::BF16(array![[bf16::from_f32(1.25)]].into_dyn()),
+        ),
+        ("d".to_string(), Tensor::I64(array![7i64, -8, 9].into_dyn())),
+        (
+            "e".to_string(),
+            Tensor::Bool(array![true, false, true].into_dyn()),
+        ),
+        (
+            "f".to_string(),
+            Tensor::F64(ArrayD::from_elem(IxDyn(&[]), 2.5)),
+        ),
+        ("g".to_string(), Tensor::F32(ArrayD::zeros(IxDyn(&[0, 3])))),
+    ])
+}
+
+#[test]
+fn test_safetensors_round_trip_file() {
+    let tensors = sample_tensors();
+    let metadata = BTreeMap::from([("format".to_string(), "pt".to_string())]);
+    let path = std::env::temp_dir().join(format!(
+        "rustops_round_trip_{}.safetensors",
+        std::process::id()
+    ));
+    save(&path, &tensors, Some(&metadata)).unwrap();
+
+    let file = SafeTensors::open(&path).unwrap();
+    assert_eq!(
+        file.names().collect::<Vec<_>>(),
+        vec!["a", "b", "c", "d", "e", "f", "g"]
+    );
+    assert_eq!(file.metadata(), &metadata);
+    assert_eq!(file.info("a").unwrap().dtype, Dtype::F32);
+    assert_eq!(file.info("a").unwrap().shape, vec![2, 3]);
+    // The mapping is aligned, so every dtype can be viewed in place
+    assert_eq!(
+        file.view::<f32>("a").unwrap(),
+        tensors["a"].as_array::<f32>().unwrap()
+    );
+    assert_eq!(
+        file.view::<f64>("f").unwrap(),
+        tensors["f"].as_array::<f64>().unwrap()
+    );
+    assert_eq!(
+        file.view::<bool>("e").unwrap(),
+        tensors["e"].as_array::<bool>().unwrap()
+    );
+    assert!(matches!(
+        file.view::<f32>("d"),
+        Err(SafetensorsError::DtypeMismatch {
+            expected: Dtype::F32,
+            found: Dtype::I64,
+            ..
+        })
+    ));
+
+    let loaded = load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.len(), tensors.len());
+    for (name, tensor) in &tensors {
+        assert_eq!(&loaded[name], tensor);
+    }
+}
+
+#[test]
+fn test_safetensors_layout_and_validation() {
+    let tensors = BTreeMap::from([
+        ("x".to_string(), Tensor::I64(array![1i64].into_dyn())),
+        (
+            "y".to_string(),
+            Tensor::Bool(array![true, false].into_dyn()),
+        ),
+    ]);
+    let bytes = serialize(&tensors, None).unwrap();
+    let header_len = u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize;
+    assert_eq!(header_len % 8, 0);
+    assert_eq!(&bytes[8 + header_len..], &[1, 0, 0, 0, 0, 0, 0, 0, 1, 0]);
+
+    // Corrupt the bool data: 2 is not a valid bool
+    let mut corrupt = bytes.clone();
+    *corrupt.last_mut().unwrap() = 2;
+    let file = SafeTensors::from_bytes(corrupt).unwrap();
+    assert!(matches!(
+        file.array::<bool>("y"),
+        Err(SafetensorsError::InvalidValue { .. })
+    ));
+
+    // Truncated data no longer matches the offsets
+    assert!(matches!(
+        SafeTensors::from_bytes(bytes[..bytes.len() - 1].to_vec()),
+        Err(SafetensorsError::InvalidOffsets { .. })
+    ));
+
+    let build = |header: &str, data: &[u8]| {
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(data);
+        SafeTensors::from_bytes(bytes)
+    };
+    let ok = build(
+        r#"{"t":{"dtype":"F32","shape":[2],"data_offsets":[0,8]}}"#,
+        &[0, 0, 128, 63, 0, 0, 0, 64],
+    )
+    .unwrap();
+    assert_eq!(
+        ok.array::<f32>("t").unwrap(),
+        array![1.0f32, 2.0].into_dyn()
+    );
+    assert!(matches!(
+        build(
+            r#"{"t":{"dtype":"F32","shape":[1],"data_offsets":[4,8]}}"#,
+            &[0; 8]
+        ),
+        Err(SafetensorsError::NonContiguous { offset: 0 })
+    ));
+    assert!(matches!(
+        build(
+            r#"{"t":{"dtype":"I8","shape":[1],"data_offsets":[0,1]}}"#,
+            &[0]
+        ),
+        Err(SafetensorsError::UnsupportedDtype { .. })
+    ));
+    assert!(matches!(
+        build(r#"{"t":{"dtype":"F32"}}"#, &[]),
+        Err(SafetensorsError::InvalidHeader { .. })
+    ));
+    assert!(matches!(
+        SafeTensors::from_bytes(vec![255, 255, 0, 0, 0, 0, 0, 0, b'{', b'}']),
+        Err(SafetensorsError::InvalidHeaderLength { .. })
+    ));
+}
@@ -0,0 +1,242 @@
+use RustOps::rng::Generator;
+use RustOps::sampling::{
+    BeamSearch, SamplingConfig, SamplingError, greedy, multinomial_sample, repetition_penalty,
+    sample, temperature, top_k, top_p,
+};
+use approx::assert_abs_diff_eq;
+use ndarray::{ArrayD, IxDyn, ShapeBuilder, array};
+
+const NEG_INF: f32 = f32::NEG_INFINITY;
+
+#[test]
+fn test_temperature() {
+    let logits = array![[1.0f32, -2.0, 4.0]].into_dyn();
+    assert_eq!(
+        temperature(&logits, 2.0).unwrap(),
+        array![[0.5f32, -1.0, 2.0]].into_dyn()
+    );
+    assert_eq!(
+        temperature(&logits, 0.0),
+        Err(SamplingError::InvalidTemperature { temperature: 0.0 })
+    );
+}
+
+#[test]
+fn test_top_k() {
+    let logits = array![[1.0f32, 3.0, 2.0, 3.0], [4.0, 0.0, -1.0, 5.0]].into_dyn();
+    assert_eq!(
+        top_k(&logits, 2).unwrap(),
+        array![[NEG_INF, 3.0, NEG_INF, 3.0], [4.0, NEG_INF, NEG_INF, 5.0]].into_dyn()
+    );
+    // Ties with the k-th value are kept
+    assert_eq!(
+        top_k(&logits, 1).unwrap(),
+        array![
+            [NEG_INF, 3.0, NEG_INF, 3.0],
+            [NEG_INF, NEG_INF, NEG_INF, 5.0]
+        ]
+        .into_dyn()
+    );
+    assert_eq!(top_k(&logits, 10).unwrap(), logits);
+    assert_eq!(top_k(&logits, 0), Err(SamplingError::InvalidTopK));
+}
+
+#[test]
+fn test_top_p() {
+    // Probabilities 0.1, 0.2, 0.3, 0.4
+    let logits = array![[0.1f32, 0.2, 0.3, 0.4]].mapv(f32::ln).into_dyn();
+    // Ascending cumulative sums 0.1, 0.3, 0.6, 1.0: 0.3 <= 1 - 0.65 is removed as well
+    let filtered = top_p(&logits, 0.65, 1).unwrap();
+    assert_eq!(
+        filtered.mapv(|x| x.is_finite()),
+        array![[false, false, true, true]].into_dyn()
+    );
+    assert_eq!(
+        top_p(&logits, 0.8, 1).unwrap().mapv(|x| x.is_finite()),
+        array![[false, true, true, true]].into_dyn()
+    );
+    assert_eq!(
+        top_p(&logits, 0.0, 1).unwrap().mapv(|x| x.is_finite()),
+        array![[false, false, false, true]].into_dyn()
+    );
+    assert_eq!(
+        top_p(&logits, 0.0, 3).unwrap().mapv(|x| x.is_finite()),
+        array![[false, true, true, true]].into_dyn()
+    );
+    assert_eq!(top_p(&logits, 1.0, 1).unwrap(), logits);
+    assert_eq!(
+        top_p(&logits, 1.5, 1),
+        Err(SamplingError::InvalidTopP { top_p: 1.5 })
+    );
+    assert_eq!(
+        top_p(&logits, 0.5, 0),
+        Err(SamplingError::InvalidMinTokensToKeep)
+    );
+}
+
+#[test]
+fn test_repetition_penalty() {
+    let logits = array![[2.0f32, -2.0, 1.0], [2.0, -2.0, 1.0]].into_dyn();
+    let previous = array![[0i64, 1, 0], [2, 2, 2]].into_dyn();
+    assert_eq!(
+        repetition_penalty(&logits, &previous, 2.0).unwrap(),
+        array![[1.0f32, -4.0, 1.0], [2.0, -2.0, 0.5]].into_dyn()
+    );
+    assert_eq!(
+        repetition_penalty(&logits, &array![[0i64], [3]].into_dyn(), 2.0),
+        Err(SamplingError::TokenOutOfRange { token: 3, vocab: 3 })
+    );
+    assert!(matches!(
+        repetition_penalty(&logits, &array![[0i64, 1]].into_dyn(), 2.0),
+        Err(SamplingError::ShapeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_greedy() {
+    let logits = array![[[0.0f32, 2.0, 2.0], [5.0, 1.0, 0.0]]].into_dyn();
+    assert_eq!(greedy(&logits).unwrap(), array![[1i64, 0]].into_dyn());
+    // Like torch.argmax, NaN wins over every number
+    let logits = array![[1.0f32, f32::NAN, 3.0], [f32::NAN, 0.0, f32::NAN]].into_dyn();
+    assert_eq!(greedy(&logits).unwrap(), array![1i64, 0].into_dyn());
+    assert!(matches!(
+        greedy(&ArrayD::<f32>::zeros(vec![2, 0])),
+        Err(SamplingError::InvalidLogits { .. })
+    ));
+}
+
+#[test]
+fn test_multinomial_sample() {
+    let logits = array![[0.0f32, 1.0, 2.0], [NEG_INF, 0.0, NEG_INF]].into_dyn();
+    let mut generator = Generator::new(0);
+    let first = multinomial_sample(&logits, &mut generator).unwrap();
+    assert_eq!(first.shape(), &[2]);
+    assert_eq!(first[1], 1);
+
+    // Same seed, same tokens
+    let mut generator = Generator::new(0);
+    assert_eq!(multinomial_sample(&logits, &mut generator).unwrap(), first);
+
+    // Only the single remaining token can be drawn
+    let mut generator = Generator::new(3);
+    for _ in 0..20 {
+        let tokens = multinomial_sample(&logits, &mut generator).unwrap();
+        assert_eq!(tokens[1], 1);
+    }
+
+    // One-hot rows of F-order logits are drawn on the rows greedy reads them from
+    let mut logits = ArrayD::from_elem(IxDyn(&[2, 3, 4]).f(), NEG_INF);
+    for (row, token) in [[0, 1, 2], [3, 0, 1]].iter().flatten().enumerate() {
+        logits[[row / 3, row % 3, *token]] = 0.0;
+    }
+    let expected = greedy(&logits).unwrap();
+    assert_eq!(expected, array![[0i64, 1, 2], [3, 0, 1]].into_dyn());
+    assert_eq!(
+        multinomial_sample(&logits, &mut generator).unwrap(),
+        expected
+    );
+    let transposed = logits.permuted_axes(IxDyn(&[1, 0, 2]));
+    assert_eq!(
+        multinomial_sample(&transposed, &mut generator).unwrap(),
+        greedy(&transposed).unwrap()
+    );
+}
+
+#[test]
+fn test_sample() {
+    let logits = array![[3.0f32, 2.9, 0.0, -1.0], [0.0, 0.1, 0.2, 5.0]].into_dyn();
+    let previous = array![[0i64], [3]].into_dyn();
+    let config = SamplingConfig {
+        top_k: Some(2),
+        repetition_penalty: Some(100.0),
+        ..Default::default()
+    };
+    let mut generator = Generator::new(42);
+    for _ in 0..20 {
+        let tokens = sample(&logits, Some(&previous), &config, &mut generator).unwrap();
+        // The penalty pushes tokens 0 and 3 out of the top 2
+        assert!(tokens[0] == 1 || tokens[0] == 2);
+        assert!(tokens[1] == 1 || tokens[1] == 2);
+    }
+
+    let config = SamplingConfig {
+        temperature: 0.5,
+        top_p: Some(0.0),
+        ..Default::default()
+    };
+    let tokens = sample(&logits, None, &config, &mut generator).unwrap();
+    assert_eq!(tokens, greedy(&logits).unwrap());
+
+    let config = SamplingConfig {
+        repetition_penalty: Some(1.5),
+        ..Default::default()
+    };
+    assert_eq!(
+        sample(&logits, None, &config, &mut generator),
+        Err(SamplingError::MissingPreviousTokens)
+    );
+}
+
+#[test]
+fn test_beam_search() {
+    // One sequence, two beams, vocabulary of three with end-of-sequence token 2
+    let mut search = BeamSearch::new(1, 2, 1.0, Some(2)).unwrap();
+
+    let step = search
+        .step(&array![[-0.1f32, -1.0, -3.0], [-0.1, -1.0, -3.0]].into_dyn())
+        .unwrap();
+    // Both start from beam 0, the others are masked out
+    assert_eq!(step.beam_indices, vec![0, 0]);
+    assert_eq!(step.tokens, vec![0, 1]);
+    assert_eq!(search.sequences(), array![[0i64], [1]].into_dyn());
+
+    // Reordering a (beams, features) cache follows the beam indices
+    let cache = array![[1.0f32, 2.0], [3.0, 4.0]].into_dyn();
+    assert_eq!(
+        step.reorder_cache(&cache, 0).unwrap(),
+        array![[1.0f32, 2.0], [1.0, 2.0]].into_dyn()
+    );
+
+    // Beam 0 is best ending now; beam 1 continues with token 0 twice over
+    let step = search
+        .step(&array![[-2.0f32, -3.0, -0.2], [-0.5, -0.6, -4.0]].into_dyn())
+        .unwrap();
+    assert_eq!(step.beam_indices, vec![1, 1]);
+    assert_eq!(step.tokens, vec![0, 1]);
+    assert_eq!(search.sequences(), array![[1i64, 0], [1, 1]].into_dyn());
+    assert!(!search.is_done());
+    assert_abs_diff_eq!(search.scores()[0], -1.5);
+    assert_abs_diff_eq!(search.scores()[1], -1.6);
+
+    let step = search
+        .step(&array![[-5.0f32, -5.0, -0.1], [-5.0, -5.0, -0.1]].into_dyn())
+        .unwrap();
+    // Both beams end; the running ones cannot catch up with the finished hypotheses
+    assert_eq!(step.beam_indices, vec![0, 0]);
+    assert!(search.is_done());
+
+    let hypotheses = search.finalize();
+    assert_eq!(hypotheses.len(), 1);
+    let best = &hypotheses[0];
+    assert_eq!(best.len(), 2);
+    assert_eq!(best[0].tokens, vec![0, 2]);
+    assert_abs_diff_eq!(best[0].score, -0.3 / 2.0, epsilon = 1e-6);
+    assert_eq!(best[1].tokens, vec![1, 0, 2]);
+    assert_abs_diff_eq!(best[1].score, -1.6 / 3.0, epsilon = 1e-6);
+}
+
+#[test]
+fn test_beam_search_errors() {
+    assert_eq!(
+        BeamSearch::new(1, 0, 1.0, None).unwrap_err(),
+        SamplingError::InvalidBeamCount {
+            batch_size: 1,
+            num_beams: 0
+        }
+    );
+    let mut search = BeamSearch::new(2, 2, 1.0, None).unwrap();
+    assert!(matches!(
+        search.step(&ArrayD::zeros(vec![2, 5])),
+        Err(SamplingError::ShapeMismatch { .. })
+    ));
+}
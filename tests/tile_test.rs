@@ -0,0 +1,30 @@
+use RustOps::functions::tile::tile;
+use ndarray::{array, s};
+
+#[test]
+fn test_tile_small() {
+    let x = array![[1, 2], [3, 4]].into_dyn();
+
+    assert_eq!(
+        tile(&x, &[2]).unwrap(),
+        array![[1, 2, 1, 2], [3, 4, 3, 4]].into_dyn()
+    );
+    assert_eq!(
+        tile(&x, &[2, 1, 1]).unwrap(),
+        array![[[1, 2], [3, 4]], [[1, 2], [3, 4]]].into_dyn()
+    );
+}
+
+#[test]
+fn test_tile_non_contiguous() {
+    let x = array![[1, 2, 3, 4], [5, 6, 7, 8]].into_dyn();
+    // Every second column of the transpose, which is neither C nor Fortran contiguous
+    let strided = x.reversed_axes().slice_move(s![..;2, ..]).into_dyn();
+    assert!(!strided.is_standard_layout());
+    assert_eq!(strided, array![[1, 5], [3, 7]].into_dyn());
+
+    assert_eq!(
+        tile(&strided, &[2, 1, 2]).unwrap(),
+        array![[[1, 5, 1, 5], [3, 7, 3, 7]], [[1, 5, 1, 5], [3, 7, 3, 7]]].into_dyn()
+    );
+}
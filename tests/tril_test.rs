@@ -0,0 +1,29 @@
+use RustOps::functions::tril::{TriangularError, tril, triu};
+use ndarray::array;
+
+#[test]
+fn test_tril_triu_small() {
+    let x = array![[1, 2, 3], [4, 5, 6], [7, 8, 9]].into_dyn();
+
+    assert_eq!(
+        tril(&x, 0).unwrap(),
+        array![[1, 0, 0], [4, 5, 0], [7, 8, 9]].into_dyn()
+    );
+    assert_eq!(
+        tril(&x, -1).unwrap(),
+        array![[0, 0, 0], [4, 0, 0], [7, 8, 0]].into_dyn()
+    );
+    assert_eq!(
+        triu(&x, 1).unwrap(),
+        array![[0, 2, 3], [0, 0, 6], [0, 0, 0]].into_dyn()
+    );
+}
+
+#[test]
+fn test_tril_requires_matrix() {
+    let x = array![1.0f32, 2.0].into_dyn();
+    assert_eq!(
+        tril(&x, 0),
+        Err(TriangularError::TooFewDimensions { ndim: 1 })
+    );
+}